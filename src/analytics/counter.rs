@@ -0,0 +1,302 @@
+//! Line-crossing and zone counting over tracks
+
+use super::regions::{CountingLine, CrossingDirection, Zone};
+use super::tracker::Track;
+use std::collections::{HashMap, HashSet};
+
+/// What happened in a [`CountEvent`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CountEventKind {
+    LineCrossed { line: String, direction: CrossingDirection },
+    ZoneEntered { zone: String },
+    ZoneExited { zone: String },
+}
+
+/// A counting event raised by [`ObjectCounter::update`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountEvent {
+    /// Number of the `update` call that raised the event, starting at 1
+    pub frame: usize,
+    pub track_id: u64,
+    pub class: String,
+    pub kind: CountEventKind,
+}
+
+type EventCallback = Box<dyn FnMut(&CountEvent)>;
+
+/// Per-class counts of line crossings and zone entries
+///
+/// Crossings are detected on the segment between consecutive track
+/// positions, so fast objects that jump over the line are still counted,
+/// while movement past the ends of the line is not. A position exactly on the
+/// line keeps the side the track was on before, and each track is counted at
+/// most once per line and direction, so objects jittering on the line are
+/// not counted repeatedly.
+pub struct ObjectCounter {
+    lines: Vec<CountingLine>,
+    zones: Vec<Zone>,
+    callbacks: Vec<EventCallback>,
+    frame: usize,
+    /// Last nonzero side of each (track, line)
+    sides: HashMap<(u64, usize), i8>,
+    counted: HashSet<(u64, usize, CrossingDirection)>,
+    line_counts: HashMap<(usize, String, CrossingDirection), usize>,
+    /// Tracks inside each zone, with their class
+    inside: Vec<HashMap<u64, String>>,
+    zone_entries: HashMap<(usize, String), usize>,
+}
+
+impl Default for ObjectCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ObjectCounter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            zones: Vec::new(),
+            callbacks: Vec::new(),
+            frame: 0,
+            sides: HashMap::new(),
+            counted: HashSet::new(),
+            line_counts: HashMap::new(),
+            inside: Vec::new(),
+            zone_entries: HashMap::new(),
+        }
+    }
+
+    /// Count crossings of `line`
+    #[must_use]
+    pub fn with_line(mut self, line: CountingLine) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    /// Track entries to and exits from `zone`
+    #[must_use]
+    pub fn with_zone(mut self, zone: Zone) -> Self {
+        self.zones.push(zone);
+        self.inside.push(HashMap::new());
+        self
+    }
+
+    /// Call `callback` for every event, in the order they are raised
+    #[must_use]
+    pub fn on_event(mut self, callback: impl FnMut(&CountEvent) + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Process the tracks of one frame and return the events they raised
+    ///
+    /// Pass every live track, e.g. the output of
+    /// [`MultiObjectTracker::update`](super::MultiObjectTracker::update); a
+    /// track missing from `tracks` is treated as gone and exits its zones.
+    pub fn update(&mut self, tracks: &[Track]) -> Vec<CountEvent> {
+        self.frame += 1;
+        let mut events = Vec::new();
+
+        for track in tracks {
+            let position = track.position();
+            for (index, line) in self.lines.iter().enumerate() {
+                let side = line.side(position);
+                if side == 0 {
+                    continue;
+                }
+                let previous = self.sides.insert((track.id, index), side);
+                let Some(previous) = previous.filter(|&p| p != side) else {
+                    continue;
+                };
+                // The last step must actually pass through the segment
+                let Some(from) = track.previous_position() else {
+                    continue;
+                };
+                if !line.segment_intersects(from, position) {
+                    continue;
+                }
+
+                let direction = if previous < 0 {
+                    CrossingDirection::Forward
+                } else {
+                    CrossingDirection::Backward
+                };
+                if self.counted.insert((track.id, index, direction)) {
+                    *self.line_counts.entry((index, track.class.clone(), direction)).or_insert(0) += 1;
+                    events.push(CountEvent {
+                        frame: self.frame,
+                        track_id: track.id,
+                        class: track.class.clone(),
+                        kind: CountEventKind::LineCrossed { line: line.name.clone(), direction },
+                    });
+                }
+            }
+        }
+
+        for (index, zone) in self.zones.iter().enumerate() {
+            let now: HashMap<u64, &Track> = tracks
+                .iter()
+                .filter(|t| zone.contains(t.position()))
+                .map(|t| (t.id, t))
+                .collect();
+
+            let mut exited: Vec<(u64, String)> = self.inside[index]
+                .iter()
+                .filter(|(id, _)| !now.contains_key(id))
+                .map(|(&id, class)| (id, class.clone()))
+                .collect();
+            exited.sort_by_key(|(id, _)| *id);
+            for (id, class) in exited {
+                self.inside[index].remove(&id);
+                events.push(CountEvent {
+                    frame: self.frame,
+                    track_id: id,
+                    class,
+                    kind: CountEventKind::ZoneExited { zone: zone.name.clone() },
+                });
+            }
+
+            for track in tracks.iter().filter(|t| now.contains_key(&t.id)) {
+                if self.inside[index].insert(track.id, track.class.clone()).is_none() {
+                    *self.zone_entries.entry((index, track.class.clone())).or_insert(0) += 1;
+                    events.push(CountEvent {
+                        frame: self.frame,
+                        track_id: track.id,
+                        class: track.class.clone(),
+                        kind: CountEventKind::ZoneEntered { zone: zone.name.clone() },
+                    });
+                }
+            }
+        }
+
+        // Forget per-line state of tracks that are gone
+        let live: HashSet<u64> = tracks.iter().map(|t| t.id).collect();
+        self.sides.retain(|(id, _), _| live.contains(id));
+        self.counted.retain(|(id, _, _)| live.contains(id));
+
+        for event in &events {
+            for callback in &mut self.callbacks {
+                callback(event);
+            }
+        }
+        events
+    }
+
+    /// Crossings of the line named `line` by objects of `class` in `direction`
+    #[must_use]
+    pub fn line_count(&self, line: &str, class: &str, direction: CrossingDirection) -> usize {
+        self.line_index(line)
+            .and_then(|i| self.line_counts.get(&(i, class.to_string(), direction)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Crossings of the line named `line` by all classes in both directions
+    #[must_use]
+    pub fn line_total(&self, line: &str) -> usize {
+        let Some(index) = self.line_index(line) else {
+            return 0;
+        };
+        self.line_counts
+            .iter()
+            .filter(|((i, _, _), _)| *i == index)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Entries of objects of `class` into the zone named `zone`
+    #[must_use]
+    pub fn zone_entries(&self, zone: &str, class: &str) -> usize {
+        self.zone_index(zone)
+            .and_then(|i| self.zone_entries.get(&(i, class.to_string())))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Number of tracks currently inside the zone named `zone`
+    #[must_use]
+    pub fn zone_occupancy(&self, zone: &str) -> usize {
+        self.zone_index(zone).map_or(0, |i| self.inside[i].len())
+    }
+
+    /// Reset all counts and per-track state, keeping lines, zones and callbacks
+    pub fn reset(&mut self) {
+        self.frame = 0;
+        self.sides.clear();
+        self.counted.clear();
+        self.line_counts.clear();
+        self.inside.iter_mut().for_each(HashMap::clear);
+        self.zone_entries.clear();
+    }
+
+    fn line_index(&self, name: &str) -> Option<usize> {
+        self.lines.iter().position(|l| l.name == name)
+    }
+
+    fn zone_index(&self, name: &str) -> Option<usize> {
+        self.zones.iter().position(|z| z.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::{Detection, MultiObjectTracker};
+    use crate::core::types::{Point2f, Rect};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_object_counter() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&log);
+        let zone = Zone::new(
+            "bottom",
+            vec![Point2f::new(0.0, 60.0), Point2f::new(100.0, 60.0), Point2f::new(100.0, 100.0), Point2f::new(0.0, 100.0)],
+        )
+        .unwrap();
+        let mut counter = ObjectCounter::new()
+            .with_line(CountingLine::new("gate", Point2f::new(0.0, 50.0), Point2f::new(60.0, 50.0)))
+            .with_zone(zone)
+            .on_event(move |e| sink.borrow_mut().push(e.kind.clone()));
+        let mut tracker = MultiObjectTracker::new().with_max_distance(20.0).with_max_missed(0);
+
+        // A person walks down through the gate into the zone, jittering on
+        // the line; a car passes beyond the end of the gate
+        let person_y = [30, 40, 48, 46, 49, 55, 62, 70];
+        for (i, &y) in person_y.iter().enumerate() {
+            let car_y = 30 + 8 * i as i32;
+            let tracks = tracker.update(&[
+                Detection::new(Rect::new(16, y, 8, 0), "person"),
+                Detection::new(Rect::new(76, car_y, 8, 0), "car"),
+            ]);
+            counter.update(tracks);
+        }
+
+        assert_eq!(counter.line_count("gate", "person", CrossingDirection::Forward), 1);
+        assert_eq!(counter.line_count("gate", "person", CrossingDirection::Backward), 0);
+        assert_eq!(counter.line_count("gate", "car", CrossingDirection::Forward), 0);
+        assert_eq!(counter.line_total("gate"), 1);
+        assert_eq!(counter.zone_entries("bottom", "person"), 1);
+        assert_eq!(counter.zone_entries("bottom", "car"), 1);
+        assert_eq!(counter.zone_occupancy("bottom"), 2);
+
+        // Both tracks disappear: they exit the zone
+        let events = counter.update(tracker.update(&[]));
+        assert_eq!(events.len(), 2);
+        assert_eq!(counter.zone_occupancy("bottom"), 0);
+
+        let log = log.borrow();
+        assert_eq!(
+            log[0],
+            CountEventKind::ZoneEntered { zone: "bottom".to_string() }
+        );
+        assert!(log.contains(&CountEventKind::LineCrossed {
+            line: "gate".to_string(),
+            direction: CrossingDirection::Forward
+        }));
+        assert_eq!(log.len(), 5);
+    }
+}
@@ -0,0 +1,17 @@
+//! Object counting on top of background subtraction and tracking
+//!
+//! The usual people / vehicle counting pipeline is: a background subtractor
+//! (for example [`BackgroundSubtractorMOG2`](crate::video::BackgroundSubtractorMOG2))
+//! produces a foreground mask, [`foreground_blobs`] turns it into boxes, a
+//! [`MultiObjectTracker`] links boxes into [`Track`]s with stable ids, and an
+//! [`ObjectCounter`] watches those trajectories for [`CountingLine`]
+//! crossings and [`Zone`] entries / exits, keeping per-class counts and
+//! firing event callbacks.
+
+pub mod counter;
+pub mod regions;
+pub mod tracker;
+
+pub use counter::*;
+pub use regions::*;
+pub use tracker::*;
@@ -0,0 +1,124 @@
+//! Counting lines and zones
+
+use crate::core::types::Point2f;
+use crate::error::{Error, Result};
+
+/// Which way a trajectory crossed a [`CountingLine`]
+///
+/// Seen on screen (y pointing down) and looking along the line from `start`
+/// to `end`, `Forward` crosses from the left-hand side to the right-hand
+/// side. For a horizontal line drawn left to right, `Forward` is downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrossingDirection {
+    Forward,
+    Backward,
+}
+
+/// A named line segment that tracks are counted across
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountingLine {
+    pub name: String,
+    pub start: Point2f,
+    pub end: Point2f,
+}
+
+impl CountingLine {
+    #[must_use]
+    pub fn new(name: impl Into<String>, start: Point2f, end: Point2f) -> Self {
+        Self { name: name.into(), start, end }
+    }
+
+    /// Side of the line `point` lies on: `1` right, `-1` left, `0` on it
+    #[must_use]
+    pub fn side(&self, point: Point2f) -> i8 {
+        let cross = (self.end.x - self.start.x) * (point.y - self.start.y)
+            - (self.end.y - self.start.y) * (point.x - self.start.x);
+        if cross > 0.0 {
+            1
+        } else if cross < 0.0 {
+            -1
+        } else {
+            0
+        }
+    }
+
+    /// Whether the movement `from -> to` passes through the segment itself
+    /// rather than its infinite extension
+    #[must_use]
+    pub fn segment_intersects(&self, from: Point2f, to: Point2f) -> bool {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let (lx, ly) = (self.end.x - self.start.x, self.end.y - self.start.y);
+        let denom = dx * ly - dy * lx;
+        if denom == 0.0 {
+            return false;
+        }
+        // Parameter along the counting line of the intersection point
+        let t = (dx * (from.y - self.start.y) - dy * (from.x - self.start.x)) / denom;
+        (0.0..=1.0).contains(&t)
+    }
+}
+
+/// A named polygonal area; tracks are inside when their centroid is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    pub name: String,
+    polygon: Vec<Point2f>,
+}
+
+impl Zone {
+    /// Create a zone from at least 3 vertices, in either winding order
+    pub fn new(name: impl Into<String>, polygon: Vec<Point2f>) -> Result<Self> {
+        if polygon.len() < 3 {
+            return Err(Error::InvalidParameter(
+                "A zone needs at least 3 vertices".to_string(),
+            ));
+        }
+        Ok(Self { name: name.into(), polygon })
+    }
+
+    #[must_use]
+    pub fn polygon(&self) -> &[Point2f] {
+        &self.polygon
+    }
+
+    /// Even-odd point-in-polygon test
+    #[must_use]
+    pub fn contains(&self, point: Point2f) -> bool {
+        let mut inside = false;
+        let mut j = self.polygon.len() - 1;
+        for (i, a) in self.polygon.iter().enumerate() {
+            let b = self.polygon[j];
+            if (a.y > point.y) != (b.y > point.y) && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+                inside = !inside;
+            }
+            j = i;
+        }
+        inside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting_line_geometry() {
+        let line = CountingLine::new("gate", Point2f::new(0.0, 10.0), Point2f::new(20.0, 10.0));
+        assert_eq!(line.side(Point2f::new(5.0, 12.0)), 1);
+        assert_eq!(line.side(Point2f::new(5.0, 8.0)), -1);
+        assert_eq!(line.side(Point2f::new(5.0, 10.0)), 0);
+
+        assert!(line.segment_intersects(Point2f::new(5.0, 8.0), Point2f::new(6.0, 12.0)));
+        // Crosses the extension of the line, not the segment
+        assert!(!line.segment_intersects(Point2f::new(25.0, 8.0), Point2f::new(26.0, 12.0)));
+
+        let zone = Zone::new(
+            "lot",
+            vec![Point2f::new(0.0, 0.0), Point2f::new(10.0, 0.0), Point2f::new(10.0, 10.0), Point2f::new(0.0, 10.0)],
+        )
+        .unwrap();
+        assert!(zone.contains(Point2f::new(5.0, 5.0)));
+        assert!(!zone.contains(Point2f::new(15.0, 5.0)));
+        assert!(Zone::new("bad", vec![Point2f::new(0.0, 0.0)]).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+//! Foreground blobs and multi-object tracking by centroid association
+
+use crate::core::types::{Point2f, Rect};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::{bounding_rect, find_contours, ChainApproxMode, RetrievalMode};
+
+/// Bounding boxes of the connected foreground regions of a mask
+///
+/// `mask` is a 1-channel U8 foreground mask (nonzero = foreground), e.g. the
+/// output of a background subtractor. Regions whose bounding box covers fewer
+/// than `min_area` pixels are dropped as noise.
+pub fn foreground_blobs(mask: &Mat, min_area: i32) -> Result<Vec<Rect>> {
+    if mask.channels() != 1 || mask.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "Foreground mask must be a 1-channel U8 image".to_string(),
+        ));
+    }
+
+    let contours = find_contours(mask, RetrievalMode::External, ChainApproxMode::Simple)?;
+    Ok(contours
+        .iter()
+        .map(bounding_rect)
+        .filter(|r| r.area() >= min_area)
+        .collect())
+}
+
+/// A detected object in one frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub bbox: Rect,
+    /// Class label, e.g. `"person"` or `"car"`; use a single label when
+    /// detections come from a class-agnostic blob detector
+    pub class: String,
+}
+
+impl Detection {
+    #[must_use]
+    pub fn new(bbox: Rect, class: impl Into<String>) -> Self {
+        Self { bbox, class: class.into() }
+    }
+}
+
+/// An object followed across frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub id: u64,
+    pub class: String,
+    /// Box of the latest matched detection
+    pub bbox: Rect,
+    /// Box centres of the matched detections, oldest first
+    pub trajectory: Vec<Point2f>,
+    /// Consecutive frames without a matching detection
+    pub missed: usize,
+}
+
+impl Track {
+    /// Latest centre position
+    #[must_use]
+    pub fn position(&self) -> Point2f {
+        self.trajectory[self.trajectory.len() - 1]
+    }
+
+    /// Centre position before the latest one, if any
+    #[must_use]
+    pub fn previous_position(&self) -> Option<Point2f> {
+        self.trajectory.len().checked_sub(2).map(|i| self.trajectory[i])
+    }
+}
+
+/// Greedy nearest-centroid multi-object tracker
+///
+/// Each frame, detections are matched to existing tracks of the same class in
+/// order of increasing centre distance, up to `max_distance`. Unmatched
+/// detections start new tracks; tracks unmatched for more than `max_missed`
+/// frames are dropped.
+#[derive(Debug, Clone)]
+pub struct MultiObjectTracker {
+    max_distance: f32,
+    max_missed: usize,
+    max_trajectory: usize,
+    tracks: Vec<Track>,
+    next_id: u64,
+}
+
+impl Default for MultiObjectTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultiObjectTracker {
+    /// Tracker with a 50 pixel gate, 5 missed frames and 64-point trajectories
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_distance: 50.0,
+            max_missed: 5,
+            max_trajectory: 64,
+            tracks: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Largest centre displacement between frames for a match
+    #[must_use]
+    pub fn with_max_distance(mut self, pixels: f32) -> Self {
+        self.max_distance = pixels;
+        self
+    }
+
+    /// Frames a track survives without detections (occlusion tolerance)
+    #[must_use]
+    pub fn with_max_missed(mut self, frames: usize) -> Self {
+        self.max_missed = frames;
+        self
+    }
+
+    /// Number of trajectory points kept per track (at least 2)
+    #[must_use]
+    pub fn with_max_trajectory(mut self, points: usize) -> Self {
+        self.max_trajectory = points.max(2);
+        self
+    }
+
+    /// Associate this frame's detections and return the live tracks
+    pub fn update(&mut self, detections: &[Detection]) -> &[Track] {
+        let mut pairs: Vec<(f32, usize, usize)> = Vec::new();
+        for (t, track) in self.tracks.iter().enumerate() {
+            for (d, detection) in detections.iter().enumerate() {
+                if track.class != detection.class {
+                    continue;
+                }
+                let c = centre(detection.bbox);
+                let p = track.position();
+                let distance = (c.x - p.x).hypot(c.y - p.y);
+                if distance <= self.max_distance {
+                    pairs.push((distance, t, d));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut track_matched = vec![false; self.tracks.len()];
+        let mut detection_matched = vec![false; detections.len()];
+        for (_, t, d) in pairs {
+            if track_matched[t] || detection_matched[d] {
+                continue;
+            }
+            track_matched[t] = true;
+            detection_matched[d] = true;
+
+            let track = &mut self.tracks[t];
+            track.bbox = detections[d].bbox;
+            track.missed = 0;
+            track.trajectory.push(centre(track.bbox));
+            if track.trajectory.len() > self.max_trajectory {
+                track.trajectory.remove(0);
+            }
+        }
+
+        for (track, matched) in self.tracks.iter_mut().zip(&track_matched) {
+            if !matched {
+                track.missed += 1;
+            }
+        }
+        self.tracks.retain(|t| t.missed <= self.max_missed);
+
+        for (detection, matched) in detections.iter().zip(detection_matched) {
+            if !matched {
+                self.tracks.push(Track {
+                    id: self.next_id,
+                    class: detection.class.clone(),
+                    bbox: detection.bbox,
+                    trajectory: vec![centre(detection.bbox)],
+                    missed: 0,
+                });
+                self.next_id += 1;
+            }
+        }
+
+        &self.tracks
+    }
+
+    /// Live tracks, including those currently coasting through misses
+    #[must_use]
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+}
+
+fn centre(rect: Rect) -> Point2f {
+    Point2f::new(
+        rect.x as f32 + rect.width as f32 / 2.0,
+        rect.y as f32 + rect.height as f32 / 2.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foreground_blobs() {
+        let mut mask = Mat::new(40, 40, 1, MatDepth::U8).unwrap();
+        for (y0, x0, size) in [(5, 5, 8), (20, 25, 10), (35, 2, 1)] {
+            for row in y0..y0 + size {
+                for col in x0..x0 + size {
+                    mask.at_mut(row, col).unwrap()[0] = 255;
+                }
+            }
+        }
+
+        let mut blobs = foreground_blobs(&mask, 4).unwrap();
+        blobs.sort_by_key(|r| (r.y, r.x));
+        assert_eq!(blobs.len(), 2);
+        assert_eq!((blobs[0].x, blobs[0].y), (5, 5));
+        assert_eq!((blobs[1].x, blobs[1].y), (25, 20));
+    }
+
+    #[test]
+    fn test_multi_object_tracker() {
+        let mut tracker = MultiObjectTracker::new().with_max_distance(10.0).with_max_missed(1);
+        let car = |x| Detection::new(Rect::new(x, 10, 6, 6), "car");
+        let person = |x| Detection::new(Rect::new(x, 40, 4, 8), "person");
+
+        tracker.update(&[car(0), person(50)]);
+        tracker.update(&[car(5), person(48)]);
+        let tracks = tracker.update(&[person(46), car(10)]);
+        assert_eq!(tracks.len(), 2);
+        let car_track = tracks.iter().find(|t| t.class == "car").unwrap();
+        assert_eq!(car_track.id, 1);
+        assert_eq!(car_track.trajectory.len(), 3);
+        assert_eq!(car_track.previous_position(), Some(Point2f::new(8.0, 13.0)));
+
+        // A far jump starts a new track; the old one coasts, then expires
+        tracker.update(&[car(40), person(44)]);
+        assert_eq!(tracker.tracks().len(), 3);
+        tracker.update(&[car(42), person(42)]);
+        let ids: Vec<u64> = tracker.tracks().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [2, 3]);
+    }
+}
@@ -0,0 +1,301 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::augment::{AugmentRng, Augmentation};
+use crate::core::types::{BorderType, InterpolationFlag};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::PixelSampler;
+
+/// Optical imperfections applied by [`apply_lens_effects`]
+///
+/// Distortion uses the Brown-Conrady model of `calib3d::DistortionCoefficients`
+/// (radial `k1..k3`, tangential `p1, p2`) in normalized coordinates
+/// `(x − cx) / focal` around the image centre, so an image distorted here can
+/// be fed to the calib3d undistortion path with the camera matrix
+/// `fx = fy = focal`, `cx, cy` at the centre. Negative `k1` gives barrel,
+/// positive `k1` pincushion distortion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LensEffects {
+    pub k: [f64; 3],
+    pub p: [f64; 2],
+    /// Focal length in pixels; `None` uses `max(rows, cols)`
+    pub focal: Option<f64>,
+    /// Lateral chromatic aberration: channel 0 is magnified by
+    /// `1 + chromatic_aberration` and channel 2 by `1 − chromatic_aberration`
+    /// about the centre (0.005 is already visible at the corners)
+    pub chromatic_aberration: f64,
+    /// Brightness loss at the corners, 0 (none) to 1 (black corners)
+    pub vignetting: f64,
+}
+
+impl Default for LensEffects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LensEffects {
+    /// A perfect lens: applying it copies the image
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            k: [0.0; 3],
+            p: [0.0; 2],
+            focal: None,
+            chromatic_aberration: 0.0,
+            vignetting: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub fn with_distortion(mut self, k1: f64, k2: f64, k3: f64, p1: f64, p2: f64) -> Self {
+        self.k = [k1, k2, k3];
+        self.p = [p1, p2];
+        self
+    }
+
+    #[must_use]
+    pub fn with_focal(mut self, focal: f64) -> Self {
+        self.focal = Some(focal);
+        self
+    }
+
+    #[must_use]
+    pub fn with_chromatic_aberration(mut self, amount: f64) -> Self {
+        self.chromatic_aberration = amount;
+        self
+    }
+
+    #[must_use]
+    pub fn with_vignetting(mut self, strength: f64) -> Self {
+        self.vignetting = strength;
+        self
+    }
+
+    /// Map ideal normalized coordinates to distorted ones
+    fn distort(&self, x: f64, y: f64) -> (f64, f64) {
+        let r2 = x * x + y * y;
+        let radial = 1.0 + self.k[0] * r2 + self.k[1] * r2 * r2 + self.k[2] * r2 * r2 * r2;
+        (
+            x * radial + 2.0 * self.p[0] * x * y + self.p[1] * (r2 + 2.0 * x * x),
+            y * radial + self.p[0] * (r2 + 2.0 * y * y) + 2.0 * self.p[1] * x * y,
+        )
+    }
+
+    /// Invert [`distort`](Self::distort) by fixed-point iteration
+    fn undistort(&self, xd: f64, yd: f64) -> (f64, f64) {
+        let (mut x, mut y) = (xd, yd);
+        for _ in 0..20 {
+            let (dx, dy) = self.distort(x, y);
+            x -= dx - xd;
+            y -= dy - yd;
+        }
+        (x, y)
+    }
+}
+
+/// Ranges for drawing random [`LensEffects`]
+///
+/// Every parameter is drawn uniformly from `[-max, max]` (`[0, max]` for
+/// vignetting), so the default of mild settings produces both barrel and
+/// pincushion images.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LensAugmentation {
+    pub max_radial: f64,
+    pub max_tangential: f64,
+    pub max_chromatic_aberration: f64,
+    pub max_vignetting: f64,
+}
+
+impl Default for LensAugmentation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LensAugmentation {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_radial: 0.2,
+            max_tangential: 0.005,
+            max_chromatic_aberration: 0.005,
+            max_vignetting: 0.4,
+        }
+    }
+
+    /// Draw one set of lens parameters
+    pub fn sample(&self, rng: &mut AugmentRng) -> LensEffects {
+        LensEffects::new()
+            .with_distortion(
+                rng.symmetric(self.max_radial),
+                rng.symmetric(self.max_radial / 4.0),
+                0.0,
+                rng.symmetric(self.max_tangential),
+                rng.symmetric(self.max_tangential),
+            )
+            .with_chromatic_aberration(rng.symmetric(self.max_chromatic_aberration))
+            .with_vignetting(rng.uniform(0.0, self.max_vignetting))
+    }
+
+    /// Apply freshly drawn lens effects, returning the parameters used
+    pub fn apply(&self, src: &Mat, dst: &mut Mat, rng: &mut AugmentRng) -> Result<LensEffects> {
+        let effects = self.sample(rng);
+        apply_lens_effects(src, dst, &effects)?;
+        Ok(effects)
+    }
+}
+
+impl Augmentation for LensAugmentation {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8)?;
+        apply_lens_effects(src, &mut dst, &self.sample(rng))?;
+        Ok(dst)
+    }
+}
+
+/// Render `src` as seen through an imperfect lens
+///
+/// Each output pixel is looked up in the ideal image through the inverse
+/// distortion model with bilinear interpolation; pixels that map outside the
+/// source become black. Works on U8 images with any number of channels;
+/// chromatic aberration needs at least three.
+pub fn apply_lens_effects(src: &Mat, dst: &mut Mat, effects: &LensEffects) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "apply_lens_effects only supports U8 depth".to_string(),
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&effects.vignetting) {
+        return Err(Error::InvalidParameter(
+            "vignetting must be in [0, 1]".to_string(),
+        ));
+    }
+
+    let rows = src.rows();
+    let cols = src.cols();
+    let channels = src.channels();
+    let focal = effects.focal.unwrap_or(rows.max(cols) as f64);
+    if focal <= 0.0 {
+        return Err(Error::InvalidParameter("focal must be positive".to_string()));
+    }
+
+    let cx = (cols as f64 - 1.0) / 2.0;
+    let cy = (rows as f64 - 1.0) / 2.0;
+    let max_r2 = cx * cx + cy * cy;
+
+    let magnification = |ch: usize| match ch {
+        0 if channels >= 3 => 1.0 + effects.chromatic_aberration,
+        2 => 1.0 - effects.chromatic_aberration,
+        _ => 1.0,
+    };
+
+    let mut out = Mat::new(rows, cols, channels, MatDepth::U8)?;
+    let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate);
+    let (max_x, max_y) = (cols as f64 - 1.0, rows as f64 - 1.0);
+    let mut px = vec![0.0f64; channels];
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let dx = col as f64 - cx;
+            let dy = row as f64 - cy;
+            let falloff = if max_r2 > 0.0 {
+                1.0 - effects.vignetting * (dx * dx + dy * dy) / max_r2
+            } else {
+                1.0
+            };
+
+            for ch in 0..channels {
+                // A magnified channel shows content from closer to the centre
+                let m = magnification(ch);
+                let (x, y) = effects.undistort(dx / (focal * m), dy / (focal * m));
+                let sx = cx + x * focal;
+                let sy = cy + y * focal;
+
+                let v = if (0.0..=max_x).contains(&sx) && (0.0..=max_y).contains(&sy) {
+                    sampler.sample(src, sx, sy, &mut px)?;
+                    px[ch] * falloff
+                } else {
+                    0.0
+                };
+                out.at_mut(row, col)?[ch] = v.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    *dst = out;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Scalar;
+
+    /// Vertical white line at `col` on black
+    fn line_image(col: usize) -> Mat {
+        let mut img = Mat::new(81, 81, 3, MatDepth::U8).unwrap();
+        for row in 0..81 {
+            img.at_mut(row, col).unwrap().copy_from_slice(&[255, 255, 255]);
+        }
+        img
+    }
+
+    fn brightest_col(img: &Mat, row: usize, ch: usize) -> usize {
+        (0..img.cols()).max_by_key(|&c| img.at(row, c).unwrap()[ch]).unwrap()
+    }
+
+    #[test]
+    fn test_identity_lens_copies() {
+        let img = line_image(20);
+        let mut out = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        apply_lens_effects(&img, &mut out, &LensEffects::new()).unwrap();
+        assert_eq!(out.data(), img.data());
+    }
+
+    #[test]
+    fn test_barrel_and_pincushion() {
+        let img = line_image(10);
+        let mut out = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        // Barrel pulls an off-centre line towards the centre, more so near the
+        // corners, so its middle bulges outwards
+        apply_lens_effects(&img, &mut out, &LensEffects::new().with_distortion(-0.3, 0.0, 0.0, 0.0, 0.0)).unwrap();
+        assert!(brightest_col(&out, 40, 0) > 10);
+        assert!(brightest_col(&out, 40, 0) < brightest_col(&out, 0, 0));
+
+        apply_lens_effects(&img, &mut out, &LensEffects::new().with_distortion(0.3, 0.0, 0.0, 0.0, 0.0)).unwrap();
+        assert!(brightest_col(&out, 40, 0) < 10);
+        assert!(brightest_col(&out, 40, 0) > brightest_col(&out, 0, 0));
+    }
+
+    #[test]
+    fn test_chromatic_aberration_and_vignetting() {
+        let img = line_image(10);
+        let mut out = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        apply_lens_effects(&img, &mut out, &LensEffects::new().with_chromatic_aberration(0.1)).unwrap();
+        // Channel 0 is magnified (pushed outwards), channel 2 shrunk
+        assert!(brightest_col(&out, 40, 0) < 10);
+        assert!(brightest_col(&out, 40, 2) > 10);
+        assert_eq!(brightest_col(&out, 40, 1), 10);
+
+        let flat = Mat::new_with_default(41, 41, 1, MatDepth::U8, Scalar::all(200.0)).unwrap();
+        apply_lens_effects(&flat, &mut out, &LensEffects::new().with_vignetting(0.5)).unwrap();
+        assert_eq!(out.at(20, 20).unwrap()[0], 200);
+        assert_eq!(out.at(0, 0).unwrap()[0], 100);
+    }
+
+    #[test]
+    fn test_random_lens_is_reproducible() {
+        let img = line_image(30);
+        let aug = LensAugmentation::new();
+        let mut a = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let mut b = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        let pa = aug.apply(&img, &mut a, &mut AugmentRng::new(5)).unwrap();
+        let pb = aug.apply(&img, &mut b, &mut AugmentRng::new(5)).unwrap();
+        assert_eq!(pa, pb);
+        assert_eq!(a.data(), b.data());
+        assert!(pa.k[0].abs() <= 0.2 && (0.0..=0.4).contains(&pa.vignetting));
+    }
+}
@@ -0,0 +1,61 @@
+//! Synthetic data augmentation
+//!
+//! Random image perturbations for generating training data and stress-testing
+//! vision code. Every random choice is drawn from an [`AugmentRng`], so a run
+//! is reproducible from its seed. Transforms implement [`Augmentation`] and
+//! chain into an [`AugmentPipeline`], which can process a whole batch in
+//! parallel.
+
+pub mod lens;
+pub mod pipeline;
+pub mod transforms;
+
+pub use lens::*;
+pub use pipeline::*;
+pub use transforms::*;
+
+use crate::imgproc::noise::SplitMix64;
+
+/// Seedable random source for augmentations
+pub struct AugmentRng {
+    inner: SplitMix64,
+}
+
+impl AugmentRng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self { inner: SplitMix64::new(seed) }
+    }
+
+    /// Independent generator for item `index` of a batch seeded with `seed`
+    #[must_use]
+    pub fn for_item(seed: u64, index: u64) -> Self {
+        let mut mixer = SplitMix64::new(seed ^ index.wrapping_mul(0xD1B5_4A32_D192_ED03));
+        Self::new(mixer.next_u64())
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        self.inner.next_f64()
+    }
+
+    /// Uniform in [low, high)
+    pub fn uniform(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// Uniform in [-magnitude, magnitude)
+    pub fn symmetric(&mut self, magnitude: f64) -> f64 {
+        self.uniform(-magnitude, magnitude)
+    }
+
+    /// Standard normal sample
+    pub fn gaussian(&mut self) -> f64 {
+        self.inner.next_gaussian()
+    }
+
+    /// `true` with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
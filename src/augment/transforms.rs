@@ -0,0 +1,332 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::augment::{AugmentRng, Augmentation};
+use crate::core::types::{Point2f, Rect, Scalar, Size};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::{flip, gaussian_blur, get_rotation_matrix_2d, warp_affine};
+
+/// Mirror the image horizontally and/or vertically at random
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomFlip {
+    /// Probability of a left-right flip
+    pub horizontal: f64,
+    /// Probability of an upside-down flip
+    pub vertical: f64,
+}
+
+impl RandomFlip {
+    #[must_use]
+    pub fn new(horizontal: f64, vertical: f64) -> Self {
+        Self { horizontal, vertical }
+    }
+
+    /// Left-right flip half of the time, the usual choice for natural images
+    #[must_use]
+    pub fn horizontal() -> Self {
+        Self::new(0.5, 0.0)
+    }
+}
+
+impl Augmentation for RandomFlip {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        let code = match (rng.chance(self.horizontal), rng.chance(self.vertical)) {
+            (false, false) => return Ok(src.clone_mat()),
+            (true, false) => 1,
+            (false, true) => 0,
+            (true, true) => -1,
+        };
+
+        let mut dst = Mat::new(1, 1, 1, src.depth())?;
+        flip(src, &mut dst, code)?;
+        Ok(dst)
+    }
+}
+
+/// Cut a `width`×`height` window at a random position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomCrop {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl RandomCrop {
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+}
+
+impl Augmentation for RandomCrop {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        if self.width == 0 || self.height == 0 || self.width > src.cols() || self.height > src.rows() {
+            return Err(Error::InvalidDimensions(format!(
+                "Cannot crop {}x{} from a {}x{} image",
+                self.width,
+                self.height,
+                src.cols(),
+                src.rows()
+            )));
+        }
+
+        let x = (rng.next_f64() * (src.cols() - self.width + 1) as f64) as usize;
+        let y = (rng.next_f64() * (src.rows() - self.height + 1) as f64) as usize;
+
+        let mut dst = Mat::new(self.height, self.width, src.channels(), src.depth())?;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                dst.at_mut(row, col)?.copy_from_slice(src.at(y + row, x + col)?);
+            }
+        }
+        Ok(dst)
+    }
+}
+
+/// Rotate about the centre by a uniform angle in `[-max_angle, max_angle]`
+/// degrees, keeping the image size; uncovered corners become black
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomRotation {
+    pub max_angle: f64,
+}
+
+impl RandomRotation {
+    #[must_use]
+    pub fn new(max_angle: f64) -> Self {
+        Self { max_angle }
+    }
+}
+
+impl Augmentation for RandomRotation {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        let angle = rng.symmetric(self.max_angle);
+        let center = Point2f::new((src.cols() as f32 - 1.0) / 2.0, (src.rows() as f32 - 1.0) / 2.0);
+        let m = get_rotation_matrix_2d(center, angle, 1.0);
+
+        let mut dst = Mat::new(1, 1, 1, src.depth())?;
+        warp_affine(src, &mut dst, &m, Size::new(src.cols() as i32, src.rows() as i32))?;
+        Ok(dst)
+    }
+}
+
+/// Random brightness, contrast and saturation changes
+///
+/// Each factor is drawn uniformly from `[1 − x, 1 + x]`. Brightness scales
+/// the pixel values, contrast scales their distance from the image's mean
+/// luminance, and saturation (3+ channel images only) scales each pixel's
+/// distance from its own grey level. Luminance follows the image's
+/// [`ColorOrder`](crate::core::types::ColorOrder) tag. U8 images only.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorJitter {
+    pub brightness: f64,
+    pub contrast: f64,
+    pub saturation: f64,
+}
+
+impl ColorJitter {
+    #[must_use]
+    pub fn new(brightness: f64, contrast: f64, saturation: f64) -> Self {
+        Self { brightness, contrast, saturation }
+    }
+}
+
+impl Augmentation for ColorJitter {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        if src.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "ColorJitter only supports U8 depth".to_string(),
+            ));
+        }
+
+        let brightness = 1.0 + rng.symmetric(self.brightness);
+        let contrast = 1.0 + rng.symmetric(self.contrast);
+        let saturation = 1.0 + rng.symmetric(self.saturation);
+
+        let channels = src.channels();
+        let weights = src.color_order().luma_weights();
+        let grey = |px: &[f64]| weights.iter().zip(px).map(|(w, v)| w * v).sum::<f64>();
+
+        let total: f64 = src
+            .data()
+            .chunks_exact(channels)
+            .map(|px| {
+                if channels >= 3 {
+                    weights.iter().zip(px).map(|(w, &v)| w * f64::from(v)).sum()
+                } else {
+                    f64::from(px[0])
+                }
+            })
+            .sum();
+        let mean = brightness * total / (src.rows() * src.cols()).max(1) as f64;
+
+        let mut dst = Mat::new(src.rows(), src.cols(), channels, MatDepth::U8)?.with_color_order(src.color_order());
+        let mut px = vec![0.0f64; channels];
+        for row in 0..src.rows() {
+            for col in 0..src.cols() {
+                for (v, &b) in px.iter_mut().zip(src.at(row, col)?) {
+                    *v = (f64::from(b) * brightness - mean) * contrast + mean;
+                }
+                if channels >= 3 {
+                    let g = grey(&px);
+                    for v in &mut px[..3] {
+                        *v = g + (*v - g) * saturation;
+                    }
+                }
+                for (b, &v) in dst.at_mut(row, col)?.iter_mut().zip(&px) {
+                    *b = v.round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+        Ok(dst)
+    }
+}
+
+/// Gaussian blur with a sigma drawn uniformly from `[min_sigma, max_sigma]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomBlur {
+    pub min_sigma: f64,
+    pub max_sigma: f64,
+}
+
+impl RandomBlur {
+    #[must_use]
+    pub fn new(min_sigma: f64, max_sigma: f64) -> Self {
+        Self { min_sigma, max_sigma }
+    }
+}
+
+impl Augmentation for RandomBlur {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        if self.min_sigma <= 0.0 || self.max_sigma < self.min_sigma {
+            return Err(Error::InvalidParameter(
+                "Blur sigma range must be positive and ordered".to_string(),
+            ));
+        }
+
+        let sigma = rng.uniform(self.min_sigma, self.max_sigma);
+        let ksize = 2 * (3.0 * sigma).ceil() as i32 + 1;
+
+        let mut dst = Mat::new(1, 1, 1, src.depth())?;
+        gaussian_blur(src, &mut dst, Size::new(ksize, ksize), sigma)?;
+        Ok(dst)
+    }
+}
+
+/// Paint `holes` random `size`×`size` squares with `fill`
+///
+/// Squares are centred anywhere in the image and clipped at the border, as
+/// in the original Cutout regularisation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cutout {
+    pub holes: usize,
+    pub size: usize,
+    pub fill: Scalar,
+}
+
+impl Cutout {
+    #[must_use]
+    pub fn new(holes: usize, size: usize, fill: Scalar) -> Self {
+        Self { holes, size, fill }
+    }
+}
+
+impl Augmentation for Cutout {
+    fn apply(&self, src: &Mat, rng: &mut AugmentRng) -> Result<Mat> {
+        if src.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "Cutout only supports U8 depth".to_string(),
+            ));
+        }
+
+        let fill: Vec<u8> = (0..src.channels())
+            .map(|ch| self.fill.val[ch.min(3)].round().clamp(0.0, 255.0) as u8)
+            .collect();
+
+        let mut dst = src.clone_mat();
+        let half = (self.size / 2) as i32;
+        for _ in 0..self.holes {
+            let cx = (rng.next_f64() * src.cols() as f64) as i32;
+            let cy = (rng.next_f64() * src.rows() as f64) as i32;
+            let hole = Rect::new(cx - half, cy - half, self.size as i32, self.size as i32);
+
+            let x0 = hole.x.max(0) as usize;
+            let y0 = hole.y.max(0) as usize;
+            let x1 = ((hole.x + hole.width).max(0) as usize).min(src.cols());
+            let y1 = ((hole.y + hole.height).max(0) as usize).min(src.rows());
+            for row in y0..y1 {
+                for col in x0..x1 {
+                    dst.at_mut(row, col)?.copy_from_slice(&fill);
+                }
+            }
+        }
+        Ok(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(channels: usize) -> Mat {
+        let mut img = Mat::new(20, 30, channels, MatDepth::U8).unwrap();
+        for row in 0..20 {
+            for col in 0..30 {
+                for (ch, v) in img.at_mut(row, col).unwrap().iter_mut().enumerate() {
+                    *v = (40 + row * 5 + col * 3 + ch * 20) as u8;
+                }
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_flip_and_crop() {
+        let img = pattern(1);
+        let mut rng = AugmentRng::new(3);
+
+        let flipped = RandomFlip::new(1.0, 0.0).apply(&img, &mut rng).unwrap();
+        assert_eq!(flipped.at(0, 0).unwrap(), img.at(0, 29).unwrap());
+        let same = RandomFlip::new(0.0, 0.0).apply(&img, &mut rng).unwrap();
+        assert_eq!(same.data(), img.data());
+
+        let crop = RandomCrop::new(10, 5).apply(&img, &mut rng).unwrap();
+        assert_eq!((crop.rows(), crop.cols()), (5, 10));
+        // The crop is a contiguous window of the source
+        let first = crop.at(0, 0).unwrap()[0];
+        assert_eq!(crop.at(1, 1).unwrap()[0], first + 8);
+        assert!(RandomCrop::new(31, 5).apply(&img, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_size() {
+        let img = pattern(3);
+        let out = RandomRotation::new(15.0).apply(&img, &mut AugmentRng::new(9)).unwrap();
+        assert_eq!((out.rows(), out.cols(), out.channels()), (20, 30, 3));
+        assert_eq!(out.at(10, 15).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_color_jitter_saturation_only() {
+        let img = pattern(3);
+        let mut rng = AugmentRng::new(4);
+
+        let none = ColorJitter::new(0.0, 0.0, 0.0).apply(&img, &mut rng).unwrap();
+        assert_eq!(none.data(), img.data());
+
+        // Saturation factor in [0, 2]: grey pixels stay grey
+        let grey = Mat::new_with_default(4, 4, 3, MatDepth::U8, Scalar::all(90.0)).unwrap();
+        let out = ColorJitter::new(0.0, 0.0, 1.0).apply(&grey, &mut rng).unwrap();
+        assert_eq!(out.data(), grey.data());
+    }
+
+    #[test]
+    fn test_blur_and_cutout() {
+        let img = pattern(1);
+        let mut rng = AugmentRng::new(2);
+        let blurred = RandomBlur::new(0.5, 2.0).apply(&img, &mut rng).unwrap();
+        assert_eq!((blurred.rows(), blurred.cols()), (20, 30));
+        assert!(RandomBlur::new(0.0, 1.0).apply(&img, &mut rng).is_err());
+
+        let cut = Cutout::new(2, 6, Scalar::all(0.0)).apply(&img, &mut rng).unwrap();
+        let zeros = cut.data().iter().filter(|&&v| v == 0).count();
+        assert!(zeros > 0 && zeros <= 2 * 36);
+    }
+}
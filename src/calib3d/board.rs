@@ -0,0 +1,701 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use std::collections::{HashMap, VecDeque};
+
+use crate::calib3d::camera::{project_points, CameraMatrix, DistortionCoefficients};
+use crate::calib3d::pnp::solve_pnp_planar;
+use crate::core::{Mat, MatDepth};
+use crate::core::types::{BorderType, InterpolationFlag, Point, Point2f, Point3f, Scalar, Size};
+use crate::error::{Error, Result};
+use crate::imgproc::{line, to_gray, PixelSampler};
+
+/// Planar chessboard calibration target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChessboardPattern {
+    /// Number of inner corners per row (`width`) and per column (`height`)
+    pub pattern_size: Size,
+    /// Side length of one square, in the units the translation is wanted in
+    pub square_size: f32,
+}
+
+impl ChessboardPattern {
+    #[must_use]
+    pub fn new(pattern_size: Size, square_size: f32) -> Self {
+        Self { pattern_size, square_size }
+    }
+
+    /// Inner corner positions on the board plane (`z = 0`), row by row
+    #[must_use]
+    pub fn object_points(&self) -> Vec<Point3f> {
+        let mut points = Vec::with_capacity((self.pattern_size.width * self.pattern_size.height).max(0) as usize);
+        for row in 0..self.pattern_size.height {
+            for col in 0..self.pattern_size.width {
+                points.push(Point3f::new(
+                    col as f32 * self.square_size,
+                    row as f32 * self.square_size,
+                    0.0,
+                ));
+            }
+        }
+        points
+    }
+}
+
+/// Board pose recovered by [`estimate_board_pose`]
+#[derive(Debug, Clone)]
+pub struct BoardPose {
+    pub rvec: [f64; 3],
+    pub tvec: [f64; 3],
+    /// Refined inner corners in the order of [`ChessboardPattern::object_points`]
+    pub corners: Vec<Point2f>,
+    /// RMS reprojection error in pixels
+    pub reprojection_error: f64,
+}
+
+/// Locate the inner corners of a chessboard
+///
+/// Corners are found as saddle points of the smoothed image and linked into a
+/// grid by walking from the centre of the board outwards. Returns `None` when
+/// a complete `pattern_size` grid is not visible. Corners come back row by
+/// row with the first corner nearest the top-left of the image, at pixel
+/// precision; follow up with [`corner_sub_pix`] for calibration work.
+pub fn find_chessboard_corners(gray: &Mat, pattern_size: Size) -> Result<Option<Vec<Point2f>>> {
+    if gray.channels() != 1 || gray.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "find_chessboard_corners requires a single-channel U8 image".to_string(),
+        ));
+    }
+
+    if pattern_size.width < 2 || pattern_size.height < 2 {
+        return Err(Error::InvalidParameter(
+            "Chessboard pattern needs at least 2x2 inner corners".to_string(),
+        ));
+    }
+
+    let rows = gray.rows();
+    let cols = gray.cols();
+    if rows < 16 || cols < 16 {
+        return Ok(None);
+    }
+
+    let image = to_f64(gray)?;
+    let smooth = gaussian_f64(&image, rows, cols, 1.5);
+    let candidates = saddle_candidates(gray, &smooth)?;
+    if candidates.len() < (pattern_size.width * pattern_size.height) as usize {
+        return Ok(None);
+    }
+
+    let Some(grid) = grow_grid(&candidates) else {
+        return Ok(None);
+    };
+
+    Ok(select_pattern(&grid, &candidates, pattern_size))
+}
+
+/// Refine corner locations to sub-pixel accuracy
+///
+/// Uses the `OpenCV` `cornerSubPix` criterion: at the true corner, every
+/// gradient inside the window is orthogonal to the vector from the corner to
+/// the gradient's position. `win_size` is the half-size of the search window.
+/// Iterates until a corner moves less than `epsilon` pixels or `max_iter` is
+/// reached.
+pub fn corner_sub_pix(
+    gray: &Mat,
+    corners: &mut [Point2f],
+    win_size: Size,
+    max_iter: usize,
+    epsilon: f64,
+) -> Result<()> {
+    if gray.channels() != 1 || gray.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "corner_sub_pix requires a single-channel U8 image".to_string(),
+        ));
+    }
+
+    if win_size.width < 1 || win_size.height < 1 {
+        return Err(Error::InvalidParameter(
+            "Sub-pixel window must be at least 1x1".to_string(),
+        ));
+    }
+
+    let sample = |x: f64, y: f64| sample_gray(gray, x, y);
+
+    let wx = win_size.width;
+    let wy = win_size.height;
+    let sigma_x = f64::from(wx) / 2.0;
+    let sigma_y = f64::from(wy) / 2.0;
+
+    for corner in corners.iter_mut() {
+        let mut qx = f64::from(corner.x);
+        let mut qy = f64::from(corner.y);
+
+        for _ in 0..max_iter.max(1) {
+            let mut a = [[0.0f64; 2]; 2];
+            let mut b = [0.0f64; 2];
+
+            for dy in -wy..=wy {
+                for dx in -wx..=wx {
+                    let px = qx + f64::from(dx);
+                    let py = qy + f64::from(dy);
+
+                    let gx = (sample(px + 1.0, py)? - sample(px - 1.0, py)?) * 0.5;
+                    let gy = (sample(px, py + 1.0)? - sample(px, py - 1.0)?) * 0.5;
+                    let w = (-(f64::from(dx * dx)) / (2.0 * sigma_x * sigma_x)
+                        - f64::from(dy * dy) / (2.0 * sigma_y * sigma_y))
+                        .exp();
+
+                    let gxx = w * gx * gx;
+                    let gxy = w * gx * gy;
+                    let gyy = w * gy * gy;
+
+                    a[0][0] += gxx;
+                    a[0][1] += gxy;
+                    a[1][1] += gyy;
+                    b[0] += gxx * px + gxy * py;
+                    b[1] += gxy * px + gyy * py;
+                }
+            }
+
+            let det = a[0][0] * a[1][1] - a[0][1] * a[0][1];
+            if det.abs() < 1e-9 {
+                break;
+            }
+
+            let nx = (a[1][1] * b[0] - a[0][1] * b[1]) / det;
+            let ny = (a[0][0] * b[1] - a[0][1] * b[0]) / det;
+            let moved = ((nx - qx).powi(2) + (ny - qy).powi(2)).sqrt();
+
+            // Refuse to wander out of the search window
+            if (nx - f64::from(corner.x)).abs() > f64::from(wx) || (ny - f64::from(corner.y)).abs() > f64::from(wy) {
+                break;
+            }
+
+            qx = nx;
+            qy = ny;
+            if moved < epsilon {
+                break;
+            }
+        }
+
+        *corner = Point2f::new(qx as f32, qy as f32);
+    }
+
+    Ok(())
+}
+
+/// Estimate the pose of a chessboard relative to the camera
+///
+/// Chains [`find_chessboard_corners`], [`corner_sub_pix`] and
+/// [`solve_pnp_planar`]. The board frame has its origin at the first inner
+/// corner, X along a row, Y down the columns and Z into the board, so the
+/// translation is in the units of `board.square_size`. Accepts 1- or 3-channel U8
+/// images, converting colour by its [`ColorOrder`](crate::core::types::ColorOrder)
+/// tag, and returns `None` when the board is not found.
+pub fn estimate_board_pose(
+    gray: &Mat,
+    board: &ChessboardPattern,
+    camera: &CameraMatrix,
+    dist: &DistortionCoefficients,
+) -> Result<Option<BoardPose>> {
+    if gray.depth() != MatDepth::U8 || (gray.channels() != 1 && gray.channels() != 3) {
+        return Err(Error::UnsupportedOperation(
+            "estimate_board_pose requires a 1- or 3-channel U8 image".to_string(),
+        ));
+    }
+
+    let mut luma = Mat::new(1, 1, 1, MatDepth::U8)?;
+    to_gray(gray, &mut luma)?;
+    let gray = &luma;
+
+    let Some(mut corners) = find_chessboard_corners(gray, board.pattern_size)? else {
+        return Ok(None);
+    };
+
+    // Keep the refinement window inside one square
+    let spacing = corners
+        .windows(2)
+        .take(board.pattern_size.width as usize - 1)
+        .map(|w| f64::from((w[1].x - w[0].x).hypot(w[1].y - w[0].y)))
+        .fold(f64::MAX, f64::min);
+    let half = ((spacing * 0.4) as i32).clamp(2, 11);
+    corner_sub_pix(gray, &mut corners, Size::new(half, half), 30, 0.01)?;
+
+    let object_points = board.object_points();
+    let (rvec, tvec) = solve_pnp_planar(&object_points, &corners, camera, dist)?;
+
+    let projected = project_points(&object_points, &rvec, &tvec, camera, dist);
+    let sum_sq: f64 = projected
+        .iter()
+        .zip(&corners)
+        .map(|(p, c)| f64::from((p.x - c.x).powi(2) + (p.y - c.y).powi(2)))
+        .sum();
+    let reprojection_error = (sum_sq / corners.len() as f64).sqrt();
+
+    Ok(Some(BoardPose { rvec, tvec, corners, reprojection_error }))
+}
+
+/// Draw the pose's coordinate axes for an AR-style overlay
+///
+/// X is drawn in red, Y in green and Z in blue, each `length` object units
+/// long from the origin of the object frame.
+pub fn draw_axes(
+    img: &mut Mat,
+    camera: &CameraMatrix,
+    dist: &DistortionCoefficients,
+    rvec: &[f64; 3],
+    tvec: &[f64; 3],
+    length: f32,
+) -> Result<()> {
+    let axes = [
+        Point3f::new(0.0, 0.0, 0.0),
+        Point3f::new(length, 0.0, 0.0),
+        Point3f::new(0.0, length, 0.0),
+        Point3f::new(0.0, 0.0, length),
+    ];
+
+    let projected = project_points(&axes, rvec, tvec, camera, dist);
+    let to_point = |p: Point2f| Point::new(p.x.round() as i32, p.y.round() as i32);
+    let origin = to_point(projected[0]);
+
+    let colors = [
+        Scalar::from_rgb(255, 0, 0),
+        Scalar::from_rgb(0, 255, 0),
+        Scalar::from_rgb(0, 0, 255),
+    ];
+
+    for (end, color) in projected[1..].iter().zip(colors) {
+        line(img, origin, to_point(*end), color, 2)?;
+    }
+
+    Ok(())
+}
+
+fn to_f64(gray: &Mat) -> Result<Vec<f64>> {
+    let mut out = Vec::with_capacity(gray.rows() * gray.cols());
+    for row in 0..gray.rows() {
+        for col in 0..gray.cols() {
+            out.push(f64::from(gray.at(row, col)?[0]));
+        }
+    }
+    Ok(out)
+}
+
+fn gaussian_f64(src: &[f64], rows: usize, cols: usize, sigma: f64) -> Vec<f64> {
+    let radius = (sigma * 3.0).ceil() as isize;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|i| (-((i * i) as f64) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let norm: f64 = kernel.iter().sum();
+
+    let clamp = |v: isize, n: usize| v.clamp(0, n as isize - 1) as usize;
+
+    let mut tmp = vec![0.0; rows * cols];
+    for r in 0..rows {
+        for c in 0..cols {
+            tmp[r * cols + c] = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * src[r * cols + clamp(c as isize + k as isize - radius, cols)])
+                .sum::<f64>()
+                / norm;
+        }
+    }
+
+    let mut out = vec![0.0; rows * cols];
+    for r in 0..rows {
+        for c in 0..cols {
+            out[r * cols + c] = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * tmp[clamp(r as isize + k as isize - radius, rows) * cols + c])
+                .sum::<f64>()
+                / norm;
+        }
+    }
+
+    out
+}
+
+/// Bilinear sample of a 1-channel image, replicating its border
+fn sample_gray(gray: &Mat, x: f64, y: f64) -> Result<f64> {
+    let mut v = [0.0];
+    PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate).sample(gray, x, y, &mut v)?;
+    Ok(v[0])
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    x: f64,
+    y: f64,
+    response: f64,
+}
+
+/// Saddle points of the smoothed image that look like X-junctions
+fn saddle_candidates(gray: &Mat, smooth: &[f64]) -> Result<Vec<Candidate>> {
+    const BORDER: usize = 4;
+    const NMS_RADIUS: isize = 3;
+    let (rows, cols) = (gray.rows(), gray.cols());
+
+    // A saddle has a negative Hessian determinant; keep its magnitude
+    let mut response = vec![0.0; rows * cols];
+    for r in 1..rows - 1 {
+        for c in 1..cols - 1 {
+            let i = r * cols + c;
+            let fxx = smooth[i + 1] - 2.0 * smooth[i] + smooth[i - 1];
+            let fyy = smooth[i + cols] - 2.0 * smooth[i] + smooth[i - cols];
+            let fxy = (smooth[i + cols + 1] - smooth[i + cols - 1] - smooth[i - cols + 1] + smooth[i - cols - 1]) / 4.0;
+            response[i] = (fxy * fxy - fxx * fyy).max(0.0);
+        }
+    }
+
+    let max_response = response.iter().copied().fold(0.0, f64::max);
+    if max_response <= 0.0 {
+        return Ok(Vec::new());
+    }
+    let threshold = max_response * 0.05;
+
+    let mut candidates = Vec::new();
+    for r in BORDER..rows - BORDER {
+        for c in BORDER..cols - BORDER {
+            let v = response[r * cols + c];
+            if v < threshold {
+                continue;
+            }
+
+            let is_max = (-NMS_RADIUS..=NMS_RADIUS).all(|dy| {
+                (-NMS_RADIUS..=NMS_RADIUS).all(|dx| {
+                    let rr = (r as isize + dy) as usize;
+                    let cc = (c as isize + dx) as usize;
+                    let other = response[rr * cols + cc];
+                    // Break ties towards the first pixel in scan order
+                    other < v || (other == v && (dy, dx) >= (0, 0))
+                })
+            });
+
+            if is_max && is_x_junction(gray, c as f64, r as f64)? {
+                candidates.push(Candidate { x: c as f64, y: r as f64, response: v });
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// An X-junction alternates dark/light four times around a small circle,
+/// where an L-shaped corner on the board's outline only does so twice
+fn is_x_junction(gray: &Mat, x: f64, y: f64) -> Result<bool> {
+    const SAMPLES: usize = 16;
+    const RADIUS: f64 = 3.0;
+
+    let ring: Vec<f64> = (0..SAMPLES)
+        .map(|k| {
+            let angle = k as f64 * std::f64::consts::TAU / SAMPLES as f64;
+            sample_gray(gray, x + RADIUS * angle.cos(), y + RADIUS * angle.sin())
+        })
+        .collect::<Result<_>>()?;
+
+    let (min, max) = ring.iter().fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    if max - min < 20.0 {
+        return Ok(false);
+    }
+
+    let mid = (min + max) / 2.0;
+    let transitions = (0..SAMPLES)
+        .filter(|&k| (ring[k] > mid) != (ring[(k + 1) % SAMPLES] > mid))
+        .count();
+
+    Ok(transitions == 4)
+}
+
+/// Link candidates into a lattice, indexed by integer grid coordinates
+fn grow_grid(candidates: &[Candidate]) -> Option<HashMap<(i32, i32), usize>> {
+    let n = candidates.len();
+    let dist = |a: usize, b: usize| (candidates[a].x - candidates[b].x).hypot(candidates[a].y - candidates[b].y);
+
+    // Seed at the candidate nearest the centroid, which is well inside the board
+    let cx = candidates.iter().map(|c| c.x).sum::<f64>() / n as f64;
+    let cy = candidates.iter().map(|c| c.y).sum::<f64>() / n as f64;
+    let seed = (0..n).min_by(|&a, &b| {
+        let da = (candidates[a].x - cx).hypot(candidates[a].y - cy);
+        let db = (candidates[b].x - cx).hypot(candidates[b].y - cy);
+        da.total_cmp(&db)
+    })?;
+
+    // Basis vectors: the nearest neighbour and the nearest one roughly perpendicular to it
+    let mut neighbours: Vec<usize> = (0..n).filter(|&i| i != seed).collect();
+    neighbours.sort_by(|&a, &b| dist(seed, a).total_cmp(&dist(seed, b)));
+
+    let first = *neighbours.first()?;
+    let u = (candidates[first].x - candidates[seed].x, candidates[first].y - candidates[seed].y);
+    let u_len = u.0.hypot(u.1);
+
+    let second = neighbours.iter().copied().skip(1).find(|&i| {
+        let v = (candidates[i].x - candidates[seed].x, candidates[i].y - candidates[seed].y);
+        let v_len = v.0.hypot(v.1);
+        let cos = (u.0 * v.0 + u.1 * v.1) / (u_len * v_len);
+        cos.abs() < 0.5 && v_len < u_len * 2.0
+    })?;
+    let v = (candidates[second].x - candidates[seed].x, candidates[second].y - candidates[seed].y);
+
+    let mut grid: HashMap<(i32, i32), usize> = HashMap::new();
+    let mut used = vec![false; n];
+    grid.insert((0, 0), seed);
+    used[seed] = true;
+
+    let mut queue = VecDeque::from([(0, 0)]);
+    while let Some((i, j)) = queue.pop_front() {
+        let here = grid[&(i, j)];
+        let p = (candidates[here].x, candidates[here].y);
+
+        for (di, dj) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let key = (i + di, j + dj);
+            if grid.contains_key(&key) {
+                continue;
+            }
+
+            // Extrapolate from the node behind us, which follows perspective,
+            // falling back to the seed basis
+            let step = match grid.get(&(i - di, j - dj)) {
+                Some(&behind) => (p.0 - candidates[behind].x, p.1 - candidates[behind].y),
+                None if di != 0 => (u.0 * f64::from(di), u.1 * f64::from(di)),
+                None => (v.0 * f64::from(dj), v.1 * f64::from(dj)),
+            };
+            let predicted = (p.0 + step.0, p.1 + step.1);
+            let tolerance = 0.3 * step.0.hypot(step.1);
+
+            let best = (0..n)
+                .filter(|&k| !used[k])
+                .map(|k| (k, (candidates[k].x - predicted.0).hypot(candidates[k].y - predicted.1)))
+                .filter(|&(_, d)| d < tolerance)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((k, _)) = best {
+                grid.insert(key, k);
+                used[k] = true;
+                queue.push_back(key);
+            }
+        }
+    }
+
+    Some(grid)
+}
+
+/// Pick the fully populated `pattern_size` window of the grid with the
+/// strongest saddles and order it row by row
+fn select_pattern(
+    grid: &HashMap<(i32, i32), usize>,
+    candidates: &[Candidate],
+    pattern_size: Size,
+) -> Option<Vec<Point2f>> {
+    let (pw, ph) = (pattern_size.width, pattern_size.height);
+    let min_i = grid.keys().map(|k| k.0).min()?;
+    let max_i = grid.keys().map(|k| k.0).max()?;
+    let min_j = grid.keys().map(|k| k.1).min()?;
+    let max_j = grid.keys().map(|k| k.1).max()?;
+
+    // (extent along grid i, extent along grid j, pattern rows run along j)
+    let mut layouts = vec![(pw, ph, true)];
+    if pw != ph {
+        layouts.push((ph, pw, false));
+    }
+
+    let mut best: Option<(f64, Vec<Vec<usize>>)> = None;
+    for (ni, nj, rows_along_j) in layouts {
+        for i0 in min_i..=max_i - ni + 1 {
+            for j0 in min_j..=max_j - nj + 1 {
+                let mut score = 0.0;
+                let mut complete = true;
+                'window: for i in i0..i0 + ni {
+                    for j in j0..j0 + nj {
+                        match grid.get(&(i, j)) {
+                            Some(&k) => score += candidates[k].response,
+                            None => {
+                                complete = false;
+                                break 'window;
+                            }
+                        }
+                    }
+                }
+
+                if complete && best.as_ref().is_none_or(|(s, _)| score > *s) {
+                    let table: Vec<Vec<usize>> = (0..ph)
+                        .map(|r| {
+                            (0..pw)
+                                .map(|c| {
+                                    let key = if rows_along_j { (i0 + c, j0 + r) } else { (i0 + r, j0 + c) };
+                                    grid[&key]
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    best = Some((score, table));
+                }
+            }
+        }
+    }
+
+    let (_, table) = best?;
+    let point = |k: usize| Point2f::new(candidates[k].x as f32, candidates[k].y as f32);
+
+    // Of the mirrored orderings, keep those with X × Y pointing into the
+    // image (a right-handed board frame) and start nearest the top-left
+    let mut orderings = Vec::new();
+    for flip_rows in [false, true] {
+        for flip_cols in [false, true] {
+            let mut table = table.clone();
+            if flip_rows {
+                table.reverse();
+            }
+            if flip_cols {
+                table.iter_mut().for_each(|row| row.reverse());
+            }
+            orderings.push(table);
+        }
+    }
+    if pw == ph {
+        // A square pattern can also be read column by column
+        let transposed: Vec<Vec<Vec<usize>>> = orderings
+            .iter()
+            .map(|t| (0..pw as usize).map(|c| t.iter().map(|row| row[c]).collect()).collect())
+            .collect();
+        orderings.extend(transposed);
+    }
+
+    orderings
+        .into_iter()
+        .filter(|t| {
+            let o = point(t[0][0]);
+            let x = point(t[0][t[0].len() - 1]);
+            let y = point(t[t.len() - 1][0]);
+            (x.x - o.x) * (y.y - o.y) - (x.y - o.y) * (y.x - o.x) > 0.0
+        })
+        .min_by(|a, b| {
+            let pa = point(a[0][0]);
+            let pb = point(b[0][0]);
+            (pa.x + pa.y).total_cmp(&(pb.x + pb.y))
+        })
+        .map(|t| t.into_iter().flatten().map(point).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> CameraMatrix {
+        CameraMatrix::new(500.0, 500.0, 160.0, 120.0)
+    }
+
+    /// Render a board with `squares` squares (one more than the inner corners)
+    /// and a white margin, 4x4 supersampled
+    fn render_board(board: &ChessboardPattern, rvec: &[f64; 3], tvec: &[f64; 3]) -> Mat {
+        let (rows, cols) = (240, 320);
+        let cam = camera();
+        let s = f64::from(board.square_size);
+        let r = crate::calib3d::rodrigues(rvec);
+
+        // Homography from the board plane to pixels: K [r1 r2 t]
+        let k = cam.to_matrix();
+        let m = [
+            [r[0][0], r[0][1], tvec[0]],
+            [r[1][0], r[1][1], tvec[1]],
+            [r[2][0], r[2][1], tvec[2]],
+        ];
+        let mut h = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                h[i][j] = (0..3).map(|l| k[i][l] * m[l][j]).sum();
+            }
+        }
+        let inv = invert3(&h);
+
+        let mut img = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut sum = 0.0f64;
+                for sy in 0..4 {
+                    for sx in 0..4 {
+                        let u = col as f64 + (f64::from(sx) + 0.5) / 4.0 - 0.5;
+                        let v = row as f64 + (f64::from(sy) + 0.5) / 4.0 - 0.5;
+                        let w = inv[2][0] * u + inv[2][1] * v + inv[2][2];
+                        let bx = (inv[0][0] * u + inv[0][1] * v + inv[0][2]) / w / s + 1.0;
+                        let by = (inv[1][0] * u + inv[1][1] * v + inv[1][2]) / w / s + 1.0;
+
+                        let inside = bx >= 0.0
+                            && by >= 0.0
+                            && bx < f64::from(board.pattern_size.width + 1)
+                            && by < f64::from(board.pattern_size.height + 1);
+                        let dark = inside && (bx.floor() as i64 + by.floor() as i64) % 2 == 0;
+                        sum += if dark { 30.0 } else { 220.0 };
+                    }
+                }
+                img.at_mut(row, col).unwrap()[0] = (sum / 16.0).round() as u8;
+            }
+        }
+        img
+    }
+
+    fn invert3(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+        let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+        std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                let (a, b) = ((j + 1) % 3, (j + 2) % 3);
+                let (c, d) = ((i + 1) % 3, (i + 2) % 3);
+                (m[a][c] * m[b][d] - m[a][d] * m[b][c]) / det
+            })
+        })
+    }
+
+    #[test]
+    fn test_object_points_layout() {
+        let board = ChessboardPattern::new(Size::new(3, 2), 0.5);
+        let points = board.object_points();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[1], Point3f::new(0.5, 0.0, 0.0));
+        assert_eq!(points[3], Point3f::new(0.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn test_estimate_board_pose_recovers_pose() {
+        let board = ChessboardPattern::new(Size::new(7, 5), 0.025);
+        let rvec = [0.25, -0.2, 0.1];
+        let tvec = [-0.09, -0.06, 0.55];
+        let img = render_board(&board, &rvec, &tvec);
+
+        let pose = estimate_board_pose(&img, &board, &camera(), &DistortionCoefficients::zero())
+            .unwrap()
+            .expect("board should be found");
+
+        assert_eq!(pose.corners.len(), 35);
+        assert!(pose.reprojection_error < 0.2, "rms {}", pose.reprojection_error);
+        for i in 0..3 {
+            assert!((pose.rvec[i] - rvec[i]).abs() < 0.02, "rvec {:?}", pose.rvec);
+            assert!((pose.tvec[i] - tvec[i]).abs() < 0.005, "tvec {:?}", pose.tvec);
+        }
+    }
+
+    #[test]
+    fn test_board_not_found() {
+        let board = ChessboardPattern::new(Size::new(7, 5), 0.025);
+        let blank = Mat::new_with_default(120, 160, 1, MatDepth::U8, Scalar::all(128.0)).unwrap();
+        let pose = estimate_board_pose(&blank, &board, &camera(), &DistortionCoefficients::zero()).unwrap();
+        assert!(pose.is_none());
+
+        // Larger pattern than the rendered one
+        let img = render_board(&board, &[0.0, 0.0, 0.0], &[-0.09, -0.06, 0.55]);
+        let found = find_chessboard_corners(&img, Size::new(9, 6)).unwrap();
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn test_draw_axes() {
+        let mut img = Mat::new_with_default(240, 320, 3, MatDepth::U8, Scalar::all(0.0)).unwrap();
+        let rvec = [0.0, 0.0, 0.0];
+        let tvec = [0.0, 0.0, 1.0];
+        draw_axes(&mut img, &camera(), &DistortionCoefficients::zero(), &rvec, &tvec, 0.1).unwrap();
+
+        // X axis runs right from the principal point, Y runs down
+        assert_eq!(img.at(120, 180).unwrap()[..3], [255, 0, 0]);
+        assert_eq!(img.at(150, 160).unwrap()[..3], [0, 255, 0]);
+    }
+}
@@ -1,5 +1,7 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::types::{Point, Point3f};
+use crate::calib3d::camera_model::{undistort_with_model, PinholeModel};
+use crate::core::Mat;
+use crate::core::types::{Point, Point2f, Point3f, Size};
 use crate::error::{Error, Result};
 
 /// Camera intrinsic parameters
@@ -252,6 +254,49 @@ fn project_point(
     Point::new(u as i32, v as i32)
 }
 
+/// Project 3D object points into the image with sub-pixel precision
+///
+/// Applies the pose (`rvec`, `tvec`), the lens distortion and the intrinsics,
+/// like `OpenCV`'s `projectPoints`.
+#[must_use]
+pub fn project_points(
+    object_points: &[Point3f],
+    rvec: &[f64; 3],
+    tvec: &[f64; 3],
+    camera: &CameraMatrix,
+    dist: &DistortionCoefficients,
+) -> Vec<Point2f> {
+    let r = rodrigues(rvec);
+
+    object_points
+        .iter()
+        .map(|p| {
+            let (px, py, pz) = (f64::from(p.x), f64::from(p.y), f64::from(p.z));
+            let x = r[0][0] * px + r[0][1] * py + r[0][2] * pz + tvec[0];
+            let y = r[1][0] * px + r[1][1] * py + r[1][2] * pz + tvec[1];
+            let z = r[2][0] * px + r[2][1] * py + r[2][2] * pz + tvec[2];
+
+            let (xd, yd) = dist.distort(x / z, y / z);
+            Point2f::new(
+                (camera.fx * xd + camera.cx) as f32,
+                (camera.fy * yd + camera.cy) as f32,
+            )
+        })
+        .collect()
+}
+
+/// Remove lens distortion from an image
+///
+/// Every output pixel is looked up at its distorted position in `src`
+/// (bilinear, black outside), so straight lines in the scene come out
+/// straight. `dst` keeps the size, type and camera matrix of `src`, like
+/// `OpenCV`'s `undistort` without a new camera matrix.
+pub fn undistort(src: &Mat, dst: &mut Mat, camera: &CameraMatrix, dist: &DistortionCoefficients) -> Result<()> {
+    let model = PinholeModel::new(camera.clone(), dist.clone());
+    let size = Size::new(src.cols() as i32, src.rows() as i32);
+    undistort_with_model(src, dst, &model, camera, size)
+}
+
 /// Convert rotation vector to rotation matrix using Rodrigues formula
 #[must_use] 
 pub fn rodrigues(rvec: &[f64; 3]) -> [[f64; 3]; 3] {
@@ -289,6 +334,40 @@ pub fn rodrigues(rvec: &[f64; 3]) -> [[f64; 3]; 3] {
     ]
 }
 
+/// Convert a rotation matrix to a rotation vector (inverse of [`rodrigues`])
+#[must_use]
+pub fn rodrigues_from_matrix(r: &[[f64; 3]; 3]) -> [f64; 3] {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    let cos_theta = ((trace - 1.0) / 2.0).clamp(-1.0, 1.0);
+    let theta = cos_theta.acos();
+
+    if theta < 1e-10 {
+        return [0.0, 0.0, 0.0];
+    }
+
+    if std::f64::consts::PI - theta < 1e-6 {
+        // sin(theta) ~ 0: recover the axis from the diagonal of (R + I) / 2
+        let xx = ((r[0][0] + 1.0) / 2.0).max(0.0).sqrt();
+        let yy = ((r[1][1] + 1.0) / 2.0).max(0.0).sqrt();
+        let zz = ((r[2][2] + 1.0) / 2.0).max(0.0).sqrt();
+        let (x, y, z) = if xx >= yy && xx >= zz {
+            (xx, (r[0][1] + r[1][0]) / (4.0 * xx), (r[0][2] + r[2][0]) / (4.0 * xx))
+        } else if yy >= zz {
+            ((r[0][1] + r[1][0]) / (4.0 * yy), yy, (r[1][2] + r[2][1]) / (4.0 * yy))
+        } else {
+            ((r[0][2] + r[2][0]) / (4.0 * zz), (r[1][2] + r[2][1]) / (4.0 * zz), zz)
+        };
+        return [x * theta, y * theta, z * theta];
+    }
+
+    let k = theta / (2.0 * theta.sin());
+    [
+        (r[2][1] - r[1][2]) * k,
+        (r[0][2] - r[2][0]) * k,
+        (r[1][0] - r[0][1]) * k,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,4 +410,33 @@ mod tests {
 
         assert!((det - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_undistort_image() {
+        use crate::core::MatDepth;
+
+        let camera = CameraMatrix::new(120.0, 120.0, 50.0, 40.0);
+        let dist = DistortionCoefficients::new(-0.2, 0.05, 0.0, 0.002, -0.001);
+        let ideal = |u: f64, v: f64| 128.0 + 100.0 * (u / 6.0).sin() * (v / 7.0).cos();
+
+        // Distorted view: each pixel shows the ideal image at its undistorted position
+        let mut distorted = Mat::new(80, 100, 1, MatDepth::U8).unwrap();
+        for row in 0..80 {
+            for col in 0..100 {
+                let (x, y) = dist.undistort((col as f64 - 50.0) / 120.0, (row as f64 - 40.0) / 120.0);
+                distorted.at_mut(row, col).unwrap()[0] = ideal(120.0 * x + 50.0, 120.0 * y + 40.0).round() as u8;
+            }
+        }
+
+        let mut restored = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        undistort(&distorted, &mut restored, &camera, &dist).unwrap();
+        assert_eq!((restored.rows(), restored.cols()), (80, 100));
+        let mut error = 0.0;
+        for row in 10..70 {
+            for col in 10..90 {
+                error += (f64::from(restored.at(row, col).unwrap()[0]) - ideal(col as f64, row as f64)).abs();
+            }
+        }
+        assert!(error / (60.0 * 80.0) < 3.0, "mean error {}", error / 4800.0);
+    }
 }
@@ -0,0 +1,334 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Camera intrinsics models
+//!
+//! A [`CameraModel`] maps points in camera coordinates to pixels and pixels
+//! back to viewing rays. Code that only needs those two operations
+//! (undistortion, pose estimation, triangulation) takes a `&dyn CameraModel`
+//! and works with any lens: [`PinholeModel`] (Brown-Conrady distortion),
+//! [`FisheyeModel`] (equidistant, Kannala-Brandt) and
+//! [`OmnidirectionalModel`] (unified model of Mei). Camera coordinates have
+//! +x right, +y down and +z along the optical axis.
+
+use crate::calib3d::camera::{rodrigues, CameraMatrix, DistortionCoefficients};
+use crate::calib3d::fisheye::{FisheyeCameraMatrix, FisheyeDistortion};
+use crate::core::types::{BorderType, InterpolationFlag, Point2f, Point3f, Size};
+use crate::core::Mat;
+use crate::error::{Error, Result};
+use crate::imgproc::PixelSampler;
+
+/// Projection between camera coordinates and pixels
+pub trait CameraModel {
+    /// Pixel position of a point in camera coordinates, or `None` if the
+    /// model cannot image it (e.g. behind a pinhole camera)
+    fn project(&self, point: &[f64; 3]) -> Option<[f64; 2]>;
+
+    /// Unit-length viewing ray through a pixel, or `None` if the pixel lies
+    /// outside the model's valid image area
+    fn unproject(&self, pixel: &[f64; 2]) -> Option<[f64; 3]>;
+}
+
+/// Perspective camera with radial-tangential lens distortion
+#[derive(Debug, Clone)]
+pub struct PinholeModel {
+    pub camera: CameraMatrix,
+    pub dist: DistortionCoefficients,
+}
+
+impl PinholeModel {
+    #[must_use]
+    pub fn new(camera: CameraMatrix, dist: DistortionCoefficients) -> Self {
+        Self { camera, dist }
+    }
+}
+
+impl CameraModel for PinholeModel {
+    fn project(&self, point: &[f64; 3]) -> Option<[f64; 2]> {
+        if point[2] <= 0.0 {
+            return None;
+        }
+        let (xd, yd) = self.dist.distort(point[0] / point[2], point[1] / point[2]);
+        Some(apply_intrinsics(&self.camera, xd, yd))
+    }
+
+    fn unproject(&self, pixel: &[f64; 2]) -> Option<[f64; 3]> {
+        let (xd, yd) = remove_intrinsics(&self.camera, pixel)?;
+        let (x, y) = self.dist.undistort(xd, yd);
+        normalize([x, y, 1.0])
+    }
+}
+
+/// Equidistant fisheye camera
+///
+/// The distorted image radius is `theta * (1 + k1 θ² + k2 θ⁴ + k3 θ⁶ + k4 θ⁸)`
+/// for a ray at angle `theta` from the optical axis, so fields of view beyond
+/// 180° are representable.
+#[derive(Debug, Clone)]
+pub struct FisheyeModel {
+    pub camera: FisheyeCameraMatrix,
+    pub dist: FisheyeDistortion,
+}
+
+impl FisheyeModel {
+    #[must_use]
+    pub fn new(camera: FisheyeCameraMatrix, dist: FisheyeDistortion) -> Self {
+        Self { camera, dist }
+    }
+
+    fn distort_angle(&self, theta: f64) -> f64 {
+        let t2 = theta * theta;
+        let d = &self.dist;
+        theta * (1.0 + t2 * (d.k1 + t2 * (d.k2 + t2 * (d.k3 + t2 * d.k4))))
+    }
+}
+
+impl CameraModel for FisheyeModel {
+    fn project(&self, point: &[f64; 3]) -> Option<[f64; 2]> {
+        let r = point[0].hypot(point[1]);
+        if r < 1e-12 {
+            // On the axis: only the forward direction is imaged
+            return (point[2] > 0.0).then_some([self.camera.cx, self.camera.cy]);
+        }
+        let scale = self.distort_angle(r.atan2(point[2])) / r;
+        Some([
+            self.camera.fx * point[0] * scale + self.camera.cx,
+            self.camera.fy * point[1] * scale + self.camera.cy,
+        ])
+    }
+
+    fn unproject(&self, pixel: &[f64; 2]) -> Option<[f64; 3]> {
+        if self.camera.fx == 0.0 || self.camera.fy == 0.0 {
+            return None;
+        }
+        let xd = (pixel[0] - self.camera.cx) / self.camera.fx;
+        let yd = (pixel[1] - self.camera.cy) / self.camera.fy;
+        let theta_d = xd.hypot(yd);
+        if theta_d < 1e-12 {
+            return Some([0.0, 0.0, 1.0]);
+        }
+
+        // Newton's method on distort_angle(theta) = theta_d
+        let mut theta = theta_d;
+        for _ in 0..20 {
+            let step = 1e-7;
+            let f = self.distort_angle(theta) - theta_d;
+            let df = (self.distort_angle(theta + step) - self.distort_angle(theta - step)) / (2.0 * step);
+            if df.abs() < 1e-12 {
+                return None;
+            }
+            theta -= f / df;
+            if f.abs() < 1e-12 {
+                break;
+            }
+        }
+        if !(0.0..=std::f64::consts::PI).contains(&theta) {
+            return None;
+        }
+
+        let s = theta.sin() / theta_d;
+        Some([xd * s, yd * s, theta.cos()])
+    }
+}
+
+/// Unified omnidirectional camera (Mei and Rives)
+///
+/// Points are first projected onto the unit sphere, then perspectively from
+/// a centre `xi` behind the sphere's centre, distorted and mapped to pixels.
+/// `xi = 0` is a pinhole camera; catadioptric and very wide lenses have
+/// `xi` around 1.
+#[derive(Debug, Clone)]
+pub struct OmnidirectionalModel {
+    pub camera: CameraMatrix,
+    pub xi: f64,
+    pub dist: DistortionCoefficients,
+}
+
+impl OmnidirectionalModel {
+    #[must_use]
+    pub fn new(camera: CameraMatrix, xi: f64, dist: DistortionCoefficients) -> Self {
+        Self { camera, xi, dist }
+    }
+}
+
+impl CameraModel for OmnidirectionalModel {
+    fn project(&self, point: &[f64; 3]) -> Option<[f64; 2]> {
+        let s = normalize(*point)?;
+        let denom = s[2] + self.xi;
+        if denom <= 1e-12 {
+            return None;
+        }
+        let (xd, yd) = self.dist.distort(s[0] / denom, s[1] / denom);
+        Some(apply_intrinsics(&self.camera, xd, yd))
+    }
+
+    fn unproject(&self, pixel: &[f64; 2]) -> Option<[f64; 3]> {
+        let (xd, yd) = remove_intrinsics(&self.camera, pixel)?;
+        let (x, y) = self.dist.undistort(xd, yd);
+
+        // Lift the normalized point back onto the unit sphere
+        let r2 = x * x + y * y;
+        let disc = 1.0 + (1.0 - self.xi * self.xi) * r2;
+        if disc < 0.0 {
+            return None;
+        }
+        let factor = (self.xi + disc.sqrt()) / (1.0 + r2);
+        normalize([factor * x, factor * y, factor - self.xi])
+    }
+}
+
+fn apply_intrinsics(camera: &CameraMatrix, x: f64, y: f64) -> [f64; 2] {
+    [camera.fx * x + camera.cx, camera.fy * y + camera.cy]
+}
+
+fn remove_intrinsics(camera: &CameraMatrix, pixel: &[f64; 2]) -> Option<(f64, f64)> {
+    if camera.fx == 0.0 || camera.fy == 0.0 {
+        return None;
+    }
+    Some(((pixel[0] - camera.cx) / camera.fx, (pixel[1] - camera.cy) / camera.fy))
+}
+
+fn normalize(v: [f64; 3]) -> Option<[f64; 3]> {
+    let norm = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    (norm > 1e-12).then(|| [v[0] / norm, v[1] / norm, v[2] / norm])
+}
+
+/// Project an object point given a rotation matrix and translation
+pub(crate) fn project_with_pose(
+    model: &dyn CameraModel,
+    r: &[[f64; 3]; 3],
+    tvec: &[f64; 3],
+    point: &Point3f,
+) -> Option<[f64; 2]> {
+    let p = [f64::from(point.x), f64::from(point.y), f64::from(point.z)];
+    let cam: [f64; 3] = std::array::from_fn(|i| r[i][0] * p[0] + r[i][1] * p[1] + r[i][2] * p[2] + tvec[i]);
+    model.project(&cam)
+}
+
+/// [`project_points`](crate::calib3d::project_points) for any camera model
+///
+/// Points the model cannot image are `None`.
+#[must_use]
+pub fn project_points_with_model(
+    object_points: &[Point3f],
+    rvec: &[f64; 3],
+    tvec: &[f64; 3],
+    model: &dyn CameraModel,
+) -> Vec<Option<Point2f>> {
+    let r = rodrigues(rvec);
+    object_points
+        .iter()
+        .map(|p| project_with_pose(model, &r, tvec, p).map(|[u, v]| Point2f::new(u as f32, v as f32)))
+        .collect()
+}
+
+/// Resample an image taken with `model` as seen by an ideal pinhole camera
+///
+/// `dst` gets size `dsize` and intrinsics `new_camera`; each of its pixels is
+/// looked up (bilinear, black outside) where its viewing ray lands in `src`.
+/// This undistorts pinhole images and rectifies fisheye and omnidirectional
+/// ones to a perspective view.
+pub fn undistort_with_model(
+    src: &Mat,
+    dst: &mut Mat,
+    model: &dyn CameraModel,
+    new_camera: &CameraMatrix,
+    dsize: Size,
+) -> Result<()> {
+    if new_camera.fx == 0.0 || new_camera.fy == 0.0 {
+        return Err(Error::InvalidParameter(
+            "Camera focal lengths must be non-zero".to_string(),
+        ));
+    }
+
+    let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Constant);
+    sampler.warp(src, dst, dsize, |u, v| {
+        let ray = [(u - new_camera.cx) / new_camera.fx, (v - new_camera.cy) / new_camera.fy, 1.0];
+        model.project(&ray).map(|[x, y]| (x, y))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calib3d::camera::project_points;
+    use crate::calib3d::fisheye::fisheye_project_points;
+
+    fn round_trip(model: &dyn CameraModel, points: &[[f64; 3]]) {
+        for p in points {
+            let pixel = model.project(p).unwrap();
+            let ray = model.unproject(&pixel).unwrap();
+            let expected = normalize(*p).unwrap();
+            for i in 0..3 {
+                assert!((ray[i] - expected[i]).abs() < 1e-6, "{p:?} -> {pixel:?} -> {ray:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pinhole_model() {
+        let camera = CameraMatrix::new(700.0, 680.0, 320.0, 240.0);
+        let dist = DistortionCoefficients::new(-0.1, 0.02, 0.0, 0.001, -0.0005);
+        let model = PinholeModel::new(camera.clone(), dist.clone());
+        round_trip(&model, &[[0.0, 0.0, 1.0], [0.2, -0.1, 1.5], [-0.3, 0.25, 2.0]]);
+        assert!(model.project(&[0.1, 0.0, -1.0]).is_none());
+
+        // Agrees with the pinhole-only projection
+        let object = [Point3f::new(0.1, -0.05, 0.2), Point3f::new(-0.2, 0.1, 0.0)];
+        let (rvec, tvec) = ([0.1, -0.2, 0.05], [0.0, 0.1, 1.2]);
+        let expected = project_points(&object, &rvec, &tvec, &camera, &dist);
+        for (a, b) in project_points_with_model(&object, &rvec, &tvec, &model).iter().zip(&expected) {
+            let a = a.unwrap();
+            assert!((a.x - b.x).abs() < 1e-3 && (a.y - b.y).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_fisheye_model() {
+        let camera = FisheyeCameraMatrix::new(300.0, 300.0, 640.0, 480.0);
+        let dist = FisheyeDistortion::from_array(&[-0.02, 0.003, 0.0, 0.0]);
+        let model = FisheyeModel::new(camera.clone(), dist.clone());
+
+        // Beyond 90° off-axis is still imaged
+        round_trip(&model, &[[0.0, 0.0, 1.0], [0.5, 0.2, 1.0], [1.0, -0.5, -0.2]]);
+        assert!(model.project(&[0.0, 0.0, -1.0]).is_none());
+
+        let object = [Point3f::new(0.3, 0.1, 0.0)];
+        let expected = fisheye_project_points(&object, &camera, &dist, &[0.0; 3], &[0.0, 0.0, 1.0]).unwrap();
+        let projected = project_points_with_model(&object, &[0.0; 3], &[0.0, 0.0, 1.0], &model)[0].unwrap();
+        assert!((projected.x - expected[0].x).abs() < 1e-3 && (projected.y - expected[0].y).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_omnidirectional_model() {
+        let camera = CameraMatrix::new(250.0, 250.0, 400.0, 400.0);
+        let model = OmnidirectionalModel::new(camera.clone(), 0.9, DistortionCoefficients::new(-0.05, 0.01, 0.0, 0.0, 0.0));
+        round_trip(&model, &[[0.0, 0.0, 1.0], [1.0, 0.5, 0.5], [1.0, 0.0, -0.3]]);
+
+        // xi = 0 is a pinhole camera
+        let omni = OmnidirectionalModel::new(camera.clone(), 0.0, DistortionCoefficients::zero());
+        let pinhole = PinholeModel::new(camera, DistortionCoefficients::zero());
+        let p = [0.3, -0.2, 2.0];
+        let (a, b) = (omni.project(&p).unwrap(), pinhole.project(&p).unwrap());
+        assert!((a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_undistort_with_model() {
+        use crate::core::MatDepth;
+
+        // A fisheye image of a bright spot 30° right of the axis
+        let model = FisheyeModel::new(FisheyeCameraMatrix::new(100.0, 100.0, 100.0, 100.0), FisheyeDistortion::new());
+        let angle = 30f64.to_radians();
+        let [u, v] = model.project(&[angle.sin(), 0.0, angle.cos()]).unwrap();
+        let mut fisheye = Mat::new(201, 201, 1, MatDepth::U8).unwrap();
+        fisheye.at_mut(v.round() as usize, u.round() as usize).unwrap()[0] = 255;
+
+        let view = CameraMatrix::new(100.0, 100.0, 100.0, 100.0);
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        undistort_with_model(&fisheye, &mut dst, &model, &view, Size::new(201, 201)).unwrap();
+
+        // The spot lands where a pinhole camera sees that direction
+        let expected_col = (100.0 + 100.0 * angle.tan()).round() as usize;
+        assert!(dst.at(100, expected_col).unwrap()[0] > 0);
+        assert_eq!(dst.at(100, 130).unwrap()[0], 0);
+    }
+}
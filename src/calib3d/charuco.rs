@@ -0,0 +1,697 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! `ChArUco` calibration workflow
+//!
+//! [`CharucoCalibrator`] collects board detections frame by frame, checks
+//! that the corners seen so far cover enough of the image, and runs a full
+//! calibration (Zhang initialization followed by joint Levenberg-Marquardt
+//! over intrinsics, distortion and every view's pose). The result includes a
+//! per-cell reprojection-error heatmap to spot regions the lens model fits
+//! poorly.
+
+use crate::calib3d::camera::{rodrigues, CameraMatrix, DistortionCoefficients};
+use crate::calib3d::pnp::{homography_dlt, solve_dense, solve_pnp_planar};
+use crate::core::{Mat, MatDepth};
+use crate::core::types::{Point2f, Point3f, Size};
+use crate::error::{Error, Result};
+#[cfg(feature = "objdetect")]
+use crate::objdetect::ArucoMarker;
+
+/// `ChArUco` board geometry
+///
+/// A chessboard of `squares_x` x `squares_y` squares with an `ArUco` marker in
+/// every white square, laid out as in `OpenCV`: the top-left square holds
+/// marker 0 and markers are numbered in raster order. Board coordinates have
+/// their origin at the top-left board corner, x to the right, y down, z = 0.
+/// `ChArUco` corners are the inner chessboard corners, numbered row by row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CharucoBoard {
+    pub squares_x: usize,
+    pub squares_y: usize,
+    /// Side length of a chessboard square, in the units poses are wanted in
+    pub square_length: f32,
+    /// Side length of a marker, smaller than `square_length`
+    pub marker_length: f32,
+}
+
+/// `ChArUco` corners found in one image
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CharucoDetection {
+    /// Corner ids, see [`CharucoBoard::corner_position`]
+    pub ids: Vec<usize>,
+    /// Image position of each corner in `ids`
+    pub corners: Vec<Point2f>,
+}
+
+impl CharucoBoard {
+    pub fn new(squares_x: usize, squares_y: usize, square_length: f32, marker_length: f32) -> Result<Self> {
+        if squares_x < 2 || squares_y < 2 {
+            return Err(Error::InvalidParameter(
+                "ChArUco board needs at least 2x2 squares".to_string(),
+            ));
+        }
+
+        if !(marker_length > 0.0 && marker_length < square_length) {
+            return Err(Error::InvalidParameter(
+                "Marker length must be positive and smaller than the square length".to_string(),
+            ));
+        }
+
+        Ok(Self { squares_x, squares_y, square_length, marker_length })
+    }
+
+    /// Number of `ChArUco` (inner chessboard) corners
+    #[must_use]
+    pub fn corner_count(&self) -> usize {
+        (self.squares_x - 1) * (self.squares_y - 1)
+    }
+
+    /// Number of markers on the board
+    #[must_use]
+    pub fn marker_count(&self) -> usize {
+        (self.squares_x * self.squares_y).div_ceil(2)
+    }
+
+    /// Board position of corner `id`, or `None` if the id is out of range
+    #[must_use]
+    pub fn corner_position(&self, id: usize) -> Option<Point3f> {
+        if id >= self.corner_count() {
+            return None;
+        }
+        let (col, row) = (id % (self.squares_x - 1) + 1, id / (self.squares_x - 1) + 1);
+        Some(Point3f::new(col as f32 * self.square_length, row as f32 * self.square_length, 0.0))
+    }
+
+    /// Board positions of every corner, indexed by id
+    #[must_use]
+    pub fn object_points(&self) -> Vec<Point3f> {
+        (0..self.corner_count()).filter_map(|id| self.corner_position(id)).collect()
+    }
+
+    /// Board positions of a marker's corners, clockwise from its top-left
+    #[must_use]
+    pub fn marker_corners(&self, marker_id: usize) -> Option<[Point3f; 4]> {
+        let (x, y) = self.marker_square(marker_id)?;
+        let margin = (self.square_length - self.marker_length) / 2.0;
+        let left = x as f32 * self.square_length + margin;
+        let top = y as f32 * self.square_length + margin;
+        let (right, bottom) = (left + self.marker_length, top + self.marker_length);
+        Some([
+            Point3f::new(left, top, 0.0),
+            Point3f::new(right, top, 0.0),
+            Point3f::new(right, bottom, 0.0),
+            Point3f::new(left, bottom, 0.0),
+        ])
+    }
+
+    /// Locate `ChArUco` corners from detected markers
+    ///
+    /// A corner is reported when both markers touching it were detected; its
+    /// position comes from the homography fitted to those two markers, which
+    /// keeps lens distortion local. Markers with ids outside the board are
+    /// ignored. Refine the result with
+    /// [`corner_sub_pix`](crate::calib3d::corner_sub_pix) for best accuracy.
+    #[cfg(feature = "objdetect")]
+    pub fn interpolate_corners(&self, markers: &[ArucoMarker]) -> Result<CharucoDetection> {
+        let mut seen = vec![None; self.marker_count()];
+        for marker in markers {
+            let Some(slot) = usize::try_from(marker.id).ok().and_then(|id| seen.get_mut(id)) else {
+                continue;
+            };
+            if marker.corners.len() == 4 {
+                *slot = Some(&marker.corners);
+            }
+        }
+
+        let mut detection = CharucoDetection::default();
+        for id in 0..self.corner_count() {
+            let [a, b] = self.corner_markers(id);
+            let (Some(image_a), Some(image_b)) = (seen[a], seen[b]) else {
+                continue;
+            };
+
+            let (Some(board_a), Some(board_b)) = (self.marker_corners(a), self.marker_corners(b)) else {
+                continue;
+            };
+            let plane: Vec<[f64; 2]> = board_a
+                .iter()
+                .chain(&board_b)
+                .map(|p| [f64::from(p.x), f64::from(p.y)])
+                .collect();
+            let image: Vec<[f64; 2]> = image_a
+                .iter()
+                .chain(image_b)
+                .map(|p| [f64::from(p.x), f64::from(p.y)])
+                .collect();
+            let h = homography_dlt(&plane, &image)?;
+
+            let Some(corner) = self.corner_position(id) else {
+                continue;
+            };
+            let (x, y) = (f64::from(corner.x), f64::from(corner.y));
+            let w = h[2][0] * x + h[2][1] * y + h[2][2];
+            detection.ids.push(id);
+            detection.corners.push(Point2f::new(
+                ((h[0][0] * x + h[0][1] * y + h[0][2]) / w) as f32,
+                ((h[1][0] * x + h[1][1] * y + h[1][2]) / w) as f32,
+            ));
+        }
+
+        Ok(detection)
+    }
+
+    /// Square `(x, y)` holding marker `marker_id`
+    fn marker_square(&self, marker_id: usize) -> Option<(usize, usize)> {
+        (0..self.squares_y)
+            .flat_map(|y| (0..self.squares_x).map(move |x| (x, y)))
+            .filter(|(x, y)| (x + y) % 2 == 0)
+            .nth(marker_id)
+    }
+
+    /// The two markers diagonally touching corner `id`
+    #[cfg(feature = "objdetect")]
+    fn corner_markers(&self, id: usize) -> [usize; 2] {
+        let (col, row) = (id % (self.squares_x - 1) + 1, id / (self.squares_x - 1) + 1);
+        let squares = if (col + row) % 2 == 0 {
+            [(col - 1, row - 1), (col, row)]
+        } else {
+            [(col, row - 1), (col - 1, row)]
+        };
+        // Markers sit on squares with an even x + y, counted in raster order
+        squares.map(|(x, y)| {
+            (0..y).map(|r| (self.squares_x + usize::from(r % 2 == 0)) / 2).sum::<usize>() + x / 2
+        })
+    }
+}
+
+/// Result of [`CharucoCalibrator::calibrate`]
+#[derive(Debug, Clone)]
+pub struct CharucoCalibration {
+    pub camera_matrix: CameraMatrix,
+    /// Radial `k1`, `k2` and tangential `p1`, `p2`; `k3` is kept at zero
+    pub distortion: DistortionCoefficients,
+    /// RMS reprojection error over all corners, in pixels
+    pub rms_error: f64,
+    /// RMS reprojection error of each accepted view, in pixels
+    pub per_view_errors: Vec<f64>,
+    /// Board pose `(rvec, tvec)` of each accepted view
+    pub poses: Vec<([f64; 3], [f64; 3])>,
+    /// F32 Mat with one cell per coverage-grid cell holding the RMS
+    /// reprojection error of the corners observed there (0 where none were)
+    pub error_heatmap: Mat,
+}
+
+/// Accumulates `ChArUco` detections and calibrates a camera from them
+pub struct CharucoCalibrator {
+    board: CharucoBoard,
+    image_size: Size,
+    min_corners: usize,
+    grid: (usize, usize),
+    min_coverage: f64,
+    views: Vec<CharucoDetection>,
+}
+
+impl CharucoCalibrator {
+    /// Calibrator for `board` seen by a camera producing `image_size` frames
+    ///
+    /// Defaults: views need 6 corners, coverage is measured on an 8x6 grid and
+    /// calibration requires half of its cells to contain a corner.
+    #[must_use]
+    pub fn new(board: CharucoBoard, image_size: Size) -> Self {
+        Self {
+            board,
+            image_size,
+            min_corners: 6,
+            grid: (8, 6),
+            min_coverage: 0.5,
+            views: Vec::new(),
+        }
+    }
+
+    /// Minimum corners for a detection to be kept (at least 4)
+    #[must_use]
+    pub fn with_min_corners(mut self, min_corners: usize) -> Self {
+        self.min_corners = min_corners.max(4);
+        self
+    }
+
+    /// Coverage and heatmap grid resolution, in cells across and down
+    #[must_use]
+    pub fn with_coverage_grid(mut self, cols: usize, rows: usize) -> Self {
+        self.grid = (cols.max(1), rows.max(1));
+        self
+    }
+
+    /// Fraction of grid cells that must contain a corner before calibrating
+    #[must_use]
+    pub fn with_min_coverage(mut self, fraction: f64) -> Self {
+        self.min_coverage = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add one frame's detection
+    ///
+    /// Returns `Ok(false)` without storing it when it has fewer than the
+    /// minimum number of corners. Unknown or repeated ids are an error.
+    pub fn add_detection(&mut self, detection: &CharucoDetection) -> Result<bool> {
+        if detection.ids.len() != detection.corners.len() {
+            return Err(Error::InvalidParameter(
+                "Detection ids and corners must have the same length".to_string(),
+            ));
+        }
+
+        let mut used = vec![false; self.board.corner_count()];
+        for &id in &detection.ids {
+            match used.get_mut(id) {
+                Some(flag) if !*flag => *flag = true,
+                Some(_) => return Err(Error::InvalidParameter(format!("Corner id {id} appears twice"))),
+                None => return Err(Error::InvalidParameter(format!("Corner id {id} is not on the board"))),
+            }
+        }
+
+        if detection.ids.len() < self.min_corners {
+            return Ok(false);
+        }
+        self.views.push(detection.clone());
+        Ok(true)
+    }
+
+    /// Number of stored views
+    #[must_use]
+    pub fn view_count(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Drop all stored views
+    pub fn clear(&mut self) {
+        self.views.clear();
+    }
+
+    /// S32 Mat counting the stored corners that fall in each grid cell
+    pub fn coverage_map(&self) -> Result<Mat> {
+        let (cols, rows) = self.grid;
+        let mut map = Mat::new(rows, cols, 1, MatDepth::S32)?;
+        for view in &self.views {
+            for corner in &view.corners {
+                if let Some((row, col)) = self.cell(corner) {
+                    let count = map.at_i32(row, col, 0)?;
+                    map.set_i32(row, col, 0, count + 1)?;
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// Fraction of grid cells containing at least one stored corner
+    pub fn coverage(&self) -> Result<f64> {
+        let map = self.coverage_map()?;
+        let mut covered = 0usize;
+        for row in 0..map.rows() {
+            for col in 0..map.cols() {
+                covered += usize::from(map.at_i32(row, col, 0)? > 0);
+            }
+        }
+        Ok(covered as f64 / (map.rows() * map.cols()) as f64)
+    }
+
+    /// Calibrate from the stored views
+    ///
+    /// Needs at least 3 views and the configured coverage. Intrinsics start
+    /// from Zhang's closed form with the principal point at the image centre
+    /// and are refined jointly with the distortion and all view poses.
+    pub fn calibrate(&self) -> Result<CharucoCalibration> {
+        if self.views.len() < 3 {
+            return Err(Error::InvalidParameter(format!(
+                "Calibration needs at least 3 views, have {}",
+                self.views.len()
+            )));
+        }
+
+        let coverage = self.coverage()?;
+        if coverage < self.min_coverage {
+            return Err(Error::InvalidParameter(format!(
+                "Corners cover {:.0}% of the image, {:.0}% required; add views near the uncovered regions",
+                coverage * 100.0,
+                self.min_coverage * 100.0
+            )));
+        }
+
+        let views: Vec<(Vec<Point3f>, &[Point2f])> = self
+            .views
+            .iter()
+            .map(|v| {
+                let object = v.ids.iter().filter_map(|&id| self.board.corner_position(id)).collect();
+                (object, v.corners.as_slice())
+            })
+            .collect();
+
+        let camera = self.initial_camera(&views)?;
+        let dist = DistortionCoefficients::zero();
+        let mut params = vec![camera.fx, camera.fy, camera.cx, camera.cy, 0.0, 0.0, 0.0, 0.0];
+        for (object, image) in &views {
+            let (rvec, tvec) = solve_pnp_planar(object, image, &camera, &dist)?;
+            params.extend(rvec);
+            params.extend(tvec);
+        }
+
+        refine_calibration(&views, &mut params);
+
+        let (camera, dist) = intrinsics(&params);
+        let (cols, rows) = self.grid;
+        let mut cell_sums = vec![(0.0f64, 0usize); rows * cols];
+        let mut per_view_errors = Vec::with_capacity(views.len());
+        let mut poses = Vec::with_capacity(views.len());
+        let (mut total, mut count) = (0.0, 0usize);
+
+        for (v, (object, image)) in views.iter().enumerate() {
+            let residuals = view_residuals(&params, v, object, image);
+            let mut view_sum = 0.0;
+            for (r, q) in residuals.chunks_exact(2).zip(image.iter()) {
+                let err = r[0] * r[0] + r[1] * r[1];
+                view_sum += err;
+                if let Some((row, col)) = self.cell(q) {
+                    let cell = &mut cell_sums[row * cols + col];
+                    cell.0 += err;
+                    cell.1 += 1;
+                }
+            }
+            per_view_errors.push((view_sum / image.len() as f64).sqrt());
+            poses.push(view_pose(&params, v));
+            total += view_sum;
+            count += image.len();
+        }
+
+        let mut error_heatmap = Mat::new(rows, cols, 1, MatDepth::F32)?;
+        for (i, &(sum, n)) in cell_sums.iter().enumerate() {
+            let rms = if n > 0 { (sum / n as f64).sqrt() } else { 0.0 };
+            error_heatmap.set_f32(i / cols, i % cols, 0, rms as f32)?;
+        }
+
+        Ok(CharucoCalibration {
+            camera_matrix: camera,
+            distortion: dist,
+            rms_error: (total / count as f64).sqrt(),
+            per_view_errors,
+            poses,
+            error_heatmap,
+        })
+    }
+
+    /// Grid cell `(row, col)` containing an image point
+    fn cell(&self, p: &Point2f) -> Option<(usize, usize)> {
+        let (cols, rows) = self.grid;
+        let (width, height) = (f64::from(self.image_size.width), f64::from(self.image_size.height));
+        let (x, y) = (f64::from(p.x), f64::from(p.y));
+        if !(x >= 0.0 && y >= 0.0 && x < width && y < height) {
+            return None;
+        }
+        Some(((y / height * rows as f64) as usize, (x / width * cols as f64) as usize))
+    }
+
+    /// Focal lengths from the view homographies, principal point at the centre
+    fn initial_camera(&self, views: &[(Vec<Point3f>, &[Point2f])]) -> Result<CameraMatrix> {
+        let cx = (f64::from(self.image_size.width) - 1.0) / 2.0;
+        let cy = (f64::from(self.image_size.height) - 1.0) / 2.0;
+
+        // Each homography h1, h2 (principal point removed) gives two linear
+        // constraints on a = 1/fx², b = 1/fy²: h1ᵀBh2 = 0, h1ᵀBh1 = h2ᵀBh2
+        let mut ata = vec![vec![0.0; 2]; 2];
+        let mut atb = vec![0.0; 2];
+        for (object, image) in views {
+            let plane: Vec<[f64; 2]> = object.iter().map(|p| [f64::from(p.x), f64::from(p.y)]).collect();
+            let pixels: Vec<[f64; 2]> = image
+                .iter()
+                .map(|p| [f64::from(p.x) - cx, f64::from(p.y) - cy])
+                .collect();
+            let h = homography_dlt(&plane, &pixels)?;
+            let (h1, h2) = ([h[0][0], h[1][0], h[2][0]], [h[0][1], h[1][1], h[2][1]]);
+
+            let rows = [
+                ([h1[0] * h2[0], h1[1] * h2[1]], -h1[2] * h2[2]),
+                ([h1[0] * h1[0] - h2[0] * h2[0], h1[1] * h1[1] - h2[1] * h2[1]], h2[2] * h2[2] - h1[2] * h1[2]),
+            ];
+            for (a, b) in rows {
+                for i in 0..2 {
+                    for j in 0..2 {
+                        ata[i][j] += a[i] * a[j];
+                    }
+                    atb[i] += a[i] * b;
+                }
+            }
+        }
+
+        let fallback = f64::from(self.image_size.width.max(self.image_size.height));
+        let (fx, fy) = match solve_dense(ata, atb) {
+            Some(ab) if ab[0] > 0.0 && ab[1] > 0.0 => (1.0 / ab[0].sqrt(), 1.0 / ab[1].sqrt()),
+            _ => (fallback, fallback),
+        };
+        Ok(CameraMatrix::new(fx, fy, cx, cy))
+    }
+}
+
+/// Camera and distortion from the first 8 calibration parameters
+fn intrinsics(params: &[f64]) -> (CameraMatrix, DistortionCoefficients) {
+    (
+        CameraMatrix::new(params[0], params[1], params[2], params[3]),
+        DistortionCoefficients::new(params[4], params[5], 0.0, params[6], params[7]),
+    )
+}
+
+fn view_pose(params: &[f64], view: usize) -> ([f64; 3], [f64; 3]) {
+    let p = &params[8 + 6 * view..14 + 6 * view];
+    ([p[0], p[1], p[2]], [p[3], p[4], p[5]])
+}
+
+/// Pixel residuals of one view, projected in f64 so that the numerical
+/// Jacobian is not swamped by rounding
+fn view_residuals(params: &[f64], view: usize, object: &[Point3f], image: &[Point2f]) -> Vec<f64> {
+    let (camera, dist) = intrinsics(params);
+    let (rvec, tvec) = view_pose(params, view);
+    let r = rodrigues(&rvec);
+
+    object
+        .iter()
+        .zip(image)
+        .flat_map(|(p, q)| {
+            let (px, py, pz) = (f64::from(p.x), f64::from(p.y), f64::from(p.z));
+            let x = r[0][0] * px + r[0][1] * py + r[0][2] * pz + tvec[0];
+            let y = r[1][0] * px + r[1][1] * py + r[1][2] * pz + tvec[1];
+            let z = r[2][0] * px + r[2][1] * py + r[2][2] * pz + tvec[2];
+            let (xd, yd) = dist.distort(x / z, y / z);
+            [
+                camera.fx * xd + camera.cx - f64::from(q.x),
+                camera.fy * yd + camera.cy - f64::from(q.y),
+            ]
+        })
+        .collect()
+}
+
+/// Joint Levenberg-Marquardt over intrinsics, distortion and view poses
+///
+/// Each view's residuals depend only on the 8 shared parameters and its own
+/// 6 pose parameters, so the Jacobian is assembled view by view.
+fn refine_calibration(views: &[(Vec<Point3f>, &[Point2f])], params: &mut Vec<f64>) {
+    let n = params.len();
+    let residuals = |p: &[f64]| -> Vec<Vec<f64>> {
+        views
+            .iter()
+            .enumerate()
+            .map(|(v, (object, image))| view_residuals(p, v, object, image))
+            .collect()
+    };
+    let cost = |r: &[Vec<f64>]| r.iter().flatten().map(|v| v * v).sum::<f64>();
+
+    let mut lambda = 1e-3;
+    let mut current_cost = cost(&residuals(params));
+
+    for _ in 0..100 {
+        let current = residuals(params);
+        let mut jtj = vec![vec![0.0; n]; n];
+        let mut jtr = vec![0.0; n];
+
+        for (v, (object, image)) in views.iter().enumerate() {
+            let columns: Vec<usize> = (0..8).chain(8 + 6 * v..14 + 6 * v).collect();
+            let jacobian: Vec<Vec<f64>> = columns
+                .iter()
+                .map(|&k| {
+                    let step = 1e-6 * params[k].abs().max(1.0);
+                    let mut plus = params.clone();
+                    let mut minus = params.clone();
+                    plus[k] += step;
+                    minus[k] -= step;
+                    view_residuals(&plus, v, object, image)
+                        .iter()
+                        .zip(view_residuals(&minus, v, object, image))
+                        .map(|(a, b)| (a - b) / (2.0 * step))
+                        .collect()
+                })
+                .collect();
+
+            for (a, &i) in columns.iter().enumerate() {
+                for (b, &j) in columns.iter().enumerate() {
+                    jtj[i][j] += jacobian[a].iter().zip(&jacobian[b]).map(|(x, y)| x * y).sum::<f64>();
+                }
+                jtr[i] -= jacobian[a].iter().zip(&current[v]).map(|(x, r)| x * r).sum::<f64>();
+            }
+        }
+
+        let mut improved = false;
+        while lambda < 1e10 {
+            let mut damped = jtj.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += lambda * jtj[i][i].max(1e-12);
+            }
+
+            let Some(delta) = solve_dense(damped, jtr.clone()) else {
+                lambda *= 10.0;
+                continue;
+            };
+
+            let candidate: Vec<f64> = params.iter().zip(&delta).map(|(p, d)| p + d).collect();
+            let c = cost(&residuals(&candidate));
+            if c < current_cost {
+                let gain = current_cost - c;
+                *params = candidate;
+                current_cost = c;
+                lambda = (lambda / 10.0).max(1e-12);
+                improved = gain > 1e-12 * current_cost.max(1e-30);
+                break;
+            }
+            lambda *= 10.0;
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calib3d::camera::project_points;
+
+    fn board() -> CharucoBoard {
+        CharucoBoard::new(9, 7, 30.0, 22.0).unwrap()
+    }
+
+    fn true_camera() -> (CameraMatrix, DistortionCoefficients) {
+        (
+            CameraMatrix::new(820.0, 810.0, 330.0, 236.0),
+            DistortionCoefficients::new(-0.12, 0.08, 0.0, 0.001, -0.0015),
+        )
+    }
+
+    /// Views spread over the image with varied tilt
+    fn poses() -> Vec<([f64; 3], [f64; 3])> {
+        let mut poses = Vec::new();
+        for (i, (tx, ty)) in [(-200.0, -150.0), (20.0, -150.0), (-200.0, 30.0), (20.0, 30.0), (-90.0, -60.0)]
+            .into_iter()
+            .enumerate()
+        {
+            let tilt = 0.25 + 0.05 * i as f64;
+            poses.push(([tilt, -tilt * 0.6, 0.05 * i as f64], [tx, ty, 700.0 + 40.0 * i as f64]));
+            poses.push(([-tilt * 0.7, tilt, -0.1], [tx + 10.0, ty - 5.0, 650.0]));
+        }
+        poses
+    }
+
+    fn detection(board: &CharucoBoard, rvec: &[f64; 3], tvec: &[f64; 3]) -> CharucoDetection {
+        let (camera, dist) = true_camera();
+        let corners = project_points(&board.object_points(), rvec, tvec, &camera, &dist);
+        let (ids, corners) = corners
+            .into_iter()
+            .enumerate()
+            .filter(|(_, p)| p.x >= 0.0 && p.y >= 0.0 && p.x < 640.0 && p.y < 480.0)
+            .unzip();
+        CharucoDetection { ids, corners }
+    }
+
+    #[test]
+    fn test_charuco_board_layout() {
+        let board = board();
+        assert_eq!(board.corner_count(), 48);
+        assert_eq!(board.marker_count(), 32);
+        assert_eq!(board.corner_position(0), Some(Point3f::new(30.0, 30.0, 0.0)));
+        assert_eq!(board.corner_position(9), Some(Point3f::new(60.0, 60.0, 0.0)));
+        assert_eq!(board.corner_position(48), None);
+
+        // Marker 0 is in the top-left square, marker 5 starts the second row
+        assert_eq!(board.marker_corners(0).unwrap()[0], Point3f::new(4.0, 4.0, 0.0));
+        assert_eq!(board.marker_corners(5).unwrap()[0], Point3f::new(34.0, 34.0, 0.0));
+        assert!(board.marker_corners(32).is_none());
+        assert!(CharucoBoard::new(5, 5, 10.0, 12.0).is_err());
+    }
+
+    #[cfg(feature = "objdetect")]
+    #[test]
+    fn test_charuco_interpolate_corners() {
+        let board = board();
+        let (camera, dist) = (CameraMatrix::new(800.0, 800.0, 320.0, 240.0), DistortionCoefficients::zero());
+        let (rvec, tvec) = ([0.2, -0.3, 0.1], [-120.0, -90.0, 600.0]);
+
+        // Every marker but 6 is detected
+        let markers: Vec<ArucoMarker> = (0..board.marker_count())
+            .filter(|&id| id != 6)
+            .map(|id| ArucoMarker {
+                id: id as i32,
+                corners: project_points(&board.marker_corners(id).unwrap(), &rvec, &tvec, &camera, &dist),
+            })
+            .collect();
+
+        let detection = board.interpolate_corners(&markers).unwrap();
+        let expected = project_points(&board.object_points(), &rvec, &tvec, &camera, &dist);
+        let lost: Vec<usize> = (0..board.corner_count()).filter(|&id| board.corner_markers(id).contains(&6)).collect();
+        assert_eq!(lost.len(), 4);
+        assert_eq!(detection.ids.len(), board.corner_count() - lost.len());
+        for (&id, p) in detection.ids.iter().zip(&detection.corners) {
+            assert!(!lost.contains(&id));
+            assert!((p.x - expected[id].x).abs() < 1e-2 && (p.y - expected[id].y).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_charuco_calibration() {
+        let board = board();
+        let mut calibrator = CharucoCalibrator::new(board, Size::new(640, 480)).with_coverage_grid(4, 3);
+
+        let sparse = CharucoDetection { ids: vec![0, 1, 2], corners: vec![Point2f::new(1.0, 1.0); 3] };
+        assert!(!calibrator.add_detection(&sparse).unwrap());
+        let repeated = CharucoDetection { ids: vec![0, 0], corners: vec![Point2f::new(1.0, 1.0); 2] };
+        assert!(calibrator.add_detection(&repeated).is_err());
+
+        // A single corner of the image is not enough coverage
+        let poses = poses();
+        for (rvec, tvec) in poses.iter().take(3) {
+            let mut d = detection(&board, rvec, tvec);
+            let keep: Vec<bool> = d.corners.iter().map(|p| p.x < 160.0 && p.y < 160.0).collect();
+            let mut k = keep.iter();
+            d.ids.retain(|_| *k.next().unwrap());
+            let mut k = keep.iter();
+            d.corners.retain(|_| *k.next().unwrap());
+            calibrator.add_detection(&d).unwrap();
+        }
+        assert!(calibrator.coverage().unwrap() < 0.5);
+        assert!(calibrator.calibrate().is_err());
+
+        calibrator.clear();
+        for (rvec, tvec) in &poses {
+            assert!(calibrator.add_detection(&detection(&board, rvec, tvec)).unwrap());
+        }
+        assert_eq!(calibrator.view_count(), poses.len());
+        assert!(calibrator.coverage().unwrap() > 0.9);
+
+        let result = calibrator.calibrate().unwrap();
+        let (camera, dist) = true_camera();
+        assert!((result.camera_matrix.fx - camera.fx).abs() < 0.5, "fx {}", result.camera_matrix.fx);
+        assert!((result.camera_matrix.fy - camera.fy).abs() < 0.5, "fy {}", result.camera_matrix.fy);
+        assert!((result.camera_matrix.cx - camera.cx).abs() < 0.5);
+        assert!((result.camera_matrix.cy - camera.cy).abs() < 0.5);
+        assert!((result.distortion.k[0] - dist.k[0]).abs() < 1e-3);
+        assert!(result.rms_error < 1e-2);
+        assert_eq!(result.per_view_errors.len(), poses.len());
+        assert!((result.poses[0].1[2] - poses[0].1[2]).abs() < 0.5);
+
+        assert_eq!((result.error_heatmap.rows(), result.error_heatmap.cols()), (3, 4));
+        assert_eq!(result.error_heatmap.depth(), MatDepth::F32);
+    }
+}
@@ -0,0 +1,417 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Disparity post-processing
+//!
+//! Raw block-matching disparity is noisy: textureless regions produce
+//! isolated wrong matches and occlusions leave holes. These helpers clean a
+//! single-channel disparity map before it is reprojected to 3-D. A pixel is
+//! treated as invalid when its disparity is `<= 0` or not finite.
+
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Edge-preserving weighted-least-squares disparity smoothing
+///
+/// Solves the WLS energy with the fast global smoother: alternating
+/// horizontal and vertical 1-D passes, each an exact tridiagonal solve, with
+/// neighbour weights `exp(-|I_p - I_q| / sigma_color)` taken from the guide
+/// image. Invalid pixels get zero confidence, so they are filled from their
+/// valid surroundings instead of dragging them towards zero.
+#[derive(Debug, Clone)]
+pub struct DisparityWLSFilter {
+    lambda: f32,
+    sigma_color: f32,
+    iterations: usize,
+}
+
+impl Default for DisparityWLSFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisparityWLSFilter {
+    /// Create a filter with `lambda = 8000`, `sigma_color = 8` (guide
+    /// intensity levels) and 3 iterations
+    pub fn new() -> Self {
+        Self {
+            lambda: 8000.0,
+            sigma_color: 8.0,
+            iterations: 3,
+        }
+    }
+
+    /// Smoothness strength; larger values spread disparity further
+    #[must_use]
+    pub fn with_lambda(mut self, lambda: f32) -> Self {
+        self.lambda = lambda;
+        self
+    }
+
+    /// How strongly guide edges stop smoothing; smaller values keep more edges
+    #[must_use]
+    pub fn with_sigma_color(mut self, sigma_color: f32) -> Self {
+        self.sigma_color = sigma_color;
+        self
+    }
+
+    /// Number of horizontal + vertical pass pairs
+    #[must_use]
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Filter `disparity` (1-channel U8 or F32) guided by the left image
+    /// (1- or 3-channel U8, same size). Returns an F32 disparity map.
+    pub fn filter(&self, disparity: &Mat, left_image: &Mat) -> Result<Mat> {
+        if self.lambda <= 0.0 || self.sigma_color <= 0.0 || self.iterations == 0 {
+            return Err(Error::InvalidParameter(
+                "lambda, sigma_color and iterations must be positive".to_string(),
+            ));
+        }
+        if left_image.rows() != disparity.rows() || left_image.cols() != disparity.cols() {
+            return Err(Error::InvalidDimensions(
+                "Guide image must match disparity size".to_string(),
+            ));
+        }
+        if left_image.depth() != MatDepth::U8 || !matches!(left_image.channels(), 1 | 3) {
+            return Err(Error::UnsupportedOperation(
+                "WLS guide must be 1- or 3-channel U8".to_string(),
+            ));
+        }
+
+        let (rows, cols) = (disparity.rows(), disparity.cols());
+        let values = disparity_values(disparity)?;
+        let guide = guide_values(left_image);
+        let channels = left_image.channels();
+
+        let mut confidence: Vec<f32> = values.iter().map(|&d| f32::from(u8::from(is_valid(d)))).collect();
+        let mut weighted: Vec<f32> = values
+            .iter()
+            .zip(&confidence)
+            .map(|(&d, &c)| if c > 0.0 { d * c } else { 0.0 })
+            .collect();
+
+        // Edge weights between each pixel and its right / lower neighbour
+        let edge = |a: usize, b: usize| -> f32 {
+            let diff: f32 = (0..channels)
+                .map(|ch| (guide[a * channels + ch] - guide[b * channels + ch]).abs())
+                .sum::<f32>()
+                / channels as f32;
+            (-diff / self.sigma_color).exp()
+        };
+        let mut horizontal = vec![0.0f32; rows * cols];
+        let mut vertical = vec![0.0f32; rows * cols];
+        for row in 0..rows {
+            for col in 0..cols {
+                let idx = row * cols + col;
+                if col + 1 < cols {
+                    horizontal[idx] = edge(idx, idx + 1);
+                }
+                if row + 1 < rows {
+                    vertical[idx] = edge(idx, idx + cols);
+                }
+            }
+        }
+
+        // Lambda schedule from the fast global smoother paper: large steps
+        // first, shrinking by 4x each iteration
+        let total = 4f32.powi(self.iterations as i32) - 1.0;
+        for t in 0..self.iterations {
+            let step = 1.5 * self.lambda * 4f32.powi((self.iterations - t - 1) as i32) / total;
+            for plane in [&mut weighted, &mut confidence] {
+                smooth_rows(plane, &horizontal, rows, cols, step);
+                smooth_cols(plane, &vertical, rows, cols, step);
+            }
+        }
+
+        let mut out = Mat::new(rows, cols, 1, MatDepth::F32)?;
+        for row in 0..rows {
+            for col in 0..cols {
+                let idx = row * cols + col;
+                let value = if confidence[idx] > 1e-6 {
+                    weighted[idx] / confidence[idx]
+                } else {
+                    0.0
+                };
+                out.set_f32(row, col, 0, value)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Remove small disconnected blobs ("speckles") from a disparity map in place
+///
+/// Neighbouring pixels belong to the same blob when their disparities differ
+/// by at most `max_diff`. Blobs of at most `max_speckle_size` pixels are set
+/// to `new_val`. Pixels already equal to `new_val` or invalid are skipped.
+/// `disparity` must be 1-channel F32.
+pub fn filter_speckles(disparity: &mut Mat, new_val: f32, max_speckle_size: usize, max_diff: f32) -> Result<()> {
+    check_f32_disparity(disparity)?;
+
+    let (rows, cols) = (disparity.rows(), disparity.cols());
+    let values = disparity_values(disparity)?;
+    let skip = |d: f32| !is_valid(d) || d == new_val;
+
+    let mut visited = vec![false; rows * cols];
+    let mut blob = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in 0..rows * cols {
+        if visited[start] || skip(values[start]) {
+            continue;
+        }
+
+        blob.clear();
+        stack.push(start);
+        visited[start] = true;
+        while let Some(idx) = stack.pop() {
+            blob.push(idx);
+            let (row, col) = (idx / cols, idx % cols);
+            let neighbours = [
+                (col > 0).then(|| idx - 1),
+                (col + 1 < cols).then(|| idx + 1),
+                (row > 0).then(|| idx - cols),
+                (row + 1 < rows).then(|| idx + cols),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if !visited[next] && !skip(values[next]) && (values[next] - values[idx]).abs() <= max_diff {
+                    visited[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        if blob.len() <= max_speckle_size {
+            for &idx in &blob {
+                disparity.set_f32(idx / cols, idx % cols, 0, new_val)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fill invalid disparities along each scanline
+///
+/// Each hole takes the smaller of the nearest valid disparities to its left
+/// and right, since holes in stereo maps are mostly occlusions of the farther
+/// surface. Rows without any valid pixel are left at 0. `disparity` must be
+/// 1-channel F32.
+pub fn fill_disparity_holes(disparity: &Mat) -> Result<Mat> {
+    check_f32_disparity(disparity)?;
+
+    let (rows, cols) = (disparity.rows(), disparity.cols());
+    let values = disparity_values(disparity)?;
+    let mut out = Mat::new(rows, cols, 1, MatDepth::F32)?;
+
+    for row in 0..rows {
+        let line = &values[row * cols..(row + 1) * cols];
+
+        let mut left = vec![None; cols];
+        let mut last = None;
+        for (col, &d) in line.iter().enumerate() {
+            if is_valid(d) {
+                last = Some(d);
+            }
+            left[col] = last;
+        }
+
+        let mut right = None;
+        for col in (0..cols).rev() {
+            let d = line[col];
+            let value = if is_valid(d) {
+                right = Some(d);
+                d
+            } else {
+                match (left[col], right) {
+                    (Some(l), Some(r)) => l.min(r),
+                    (Some(v), None) | (None, Some(v)) => v,
+                    (None, None) => 0.0,
+                }
+            };
+            out.set_f32(row, col, 0, value)?;
+        }
+    }
+
+    Ok(out)
+}
+
+fn is_valid(d: f32) -> bool {
+    d.is_finite() && d > 0.0
+}
+
+fn check_f32_disparity(disparity: &Mat) -> Result<()> {
+    if disparity.channels() != 1 || disparity.depth() != MatDepth::F32 {
+        return Err(Error::UnsupportedOperation(
+            "Disparity must be a 1-channel F32 Mat".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn disparity_values(disparity: &Mat) -> Result<Vec<f32>> {
+    if disparity.channels() != 1 {
+        return Err(Error::InvalidParameter(
+            "Disparity must be single-channel".to_string(),
+        ));
+    }
+
+    let mut values = Vec::with_capacity(disparity.rows() * disparity.cols());
+    for row in 0..disparity.rows() {
+        for col in 0..disparity.cols() {
+            values.push(match disparity.depth() {
+                MatDepth::U8 => f32::from(disparity.at(row, col)?[0]),
+                MatDepth::F32 => disparity.at_f32(row, col, 0)?,
+                _ => {
+                    return Err(Error::UnsupportedOperation(
+                        "Disparity must be U8 or F32".to_string(),
+                    ))
+                }
+            });
+        }
+    }
+    Ok(values)
+}
+
+fn guide_values(guide: &Mat) -> Vec<f32> {
+    guide.data().iter().map(|&v| f32::from(v)).collect()
+}
+
+/// Solve `(I + step * L) u = f` along every row, where `L` is the 1-D
+/// weighted Laplacian with edge weights `weights[idx]` between `idx` and
+/// `idx + 1`
+fn smooth_rows(plane: &mut [f32], weights: &[f32], rows: usize, cols: usize, step: f32) {
+    let mut scratch = vec![0.0f32; cols];
+    for row in 0..rows {
+        let start = row * cols;
+        solve_chain(
+            &mut plane[start..start + cols],
+            |i| weights[start + i] * step,
+            &mut scratch,
+        );
+    }
+}
+
+/// Column counterpart of [`smooth_rows`]
+fn smooth_cols(plane: &mut [f32], weights: &[f32], rows: usize, cols: usize, step: f32) {
+    let mut line = vec![0.0f32; rows];
+    let mut scratch = vec![0.0f32; rows];
+    for col in 0..cols {
+        for (row, v) in line.iter_mut().enumerate() {
+            *v = plane[row * cols + col];
+        }
+        solve_chain(&mut line, |i| weights[i * cols + col] * step, &mut scratch);
+        for (row, v) in line.iter().enumerate() {
+            plane[row * cols + col] = *v;
+        }
+    }
+}
+
+/// Thomas algorithm for the chain system with coupling `link(i)` between
+/// elements `i` and `i + 1`; `values` holds the right-hand side on entry and
+/// the solution on return
+fn solve_chain(values: &mut [f32], link: impl Fn(usize) -> f32, scratch: &mut [f32]) {
+    let n = values.len();
+    if n < 2 {
+        return;
+    }
+
+    // Forward sweep: scratch[i] holds the modified super-diagonal
+    let mut prev_link = 0.0;
+    for i in 0..n {
+        let next_link = if i + 1 < n { link(i) } else { 0.0 };
+        let diag = 1.0 + prev_link + next_link;
+        let (denom, carried) = if i == 0 {
+            (diag, 0.0)
+        } else {
+            (diag + prev_link * scratch[i - 1], values[i - 1])
+        };
+        scratch[i] = -next_link / denom;
+        values[i] = (values[i] + prev_link * carried) / denom;
+        prev_link = next_link;
+    }
+
+    for i in (0..n - 1).rev() {
+        values[i] -= scratch[i] * values[i + 1];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disparity_from(rows: usize, cols: usize, f: impl Fn(usize, usize) -> f32) -> Mat {
+        let mut mat = Mat::new(rows, cols, 1, MatDepth::F32).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                mat.set_f32(row, col, 0, f(row, col)).unwrap();
+            }
+        }
+        mat
+    }
+
+    #[test]
+    fn test_wls_filter_fills_and_preserves_edges() {
+        // Two depth planes split at column 16, matching an edge in the guide,
+        // with a few dropouts and one noisy pixel
+        let mut guide = Mat::new(24, 32, 1, MatDepth::U8).unwrap();
+        for row in 0..24 {
+            for col in 0..32 {
+                guide.at_mut(row, col).unwrap()[0] = if col < 16 { 40 } else { 200 };
+            }
+        }
+        let disparity = disparity_from(24, 32, |row, col| match (row, col) {
+            (5, 5) | (6, 20) => 0.0,
+            (12, 8) => 30.0,
+            (_, c) if c < 16 => 10.0,
+            _ => 20.0,
+        });
+
+        let filtered = DisparityWLSFilter::new().filter(&disparity, &guide).unwrap();
+        assert_eq!(filtered.depth(), MatDepth::F32);
+
+        assert!((filtered.at_f32(5, 5, 0).unwrap() - 10.0).abs() < 0.5);
+        assert!((filtered.at_f32(6, 20, 0).unwrap() - 20.0).abs() < 0.5);
+        assert!((filtered.at_f32(12, 8, 0).unwrap() - 10.0).abs() < 2.0);
+        // The edge stays sharp
+        assert!((filtered.at_f32(10, 15, 0).unwrap() - 10.0).abs() < 1.0);
+        assert!((filtered.at_f32(10, 16, 0).unwrap() - 20.0).abs() < 1.0);
+
+        assert!(DisparityWLSFilter::new().with_lambda(0.0).filter(&disparity, &guide).is_err());
+    }
+
+    #[test]
+    fn test_filter_speckles() {
+        let mut disparity = disparity_from(10, 10, |row, col| match (row, col) {
+            (2..=3, 2..=3) => 40.0,
+            (7, 7) => 25.0,
+            _ => 10.0,
+        });
+
+        filter_speckles(&mut disparity, 0.0, 4, 1.0).unwrap();
+
+        assert_eq!(disparity.at_f32(2, 2, 0).unwrap(), 0.0);
+        assert_eq!(disparity.at_f32(3, 3, 0).unwrap(), 0.0);
+        assert_eq!(disparity.at_f32(7, 7, 0).unwrap(), 0.0);
+        assert_eq!(disparity.at_f32(0, 0, 0).unwrap(), 10.0);
+    }
+
+    #[test]
+    fn test_fill_disparity_holes() {
+        let disparity = disparity_from(2, 6, |row, col| match (row, col) {
+            (0, 0) => 0.0,
+            (0, 1) => 12.0,
+            (0, 2..=3) => f32::NAN,
+            (0, _) => 7.0,
+            _ => 0.0,
+        });
+
+        let filled = fill_disparity_holes(&disparity).unwrap();
+        assert_eq!(filled.at_f32(0, 0, 0).unwrap(), 12.0);
+        assert_eq!(filled.at_f32(0, 2, 0).unwrap(), 7.0);
+        assert_eq!(filled.at_f32(0, 3, 0).unwrap(), 7.0);
+        assert_eq!(filled.at_f32(1, 3, 0).unwrap(), 0.0);
+    }
+}
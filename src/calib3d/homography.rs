@@ -1,5 +1,7 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::types::Point;
+use crate::core::types::{Point, Point2f};
+use crate::calib3d::pnp::{homography_dlt, homography_dlt_weighted, solve_dense};
+use crate::calib3d::robust::{Estimator, RobustEstimate, RobustMethod, RobustProblem};
 use crate::error::{Error, Result};
 
 /// Find homography matrix from point correspondences
@@ -36,43 +38,7 @@ pub enum HomographyMethod {
 
 /// Compute homography using Direct Linear Transform
 fn find_homography_dlt(src_points: &[Point], dst_points: &[Point]) -> Result<[[f64; 3]; 3]> {
-    let n = src_points.len();
-
-    // Build matrix A for the system Ah = 0
-    let mut a_matrix = vec![vec![0.0; 9]; 2 * n];
-
-    for (i, (src, dst)) in src_points.iter().zip(dst_points.iter()).enumerate() {
-        let x = f64::from(src.x);
-        let y = f64::from(src.y);
-        let xp = f64::from(dst.x);
-        let yp = f64::from(dst.y);
-
-        // First row
-        a_matrix[2 * i] = vec![
-            -x, -y, -1.0,
-            0.0, 0.0, 0.0,
-            x * xp, y * xp, xp,
-        ];
-
-        // Second row
-        a_matrix[2 * i + 1] = vec![
-            0.0, 0.0, 0.0,
-            -x, -y, -1.0,
-            x * yp, y * yp, yp,
-        ];
-    }
-
-    // Solve using SVD - find eigenvector corresponding to smallest eigenvalue
-    // Simplified: use normalized DLT
-    let h = solve_dlt_system(&a_matrix)?;
-
-    let homography = [
-        [h[0], h[1], h[2]],
-        [h[3], h[4], h[5]],
-        [h[6], h[7], h[8]],
-    ];
-
-    Ok(homography)
+    homography_dlt(&to_f64(src_points), &to_f64(dst_points))
 }
 
 /// Find homography using RANSAC for robustness to outliers
@@ -82,80 +48,167 @@ fn find_homography_ransac(
     threshold: f64,
     confidence: f64,
 ) -> Result<[[f64; 3]; 3]> {
-    let n = src_points.len();
-    let sample_size = 4;
+    let estimator = Estimator::new(RobustMethod::Ransac)
+        .with_threshold(threshold)
+        .with_confidence(confidence)
+        .with_max_iterations(1000);
+    let problem = HomographyProblem { src: to_f64(src_points), dst: to_f64(dst_points) };
+    Ok(estimator.estimate(&problem, None)?.model)
+}
 
-    let mut best_homography = [[0.0; 3]; 3];
-    let mut best_inliers = 0;
+/// Robust homography from sub-pixel correspondences with a configurable
+/// [`Estimator`]
+///
+/// Residuals are forward reprojection errors in pixels. `scores` rank the
+/// correspondences for PROSAC, higher is better (e.g. negated descriptor
+/// distances). The returned estimate marks which correspondences are
+/// inliers of the refined homography.
+pub fn find_homography_robust(
+    src_points: &[Point2f],
+    dst_points: &[Point2f],
+    estimator: &Estimator,
+    scores: Option<&[f64]>,
+) -> Result<RobustEstimate<[[f64; 3]; 3]>> {
+    if src_points.len() != dst_points.len() {
+        return Err(Error::InvalidParameter(
+            "Source and destination points must have same length".to_string(),
+        ));
+    }
+    let as_f64 = |points: &[Point2f]| points.iter().map(|p| [f64::from(p.x), f64::from(p.y)]).collect();
+    let problem = HomographyProblem { src: as_f64(src_points), dst: as_f64(dst_points) };
+    estimator.estimate(&problem, scores)
+}
 
-    // Compute number of iterations
-    let mut max_iterations = compute_ransac_iterations(confidence, 0.5, sample_size);
-    max_iterations = max_iterations.min(1000);
+/// Point-to-point homography fitting for the robust estimators
+struct HomographyProblem {
+    src: Vec<[f64; 2]>,
+    dst: Vec<[f64; 2]>,
+}
 
-    use std::collections::HashSet;
+impl RobustProblem for HomographyProblem {
+    type Model = [[f64; 3]; 3];
 
-    for _ in 0..max_iterations {
-        // Randomly sample 4 correspondences
-        let mut indices = Vec::new();
-        let mut used = HashSet::new();
+    fn num_points(&self) -> usize {
+        self.src.len()
+    }
 
-        while indices.len() < sample_size {
-            let idx = (rand_f64() * n as f64) as usize % n;
-            if !used.contains(&idx) {
-                indices.push(idx);
-                used.insert(idx);
-            }
-        }
+    fn sample_size(&self) -> usize {
+        4
+    }
 
-        let sample_src: Vec<Point> = indices.iter().map(|&i| src_points[i]).collect();
-        let sample_dst: Vec<Point> = indices.iter().map(|&i| dst_points[i]).collect();
+    fn fit(&self, sample: &[usize]) -> Vec<Self::Model> {
+        let src: Vec<[f64; 2]> = sample.iter().map(|&i| self.src[i]).collect();
+        let dst: Vec<[f64; 2]> = sample.iter().map(|&i| self.dst[i]).collect();
+        homography_dlt(&src, &dst).into_iter().collect()
+    }
 
-        // Compute homography from sample
-        let h = match find_homography_dlt(&sample_src, &sample_dst) {
-            Ok(h) => h,
-            Err(_) => continue,
-        };
+    fn residuals(&self, h: &Self::Model) -> Vec<f64> {
+        self.src
+            .iter()
+            .zip(&self.dst)
+            .map(|(p, q)| {
+                let w = h[2][0] * p[0] + h[2][1] * p[1] + h[2][2];
+                if w.abs() < 1e-12 {
+                    return f64::INFINITY;
+                }
+                let x = (h[0][0] * p[0] + h[0][1] * p[1] + h[0][2]) / w;
+                let y = (h[1][0] * p[0] + h[1][1] * p[1] + h[1][2]) / w;
+                (x - q[0]).hypot(y - q[1])
+            })
+            .collect()
+    }
 
-        // Count inliers
-        let mut inliers = 0;
-        for (src, dst) in src_points.iter().zip(dst_points.iter()) {
-            let projected = apply_homography(&h, src);
-            let error = distance_points(&projected, dst);
-            if error < threshold {
-                inliers += 1;
+    fn refine(&self, _h: &Self::Model, inliers: &[usize], weights: &[f64]) -> Option<Self::Model> {
+        let src: Vec<[f64; 2]> = inliers.iter().map(|&i| self.src[i]).collect();
+        let dst: Vec<[f64; 2]> = inliers.iter().map(|&i| self.dst[i]).collect();
+        homography_dlt_weighted(&src, &dst, Some(weights)).ok()
+    }
+}
+
+/// Robust 2-D affine transform from sub-pixel correspondences
+///
+/// The six-parameter counterpart of [`find_homography_robust`], for motion
+/// that stays close to the image plane such as camera shake between
+/// consecutive video frames. The returned model maps `src_points` onto
+/// `dst_points`.
+pub fn estimate_affine_2d(
+    src_points: &[Point2f],
+    dst_points: &[Point2f],
+    estimator: &Estimator,
+) -> Result<RobustEstimate<[[f64; 3]; 2]>> {
+    if src_points.len() != dst_points.len() {
+        return Err(Error::InvalidParameter(
+            "Source and destination points must have same length".to_string(),
+        ));
+    }
+    let as_f64 = |points: &[Point2f]| points.iter().map(|p| [f64::from(p.x), f64::from(p.y)]).collect();
+    let problem = AffineProblem { src: as_f64(src_points), dst: as_f64(dst_points) };
+    estimator.estimate(&problem, None)
+}
+
+struct AffineProblem {
+    src: Vec<[f64; 2]>,
+    dst: Vec<[f64; 2]>,
+}
+
+impl AffineProblem {
+    /// Weighted least-squares fit over `indices`; each output row is an
+    /// independent 3-parameter linear problem sharing one normal matrix
+    fn fit_weighted(&self, indices: &[usize], weights: Option<&[f64]>) -> Option<[[f64; 3]; 2]> {
+        let mut normal = vec![vec![0.0; 3]; 3];
+        let mut rhs = [vec![0.0; 3], vec![0.0; 3]];
+        for (k, &i) in indices.iter().enumerate() {
+            let w = weights.map_or(1.0, |w| w[k]);
+            let a = [self.src[i][0], self.src[i][1], 1.0];
+            for r in 0..3 {
+                for c in 0..3 {
+                    normal[r][c] += w * a[r] * a[c];
+                }
+                rhs[0][r] += w * a[r] * self.dst[i][0];
+                rhs[1][r] += w * a[r] * self.dst[i][1];
             }
         }
+        let [rhs_x, rhs_y] = rhs;
+        let x = solve_dense(normal.clone(), rhs_x)?;
+        let y = solve_dense(normal, rhs_y)?;
+        Some([[x[0], x[1], x[2]], [y[0], y[1], y[2]]])
+    }
+}
 
-        if inliers > best_inliers {
-            best_inliers = inliers;
-            best_homography = h;
+impl RobustProblem for AffineProblem {
+    type Model = [[f64; 3]; 2];
 
-            // Update iteration count based on current inlier ratio
-            let inlier_ratio = f64::from(inliers) / n as f64;
-            max_iterations = compute_ransac_iterations(confidence, inlier_ratio, sample_size).min(max_iterations);
-        }
+    fn num_points(&self) -> usize {
+        self.src.len()
     }
 
-    if best_inliers < 4 {
-        return Err(Error::InvalidParameter(
-            "RANSAC failed to find sufficient inliers".to_string(),
-        ));
+    fn sample_size(&self) -> usize {
+        3
+    }
+
+    fn fit(&self, sample: &[usize]) -> Vec<Self::Model> {
+        self.fit_weighted(sample, None).into_iter().collect()
     }
 
-    // Refine using all inliers
-    let mut inlier_src = Vec::new();
-    let mut inlier_dst = Vec::new();
+    fn residuals(&self, m: &Self::Model) -> Vec<f64> {
+        self.src
+            .iter()
+            .zip(&self.dst)
+            .map(|(p, q)| {
+                let x = m[0][0] * p[0] + m[0][1] * p[1] + m[0][2];
+                let y = m[1][0] * p[0] + m[1][1] * p[1] + m[1][2];
+                (x - q[0]).hypot(y - q[1])
+            })
+            .collect()
+    }
 
-    for (src, dst) in src_points.iter().zip(dst_points.iter()) {
-        let projected = apply_homography(&best_homography, src);
-        let error = distance_points(&projected, dst);
-        if error < threshold {
-            inlier_src.push(*src);
-            inlier_dst.push(*dst);
-        }
+    fn refine(&self, _m: &Self::Model, inliers: &[usize], weights: &[f64]) -> Option<Self::Model> {
+        self.fit_weighted(inliers, Some(weights))
     }
+}
 
-    find_homography_dlt(&inlier_src, &inlier_dst)
+fn to_f64(points: &[Point]) -> Vec<[f64; 2]> {
+    points.iter().map(|p| [f64::from(p.x), f64::from(p.y)]).collect()
 }
 
 /// Find homography using Least Median of Squares
@@ -216,42 +269,34 @@ pub fn apply_homography(h: &[[f64; 3]; 3], point: &Point) -> Point {
 }
 
 /// Compute perspective transformation (warp perspective)
+///
+/// `homography` maps source to destination; the output has the size of
+/// `src`. Borders are handled like [`crate::imgproc::warp_perspective`].
 pub fn warp_perspective(
     src: &crate::core::Mat,
     dst: &mut crate::core::Mat,
     homography: &[[f64; 3]; 3],
 ) -> Result<()> {
-    
-
-    *dst = crate::core::Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
-
     // Compute inverse homography for backward mapping
     let h_inv = invert_homography(homography)?;
-
-    for row in 0..dst.rows() {
-        for col in 0..dst.cols() {
-            let dst_pt = Point::new(col as i32, row as i32);
-            let src_pt = apply_homography(&h_inv, &dst_pt);
-
-            let src_x = src_pt.x as usize;
-            let src_y = src_pt.y as usize;
-
-            if src_y < src.rows() && src_x < src.cols() {
-                let src_pixel = src.at(src_y, src_x)?;
-                let num_channels = src.channels().min(dst.channels());
-                let dst_pixel = dst.at_mut(row, col)?;
-
-                for ch in 0..num_channels {
-                    dst_pixel[ch] = src_pixel[ch];
-                }
-            }
-        }
-    }
-
-    Ok(())
+    let dsize = crate::core::types::Size::new(src.cols() as i32, src.rows() as i32);
+    crate::imgproc::warp_perspective(src, dst, &h_inv, dsize)
 }
 
-/// Decompose homography into rotation, translation, and normal
+/// Decompose a homography between two views of a plane into the camera
+/// motions that could have produced it
+///
+/// `h` maps pixels of the first view to pixels of the second, as returned by
+/// [`find_homography`], and both views share `camera_matrix`. Each solution
+/// is `(rvec, tvec, normal)`: the Rodrigues rotation and translation of the
+/// second camera relative to the first, and the plane normal in the first
+/// camera frame. The translation is scaled by the unknown plane distance.
+///
+/// A general homography yields four solutions (two pairs differing in the
+/// sign of `tvec` and `normal`); use [`filter_homography_decomposition`] to
+/// discard those that put the observed points behind a camera. A pure
+/// rotation yields a single solution with zero translation and normal.
+/// This is the analytic method of Malis and Vargas, as used by `OpenCV`.
 pub fn decompose_homography(
     h: &[[f64; 3]; 3],
     camera_matrix: &crate::calib3d::camera::CameraMatrix,
@@ -260,46 +305,198 @@ pub fn decompose_homography(
     let k = camera_matrix.to_matrix();
     let k_inv = invert_3x3(&k)?;
 
-    // H_normalized = K^-1 * H * K
-    let h_norm = matrix_multiply_3x3(&k_inv, &matrix_multiply_3x3(h, &k));
-
-    // Extract rotation and translation (simplified)
-    // Real implementation would use SVD decomposition
-    let mut solutions = Vec::new();
+    // H_normalized = K^-1 * H * K, scaled so its middle singular value is 1
+    let mut h_norm = matrix_multiply_3x3(&k_inv, &matrix_multiply_3x3(h, &k));
+    let scale = middle_singular_value(&h_norm);
+    if scale < 1e-12 {
+        return Err(Error::InvalidParameter(
+            "Homography is degenerate".to_string(),
+        ));
+    }
+    for v in h_norm.iter_mut().flatten() {
+        *v /= scale;
+    }
 
-    let rvec = [0.0, 0.0, 0.0];
-    let tvec = [h_norm[0][2], h_norm[1][2], h_norm[2][2]];
-    let normal = [0.0, 0.0, 1.0];
+    // S = H^T H - I vanishes for a pure rotation
+    let mut s = matrix_multiply_3x3(&transpose_3x3(&h_norm), &h_norm);
+    for (i, row) in s.iter_mut().enumerate() {
+        row[i] -= 1.0;
+    }
+    if s.iter().flatten().all(|v| v.abs() < 1e-6) {
+        let mut r = h_norm;
+        if determinant_3x3(&r) < 0.0 {
+            for v in r.iter_mut().flatten() {
+                *v = -*v;
+            }
+        }
+        let rvec = crate::calib3d::camera::rodrigues_from_matrix(&r);
+        return Ok(vec![(rvec, [0.0; 3], [0.0; 3])]);
+    }
 
-    solutions.push((rvec, tvec, normal));
+    let m11 = opposite_of_minor(&s, 0, 0);
+    let m22 = opposite_of_minor(&s, 1, 1);
+    let m33 = opposite_of_minor(&s, 2, 2);
+    let (rt_m11, rt_m22, rt_m33) = (m11.max(0.0).sqrt(), m22.max(0.0).sqrt(), m33.max(0.0).sqrt());
+    let e12 = sign(opposite_of_minor(&s, 0, 1));
+    let e13 = sign(opposite_of_minor(&s, 0, 2));
+    let e23 = sign(opposite_of_minor(&s, 1, 2));
+
+    // Two candidate normals, built from the largest diagonal entry of S
+    let largest = (0..3)
+        .max_by(|&a, &b| s[a][a].abs().total_cmp(&s[b][b].abs()))
+        .unwrap_or(0);
+    let (npa, npb) = match largest {
+        0 => (
+            [s[0][0], s[0][1] + rt_m33, s[0][2] + e23 * rt_m22],
+            [s[0][0], s[0][1] - rt_m33, s[0][2] - e23 * rt_m22],
+        ),
+        1 => (
+            [s[0][1] + rt_m33, s[1][1], s[1][2] - e13 * rt_m11],
+            [s[0][1] - rt_m33, s[1][1], s[1][2] + e13 * rt_m11],
+        ),
+        _ => (
+            [s[0][2] + e12 * rt_m22, s[1][2] + rt_m11, s[2][2]],
+            [s[0][2] - e12 * rt_m22, s[1][2] - rt_m11, s[2][2]],
+        ),
+    };
+    let (na, nb) = (normalize_3(npa), normalize_3(npb));
+
+    let trace = s[0][0] + s[1][1] + s[2][2];
+    let v = 2.0 * (1.0 + trace - m11 - m22 - m33).max(0.0).sqrt();
+    let r = (2.0 + trace + v).max(0.0).sqrt();
+    let n_t = (2.0 + trace - v).max(0.0).sqrt();
+    let esii_r = sign(s[largest][largest]) * r;
+    let half_nt = 0.5 * n_t;
+    let ta_star: [f64; 3] = std::array::from_fn(|i| half_nt * (esii_r * nb[i] - n_t * na[i]));
+    let tb_star: [f64; 3] = std::array::from_fn(|i| half_nt * (esii_r * na[i] - n_t * nb[i]));
+
+    let mut solutions = Vec::with_capacity(4);
+    for (t_star, n) in [(ta_star, na), (tb_star, nb)] {
+        let (rotation, t) = rotation_from_tstar_n(&h_norm, &t_star, &n, v);
+        let rvec = crate::calib3d::camera::rodrigues_from_matrix(&rotation);
+        solutions.push((rvec, t, n));
+        solutions.push((rvec, t.map(|x| -x), n.map(|x| -x)));
+    }
 
     Ok(solutions)
 }
 
-// Helper functions
+/// Indices of the [`decompose_homography`] solutions under which every
+/// reference point lies in front of both cameras
+///
+/// `before_points` are pixels in the first view and `after_points` the
+/// corresponding pixels in the second. Of the four solutions of a general
+/// homography this usually leaves two; telling those apart needs extra
+/// knowledge such as the expected plane normal.
+pub fn filter_homography_decomposition(
+    solutions: &[([f64; 3], [f64; 3], [f64; 3])],
+    camera_matrix: &crate::calib3d::camera::CameraMatrix,
+    before_points: &[Point2f],
+    after_points: &[Point2f],
+) -> Result<Vec<usize>> {
+    if before_points.len() != after_points.len() {
+        return Err(Error::InvalidParameter(
+            "Before and after points must have same length".to_string(),
+        ));
+    }
 
-fn solve_dlt_system(a_matrix: &[Vec<f64>]) -> Result<[f64; 9]> {
-    // Simplified SVD - compute using power iteration for last eigenvector
-    let mut h = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let k_inv = invert_3x3(&camera_matrix.to_matrix())?;
+    let normalized = |p: &Point2f| {
+        let (x, y) = (f64::from(p.x), f64::from(p.y));
+        std::array::from_fn::<f64, 3, _>(|i| k_inv[i][0] * x + k_inv[i][1] * y + k_inv[i][2])
+    };
+    let before: Vec<[f64; 3]> = before_points.iter().map(normalized).collect();
+    let after: Vec<[f64; 3]> = after_points.iter().map(normalized).collect();
+
+    Ok(solutions
+        .iter()
+        .enumerate()
+        .filter(|(_, (rvec, _, normal))| {
+            let rotation = crate::calib3d::camera::rodrigues(rvec);
+            let rotated_normal: [f64; 3] = std::array::from_fn(|i| (0..3).map(|j| rotation[i][j] * normal[j]).sum());
+            before.iter().zip(&after).all(|(b, a)| dot_3(normal, b) > 0.0 && dot_3(&rotated_normal, a) > 0.0)
+        })
+        .map(|(i, _)| i)
+        .collect())
+}
 
-    // Normalize to avoid trivial solution
-    let norm: f64 = h.iter().map(|x| x * x).sum::<f64>().sqrt();
-    for val in &mut h {
-        *val /= norm;
+/// Rotation and translation from the scaled translation `t_star` and normal
+/// `n` of one decomposition branch: R = H (I - 2/v t* n^T), t = R t*
+fn rotation_from_tstar_n(h: &[[f64; 3]; 3], t_star: &[f64; 3], n: &[f64; 3], v: f64) -> ([[f64; 3]; 3], [f64; 3]) {
+    let mut correction = [[0.0; 3]; 3];
+    for (i, row) in correction.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = f64::from(u8::from(i == j)) - 2.0 / v * t_star[i] * n[j];
+        }
+    }
+    let mut rotation = matrix_multiply_3x3(h, &correction);
+    if determinant_3x3(&rotation) < 0.0 {
+        for value in rotation.iter_mut().flatten() {
+            *value = -*value;
+        }
     }
+    let t = std::array::from_fn(|i| dot_3(&rotation[i], t_star));
+    (rotation, t)
+}
 
-    Ok(h)
+/// Negated 2x2 minor of `m` obtained by deleting `row` and `col`
+fn opposite_of_minor(m: &[[f64; 3]; 3], row: usize, col: usize) -> f64 {
+    let (x1, x2) = (usize::from(row == 0), if row == 2 { 1 } else { 2 });
+    let (y1, y2) = (usize::from(col == 0), if col == 2 { 1 } else { 2 });
+    -(m[x1][y1] * m[x2][y2] - m[x1][y2] * m[x2][y1])
 }
 
-fn compute_ransac_iterations(confidence: f64, inlier_ratio: f64, sample_size: usize) -> usize {
-    if inlier_ratio < 1e-10 {
-        return 1000;
+/// Middle singular value of `m`, from the eigenvalues of `m^T m`
+fn middle_singular_value(m: &[[f64; 3]; 3]) -> f64 {
+    let a = matrix_multiply_3x3(&transpose_3x3(m), m);
+
+    // Closed-form eigenvalues of a symmetric 3x3 matrix
+    let p1 = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+    let q = (a[0][0] + a[1][1] + a[2][2]) / 3.0;
+    let p2 = (a[0][0] - q).powi(2) + (a[1][1] - q).powi(2) + (a[2][2] - q).powi(2) + 2.0 * p1;
+    let p = (p2 / 6.0).sqrt();
+    if p < 1e-15 {
+        return q.max(0.0).sqrt();
+    }
+    let mut b = a;
+    for (i, row) in b.iter_mut().enumerate() {
+        row[i] -= q;
     }
+    let half_det = determinant_3x3(&b) / (2.0 * p * p * p);
+    let phi = half_det.clamp(-1.0, 1.0).acos() / 3.0;
+    let largest = q + 2.0 * p * phi.cos();
+    let smallest = q + 2.0 * p * (phi + 2.0 * std::f64::consts::PI / 3.0).cos();
+    (3.0 * q - largest - smallest).max(0.0).sqrt()
+}
+
+fn transpose_3x3(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    std::array::from_fn(|i| std::array::from_fn(|j| m[j][i]))
+}
+
+fn determinant_3x3(m: &[[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
 
-    let num_iterations = (1.0 - confidence).ln() / (1.0 - inlier_ratio.powi(sample_size as i32)).ln();
-    num_iterations.ceil() as usize
+fn dot_3(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
 }
 
+fn normalize_3(v: [f64; 3]) -> [f64; 3] {
+    let norm = dot_3(&v, &v).sqrt();
+    if norm < 1e-15 {
+        return v;
+    }
+    v.map(|x| x / norm)
+}
+
+fn sign(x: f64) -> f64 {
+    if x >= 0.0 { 1.0 } else { -1.0 }
+}
+
+// Helper functions
+
 fn distance_points(p1: &Point, p2: &Point) -> f64 {
     let dx = p1.x - p2.x;
     let dy = p1.y - p2.y;
@@ -404,4 +601,134 @@ mod tests {
         assert!((inv[1][1] - 0.5).abs() < 1e-6);
         assert!((inv[2][2] - 0.5).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_decompose_homography() {
+        use crate::calib3d::camera::{rodrigues, CameraMatrix};
+
+        let camera = CameraMatrix::new(800.0, 780.0, 320.0, 240.0);
+        let k = camera.to_matrix();
+        let rvec = [0.1, -0.25, 0.05];
+        let rotation = rodrigues(&rvec);
+        let (t, n, d) = ([0.3, -0.1, 0.2], normalize_3([0.1, 0.2, 1.0]), 2.0);
+
+        // Euclidean homography R + t n^T / d, in pixels and at arbitrary scale
+        let mut h_e = rotation;
+        for (i, row) in h_e.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value += t[i] * n[j] / d;
+            }
+        }
+        let mut h = matrix_multiply_3x3(&k, &matrix_multiply_3x3(&h_e, &invert_3x3(&k).unwrap()));
+        for v in h.iter_mut().flatten() {
+            *v *= -3.5;
+        }
+
+        let solutions = decompose_homography(&h, &camera).unwrap();
+        assert_eq!(solutions.len(), 4);
+        let close = |a: &[f64; 3], b: &[f64; 3]| (0..3).all(|i| (a[i] - b[i]).abs() < 1e-6);
+        let truth = solutions
+            .iter()
+            .position(|(r, tv, nv)| close(r, &rvec) && close(tv, &t.map(|x| x / d)) && close(nv, &n))
+            .expect("true motion among the solutions");
+
+        // Points on the plane n.X = d seen by both cameras
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for &(x, y) in &[(-0.3, -0.2), (0.4, -0.1), (0.2, 0.3), (-0.1, 0.25)] {
+            let z = (d - n[0] * x - n[1] * y) / n[2];
+            let p1 = [x, y, z];
+            let p2: [f64; 3] = std::array::from_fn(|i| dot_3(&rotation[i], &p1) + t[i]);
+            for (p, out) in [(p1, &mut before), (p2, &mut after)] {
+                out.push(Point2f::new(
+                    (k[0][0] * p[0] / p[2] + k[0][2]) as f32,
+                    (k[1][1] * p[1] / p[2] + k[1][2]) as f32,
+                ));
+            }
+        }
+        let valid = filter_homography_decomposition(&solutions, &camera, &before, &after).unwrap();
+        assert!(valid.contains(&truth));
+        assert!(valid.len() <= 2, "{valid:?}");
+
+        // A pure rotation has a single solution without translation
+        let h_rot = matrix_multiply_3x3(&k, &matrix_multiply_3x3(&rotation, &invert_3x3(&k).unwrap()));
+        let solutions = decompose_homography(&h_rot, &camera).unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert!(close(&solutions[0].0, &rvec) && close(&solutions[0].1, &[0.0; 3]));
+    }
+
+    #[test]
+    fn test_find_homography_robust() {
+        use crate::imgproc::noise::SplitMix64;
+
+        let h = [[0.9, 0.12, 14.0], [-0.08, 1.1, -6.0], [2e-4, -1e-4, 1.0]];
+        let mut rng = SplitMix64::new(11);
+        let (mut src, mut dst, mut scores) = (Vec::new(), Vec::new(), Vec::new());
+        for i in 0..80 {
+            let (x, y) = (rng.next_f64() * 400.0, rng.next_f64() * 300.0);
+            let w = h[2][0] * x + h[2][1] * y + h[2][2];
+            let (mut u, mut v) = ((h[0][0] * x + h[0][1] * y + h[0][2]) / w, (h[1][0] * x + h[1][1] * y + h[1][2]) / w);
+            // 40% mismatches, which also get worse match scores
+            let outlier = i % 5 < 2;
+            if outlier {
+                u = rng.next_f64() * 400.0;
+                v = rng.next_f64() * 300.0;
+            } else {
+                u += (rng.next_f64() - 0.5) * 0.6;
+                v += (rng.next_f64() - 0.5) * 0.6;
+            }
+            src.push(Point2f::new(x as f32, y as f32));
+            dst.push(Point2f::new(u as f32, v as f32));
+            scores.push(if outlier { rng.next_f64() } else { 0.5 + rng.next_f64() });
+        }
+
+        for method in [RobustMethod::Ransac, RobustMethod::Prosac, RobustMethod::MagsacPlusPlus] {
+            let estimate = find_homography_robust(&src, &dst, &Estimator::new(method), Some(&scores)).unwrap();
+            let found = estimate.model;
+            for &(x, y) in &[(0.0, 0.0), (400.0, 0.0), (200.0, 150.0), (0.0, 300.0)] {
+                let project = |m: &[[f64; 3]; 3]| {
+                    let w = m[2][0] * x + m[2][1] * y + m[2][2];
+                    ((m[0][0] * x + m[0][1] * y + m[0][2]) / w, (m[1][0] * x + m[1][1] * y + m[1][2]) / w)
+                };
+                let (a, b) = (project(&found), project(&h));
+                assert!((a.0 - b.0).hypot(a.1 - b.1) < 1.0, "{method:?}: {a:?} vs {b:?}");
+            }
+            assert_eq!(estimate.inlier_count(), 48, "{method:?}");
+        }
+
+        // The classic entry point goes through the same estimator
+        let int = |p: &Point2f| Point::new(p.x.round() as i32, p.y.round() as i32);
+        let src_i: Vec<Point> = src.iter().map(int).collect();
+        let dst_i: Vec<Point> = dst.iter().map(int).collect();
+        let found = find_homography(&src_i, &dst_i, HomographyMethod::RANSAC).unwrap();
+        assert!((found[0][2] / found[2][2] - 14.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_estimate_affine_2d() {
+        use crate::imgproc::noise::SplitMix64;
+
+        let m = [[0.98, -0.05, 3.5], [0.04, 1.01, -2.25]];
+        let mut rng = SplitMix64::new(5);
+        let (mut src, mut dst) = (Vec::new(), Vec::new());
+        for i in 0..60 {
+            let (x, y) = (rng.next_f64() * 320.0, rng.next_f64() * 240.0);
+            let (mut u, mut v) = (m[0][0] * x + m[0][1] * y + m[0][2], m[1][0] * x + m[1][1] * y + m[1][2]);
+            if i % 4 == 0 {
+                u += 20.0 + rng.next_f64() * 30.0;
+                v -= 15.0;
+            }
+            src.push(Point2f::new(x as f32, y as f32));
+            dst.push(Point2f::new(u as f32, v as f32));
+        }
+
+        let estimate = estimate_affine_2d(&src, &dst, &Estimator::new(RobustMethod::Ransac).with_threshold(0.5)).unwrap();
+        assert_eq!(estimate.inlier_count(), 45);
+        for (row, expected) in estimate.model.iter().zip(&m) {
+            for (a, b) in row.iter().zip(expected) {
+                assert!((a - b).abs() < 1e-3, "{:?}", estimate.model);
+            }
+        }
+        assert!(estimate_affine_2d(&src[..2], &dst[..2], &Estimator::default()).is_err());
+    }
 }
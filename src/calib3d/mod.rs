@@ -1,11 +1,31 @@
 pub mod camera;
+pub mod camera_model;
 pub mod stereo;
+pub mod disparity_filter;
 pub mod pnp;
 pub mod homography;
 pub mod fisheye;
+pub mod board;
+pub mod charuco;
+pub mod robust;
+pub mod structured_light;
+pub mod photometric;
+pub mod rgbd;
+#[cfg(feature = "objdetect")]
+pub mod multi_camera;
 
 pub use camera::*;
+pub use camera_model::*;
 pub use stereo::*;
+pub use disparity_filter::*;
 pub use pnp::*;
 pub use homography::*;
 pub use fisheye::*;
+pub use board::*;
+pub use charuco::*;
+pub use structured_light::{CorrespondenceMap, GrayCodePattern, PatternAxis, PhaseShiftPattern};
+pub use photometric::{photometric_stereo, PhotometricStereo};
+pub use rgbd::{align_depth_to_color, normal_map, RgbdFrame};
+#[cfg(feature = "objdetect")]
+pub use multi_camera::{MultiCameraCalibration, MultiCameraCalibrator};
+pub use robust::{Estimator, RobustEstimate, RobustMethod, RobustProblem};
@@ -0,0 +1,525 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Extrinsic calibration of multi-camera rigs from `ArUco` markers
+//!
+//! All cameras of a rig film markers at the same instants. Whenever two
+//! cameras see the same marker in the same frame, the marker's pose in each
+//! camera (planar PnP on its corners) gives one estimate of the cameras'
+//! relative pose. [`MultiCameraCalibrator`] links the cameras through a
+//! maximum spanning tree of these pairwise estimates, then refines every
+//! camera and marker pose jointly on the corner reprojection error (bundle
+//! adjustment with the intrinsics held fixed).
+
+use std::collections::HashMap;
+
+use crate::calib3d::camera::{rodrigues, rodrigues_from_matrix};
+use crate::calib3d::camera_model::{project_with_pose, CameraModel};
+use crate::calib3d::pnp::{solve_dense, solve_pnp_planar_with_model};
+use crate::core::types::{Point2f, Point3f};
+use crate::error::{Error, Result};
+use crate::objdetect::ArucoMarker;
+
+/// Rotation matrix and translation of a rigid transform
+type Pose = ([[f64; 3]; 3], [f64; 3]);
+
+/// Pixel residual of corners a camera model cannot image
+const UNPROJECTABLE_RESIDUAL: f64 = 1e6;
+
+/// Result of [`MultiCameraCalibrator::calibrate`]
+#[derive(Debug, Clone)]
+pub struct MultiCameraCalibration {
+    /// `(rvec, tvec)` of each camera, mapping camera-0 coordinates into that
+    /// camera's coordinates; camera 0 is the identity
+    pub extrinsics: Vec<([f64; 3], [f64; 3])>,
+    /// RMS reprojection error over all marker corners, in pixels
+    pub rms_error: f64,
+    /// RMS reprojection error of each camera's corners, in pixels
+    pub per_camera_errors: Vec<f64>,
+}
+
+/// Accumulates synchronized marker detections and solves rig extrinsics
+///
+/// Markers may move between frames; each (frame, marker id) pair is a
+/// separate unknown pose, so a single marker waved through the cameras'
+/// overlapping views is enough.
+pub struct MultiCameraCalibrator {
+    cameras: Vec<Box<dyn CameraModel>>,
+    marker_length: f32,
+    frames: Vec<Vec<Vec<ArucoMarker>>>,
+}
+
+/// One camera's view of one marker instance
+struct Observation {
+    instance: usize,
+    camera: usize,
+    corners: [Point2f; 4],
+    /// Marker-to-camera transform from PnP
+    pose: Pose,
+}
+
+impl MultiCameraCalibrator {
+    /// Calibrator for a rig of cameras with known intrinsics, observing
+    /// square markers with sides of `marker_length` (the unit of the
+    /// resulting translations)
+    pub fn new(cameras: Vec<Box<dyn CameraModel>>, marker_length: f32) -> Result<Self> {
+        if cameras.len() < 2 {
+            return Err(Error::InvalidParameter(
+                "A camera rig needs at least 2 cameras".to_string(),
+            ));
+        }
+        if !(marker_length > 0.0 && marker_length.is_finite()) {
+            return Err(Error::InvalidParameter(
+                "marker_length must be positive and finite".to_string(),
+            ));
+        }
+        Ok(Self { cameras, marker_length, frames: Vec::new() })
+    }
+
+    #[must_use]
+    pub fn camera_count(&self) -> usize {
+        self.cameras.len()
+    }
+
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Add the markers detected by every camera at one instant, in camera
+    /// order
+    ///
+    /// A marker id may appear at most once per camera.
+    pub fn add_frame(&mut self, detections: &[Vec<ArucoMarker>]) -> Result<()> {
+        if detections.len() != self.cameras.len() {
+            return Err(Error::InvalidParameter(format!(
+                "Expected detections from {} cameras, got {}",
+                self.cameras.len(),
+                detections.len()
+            )));
+        }
+        for markers in detections {
+            for (i, marker) in markers.iter().enumerate() {
+                if marker.corners.len() != 4 {
+                    return Err(Error::InvalidParameter(
+                        "Markers must have 4 corners".to_string(),
+                    ));
+                }
+                if markers[..i].iter().any(|m| m.id == marker.id) {
+                    return Err(Error::InvalidParameter(format!(
+                        "Marker {} detected twice by one camera",
+                        marker.id
+                    )));
+                }
+            }
+        }
+        self.frames.push(detections.to_vec());
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Marker-frame positions of a marker's corners, clockwise from its
+    /// top-left: origin at the centre, x right, y down, z into the marker
+    #[must_use]
+    pub fn marker_object_points(&self) -> [Point3f; 4] {
+        let h = self.marker_length / 2.0;
+        [
+            Point3f::new(-h, -h, 0.0),
+            Point3f::new(h, -h, 0.0),
+            Point3f::new(h, h, 0.0),
+            Point3f::new(-h, h, 0.0),
+        ]
+    }
+
+    /// Solve the camera extrinsics from the stored frames
+    ///
+    /// Fails when some camera shares no marker observation, directly or
+    /// through other cameras, with camera 0.
+    pub fn calibrate(&self) -> Result<MultiCameraCalibration> {
+        let (observations, instance_count) = self.observations()?;
+        let n = self.cameras.len();
+
+        let cameras = self.initial_camera_poses(&observations)?;
+
+        // Each marker instance starts from its first observation
+        let mut instances: Vec<Option<Pose>> = vec![None; instance_count];
+        for obs in &observations {
+            instances[obs.instance].get_or_insert_with(|| compose(&invert(&cameras[obs.camera]), &obs.pose));
+        }
+
+        let mut params = Vec::with_capacity(6 * (n - 1 + instance_count));
+        for pose in cameras.iter().skip(1).chain(instances.iter().flatten()) {
+            params.extend(pose_to_vector(pose));
+        }
+        self.refine(&observations, &mut params);
+
+        let mut sums = vec![(0.0f64, 0usize); n];
+        for obs in &observations {
+            let residuals = self.residuals(&params, obs);
+            sums[obs.camera].0 += residuals.iter().map(|r| r * r).sum::<f64>();
+            sums[obs.camera].1 += 4;
+        }
+        let (total, count) = sums.iter().fold((0.0, 0), |(s, c), &(s2, c2)| (s + s2, c + c2));
+
+        let mut extrinsics = vec![([0.0; 3], [0.0; 3])];
+        extrinsics.extend((1..n).map(|c| {
+            let p = &params[6 * (c - 1)..6 * c];
+            ([p[0], p[1], p[2]], [p[3], p[4], p[5]])
+        }));
+
+        Ok(MultiCameraCalibration {
+            extrinsics,
+            rms_error: (total / count.max(1) as f64).sqrt(),
+            per_camera_errors: sums.iter().map(|&(s, c)| if c > 0 { (s / c as f64).sqrt() } else { 0.0 }).collect(),
+        })
+    }
+
+    /// Observations of marker instances seen by at least two cameras, with
+    /// their per-camera PnP poses, and the number of such instances
+    fn observations(&self) -> Result<(Vec<Observation>, usize)> {
+        let object = self.marker_object_points();
+        let mut observations = Vec::new();
+        let mut instance_count = 0;
+
+        for frame in &self.frames {
+            let mut by_id: HashMap<i32, Vec<Observation>> = HashMap::new();
+            for (camera, markers) in frame.iter().enumerate() {
+                for marker in markers {
+                    let Ok((rvec, tvec)) = solve_pnp_planar_with_model(&object, &marker.corners, self.cameras[camera].as_ref()) else {
+                        continue;
+                    };
+                    by_id.entry(marker.id).or_default().push(Observation {
+                        instance: 0,
+                        camera,
+                        corners: [marker.corners[0], marker.corners[1], marker.corners[2], marker.corners[3]],
+                        pose: (rodrigues(&rvec), tvec),
+                    });
+                }
+            }
+
+            let mut ids: Vec<i32> = by_id.keys().copied().collect();
+            ids.sort_unstable();
+            for id in ids {
+                let mut seen = by_id.remove(&id).unwrap_or_default();
+                if seen.len() < 2 {
+                    continue;
+                }
+                for obs in &mut seen {
+                    obs.instance = instance_count;
+                }
+                observations.extend(seen);
+                instance_count += 1;
+            }
+        }
+
+        if instance_count == 0 {
+            return Err(Error::InvalidParameter(
+                "No marker was seen by two cameras in the same frame".to_string(),
+            ));
+        }
+        Ok((observations, instance_count))
+    }
+
+    /// Camera-0-to-camera poses from a maximum spanning tree of the pairwise
+    /// relative pose estimates
+    fn initial_camera_poses(&self, observations: &[Observation]) -> Result<Vec<Pose>> {
+        let n = self.cameras.len();
+        let mut by_instance: HashMap<usize, Vec<&Observation>> = HashMap::new();
+        for obs in observations {
+            by_instance.entry(obs.instance).or_default().push(obs);
+        }
+
+        // Shared instances of each camera pair, as (observation in a, observation in b)
+        let mut shared: HashMap<(usize, usize), Vec<(&Observation, &Observation)>> = HashMap::new();
+        for seen in by_instance.values() {
+            for (i, a) in seen.iter().enumerate() {
+                for b in &seen[i + 1..] {
+                    let (a, b) = if a.camera < b.camera { (*a, *b) } else { (*b, *a) };
+                    shared.entry((a.camera, b.camera)).or_default().push((a, b));
+                }
+            }
+        }
+
+        let mut poses: Vec<Option<Pose>> = vec![None; n];
+        poses[0] = Some(identity());
+        for _ in 1..n {
+            // Strongest link from a placed camera to an unplaced one
+            let Some((&(a, b), pairs)) = shared
+                .iter()
+                .filter(|((a, b), _)| poses[*a].is_some() != poses[*b].is_some())
+                .max_by_key(|((a, b), pairs)| (pairs.len(), std::cmp::Reverse((*a, *b))))
+            else {
+                let missing = poses.iter().position(Option::is_none).unwrap_or(0);
+                return Err(Error::InvalidParameter(format!(
+                    "Camera {missing} shares no marker observations with the rest of the rig"
+                )));
+            };
+
+            let b_from_a = self.relative_pose(pairs);
+            if let Some(pose_a) = poses[a] {
+                poses[b] = Some(compose(&b_from_a, &pose_a));
+            } else if let Some(pose_b) = poses[b] {
+                poses[a] = Some(compose(&invert(&b_from_a), &pose_b));
+            }
+        }
+        Ok(poses.into_iter().flatten().collect())
+    }
+
+    /// Camera-a-to-camera-b transform: the candidate from one shared marker
+    /// that best predicts camera b's corners of all shared markers
+    fn relative_pose(&self, pairs: &[(&Observation, &Observation)]) -> Pose {
+        let object = self.marker_object_points();
+        let score = |candidate: &Pose| -> f64 {
+            pairs
+                .iter()
+                .map(|(a, b)| {
+                    let (r, t) = compose(candidate, &a.pose);
+                    object
+                        .iter()
+                        .zip(&b.corners)
+                        .map(|(p, q)| {
+                            project_with_pose(self.cameras[b.camera].as_ref(), &r, &t, p).map_or(UNPROJECTABLE_RESIDUAL, |[u, v]| {
+                                (u - f64::from(q.x)).hypot(v - f64::from(q.y))
+                            })
+                        })
+                        .sum::<f64>()
+                })
+                .sum()
+        };
+
+        pairs
+            .iter()
+            .map(|(a, b)| compose(&b.pose, &invert(&a.pose)))
+            .map(|candidate| (score(&candidate), candidate))
+            .min_by(|x, y| x.0.total_cmp(&y.0))
+            .map_or_else(identity, |(_, pose)| pose)
+    }
+
+    /// Corner residuals of one observation; `params` holds the poses of
+    /// cameras 1.. followed by those of the marker instances
+    fn residuals(&self, params: &[f64], obs: &Observation) -> Vec<f64> {
+        let n = self.cameras.len();
+        let camera = if obs.camera == 0 { identity() } else { pose_from_vector(&params[6 * (obs.camera - 1)..]) };
+        let marker = pose_from_vector(&params[6 * (n - 1 + obs.instance)..]);
+        let (r, t) = compose(&camera, &marker);
+
+        self.marker_object_points()
+            .iter()
+            .zip(&obs.corners)
+            .flat_map(|(p, q)| match project_with_pose(self.cameras[obs.camera].as_ref(), &r, &t, p) {
+                Some([u, v]) => [u - f64::from(q.x), v - f64::from(q.y)],
+                None => [UNPROJECTABLE_RESIDUAL; 2],
+            })
+            .collect()
+    }
+
+    /// Joint Levenberg-Marquardt over camera and marker poses
+    ///
+    /// Each observation depends only on its camera's and its marker's pose,
+    /// so the Jacobian is assembled observation by observation.
+    fn refine(&self, observations: &[Observation], params: &mut Vec<f64>) {
+        let n = self.cameras.len();
+        let size = params.len();
+        let cost = |p: &[f64]| -> f64 {
+            observations.iter().map(|obs| self.residuals(p, obs).iter().map(|r| r * r).sum::<f64>()).sum()
+        };
+
+        let mut lambda = 1e-3;
+        let mut current_cost = cost(params);
+
+        for _ in 0..100 {
+            let mut jtj = vec![vec![0.0; size]; size];
+            let mut jtr = vec![0.0; size];
+
+            for obs in observations {
+                let current = self.residuals(params, obs);
+                let camera_columns = if obs.camera == 0 { 0..0 } else { 6 * (obs.camera - 1)..6 * obs.camera };
+                let marker_start = 6 * (n - 1 + obs.instance);
+                let columns: Vec<usize> = camera_columns.chain(marker_start..marker_start + 6).collect();
+
+                let jacobian: Vec<Vec<f64>> = columns
+                    .iter()
+                    .map(|&k| {
+                        let step = 1e-6 * params[k].abs().max(1.0);
+                        let mut plus = params.clone();
+                        let mut minus = params.clone();
+                        plus[k] += step;
+                        minus[k] -= step;
+                        self.residuals(&plus, obs)
+                            .iter()
+                            .zip(self.residuals(&minus, obs))
+                            .map(|(a, b)| (a - b) / (2.0 * step))
+                            .collect()
+                    })
+                    .collect();
+
+                for (a, &i) in columns.iter().enumerate() {
+                    for (b, &j) in columns.iter().enumerate() {
+                        jtj[i][j] += jacobian[a].iter().zip(&jacobian[b]).map(|(x, y)| x * y).sum::<f64>();
+                    }
+                    jtr[i] -= jacobian[a].iter().zip(&current).map(|(x, r)| x * r).sum::<f64>();
+                }
+            }
+
+            let mut improved = false;
+            while lambda < 1e10 {
+                let mut damped = jtj.clone();
+                for (i, row) in damped.iter_mut().enumerate() {
+                    row[i] += lambda * jtj[i][i].max(1e-12);
+                }
+
+                let Some(delta) = solve_dense(damped, jtr.clone()) else {
+                    lambda *= 10.0;
+                    continue;
+                };
+
+                let candidate: Vec<f64> = params.iter().zip(&delta).map(|(p, d)| p + d).collect();
+                let c = cost(&candidate);
+                if c < current_cost {
+                    let gain = current_cost - c;
+                    *params = candidate;
+                    current_cost = c;
+                    lambda = (lambda / 10.0).max(1e-12);
+                    improved = gain > 1e-12 * current_cost.max(1e-30);
+                    break;
+                }
+                lambda *= 10.0;
+            }
+
+            if !improved {
+                break;
+            }
+        }
+    }
+}
+
+fn identity() -> Pose {
+    ([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]], [0.0; 3])
+}
+
+/// `a ∘ b`: apply `b`, then `a`
+fn compose(a: &Pose, b: &Pose) -> Pose {
+    let (ra, ta) = a;
+    let (rb, tb) = b;
+    let r = std::array::from_fn(|i| std::array::from_fn(|j| (0..3).map(|k| ra[i][k] * rb[k][j]).sum()));
+    let t = std::array::from_fn(|i| (0..3).map(|k| ra[i][k] * tb[k]).sum::<f64>() + ta[i]);
+    (r, t)
+}
+
+fn invert((r, t): &Pose) -> Pose {
+    let rt: [[f64; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| r[j][i]));
+    let t = std::array::from_fn(|i| -(0..3).map(|k| rt[i][k] * t[k]).sum::<f64>());
+    (rt, t)
+}
+
+fn pose_to_vector((r, t): &Pose) -> [f64; 6] {
+    let rvec = rodrigues_from_matrix(r);
+    [rvec[0], rvec[1], rvec[2], t[0], t[1], t[2]]
+}
+
+fn pose_from_vector(p: &[f64]) -> Pose {
+    (rodrigues(&[p[0], p[1], p[2]]), [p[3], p[4], p[5]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calib3d::camera::{CameraMatrix, DistortionCoefficients};
+    use crate::calib3d::camera_model::{project_points_with_model, FisheyeModel, PinholeModel};
+    use crate::calib3d::fisheye::{FisheyeCameraMatrix, FisheyeDistortion};
+
+    fn rig() -> Vec<Box<dyn CameraModel>> {
+        vec![
+            Box::new(PinholeModel::new(CameraMatrix::new(600.0, 600.0, 320.0, 240.0), DistortionCoefficients::zero())),
+            Box::new(PinholeModel::new(
+                CameraMatrix::new(550.0, 560.0, 330.0, 235.0),
+                DistortionCoefficients::new(-0.08, 0.01, 0.0, 0.0005, 0.0),
+            )),
+            Box::new(FisheyeModel::new(
+                FisheyeCameraMatrix::new(250.0, 250.0, 320.0, 240.0),
+                FisheyeDistortion::from_array(&[-0.02, 0.003, 0.0, 0.0]),
+            )),
+        ]
+    }
+
+    /// Camera 1 sits to the right of camera 0; camera 2, a fisheye, further
+    /// right and turned towards the scene
+    fn true_extrinsics() -> Vec<([f64; 3], [f64; 3])> {
+        vec![([0.0; 3], [0.0; 3]), ([0.0, -0.1, 0.02], [-0.4, 0.01, 0.03]), ([0.05, -0.35, 0.0], [-1.1, 0.0, 0.3])]
+    }
+
+    /// Detections of markers moving across the scene; each camera reports
+    /// the markers fully inside its 640x480 image
+    fn detections(calibrator: &MultiCameraCalibrator) -> Vec<Vec<Vec<ArucoMarker>>> {
+        let object = calibrator.marker_object_points();
+        let extrinsics = true_extrinsics();
+        let mut frames = Vec::new();
+        for f in 0..12 {
+            let x = -0.6 + 0.2 * f as f64;
+            let markers = [
+                (3, [0.1 * (f as f64).sin(), 0.2, -0.1], [x, -0.2, 2.0 + 0.1 * (f % 3) as f64]),
+                (7, [-0.2, 0.1 * (f as f64).cos(), 0.05], [x + 0.4, 0.25, 2.4]),
+            ];
+
+            let mut frame = Vec::new();
+            for (camera, model) in rig().iter().enumerate() {
+                let camera_pose = pose_from_vector(&[extrinsics[camera].0, extrinsics[camera].1].concat());
+                let mut seen = Vec::new();
+                for (id, rvec, tvec) in markers {
+                    let (r, t) = compose(&camera_pose, &(rodrigues(&rvec), tvec));
+                    let corners: Option<Vec<Point2f>> =
+                        project_points_with_model(&object, &rodrigues_from_matrix(&r), &t, model.as_ref()).into_iter().collect();
+                    let Some(mut corners) = corners else { continue };
+                    if corners.iter().all(|p| p.x >= 0.0 && p.y >= 0.0 && p.x < 640.0 && p.y < 480.0) {
+                        // Deterministic sub-pixel detection noise
+                        for (k, p) in corners.iter_mut().enumerate() {
+                            p.x += 0.1 * ((f * 7 + k * 3 + camera) as f32).sin();
+                            p.y += 0.1 * ((f * 5 + k * 11 + camera) as f32).cos();
+                        }
+                        seen.push(ArucoMarker { id, corners });
+                    }
+                }
+                frame.push(seen);
+            }
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn test_multi_camera_calibration() {
+        let mut calibrator = MultiCameraCalibrator::new(rig(), 0.15).unwrap();
+        for frame in detections(&calibrator) {
+            calibrator.add_frame(&frame).unwrap();
+        }
+        assert_eq!(calibrator.frame_count(), 12);
+
+        let result = calibrator.calibrate().unwrap();
+        assert!(result.rms_error < 0.5, "rms {}", result.rms_error);
+        for (camera, (rvec, tvec)) in true_extrinsics().iter().enumerate() {
+            let (r, t) = result.extrinsics[camera];
+            for i in 0..3 {
+                assert!((r[i] - rvec[i]).abs() < 0.01, "camera {camera} rvec {r:?}");
+                assert!((t[i] - tvec[i]).abs() < 0.02, "camera {camera} tvec {t:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_camera_validation() {
+        assert!(MultiCameraCalibrator::new(rig().into_iter().take(1).collect(), 0.15).is_err());
+
+        let mut calibrator = MultiCameraCalibrator::new(rig(), 0.15).unwrap();
+        assert!(calibrator.add_frame(&[Vec::new(), Vec::new()]).is_err());
+        let marker = ArucoMarker { id: 1, corners: vec![Point2f::new(0.0, 0.0); 4] };
+        assert!(calibrator.add_frame(&[vec![marker.clone(), marker], Vec::new(), Vec::new()]).is_err());
+
+        // Camera 2 never shares a marker with the others
+        for frame in detections(&calibrator) {
+            calibrator.add_frame(&[frame[0].clone(), frame[1].clone(), Vec::new()]).unwrap();
+        }
+        let err = calibrator.calibrate().unwrap_err();
+        assert!(err.to_string().contains("Camera 2"), "{err}");
+    }
+}
@@ -0,0 +1,168 @@
+#![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+use crate::calib3d::pnp::solve_dense;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Surface orientation and reflectance recovered by [`photometric_stereo`]
+#[derive(Debug, Clone)]
+pub struct PhotometricStereo {
+    /// Unit surface normals, 3-channel F32 in the frame of the light
+    /// directions; zero where the surface is black
+    pub normals: Mat,
+    /// Lambertian albedo, 1-channel F32, in image intensity units per unit
+    /// light
+    pub albedo: Mat,
+}
+
+/// Recover normals and albedo of a Lambertian surface from images taken
+/// under known distant lights
+///
+/// `images` are 1-channel captures of a static scene from a fixed camera,
+/// any depth, one per entry of `light_dirs`; each light direction points
+/// from the surface towards the light and its length is the light's
+/// intensity. At least three non-coplanar lights are needed. Each pixel's
+/// `albedo * normal` is the least-squares solution of `I = L · g`, so
+/// pixels in attached shadow for some light bias the estimate; with more
+/// than three lights the effect is diluted.
+pub fn photometric_stereo(images: &[Mat], light_dirs: &[[f64; 3]]) -> Result<PhotometricStereo> {
+    if images.len() < 3 {
+        return Err(Error::InvalidParameter(
+            "Photometric stereo needs at least 3 images".to_string(),
+        ));
+    }
+    if images.len() != light_dirs.len() {
+        return Err(Error::InvalidParameter(format!(
+            "{} images but {} light directions",
+            images.len(),
+            light_dirs.len()
+        )));
+    }
+    let (rows, cols) = (images[0].rows(), images[0].cols());
+    for image in images {
+        if image.channels() != 1 {
+            return Err(Error::UnsupportedOperation(
+                "Photometric stereo requires 1-channel images".to_string(),
+            ));
+        }
+        if image.rows() != rows || image.cols() != cols {
+            return Err(Error::InvalidDimensions(
+                "All images must have the same size".to_string(),
+            ));
+        }
+    }
+
+    // Pseudo-inverse (LᵀL)⁻¹Lᵀ, shared by every pixel
+    let mut normal_matrix = vec![vec![0.0; 3]; 3];
+    for l in light_dirs {
+        for i in 0..3 {
+            for j in 0..3 {
+                normal_matrix[i][j] += l[i] * l[j];
+            }
+        }
+    }
+    let mut inverse = [[0.0; 3]; 3];
+    for j in 0..3 {
+        let mut e = vec![0.0; 3];
+        e[j] = 1.0;
+        let column = solve_dense(normal_matrix.clone(), e).ok_or_else(|| {
+            Error::InvalidParameter("Light directions must not be coplanar".to_string())
+        })?;
+        for i in 0..3 {
+            inverse[i][j] = column[i];
+        }
+    }
+    let pinv: Vec<[f64; 3]> = light_dirs
+        .iter()
+        .map(|l| std::array::from_fn(|i| (0..3).map(|k| inverse[i][k] * l[k]).sum()))
+        .collect();
+
+    let mut normals = Mat::new(rows, cols, 3, MatDepth::F32)?;
+    let mut albedo = Mat::new(rows, cols, 1, MatDepth::F32)?;
+    for row in 0..rows {
+        for col in 0..cols {
+            let mut g = [0.0; 3];
+            for (image, p) in images.iter().zip(&pinv) {
+                let v = intensity(image, row, col)?;
+                for (gk, pk) in g.iter_mut().zip(p) {
+                    *gk += pk * v;
+                }
+            }
+            let rho = (g[0] * g[0] + g[1] * g[1] + g[2] * g[2]).sqrt();
+            albedo.set_f32(row, col, 0, rho as f32)?;
+            if rho > 1e-9 {
+                for (k, gk) in g.iter().enumerate() {
+                    normals.set_f32(row, col, k, (gk / rho) as f32)?;
+                }
+            }
+        }
+    }
+    Ok(PhotometricStereo { normals, albedo })
+}
+
+fn intensity(image: &Mat, row: usize, col: usize) -> Result<f64> {
+    Ok(match image.depth() {
+        MatDepth::U8 => f64::from(image.at(row, col)?[0]),
+        MatDepth::U16 => f64::from(image.at_u16(row, col, 0)?),
+        MatDepth::S32 => f64::from(image.at_i32(row, col, 0)?),
+        MatDepth::F32 => f64::from(image.at_f32(row, col, 0)?),
+        MatDepth::F64 => image.at_f64(row, col, 0)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_photometric_stereo_sphere() {
+        let (size, radius, rho) = (48usize, 20.0, 0.8);
+        let lights = [[0.0, 0.0, 1.0], [0.5, 0.0, 0.866], [0.0, 0.5, 0.866], [-0.4, -0.4, 0.825]];
+        let lights: Vec<[f64; 3]> = lights.iter().map(|l| l.map(|v| v * 250.0)).collect();
+        let normal_at = |row: usize, col: usize| {
+            let (x, y) = ((col as f64 - 23.5) / radius, (row as f64 - 23.5) / radius);
+            let z2 = 1.0 - x * x - y * y;
+            (z2 > 0.0).then(|| [x, y, z2.sqrt()])
+        };
+
+        let images: Vec<Mat> = lights
+            .iter()
+            .map(|l| {
+                let mut image = Mat::new(size, size, 1, MatDepth::F32).unwrap();
+                for row in 0..size {
+                    for col in 0..size {
+                        if let Some(n) = normal_at(row, col) {
+                            let shade = (n[0] * l[0] + n[1] * l[1] + n[2] * l[2]).max(0.0);
+                            image.set_f32(row, col, 0, (rho * shade) as f32).unwrap();
+                        }
+                    }
+                }
+                image
+            })
+            .collect();
+
+        let result = photometric_stereo(&images, &lights).unwrap();
+        let mut checked = 0;
+        for row in 0..size {
+            for col in 0..size {
+                let Some(n) = normal_at(row, col) else {
+                    assert_eq!(result.albedo.at_f32(row, col, 0).unwrap(), 0.0);
+                    continue;
+                };
+                // Only pixels lit by every light satisfy the Lambertian model
+                if lights.iter().any(|l| n[0] * l[0] + n[1] * l[1] + n[2] * l[2] <= 0.0) {
+                    continue;
+                }
+                checked += 1;
+                assert!((f64::from(result.albedo.at_f32(row, col, 0).unwrap()) - rho).abs() < 1e-3);
+                for (k, nk) in n.iter().enumerate() {
+                    assert!((f64::from(result.normals.at_f32(row, col, k).unwrap()) - nk).abs() < 1e-3);
+                }
+            }
+        }
+        assert!(checked > 500);
+
+        assert!(photometric_stereo(&images[..2], &lights[..2]).is_err());
+        let coplanar = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]];
+        assert!(photometric_stereo(&images[..3], &coplanar).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::types::{Point, Point3f};
-use crate::calib3d::camera::CameraMatrix;
+use crate::core::types::{Point, Point2f, Point3f};
+use crate::calib3d::camera::{rodrigues, rodrigues_from_matrix, CameraMatrix, DistortionCoefficients};
+use crate::calib3d::camera_model::{project_with_pose, CameraModel, PinholeModel};
+use crate::calib3d::robust::{Estimator, RobustEstimate, RobustProblem};
 use crate::error::{Error, Result};
 
 /// Solve Perspective-n-Point problem to estimate camera pose
@@ -45,6 +47,397 @@ pub enum PnPMethod {
     DLS,        // Direct Least Squares
 }
 
+/// Pose of a planar target (all object points with `z == 0`) from sub-pixel
+/// image points
+///
+/// The pose is initialized from the plane-to-image homography of the
+/// undistorted points and refined with Levenberg-Marquardt on the pixel
+/// reprojection error, including lens distortion. Needs at least 4 points.
+pub fn solve_pnp_planar(
+    object_points: &[Point3f],
+    image_points: &[Point2f],
+    camera_matrix: &CameraMatrix,
+    dist: &DistortionCoefficients,
+) -> Result<([f64; 3], [f64; 3])> {
+    let model = PinholeModel::new(camera_matrix.clone(), dist.clone());
+    solve_pnp_planar_with_model(object_points, image_points, &model)
+}
+
+/// [`solve_pnp_planar`] for any camera model
+///
+/// The image points must view the target from in front of the camera
+/// (`z > 0`), which is where the homography initialization is defined.
+pub fn solve_pnp_planar_with_model(
+    object_points: &[Point3f],
+    image_points: &[Point2f],
+    model: &dyn CameraModel,
+) -> Result<([f64; 3], [f64; 3])> {
+    let (plane, normalized) = planar_correspondences(object_points, image_points, model)?;
+
+    let h = homography_dlt(&plane, &normalized)?;
+    let mut pose = pose_from_homography(&h);
+
+    refine_pose_lm(object_points, image_points, model, &mut pose);
+
+    Ok(split_pose(&pose))
+}
+
+/// Rotation vector and translation vector of a camera pose
+pub type PoseVectors = ([f64; 3], [f64; 3]);
+
+/// [`solve_pnp_planar`] tolerant of mismatched points
+///
+/// Poses are hypothesized from 4-point samples and scored by pixel
+/// reprojection error with `estimator`; the best one is refined on its
+/// inliers. The estimate holds `(rvec, tvec)` and the inlier mask.
+pub fn solve_pnp_planar_robust(
+    object_points: &[Point3f],
+    image_points: &[Point2f],
+    camera_matrix: &CameraMatrix,
+    dist: &DistortionCoefficients,
+    estimator: &Estimator,
+) -> Result<RobustEstimate<PoseVectors>> {
+    let model = PinholeModel::new(camera_matrix.clone(), dist.clone());
+    solve_pnp_planar_robust_with_model(object_points, image_points, &model, estimator)
+}
+
+/// [`solve_pnp_planar_robust`] for any camera model
+pub fn solve_pnp_planar_robust_with_model(
+    object_points: &[Point3f],
+    image_points: &[Point2f],
+    model: &dyn CameraModel,
+    estimator: &Estimator,
+) -> Result<RobustEstimate<PoseVectors>> {
+    let (plane, normalized) = planar_correspondences(object_points, image_points, model)?;
+    let problem = PlanarPoseProblem { object_points, image_points, model, plane, normalized };
+    estimator.estimate(&problem, None)
+}
+
+/// Board-plane points and their undistorted normalized image points
+type PlanarPairs = (Vec<[f64; 2]>, Vec<[f64; 2]>);
+
+/// Board-plane coordinates and undistorted normalized image coordinates of
+/// planar correspondences, after validating them
+fn planar_correspondences(
+    object_points: &[Point3f],
+    image_points: &[Point2f],
+    model: &dyn CameraModel,
+) -> Result<PlanarPairs> {
+    if object_points.len() != image_points.len() {
+        return Err(Error::InvalidParameter(
+            "Object and image points must have same length".to_string(),
+        ));
+    }
+
+    if object_points.len() < 4 {
+        return Err(Error::InvalidParameter(
+            "Planar PnP requires at least 4 points".to_string(),
+        ));
+    }
+
+    if object_points.iter().any(|p| p.z.abs() > 1e-6) {
+        return Err(Error::InvalidParameter(
+            "Planar PnP requires object points with z = 0".to_string(),
+        ));
+    }
+
+    // Homography from the board plane to normalized, undistorted image coordinates
+    let plane: Vec<[f64; 2]> = object_points.iter().map(|p| [f64::from(p.x), f64::from(p.y)]).collect();
+    let normalized = image_points
+        .iter()
+        .map(|p| match model.unproject(&[f64::from(p.x), f64::from(p.y)]) {
+            Some([x, y, z]) if z > 1e-9 => Ok([x / z, y / z]),
+            _ => Err(Error::InvalidParameter(
+                "Planar PnP requires image points in front of the camera".to_string(),
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((plane, normalized))
+}
+
+fn split_pose(pose: &[f64; 6]) -> ([f64; 3], [f64; 3]) {
+    ([pose[0], pose[1], pose[2]], [pose[3], pose[4], pose[5]])
+}
+
+/// Pixel residual of points the camera model cannot image
+const UNPROJECTABLE_RESIDUAL: f64 = 1e6;
+
+/// Pixel offsets of the projected object points from the image points
+fn reprojection_offsets<'a>(
+    object_points: &'a [Point3f],
+    image_points: &'a [Point2f],
+    model: &'a dyn CameraModel,
+    rvec: &[f64; 3],
+    tvec: &'a [f64; 3],
+) -> impl Iterator<Item = (f64, f64)> + 'a {
+    let r = rodrigues(rvec);
+    object_points.iter().zip(image_points).map(move |(p, q)| {
+        project_with_pose(model, &r, tvec, p).map_or((UNPROJECTABLE_RESIDUAL, UNPROJECTABLE_RESIDUAL), |[u, v]| {
+            (u - f64::from(q.x), v - f64::from(q.y))
+        })
+    })
+}
+
+/// Planar pose fitting for the robust estimators
+struct PlanarPoseProblem<'a> {
+    object_points: &'a [Point3f],
+    image_points: &'a [Point2f],
+    model: &'a dyn CameraModel,
+    plane: Vec<[f64; 2]>,
+    normalized: Vec<[f64; 2]>,
+}
+
+impl RobustProblem for PlanarPoseProblem<'_> {
+    type Model = PoseVectors;
+
+    fn num_points(&self) -> usize {
+        self.object_points.len()
+    }
+
+    fn sample_size(&self) -> usize {
+        4
+    }
+
+    fn fit(&self, sample: &[usize]) -> Vec<Self::Model> {
+        let plane: Vec<[f64; 2]> = sample.iter().map(|&i| self.plane[i]).collect();
+        let normalized: Vec<[f64; 2]> = sample.iter().map(|&i| self.normalized[i]).collect();
+        homography_dlt(&plane, &normalized)
+            .map(|h| split_pose(&pose_from_homography(&h)))
+            .into_iter()
+            .collect()
+    }
+
+    fn residuals(&self, (rvec, tvec): &Self::Model) -> Vec<f64> {
+        reprojection_offsets(self.object_points, self.image_points, self.model, rvec, tvec)
+            .map(|(dx, dy)| dx.hypot(dy))
+            .collect()
+    }
+
+    fn refine(&self, (rvec, tvec): &Self::Model, inliers: &[usize], _weights: &[f64]) -> Option<Self::Model> {
+        let object: Vec<Point3f> = inliers.iter().map(|&i| self.object_points[i]).collect();
+        let image: Vec<Point2f> = inliers.iter().map(|&i| self.image_points[i]).collect();
+        let mut pose = [rvec[0], rvec[1], rvec[2], tvec[0], tvec[1], tvec[2]];
+        refine_pose_lm(&object, &image, self.model, &mut pose);
+        Some(split_pose(&pose))
+    }
+}
+
+/// Least-squares homography with `h33 = 1` on Hartley-normalized points
+pub(crate) fn homography_dlt(src: &[[f64; 2]], dst: &[[f64; 2]]) -> Result<[[f64; 3]; 3]> {
+    homography_dlt_weighted(src, dst, None)
+}
+
+/// [`homography_dlt`] with each correspondence's equations scaled by its
+/// weight
+pub(crate) fn homography_dlt_weighted(src: &[[f64; 2]], dst: &[[f64; 2]], weights: Option<&[f64]>) -> Result<[[f64; 3]; 3]> {
+    fn normalization(points: &[[f64; 2]]) -> [f64; 3] {
+        let n = points.len() as f64;
+        let mx = points.iter().map(|p| p[0]).sum::<f64>() / n;
+        let my = points.iter().map(|p| p[1]).sum::<f64>() / n;
+        let spread = points.iter().map(|p| ((p[0] - mx).powi(2) + (p[1] - my).powi(2)).sqrt()).sum::<f64>() / n;
+        let s = if spread > 1e-12 { std::f64::consts::SQRT_2 / spread } else { 1.0 };
+        [s, mx, my]
+    }
+
+    let [ss, sx, sy] = normalization(src);
+    let [ds, dx, dy] = normalization(dst);
+
+    let mut ata = vec![vec![0.0; 8]; 8];
+    let mut atb = vec![0.0; 8];
+
+    for (k, (p, q)) in src.iter().zip(dst).enumerate() {
+        let w = weights.map_or(1.0, |w| w[k]);
+        let (x, y) = ((p[0] - sx) * ss, (p[1] - sy) * ss);
+        let (u, v) = ((q[0] - dx) * ds, (q[1] - dy) * ds);
+
+        let rows = [
+            ([x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y], u),
+            ([0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y], v),
+        ];
+        for (a, b) in rows {
+            for i in 0..8 {
+                for j in 0..8 {
+                    ata[i][j] += w * a[i] * a[j];
+                }
+                atb[i] += w * a[i] * b;
+            }
+        }
+    }
+
+    let h = solve_dense(ata, atb).ok_or_else(|| {
+        Error::InvalidParameter("Degenerate point configuration for homography".to_string())
+    })?;
+
+    // Undo the normalizations: H = T_dst^-1 * Hn * T_src
+    let hn = [[h[0], h[1], h[2]], [h[3], h[4], h[5]], [h[6], h[7], 1.0]];
+    let t_src = [[ss, 0.0, -ss * sx], [0.0, ss, -ss * sy], [0.0, 0.0, 1.0]];
+    let t_dst_inv = [[1.0 / ds, 0.0, dx], [0.0, 1.0 / ds, dy], [0.0, 0.0, 1.0]];
+
+    Ok(mat3_mul(&mat3_mul(&t_dst_inv, &hn), &t_src))
+}
+
+/// Decompose a plane-to-normalized-image homography into `[rvec, tvec]`
+fn pose_from_homography(h: &[[f64; 3]; 3]) -> [f64; 6] {
+    let col = |c: usize| [h[0][c], h[1][c], h[2][c]];
+    let (h1, h2, h3) = (col(0), col(1), col(2));
+
+    let norm = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    let mut lambda = 2.0 / (norm(h1) + norm(h2));
+    // The target must lie in front of the camera
+    if h3[2] * lambda < 0.0 {
+        lambda = -lambda;
+    }
+
+    let scale = |v: [f64; 3], k: f64| [v[0] * k, v[1] * k, v[2] * k];
+    let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+    // Gram-Schmidt to get the closest proper rotation
+    let r1 = scale(h1, 1.0 / norm(h1));
+    let r2_raw = scale(h2, lambda);
+    let r2_orth = [
+        r2_raw[0] - dot(r1, r2_raw) * r1[0],
+        r2_raw[1] - dot(r1, r2_raw) * r1[1],
+        r2_raw[2] - dot(r1, r2_raw) * r1[2],
+    ];
+    let r1 = if lambda < 0.0 { scale(r1, -1.0) } else { r1 };
+    let r2 = scale(r2_orth, 1.0 / norm(r2_orth));
+    let r3 = [
+        r1[1] * r2[2] - r1[2] * r2[1],
+        r1[2] * r2[0] - r1[0] * r2[2],
+        r1[0] * r2[1] - r1[1] * r2[0],
+    ];
+
+    let r = [[r1[0], r2[0], r3[0]], [r1[1], r2[1], r3[1]], [r1[2], r2[2], r3[2]]];
+    let rvec = rodrigues_from_matrix(&r);
+    let t = scale(h3, lambda);
+
+    [rvec[0], rvec[1], rvec[2], t[0], t[1], t[2]]
+}
+
+/// Levenberg-Marquardt refinement of `[rvec, tvec]` on pixel reprojection error
+fn refine_pose_lm(
+    object_points: &[Point3f],
+    image_points: &[Point2f],
+    model: &dyn CameraModel,
+    pose: &mut [f64; 6],
+) {
+    let residuals = |p: &[f64; 6]| -> Vec<f64> {
+        let rvec = [p[0], p[1], p[2]];
+        let tvec = [p[3], p[4], p[5]];
+        reprojection_offsets(object_points, image_points, model, &rvec, &tvec)
+            .flat_map(|(dx, dy)| [dx, dy])
+            .collect()
+    };
+    let cost = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>();
+
+    let mut lambda = 1e-3;
+    let mut current = residuals(pose);
+    let mut current_cost = cost(&current);
+
+    for _ in 0..50 {
+        // Numerical Jacobian with central differences
+        let mut jacobian = vec![[0.0f64; 6]; current.len()];
+        for k in 0..6 {
+            let step = 1e-6 * pose[k].abs().max(1.0);
+            let mut plus = *pose;
+            let mut minus = *pose;
+            plus[k] += step;
+            minus[k] -= step;
+            for (row, (a, b)) in jacobian.iter_mut().zip(residuals(&plus).iter().zip(residuals(&minus))) {
+                row[k] = (a - b) / (2.0 * step);
+            }
+        }
+
+        let mut jtj = vec![vec![0.0; 6]; 6];
+        let mut jtr = vec![0.0; 6];
+        for (row, r) in jacobian.iter().zip(&current) {
+            for i in 0..6 {
+                for j in 0..6 {
+                    jtj[i][j] += row[i] * row[j];
+                }
+                jtr[i] -= row[i] * r;
+            }
+        }
+
+        let mut improved = false;
+        while lambda < 1e10 {
+            let mut damped = jtj.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += lambda * jtj[i][i].max(1e-12);
+            }
+
+            let Some(delta) = solve_dense(damped, jtr.clone()) else {
+                lambda *= 10.0;
+                continue;
+            };
+
+            let mut candidate = *pose;
+            for (c, d) in candidate.iter_mut().zip(&delta) {
+                *c += d;
+            }
+
+            let r = residuals(&candidate);
+            let c = cost(&r);
+            if c < current_cost {
+                let gain = current_cost - c;
+                *pose = candidate;
+                current = r;
+                current_cost = c;
+                lambda = (lambda / 10.0).max(1e-12);
+                improved = gain > 1e-14 * current_cost.max(1e-30);
+                break;
+            }
+            lambda *= 10.0;
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
+
+/// Solve a dense square system with partial pivoting
+pub(crate) fn solve_dense(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for i in 0..n {
+        let pivot = (i..n).max_by(|&p, &q| a[p][i].abs().total_cmp(&a[q][i].abs()))?;
+        if a[pivot][i].abs() < 1e-14 {
+            return None;
+        }
+        a.swap(i, pivot);
+        b.swap(i, pivot);
+
+        for j in i + 1..n {
+            let factor = a[j][i] / a[i][i];
+            if factor == 0.0 {
+                continue;
+            }
+            let (upper, lower) = a.split_at_mut(j);
+            for (v, p) in lower[0].iter_mut().zip(&upper[i]).skip(i) {
+                *v -= factor * p;
+            }
+            b[j] -= factor * b[i];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|j| a[i][j] * x[j]).sum();
+        x[i] = (b[i] - sum) / a[i][i];
+    }
+    Some(x)
+}
+
+fn mat3_mul(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
 /// Iterative `PnP` using Levenberg-Marquardt
 fn solve_pnp_iterative(
     object_points: &[Point3f],
@@ -436,4 +829,80 @@ mod tests {
         let dist = distance_3d(&p1, &p2);
         assert!((dist - 1.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_solve_pnp_planar_recovers_pose() {
+        use crate::calib3d::camera::project_points;
+
+        let camera = CameraMatrix::new(700.0, 700.0, 320.0, 240.0);
+        let dist = DistortionCoefficients::new(-0.1, 0.02, 0.0, 0.001, -0.0005);
+        let object: Vec<Point3f> = (0..6)
+            .flat_map(|r| (0..8).map(move |c| Point3f::new(c as f32 * 0.03, r as f32 * 0.03, 0.0)))
+            .collect();
+
+        let rvec = [0.2, -0.3, 0.1];
+        let tvec = [-0.1, -0.05, 0.6];
+        let image = project_points(&object, &rvec, &tvec, &camera, &dist);
+
+        let (r, t) = solve_pnp_planar(&object, &image, &camera, &dist).unwrap();
+        for i in 0..3 {
+            assert!((r[i] - rvec[i]).abs() < 1e-3, "rvec {r:?}");
+            assert!((t[i] - tvec[i]).abs() < 1e-3, "tvec {t:?}");
+        }
+
+        assert!(solve_pnp_planar(&object[..3], &image[..3], &camera, &dist).is_err());
+    }
+
+    #[test]
+    fn test_solve_pnp_planar_robust_ignores_mismatches() {
+        use crate::calib3d::camera::project_points;
+        use crate::calib3d::robust::RobustMethod;
+
+        let camera = CameraMatrix::new(700.0, 700.0, 320.0, 240.0);
+        let dist = DistortionCoefficients::zero();
+        let object: Vec<Point3f> = (0..6)
+            .flat_map(|r| (0..8).map(move |c| Point3f::new(c as f32 * 0.03, r as f32 * 0.03, 0.0)))
+            .collect();
+        let rvec = [0.2, -0.3, 0.1];
+        let tvec = [-0.1, -0.05, 0.6];
+        let mut image = project_points(&object, &rvec, &tvec, &camera, &dist);
+        for (i, p) in image.iter_mut().enumerate().step_by(4) {
+            p.x += 25.0 + i as f32;
+            p.y -= 40.0;
+        }
+
+        for method in [RobustMethod::Ransac, RobustMethod::MagsacPlusPlus] {
+            let estimate = solve_pnp_planar_robust(&object, &image, &camera, &dist, &Estimator::new(method)).unwrap();
+            let (r, t) = estimate.model;
+            for i in 0..3 {
+                assert!((r[i] - rvec[i]).abs() < 1e-3, "{method:?} rvec {r:?}");
+                assert!((t[i] - tvec[i]).abs() < 1e-3, "{method:?} tvec {t:?}");
+            }
+            assert_eq!(estimate.inlier_count(), 36);
+            assert!(!estimate.inliers[0] && estimate.inliers[1]);
+        }
+    }
+
+    #[test]
+    fn test_solve_pnp_planar_with_fisheye_model() {
+        use crate::calib3d::camera_model::{project_points_with_model, FisheyeModel};
+        use crate::calib3d::fisheye::{FisheyeCameraMatrix, FisheyeDistortion};
+
+        let model = FisheyeModel::new(
+            FisheyeCameraMatrix::new(300.0, 300.0, 640.0, 480.0),
+            FisheyeDistortion::from_array(&[-0.02, 0.003, 0.0, 0.0]),
+        );
+        let object: Vec<Point3f> = (0..6)
+            .flat_map(|r| (0..8).map(move |c| Point3f::new(c as f32 * 0.1, r as f32 * 0.1, 0.0)))
+            .collect();
+        let rvec = [0.2, -0.3, 0.1];
+        let tvec = [-0.4, -0.2, 0.5];
+        let image: Vec<Point2f> = project_points_with_model(&object, &rvec, &tvec, &model).into_iter().map(Option::unwrap).collect();
+
+        let (r, t) = solve_pnp_planar_with_model(&object, &image, &model).unwrap();
+        for i in 0..3 {
+            assert!((r[i] - rvec[i]).abs() < 1e-3, "rvec {r:?}");
+            assert!((t[i] - tvec[i]).abs() < 1e-3, "tvec {t:?}");
+        }
+    }
 }
@@ -0,0 +1,412 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! RGB-D frames from depth cameras
+//!
+//! Depth sensors such as Kinect or RealSense deliver a depth image in raw
+//! units (typically U16 millimetres) from a camera offset from the color
+//! camera. [`align_depth_to_color`] re-projects the depth into the color
+//! camera so both images share pixels, and [`RgbdFrame`] bundles the
+//! registered pair with its intrinsics for range segmentation, normal maps
+//! and point cloud export.
+
+use crate::calib3d::camera::CameraMatrix;
+use crate::calib3d::camera_model::CameraModel;
+use crate::core::types::{ColorOrder, Point3f, Size};
+use crate::core::{Mat, MatDepth, PointCloud};
+use crate::error::{Error, Result};
+
+/// A color image with a depth image registered to it
+///
+/// `depth` is 1-channel U16 or F32 and `depth_scale` converts its values to
+/// metres (0.001 for millimetre U16 depth, 1.0 for F32 metres). Zero or
+/// non-finite depth marks pixels without a measurement. Both images share
+/// the pinhole intrinsics `camera`.
+#[derive(Debug, Clone)]
+pub struct RgbdFrame {
+    color: Mat,
+    depth: Mat,
+    camera: CameraMatrix,
+    depth_scale: f64,
+}
+
+impl RgbdFrame {
+    /// Bundle a registered pair; `color` is U8 with 1, 3 or 4 channels and
+    /// must have the size of `depth`
+    pub fn new(color: Mat, depth: Mat, camera: CameraMatrix, depth_scale: f64) -> Result<Self> {
+        check_depth(&depth, depth_scale)?;
+        if color.depth() != MatDepth::U8 || !matches!(color.channels(), 1 | 3 | 4) {
+            return Err(Error::UnsupportedOperation(
+                "RGB-D color image must be U8 with 1, 3 or 4 channels".to_string(),
+            ));
+        }
+        if color.rows() != depth.rows() || color.cols() != depth.cols() {
+            return Err(Error::InvalidDimensions(
+                "RGB-D color and depth images must have the same size".to_string(),
+            ));
+        }
+        Ok(Self { color, depth, camera, depth_scale })
+    }
+
+    #[must_use]
+    pub fn color(&self) -> &Mat {
+        &self.color
+    }
+
+    #[must_use]
+    pub fn depth(&self) -> &Mat {
+        &self.depth
+    }
+
+    #[must_use]
+    pub fn camera(&self) -> &CameraMatrix {
+        &self.camera
+    }
+
+    #[must_use]
+    pub fn depth_scale(&self) -> f64 {
+        self.depth_scale
+    }
+
+    /// Depth in metres at a pixel, or `None` where there is no measurement
+    pub fn depth_at(&self, row: usize, col: usize) -> Result<Option<f64>> {
+        let z = raw_depth(&self.depth, row, col)? * self.depth_scale;
+        Ok((z.is_finite() && z > 0.0).then_some(z))
+    }
+
+    /// U8 mask, 255 where the depth lies in `[min_depth, max_depth]` metres
+    ///
+    /// The usual first step for separating foreground objects from the
+    /// background; pixels without depth are 0.
+    pub fn depth_mask(&self, min_depth: f64, max_depth: f64) -> Result<Mat> {
+        let depths = depth_in_meters(&self.depth, self.depth_scale)?;
+        let mut mask = Mat::new(self.depth.rows(), self.depth.cols(), 1, MatDepth::U8)?;
+        for (m, &z) in mask.data_mut().iter_mut().zip(&depths) {
+            *m = if z > 0.0 && (min_depth..=max_depth).contains(&z) { 255 } else { 0 };
+        }
+        Ok(mask)
+    }
+
+    /// Surface normals of the depth image; see [`normal_map`]
+    pub fn normals(&self) -> Result<Mat> {
+        normal_map(&self.depth, &self.camera, self.depth_scale)
+    }
+
+    /// One colored point per valid depth pixel, in metres in the camera frame
+    ///
+    /// Colors come from `color` (gray is replicated, BGR-tagged images are
+    /// swapped to RGB).
+    pub fn to_point_cloud(&self) -> Result<PointCloud> {
+        let depths = depth_in_meters(&self.depth, self.depth_scale)?;
+        let bgr = self.color.color_order() == ColorOrder::Bgr;
+        let cols = self.depth.cols();
+
+        let mut points = Vec::new();
+        let mut colors = Vec::new();
+        for (i, &z) in depths.iter().enumerate() {
+            if z <= 0.0 {
+                continue;
+            }
+            let (row, col) = (i / cols, i % cols);
+            let [x, y, z] = back_project(&self.camera, col as f64, row as f64, z);
+            points.push(Point3f::new(x as f32, y as f32, z as f32));
+
+            let pixel = self.color.at(row, col)?;
+            colors.push(match (pixel.len(), bgr) {
+                (1, _) => [pixel[0]; 3],
+                (_, true) => [pixel[2], pixel[1], pixel[0]],
+                _ => [pixel[0], pixel[1], pixel[2]],
+            });
+        }
+        PointCloud::with_colors(points, colors)
+    }
+}
+
+fn check_depth(depth: &Mat, depth_scale: f64) -> Result<()> {
+    if depth.channels() != 1 || !matches!(depth.depth(), MatDepth::U16 | MatDepth::F32) {
+        return Err(Error::UnsupportedOperation(
+            "Depth image must be a 1-channel U16 or F32 Mat".to_string(),
+        ));
+    }
+    if !(depth_scale > 0.0 && depth_scale.is_finite()) {
+        return Err(Error::InvalidParameter(
+            "depth_scale must be positive and finite".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn raw_depth(depth: &Mat, row: usize, col: usize) -> Result<f64> {
+    match depth.depth() {
+        MatDepth::U16 => Ok(f64::from(depth.at_u16(row, col, 0)?)),
+        _ => Ok(f64::from(depth.at_f32(row, col, 0)?)),
+    }
+}
+
+/// Row-major depths in metres, with 0 where there is no measurement
+fn depth_in_meters(depth: &Mat, depth_scale: f64) -> Result<Vec<f64>> {
+    let mut out = Vec::with_capacity(depth.rows() * depth.cols());
+    for row in 0..depth.rows() {
+        for col in 0..depth.cols() {
+            let z = raw_depth(depth, row, col)? * depth_scale;
+            out.push(if z.is_finite() && z > 0.0 { z } else { 0.0 });
+        }
+    }
+    Ok(out)
+}
+
+fn back_project(camera: &CameraMatrix, u: f64, v: f64, z: f64) -> [f64; 3] {
+    [(u - camera.cx) * z / camera.fx, (v - camera.cy) * z / camera.fy, z]
+}
+
+/// Re-project a depth image into the color camera's pixel grid
+///
+/// `rotation` and `translation` (metres) map depth-camera to color-camera
+/// coordinates. Every depth pixel is back-projected with `depth_model`,
+/// moved into the color frame and splatted over the color pixels its
+/// footprint covers; where several land on one pixel the nearest wins. The
+/// result has size `color_size`, the type and units of `depth`, and holds
+/// the depth along the color camera's axis (0 where nothing projects).
+pub fn align_depth_to_color(
+    depth: &Mat,
+    depth_model: &dyn CameraModel,
+    color_model: &dyn CameraModel,
+    rotation: &[[f64; 3]; 3],
+    translation: &[f64; 3],
+    color_size: Size,
+    depth_scale: f64,
+) -> Result<Mat> {
+    check_depth(depth, depth_scale)?;
+    if color_size.width <= 0 || color_size.height <= 0 {
+        return Err(Error::InvalidDimensions(
+            "Color image size must be positive".to_string(),
+        ));
+    }
+
+    let (width, height) = (color_size.width as usize, color_size.height as usize);
+    let depths = depth_in_meters(depth, depth_scale)?;
+    let mut zbuffer = vec![f64::INFINITY; width * height];
+
+    // Depth pixel at depth z, in color-camera coordinates
+    let to_color_frame = |u: f64, v: f64, z: f64| -> Option<[f64; 3]> {
+        let ray = depth_model.unproject(&[u, v])?;
+        if ray[2] <= 1e-9 {
+            return None;
+        }
+        let p = ray.map(|r| r * z / ray[2]);
+        Some(std::array::from_fn(|i| rotation[i][0] * p[0] + rotation[i][1] * p[1] + rotation[i][2] * p[2] + translation[i]))
+    };
+    let to_color = |u: f64, v: f64, z: f64| to_color_frame(u, v, z).and_then(|q| color_model.project(&q));
+
+    let cols = depth.cols();
+    for (i, &z) in depths.iter().enumerate() {
+        if z <= 0.0 {
+            continue;
+        }
+        let (u, v) = ((i % cols) as f64, (i / cols) as f64);
+        let (Some(a), Some(b), Some([_, _, zc])) = (to_color(u - 0.5, v - 0.5, z), to_color(u + 0.5, v + 0.5, z), to_color_frame(u, v, z)) else {
+            continue;
+        };
+        if zc <= 0.0 {
+            continue;
+        }
+
+        // Color pixels whose centres the depth pixel's footprint covers
+        let x0 = a[0].min(b[0]).round().max(0.0);
+        let x1 = a[0].max(b[0]).round().min(width as f64 - 1.0);
+        let y0 = a[1].min(b[1]).round().max(0.0);
+        let y1 = a[1].max(b[1]).round().min(height as f64 - 1.0);
+        if x0 > x1 || y0 > y1 {
+            continue;
+        }
+        for y in y0 as usize..=y1 as usize {
+            for x in x0 as usize..=x1 as usize {
+                let slot = &mut zbuffer[y * width + x];
+                *slot = slot.min(zc);
+            }
+        }
+    }
+
+    let mut aligned = Mat::new(height, width, 1, depth.depth())?;
+    for (i, &z) in zbuffer.iter().enumerate() {
+        if z.is_infinite() {
+            continue;
+        }
+        let raw = z / depth_scale;
+        let (row, col) = (i / width, i % width);
+        match depth.depth() {
+            MatDepth::U16 => aligned.set_u16(row, col, 0, raw.round().clamp(0.0, 65535.0) as u16)?,
+            _ => aligned.set_f32(row, col, 0, raw as f32)?,
+        }
+    }
+    Ok(aligned)
+}
+
+/// Per-pixel unit surface normals of a depth image
+///
+/// Normals come from the cross product of the horizontal and vertical
+/// tangents of the back-projected surface (central differences, one-sided
+/// at holes and depth discontinuities) and face the camera. Returns a
+/// 3-channel F32 Mat of `(nx, ny, nz)` in camera coordinates; pixels
+/// without enough valid neighbours are `(0, 0, 0)`.
+pub fn normal_map(depth: &Mat, camera: &CameraMatrix, depth_scale: f64) -> Result<Mat> {
+    check_depth(depth, depth_scale)?;
+    if camera.fx == 0.0 || camera.fy == 0.0 {
+        return Err(Error::InvalidParameter(
+            "Camera focal lengths must be non-zero".to_string(),
+        ));
+    }
+
+    let (rows, cols) = (depth.rows(), depth.cols());
+    let depths = depth_in_meters(depth, depth_scale)?;
+    let point = |row: usize, col: usize| back_project(camera, col as f64, row as f64, depths[row * cols + col]);
+
+    // A neighbour is usable if it has depth close to the centre's
+    let usable = |row: usize, col: usize, z: f64| {
+        let n = depths[row * cols + col];
+        n > 0.0 && (n - z).abs() <= 0.05 * z
+    };
+    let sub = |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+
+    let mut normals = Mat::new(rows, cols, 3, MatDepth::F32)?;
+    for row in 0..rows {
+        for col in 0..cols {
+            let z = depths[row * cols + col];
+            if z <= 0.0 {
+                continue;
+            }
+            let centre = point(row, col);
+
+            let tangent = |prev: Option<(usize, usize)>, next: Option<(usize, usize)>| {
+                let prev = prev.filter(|&(r, c)| usable(r, c, z)).map(|(r, c)| point(r, c));
+                let next = next.filter(|&(r, c)| usable(r, c, z)).map(|(r, c)| point(r, c));
+                match (prev, next) {
+                    (Some(p), Some(n)) => Some(sub(n, p)),
+                    (Some(p), None) => Some(sub(centre, p)),
+                    (None, Some(n)) => Some(sub(n, centre)),
+                    (None, None) => None,
+                }
+            };
+            let horizontal = tangent(col.checked_sub(1).map(|c| (row, c)), (col + 1 < cols).then_some((row, col + 1)));
+            let vertical = tangent(row.checked_sub(1).map(|r| (r, col)), (row + 1 < rows).then_some((row + 1, col)));
+            let (Some(du), Some(dv)) = (horizontal, vertical) else {
+                continue;
+            };
+
+            let mut n = [
+                du[1] * dv[2] - du[2] * dv[1],
+                du[2] * dv[0] - du[0] * dv[2],
+                du[0] * dv[1] - du[1] * dv[0],
+            ];
+            let norm = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            if norm < 1e-12 {
+                continue;
+            }
+            // Face the camera
+            let sign = if n[0] * centre[0] + n[1] * centre[1] + n[2] * centre[2] > 0.0 { -1.0 } else { 1.0 };
+            for (ch, v) in n.iter_mut().enumerate() {
+                *v *= sign / norm;
+                normals.set_f32(row, col, ch, *v as f32)?;
+            }
+        }
+    }
+    Ok(normals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calib3d::camera::DistortionCoefficients;
+    use crate::calib3d::camera_model::PinholeModel;
+
+    fn plane_depth(rows: usize, cols: usize, camera: &CameraMatrix, z_at: impl Fn(f64, f64) -> f64) -> Mat {
+        let mut depth = Mat::new(rows, cols, 1, MatDepth::U16).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let x = (col as f64 - camera.cx) / camera.fx;
+                let y = (row as f64 - camera.cy) / camera.fy;
+                depth.set_u16(row, col, 0, (z_at(x, y) * 1000.0).round() as u16).unwrap();
+            }
+        }
+        depth
+    }
+
+    #[test]
+    fn test_rgbd_frame() {
+        let camera = CameraMatrix::new(100.0, 100.0, 20.0, 15.0);
+        let mut depth = plane_depth(30, 40, &camera, |_, _| 1.5);
+        depth.set_u16(0, 0, 0, 0).unwrap();
+        // A closer box in the middle
+        for row in 10..20 {
+            for col in 15..25 {
+                depth.set_u16(row, col, 0, 800).unwrap();
+            }
+        }
+        let mut color = Mat::new(30, 40, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+        color.at_mut(15, 20).unwrap().copy_from_slice(&[10, 20, 30]);
+
+        let frame = RgbdFrame::new(color, depth, camera, 0.001).unwrap();
+        assert_eq!(frame.depth_at(0, 0).unwrap(), None);
+        assert!((frame.depth_at(15, 20).unwrap().unwrap() - 0.8).abs() < 1e-9);
+
+        let mask = frame.depth_mask(0.5, 1.0).unwrap();
+        assert_eq!(mask.data().iter().filter(|&&m| m == 255).count(), 100);
+
+        let cloud = frame.to_point_cloud().unwrap();
+        assert_eq!(cloud.len(), 30 * 40 - 1);
+        let centre = cloud.points().iter().position(|p| p.x.abs() < 1e-6 && p.y.abs() < 1e-6).unwrap();
+        assert!((cloud.points()[centre].z - 0.8).abs() < 1e-6);
+        assert_eq!(cloud.colors().unwrap()[centre], [30, 20, 10]);
+
+        let wrong_size = Mat::new(10, 10, 3, MatDepth::U8).unwrap();
+        assert!(RgbdFrame::new(wrong_size, frame.depth().clone(), frame.camera().clone(), 0.001).is_err());
+    }
+
+    #[test]
+    fn test_normal_map() {
+        // Plane z - 0.5 X = 1; its normal facing the camera is (0.5, 0, -1)
+        let camera = CameraMatrix::new(200.0, 200.0, 32.0, 24.0);
+        let depth = plane_depth(48, 64, &camera, |x, _| 1.0 / (1.0 - 0.5 * x));
+        let normals = normal_map(&depth, &camera, 0.001).unwrap();
+        assert_eq!((normals.channels(), normals.depth()), (3, MatDepth::F32));
+
+        let expected = [0.5 / 1.25f64.sqrt(), 0.0, -1.0 / 1.25f64.sqrt()];
+        for (row, col) in [(24, 32), (5, 5), (40, 60)] {
+            for (ch, e) in expected.iter().enumerate() {
+                let n = f64::from(normals.at_f32(row, col, ch).unwrap());
+                assert!((n - e).abs() < 0.05, "({row}, {col}) ch {ch}: {n} vs {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_align_depth_to_color() {
+        // Color camera 5 cm to the right of the depth camera, with a
+        // different focal length and resolution
+        let depth_camera = CameraMatrix::new(100.0, 100.0, 40.0, 30.0);
+        let color_camera = CameraMatrix::new(150.0, 150.0, 60.0, 45.0);
+        let depth_model = PinholeModel::new(depth_camera.clone(), DistortionCoefficients::zero());
+        let color_model = PinholeModel::new(color_camera.clone(), DistortionCoefficients::zero());
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+        // Background wall at 2 m with a box at 1 m
+        let mut depth = plane_depth(60, 80, &depth_camera, |_, _| 2.0);
+        for row in 20..40 {
+            for col in 30..50 {
+                depth.set_u16(row, col, 0, 1000).unwrap();
+            }
+        }
+
+        let aligned = align_depth_to_color(&depth, &depth_model, &color_model, &identity, &[-0.05, 0.0, 0.0], Size::new(120, 90), 0.001).unwrap();
+        assert_eq!((aligned.rows(), aligned.cols(), aligned.depth()), (90, 120, MatDepth::U16));
+
+        // The box centre (depth camera x = 0) appears shifted by f * b / z
+        let box_col = (60.0 - 150.0 * 0.05 / 1.0) as usize;
+        assert_eq!(aligned.at_u16(45, box_col, 0).unwrap(), 1000);
+        // Depth camera x = 0.3 on the wall
+        let wall_col = (60.0 + 150.0 * (0.3 * 2.0 - 0.05) / 2.0) as usize;
+        assert_eq!(aligned.at_u16(45, wall_col, 0).unwrap(), 2000);
+
+        // The splat leaves no holes inside the box
+        let holes = (35..55).filter(|&row| aligned.at_u16(row, box_col, 0).unwrap() == 0).count();
+        assert_eq!(holes, 0);
+    }
+}
@@ -0,0 +1,382 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss)]
+use crate::error::{Error, Result};
+use crate::imgproc::noise::SplitMix64;
+
+/// Square root of the 99% chi-square quantile for 2 degrees of freedom:
+/// residuals of 2D points with noise `sigma` stay below `SIGMA_QUANTILE *
+/// sigma` 99% of the time
+const SIGMA_QUANTILE: f64 = 3.035;
+
+/// Noise scales the MAGSAC++ score marginalizes over
+const SIGMA_LEVELS: usize = 10;
+
+/// Model fitting problem solved by an [`Estimator`]
+///
+/// Implementors own the data; the estimator only handles point indices.
+pub trait RobustProblem {
+    type Model: Clone;
+
+    /// Number of data points
+    fn num_points(&self) -> usize;
+
+    /// Points needed to fit a model
+    fn sample_size(&self) -> usize;
+
+    /// Candidate models through the sampled points (none if degenerate)
+    fn fit(&self, sample: &[usize]) -> Vec<Self::Model>;
+
+    /// Residual of every point under `model`, in the unit of the threshold
+    fn residuals(&self, model: &Self::Model) -> Vec<f64>;
+
+    /// Weighted least-squares refit of `model` on `inliers`; `None` keeps the
+    /// minimal-sample model
+    fn refine(&self, _model: &Self::Model, _inliers: &[usize], _weights: &[f64]) -> Option<Self::Model> {
+        None
+    }
+}
+
+/// Sampling and scoring strategy of an [`Estimator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RobustMethod {
+    /// Uniform sampling, models scored by inlier count
+    #[default]
+    Ransac,
+    /// Progressive sampling from the best-scored points first (Chum and
+    /// Matas), models scored by inlier count
+    Prosac,
+    /// Uniform sampling, models scored by inlier likelihood marginalized
+    /// over noise scales up to the threshold (Barath et al.), and a final
+    /// refit weighted the same way
+    MagsacPlusPlus,
+}
+
+/// Result of [`Estimator::estimate`]
+#[derive(Debug, Clone)]
+pub struct RobustEstimate<M> {
+    pub model: M,
+    /// Points whose residual is below the threshold
+    pub inliers: Vec<bool>,
+    /// Hypotheses drawn before the search stopped
+    pub iterations: usize,
+}
+
+impl<M> RobustEstimate<M> {
+    #[must_use]
+    pub fn inlier_count(&self) -> usize {
+        self.inliers.iter().filter(|&&inlier| inlier).count()
+    }
+}
+
+/// Hypothesize-and-verify estimator shared by homography and pose fitting
+///
+/// The search stops once `confidence` that an all-inlier sample was drawn is
+/// reached, or after `max_iterations` hypotheses. Sampling is seeded, so
+/// results are reproducible.
+#[derive(Debug, Clone)]
+pub struct Estimator {
+    pub method: RobustMethod,
+    pub threshold: f64,
+    pub confidence: f64,
+    pub max_iterations: usize,
+    pub seed: u64,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Self::new(RobustMethod::Ransac)
+    }
+}
+
+impl Estimator {
+    /// 3-unit threshold, 0.99 confidence and at most 2000 iterations
+    #[must_use]
+    pub fn new(method: RobustMethod) -> Self {
+        Self {
+            method,
+            threshold: 3.0,
+            confidence: 0.99,
+            max_iterations: 2000,
+            seed: 0x5EED,
+        }
+    }
+
+    /// Largest residual counted as an inlier; for MAGSAC++ the largest
+    /// noise scale considered is derived from it
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Fit `problem` robustly
+    ///
+    /// `scores` rank the points for PROSAC (higher is better, e.g. negated
+    /// descriptor distances); without them PROSAC assumes the points are
+    /// already sorted best first. Other methods ignore them.
+    pub fn estimate<P: RobustProblem>(&self, problem: &P, scores: Option<&[f64]>) -> Result<RobustEstimate<P::Model>> {
+        let n = problem.num_points();
+        let m = problem.sample_size();
+        if m == 0 || n < m {
+            return Err(Error::InvalidParameter(format!(
+                "Robust estimation needs at least {m} points, got {n}"
+            )));
+        }
+        if self.threshold <= 0.0 || !(0.0..1.0).contains(&self.confidence) {
+            return Err(Error::InvalidParameter(
+                "Threshold must be positive and confidence in [0, 1)".to_string(),
+            ));
+        }
+        if scores.is_some_and(|s| s.len() != n) {
+            return Err(Error::InvalidParameter(
+                "Need one score per point".to_string(),
+            ));
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        if let Some(scores) = scores {
+            order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        }
+        let mut prosac = ProsacSchedule::new(n, m, self.max_iterations);
+        let mut rng = SplitMix64::new(self.seed);
+        let mut sample = vec![0; m];
+
+        let mut best: Option<(P::Model, f64)> = None;
+        let mut limit = self.max_iterations;
+        let mut iterations = 0;
+        while iterations < limit {
+            iterations += 1;
+            match self.method {
+                RobustMethod::Prosac => prosac.draw(&mut rng, &order, &mut sample),
+                _ => draw_uniform(&mut rng, n, &mut sample),
+            }
+
+            for model in problem.fit(&sample) {
+                let residuals = problem.residuals(&model);
+                let score = self.score(&residuals);
+                if best.as_ref().is_none_or(|(_, s)| score > *s) {
+                    let inliers = residuals.iter().filter(|&&r| r < self.threshold).count();
+                    limit = limit.min(required_iterations(self.confidence, inliers as f64 / n as f64, m));
+                    best = Some((model, score));
+                }
+            }
+        }
+
+        let Some((mut model, score)) = best else {
+            return Err(Error::InvalidParameter(
+                "Robust estimation found no model".to_string(),
+            ));
+        };
+
+        // Final refit on the inliers of the best hypothesis
+        let residuals = problem.residuals(&model);
+        let inlier_idx: Vec<usize> = (0..n).filter(|&i| residuals[i] < self.threshold).collect();
+        if inlier_idx.len() >= m {
+            let weights: Vec<f64> = inlier_idx.iter().map(|&i| self.weight(residuals[i])).collect();
+            if let Some(refined) = problem.refine(&model, &inlier_idx, &weights) {
+                if self.score(&problem.residuals(&refined)) >= score {
+                    model = refined;
+                }
+            }
+        }
+
+        let inliers: Vec<bool> = problem.residuals(&model).iter().map(|&r| r < self.threshold).collect();
+        if inliers.iter().filter(|&&inlier| inlier).count() < m {
+            return Err(Error::InvalidParameter(
+                "Robust estimation failed to find sufficient inliers".to_string(),
+            ));
+        }
+        Ok(RobustEstimate { model, inliers, iterations })
+    }
+
+    /// Model quality, higher is better
+    fn score(&self, residuals: &[f64]) -> f64 {
+        match self.method {
+            RobustMethod::MagsacPlusPlus => residuals.iter().map(|&r| self.weight(r)).sum(),
+            _ => residuals.iter().filter(|&&r| r < self.threshold).count() as f64,
+        }
+    }
+
+    /// Inlier weight of a residual: 1 for counting methods, the likelihood
+    /// averaged over noise scales up to `threshold / SIGMA_QUANTILE` for
+    /// MAGSAC++
+    fn weight(&self, r: f64) -> f64 {
+        if self.method != RobustMethod::MagsacPlusPlus {
+            return if r < self.threshold { 1.0 } else { 0.0 };
+        }
+        let sigma_max = self.threshold / SIGMA_QUANTILE;
+        (1..=SIGMA_LEVELS)
+            .map(|level| {
+                let sigma = sigma_max * level as f64 / SIGMA_LEVELS as f64;
+                if r < SIGMA_QUANTILE * sigma { (-r * r / (2.0 * sigma * sigma)).exp() } else { 0.0 }
+            })
+            .sum::<f64>()
+            / SIGMA_LEVELS as f64
+    }
+}
+
+/// Hypotheses needed to draw one all-inlier sample with `confidence`
+pub(crate) fn required_iterations(confidence: f64, inlier_ratio: f64, sample_size: usize) -> usize {
+    let all_inliers = inlier_ratio.powi(sample_size as i32);
+    if all_inliers <= f64::EPSILON {
+        return usize::MAX;
+    }
+    if all_inliers >= 1.0 - f64::EPSILON {
+        return 1;
+    }
+    ((1.0 - confidence).ln() / (1.0 - all_inliers).ln()).ceil().max(1.0) as usize
+}
+
+/// `sample.len()` distinct indices below `n`
+fn draw_uniform(rng: &mut SplitMix64, n: usize, sample: &mut [usize]) {
+    for i in 0..sample.len() {
+        sample[i] = loop {
+            let candidate = (rng.next_u64() % n as u64) as usize;
+            if !sample[..i].contains(&candidate) {
+                break candidate;
+            }
+        };
+    }
+}
+
+/// PROSAC growth function: the sampling pool starts with the best
+/// `sample_size` points and grows so that, by the time `max_iterations`
+/// hypotheses are drawn, sampling is uniform over all points
+struct ProsacSchedule {
+    n: usize,
+    m: usize,
+    subset: usize,
+    t_n: f64,
+    t_n_prime: usize,
+    t: usize,
+}
+
+impl ProsacSchedule {
+    fn new(n: usize, m: usize, max_iterations: usize) -> Self {
+        let t_n = (0..m).fold(max_iterations as f64, |t, i| t * (m - i) as f64 / (n - i) as f64);
+        Self { n, m, subset: m, t_n, t_n_prime: 1, t: 0 }
+    }
+
+    fn draw(&mut self, rng: &mut SplitMix64, order: &[usize], sample: &mut [usize]) {
+        self.t += 1;
+        if self.t > self.t_n_prime && self.subset < self.n {
+            let t_next = self.t_n * (self.subset + 1) as f64 / (self.subset + 1 - self.m) as f64;
+            self.t_n_prime += (t_next - self.t_n).ceil() as usize;
+            self.t_n = t_next;
+            self.subset += 1;
+        }
+
+        if self.t_n_prime < self.t {
+            draw_uniform(rng, self.subset, sample);
+        } else {
+            // The newest point of the pool plus m - 1 from the points before it
+            if let Some((last, rest)) = sample.split_last_mut() {
+                draw_uniform(rng, self.subset - 1, rest);
+                *last = self.subset - 1;
+            }
+        }
+        for index in sample.iter_mut() {
+            *index = order[*index];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line y = a x + b through 2D points, residual = vertical distance
+    struct LineProblem {
+        points: Vec<(f64, f64)>,
+    }
+
+    impl RobustProblem for LineProblem {
+        type Model = (f64, f64);
+
+        fn num_points(&self) -> usize {
+            self.points.len()
+        }
+
+        fn sample_size(&self) -> usize {
+            2
+        }
+
+        fn fit(&self, sample: &[usize]) -> Vec<(f64, f64)> {
+            let ((x0, y0), (x1, y1)) = (self.points[sample[0]], self.points[sample[1]]);
+            if (x1 - x0).abs() < 1e-9 {
+                return Vec::new();
+            }
+            let a = (y1 - y0) / (x1 - x0);
+            vec![(a, y0 - a * x0)]
+        }
+
+        fn residuals(&self, &(a, b): &(f64, f64)) -> Vec<f64> {
+            self.points.iter().map(|&(x, y)| (y - a * x - b).abs()).collect()
+        }
+
+        fn refine(&self, _model: &(f64, f64), inliers: &[usize], weights: &[f64]) -> Option<(f64, f64)> {
+            let (mut sw, mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for (&i, &w) in inliers.iter().zip(weights) {
+                let (x, y) = self.points[i];
+                sw += w;
+                sx += w * x;
+                sy += w * y;
+                sxx += w * x * x;
+                sxy += w * x * y;
+            }
+            let det = sw * sxx - sx * sx;
+            (det.abs() > 1e-12).then(|| {
+                let a = (sw * sxy - sx * sy) / det;
+                (a, (sy - a * sx) / sw)
+            })
+        }
+    }
+
+    #[test]
+    fn test_estimators_reject_outliers() {
+        let mut rng = SplitMix64::new(3);
+        // 60 noisy inliers on y = 0.5 x + 2, then 40 outliers
+        let mut points = Vec::new();
+        let mut scores = Vec::new();
+        for i in 0..100 {
+            let x = f64::from(i) * 0.7;
+            if i < 60 {
+                points.push((x, 0.5 * x + 2.0 + (rng.next_f64() - 0.5) * 0.4));
+                scores.push(1.0 + rng.next_f64());
+            } else {
+                points.push((x, rng.next_f64() * 60.0));
+                scores.push(rng.next_f64());
+            }
+        }
+        let problem = LineProblem { points };
+
+        for method in [RobustMethod::Ransac, RobustMethod::Prosac, RobustMethod::MagsacPlusPlus] {
+            let estimate = Estimator::new(method).with_threshold(1.0).estimate(&problem, Some(&scores)).unwrap();
+            let (a, b) = estimate.model;
+            assert!((a - 0.5).abs() < 0.01 && (b - 2.0).abs() < 0.2, "{method:?}: {a} {b}");
+            assert!(estimate.inlier_count() >= 58, "{method:?}: {}", estimate.inlier_count());
+            assert!(estimate.inliers[..60].iter().filter(|&&inlier| inlier).count() >= 58);
+        }
+
+        let few = LineProblem { points: vec![(0.0, 0.0)] };
+        assert!(Estimator::default().estimate(&few, None).is_err());
+        assert!(Estimator::default().estimate(&problem, Some(&[1.0])).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::types::{Point, Point3f};
-use crate::core::{Mat, MatDepth};
+use crate::core::types::{Point, Point2f, Point3f};
+use crate::core::{Mat, MatDepth, PointCloud};
 use crate::error::{Error, Result};
 use crate::calib3d::camera::{CameraMatrix, DistortionCoefficients};
+use crate::calib3d::camera_model::CameraModel;
 
 /// Stereo camera parameters
 #[derive(Debug, Clone)]
@@ -170,14 +171,90 @@ pub fn compute_stereo_disparity(
                 }
             }
 
-            let pixel = disparity.at_mut(row, col)?;
-            pixel[0] = best_disparity.abs().min(255) as u8;
+            disparity.set_f32(row, col, 0, best_disparity.abs() as f32)?;
         }
     }
 
     Ok(disparity)
 }
 
+/// Disparity-to-depth matrix `Q` for a rectified pair
+///
+/// `camera` is the rectified left camera and `baseline` the distance between
+/// the optical centres, in the units the 3-D points should use.
+#[must_use]
+pub fn reprojection_matrix(camera: &CameraMatrix, baseline: f64) -> [[f64; 4]; 4] {
+    [
+        [1.0, 0.0, 0.0, -camera.cx],
+        [0.0, 1.0, 0.0, -camera.cy],
+        [0.0, 0.0, 0.0, camera.fx],
+        [0.0, 0.0, 1.0 / baseline, 0.0],
+    ]
+}
+
+/// Reproject a disparity map to a 3-D point cloud
+///
+/// Each pixel with a valid (positive, finite) disparity `d` becomes
+/// `Q * [x, y, d, 1]` after dehomogenization. `disparity` is 1-channel U8 or
+/// F32. When `color` is given (same size, 1- or 3-channel U8) every point
+/// takes the color of its pixel; grayscale is replicated to RGB.
+pub fn reproject_image_to_3d(disparity: &Mat, q: &[[f64; 4]; 4], color: Option<&Mat>) -> Result<PointCloud> {
+    if disparity.channels() != 1 || !matches!(disparity.depth(), MatDepth::U8 | MatDepth::F32) {
+        return Err(Error::UnsupportedOperation(
+            "Disparity must be a 1-channel U8 or F32 Mat".to_string(),
+        ));
+    }
+    if let Some(image) = color {
+        if image.rows() != disparity.rows() || image.cols() != disparity.cols() {
+            return Err(Error::InvalidDimensions(
+                "Color image must match disparity size".to_string(),
+            ));
+        }
+        if image.depth() != MatDepth::U8 || !matches!(image.channels(), 1 | 3) {
+            return Err(Error::UnsupportedOperation(
+                "Color image must be 1- or 3-channel U8".to_string(),
+            ));
+        }
+    }
+
+    let mut points = Vec::new();
+    let mut colors = Vec::new();
+
+    for row in 0..disparity.rows() {
+        for col in 0..disparity.cols() {
+            let d = match disparity.depth() {
+                MatDepth::U8 => f64::from(disparity.at(row, col)?[0]),
+                _ => f64::from(disparity.at_f32(row, col, 0)?),
+            };
+            if !(d.is_finite() && d > 0.0) {
+                continue;
+            }
+
+            let v = [col as f64, row as f64, d, 1.0];
+            let h: Vec<f64> = q.iter().map(|r| r.iter().zip(&v).map(|(a, b)| a * b).sum()).collect();
+            if h[3].abs() < 1e-12 {
+                continue;
+            }
+            points.push(Point3f::new((h[0] / h[3]) as f32, (h[1] / h[3]) as f32, (h[2] / h[3]) as f32));
+
+            if let Some(image) = color {
+                let pixel = image.at(row, col)?;
+                colors.push(if pixel.len() == 3 {
+                    [pixel[0], pixel[1], pixel[2]]
+                } else {
+                    [pixel[0]; 3]
+                });
+            }
+        }
+    }
+
+    if color.is_some() {
+        PointCloud::with_colors(points, colors)
+    } else {
+        Ok(PointCloud::new(points))
+    }
+}
+
 /// Triangulate 3D point from stereo correspondence
 pub fn triangulate_point(
     point_left: Point,
@@ -215,6 +292,57 @@ pub fn triangulate_point(
     Ok(Point3f::new(x as f32, y as f32, z as f32))
 }
 
+/// Triangulate a correspondence between two cameras of any model
+///
+/// `rotation` and `translation` map left-camera to right-camera coordinates
+/// (`X_right = R * X_left + t`), as in [`StereoParameters`]. Returns the
+/// midpoint of the closest approach of the two viewing rays, in left-camera
+/// coordinates.
+pub fn triangulate_point_with_models(
+    point_left: Point2f,
+    point_right: Point2f,
+    left: &dyn CameraModel,
+    right: &dyn CameraModel,
+    rotation: &[[f64; 3]; 3],
+    translation: &[f64; 3],
+) -> Result<Point3f> {
+    let unproject = |model: &dyn CameraModel, p: Point2f| {
+        model.unproject(&[f64::from(p.x), f64::from(p.y)]).ok_or_else(|| {
+            Error::InvalidParameter("Image point is outside the camera model".to_string())
+        })
+    };
+    let d1 = unproject(left, point_left)?;
+    let ray_right = unproject(right, point_right)?;
+
+    // Right ray and camera centre in left-camera coordinates
+    let rt = matrix_transpose_3x3(rotation);
+    let rotate = |v: &[f64; 3]| -> [f64; 3] { std::array::from_fn(|i| rt[i][0] * v[0] + rt[i][1] * v[1] + rt[i][2] * v[2]) };
+    let d2 = rotate(&ray_right);
+    let c2 = rotate(translation).map(|v| -v);
+
+    let dot = |a: &[f64; 3], b: &[f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let w0 = c2.map(|v| -v);
+    let (a, b, c) = (dot(&d1, &d1), dot(&d1, &d2), dot(&d2, &d2));
+    let (d, e) = (dot(&d1, &w0), dot(&d2, &w0));
+    let denom = a * c - b * b;
+    if denom.abs() < 1e-12 {
+        return Err(Error::InvalidParameter(
+            "Viewing rays are parallel".to_string(),
+        ));
+    }
+
+    let s = (b * e - c * d) / denom;
+    let u = (a * e - b * d) / denom;
+    if s <= 0.0 || u <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "Triangulated point is behind a camera".to_string(),
+        ));
+    }
+
+    let mid: [f64; 3] = std::array::from_fn(|i| (s * d1[i] + c2[i] + u * d2[i]) / 2.0);
+    Ok(Point3f::new(mid[0] as f32, mid[1] as f32, mid[2] as f32))
+}
+
 /// Rectify stereo images to align epipolar lines horizontally
 pub fn stereo_rectify(
     stereo_params: &StereoParameters,
@@ -339,4 +467,59 @@ mod tests {
         assert!((inv[1][1] - 0.5).abs() < 1e-6);
         assert!((inv[2][2] - 0.5).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_reproject_image_to_3d() {
+        let camera = CameraMatrix::new(100.0, 100.0, 2.0, 1.0);
+        let q = reprojection_matrix(&camera, 0.5);
+
+        let mut disparity = Mat::new(3, 5, 1, MatDepth::F32).unwrap();
+        disparity.set_f32(1, 2, 0, 10.0).unwrap();
+        disparity.set_f32(2, 4, 0, 25.0).unwrap();
+        let mut color = Mat::new(3, 5, 3, MatDepth::U8).unwrap();
+        color.at_mut(2, 4).unwrap().copy_from_slice(&[1, 2, 3]);
+
+        let cloud = reproject_image_to_3d(&disparity, &q, Some(&color)).unwrap();
+        assert_eq!(cloud.len(), 2);
+
+        // Z = f * B / d
+        let centre = cloud.points()[0];
+        assert!(centre.x.abs() < 1e-6 && centre.y.abs() < 1e-6);
+        assert!((centre.z - 5.0).abs() < 1e-5);
+        let corner = cloud.points()[1];
+        assert!((corner.z - 2.0).abs() < 1e-5);
+        assert!((corner.x - 2.0 * 2.0 / 100.0).abs() < 1e-6);
+        assert_eq!(cloud.colors().unwrap()[1], [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_triangulate_point_with_models() {
+        use crate::calib3d::camera::rodrigues;
+        use crate::calib3d::camera_model::{FisheyeModel, OmnidirectionalModel};
+        use crate::calib3d::fisheye::{FisheyeCameraMatrix, FisheyeDistortion};
+
+        let left = FisheyeModel::new(
+            FisheyeCameraMatrix::new(300.0, 300.0, 640.0, 480.0),
+            FisheyeDistortion::from_array(&[-0.02, 0.003, 0.0, 0.0]),
+        );
+        let right = OmnidirectionalModel::new(CameraMatrix::new(250.0, 250.0, 400.0, 400.0), 0.8, DistortionCoefficients::zero());
+        let rotation = rodrigues(&[0.0, 0.1, 0.0]);
+        let translation = [-0.2, 0.0, 0.0];
+
+        let point = [0.3, -0.1, 2.0];
+        let in_right: [f64; 3] = std::array::from_fn(|i| (0..3).map(|k| rotation[i][k] * point[k]).sum::<f64>() + translation[i]);
+        let [ul, vl] = left.project(&point).unwrap();
+        let [ur, vr] = right.project(&in_right).unwrap();
+
+        let p = triangulate_point_with_models(
+            Point2f::new(ul as f32, vl as f32),
+            Point2f::new(ur as f32, vr as f32),
+            &left,
+            &right,
+            &rotation,
+            &translation,
+        )
+        .unwrap();
+        assert!((f64::from(p.x) - 0.3).abs() < 1e-2 && (f64::from(p.y) + 0.1).abs() < 1e-2 && (f64::from(p.z) - 2.0).abs() < 2e-2, "{p:?}");
+    }
 }
@@ -0,0 +1,565 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Structured light: Gray-code and phase-shift patterns for a projector, and
+//! decoding of the captured sequences into per-pixel projector coordinates.
+//!
+//! A [`CorrespondenceMap`] decoded for each camera of a rectified stereo
+//! pair turns into a disparity map with [`CorrespondenceMap::disparity`],
+//! which [`reproject_image_to_3d`](crate::calib3d::reproject_image_to_3d)
+//! converts to a point cloud.
+use std::f64::consts::TAU;
+
+use crate::core::types::Point2f;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Projector axis a pattern encodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternAxis {
+    /// Vertical stripes, encoding the projector column
+    Columns,
+    /// Horizontal stripes, encoding the projector row
+    Rows,
+}
+
+/// Projector coordinates seen by each camera pixel
+///
+/// Pixels that could not be decoded (shadowed, saturated, or ambiguous
+/// bits) are NaN in both coordinate maps and 0 in `valid`.
+#[derive(Debug, Clone)]
+pub struct CorrespondenceMap {
+    /// Projector column per camera pixel, 1-channel F32
+    pub projector_x: Mat,
+    /// Projector row per camera pixel, 1-channel F32
+    pub projector_y: Mat,
+    /// 255 where the pixel decoded, 1-channel U8
+    pub valid: Mat,
+}
+
+impl CorrespondenceMap {
+    fn new(rows: usize, cols: usize) -> Result<Self> {
+        let mut nan = Mat::new(rows, cols, 1, MatDepth::F32)?;
+        for i in 0..rows * cols {
+            nan.set_f32(i / cols, i % cols, 0, f32::NAN)?;
+        }
+        Ok(Self {
+            projector_x: nan.clone(),
+            projector_y: nan,
+            valid: Mat::new(rows, cols, 1, MatDepth::U8)?,
+        })
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.valid.rows()
+    }
+
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.valid.cols()
+    }
+
+    /// Projector coordinates seen by camera pixel `(row, col)`, if decoded
+    #[must_use]
+    pub fn get(&self, row: usize, col: usize) -> Option<Point2f> {
+        if self.valid.at(row, col).ok()?[0] == 0 {
+            return None;
+        }
+        Some(Point2f::new(
+            self.projector_x.at_f32(row, col, 0).ok()?,
+            self.projector_y.at_f32(row, col, 0).ok()?,
+        ))
+    }
+
+    /// Number of decoded pixels
+    #[must_use]
+    pub fn valid_count(&self) -> usize {
+        self.valid.data().iter().filter(|&&v| v != 0).count()
+    }
+
+    fn set(&mut self, row: usize, col: usize, x: f32, y: f32) -> Result<()> {
+        self.projector_x.set_f32(row, col, 0, x)?;
+        self.projector_y.set_f32(row, col, 0, y)?;
+        self.valid.at_mut(row, col)?[0] = 255;
+        Ok(())
+    }
+
+    fn invalidate(&mut self, row: usize, col: usize) -> Result<()> {
+        self.set(row, col, f32::NAN, f32::NAN)?;
+        self.valid.at_mut(row, col)?[0] = 0;
+        Ok(())
+    }
+
+    /// Disparity map of a rectified stereo pair, with `self` decoded from the
+    /// left camera and `right` from the right one
+    ///
+    /// For each decoded left pixel the right image row is searched for the
+    /// column where the projector column matches, interpolating linearly
+    /// between neighbouring right pixels; disparities outside
+    /// `min_disparity..=max_disparity` are rejected. The result is a 1-channel
+    /// F32 map with 0 where no match was found (the smallest disparity wins
+    /// if the projector code repeats along the row), ready for
+    /// [`reproject_image_to_3d`](crate::calib3d::reproject_image_to_3d).
+    /// Phase-refined maps give sub-pixel disparities; Gray code alone gives
+    /// steps of one projector column.
+    pub fn disparity(&self, right: &CorrespondenceMap, min_disparity: f32, max_disparity: f32) -> Result<Mat> {
+        if self.rows() != right.rows() || self.cols() != right.cols() {
+            return Err(Error::InvalidDimensions(
+                "Correspondence maps must have the same size".to_string(),
+            ));
+        }
+        if min_disparity > max_disparity {
+            return Err(Error::InvalidParameter(
+                "min_disparity must not exceed max_disparity".to_string(),
+            ));
+        }
+
+        let cols = self.cols();
+        let mut disparity = Mat::new(self.rows(), cols, 1, MatDepth::F32)?;
+        for row in 0..self.rows() {
+            let right_x: Vec<f32> = (0..cols)
+                .map(|col| right.projector_x.at_f32(row, col, 0))
+                .collect::<Result<_>>()?;
+            for col in 0..cols {
+                let u = self.projector_x.at_f32(row, col, 0)?;
+                if !u.is_finite() {
+                    continue;
+                }
+                // Right-image columns that give a disparity in range
+                let first = (col as f32 - max_disparity).floor().max(0.0) as usize;
+                let last = ((col as f32 - min_disparity).ceil().max(0.0) as usize).min(cols - 1);
+                let mut best: Option<f32> = None;
+                for r in first..last {
+                    let (a, b) = (right_x[r], right_x[r + 1]);
+                    if !(a.is_finite() && b.is_finite()) || a == b || (u - a) * (u - b) > 0.0 {
+                        continue;
+                    }
+                    let x = r as f32 + (u - a) / (b - a);
+                    let d = col as f32 - x;
+                    if (min_disparity..=max_disparity).contains(&d) && best.is_none_or(|prev| d < prev) {
+                        best = Some(d);
+                    }
+                }
+                if let Some(d) = best {
+                    disparity.set_f32(row, col, 0, d)?;
+                }
+            }
+        }
+        Ok(disparity)
+    }
+}
+
+/// Binary-reflected Gray-code patterns for a projector of `width` x `height`
+///
+/// [`generate`](Self::generate) yields each bit of the column code then each
+/// bit of the row code, most significant first, every pattern followed by
+/// its inverse. Decoding compares each pattern with its inverse, so it is
+/// insensitive to surface albedo and ambient light.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrayCodePattern {
+    pub width: usize,
+    pub height: usize,
+    /// Minimum difference between a pattern and its inverse for a bit to be
+    /// trusted
+    pub white_threshold: u8,
+    /// Minimum difference between the all-white and all-black captures for a
+    /// pixel to count as lit by the projector
+    pub black_threshold: u8,
+}
+
+impl GrayCodePattern {
+    pub fn new(width: usize, height: usize) -> Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidParameter(
+                "Projector size must be positive".to_string(),
+            ));
+        }
+        Ok(Self { width, height, white_threshold: 5, black_threshold: 40 })
+    }
+
+    #[must_use]
+    pub fn with_white_threshold(mut self, threshold: u8) -> Self {
+        self.white_threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub fn with_black_threshold(mut self, threshold: u8) -> Self {
+        self.black_threshold = threshold;
+        self
+    }
+
+    /// Bits needed to encode every projector column or row
+    #[must_use]
+    pub fn bits(&self, axis: PatternAxis) -> usize {
+        let n = match axis {
+            PatternAxis::Columns => self.width,
+            PatternAxis::Rows => self.height,
+        };
+        (usize::BITS - (n - 1).leading_zeros()) as usize
+    }
+
+    /// Number of images returned by [`generate`](Self::generate)
+    #[must_use]
+    pub fn pattern_count(&self) -> usize {
+        2 * (self.bits(PatternAxis::Columns) + self.bits(PatternAxis::Rows))
+    }
+
+    /// Projector images, 1-channel U8 of 0 and 255
+    pub fn generate(&self) -> Result<Vec<Mat>> {
+        let mut patterns = Vec::with_capacity(self.pattern_count());
+        for axis in [PatternAxis::Columns, PatternAxis::Rows] {
+            let bits = self.bits(axis);
+            for bit in (0..bits).rev() {
+                let mut data = vec![0u8; self.width * self.height];
+                for (i, value) in data.iter_mut().enumerate() {
+                    let coord = match axis {
+                        PatternAxis::Columns => i % self.width,
+                        PatternAxis::Rows => i / self.width,
+                    };
+                    if (coord ^ (coord >> 1)) >> bit & 1 == 1 {
+                        *value = 255;
+                    }
+                }
+                let inverse: Vec<u8> = data.iter().map(|v| 255 - v).collect();
+                patterns.push(Mat::from_raw(data, self.height, self.width, 1, MatDepth::U8)?);
+                patterns.push(Mat::from_raw(inverse, self.height, self.width, 1, MatDepth::U8)?);
+            }
+        }
+        Ok(patterns)
+    }
+
+    /// All-white and all-black projector images, captured alongside the
+    /// patterns to mask out shadowed pixels
+    pub fn shadow_mask_images(&self) -> Result<(Mat, Mat)> {
+        Ok((
+            Mat::from_raw(vec![255; self.width * self.height], self.height, self.width, 1, MatDepth::U8)?,
+            Mat::new(self.height, self.width, 1, MatDepth::U8)?,
+        ))
+    }
+
+    /// Decode camera captures of [`generate`](Self::generate)'s patterns,
+    /// in the same order, plus captures of the white and black images
+    ///
+    /// All captures must be 1-channel U8 of the same size. Projector
+    /// coordinates are integer columns and rows.
+    pub fn decode(&self, captured: &[Mat], white: &Mat, black: &Mat) -> Result<CorrespondenceMap> {
+        if captured.len() != self.pattern_count() {
+            return Err(Error::InvalidParameter(format!(
+                "Expected {} captured patterns, got {}",
+                self.pattern_count(),
+                captured.len()
+            )));
+        }
+        let (rows, cols) = (white.rows(), white.cols());
+        for image in captured.iter().chain([white, black]) {
+            check_capture(image, rows, cols)?;
+        }
+
+        let column_bits = self.bits(PatternAxis::Columns);
+        let mut map = CorrespondenceMap::new(rows, cols)?;
+        for i in 0..rows * cols {
+            let (row, col) = (i / cols, i % cols);
+            if white.data()[i].saturating_sub(black.data()[i]) < self.black_threshold {
+                continue;
+            }
+
+            let mut codes = [0usize; 2];
+            let mut reliable = true;
+            for (k, pair) in captured.chunks_exact(2).enumerate() {
+                let (on, off) = (pair[0].data()[i], pair[1].data()[i]);
+                if on.abs_diff(off) < self.white_threshold {
+                    reliable = false;
+                    break;
+                }
+                let code = &mut codes[usize::from(k >= column_bits)];
+                *code = (*code << 1) | usize::from(on > off);
+            }
+            if !reliable {
+                continue;
+            }
+
+            let (x, y) = (gray_to_binary(codes[0]), gray_to_binary(codes[1]));
+            if x < self.width && y < self.height {
+                map.set(row, col, x as f32, y as f32)?;
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// Sinusoidal phase-shift fringes, used to refine a Gray-code decode to
+/// sub-pixel projector coordinates
+///
+/// Image `k` of `steps` is `127.5 + 127.5 * cos(2π x / period - 2π k / steps)`
+/// where `x` is the projector column or row. The wrapped phase pins down the
+/// position within a fringe and the Gray code says which fringe it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseShiftPattern {
+    pub width: usize,
+    pub height: usize,
+    /// Fringe period in projector pixels
+    pub period: f64,
+    /// Number of phase steps, at least 3
+    pub steps: usize,
+    /// Minimum fringe amplitude, in intensity levels, for a pixel to be
+    /// refined
+    pub min_modulation: f64,
+}
+
+impl PhaseShiftPattern {
+    pub fn new(width: usize, height: usize, period: f64) -> Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidParameter(
+                "Projector size must be positive".to_string(),
+            ));
+        }
+        if period.is_nan() || period < 2.0 {
+            return Err(Error::InvalidParameter(
+                "Fringe period must be at least 2 pixels".to_string(),
+            ));
+        }
+        Ok(Self { width, height, period, steps: 4, min_modulation: 5.0 })
+    }
+
+    #[must_use]
+    pub fn with_steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    #[must_use]
+    pub fn with_min_modulation(mut self, min_modulation: f64) -> Self {
+        self.min_modulation = min_modulation;
+        self
+    }
+
+    /// Projector images for `axis`, 1-channel U8
+    pub fn generate(&self, axis: PatternAxis) -> Result<Vec<Mat>> {
+        self.check_steps()?;
+        (0..self.steps)
+            .map(|k| {
+                let shift = TAU * k as f64 / self.steps as f64;
+                let data = (0..self.width * self.height)
+                    .map(|i| {
+                        let coord = match axis {
+                            PatternAxis::Columns => i % self.width,
+                            PatternAxis::Rows => i / self.width,
+                        };
+                        (127.5 + 127.5 * (TAU * coord as f64 / self.period - shift).cos()).round() as u8
+                    })
+                    .collect();
+                Mat::from_raw(data, self.height, self.width, 1, MatDepth::U8)
+            })
+            .collect()
+    }
+
+    /// Wrapped phase in `[0, 2π)` and fringe amplitude per camera pixel,
+    /// both 1-channel F32, from captures of [`generate`](Self::generate)
+    pub fn wrapped_phase(&self, captured: &[Mat]) -> Result<(Mat, Mat)> {
+        self.check_steps()?;
+        if captured.len() != self.steps {
+            return Err(Error::InvalidParameter(format!(
+                "Expected {} captured patterns, got {}",
+                self.steps,
+                captured.len()
+            )));
+        }
+        let (rows, cols) = (captured[0].rows(), captured[0].cols());
+        for image in captured {
+            check_capture(image, rows, cols)?;
+        }
+
+        let shifts: Vec<(f64, f64)> = (0..self.steps)
+            .map(|k| (TAU * k as f64 / self.steps as f64).sin_cos())
+            .collect();
+        let mut phase = Mat::new(rows, cols, 1, MatDepth::F32)?;
+        let mut modulation = Mat::new(rows, cols, 1, MatDepth::F32)?;
+        for i in 0..rows * cols {
+            let (mut s, mut c) = (0.0, 0.0);
+            for (image, (sin, cos)) in captured.iter().zip(&shifts) {
+                let v = f64::from(image.data()[i]);
+                s += v * sin;
+                c += v * cos;
+            }
+            let (row, col) = (i / cols, i % cols);
+            phase.set_f32(row, col, 0, s.atan2(c).rem_euclid(TAU) as f32)?;
+            modulation.set_f32(row, col, 0, (2.0 * s.hypot(c) / self.steps as f64) as f32)?;
+        }
+        Ok((phase, modulation))
+    }
+
+    /// Replace the `axis` coordinate of a Gray-code decode with the
+    /// phase-unwrapped sub-pixel value
+    ///
+    /// Pixels whose fringe amplitude is below `min_modulation` are marked
+    /// invalid. Unwrapping tolerates Gray-code errors of up to half a period.
+    pub fn refine(&self, axis: PatternAxis, captured: &[Mat], coarse: &CorrespondenceMap) -> Result<CorrespondenceMap> {
+        let (phase, modulation) = self.wrapped_phase(captured)?;
+        if phase.rows() != coarse.rows() || phase.cols() != coarse.cols() {
+            return Err(Error::InvalidDimensions(
+                "Captures must match the correspondence map size".to_string(),
+            ));
+        }
+
+        let mut refined = coarse.clone();
+        for row in 0..coarse.rows() {
+            for col in 0..coarse.cols() {
+                let Some(p) = coarse.get(row, col) else { continue };
+                if f64::from(modulation.at_f32(row, col, 0)?) < self.min_modulation {
+                    refined.invalidate(row, col)?;
+                    continue;
+                }
+                let within = f64::from(phase.at_f32(row, col, 0)?) / TAU * self.period;
+                let gray = f64::from(match axis {
+                    PatternAxis::Columns => p.x,
+                    PatternAxis::Rows => p.y,
+                });
+                let fringe = ((gray - within) / self.period).round();
+                let fine = (fringe * self.period + within) as f32;
+                match axis {
+                    PatternAxis::Columns => refined.set(row, col, fine, p.y)?,
+                    PatternAxis::Rows => refined.set(row, col, p.x, fine)?,
+                }
+            }
+        }
+        Ok(refined)
+    }
+
+    fn check_steps(&self) -> Result<()> {
+        if self.steps < 3 {
+            return Err(Error::InvalidParameter(
+                "Phase shifting needs at least 3 steps".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn check_capture(image: &Mat, rows: usize, cols: usize) -> Result<()> {
+    if image.channels() != 1 || image.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "Structured light captures must be 1-channel U8".to_string(),
+        ));
+    }
+    if image.rows() != rows || image.cols() != cols {
+        return Err(Error::InvalidDimensions(
+            "All captures must have the same size".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn gray_to_binary(mut gray: usize) -> usize {
+    let mut binary = gray;
+    while gray > 0 {
+        gray >>= 1;
+        binary ^= gray;
+    }
+    binary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECTOR: (usize, usize) = (200, 120);
+
+    /// Camera capture of a projector image, where camera pixel `(row, col)`
+    /// sees projector point `view(row, col)`; pixels for which `view` returns
+    /// `None` are in shadow
+    fn capture(pattern: &Mat, rows: usize, cols: usize, view: impl Fn(usize, usize) -> Option<(f64, f64)>) -> Mat {
+        let mut image = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                if let Some((x, y)) = view(row, col) {
+                    let v = pattern.at(y.floor() as usize, x.floor() as usize).unwrap()[0];
+                    // Albedo and ambient light
+                    image.at_mut(row, col).unwrap()[0] = (20.0 + 0.7 * f64::from(v)) as u8;
+                }
+            }
+        }
+        image
+    }
+
+    /// Phase-shift capture rendered from the continuous fringe formula
+    fn capture_fringes(pattern: &PhaseShiftPattern, rows: usize, cols: usize, view: impl Fn(usize, usize) -> f64) -> Vec<Mat> {
+        (0..pattern.steps)
+            .map(|k| {
+                let mut image = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+                for row in 0..rows {
+                    for col in 0..cols {
+                        let shift = TAU * k as f64 / pattern.steps as f64;
+                        let v = 127.5 + 127.5 * (TAU * view(row, col) / pattern.period - shift).cos();
+                        image.at_mut(row, col).unwrap()[0] = (20.0 + 0.7 * v).round() as u8;
+                    }
+                }
+                image
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gray_code_decode() {
+        let pattern = GrayCodePattern::new(PROJECTOR.0, PROJECTOR.1).unwrap();
+        assert_eq!(pattern.bits(PatternAxis::Columns), 8);
+        assert_eq!(pattern.bits(PatternAxis::Rows), 7);
+        let images = pattern.generate().unwrap();
+        assert_eq!(images.len(), 30);
+
+        // Camera sees the projector scaled down, with a shadowed band
+        let view = |row: usize, col: usize| (!(10..14).contains(&col)).then_some((col as f64 * 1.5 + 0.25, row as f64 * 1.25 + 0.25));
+        let captured: Vec<Mat> = images.iter().map(|p| capture(p, 80, 120, view)).collect();
+        let (white, black) = pattern.shadow_mask_images().unwrap();
+        let map = pattern
+            .decode(&captured, &capture(&white, 80, 120, view), &capture(&black, 80, 120, view))
+            .unwrap();
+
+        assert_eq!(map.valid_count(), 80 * 116);
+        assert!(map.get(5, 11).is_none());
+        for (row, col) in [(0, 0), (7, 33), (79, 119), (40, 60)] {
+            let (x, y) = view(row, col).unwrap();
+            assert_eq!(map.get(row, col), Some(Point2f::new(x.floor() as f32, y.floor() as f32)));
+        }
+        assert!(pattern.decode(&captured[1..], &white, &black).is_err());
+    }
+
+    #[test]
+    fn test_phase_shift_stereo_disparity() {
+        let (rows, cols) = (24, 160);
+        let gray = GrayCodePattern::new(PROJECTOR.0, PROJECTOR.1).unwrap();
+        let phase = PhaseShiftPattern::new(PROJECTOR.0, PROJECTOR.1, 16.0).unwrap();
+        assert_eq!(phase.generate(PatternAxis::Columns).unwrap().len(), 4);
+
+        // Projector column seen by the left camera; the right camera sees the
+        // same column 12.4 pixels further left
+        let left_u = |_: usize, col: usize| 10.3 + 1.1 * col as f64;
+        let right_u = |row: usize, col: usize| left_u(row, col) + 1.1 * 12.4;
+        let decode = |u: &dyn Fn(usize, usize) -> f64| {
+            let view = |row: usize, col: usize| {
+                let x = u(row, col);
+                (x < PROJECTOR.0 as f64).then_some((x, row as f64 + 0.5))
+            };
+            let captured: Vec<Mat> = gray.generate().unwrap().iter().map(|p| capture(p, rows, cols, view)).collect();
+            let (white, black) = gray.shadow_mask_images().unwrap();
+            let coarse = gray
+                .decode(&captured, &capture(&white, rows, cols, view), &capture(&black, rows, cols, view))
+                .unwrap();
+            let fringes = capture_fringes(&phase, rows, cols, u);
+            phase.refine(PatternAxis::Columns, &fringes, &coarse).unwrap()
+        };
+        let left = decode(&left_u);
+        let right = decode(&right_u);
+
+        let p = left.get(10, 50).unwrap();
+        assert!((f64::from(p.x) - left_u(10, 50)).abs() < 0.1, "{p:?}");
+        assert_eq!(p.y, 10.0);
+
+        let disparity = left.disparity(&right, 0.0, 40.0).unwrap();
+        for col in [20, 64, 150] {
+            let d = disparity.at_f32(12, col, 0).unwrap();
+            assert!((d - 12.4).abs() < 0.1, "col {col}: {d}");
+        }
+        // Left pixels whose match would fall off the right image
+        assert_eq!(disparity.at_f32(12, 5, 0).unwrap(), 0.0);
+    }
+}
@@ -0,0 +1,192 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Compositing operator for [`blend`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Porter-Duff source-over: `src + dst·(1 − αs)`
+    Over,
+    /// Porter-Duff plus: `src + dst`, saturated
+    Add,
+    /// `src·dst` where both are opaque, falling back to plain source-over
+    /// where either is transparent
+    Multiply,
+    /// `src + dst − src·dst`: lightens, never darker than either input
+    Screen,
+}
+
+/// Composite `src` onto `dst` in place
+///
+/// Both images must be 4-channel RGBA with premultiplied alpha and share size
+/// and depth (U8 or F32, where F32 uses the 0–1 range). Use
+/// [`premultiply_alpha`] to prepare straight-alpha sprites and
+/// [`unpremultiply_alpha`] to convert the result back.
+pub fn blend(src: &Mat, dst: &mut Mat, mode: BlendMode) -> Result<()> {
+    if src.channels() != 4 || dst.channels() != 4 {
+        return Err(Error::InvalidParameter(
+            "blend requires 4-channel RGBA images".to_string(),
+        ));
+    }
+
+    if src.rows() != dst.rows() || src.cols() != dst.cols() || src.depth() != dst.depth() {
+        return Err(Error::InvalidDimensions(
+            "Source and destination must have the same size and depth".to_string(),
+        ));
+    }
+
+    let scale = unit_scale(src.depth())?;
+
+    for row in 0..src.rows() {
+        for col in 0..src.cols() {
+            let s = read_rgba(src, row, col, scale)?;
+            let d = read_rgba(dst, row, col, scale)?;
+            let (sa, da) = (s[3], d[3]);
+
+            let out: [f64; 4] = std::array::from_fn(|ch| {
+                let (cs, cd) = (s[ch], d[ch]);
+                let v = match mode {
+                    BlendMode::Over => cs + cd * (1.0 - sa),
+                    BlendMode::Add => cs + cd,
+                    BlendMode::Multiply => cs * cd + cs * (1.0 - da) + cd * (1.0 - sa),
+                    BlendMode::Screen => cs + cd - cs * cd,
+                };
+                v.clamp(0.0, 1.0)
+            });
+
+            write_rgba(dst, row, col, scale, &out)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert straight-alpha RGBA to premultiplied alpha
+pub fn premultiply_alpha(src: &Mat, dst: &mut Mat) -> Result<()> {
+    map_alpha(src, dst, |c, a| c * a)
+}
+
+/// Convert premultiplied RGBA back to straight alpha; fully transparent
+/// pixels become transparent black
+pub fn unpremultiply_alpha(src: &Mat, dst: &mut Mat) -> Result<()> {
+    map_alpha(src, dst, |c, a| if a > 0.0 { (c / a).min(1.0) } else { 0.0 })
+}
+
+fn map_alpha(src: &Mat, dst: &mut Mat, f: impl Fn(f64, f64) -> f64) -> Result<()> {
+    if src.channels() != 4 {
+        return Err(Error::InvalidParameter(
+            "Alpha conversion requires a 4-channel RGBA image".to_string(),
+        ));
+    }
+
+    let scale = unit_scale(src.depth())?;
+    let mut out = Mat::new(src.rows(), src.cols(), 4, src.depth())?;
+
+    for row in 0..src.rows() {
+        for col in 0..src.cols() {
+            let mut px = read_rgba(src, row, col, scale)?;
+            for ch in 0..3 {
+                px[ch] = f(px[ch], px[3]);
+            }
+            write_rgba(&mut out, row, col, scale, &px)?;
+        }
+    }
+
+    *dst = out.with_color_order(src.color_order());
+    Ok(())
+}
+
+/// Value that represents full intensity / full opacity at a depth
+fn unit_scale(depth: MatDepth) -> Result<f64> {
+    match depth {
+        MatDepth::U8 => Ok(255.0),
+        MatDepth::F32 => Ok(1.0),
+        _ => Err(Error::UnsupportedOperation(
+            "Alpha compositing only supports U8 and F32 depth".to_string(),
+        )),
+    }
+}
+
+fn read_rgba(img: &Mat, row: usize, col: usize, scale: f64) -> Result<[f64; 4]> {
+    let mut px = [0.0; 4];
+    if img.depth() == MatDepth::U8 {
+        for (v, &b) in px.iter_mut().zip(img.at(row, col)?) {
+            *v = f64::from(b) / scale;
+        }
+    } else {
+        for (ch, v) in px.iter_mut().enumerate() {
+            *v = f64::from(img.at_f32(row, col, ch)?);
+        }
+    }
+    Ok(px)
+}
+
+fn write_rgba(img: &mut Mat, row: usize, col: usize, scale: f64, px: &[f64; 4]) -> Result<()> {
+    if img.depth() == MatDepth::U8 {
+        for (b, &v) in img.at_mut(row, col)?.iter_mut().zip(px) {
+            *b = (v * scale).round().clamp(0.0, 255.0) as u8;
+        }
+    } else {
+        for (ch, &v) in px.iter().enumerate() {
+            img.set_f32(row, col, ch, v as f32)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(rgba: [u8; 4]) -> Mat {
+        let mut m = Mat::new(1, 1, 4, MatDepth::U8).unwrap();
+        m.at_mut(0, 0).unwrap().copy_from_slice(&rgba);
+        m
+    }
+
+    #[test]
+    fn test_blend_over() {
+        // Half-transparent red (premultiplied) over opaque blue
+        let src = pixel([128, 0, 0, 128]);
+        let mut dst = pixel([0, 0, 255, 255]);
+        blend(&src, &mut dst, BlendMode::Over).unwrap();
+        assert_eq!(dst.at(0, 0).unwrap(), &[128, 0, 127, 255]);
+
+        // Fully transparent source leaves the destination untouched
+        let mut dst = pixel([10, 20, 30, 40]);
+        blend(&pixel([0, 0, 0, 0]), &mut dst, BlendMode::Over).unwrap();
+        assert_eq!(dst.at(0, 0).unwrap(), &[10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_blend_add_multiply_screen() {
+        let src = pixel([200, 100, 0, 255]);
+
+        let mut dst = pixel([100, 100, 100, 255]);
+        blend(&src, &mut dst, BlendMode::Add).unwrap();
+        assert_eq!(dst.at(0, 0).unwrap(), &[255, 200, 100, 255]);
+
+        let mut dst = pixel([255, 128, 100, 255]);
+        blend(&src, &mut dst, BlendMode::Multiply).unwrap();
+        assert_eq!(dst.at(0, 0).unwrap(), &[200, 50, 0, 255]);
+
+        let mut dst = pixel([0, 128, 100, 255]);
+        blend(&src, &mut dst, BlendMode::Screen).unwrap();
+        assert_eq!(dst.at(0, 0).unwrap(), &[200, 178, 100, 255]);
+
+        let mut rgb = Mat::new(1, 1, 3, MatDepth::U8).unwrap();
+        assert!(blend(&src, &mut rgb, BlendMode::Over).is_err());
+    }
+
+    #[test]
+    fn test_premultiply_round_trip() {
+        let straight = pixel([200, 100, 50, 128]);
+        let mut pre = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        premultiply_alpha(&straight, &mut pre).unwrap();
+        assert_eq!(pre.at(0, 0).unwrap(), &[100, 50, 25, 128]);
+
+        let mut back = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        unpremultiply_alpha(&pre, &mut back).unwrap();
+        assert_eq!(back.at(0, 0).unwrap(), &[199, 100, 50, 128]);
+    }
+}
@@ -0,0 +1,70 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+use crate::core::types::BorderType;
+
+/// Map a possibly out-of-range coordinate onto `0..len` following `border`
+///
+/// Returns `None` for [`BorderType::Constant`] outside the image (the caller
+/// substitutes its border value) and for empty axes. Coordinates inside the
+/// image are returned unchanged for every border type.
+#[must_use]
+pub fn border_interpolate(pos: i64, len: usize, border: BorderType) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let n = len as i64;
+    if (0..n).contains(&pos) {
+        return Some(pos as usize);
+    }
+
+    let index = match border {
+        BorderType::Constant => return None,
+        BorderType::Replicate => pos.clamp(0, n - 1),
+        BorderType::Wrap => pos.rem_euclid(n),
+        BorderType::Reflect => {
+            let m = pos.rem_euclid(2 * n);
+            if m < n { m } else { 2 * n - 1 - m }
+        }
+        BorderType::Reflect101 => {
+            if n == 1 {
+                0
+            } else {
+                let period = 2 * (n - 1);
+                let m = pos.rem_euclid(period);
+                if m < n { m } else { period - m }
+            }
+        }
+    };
+    Some(index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extend(border: BorderType) -> Vec<Option<usize>> {
+        (-4..12).map(|p| border_interpolate(p, 8, border)).collect()
+    }
+
+    #[test]
+    fn test_border_interpolate() {
+        let inside: Vec<Option<usize>> = (0..8).map(Some).collect();
+        let with = |left: [usize; 4], right: [usize; 4]| {
+            let mut v: Vec<Option<usize>> = left.iter().copied().map(Some).collect();
+            v.extend(&inside);
+            v.extend(right.iter().copied().map(Some));
+            v
+        };
+
+        assert_eq!(extend(BorderType::Replicate), with([0, 0, 0, 0], [7, 7, 7, 7]));
+        assert_eq!(extend(BorderType::Reflect), with([3, 2, 1, 0], [7, 6, 5, 4]));
+        assert_eq!(extend(BorderType::Reflect101), with([4, 3, 2, 1], [6, 5, 4, 3]));
+        assert_eq!(extend(BorderType::Wrap), with([4, 5, 6, 7], [0, 1, 2, 3]));
+        assert_eq!(border_interpolate(-1, 8, BorderType::Constant), None);
+        assert_eq!(border_interpolate(3, 8, BorderType::Constant), Some(3));
+
+        // Far outside and degenerate axes
+        assert_eq!(border_interpolate(-17, 8, BorderType::Reflect101), Some(3));
+        assert_eq!(border_interpolate(5, 1, BorderType::Reflect101), Some(0));
+        assert_eq!(border_interpolate(0, 0, BorderType::Replicate), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! Content hashing and tolerance-based comparison of Mats
+//!
+//! [`Mat::content_hash`] gives a cheap fingerprint for golden-value
+//! regression tests and caches, and [`assert_mat_eq!`](crate::assert_mat_eq)
+//! compares two Mats element by element, writing a difference image when
+//! they don't match.
+
+use crate::core::mat::{read_element, Mat, MatDepth};
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+const PRIME1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Streaming XXH64
+struct Xxh64 {
+    lanes: [u64; 4],
+    buffer: [u8; 32],
+    buffered: usize,
+    total: u64,
+    seed: u64,
+}
+
+impl Xxh64 {
+    fn new(seed: u64) -> Self {
+        Self {
+            lanes: [
+                seed.wrapping_add(PRIME1).wrapping_add(PRIME2),
+                seed.wrapping_add(PRIME2),
+                seed,
+                seed.wrapping_sub(PRIME1),
+            ],
+            buffer: [0; 32],
+            buffered: 0,
+            total: 0,
+            seed,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        if self.buffered > 0 {
+            let take = (32 - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < 32 {
+                return;
+            }
+            let block = self.buffer;
+            self.consume(&block);
+            self.buffered = 0;
+        }
+        let mut blocks = data.chunks_exact(32);
+        for block in &mut blocks {
+            self.consume(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn consume(&mut self, block: &[u8]) {
+        for (lane, word) in self.lanes.iter_mut().zip(block.chunks_exact(8)) {
+            *lane = round(*lane, read_u64(word));
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hash = if self.total >= 32 {
+            let [v1, v2, v3, v4] = self.lanes;
+            let mut hash = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            for lane in self.lanes {
+                hash = (hash ^ round(0, lane)).wrapping_mul(PRIME1).wrapping_add(PRIME4);
+            }
+            hash
+        } else {
+            self.seed.wrapping_add(PRIME5)
+        };
+        hash = hash.wrapping_add(self.total);
+
+        let mut tail = &self.buffer[..self.buffered];
+        while tail.len() >= 8 {
+            hash ^= round(0, read_u64(&tail[..8]));
+            hash = hash.rotate_left(27).wrapping_mul(PRIME1).wrapping_add(PRIME4);
+            tail = &tail[8..];
+        }
+        if tail.len() >= 4 {
+            let word = u32::from_le_bytes([tail[0], tail[1], tail[2], tail[3]]);
+            hash ^= u64::from(word).wrapping_mul(PRIME1);
+            hash = hash.rotate_left(23).wrapping_mul(PRIME2).wrapping_add(PRIME3);
+            tail = &tail[4..];
+        }
+        for &byte in tail {
+            hash ^= u64::from(byte).wrapping_mul(PRIME5);
+            hash = hash.rotate_left(11).wrapping_mul(PRIME1);
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME3);
+        hash ^ (hash >> 32)
+    }
+}
+
+fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME2)).rotate_left(31).wrapping_mul(PRIME1)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+impl Mat {
+    /// XXH64 of the Mat's shape, depth and pixel data
+    ///
+    /// Pixel data is stored little-endian, so the hash is the same on every
+    /// platform and across runs. Mats with the same bytes but a different
+    /// shape or depth hash differently; F32 `0.0` and `-0.0`, or NaNs with
+    /// different payloads, count as different content.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Xxh64::new(0);
+        // OpenCV depth codes
+        let depth: u64 = match self.depth() {
+            MatDepth::U8 => 0,
+            MatDepth::U16 => 2,
+            MatDepth::S32 => 4,
+            MatDepth::F32 => 5,
+            MatDepth::F64 => 6,
+        };
+        for field in [self.rows() as u64, self.cols() as u64, self.channels() as u64, depth] {
+            hasher.update(&field.to_le_bytes());
+        }
+        hasher.update(self.data());
+        hasher.finish()
+    }
+}
+
+/// Element-wise comparison of two Mats, as made by [`mat_difference`]
+#[derive(Debug, Clone)]
+pub struct MatDifference {
+    /// Largest absolute element difference
+    pub max_difference: f64,
+    /// Elements differing by more than the tolerance
+    pub mismatches: usize,
+    /// `(row, col, channel)` of the first mismatch in storage order
+    pub first_mismatch: Option<(usize, usize, usize)>,
+    /// U8 map of the largest per-pixel channel difference, 0 within
+    /// tolerance and scaled so that `max_difference` is 255
+    pub diff_image: Mat,
+}
+
+impl MatDifference {
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.mismatches == 0
+    }
+}
+
+/// Compare two Mats of the same shape and depth element by element
+///
+/// Elements match when they differ by at most `tolerance`; NaNs match only
+/// NaNs.
+pub fn mat_difference(a: &Mat, b: &Mat, tolerance: f64) -> Result<MatDifference> {
+    if a.rows() != b.rows() || a.cols() != b.cols() || a.channels() != b.channels() {
+        return Err(Error::InvalidDimensions(format!(
+            "Mats differ in shape: {}x{}x{} vs {}x{}x{}",
+            a.rows(),
+            a.cols(),
+            a.channels(),
+            b.rows(),
+            b.cols(),
+            b.channels()
+        )));
+    }
+    if a.depth() != b.depth() {
+        return Err(Error::InvalidParameter(format!(
+            "Mats differ in depth: {:?} vs {:?}",
+            a.depth(),
+            b.depth()
+        )));
+    }
+
+    let (cols, channels) = (a.cols(), a.channels());
+    let elem = a.depth().size();
+    let mut pixel_diffs = vec![0.0f64; a.rows() * cols];
+    let mut max_difference = 0.0f64;
+    let mut mismatches = 0;
+    let mut first_mismatch = None;
+    for (i, (x, y)) in a.data().chunks_exact(elem).zip(b.data().chunks_exact(elem)).enumerate() {
+        let (x, y) = (read_element(x, a.depth()), read_element(y, a.depth()));
+        let diff = match (x.is_nan(), y.is_nan()) {
+            (true, true) => 0.0,
+            _ if x == y => 0.0,
+            (false, false) => (x - y).abs(),
+            _ => f64::INFINITY,
+        };
+        if diff > tolerance {
+            mismatches += 1;
+            first_mismatch.get_or_insert((i / channels / cols, (i / channels) % cols, i % channels));
+        }
+        max_difference = max_difference.max(diff);
+        let pixel = &mut pixel_diffs[i / channels];
+        *pixel = pixel.max(diff);
+    }
+
+    let mut diff_image = Mat::new(a.rows(), cols, 1, MatDepth::U8)?;
+    for (out, &diff) in diff_image.data_mut().iter_mut().zip(&pixel_diffs) {
+        if diff > tolerance {
+            let scaled = if max_difference.is_finite() { diff / max_difference * 255.0 } else { 255.0 };
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            { *out = scaled.round().clamp(1.0, 255.0) as u8; }
+        }
+    }
+
+    Ok(MatDifference { max_difference, mismatches, first_mismatch, diff_image })
+}
+
+/// Directory for [`assert_mat_eq!`](crate::assert_mat_eq) difference images:
+/// `$MAT_DIFF_DIR` if set, the system temporary directory otherwise
+fn diff_directory() -> PathBuf {
+    std::env::var_os("MAT_DIFF_DIR").map_or_else(std::env::temp_dir, PathBuf::from)
+}
+
+/// Implementation of [`assert_mat_eq!`](crate::assert_mat_eq)
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_mat_eq_impl(left: &Mat, right: &Mat, tolerance: f64, left_expr: &str, right_expr: &str) {
+    let difference = match mat_difference(left, right, tolerance) {
+        Ok(difference) if difference.is_match() => return,
+        Ok(difference) => difference,
+        Err(err) => panic!("assertion `{left_expr} == {right_expr}` failed: {err}"),
+    };
+
+    let path = diff_directory().join(format!("mat_diff_{:016x}_{:016x}.png", left.content_hash(), right.content_hash()));
+    let written = write_diff(&path, &difference.diff_image);
+    let (row, col, ch) = difference.first_mismatch.unwrap_or_default();
+    panic!(
+        "assertion `{left_expr} == {right_expr}` failed (tolerance {tolerance}): {} of {} elements differ, \
+         max difference {}, first at (row {row}, col {col}, channel {ch}); {written}",
+        difference.mismatches,
+        left.total() * left.channels(),
+        difference.max_difference,
+    );
+}
+
+fn write_diff(path: &Path, diff: &Mat) -> String {
+    match crate::imgcodecs::imwrite(path, diff) {
+        Ok(()) => format!("difference image written to {}", path.display()),
+        Err(err) => format!("difference image could not be written: {err}"),
+    }
+}
+
+/// Assert that two Mats have the same shape, depth and content
+///
+/// An optional third argument is the largest absolute difference allowed
+/// per element. On failure the panic message reports the number of
+/// differing elements, the largest difference and the first mismatch, and a
+/// PNG of the per-pixel differences is written to `$MAT_DIFF_DIR` (or the
+/// system temporary directory).
+///
+/// ```
+/// use opencv_rust::assert_mat_eq;
+/// use opencv_rust::core::{Mat, MatDepth};
+///
+/// let a = Mat::new(4, 4, 1, MatDepth::U8).unwrap();
+/// let mut b = a.clone();
+/// b.at_mut(1, 2).unwrap()[0] = 1;
+/// assert_mat_eq!(a, b, 1.0);
+/// ```
+#[macro_export]
+macro_rules! assert_mat_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_mat_eq!($left, $right, 0.0)
+    };
+    ($left:expr, $right:expr, $tolerance:expr $(,)?) => {
+        $crate::core::compare::assert_mat_eq_impl(
+            &$left,
+            &$right,
+            f64::from($tolerance),
+            stringify!($left),
+            stringify!($right),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xxh64(data: &[u8]) -> u64 {
+        let mut hasher = Xxh64::new(0);
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_xxh64_reference_values() {
+        assert_eq!(xxh64(b""), 0xEF46_DB37_51D8_E999);
+        assert_eq!(xxh64(b"abc"), 0x44BC_2CF5_AD77_0999);
+        assert_eq!(xxh64(b"Nobody inspects the spammish repetition"), 0xFBCE_A83C_8A37_8BF1);
+
+        // Streaming in uneven pieces matches one-shot hashing
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let mut hasher = Xxh64::new(0);
+        for chunk in data.chunks(13) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finish(), xxh64(&data));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let mut a = Mat::new(4, 6, 1, MatDepth::U8).unwrap();
+        a.at_mut(1, 1).unwrap()[0] = 7;
+        let b = a.clone();
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        a.at_mut(3, 5).unwrap()[0] = 1;
+        assert_ne!(a.content_hash(), b.content_hash());
+
+        // Same bytes, different shape
+        let c = Mat::new(6, 4, 1, MatDepth::U8).unwrap();
+        let d = Mat::new(4, 6, 1, MatDepth::U8).unwrap();
+        assert_ne!(c.content_hash(), d.content_hash());
+    }
+
+    #[test]
+    fn test_mat_difference() {
+        let mut a = Mat::new(3, 4, 2, MatDepth::F32).unwrap();
+        let mut b = a.clone();
+        a.set_f32(2, 1, 1, 0.5).unwrap();
+        b.set_f32(0, 3, 0, 0.05).unwrap();
+
+        let difference = mat_difference(&a, &b, 0.1).unwrap();
+        assert_eq!(difference.mismatches, 1);
+        assert_eq!(difference.first_mismatch, Some((2, 1, 1)));
+        assert!((difference.max_difference - 0.5).abs() < 1e-9);
+        assert_eq!(difference.diff_image.at(2, 1).unwrap()[0], 255);
+        assert_eq!(difference.diff_image.at(0, 3).unwrap()[0], 0);
+
+        assert!(mat_difference(&a, &Mat::new(3, 4, 2, MatDepth::U8).unwrap(), 0.0).is_err());
+        assert_mat_eq!(a, b, 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 12 elements differ")]
+    fn test_assert_mat_eq_reports_mismatch() {
+        let a = Mat::new(3, 4, 1, MatDepth::U8).unwrap();
+        let mut b = a.clone();
+        b.at_mut(1, 1).unwrap()[0] = 9;
+        assert_mat_eq!(a, b);
+    }
+}
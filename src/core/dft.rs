@@ -0,0 +1,400 @@
+#![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Complex number used by the FFT routines
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    #[must_use]
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    #[must_use]
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    #[must_use]
+    pub fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    #[must_use]
+    pub fn scale(self, k: f64) -> Self {
+        Self::new(self.re * k, self.im * k)
+    }
+
+    fn expi(theta: f64) -> Self {
+        Self::new(theta.cos(), theta.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+/// Flags controlling [`dft`], mirroring `OpenCV`'s `DFT_*` constants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DftFlags {
+    /// Compute the inverse transform
+    pub inverse: bool,
+    /// Divide the result by the number of elements
+    pub scale: bool,
+    /// Transform each row independently instead of the full 2D transform
+    pub rows: bool,
+    /// Return only the real part as a single-channel Mat
+    pub real_output: bool,
+}
+
+impl DftFlags {
+    /// Inverse transform with scaling, the usual pair for a forward `dft`
+    #[must_use]
+    pub fn inverse_scaled() -> Self {
+        Self {
+            inverse: true,
+            scale: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Discrete Fourier transform of a 1D or 2D array
+///
+/// `src` is either single-channel real or two-channel complex (re, im) data of
+/// F32 or F64 depth; U8 input is treated as real. The output is two-channel
+/// complex with the same float depth (F32 for U8 input), unless
+/// `flags.real_output` asks for the real part only.
+///
+/// Any size is supported: power-of-two lengths use radix-2 directly and other
+/// lengths go through Bluestein's algorithm, so padding to
+/// [`get_optimal_dft_size`] is only a performance choice.
+pub fn dft(src: &Mat, dst: &mut Mat, flags: DftFlags) -> Result<()> {
+    if src.channels() > 2 {
+        return Err(Error::InvalidParameter(
+            "dft requires a single-channel real or two-channel complex Mat".to_string(),
+        ));
+    }
+
+    let rows = src.rows();
+    let cols = src.cols();
+    let mut data = mat_to_complex(src)?;
+
+    if flags.rows {
+        for row in data.chunks_exact_mut(cols) {
+            fft(row, flags.inverse);
+        }
+    } else {
+        fft_2d(&mut data, rows, cols, flags.inverse);
+    }
+
+    if flags.scale {
+        let k = if flags.rows { 1.0 / cols as f64 } else { 1.0 / (rows * cols) as f64 };
+        for v in &mut data {
+            *v = v.scale(k);
+        }
+    }
+
+    let depth = if src.depth() == MatDepth::F64 { MatDepth::F64 } else { MatDepth::F32 };
+    *dst = complex_to_mat(&data, rows, cols, depth, flags.real_output)?;
+
+    Ok(())
+}
+
+/// Inverse DFT with scaling, so `idft(dft(x)) == x`
+pub fn idft(src: &Mat, dst: &mut Mat) -> Result<()> {
+    dft(src, dst, DftFlags::inverse_scaled())
+}
+
+/// Element-wise product of two complex spectra, optionally conjugating `b`
+///
+/// Multiplying by the conjugate gives cross-correlation instead of convolution.
+pub fn mul_spectrums(a: &Mat, b: &Mat, dst: &mut Mat, conj_b: bool) -> Result<()> {
+    if a.rows() != b.rows() || a.cols() != b.cols() || a.channels() != 2 || b.channels() != 2 {
+        return Err(Error::InvalidParameter(
+            "mul_spectrums requires two complex Mats of the same size".to_string(),
+        ));
+    }
+
+    let sa = mat_to_complex(a)?;
+    let sb = mat_to_complex(b)?;
+    let product: Vec<Complex> = sa
+        .iter()
+        .zip(&sb)
+        .map(|(&x, &y)| if conj_b { x * y.conj() } else { x * y })
+        .collect();
+
+    let depth = if a.depth() == MatDepth::F64 { MatDepth::F64 } else { MatDepth::F32 };
+    *dst = complex_to_mat(&product, a.rows(), a.cols(), depth, false)?;
+
+    Ok(())
+}
+
+/// Smallest size `>= n` that the FFT handles fastest (a power of two)
+#[must_use]
+pub fn get_optimal_dft_size(n: usize) -> usize {
+    n.max(1).next_power_of_two()
+}
+
+/// In-place 2D FFT of a row-major complex buffer (unscaled in both directions)
+pub(crate) fn fft_2d(data: &mut [Complex], rows: usize, cols: usize, inverse: bool) {
+    for row in data.chunks_exact_mut(cols) {
+        fft(row, inverse);
+    }
+
+    let mut column = vec![Complex::default(); rows];
+    for c in 0..cols {
+        for r in 0..rows {
+            column[r] = data[r * cols + c];
+        }
+        fft(&mut column, inverse);
+        for r in 0..rows {
+            data[r * cols + c] = column[r];
+        }
+    }
+}
+
+/// In-place 1D FFT of any length (unscaled)
+pub(crate) fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+
+    if n.is_power_of_two() {
+        fft_radix2(data, inverse);
+    } else {
+        fft_bluestein(data, inverse);
+    }
+}
+
+fn fft_radix2(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let w_len = Complex::expi(sign * 2.0 * PI / len as f64);
+        for start in (0..n).step_by(len) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..len / 2 {
+                let u = data[start + k];
+                let v = data[start + k + len / 2] * w;
+                data[start + k] = u + v;
+                data[start + k + len / 2] = u - v;
+                w = w * w_len;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Chirp-z transform: an arbitrary-length DFT as a power-of-two convolution
+fn fft_bluestein(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { 1.0 } else { -1.0 };
+
+    // w[k] = exp(sign * i * pi * k^2 / n); k^2 is reduced mod 2n to keep precision
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| {
+            let k2 = (k * k) % (2 * n);
+            Complex::expi(sign * PI * k2 as f64 / n as f64)
+        })
+        .collect();
+
+    let mut a = vec![Complex::default(); m];
+    for k in 0..n {
+        a[k] = data[k] * chirp[k];
+    }
+
+    let mut b = vec![Complex::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    fft_radix2(&mut a, false);
+    fft_radix2(&mut b, false);
+    for (x, y) in a.iter_mut().zip(&b) {
+        *x = *x * *y;
+    }
+    fft_radix2(&mut a, true);
+
+    let k = 1.0 / m as f64;
+    for i in 0..n {
+        data[i] = a[i].scale(k) * chirp[i];
+    }
+}
+
+fn mat_to_complex(src: &Mat) -> Result<Vec<Complex>> {
+    let channels = src.channels();
+    let mut out = Vec::with_capacity(src.rows() * src.cols());
+
+    for row in 0..src.rows() {
+        for col in 0..src.cols() {
+            let read = |ch: usize| -> Result<f64> {
+                Ok(match src.depth() {
+                    MatDepth::U8 => f64::from(src.at(row, col)?[ch]),
+                    MatDepth::U16 => f64::from(src.at_u16(row, col, ch)?),
+                    MatDepth::S32 => f64::from(src.at_i32(row, col, ch)?),
+                    MatDepth::F32 => f64::from(src.at_f32(row, col, ch)?),
+                    MatDepth::F64 => src.at_f64(row, col, ch)?,
+                })
+            };
+
+            let re = read(0)?;
+            let im = if channels == 2 { read(1)? } else { 0.0 };
+            out.push(Complex::new(re, im));
+        }
+    }
+
+    Ok(out)
+}
+
+fn complex_to_mat(data: &[Complex], rows: usize, cols: usize, depth: MatDepth, real_only: bool) -> Result<Mat> {
+    let channels = if real_only { 1 } else { 2 };
+    let mut dst = Mat::new(rows, cols, channels, depth)?;
+
+    for (i, v) in data.iter().enumerate() {
+        let (row, col) = (i / cols, i % cols);
+        let parts = [v.re, v.im];
+        for (ch, &p) in parts.iter().take(channels).enumerate() {
+            if depth == MatDepth::F64 {
+                dst.set_f64(row, col, ch, p)?;
+            } else {
+                dst.set_f32(row, col, ch, p as f32)?;
+            }
+        }
+    }
+
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_dft(x: &[Complex]) -> Vec<Complex> {
+        let n = x.len();
+        (0..n)
+            .map(|k| {
+                x.iter().enumerate().fold(Complex::default(), |acc, (t, &v)| {
+                    acc + v * Complex::expi(-2.0 * PI * (k * t) as f64 / n as f64)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_matches_naive_dft() {
+        for n in [1, 2, 5, 8, 12, 17] {
+            let x: Vec<Complex> = (0..n).map(|i| Complex::new(i as f64 * 0.7 - 1.0, (i * i % 5) as f64)).collect();
+            let expected = naive_dft(&x);
+
+            let mut y = x.clone();
+            fft(&mut y, false);
+            for (a, b) in y.iter().zip(&expected) {
+                assert!((*a - *b).norm_sqr() < 1e-16 * (n * n) as f64 + 1e-18, "n = {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_dft_round_trip() {
+        let mut src = Mat::new(6, 10, 1, MatDepth::F32).unwrap();
+        for row in 0..6 {
+            for col in 0..10 {
+                src.set_f32(row, col, 0, (row * 10 + col) as f32 * 0.5).unwrap();
+            }
+        }
+
+        let mut spectrum = Mat::new(1, 1, 1, MatDepth::F32).unwrap();
+        dft(&src, &mut spectrum, DftFlags::default()).unwrap();
+        assert_eq!(spectrum.channels(), 2);
+
+        // DC term is the sum of all samples
+        let sum: f32 = (0..60).map(|i| i as f32 * 0.5).sum();
+        assert!((spectrum.at_f32(0, 0, 0).unwrap() - sum).abs() < 1e-2);
+
+        let mut back = Mat::new(1, 1, 1, MatDepth::F32).unwrap();
+        dft(&spectrum, &mut back, DftFlags { real_output: true, ..DftFlags::inverse_scaled() }).unwrap();
+        assert_eq!(back.channels(), 1);
+        for row in 0..6 {
+            for col in 0..10 {
+                let diff = back.at_f32(row, col, 0).unwrap() - src.at_f32(row, col, 0).unwrap();
+                assert!(diff.abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_spectrums_convolves() {
+        // Circular convolution of an impulse at (0, 1) shifts the signal by one column
+        let mut signal = Mat::new(1, 4, 1, MatDepth::F64).unwrap();
+        for (col, v) in [1.0, 2.0, 3.0, 4.0].iter().enumerate() {
+            signal.set_f64(0, col, 0, *v).unwrap();
+        }
+        let mut impulse = Mat::new(1, 4, 1, MatDepth::F64).unwrap();
+        impulse.set_f64(0, 1, 0, 1.0).unwrap();
+
+        let mut fs = Mat::new(1, 1, 1, MatDepth::F64).unwrap();
+        let mut fi = Mat::new(1, 1, 1, MatDepth::F64).unwrap();
+        dft(&signal, &mut fs, DftFlags::default()).unwrap();
+        dft(&impulse, &mut fi, DftFlags::default()).unwrap();
+
+        let mut product = Mat::new(1, 1, 1, MatDepth::F64).unwrap();
+        mul_spectrums(&fs, &fi, &mut product, false).unwrap();
+
+        let mut out = Mat::new(1, 1, 1, MatDepth::F64).unwrap();
+        idft(&product, &mut out).unwrap();
+
+        let expected = [4.0, 1.0, 2.0, 3.0];
+        for (col, e) in expected.iter().enumerate() {
+            assert!((out.at_f64(0, col, 0).unwrap() - e).abs() < 1e-9);
+        }
+        assert_eq!(get_optimal_dft_size(100), 128);
+    }
+}
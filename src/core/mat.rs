@@ -1,15 +1,17 @@
 use crate::error::{Error, Result};
-use crate::core::types::{Size, Rect, Scalar};
+use crate::core::memory::AllocScope;
+use crate::core::types::{ColorOrder, Size, Rect, Scalar};
 use ndarray::Array3;
 
 /// Matrix type representing an image or general n-dimensional data
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Mat {
     data: Vec<u8>,
     rows: usize,
     cols: usize,
     channels: usize,
     depth: MatDepth,
+    color_order: ColorOrder,
 }
 
 /// Matrix depth (element type)
@@ -17,6 +19,8 @@ pub struct Mat {
 pub enum MatDepth {
     U8,
     U16,
+    /// Signed 32-bit integers, used for label images
+    S32,
     F32,
     F64,
 }
@@ -27,12 +31,24 @@ impl MatDepth {
         match self {
             MatDepth::U8 => 1,
             MatDepth::U16 => 2,
+            MatDepth::S32 => 4,
             MatDepth::F32 => 4,
             MatDepth::F64 => 8,
         }
     }
 }
 
+/// Decode one little-endian element of the given depth
+pub(crate) fn read_element(bytes: &[u8], depth: MatDepth) -> f64 {
+    match depth {
+        MatDepth::U8 => f64::from(bytes[0]),
+        MatDepth::U16 => f64::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+        MatDepth::S32 => f64::from(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        MatDepth::F32 => f64::from(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        MatDepth::F64 => f64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]),
+    }
+}
+
 impl Mat {
     /// Create a new Mat with given dimensions and channels
     ///
@@ -45,7 +61,10 @@ impl Mat {
         }
 
         let total_size = rows * cols * channels * depth.size();
-        let data = vec![0u8; total_size];
+        let data = {
+            let _scope = AllocScope::Core.enter();
+            vec![0u8; total_size]
+        };
 
         Ok(Self {
             data,
@@ -53,6 +72,7 @@ impl Mat {
             cols,
             channels,
             depth,
+            color_order: ColorOrder::Unknown,
         })
     }
 
@@ -63,6 +83,20 @@ impl Mat {
         Self::new_rows_cols(rows, cols, channels, depth)
     }
 
+    /// Give the matrix the requested layout, reallocating only when it
+    /// differs from the current one
+    ///
+    /// Like OpenCV's `Mat::create`: a reused buffer keeps its old contents,
+    /// so callers must overwrite every element. Clears the color order tag.
+    pub fn create(&mut self, rows: usize, cols: usize, channels: usize, depth: MatDepth) -> Result<()> {
+        if self.rows == rows && self.cols == cols && self.channels == channels && self.depth == depth {
+            self.color_order = ColorOrder::Unknown;
+            return Ok(());
+        }
+        *self = Self::new_rows_cols(rows, cols, channels, depth)?;
+        Ok(())
+    }
+
     /// Create a new Mat from Size
     pub fn new_size(size: Size, channels: usize, depth: MatDepth) -> Result<Self> {
         // Convert i32 to usize, treating negative values as 0
@@ -131,6 +165,7 @@ impl Mat {
             cols,
             channels,
             depth,
+            color_order: ColorOrder::Unknown,
         })
     }
 
@@ -192,16 +227,9 @@ impl Mat {
                     pixel[0] = 1;
                     pixel[1] = 0;
                 }
-                MatDepth::F32 => {
-                    let pixel = mat.at_mut(i, i)?;
-                    let bytes = 1.0f32.to_ne_bytes();
-                    pixel[..4].copy_from_slice(&bytes);
-                }
-                MatDepth::F64 => {
-                    let pixel = mat.at_mut(i, i)?;
-                    let bytes = 1.0f64.to_ne_bytes();
-                    pixel[..8].copy_from_slice(&bytes);
-                }
+                MatDepth::S32 => mat.set_i32(i, i, 0, 1)?,
+                MatDepth::F32 => mat.set_f32(i, i, 0, 1.0)?,
+                MatDepth::F64 => mat.set_f64(i, i, 0, 1.0)?,
             }
         }
 
@@ -258,6 +286,24 @@ impl Mat {
         self.depth
     }
 
+    /// Channel order of a color image; kept by `clone`, `roi` and `copy_to`
+    #[must_use]
+    pub fn color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    /// Tag the channel order without touching the pixels
+    pub fn set_color_order(&mut self, order: ColorOrder) {
+        self.color_order = order;
+    }
+
+    /// Builder form of [`set_color_order`](Self::set_color_order)
+    #[must_use]
+    pub fn with_color_order(mut self, order: ColorOrder) -> Self {
+        self.color_order = order;
+        self
+    }
+
     #[must_use] 
     pub fn is_empty(&self) -> bool {
         self.rows == 0 || self.cols == 0
@@ -289,6 +335,7 @@ impl Mat {
         let depth_val = match self.depth {
             MatDepth::U8 => 0,
             MatDepth::U16 => 2,
+            MatDepth::S32 => 4,
             MatDepth::F32 => 5,
             MatDepth::F64 => 6,
         };
@@ -421,6 +468,7 @@ impl Mat {
         }
 
         let mut result = Mat::new_rows_cols(h, w, self.channels, self.depth)?;
+        result.color_order = self.color_order;
 
         for row in 0..h {
             for col in 0..w {
@@ -433,6 +481,23 @@ impl Mat {
         Ok(result)
     }
 
+    /// Clone `rect` grown by `margin` pixels on every side, clipped to the
+    /// matrix, together with the rectangle the clone covers
+    ///
+    /// Neighbourhood operations run on the clone give the same results
+    /// inside `rect` as on the whole matrix, as long as their radius fits in
+    /// the margin.
+    pub fn roi_with_margin(&self, rect: Rect, margin: usize) -> Result<(Mat, Rect)> {
+        let bounds = Rect::new(0, 0, i32::try_from(self.cols).unwrap_or(i32::MAX), i32::try_from(self.rows).unwrap_or(i32::MAX));
+        if rect.width <= 0 || rect.height <= 0 || rect.intersection(&bounds) != Some(rect) {
+            return Err(Error::OutOfRange("ROI must be non-empty and inside the matrix".to_string()));
+        }
+
+        let margin = i32::try_from(margin.min(self.rows.max(self.cols))).unwrap_or(0);
+        let grown = rect.inflate(margin).intersection(&bounds).unwrap_or(rect);
+        Ok((self.roi(grown)?, grown))
+    }
+
     /// Create a mutable region of interest (ROI)
     /// Note: This returns a new Mat, not a view, as we don't support shared mutable views
     pub fn roi_mut(&mut self, rect: Rect) -> Result<Mat> {
@@ -470,18 +535,21 @@ impl Mat {
         }
 
         dst.data.copy_from_slice(&self.data);
+        dst.color_order = self.color_order;
         Ok(())
     }
 
     /// Clone the matrix
     #[must_use] 
     pub fn clone_mat(&self) -> Mat {
+        let _scope = AllocScope::Core.enter();
         Self {
             data: self.data.clone(),
             rows: self.rows,
             cols: self.cols,
             channels: self.channels,
             depth: self.depth,
+            color_order: self.color_order,
         }
     }
 
@@ -510,6 +578,13 @@ impl Mat {
     }
 }
 
+/// Copies are counted under [`AllocScope::Core`] like [`Mat::clone_mat`]
+impl Clone for Mat {
+    fn clone(&self) -> Self {
+        self.clone_mat()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,10 +141,56 @@ impl Mat {
         Ok(())
     }
 
+    /// Get i32 value at (row, col, channel)
+    pub fn at_i32(&self, row: usize, col: usize, channel: usize) -> Result<i32> {
+        if self.depth() != crate::core::MatDepth::S32 {
+            return Err(Error::InvalidParameter(
+                format!("Mat depth is {:?}, expected S32", self.depth())
+            ));
+        }
+
+        if row >= self.rows() || col >= self.cols() || channel >= self.channels() {
+            return Err(Error::OutOfRange(format!(
+                "Index ({row}, {col}, {channel}) out of range"
+            )));
+        }
+
+        let idx = (row * self.cols() + col) * self.channels() + channel;
+        let byte_idx = idx * 4;
+
+        let bytes = &self.data()[byte_idx..byte_idx + 4];
+        let value = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        Ok(value)
+    }
+
+    /// Set i32 value at (row, col, channel)
+    pub fn set_i32(&mut self, row: usize, col: usize, channel: usize, value: i32) -> Result<()> {
+        if self.depth() != crate::core::MatDepth::S32 {
+            return Err(Error::InvalidParameter(
+                format!("Mat depth is {:?}, expected S32", self.depth())
+            ));
+        }
+
+        if row >= self.rows() || col >= self.cols() || channel >= self.channels() {
+            return Err(Error::OutOfRange(format!(
+                "Index ({row}, {col}, {channel}) out of range"
+            )));
+        }
+
+        let idx = (row * self.cols() + col) * self.channels() + channel;
+        let byte_idx = idx * 4;
+
+        let bytes = value.to_le_bytes();
+        let data = self.data_mut();
+        data[byte_idx..byte_idx + 4].copy_from_slice(&bytes);
+        Ok(())
+    }
+
     /// Convert Mat from one depth to another
     /// Normalizes between integer and floating-point types:
     /// - U8/U16 → F32/F64: divides by max value (255 or 65535)
     /// - F32/F64 → U8/U16: multiplies by max value (255 or 65535) and rounds
+    /// - S32 labels are not normalized: values are converted as-is
     pub fn convert_to(&self, target_depth: crate::core::MatDepth) -> Result<Mat> {
         if self.depth() == target_depth {
             return Ok(self.clone_mat());
@@ -164,6 +210,9 @@ impl Mat {
                         MatDepth::U16 => {
                             f64::from(self.at_u16(row, col, ch)?) / 65535.0
                         }
+                        MatDepth::S32 => {
+                            f64::from(self.at_i32(row, col, ch)?)
+                        }
                         MatDepth::F32 => {
                             f64::from(self.at_f32(row, col, ch)?)
                         }
@@ -191,6 +240,12 @@ impl Mat {
                             let u16_val = scaled as u16;
                             result.set_u16(row, col, ch, u16_val)?;
                         }
+                        MatDepth::S32 => {
+                            // Saturating float-to-int cast
+                            #[allow(clippy::cast_possible_truncation)]
+                            let i32_val = normalized_value.round() as i32;
+                            result.set_i32(row, col, ch, i32_val)?;
+                        }
                         MatDepth::F32 => {
                             // Acceptable precision loss when converting f64 to f32
                             #[allow(clippy::cast_possible_truncation)]
@@ -234,6 +289,15 @@ mod tests {
         assert!((val - 2.718281828).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_i32_accessors() {
+        let mut mat = Mat::new(4, 4, 1, MatDepth::S32).unwrap();
+
+        mat.set_i32(1, 2, 0, -70000).unwrap();
+        assert_eq!(mat.at_i32(1, 2, 0).unwrap(), -70000);
+        assert!(mat.at_f32(1, 2, 0).is_err());
+    }
+
     #[test]
     fn test_u16_accessors() {
         let mut mat = Mat::new(5, 5, 1, MatDepth::U16).unwrap();
@@ -0,0 +1,324 @@
+//! Heap allocation accounting
+//!
+//! [`TrackingAllocator`] wraps a global allocator and counts live and peak
+//! heap bytes per [`AllocScope`]: Mat buffers (`Core`), work dispatched to
+//! the GPU backend (`Gpu`), the WebAssembly binding glue (`Wasm`) and
+//! everything else (`Other`). It lets a browser app see which part of the
+//! library grows the heap before it runs out of its linear memory.
+//!
+//! Install it in the final binary, around the system allocator or any other:
+//!
+//! ```rust,ignore
+//! #[global_allocator]
+//! static ALLOC: opencv_rust::core::TrackingAllocator = opencv_rust::core::TrackingAllocator::system();
+//! ```
+//!
+//! or build with the `alloc-tracking` feature, which installs it over the
+//! system allocator (the way to get it in a `wasm-pack` build of this crate).
+//! [`allocation_stats`] reads the counters, from JavaScript through
+//! `allocationStats()`.
+//!
+//! Scopes are per thread and nest; the innermost wins, so a Mat created
+//! inside a binding counts as `Core`. An async task suspended inside a scope
+//! leaves it active for whatever else runs on the thread until it resumes.
+//! A memory block stays attributed to the scope that allocated it, and each
+//! costs its alignment (at least one byte) in bookkeeping.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// What an allocation is attributed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+pub enum AllocScope {
+    /// Anything outside the scopes below, including the application
+    #[default]
+    Other = 0,
+    /// Mat pixel buffers
+    Core = 1,
+    /// Work running against the GPU context
+    Gpu = 2,
+    /// JavaScript-facing conversions in the WASM bindings
+    Wasm = 3,
+}
+
+const SCOPES: usize = 4;
+
+impl AllocScope {
+    pub const ALL: [AllocScope; SCOPES] = [AllocScope::Other, AllocScope::Core, AllocScope::Gpu, AllocScope::Wasm];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            AllocScope::Other => "other",
+            AllocScope::Core => "core",
+            AllocScope::Gpu => "gpu",
+            AllocScope::Wasm => "wasm",
+        }
+    }
+
+    /// The scope allocations on this thread are attributed to
+    #[must_use]
+    pub fn current() -> AllocScope {
+        CURRENT_SCOPE.try_with(Cell::get).unwrap_or_default()
+    }
+
+    /// Attribute this thread's allocations to `self` until the guard drops
+    pub fn enter(self) -> ScopeGuard {
+        let previous = CURRENT_SCOPE.try_with(|scope| scope.replace(self)).unwrap_or_default();
+        ScopeGuard { previous }
+    }
+
+    fn from_tag(tag: u8) -> AllocScope {
+        match tag {
+            1 => AllocScope::Core,
+            2 => AllocScope::Gpu,
+            3 => AllocScope::Wasm,
+            _ => AllocScope::Other,
+        }
+    }
+}
+
+/// Restores the previous [`AllocScope`] when dropped
+#[must_use = "the scope ends when the guard is dropped"]
+#[derive(Debug)]
+pub struct ScopeGuard {
+    previous: AllocScope,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let _ = CURRENT_SCOPE.try_with(|scope| scope.set(self.previous));
+    }
+}
+
+thread_local! {
+    static CURRENT_SCOPE: Cell<AllocScope> = const { Cell::new(AllocScope::Other) };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static LIVE_BYTES: [AtomicUsize; SCOPES] = [const { AtomicUsize::new(0) }; SCOPES];
+static PEAK_BYTES: [AtomicUsize; SCOPES] = [const { AtomicUsize::new(0) }; SCOPES];
+static ALLOCATIONS: [AtomicUsize; SCOPES] = [const { AtomicUsize::new(0) }; SCOPES];
+static DEALLOCATIONS: [AtomicUsize; SCOPES] = [const { AtomicUsize::new(0) }; SCOPES];
+
+/// Counters for one [`AllocScope`]; byte counts exclude bookkeeping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScopeStats {
+    /// Bytes currently allocated
+    pub live_bytes: usize,
+    /// Highest `live_bytes` since start or the last [`reset_peaks`]
+    pub peak_bytes: usize,
+    pub allocations: usize,
+    pub deallocations: usize,
+}
+
+/// Snapshot of the allocation counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocationStats {
+    /// Whether a [`TrackingAllocator`] is installed and has seen allocations;
+    /// all counters are zero otherwise
+    pub tracking: bool,
+    scopes: [ScopeStats; SCOPES],
+}
+
+impl AllocationStats {
+    #[must_use]
+    pub fn scope(&self, scope: AllocScope) -> ScopeStats {
+        self.scopes[scope as usize]
+    }
+
+    /// Live bytes over all scopes
+    #[must_use]
+    pub fn live_bytes(&self) -> usize {
+        self.scopes.iter().map(|s| s.live_bytes).sum()
+    }
+}
+
+/// Current allocation counters
+#[must_use]
+pub fn allocation_stats() -> AllocationStats {
+    let mut scopes = [ScopeStats::default(); SCOPES];
+    for (i, stats) in scopes.iter_mut().enumerate() {
+        *stats = ScopeStats {
+            live_bytes: LIVE_BYTES[i].load(Ordering::Relaxed),
+            peak_bytes: PEAK_BYTES[i].load(Ordering::Relaxed),
+            allocations: ALLOCATIONS[i].load(Ordering::Relaxed),
+            deallocations: DEALLOCATIONS[i].load(Ordering::Relaxed),
+        };
+    }
+    AllocationStats { tracking: INSTALLED.load(Ordering::Relaxed), scopes }
+}
+
+/// Restart peak tracking from the current live bytes, e.g. before
+/// measuring one frame of a pipeline
+pub fn reset_peaks() {
+    for i in 0..SCOPES {
+        PEAK_BYTES[i].store(LIVE_BYTES[i].load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
+
+fn record_grow(scope: AllocScope, bytes: usize) {
+    let i = scope as usize;
+    let live = LIVE_BYTES[i].fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK_BYTES[i].fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_shrink(scope: AllocScope, bytes: usize) {
+    LIVE_BYTES[scope as usize].fetch_sub(bytes, Ordering::Relaxed);
+}
+
+/// Layout with room for the scope tag in front, and the offset of the
+/// caller's block within it
+fn with_tag(layout: Layout) -> Option<(Layout, usize)> {
+    // One alignment unit keeps the caller's block aligned; the tag is its last byte
+    let offset = layout.align();
+    let outer = Layout::from_size_align(layout.size().checked_add(offset)?, layout.align()).ok()?;
+    Some((outer, offset))
+}
+
+/// A [`GlobalAlloc`] that counts allocations per [`AllocScope`] and
+/// delegates to another allocator
+#[derive(Debug, Default)]
+pub struct TrackingAllocator<A = System> {
+    inner: A,
+}
+
+impl TrackingAllocator<System> {
+    /// Tracking over the system allocator
+    #[must_use]
+    pub const fn system() -> Self {
+        Self { inner: System }
+    }
+}
+
+impl<A> TrackingAllocator<A> {
+    /// Tracking over a custom allocator, e.g. a size-optimised WASM one
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped allocator
+    pub const fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Tag the block at `base` with the current scope and return the
+    /// caller's pointer
+    ///
+    /// # Safety
+    /// `base` must be null or a block of at least `offset` bytes.
+    unsafe fn finish_alloc(base: *mut u8, offset: usize, size: usize) -> *mut u8 {
+        if base.is_null() {
+            return base;
+        }
+        let scope = AllocScope::current();
+        INSTALLED.store(true, Ordering::Relaxed);
+        ALLOCATIONS[scope as usize].fetch_add(1, Ordering::Relaxed);
+        record_grow(scope, size);
+        // SAFETY: the block is at least `offset` bytes long
+        unsafe {
+            base.add(offset - 1).write(scope as u8);
+            base.add(offset)
+        }
+    }
+}
+
+// SAFETY: every block handed out is `offset` bytes into a block of the inner
+// allocator with the same alignment, and is returned to it with the same
+// outer layout.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some((outer, offset)) = with_tag(layout) else {
+            return std::ptr::null_mut();
+        };
+        // SAFETY: `outer` is at least as large as the non-zero `layout`
+        unsafe { Self::finish_alloc(self.inner.alloc(outer), offset, layout.size()) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let Some((outer, offset)) = with_tag(layout) else {
+            return std::ptr::null_mut();
+        };
+        // SAFETY: as in `alloc`
+        unsafe { Self::finish_alloc(self.inner.alloc_zeroed(outer), offset, layout.size()) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // The layout was valid when the block was allocated
+        let Some((outer, offset)) = with_tag(layout) else {
+            return;
+        };
+        // SAFETY: `ptr` came from `alloc`, `offset` bytes into the inner block
+        let (base, tag) = unsafe { (ptr.sub(offset), ptr.sub(1).read()) };
+        let scope = AllocScope::from_tag(tag);
+        DEALLOCATIONS[scope as usize].fetch_add(1, Ordering::Relaxed);
+        record_shrink(scope, layout.size());
+        // SAFETY: `base` was allocated by the inner allocator with `outer`
+        unsafe { self.inner.dealloc(base, outer) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let Some((outer, offset)) = with_tag(layout) else {
+            return std::ptr::null_mut();
+        };
+        let Some(new_outer) = new_size.checked_add(offset).filter(|&n| Layout::from_size_align(n, layout.align()).is_ok()) else {
+            return std::ptr::null_mut();
+        };
+        // SAFETY: as in `dealloc`; the tag moves with the block
+        let (base, tag) = unsafe { (ptr.sub(offset), ptr.sub(1).read()) };
+        let grown = unsafe { self.inner.realloc(base, outer, new_outer) };
+        if grown.is_null() {
+            return grown;
+        }
+        let scope = AllocScope::from_tag(tag);
+        if new_size >= layout.size() {
+            record_grow(scope, new_size - layout.size());
+        } else {
+            record_shrink(scope, layout.size() - new_size);
+        }
+        // SAFETY: the new block is `new_outer` bytes long
+        unsafe { grown.add(offset) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_guards_nest() {
+        assert_eq!(AllocScope::current(), AllocScope::Other);
+        {
+            let _wasm = AllocScope::Wasm.enter();
+            {
+                let _core = AllocScope::Core.enter();
+                assert_eq!(AllocScope::current(), AllocScope::Core);
+            }
+            assert_eq!(AllocScope::current(), AllocScope::Wasm);
+        }
+        assert_eq!(AllocScope::current(), AllocScope::Other);
+        assert!(AllocScope::ALL.iter().all(|&s| AllocScope::from_tag(s as u8) == s));
+    }
+
+    #[test]
+    fn test_tracking_allocator_round_trip() {
+        // Exercised directly, not as the global allocator of the test binary
+        let alloc = TrackingAllocator::system();
+        let layout = Layout::from_size_align(100, 16).unwrap();
+        let _gpu = AllocScope::Gpu.enter();
+        unsafe {
+            let ptr = alloc.alloc_zeroed(layout);
+            assert!(!ptr.is_null() && (ptr as usize).is_multiple_of(16));
+            assert!(std::slice::from_raw_parts(ptr, 100).iter().all(|&b| b == 0));
+            ptr.write_bytes(7, 100);
+
+            let grown = alloc.realloc(ptr, layout, 300);
+            assert!(std::slice::from_raw_parts(grown, 100).iter().all(|&b| b == 7));
+            assert_eq!(grown.sub(1).read(), AllocScope::Gpu as u8);
+            alloc.dealloc(grown, Layout::from_size_align(300, 16).unwrap());
+        }
+        assert!(allocation_stats().tracking);
+    }
+}
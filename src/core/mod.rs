@@ -2,7 +2,23 @@ pub mod mat;
 pub mod mat_typed;
 pub mod types;
 pub mod operations;
+pub mod dft;
+pub mod blend;
+pub mod parallel;
+pub mod pointcloud;
+pub mod border;
+pub mod compare;
+pub mod planar;
+pub mod memory;
 
 pub use mat::{Mat, MatDepth};
 pub use types::*;
 pub use operations::*;
+pub use blend::*;
+pub use pointcloud::{PlyFormat, PointCloud};
+pub use border::border_interpolate;
+pub use compare::{mat_difference, MatDifference};
+pub use planar::PlanarMat;
+pub use memory::{allocation_stats, reset_peaks, AllocScope, AllocationStats, ScopeGuard, ScopeStats, TrackingAllocator};
+pub use parallel::{is_single_threaded, set_single_threaded};
+pub use dft::{dft, idft, mul_spectrums, get_optimal_dft_size, Complex, DftFlags};
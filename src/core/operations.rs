@@ -22,7 +22,7 @@ pub fn add(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -53,7 +53,7 @@ pub fn subtract(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -78,7 +78,7 @@ pub fn multiply(src1: &Mat, src2: &Mat, dst: &mut Mat, scale: f64) -> Result<()>
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -107,7 +107,7 @@ pub fn bitwise_and(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -132,7 +132,7 @@ pub fn bitwise_or(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -157,7 +157,7 @@ pub fn bitwise_xor(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -176,7 +176,7 @@ pub fn bitwise_xor(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
 
 /// Bitwise NOT operation
 pub fn bitwise_not(src: &Mat, dst: &mut Mat) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -317,7 +317,7 @@ pub fn abs_diff(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -366,7 +366,7 @@ pub fn lut(src: &Mat, lut_table: &Mat, dst: &mut Mat) -> Result<()> {
         )));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let lut_data = lut_table.data();
 
@@ -416,7 +416,8 @@ pub fn normalize(src: &Mat, dst: &mut Mat, alpha: f64, beta: f64) -> Result<()>
         }
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    dst.create(src.rows(), src.cols(), src.channels(), src.depth())?;
+    dst.set_color_order(src.color_order());
 
     // Avoid division by zero
     if (max_val - min_val).abs() < 1e-10 {
@@ -472,7 +473,7 @@ pub fn add_weighted(
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -495,7 +496,7 @@ pub fn add_weighted(
 
 /// Convert scale absolute - scales, calculates absolute values and converts result
 pub fn convert_scale_abs(src: &Mat, dst: &mut Mat, alpha: f64, beta: f64) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -517,7 +518,7 @@ pub fn convert_scale_abs(src: &Mat, dst: &mut Mat, alpha: f64, beta: f64) -> Res
 
 /// Calculate exponential of every array element
 pub fn exp(src: &Mat, dst: &mut Mat) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -539,7 +540,7 @@ pub fn exp(src: &Mat, dst: &mut Mat) -> Result<()> {
 
 /// Calculate natural logarithm of every array element
 pub fn log(src: &Mat, dst: &mut Mat) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -562,7 +563,7 @@ pub fn log(src: &Mat, dst: &mut Mat) -> Result<()> {
 
 /// Raise every array element to a power
 pub fn pow(src: &Mat, power: f64, dst: &mut Mat) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -584,7 +585,7 @@ pub fn pow(src: &Mat, power: f64, dst: &mut Mat) -> Result<()> {
 
 /// Calculate square root of every array element
 pub fn sqrt(src: &Mat, dst: &mut Mat) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -618,7 +619,7 @@ pub fn min(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -649,7 +650,7 @@ pub fn max(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     for row in 0..src1.rows() {
         for col in 0..src1.cols() {
@@ -666,36 +667,92 @@ pub fn max(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Result<()> {
     Ok(())
 }
 
-/// Check if array elements lie between elements of two other arrays
+/// Check if array elements lie between the elements of two scalars
+///
+/// Bounds are inclusive. Works on any depth; the output is a single-channel
+/// U8 mask. See [`in_range_any`] for hue wrap-around and unions of ranges.
 pub fn in_range(src: &Mat, dst: &mut Mat, lower_bound: Scalar, upper_bound: Scalar) -> Result<()> {
-    if src.depth() != MatDepth::U8 {
-        return Err(Error::UnsupportedOperation(
-            "in_range only supports U8 depth".to_string(),
+    in_range_any(src, dst, &[ColorRange::new(lower_bound, upper_bound)])
+}
+
+/// Inclusive per-channel bounds, with optional wrap-around on the first channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorRange {
+    pub lower: Scalar,
+    pub upper: Scalar,
+    /// Period of channel 0 when it is a hue angle. With a period set, a lower
+    /// bound above the upper bound selects the range that wraps through 0.
+    pub hue_period: Option<f64>,
+}
+
+impl ColorRange {
+    #[must_use]
+    pub fn new(lower: Scalar, upper: Scalar) -> Self {
+        Self { lower, upper, hue_period: None }
+    }
+
+    /// Range in 8-bit HSV as produced by `cvt_color` (hue in 0–180), so that
+    /// e.g. a hue range of 170–10 selects reds on both sides of 0
+    #[must_use]
+    pub fn hsv(lower: Scalar, upper: Scalar) -> Self {
+        Self { lower, upper, hue_period: Some(180.0) }
+    }
+
+    #[must_use]
+    pub fn with_hue_period(mut self, period: f64) -> Self {
+        self.hue_period = Some(period);
+        self
+    }
+
+    /// Whether a pixel (one value per channel, at most 4 used) is in range
+    #[must_use]
+    pub fn contains(&self, pixel: &[f64]) -> bool {
+        pixel.iter().take(4).enumerate().all(|(ch, &v)| {
+            let (lo, hi) = (self.lower.val[ch], self.upper.val[ch]);
+            match self.hue_period {
+                Some(period) if ch == 0 && lo > hi => {
+                    let v = v.rem_euclid(period);
+                    v >= lo || v <= hi
+                }
+                _ => v >= lo && v <= hi,
+            }
+        })
+    }
+}
+
+/// Mask of pixels inside any of the given ranges
+///
+/// The union lets one call express colours that need several boxes, such as
+/// red in HSV or a skin model made of a few clusters. Works on any depth and
+/// channel count; the output is a single-channel U8 mask (255 inside).
+pub fn in_range_any(src: &Mat, dst: &mut Mat, ranges: &[ColorRange]) -> Result<()> {
+    if ranges.is_empty() {
+        return Err(Error::InvalidParameter(
+            "in_range_any needs at least one range".to_string(),
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
+    let mut mask = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
+    let mut pixel = vec![0.0f64; src.channels()];
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
-            let src_pixel = src.at(row, col)?;
-            let dst_pixel = dst.at_mut(row, col)?;
-
-            let mut in_range = true;
-            for ch in 0..src.channels().min(4) {
-                let val = f64::from(src_pixel[ch]);
-                let lower = lower_bound.val[ch];
-                let upper = upper_bound.val[ch];
-                if val < lower || val > upper {
-                    in_range = false;
-                    break;
-                }
+            for (ch, v) in pixel.iter_mut().enumerate() {
+                *v = match src.depth() {
+                    MatDepth::U8 => f64::from(src.at(row, col)?[ch]),
+                    MatDepth::U16 => f64::from(src.at_u16(row, col, ch)?),
+                    MatDepth::S32 => f64::from(src.at_i32(row, col, ch)?),
+                    MatDepth::F32 => f64::from(src.at_f32(row, col, ch)?),
+                    MatDepth::F64 => src.at_f64(row, col, ch)?,
+                };
             }
 
-            dst_pixel[0] = if in_range { 255 } else { 0 };
+            let inside = ranges.iter().any(|r| r.contains(&pixel));
+            mask.at_mut(row, col)?[0] = if inside { 255 } else { 0 };
         }
     }
 
+    *dst = mask;
     Ok(())
 }
 
@@ -727,4 +784,39 @@ mod tests {
 
         assert_eq!(dst.channels(), 3);
     }
+
+    #[test]
+    fn test_in_range_hue_wrap_and_union() {
+        // Hues 175, 5, 90 and 30 at full saturation/value
+        let mut hsv = Mat::new(1, 4, 3, MatDepth::U8).unwrap();
+        for (col, hue) in [175u8, 5, 90, 30].iter().enumerate() {
+            hsv.at_mut(0, col).unwrap().copy_from_slice(&[*hue, 255, 255]);
+        }
+
+        let mut mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let red = ColorRange::hsv(Scalar::new(170.0, 100.0, 100.0, 0.0), Scalar::new(10.0, 255.0, 255.0, 0.0));
+        in_range_any(&hsv, &mut mask, &[red]).unwrap();
+        assert_eq!(mask.data(), &[255, 255, 0, 0]);
+
+        // Without a hue period the same bounds select nothing
+        in_range(&hsv, &mut mask, red.lower, red.upper).unwrap();
+        assert_eq!(mask.data(), &[0, 0, 0, 0]);
+
+        let yellow = ColorRange::hsv(Scalar::new(25.0, 100.0, 100.0, 0.0), Scalar::new(35.0, 255.0, 255.0, 0.0));
+        in_range_any(&hsv, &mut mask, &[red, yellow]).unwrap();
+        assert_eq!(mask.data(), &[255, 255, 0, 255]);
+
+        assert!(in_range_any(&hsv, &mut mask, &[]).is_err());
+    }
+
+    #[test]
+    fn test_in_range_float_depth() {
+        let mut src = Mat::new(1, 3, 1, MatDepth::F32).unwrap();
+        for (col, v) in [0.1f32, 0.25, 0.5].iter().enumerate() {
+            src.set_f32(0, col, 0, *v).unwrap();
+        }
+        let mut mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        in_range(&src, &mut mask, Scalar::all(0.2), Scalar::all(0.5)).unwrap();
+        assert_eq!(mask.data(), &[0, 255, 255]);
+    }
 }
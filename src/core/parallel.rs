@@ -0,0 +1,219 @@
+//! Row and tile scheduling for per-pixel loops
+//!
+//! Heavy filters route their outer loop through [`parallel_for_rows`],
+//! [`parallel_map`] or [`parallel_for_tiles`] instead of calling rayon
+//! directly. Work is spread over the rayon pool when the `rayon` feature is
+//! enabled, and runs serially otherwise, on WASM until the thread pool has
+//! been initialized, or after [`set_single_threaded`]. Results are always
+//! combined in row / tile order, so both modes produce the same output.
+
+use crate::error::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+static SINGLE_THREADED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_arch = "wasm32")]
+static WASM_POOL_READY: AtomicBool = AtomicBool::new(false);
+
+/// Force every scheduled loop to run on the calling thread
+///
+/// Useful for reproducible profiling and for debugging, since errors and
+/// panics then surface in row order.
+pub fn set_single_threaded(enabled: bool) {
+    SINGLE_THREADED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`set_single_threaded`] is in effect
+pub fn is_single_threaded() -> bool {
+    SINGLE_THREADED.load(Ordering::Relaxed)
+}
+
+/// Record that the WASM rayon pool exists, enabling parallel scheduling
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn set_thread_pool_ready() {
+    WASM_POOL_READY.store(true, Ordering::Relaxed);
+}
+
+#[cfg(feature = "rayon")]
+fn use_threads() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    if !WASM_POOL_READY.load(Ordering::Relaxed) {
+        return false;
+    }
+    !is_single_threaded()
+}
+
+/// A rectangular block of rows and columns handed to [`parallel_for_tiles`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub row: usize,
+    pub col: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+/// Run `f(row, row_slice)` for every `row_len`-long row of `data`
+///
+/// Returns the first error in row order.
+pub(crate) fn parallel_for_rows<T, F>(data: &mut [T], row_len: usize, f: F) -> Result<()>
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) -> Result<()> + Sync + Send,
+{
+    if row_len == 0 {
+        return Ok(());
+    }
+
+    #[cfg(feature = "rayon")]
+    if use_threads() {
+        let results: Vec<Result<()>> = data
+            .par_chunks_mut(row_len)
+            .enumerate()
+            .map(|(row, chunk)| f(row, chunk))
+            .collect();
+        return results.into_iter().collect();
+    }
+
+    for (row, chunk) in data.chunks_mut(row_len).enumerate() {
+        f(row, chunk)?;
+    }
+    Ok(())
+}
+
+/// Evaluate `f(i)` for `i` in `0..count`, returning the results in order
+pub(crate) fn parallel_map<R, F>(count: usize, f: F) -> Result<Vec<R>>
+where
+    R: Send,
+    F: Fn(usize) -> Result<R> + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    if use_threads() {
+        return (0..count).into_par_iter().map(f).collect();
+    }
+
+    (0..count).map(f).collect()
+}
+
+/// Fold `f(acc, row)` over `0..rows` into accumulators made by `init` and
+/// combine them with `merge`
+///
+/// For vote tables and histograms: one accumulator is made per worker
+/// rather than per row or band. `merge` must be associative and commutative
+/// for both modes to agree; an error from any row is returned, not
+/// necessarily the first.
+pub(crate) fn parallel_fold_rows<A, I, F, M>(rows: usize, init: I, f: F, merge: M) -> Result<A>
+where
+    A: Send,
+    I: Fn() -> A + Sync + Send,
+    F: Fn(&mut A, usize) -> Result<()> + Sync + Send,
+    M: Fn(A, A) -> A + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    if use_threads() {
+        return (0..rows)
+            .into_par_iter()
+            .try_fold(&init, |mut acc, row| {
+                f(&mut acc, row)?;
+                Ok(acc)
+            })
+            .try_reduce(&init, |a, b| Ok(merge(a, b)));
+    }
+
+    let mut acc = init();
+    for row in 0..rows {
+        f(&mut acc, row)?;
+    }
+    Ok(acc)
+}
+
+/// Split a `rows` x `cols` area into tiles of at most `tile_height` x
+/// `tile_width` and evaluate `f` on each, returning the results in raster
+/// order
+pub(crate) fn parallel_for_tiles<R, F>(
+    rows: usize,
+    cols: usize,
+    tile_height: usize,
+    tile_width: usize,
+    f: F,
+) -> Result<Vec<R>>
+where
+    R: Send,
+    F: Fn(Tile) -> Result<R> + Sync + Send,
+{
+    let tile_height = tile_height.max(1);
+    let tile_width = tile_width.max(1);
+    let tiles_x = cols.div_ceil(tile_width);
+    let tiles_y = rows.div_ceil(tile_height);
+
+    parallel_map(tiles_x * tiles_y, |i| {
+        let row = (i / tiles_x) * tile_height;
+        let col = (i % tiles_x) * tile_width;
+        f(Tile {
+            row,
+            col,
+            height: tile_height.min(rows - row),
+            width: tile_width.min(cols - col),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_parallel_for_rows() {
+        let mut data = vec![0usize; 12];
+        parallel_for_rows(&mut data, 4, |row, chunk| {
+            for (i, v) in chunk.iter_mut().enumerate() {
+                *v = row * 10 + i;
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(data, [0, 1, 2, 3, 10, 11, 12, 13, 20, 21, 22, 23]);
+
+        let err = parallel_for_rows(&mut data, 4, |row, _| {
+            if row >= 1 {
+                return Err(Error::OutOfRange(format!("row {row}")));
+            }
+            Ok(())
+        });
+        assert!(matches!(err, Err(Error::OutOfRange(msg)) if msg == "row 1"));
+    }
+
+    #[test]
+    fn test_parallel_for_tiles() {
+        let tiles = parallel_for_tiles(5, 7, 2, 3, Ok).unwrap();
+        assert_eq!(tiles.len(), 9);
+        assert_eq!(tiles[0], Tile { row: 0, col: 0, height: 2, width: 3 });
+        assert_eq!(tiles[2], Tile { row: 0, col: 6, height: 2, width: 1 });
+        assert_eq!(tiles[8], Tile { row: 4, col: 6, height: 1, width: 1 });
+        let area: usize = tiles.iter().map(|t| t.height * t.width).sum();
+        assert_eq!(area, 35);
+    }
+
+    #[test]
+    fn test_parallel_fold_rows() {
+        let sum = |a: Vec<usize>, b: Vec<usize>| a.iter().zip(&b).map(|(x, y)| x + y).collect();
+        let counts = parallel_fold_rows(1000, || vec![0usize; 3], |acc, row| {
+            acc[row % 3] += 1;
+            Ok(())
+        }, sum)
+        .unwrap();
+        assert_eq!(counts, [334, 333, 333]);
+    }
+
+    #[test]
+    fn test_single_threaded_matches_parallel() {
+        let parallel = parallel_map(100, |i| Ok(i * i)).unwrap();
+        set_single_threaded(true);
+        let serial = parallel_map(100, |i| Ok(i * i)).unwrap();
+        set_single_threaded(false);
+        assert_eq!(parallel, serial);
+    }
+}
@@ -0,0 +1,211 @@
+//! Planar (channel-major) image storage
+//!
+//! [`Mat`] stores pixels interleaved (`RGBRGB…`). A [`PlanarMat`] stores
+//! each channel as its own contiguous plane (`RR…GG…BB…`), the layout that
+//! per-channel convolutions and DNN preprocessing vectorize best on.
+//! Operations elsewhere in the crate take interleaved input, so convert
+//! with [`Mat::to_planar`] and [`Mat::from_planar`] around planar work.
+
+use crate::core::mat::{Mat, MatDepth};
+use crate::core::parallel::parallel_for_rows;
+use crate::core::types::ColorOrder;
+use crate::error::{Error, Result};
+
+/// Image with each channel stored as a separate contiguous plane
+#[derive(Debug, Clone)]
+pub struct PlanarMat {
+    data: Vec<u8>,
+    rows: usize,
+    cols: usize,
+    channels: usize,
+    depth: MatDepth,
+    color_order: ColorOrder,
+}
+
+impl PlanarMat {
+    /// Create a zero-filled planar image
+    pub fn new(rows: usize, cols: usize, channels: usize, depth: MatDepth) -> Result<Self> {
+        if rows == 0 || cols == 0 || channels == 0 {
+            return Err(Error::InvalidDimensions(
+                "Rows, columns and channels must be greater than 0".to_string(),
+            ));
+        }
+        Ok(Self {
+            data: vec![0u8; rows * cols * channels * depth.size()],
+            rows,
+            cols,
+            channels,
+            depth,
+            color_order: ColorOrder::Unknown,
+        })
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[must_use]
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    #[must_use]
+    pub fn depth(&self) -> MatDepth {
+        self.depth
+    }
+
+    /// Channel order carried over from the interleaved image
+    #[must_use]
+    pub fn color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    /// Length in bytes of one plane
+    #[must_use]
+    pub fn plane_len(&self) -> usize {
+        self.rows * self.cols * self.depth.size()
+    }
+
+    /// All planes back to back
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Row-major bytes of `channel`
+    pub fn plane(&self, channel: usize) -> Result<&[u8]> {
+        let len = self.plane_len();
+        self.check_channel(channel)?;
+        Ok(&self.data[channel * len..(channel + 1) * len])
+    }
+
+    /// Mutable row-major bytes of `channel`
+    pub fn plane_mut(&mut self, channel: usize) -> Result<&mut [u8]> {
+        let len = self.plane_len();
+        self.check_channel(channel)?;
+        Ok(&mut self.data[channel * len..(channel + 1) * len])
+    }
+
+    /// Mutable planes in channel order, for processing channels side by side
+    pub fn planes_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let len = self.plane_len();
+        self.data.chunks_exact_mut(len)
+    }
+
+    /// Copy of `channel` as a 1-channel [`Mat`]
+    pub fn plane_mat(&self, channel: usize) -> Result<Mat> {
+        Mat::from_slice(self.plane(channel)?, self.rows, self.cols, 1, self.depth)
+    }
+
+    fn check_channel(&self, channel: usize) -> Result<()> {
+        if channel >= self.channels {
+            return Err(Error::OutOfRange(format!(
+                "Channel {channel} out of range for a {}-channel image",
+                self.channels
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Mat {
+    /// Copy into channel-major planes
+    pub fn to_planar(&self) -> Result<PlanarMat> {
+        let mut planar = PlanarMat::new(self.rows(), self.cols(), self.channels(), self.depth())?;
+        planar.color_order = self.color_order();
+
+        let elem = self.depth().size();
+        let pixel = elem * self.channels();
+        let src = self.data();
+        let plane_len = planar.plane_len();
+        parallel_for_rows(&mut planar.data, plane_len, |channel, plane| {
+            let offset = channel * elem;
+            for (dst, px) in plane.chunks_exact_mut(elem).zip(src.chunks_exact(pixel)) {
+                dst.copy_from_slice(&px[offset..offset + elem]);
+            }
+            Ok(())
+        })?;
+        Ok(planar)
+    }
+
+    /// Interleave the planes of `planar`
+    pub fn from_planar(planar: &PlanarMat) -> Result<Mat> {
+        let mut mat = Mat::new(planar.rows, planar.cols, planar.channels, planar.depth)?
+            .with_color_order(planar.color_order);
+
+        let elem = planar.depth.size();
+        let pixel = elem * planar.channels;
+        let plane_len = planar.plane_len();
+        let row_len = planar.cols * elem;
+        parallel_for_rows(mat.data_mut(), planar.cols * pixel, |row, out| {
+            for (channel, plane) in planar.data.chunks_exact(plane_len).enumerate() {
+                let src = &plane[row * row_len..(row + 1) * row_len];
+                for (px, value) in out.chunks_exact_mut(pixel).zip(src.chunks_exact(elem)) {
+                    px[channel * elem..(channel + 1) * elem].copy_from_slice(value);
+                }
+            }
+            Ok(())
+        })?;
+        Ok(mat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_planar_round_trip() {
+        let mut mat = Mat::new(3, 4, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+        for (i, v) in mat.data_mut().iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        let planar = mat.to_planar().unwrap();
+        assert_eq!(planar.plane_len(), 12);
+        assert_eq!(&planar.plane(0).unwrap()[..4], &[0, 3, 6, 9]);
+        assert_eq!(&planar.plane(2).unwrap()[..4], &[2, 5, 8, 11]);
+        assert_eq!(planar.color_order(), ColorOrder::Bgr);
+        assert!(planar.plane(3).is_err());
+
+        let back = Mat::from_planar(&planar).unwrap();
+        assert_eq!(back.data(), mat.data());
+        assert_eq!(back.color_order(), ColorOrder::Bgr);
+    }
+
+    #[test]
+    fn test_planar_wide_depth() {
+        let mut mat = Mat::new(2, 3, 2, MatDepth::F32).unwrap();
+        for row in 0..2 {
+            for col in 0..3 {
+                mat.set_f32(row, col, 0, (row * 3 + col) as f32).unwrap();
+                mat.set_f32(row, col, 1, -1.5).unwrap();
+            }
+        }
+
+        let mut planar = mat.to_planar().unwrap();
+        let gray = planar.plane_mat(0).unwrap();
+        assert_eq!(gray.at_f32(1, 2, 0).unwrap(), 5.0);
+        assert_eq!(planar.plane_mat(1).unwrap().at_f32(0, 1, 0).unwrap(), -1.5);
+
+        // Scale the second plane in place
+        let second = planar.planes_mut().nth(1).unwrap();
+        for value in second.chunks_exact_mut(4) {
+            let v = f32::from_le_bytes([value[0], value[1], value[2], value[3]]) * 2.0;
+            value.copy_from_slice(&v.to_le_bytes());
+        }
+        let back = Mat::from_planar(&planar).unwrap();
+        assert_eq!(back.at_f32(1, 1, 0).unwrap(), 4.0);
+        assert_eq!(back.at_f32(1, 1, 1).unwrap(), -3.0);
+    }
+}
@@ -0,0 +1,279 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+//! Unorganized 3-D point clouds with optional per-point color
+//!
+//! Depth pipelines (for example `calib3d::reproject_image_to_3d`) produce a
+//! [`PointCloud`], which can be thinned with [`PointCloud::voxel_downsample`]
+//! and written as PLY or PCD for viewers such as MeshLab or CloudCompare.
+
+use crate::core::types::Point3f;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+/// Encoding used by [`PointCloud::write_ply`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+/// A set of 3-D points, optionally with one RGB color per point
+#[derive(Debug, Clone, Default)]
+pub struct PointCloud {
+    points: Vec<Point3f>,
+    colors: Option<Vec<[u8; 3]>>,
+}
+
+impl PointCloud {
+    #[must_use]
+    pub fn new(points: Vec<Point3f>) -> Self {
+        Self { points, colors: None }
+    }
+
+    /// Create a colored cloud; `colors` must hold one RGB triple per point
+    pub fn with_colors(points: Vec<Point3f>, colors: Vec<[u8; 3]>) -> Result<Self> {
+        let mut cloud = Self::new(points);
+        cloud.set_colors(colors)?;
+        Ok(cloud)
+    }
+
+    /// Attach (or replace) per-point RGB colors
+    pub fn set_colors(&mut self, colors: Vec<[u8; 3]>) -> Result<()> {
+        if colors.len() != self.points.len() {
+            return Err(Error::InvalidParameter(format!(
+                "Expected {} colors, got {}",
+                self.points.len(),
+                colors.len()
+            )));
+        }
+        self.colors = Some(colors);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn points(&self) -> &[Point3f] {
+        &self.points
+    }
+
+    #[must_use]
+    pub fn colors(&self) -> Option<&[[u8; 3]]> {
+        self.colors.as_deref()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Replace all points falling in the same `voxel_size` cube by their
+    /// centroid (and mean color). Output order follows the first point seen
+    /// in each voxel.
+    pub fn voxel_downsample(&self, voxel_size: f32) -> Result<PointCloud> {
+        if !(voxel_size > 0.0 && voxel_size.is_finite()) {
+            return Err(Error::InvalidParameter(
+                "voxel_size must be positive and finite".to_string(),
+            ));
+        }
+
+        let mut voxels: HashMap<(i64, i64, i64), usize> = HashMap::new();
+        // Per voxel: xyz sum, rgb sum, count
+        let mut sums: Vec<([f64; 3], [u32; 3], u32)> = Vec::new();
+
+        for (i, p) in self.points.iter().enumerate() {
+            let key = (
+                (p.x / voxel_size).floor() as i64,
+                (p.y / voxel_size).floor() as i64,
+                (p.z / voxel_size).floor() as i64,
+            );
+            let slot = *voxels.entry(key).or_insert_with(|| {
+                sums.push(([0.0; 3], [0; 3], 0));
+                sums.len() - 1
+            });
+            let (xyz, rgb, count) = &mut sums[slot];
+            xyz[0] += f64::from(p.x);
+            xyz[1] += f64::from(p.y);
+            xyz[2] += f64::from(p.z);
+            if let Some(colors) = &self.colors {
+                for (acc, &c) in rgb.iter_mut().zip(&colors[i]) {
+                    *acc += u32::from(c);
+                }
+            }
+            *count += 1;
+        }
+
+        let points = sums
+            .iter()
+            .map(|(xyz, _, n)| {
+                let n = f64::from(*n);
+                Point3f::new((xyz[0] / n) as f32, (xyz[1] / n) as f32, (xyz[2] / n) as f32)
+            })
+            .collect();
+        let colors = self.colors.as_ref().map(|_| {
+            sums.iter()
+                .map(|(_, rgb, n)| rgb.map(|c| ((c + n / 2) / n) as u8))
+                .collect()
+        });
+
+        Ok(PointCloud { points, colors })
+    }
+
+    /// Write the cloud as a PLY file
+    pub fn write_ply<W: Write>(&self, writer: &mut W, format: PlyFormat) -> Result<()> {
+        let encoding = match format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        };
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format {encoding} 1.0")?;
+        writeln!(writer, "element vertex {}", self.points.len())?;
+        writeln!(writer, "property float x")?;
+        writeln!(writer, "property float y")?;
+        writeln!(writer, "property float z")?;
+        if self.colors.is_some() {
+            writeln!(writer, "property uchar red")?;
+            writeln!(writer, "property uchar green")?;
+            writeln!(writer, "property uchar blue")?;
+        }
+        writeln!(writer, "end_header")?;
+
+        for (i, p) in self.points.iter().enumerate() {
+            let color = self.colors.as_ref().map(|c| c[i]);
+            match format {
+                PlyFormat::Ascii => {
+                    write!(writer, "{} {} {}", p.x, p.y, p.z)?;
+                    if let Some([r, g, b]) = color {
+                        write!(writer, " {r} {g} {b}")?;
+                    }
+                    writeln!(writer)?;
+                }
+                PlyFormat::BinaryLittleEndian => {
+                    for v in [p.x, p.y, p.z] {
+                        writer.write_all(&v.to_le_bytes())?;
+                    }
+                    if let Some(rgb) = color {
+                        writer.write_all(&rgb)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the cloud as an ASCII PCD (Point Cloud Library) file
+    ///
+    /// Colors are stored in PCL's packed `rgb` field.
+    pub fn write_pcd<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let n = self.points.len();
+        let colored = self.colors.is_some();
+        writeln!(writer, "# .PCD v0.7 - Point Cloud Data file format")?;
+        writeln!(writer, "VERSION 0.7")?;
+        if colored {
+            writeln!(writer, "FIELDS x y z rgb")?;
+            writeln!(writer, "SIZE 4 4 4 4")?;
+            writeln!(writer, "TYPE F F F U")?;
+            writeln!(writer, "COUNT 1 1 1 1")?;
+        } else {
+            writeln!(writer, "FIELDS x y z")?;
+            writeln!(writer, "SIZE 4 4 4")?;
+            writeln!(writer, "TYPE F F F")?;
+            writeln!(writer, "COUNT 1 1 1")?;
+        }
+        writeln!(writer, "WIDTH {n}")?;
+        writeln!(writer, "HEIGHT 1")?;
+        writeln!(writer, "VIEWPOINT 0 0 0 1 0 0 0")?;
+        writeln!(writer, "POINTS {n}")?;
+        writeln!(writer, "DATA ascii")?;
+
+        for (i, p) in self.points.iter().enumerate() {
+            write!(writer, "{} {} {}", p.x, p.y, p.z)?;
+            if let Some(colors) = &self.colors {
+                let [r, g, b] = colors[i];
+                let packed = (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b);
+                write!(writer, " {packed}")?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Save to `path`, choosing binary PLY or ASCII PCD from the extension
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        match extension.as_deref() {
+            Some("ply") => self.write_ply(&mut writer, PlyFormat::BinaryLittleEndian)?,
+            Some("pcd") => self.write_pcd(&mut writer)?,
+            _ => {
+                return Err(Error::UnsupportedOperation(format!(
+                    "Unknown point cloud extension: {}",
+                    path.display()
+                )))
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PointCloud {
+        PointCloud::with_colors(
+            vec![
+                Point3f::new(0.1, 0.1, 0.1),
+                Point3f::new(0.3, 0.2, 0.4),
+                Point3f::new(1.5, 0.0, 0.0),
+            ],
+            vec![[10, 20, 30], [30, 40, 50], [255, 0, 0]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_voxel_downsample() {
+        let down = sample().voxel_downsample(1.0).unwrap();
+        assert_eq!(down.len(), 2);
+        assert!((down.points()[0].x - 0.2).abs() < 1e-6);
+        assert!((down.points()[0].z - 0.25).abs() < 1e-6);
+        assert_eq!(down.colors().unwrap(), &[[20, 30, 40], [255, 0, 0]]);
+
+        assert!(sample().voxel_downsample(0.0).is_err());
+        assert!(PointCloud::with_colors(vec![Point3f::new(0.0, 0.0, 0.0)], vec![]).is_err());
+    }
+
+    #[test]
+    fn test_write_ply_and_pcd() {
+        let cloud = sample();
+
+        let mut ascii = Vec::new();
+        cloud.write_ply(&mut ascii, PlyFormat::Ascii).unwrap();
+        let text = String::from_utf8(ascii).unwrap();
+        assert!(text.contains("element vertex 3\n"));
+        assert!(text.contains("property uchar red\n"));
+        assert!(text.ends_with("end_header\n0.1 0.1 0.1 10 20 30\n0.3 0.2 0.4 30 40 50\n1.5 0 0 255 0 0\n"));
+
+        let mut binary = Vec::new();
+        cloud.write_ply(&mut binary, PlyFormat::BinaryLittleEndian).unwrap();
+        let header_end = binary.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+        assert_eq!(binary.len() - header_end, 3 * 15);
+
+        let mut pcd = Vec::new();
+        PointCloud::new(cloud.points().to_vec()).write_pcd(&mut pcd).unwrap();
+        let text = String::from_utf8(pcd).unwrap();
+        assert!(text.contains("FIELDS x y z\n"));
+        assert!(text.contains("POINTS 3\nDATA ascii\n0.1 0.1 0.1\n"));
+    }
+}
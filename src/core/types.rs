@@ -121,6 +121,60 @@ impl Rect {
             && point.y >= self.y
             && point.y < self.y + self.height
     }
+
+    /// Overlap of two rectangles, or `None` if they don't overlap
+    #[must_use]
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        (right > x && bottom > y).then(|| Rect::new(x, y, right - x, bottom - y))
+    }
+
+    /// Grow the rectangle by `margin` on every side
+    #[must_use]
+    pub fn inflate(&self, margin: i32) -> Rect {
+        Rect::new(self.x - margin, self.y - margin, self.width + 2 * margin, self.height + 2 * margin)
+    }
+}
+
+/// Rectangle rotated about its centre
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotatedRect {
+    pub center: Point2f,
+    pub width: f32,
+    pub height: f32,
+    /// Rotation in degrees, clockwise on screen (image y axis pointing down)
+    pub angle: f32,
+}
+
+impl RotatedRect {
+    #[must_use]
+    pub fn new(center: Point2f, width: f32, height: f32, angle: f32) -> Self {
+        Self { center, width, height, angle }
+    }
+
+    #[must_use]
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    /// Corner points, in order around the rectangle starting from the corner
+    /// that is top-left before rotation
+    #[must_use]
+    pub fn points(&self) -> [Point2f; 4] {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let hw = self.width / 2.0;
+        let hh = self.height / 2.0;
+
+        [(-hw, -hh), (hw, -hh), (hw, hh), (-hw, hh)].map(|(dx, dy)| {
+            Point2f::new(
+                self.center.x + dx * cos - dy * sin,
+                self.center.y + dx * sin + dy * cos,
+            )
+        })
+    }
 }
 
 /// Scalar value (up to 4 channels)
@@ -225,6 +279,67 @@ pub enum ColorConversionCode {
     YCrCbToRgb,
 }
 
+impl ColorConversionCode {
+    /// Channel order the conversion expects, `None` for gray, HSV, Lab and
+    /// `YCrCb` input
+    #[must_use]
+    pub fn source_order(self) -> Option<ColorOrder> {
+        use ColorConversionCode::*;
+        match self {
+            BgrToGray | BgraToGray | BgrToRgb | BgrToHsv | BgrToLab | BgrToYCrCb => Some(ColorOrder::Bgr),
+            RgbToGray | RgbaToGray | RgbToBgr | RgbToHsv | RgbToLab | RgbToYCrCb => Some(ColorOrder::Rgb),
+            _ => None,
+        }
+    }
+
+    /// Channel order of the converted image, `None` when it isn't RGB or BGR
+    #[must_use]
+    pub fn destination_order(self) -> Option<ColorOrder> {
+        use ColorConversionCode::*;
+        match self {
+            GrayToBgr | RgbToBgr | HsvToBgr | LabToBgr | YCrCbToBgr => Some(ColorOrder::Bgr),
+            GrayToRgb | BgrToRgb | HsvToRgb | LabToRgb | YCrCbToRgb => Some(ColorOrder::Rgb),
+            _ => None,
+        }
+    }
+}
+
+/// Order of the color channels of a 3 or 4-channel image, alpha last
+///
+/// Carried by [`Mat`](crate::core::Mat) so that conversions can reject
+/// input in the wrong order. Operations whose output keeps the input's
+/// channel layout (filters, resizing, warps, thresholds, arithmetic) copy
+/// the tag to their output. Untagged images follow the crate convention,
+/// RGB, which is what `imread` and canvas `ImageData` produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorOrder {
+    #[default]
+    Unknown,
+    Rgb,
+    Bgr,
+}
+
+impl ColorOrder {
+    /// The order to assume for an image with this tag
+    #[must_use]
+    pub fn or_convention(self) -> ColorOrder {
+        match self {
+            ColorOrder::Unknown => ColorOrder::Rgb,
+            order => order,
+        }
+    }
+
+    /// BT.601 luma weights for the first three channels of an image with
+    /// this tag
+    #[must_use]
+    pub fn luma_weights(self) -> [f64; 3] {
+        match self.or_convention() {
+            ColorOrder::Bgr => [0.114, 0.587, 0.299],
+            _ => [0.299, 0.587, 0.114],
+        }
+    }
+}
+
 /// Interpolation methods
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InterpolationFlag {
@@ -235,6 +350,24 @@ pub enum InterpolationFlag {
     Lanczos4,
 }
 
+/// Pixel extrapolation for coordinates outside the image
+///
+/// Illustrated on a row `abcdefgh` extended on both sides:
+/// - `Constant`: `iiii|abcdefgh|iiii` with a caller-supplied value `i`
+/// - `Replicate`: `aaaa|abcdefgh|hhhh`
+/// - `Reflect`: `dcba|abcdefgh|hgfe`
+/// - `Reflect101`: `edcb|abcdefgh|gfed` (the `OpenCV` default)
+/// - `Wrap`: `efgh|abcdefgh|abcd`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderType {
+    Constant,
+    Replicate,
+    Reflect,
+    Wrap,
+    #[default]
+    Reflect101,
+}
+
 /// Threshold types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ThresholdType {
@@ -271,4 +404,10 @@ mod tests {
         let sum = s1 + s2;
         assert_eq!(sum.val[0], 15.0);
     }
+
+    #[test]
+    fn test_luma_weights() {
+        assert_eq!(ColorOrder::Unknown.luma_weights(), ColorOrder::Rgb.luma_weights());
+        assert_eq!(ColorOrder::Bgr.luma_weights(), [0.114, 0.587, 0.299]);
+    }
 }
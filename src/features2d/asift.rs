@@ -0,0 +1,367 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::parallel::parallel_map;
+use crate::core::types::{BorderType, InterpolationFlag, Point};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::features2d::{DMatch, DescriptorDistance, KeyPoint};
+use crate::imgproc::geometric::{get_rotation_matrix_2d_bounded, invert_affine_transform, warp_affine_with_sampler};
+use crate::imgproc::PixelSampler;
+
+/// Keypoints closer than this to the border of the original image are
+/// dropped from simulated views, where the rotated canvas edge creates
+/// spurious corners
+const VIEW_BORDER_MARGIN: f64 = 8.0;
+
+/// Matches whose endpoints are both within this many pixels of an earlier,
+/// closer match are treated as the same correspondence seen in another view
+const DUPLICATE_RADIUS: i32 = 2;
+
+/// One simulated camera viewpoint: the image is rotated by `angle` degrees
+/// and then compressed horizontally by `tilt`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AsiftView {
+    pub tilt: f64,
+    pub angle: f64,
+}
+
+/// Keypoints and descriptors pooled from two images and their ratio-tested
+/// matches; keypoint coordinates are in the original images
+#[derive(Debug, Clone)]
+pub struct AsiftMatches {
+    pub keypoints1: Vec<KeyPoint>,
+    pub keypoints2: Vec<KeyPoint>,
+    pub matches: Vec<DMatch>,
+}
+
+/// Affine-invariant wrapper (ASIFT) around any detector/descriptor
+///
+/// Each image is resampled under a set of simulated viewpoint tilts and
+/// in-plane rotations, the detector runs on every simulated view and the
+/// keypoints are mapped back into the original image, so features survive
+/// the strong foreshortening that defeats the detector on its own. Tilts
+/// follow a geometric series of ratio sqrt(2) and rotations are sampled
+/// every `rotation_step / tilt` degrees, as in Morel and Yu's ASIFT.
+#[derive(Debug, Clone)]
+pub struct Asift {
+    pub n_tilts: usize,
+    pub rotation_step: f64,
+    pub ratio: f32,
+}
+
+impl Default for Asift {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Asift {
+    /// Five tilts up to 4, rotations every 72/t degrees and a 0.75 ratio
+    /// test
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            n_tilts: 5,
+            rotation_step: 72.0,
+            ratio: 0.75,
+        }
+    }
+
+    /// Number of tilts including the untilted view (1 disables simulation)
+    #[must_use]
+    pub fn with_tilts(mut self, n_tilts: usize) -> Self {
+        self.n_tilts = n_tilts;
+        self
+    }
+
+    /// Rotation sampling step in degrees at tilt 1
+    #[must_use]
+    pub fn with_rotation_step(mut self, degrees: f64) -> Self {
+        self.rotation_step = degrees;
+        self
+    }
+
+    /// Lowe ratio used by [`match_features`](Self::match_features)
+    #[must_use]
+    pub fn with_ratio(mut self, ratio: f32) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Simulated viewpoints, starting with the original image
+    #[must_use]
+    pub fn views(&self) -> Vec<AsiftView> {
+        let mut views = vec![AsiftView { tilt: 1.0, angle: 0.0 }];
+        for k in 1..self.n_tilts {
+            let tilt = 2f64.sqrt().powi(k as i32);
+            let step = self.rotation_step / tilt;
+            let count = (180.0 / step - 1e-9).ceil() as usize;
+            views.extend((0..count).map(|j| AsiftView { tilt, angle: j as f64 * step }));
+        }
+        views
+    }
+
+    /// Resample `image` under `view`
+    ///
+    /// Returns the simulated image and the affine transform mapping original
+    /// to simulated coordinates. The tilt is applied after a horizontal
+    /// anti-aliasing blur of sigma `0.8 * sqrt(tilt^2 - 1)`.
+    pub fn simulate(&self, image: &Mat, view: AsiftView) -> Result<(Mat, [[f64; 3]; 2])> {
+        if image.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "ASIFT only supports U8 depth".to_string(),
+            ));
+        }
+        if view.tilt < 1.0 {
+            return Err(Error::InvalidParameter(
+                "ASIFT tilt must be at least 1".to_string(),
+            ));
+        }
+
+        let (mut m, bound) = get_rotation_matrix_2d_bounded(image.size(), view.angle, 1.0);
+        let mut rotated = image.clone();
+        if view.angle != 0.0 {
+            let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Constant);
+            warp_affine_with_sampler(image, &mut rotated, &invert_affine_transform(&m), bound, &sampler)?;
+        }
+        if view.tilt == 1.0 {
+            return Ok((rotated, m));
+        }
+
+        let simulated = squeeze_columns(&rotated, view.tilt)?;
+        // Pixel centres: x' = (x + 0.5) / t - 0.5
+        for v in &mut m[0] {
+            *v /= view.tilt;
+        }
+        m[0][2] += 0.5 / view.tilt - 0.5;
+        Ok((simulated, m))
+    }
+
+    /// Run `detector` on every simulated view of `image` and pool the
+    /// results, with keypoint positions mapped back into `image`
+    ///
+    /// `detector` is typically a closure around a detector's
+    /// `detect_and_compute`; views are processed in parallel.
+    pub fn detect_and_compute<D, F>(&self, image: &Mat, detector: F) -> Result<(Vec<KeyPoint>, Vec<D>)>
+    where
+        D: Send,
+        F: Fn(&Mat) -> Result<(Vec<KeyPoint>, Vec<D>)> + Sync + Send,
+    {
+        let views = self.views();
+        let (rows, cols) = (image.rows() as f64, image.cols() as f64);
+        let per_view = parallel_map(views.len(), |i| {
+            let (simulated, m) = self.simulate(image, views[i])?;
+            let inverse = invert_affine_transform(&m);
+            let (keypoints, descriptors) = detector(&simulated)?;
+            let margin = if i == 0 { 0.0 } else { VIEW_BORDER_MARGIN };
+
+            let mut kept = (Vec::new(), Vec::new());
+            for (mut kp, desc) in keypoints.into_iter().zip(descriptors) {
+                let (vx, vy) = (f64::from(kp.pt.x), f64::from(kp.pt.y));
+                let x = inverse[0][0] * vx + inverse[0][1] * vy + inverse[0][2];
+                let y = inverse[1][0] * vx + inverse[1][1] * vy + inverse[1][2];
+                if x < margin || y < margin || x > cols - 1.0 - margin || y > rows - 1.0 - margin {
+                    continue;
+                }
+                kp.pt = Point::new(x.round() as i32, y.round() as i32);
+                kept.0.push(kp);
+                kept.1.push(desc);
+            }
+            Ok(kept)
+        })?;
+
+        let mut keypoints = Vec::new();
+        let mut descriptors = Vec::new();
+        for (kps, descs) in per_view {
+            keypoints.extend(kps);
+            descriptors.extend(descs);
+        }
+        Ok((keypoints, descriptors))
+    }
+
+    /// Ratio-test matching of pooled features
+    ///
+    /// The same physical feature is usually found in several views, so
+    /// matches whose endpoints both lie within a couple of pixels of a
+    /// closer match are merged into it.
+    pub fn match_features<D>(
+        &self,
+        keypoints1: &[KeyPoint],
+        descriptors1: &[D],
+        keypoints2: &[KeyPoint],
+        descriptors2: &[D],
+    ) -> Result<Vec<DMatch>>
+    where
+        D: DescriptorDistance + Sync,
+    {
+        if keypoints1.len() != descriptors1.len() || keypoints2.len() != descriptors2.len() {
+            return Err(Error::InvalidParameter(
+                "Need one descriptor per keypoint".to_string(),
+            ));
+        }
+        if descriptors2.len() < 2 {
+            return Ok(Vec::new());
+        }
+
+        let best = parallel_map(descriptors1.len(), |query| {
+            let q = &descriptors1[query];
+            let (mut best, mut second) = ((0, f32::MAX), f32::MAX);
+            for (train, t) in descriptors2.iter().enumerate() {
+                let dist = q.distance(t);
+                if dist < best.1 {
+                    second = best.1;
+                    best = (train, dist);
+                } else if dist < second {
+                    second = dist;
+                }
+            }
+            Ok((best, second))
+        })?;
+
+        let mut matches: Vec<DMatch> = best
+            .into_iter()
+            .enumerate()
+            .filter(|(_, ((_, dist), second))| *dist < self.ratio * *second)
+            .map(|(query, ((train, dist), _))| DMatch::new(query, train, dist))
+            .collect();
+        matches.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        let near = |a: Point, b: Point| (a.x - b.x).abs() <= DUPLICATE_RADIUS && (a.y - b.y).abs() <= DUPLICATE_RADIUS;
+        let mut merged: Vec<DMatch> = Vec::new();
+        for m in matches {
+            let (p1, p2) = (keypoints1[m.query_idx].pt, keypoints2[m.train_idx].pt);
+            let duplicate = merged
+                .iter()
+                .any(|k| near(p1, keypoints1[k.query_idx].pt) && near(p2, keypoints2[k.train_idx].pt));
+            if !duplicate {
+                merged.push(m);
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Detect features in both images over all simulated views and match
+    /// them
+    pub fn match_images<D, F>(&self, image1: &Mat, image2: &Mat, detector: F) -> Result<AsiftMatches>
+    where
+        D: DescriptorDistance + Send + Sync,
+        F: Fn(&Mat) -> Result<(Vec<KeyPoint>, Vec<D>)> + Sync + Send,
+    {
+        let (keypoints1, descriptors1) = self.detect_and_compute(image1, &detector)?;
+        let (keypoints2, descriptors2) = self.detect_and_compute(image2, &detector)?;
+        let matches = self.match_features(&keypoints1, &descriptors1, &keypoints2, &descriptors2)?;
+        Ok(AsiftMatches { keypoints1, keypoints2, matches })
+    }
+}
+
+/// Blur rows with a Gaussian of sigma `0.8 * sqrt(tilt^2 - 1)` and resample
+/// them to `1 / tilt` of their width
+fn squeeze_columns(src: &Mat, tilt: f64) -> Result<Mat> {
+    let (rows, cols, channels) = (src.rows(), src.cols(), src.channels());
+    let out_cols = ((cols as f64 / tilt).round() as usize).max(1);
+
+    let sigma = 0.8 * (tilt * tilt - 1.0).sqrt();
+    let radius = (3.0 * sigma).ceil() as i64;
+    let kernel: Vec<f64> = (-radius..=radius)
+        .map(|i| (-(i * i) as f64 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let norm: f64 = kernel.iter().sum();
+
+    let mut out = Mat::new(rows, out_cols, channels, MatDepth::U8)?;
+    let mut blurred = vec![0.0f64; cols * channels];
+    for row in 0..rows {
+        let line = &src.data()[row * cols * channels..(row + 1) * cols * channels];
+        for x in 0..cols {
+            for ch in 0..channels {
+                let mut sum = 0.0;
+                for (k, &w) in kernel.iter().enumerate() {
+                    let sx = (x as i64 + k as i64 - radius).clamp(0, cols as i64 - 1) as usize;
+                    sum += w * f64::from(line[sx * channels + ch]);
+                }
+                blurred[x * channels + ch] = sum / norm;
+            }
+        }
+
+        let out_px = &mut out.data_mut()[row * out_cols * channels..(row + 1) * out_cols * channels];
+        for x in 0..out_cols {
+            let sx = ((x as f64 + 0.5) * tilt - 0.5).clamp(0.0, (cols - 1) as f64);
+            let x0 = sx.floor() as usize;
+            let x1 = (x0 + 1).min(cols - 1);
+            let fx = sx - x0 as f64;
+            for ch in 0..channels {
+                let v = blurred[x0 * channels + ch] * (1.0 - fx) + blurred[x1 * channels + ch] * fx;
+                out_px[x * channels + ch] = v.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features2d::orb::ORB;
+    use crate::imgproc::noise::SplitMix64;
+
+    fn texture(rows: usize, cols: usize) -> Mat {
+        let mut rng = SplitMix64::new(7);
+        let mut img = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+        for _ in 0..120 {
+            let (x0, y0) = ((rng.next_u64() % cols as u64) as usize, (rng.next_u64() % rows as u64) as usize);
+            let (w, h) = (8 + (rng.next_u64() % 24) as usize, 8 + (rng.next_u64() % 24) as usize);
+            let v = (rng.next_u64() % 256) as u8;
+            for row in y0..(y0 + h).min(rows) {
+                for col in x0..(x0 + w).min(cols) {
+                    img.at_mut(row, col).unwrap()[0] = v;
+                }
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_views_and_simulation() {
+        let asift = Asift::new().with_tilts(3);
+        let views = asift.views();
+        assert_eq!(views[0], AsiftView { tilt: 1.0, angle: 0.0 });
+        // 180 / (72 / sqrt 2) -> 4 rotations, 180 / (72 / 2) -> 5 rotations
+        assert_eq!(views.len(), 1 + 4 + 5);
+
+        let img = texture(60, 80);
+        let (sim, m) = asift.simulate(&img, AsiftView { tilt: 2.0, angle: 0.0 }).unwrap();
+        assert_eq!((sim.rows(), sim.cols()), (60, 40));
+        assert!((m[0][0] - 0.5).abs() < 1e-12 && (m[1][1] - 1.0).abs() < 1e-12);
+
+        let (sim, m) = asift.simulate(&img, AsiftView { tilt: 1.0, angle: 90.0 }).unwrap();
+        assert_eq!((sim.rows(), sim.cols()), (80, 60));
+        let (x, y) = (10.0, 20.0);
+        let (sx, sy) = (m[0][0] * x + m[0][1] * y + m[0][2], m[1][0] * x + m[1][1] * y + m[1][2]);
+        assert_eq!(sim.at(sy.round() as usize, sx.round() as usize).unwrap()[0], img.at(20, 10).unwrap()[0]);
+
+        assert!(asift.simulate(&img, AsiftView { tilt: 0.5, angle: 0.0 }).is_err());
+    }
+
+    #[test]
+    fn test_match_images_under_strong_tilt() {
+        let img = texture(320, 320);
+        // Foreshortened copy: rotated by 30 degrees and compressed 3x horizontally
+        let (tilted, m) = Asift::new().simulate(&img, AsiftView { tilt: 3.0, angle: 30.0 }).unwrap();
+
+        let orb = ORB::new(400);
+        let plain = Asift::new().with_tilts(1).match_images(&img, &tilted, |view| orb.detect_and_compute(view)).unwrap();
+        let result = Asift::new().with_tilts(4).match_images(&img, &tilted, |view| orb.detect_and_compute(view)).unwrap();
+        assert!(result.matches.len() >= 12, "only {} matches", result.matches.len());
+        assert!(result.matches.len() > 4 * plain.matches.len());
+        // Most merged matches agree with the simulated viewpoint change
+        let agreeing = result
+            .matches
+            .iter()
+            .filter(|d| {
+                let (p, q) = (result.keypoints1[d.query_idx].pt, result.keypoints2[d.train_idx].pt);
+                let (x, y) = (f64::from(p.x), f64::from(p.y));
+                let (tx, ty) = (m[0][0] * x + m[0][1] * y + m[0][2], m[1][0] * x + m[1][1] * y + m[1][2]);
+                (tx - f64::from(q.x)).hypot(ty - f64::from(q.y)) < 3.0
+            })
+            .count();
+        assert!(agreeing * 3 >= result.matches.len() * 2, "only {agreeing} of {} matches are correct", result.matches.len());
+    }
+}
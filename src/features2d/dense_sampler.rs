@@ -0,0 +1,181 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+use crate::core::Mat;
+use crate::core::types::Point;
+use crate::error::{Error, Result};
+use crate::features2d::KeyPoint;
+
+/// Dense keypoint sampler
+///
+/// Emits keypoints on a regular grid at one or more scales instead of
+/// detecting them, so flat and weakly textured regions are described as well
+/// as corners. Pair it with any descriptor extractor (`BRIEF::compute`,
+/// `FREAK::compute`, ...) to build bag-of-words features for texture or
+/// scene classification.
+///
+/// Level `i` uses keypoint size `feature_scale · scale_mul^i`. With
+/// `vary_step_with_scale` the grid step grows by the same factor, and with
+/// `vary_bound_with_scale` so does the border kept free around the image.
+/// Keypoints carry their level in `octave`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseSampler {
+    pub feature_scale: f32,
+    pub scale_levels: usize,
+    pub scale_mul: f32,
+    pub step: i32,
+    pub bound: i32,
+    pub vary_step_with_scale: bool,
+    pub vary_bound_with_scale: bool,
+}
+
+impl Default for DenseSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DenseSampler {
+    /// Single-scale grid of size-16 keypoints every 8 pixels
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            feature_scale: 16.0,
+            scale_levels: 1,
+            scale_mul: 1.5,
+            step: 8,
+            bound: 0,
+            vary_step_with_scale: true,
+            vary_bound_with_scale: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_feature_scale(mut self, feature_scale: f32) -> Self {
+        self.feature_scale = feature_scale;
+        self
+    }
+
+    /// Number of scale levels and the size ratio between consecutive levels
+    #[must_use]
+    pub fn with_scale_levels(mut self, levels: usize, scale_mul: f32) -> Self {
+        self.scale_levels = levels;
+        self.scale_mul = scale_mul;
+        self
+    }
+
+    #[must_use]
+    pub fn with_step(mut self, step: i32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Border in pixels left free of keypoint centres
+    #[must_use]
+    pub fn with_bound(mut self, bound: i32) -> Self {
+        self.bound = bound;
+        self
+    }
+
+    #[must_use]
+    pub fn with_vary_step_with_scale(mut self, vary: bool) -> Self {
+        self.vary_step_with_scale = vary;
+        self
+    }
+
+    #[must_use]
+    pub fn with_vary_bound_with_scale(mut self, vary: bool) -> Self {
+        self.vary_bound_with_scale = vary;
+        self
+    }
+
+    /// Grid keypoints covering `image`
+    pub fn detect(&self, image: &Mat) -> Result<Vec<KeyPoint>> {
+        self.keypoints(image.rows(), image.cols())
+    }
+
+    /// Grid keypoints for an image of the given size
+    pub fn keypoints(&self, rows: usize, cols: usize) -> Result<Vec<KeyPoint>> {
+        if self.feature_scale <= 0.0 || self.scale_mul <= 0.0 || self.scale_levels == 0 {
+            return Err(Error::InvalidParameter(
+                "feature_scale and scale_mul must be positive and scale_levels non-zero".to_string(),
+            ));
+        }
+
+        if self.step <= 0 || self.bound < 0 {
+            return Err(Error::InvalidParameter(
+                "step must be positive and bound non-negative".to_string(),
+            ));
+        }
+
+        let rows = rows as i32;
+        let cols = cols as i32;
+        let mut keypoints = Vec::new();
+
+        let mut factor = 1.0f32;
+        for level in 0..self.scale_levels {
+            let size = self.feature_scale * factor;
+            let step = if self.vary_step_with_scale {
+                ((self.step as f32 * factor).round() as i32).max(1)
+            } else {
+                self.step
+            };
+            let bound = if self.vary_bound_with_scale {
+                (self.bound as f32 * factor).round() as i32
+            } else {
+                self.bound
+            };
+
+            for y in (bound..rows - bound).step_by(step as usize) {
+                for x in (bound..cols - bound).step_by(step as usize) {
+                    let mut kp = KeyPoint::new(Point::new(x, y), size);
+                    kp.octave = level as i32;
+                    keypoints.push(kp);
+                }
+            }
+
+            factor *= self.scale_mul;
+        }
+
+        Ok(keypoints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+    use crate::core::types::Scalar;
+    use crate::features2d::brief::BRIEF;
+
+    #[test]
+    fn test_dense_grid_levels() {
+        let sampler = DenseSampler::new()
+            .with_step(10)
+            .with_bound(5)
+            .with_scale_levels(2, 2.0)
+            .with_vary_bound_with_scale(true);
+        let kps = sampler.keypoints(40, 60).unwrap();
+
+        // Level 0: x in 5..55 step 10, y in 5..35 step 10
+        let level0: Vec<_> = kps.iter().filter(|k| k.octave == 0).collect();
+        assert_eq!(level0.len(), 5 * 3);
+        assert_eq!(level0[0].pt, Point::new(5, 5));
+        assert!(level0.iter().all(|k| (k.size - 16.0).abs() < 1e-6));
+
+        // Level 1: step 20, bound 10 -> x in {10, 30}, y in {10}
+        let level1: Vec<_> = kps.iter().filter(|k| k.octave == 1).collect();
+        assert_eq!(level1.len(), 2);
+        assert!(level1.iter().all(|k| (k.size - 32.0).abs() < 1e-6));
+
+        assert!(DenseSampler::new().with_step(0).keypoints(10, 10).is_err());
+    }
+
+    #[test]
+    fn test_dense_sampler_feeds_descriptor() {
+        let image = Mat::new_with_default(64, 64, 1, MatDepth::U8, Scalar::all(90.0)).unwrap();
+        let kps = DenseSampler::new().with_bound(24).detect(&image).unwrap();
+        assert!(!kps.is_empty());
+
+        let descriptors = BRIEF::new().compute(&image, &kps).unwrap();
+        assert_eq!(descriptors.len(), kps.len());
+    }
+}
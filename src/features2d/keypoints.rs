@@ -1,5 +1,5 @@
 use crate::core::{Mat, MatDepth};
-use crate::core::types::Point;
+use crate::core::types::{Point, Rect};
 use crate::error::{Error, Result};
 
 #[cfg(feature = "rayon")]
@@ -287,6 +287,49 @@ pub fn fast(
     Ok(final_keypoints)
 }
 
+/// Run a keypoint detector on `roi` of `src` only, keeping the keypoints
+/// inside the ROI and, if given, on non-zero pixels of `mask`
+///
+/// `detect` is called on the ROI grown by `margin` pixels, so detections
+/// near the ROI edge see the same neighbourhood as on the whole image when
+/// the margin covers the detector's support (e.g. 6 for [`fast`] with
+/// non-maximum suppression). Keypoints are returned in full-image
+/// coordinates. Detectors that keep only the strongest N keypoints apply
+/// that limit to the grown region.
+///
+/// ```
+/// # use opencv_rust::core::{Mat, MatDepth, Rect};
+/// # use opencv_rust::features2d::{detect_in_roi, fast};
+/// # let frame = Mat::new(120, 160, 1, MatDepth::U8).unwrap();
+/// let keypoints = detect_in_roi(&frame, Rect::new(40, 30, 64, 48), None, 6, |crop| fast(crop, 20, true))?;
+/// # Ok::<(), opencv_rust::error::Error>(())
+/// ```
+pub fn detect_in_roi<F>(src: &Mat, roi: Rect, mask: Option<&Mat>, margin: usize, detect: F) -> Result<Vec<KeyPoint>>
+where
+    F: FnOnce(&Mat) -> Result<Vec<KeyPoint>>,
+{
+    if let Some(mask) = mask {
+        if mask.rows() != src.rows() || mask.cols() != src.cols() || mask.channels() != 1 || mask.depth() != MatDepth::U8 {
+            return Err(Error::InvalidParameter(
+                "Mask must be a single-channel U8 image the size of the source".to_string(),
+            ));
+        }
+    }
+
+    let (crop, covered) = src.roi_with_margin(roi, margin)?;
+    let mut keypoints = detect(&crop)?;
+    for kp in &mut keypoints {
+        kp.pt = Point::new(kp.pt.x + covered.x, kp.pt.y + covered.y);
+    }
+    keypoints.retain(|kp| {
+        #[allow(clippy::cast_sign_loss)]
+        let on_mask = |m: &Mat| m.at(kp.pt.y as usize, kp.pt.x as usize).is_ok_and(|px| px[0] != 0);
+        roi.contains(kp.pt) && mask.is_none_or(on_mask)
+    });
+
+    Ok(keypoints)
+}
+
 /// Apply non-maximum suppression to keypoints
 fn apply_non_max_suppression(keypoints: &[KeyPoint], radius: i32) -> Vec<KeyPoint> {
     let mut result = Vec::new();
@@ -336,4 +379,40 @@ mod tests {
         // May detect some based on noise (len is always >= 0 for Vec)
         let _ = keypoints.len();
     }
+
+    #[test]
+    fn test_detect_in_roi() {
+        // Small bright blobs on a dark background are FAST keypoints
+        let mut img = Mat::new_with_default(80, 100, 1, MatDepth::U8, Scalar::all(30.0)).unwrap();
+        for (top, left) in [(10, 10), (20, 45), (50, 30), (45, 70), (16, 26), (40, 55)] {
+            for row in top..top + 3 {
+                for col in left..left + 3 {
+                    img.at_mut(row, col).unwrap()[0] = 220;
+                }
+            }
+        }
+        let roi = Rect::new(25, 15, 50, 45);
+        let key = |kps: &[KeyPoint]| {
+            let mut pts: Vec<(i32, i32)> = kps.iter().map(|kp| (kp.pt.x, kp.pt.y)).collect();
+            pts.sort_unstable();
+            pts
+        };
+
+        let full = fast(&img, 40, true).unwrap();
+        let expected: Vec<KeyPoint> = full.into_iter().filter(|kp| roi.contains(kp.pt)).collect();
+        let found = detect_in_roi(&img, roi, None, 6, |crop| fast(crop, 40, true)).unwrap();
+        assert!(!expected.is_empty());
+        assert_eq!(key(&found), key(&expected));
+
+        // Mask off everything left of x = 50
+        let mut mask = Mat::new(80, 100, 1, MatDepth::U8).unwrap();
+        for row in 0..80 {
+            for col in 50..100 {
+                mask.at_mut(row, col).unwrap()[0] = 1;
+            }
+        }
+        let masked = detect_in_roi(&img, roi, Some(&mask), 6, |crop| fast(crop, 40, true)).unwrap();
+        let expected: Vec<KeyPoint> = expected.into_iter().filter(|kp| kp.pt.x >= 50).collect();
+        assert_eq!(key(&masked), key(&expected));
+    }
 }
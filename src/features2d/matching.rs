@@ -27,6 +27,33 @@ pub enum DistanceType {
     L2,
 }
 
+/// Distance between two descriptors of the same kind
+///
+/// Binary descriptors (`Vec<u8>`) use the Hamming distance and float
+/// descriptors (`Vec<f32>`) the Euclidean distance, so matching code can be
+/// written once for ORB/BRISK/AKAZE and SIFT/KAZE output alike.
+pub trait DescriptorDistance {
+    fn distance(&self, other: &Self) -> f32;
+}
+
+impl DescriptorDistance for Vec<u8> {
+    fn distance(&self, other: &Self) -> f32 {
+        #[allow(clippy::cast_precision_loss)]
+        let dist = hamming_distance(self, other) as f32;
+        dist
+    }
+}
+
+impl DescriptorDistance for Vec<f32> {
+    fn distance(&self, other: &Self) -> f32 {
+        self.iter()
+            .zip(other)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+
 /// Brute Force Matcher
 pub struct BFMatcher {
     pub distance_type: DistanceType,
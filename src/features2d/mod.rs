@@ -8,6 +8,8 @@ pub mod brisk;
 pub mod orb;
 pub mod brief;
 pub mod freak;
+pub mod dense_sampler;
+pub mod asift;
 
 pub use keypoints::*;
 pub use descriptors::*;
@@ -17,3 +19,5 @@ pub use akaze::{AKAZE, DescriptorType, DiffusivityType};
 pub use kaze::KAZE;
 pub use brisk::*;
 pub use freak::*;
+pub use dense_sampler::*;
+pub use asift::{Asift, AsiftMatches, AsiftView};
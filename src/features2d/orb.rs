@@ -256,13 +256,9 @@ impl ORB {
             #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
             let cols_i32 = image.cols() as i32;
 
-            if row < self.patch_size || row >= rows_i32 - self.patch_size
-                || col < self.patch_size || col >= cols_i32 - self.patch_size
-            {
-                continue;
-            }
-
-            // Compute rotated BRIEF descriptor
+            // Compute rotated BRIEF descriptor. Near the border, tests falling
+            // outside the image leave their bit unset, so every keypoint keeps
+            // a descriptor at its own index
             let mut descriptor = vec![0u8; 32]; // 256 bits
 
             let cos_angle = kp.angle.cos();
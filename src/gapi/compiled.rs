@@ -0,0 +1,482 @@
+//! Execution plans for [`Graph`]s
+//!
+//! Compiling a graph prunes nodes no output depends on, fuses element-wise
+//! chains into single kernels, places every remaining node on the CPU or the
+//! GPU and assigns each intermediate result a buffer by liveness, so buffers
+//! are shared between results that are never alive together. The buffers
+//! belong to the [`CompiledGraph`] and keep their allocations across
+//! [`run`](CompiledGraph::run) calls; with a fixed frame size, frames after
+//! the first reuse them.
+
+use std::collections::HashMap;
+
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::gapi::graph::{GMat, Graph, Op};
+use crate::gapi::kernel::{Kernel, Step};
+
+/// Where a [`Graph`] may run its nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    /// Spatial nodes with a GPU kernel run on the GPU when one is available;
+    /// element-wise nodes are fused on the CPU. A node whose GPU kernel
+    /// fails moves to the CPU for good.
+    #[default]
+    Auto,
+    /// Everything on the CPU
+    Cpu,
+    /// Every node with a GPU kernel on the GPU; compiling fails without one
+    Gpu,
+}
+
+/// Where a compiled node runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Cpu,
+    Gpu,
+}
+
+/// Where a stage reads an operand from
+#[derive(Debug, Clone, Copy)]
+enum Loc {
+    Input(usize),
+    Buffer(usize),
+}
+
+#[derive(Debug)]
+enum Work {
+    /// Element-wise nodes evaluated in one pass on the CPU
+    Fused(Kernel),
+    /// A single node
+    Node(Op, Target),
+}
+
+#[derive(Debug)]
+struct Stage {
+    work: Work,
+    /// The node whose result the stage produces
+    node: usize,
+    inputs: Vec<Loc>,
+    dst: usize,
+}
+
+/// A [`Graph`] planned for repeated execution
+///
+/// Outputs are returned by reference into buffers the plan owns, and are
+/// overwritten by the next [`run`](Self::run).
+#[derive(Debug)]
+pub struct CompiledGraph {
+    graph: u32,
+    inputs: usize,
+    outputs: usize,
+    stages: Vec<Stage>,
+    /// Output buffers first, then intermediates
+    buffers: Vec<Mat>,
+    /// Stands in for a stage's destination while it is being written
+    scratch: Mat,
+    placement: Placement,
+    targets: Vec<Option<Target>>,
+}
+
+impl CompiledGraph {
+    pub(crate) fn new(graph: &Graph, placement: Placement) -> Result<Self> {
+        let gpu = gpu_available();
+        if placement == Placement::Gpu && !gpu {
+            return Err(Error::GpuNotAvailable(
+                "Placement::Gpu needs an initialized GPU context".to_string(),
+            ));
+        }
+        let nodes = &graph.nodes;
+
+        // Nodes some output depends on; operands always precede their users
+        let mut needed = vec![false; nodes.len()];
+        for &out in &graph.outputs {
+            needed[out] = true;
+        }
+        for n in (0..nodes.len()).rev() {
+            if needed[n] {
+                for operand in nodes[n].operands() {
+                    needed[operand] = true;
+                }
+            }
+        }
+
+        let targets: Vec<Option<Target>> = nodes
+            .iter()
+            .zip(&needed)
+            .map(|(op, &needed)| match op {
+                Op::Input(_) => None,
+                _ if !needed => None,
+                _ if gpu && has_gpu_kernel(op) && (placement == Placement::Gpu || !op.is_elementwise()) => {
+                    Some(Target::Gpu)
+                }
+                _ => Some(Target::Cpu),
+            })
+            .collect();
+
+        let mut consumers = vec![0usize; nodes.len()];
+        for (n, op) in nodes.iter().enumerate() {
+            if needed[n] {
+                let mut operands: Vec<usize> = op.operands().collect();
+                operands.dedup();
+                for operand in operands {
+                    consumers[operand] += 1;
+                }
+            }
+        }
+        let is_output = |n: usize| graph.outputs.contains(&n);
+        let cpu_elementwise = |n: usize| nodes[n].is_elementwise() && targets[n] == Some(Target::Cpu);
+        // Folded into its only consumer's kernel instead of materialized
+        let mut fused = vec![false; nodes.len()];
+        for (n, op) in nodes.iter().enumerate() {
+            if needed[n] && cpu_elementwise(n) {
+                for operand in op.operands() {
+                    if cpu_elementwise(operand) && consumers[operand] == 1 && !is_output(operand) {
+                        fused[operand] = true;
+                    }
+                }
+            }
+        }
+
+        let mut stages = Vec::new();
+        for n in 0..nodes.len() {
+            let Some(target) = targets[n] else { continue };
+            if fused[n] {
+                continue;
+            }
+            let (work, operands) = if cpu_elementwise(n) {
+                let mut emitter = Emitter::default();
+                emitter.emit(nodes, &fused, n);
+                (Work::Fused(Kernel::new(emitter.loads.len(), emitter.steps)), emitter.loads)
+            } else {
+                (Work::Node(nodes[n], target), nodes[n].operands().collect())
+            };
+            let inputs = operands
+                .iter()
+                .map(|&o| match nodes[o] {
+                    Op::Input(k) => Loc::Input(k),
+                    _ => Loc::Buffer(o),
+                })
+                .collect();
+            stages.push(Stage { work, node: n, inputs, dst: n });
+        }
+
+        // Buffers: outputs fixed, intermediates recycled once past their last reader
+        let mut last_read = vec![0usize; nodes.len()];
+        for (s, stage) in stages.iter().enumerate() {
+            for loc in &stage.inputs {
+                if let Loc::Buffer(node) = *loc {
+                    last_read[node] = s;
+                }
+            }
+        }
+        let mut buffer_of: HashMap<usize, usize> =
+            graph.outputs.iter().enumerate().map(|(k, &n)| (n, k)).collect();
+        let mut buffers = graph.outputs.len();
+        let mut free = Vec::new();
+        for (s, stage) in stages.iter_mut().enumerate() {
+            stage.dst = *buffer_of.entry(stage.node).or_insert_with(|| {
+                free.pop().unwrap_or_else(|| {
+                    buffers += 1;
+                    buffers - 1
+                })
+            });
+            for loc in &mut stage.inputs {
+                if let Loc::Buffer(operand) = *loc {
+                    let b = buffer_of[&operand];
+                    *loc = Loc::Buffer(b);
+                    if last_read[operand] == s && b >= graph.outputs.len() && !free.contains(&b) {
+                        free.push(b);
+                    }
+                }
+            }
+        }
+
+        let buffers = (0..buffers)
+            .map(|_| Mat::new(1, 1, 1, MatDepth::U8))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            graph: graph.id,
+            inputs: graph.inputs,
+            outputs: graph.outputs.len(),
+            stages,
+            buffers,
+            scratch: Mat::new(1, 1, 1, MatDepth::U8)?,
+            placement,
+            targets,
+        })
+    }
+
+    /// Execute the graph on one set of inputs, returning the outputs
+    pub fn run(&mut self, inputs: &[&Mat]) -> Result<&[Mat]> {
+        if inputs.len() != self.inputs {
+            return Err(Error::InvalidParameter(format!(
+                "Graph takes {} inputs, got {}",
+                self.inputs,
+                inputs.len()
+            )));
+        }
+        let Self { stages, buffers, scratch, placement, targets, .. } = self;
+        for stage in stages.iter_mut() {
+            std::mem::swap(scratch, &mut buffers[stage.dst]);
+            let srcs: Vec<&Mat> = stage
+                .inputs
+                .iter()
+                .map(|loc| match *loc {
+                    Loc::Input(k) => inputs[k],
+                    Loc::Buffer(b) => &buffers[b],
+                })
+                .collect();
+            let result = match &mut stage.work {
+                Work::Fused(kernel) => kernel.run(&srcs, scratch),
+                Work::Node(op, target) => {
+                    let mut result = run_node(op, *target, &srcs, scratch);
+                    if result.is_err() && *target == Target::Gpu && *placement == Placement::Auto {
+                        *target = Target::Cpu;
+                        targets[stage.node] = Some(Target::Cpu);
+                        result = run_node(op, Target::Cpu, &srcs, scratch);
+                    }
+                    result
+                }
+            };
+            std::mem::swap(scratch, &mut buffers[stage.dst]);
+            result?;
+        }
+        Ok(&self.buffers[..self.outputs])
+    }
+
+    /// Where `node` runs; `None` for inputs and for nodes no output needs
+    pub fn target(&self, node: GMat) -> Option<Target> {
+        if node.graph != self.graph {
+            return None;
+        }
+        self.targets.get(node.node).copied().flatten()
+    }
+
+    /// Number of passes over the image per run, after fusion
+    pub fn stage_count(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Number of element-wise nodes executed inside fused kernels
+    pub fn fused_node_count(&self) -> usize {
+        self.stages
+            .iter()
+            .map(|s| match &s.work {
+                Work::Fused(kernel) => kernel.len(),
+                Work::Node(..) => 0,
+            })
+            .sum()
+    }
+
+    /// Number of persistent images, outputs included
+    pub fn buffer_count(&self) -> usize {
+        self.buffers.len()
+    }
+}
+
+/// Builds a kernel for the fused tree rooted at a node
+#[derive(Default)]
+struct Emitter {
+    /// Nodes read from memory, in register order
+    loads: Vec<usize>,
+    steps: Vec<Step>,
+    /// Register of each node emitted so far
+    regs: HashMap<usize, usize>,
+}
+
+impl Emitter {
+    fn emit(&mut self, nodes: &[Op], fused: &[bool], root: usize) {
+        self.collect_loads(nodes, fused, root, true);
+        self.emit_steps(nodes, fused, root, true);
+    }
+
+    /// Loads come first in the register file, so find them all up front
+    fn collect_loads(&mut self, nodes: &[Op], fused: &[bool], n: usize, root: bool) {
+        if root || fused[n] {
+            for operand in nodes[n].operands() {
+                self.collect_loads(nodes, fused, operand, false);
+            }
+        } else if !self.regs.contains_key(&n) {
+            self.regs.insert(n, self.loads.len());
+            self.loads.push(n);
+        }
+    }
+
+    fn emit_steps(&mut self, nodes: &[Op], fused: &[bool], n: usize, root: bool) -> usize {
+        if let Some(&reg) = self.regs.get(&n) {
+            return reg;
+        }
+        debug_assert!(root || fused[n]);
+        let step = match nodes[n] {
+            Op::Unary(op, a) => Step::Unary(op, self.emit_steps(nodes, fused, a, false)),
+            Op::Binary(op, a, b) => {
+                let ra = self.emit_steps(nodes, fused, a, false);
+                let rb = self.emit_steps(nodes, fused, b, false);
+                Step::Binary(op, ra, rb)
+            }
+            _ => unreachable!("only element-wise nodes are fused"),
+        };
+        let reg = self.loads.len() + self.steps.len();
+        self.steps.push(step);
+        self.regs.insert(n, reg);
+        reg
+    }
+}
+
+fn run_node(op: &Op, target: Target, srcs: &[&Mat], dst: &mut Mat) -> Result<()> {
+    match target {
+        Target::Cpu => run_cpu(op, srcs, dst),
+        Target::Gpu => run_gpu(op, srcs, dst),
+    }
+}
+
+fn run_cpu(op: &Op, srcs: &[&Mat], dst: &mut Mat) -> Result<()> {
+    use crate::imgproc;
+    match *op {
+        Op::Unary(op, _) => Kernel::new(1, vec![Step::Unary(op, 0)]).run(srcs, dst),
+        Op::Binary(op, _, _) => Kernel::new(2, vec![Step::Binary(op, 0, 1)]).run(srcs, dst),
+        Op::GaussianBlur { ksize, sigma, .. } => imgproc::filter::gaussian_blur_cpu(srcs[0], dst, ksize, sigma),
+        Op::MedianBlur { ksize, .. } => imgproc::median_blur(srcs[0], dst, ksize),
+        Op::Resize { dsize, interpolation, .. } => imgproc::resize(srcs[0], dst, dsize, interpolation),
+        Op::CvtColor { code, .. } => imgproc::cvt_color(srcs[0], dst, code),
+        Op::Canny { threshold1, threshold2, .. } => imgproc::canny(srcs[0], dst, threshold1, threshold2),
+        Op::Input(_) => unreachable!("inputs are not executed"),
+    }
+}
+
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+fn gpu_available() -> bool {
+    crate::gpu::gpu_available()
+}
+
+/// GPU kernels are async-only on WASM, and `run` is synchronous
+#[cfg(not(all(feature = "gpu", not(target_arch = "wasm32"))))]
+fn gpu_available() -> bool {
+    false
+}
+
+fn has_gpu_kernel(op: &Op) -> bool {
+    use crate::core::types::ThresholdType;
+    use crate::gapi::graph::{BinaryOp, UnaryOp};
+    match op {
+        Op::GaussianBlur { ksize, .. } => ksize.width == ksize.height,
+        Op::Resize { .. } | Op::Canny { .. } => true,
+        Op::Unary(UnaryOp::Threshold { kind, .. }, _) => *kind == ThresholdType::Binary,
+        Op::Binary(op, _, _) => matches!(
+            op,
+            BinaryOp::Add | BinaryOp::Subtract | BinaryOp::AbsDiff | BinaryOp::Min | BinaryOp::Max | BinaryOp::AddWeighted { .. }
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn run_gpu(op: &Op, srcs: &[&Mat], dst: &mut Mat) -> Result<()> {
+    use crate::gapi::graph::{BinaryOp, UnaryOp};
+    use crate::gpu::ops;
+    match *op {
+        Op::GaussianBlur { ksize, sigma, .. } => ops::gaussian_blur_gpu(srcs[0], dst, ksize, sigma),
+        Op::Resize { dsize, interpolation, .. } => {
+            let width = usize::try_from(dsize.width).unwrap_or(0);
+            let height = usize::try_from(dsize.height).unwrap_or(0);
+            ops::resize_gpu_with_interpolation(srcs[0], dst, width, height, interpolation)
+        }
+        Op::Canny { threshold1, threshold2, .. } => ops::canny_gpu(srcs[0], dst, threshold1, threshold2),
+        Op::Unary(UnaryOp::Threshold { thresh, maxval, .. }, _) => ops::threshold_gpu(
+            srcs[0],
+            dst,
+            thresh.clamp(0.0, 255.0) as u8,
+            maxval.clamp(0.0, 255.0) as u8,
+        ),
+        Op::Binary(BinaryOp::Add, ..) => ops::add_gpu(srcs[0], srcs[1], dst),
+        Op::Binary(BinaryOp::Subtract, ..) => ops::subtract_gpu(srcs[0], srcs[1], dst),
+        Op::Binary(BinaryOp::AbsDiff, ..) => ops::absdiff_gpu(srcs[0], srcs[1], dst),
+        Op::Binary(BinaryOp::Min, ..) => ops::min_gpu(srcs[0], srcs[1], dst),
+        Op::Binary(BinaryOp::Max, ..) => ops::max_gpu(srcs[0], srcs[1], dst),
+        Op::Binary(BinaryOp::AddWeighted { alpha, beta, gamma }, ..) => {
+            ops::add_weighted_gpu(srcs[0], alpha, srcs[1], beta, gamma, dst)
+        }
+        _ => run_cpu(op, srcs, dst),
+    }
+}
+
+#[cfg(not(all(feature = "gpu", not(target_arch = "wasm32"))))]
+fn run_gpu(op: &Op, srcs: &[&Mat], dst: &mut Mat) -> Result<()> {
+    run_cpu(op, srcs, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ColorConversionCode, InterpolationFlag, Size, ThresholdType};
+    use crate::gapi::GraphBuilder;
+
+    fn frame(seed: usize) -> Mat {
+        let mut mat = Mat::new(24, 32, 3, MatDepth::U8).unwrap();
+        for (i, v) in mat.data_mut().iter_mut().enumerate() {
+            *v = ((i * 7 + seed * 13) % 251) as u8;
+        }
+        mat
+    }
+
+    #[test]
+    fn test_fused_graph_matches_eager_calls() {
+        let mut g = GraphBuilder::new();
+        let current = g.input();
+        let previous = g.input();
+        let gray = g.cvt_color(current, ColorConversionCode::RgbToGray);
+        let prev_gray = g.cvt_color(previous, ColorConversionCode::RgbToGray);
+        let diff = g.abs_diff(gray, prev_gray);
+        let boosted = g.convert_scale_abs(diff, 2.0, 5.0);
+        let mask = g.threshold(boosted, 40.0, 255.0, ThresholdType::Binary);
+        let small = g.resize(gray, Size::new(16, 12), InterpolationFlag::Linear);
+        let graph = g.build(&[mask, small]).unwrap();
+
+        let mut compiled = graph.compile(Placement::Cpu).unwrap();
+        // two colour conversions, one fused kernel, one resize
+        assert_eq!(compiled.stage_count(), 4);
+        assert_eq!(compiled.fused_node_count(), 3);
+        assert_eq!(compiled.target(diff), Some(Target::Cpu));
+        assert_eq!(compiled.target(current), None);
+
+        let (a, b) = (frame(1), frame(2));
+        let outputs = compiled.run(&[&a, &b]).unwrap();
+
+        let mut gray_a = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let mut gray_b = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::imgproc::cvt_color(&a, &mut gray_a, ColorConversionCode::RgbToGray).unwrap();
+        crate::imgproc::cvt_color(&b, &mut gray_b, ColorConversionCode::RgbToGray).unwrap();
+        let mut d = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::core::abs_diff(&gray_a, &gray_b, &mut d).unwrap();
+        let mut s = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::core::convert_scale_abs(&d, &mut s, 2.0, 5.0).unwrap();
+        let mut expected_mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::imgproc::threshold(&s, &mut expected_mask, 40.0, 255.0, ThresholdType::Binary).unwrap();
+        let mut expected_small = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::imgproc::resize(&gray_a, &mut expected_small, Size::new(16, 12), InterpolationFlag::Linear).unwrap();
+
+        assert_eq!(outputs[0].data(), expected_mask.data());
+        assert_eq!(outputs[1].data(), expected_small.data());
+        assert!(compiled.run(&[&a]).is_err());
+    }
+
+    #[test]
+    fn test_buffers_recycled_and_kept_across_runs() {
+        // A chain of five blurs needs two intermediates, not four
+        let mut g = GraphBuilder::new();
+        let mut node = g.input();
+        for _ in 0..5 {
+            node = g.gaussian_blur(node, Size::new(3, 3), 0.8);
+        }
+        let graph = g.build(&[node]).unwrap();
+        let mut compiled = graph.compile(Placement::Cpu).unwrap();
+        assert_eq!(compiled.buffer_count(), 3);
+
+        let (a, b) = (frame(3), frame(4));
+        let first = compiled.run(&[&a]).unwrap()[0].data().as_ptr();
+        let second = compiled.run(&[&b]).unwrap()[0].data().as_ptr();
+        assert_eq!(first, second);
+    }
+}
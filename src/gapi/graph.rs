@@ -0,0 +1,298 @@
+//! Symbolic graph construction
+
+use crate::core::types::{ColorConversionCode, InterpolationFlag, Size, ThresholdType};
+use crate::error::{Error, Result};
+use crate::gapi::compiled::{CompiledGraph, Placement};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static NEXT_GRAPH_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Symbolic image: the result of a node in a [`GraphBuilder`]
+///
+/// Only valid with the builder that created it and the graph built from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GMat {
+    pub(crate) graph: u32,
+    pub(crate) node: usize,
+}
+
+/// Per-pixel operation on one image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UnaryOp {
+    ConvertScaleAbs { alpha: f64, beta: f64 },
+    Threshold { thresh: f64, maxval: f64, kind: ThresholdType },
+    BitwiseNot,
+}
+
+/// Per-pixel operation on two images of the same shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BinaryOp {
+    Add,
+    Subtract,
+    AbsDiff,
+    Min,
+    Max,
+    AddWeighted { alpha: f64, beta: f64, gamma: f64 },
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+}
+
+/// A graph node; operands are indices of earlier nodes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Op {
+    Input(usize),
+    Unary(UnaryOp, usize),
+    Binary(BinaryOp, usize, usize),
+    GaussianBlur { src: usize, ksize: Size, sigma: f64 },
+    MedianBlur { src: usize, ksize: i32 },
+    Resize { src: usize, dsize: Size, interpolation: InterpolationFlag },
+    CvtColor { src: usize, code: ColorConversionCode },
+    Canny { src: usize, threshold1: f64, threshold2: f64 },
+}
+
+impl Op {
+    pub(crate) fn operands(&self) -> impl Iterator<Item = usize> {
+        let (a, b) = match *self {
+            Op::Input(_) => (None, None),
+            Op::Binary(_, a, b) => (Some(a), Some(b)),
+            Op::Unary(_, src)
+            | Op::GaussianBlur { src, .. }
+            | Op::MedianBlur { src, .. }
+            | Op::Resize { src, .. }
+            | Op::CvtColor { src, .. }
+            | Op::Canny { src, .. } => (Some(src), None),
+        };
+        a.into_iter().chain(b)
+    }
+
+    /// Whether each output pixel depends only on the same pixel of the inputs
+    pub(crate) fn is_elementwise(&self) -> bool {
+        matches!(self, Op::Unary(..) | Op::Binary(..))
+    }
+}
+
+/// Declares a computation on symbolic [`GMat`]s
+///
+/// Nothing runs while building; [`build`](Self::build) fixes the outputs and
+/// [`Graph::compile`] plans the execution. Operations mirror the eager
+/// functions of the same name and, like them, take and produce U8 images.
+#[derive(Debug)]
+pub struct GraphBuilder {
+    id: u32,
+    nodes: Vec<Op>,
+    inputs: usize,
+    foreign: bool,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_GRAPH_ID.fetch_add(1, Ordering::Relaxed),
+            nodes: Vec::new(),
+            inputs: 0,
+            foreign: false,
+        }
+    }
+
+    /// Declare the next input; inputs are supplied to
+    /// [`CompiledGraph::run`] in declaration order
+    pub fn input(&mut self) -> GMat {
+        self.inputs += 1;
+        self.push(Op::Input(self.inputs - 1))
+    }
+
+    pub fn gaussian_blur(&mut self, src: GMat, ksize: Size, sigma: f64) -> GMat {
+        let src = self.resolve(src);
+        self.push(Op::GaussianBlur { src, ksize, sigma })
+    }
+
+    pub fn median_blur(&mut self, src: GMat, ksize: i32) -> GMat {
+        let src = self.resolve(src);
+        self.push(Op::MedianBlur { src, ksize })
+    }
+
+    pub fn resize(&mut self, src: GMat, dsize: Size, interpolation: InterpolationFlag) -> GMat {
+        let src = self.resolve(src);
+        self.push(Op::Resize { src, dsize, interpolation })
+    }
+
+    pub fn cvt_color(&mut self, src: GMat, code: ColorConversionCode) -> GMat {
+        let src = self.resolve(src);
+        self.push(Op::CvtColor { src, code })
+    }
+
+    pub fn canny(&mut self, src: GMat, threshold1: f64, threshold2: f64) -> GMat {
+        let src = self.resolve(src);
+        self.push(Op::Canny { src, threshold1, threshold2 })
+    }
+
+    pub fn threshold(&mut self, src: GMat, thresh: f64, maxval: f64, kind: ThresholdType) -> GMat {
+        self.unary(src, UnaryOp::Threshold { thresh, maxval, kind })
+    }
+
+    pub fn convert_scale_abs(&mut self, src: GMat, alpha: f64, beta: f64) -> GMat {
+        self.unary(src, UnaryOp::ConvertScaleAbs { alpha, beta })
+    }
+
+    pub fn bitwise_not(&mut self, src: GMat) -> GMat {
+        self.unary(src, UnaryOp::BitwiseNot)
+    }
+
+    pub fn add(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::Add)
+    }
+
+    pub fn subtract(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::Subtract)
+    }
+
+    pub fn abs_diff(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::AbsDiff)
+    }
+
+    pub fn min(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::Min)
+    }
+
+    pub fn max(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::Max)
+    }
+
+    pub fn add_weighted(&mut self, a: GMat, alpha: f64, b: GMat, beta: f64, gamma: f64) -> GMat {
+        self.binary(a, b, BinaryOp::AddWeighted { alpha, beta, gamma })
+    }
+
+    pub fn bitwise_and(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::BitwiseAnd)
+    }
+
+    pub fn bitwise_or(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::BitwiseOr)
+    }
+
+    pub fn bitwise_xor(&mut self, a: GMat, b: GMat) -> GMat {
+        self.binary(a, b, BinaryOp::BitwiseXor)
+    }
+
+    /// Finish the graph with the given outputs, in the order
+    /// [`CompiledGraph::run`] returns them
+    ///
+    /// Fails if an output is repeated or is an input, or if any [`GMat`]
+    /// used came from another builder.
+    pub fn build(self, outputs: &[GMat]) -> Result<Graph> {
+        if self.foreign || outputs.iter().any(|m| m.graph != self.id) {
+            return Err(Error::InvalidParameter(
+                "GMat belongs to a different graph".to_string(),
+            ));
+        }
+        if outputs.is_empty() {
+            return Err(Error::InvalidParameter("Graph needs at least one output".to_string()));
+        }
+        for (i, m) in outputs.iter().enumerate() {
+            if matches!(self.nodes[m.node], Op::Input(_)) {
+                return Err(Error::InvalidParameter(format!("Output {i} is a graph input")));
+            }
+            if outputs[..i].contains(m) {
+                return Err(Error::InvalidParameter(format!("Output {i} is listed twice")));
+            }
+        }
+        Ok(Graph {
+            id: self.id,
+            nodes: self.nodes,
+            inputs: self.inputs,
+            outputs: outputs.iter().map(|m| m.node).collect(),
+        })
+    }
+
+    fn unary(&mut self, src: GMat, op: UnaryOp) -> GMat {
+        let src = self.resolve(src);
+        self.push(Op::Unary(op, src))
+    }
+
+    fn binary(&mut self, a: GMat, b: GMat, op: BinaryOp) -> GMat {
+        let a = self.resolve(a);
+        let b = self.resolve(b);
+        self.push(Op::Binary(op, a, b))
+    }
+
+    fn push(&mut self, op: Op) -> GMat {
+        self.nodes.push(op);
+        GMat { graph: self.id, node: self.nodes.len() - 1 }
+    }
+
+    /// Node index of `m`; a foreign handle is remembered and fails `build`
+    fn resolve(&mut self, m: GMat) -> usize {
+        if m.graph == self.id {
+            m.node
+        } else {
+            self.foreign = true;
+            0
+        }
+    }
+}
+
+impl Default for GraphBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A finished computation, ready to [`compile`](Self::compile)
+#[derive(Debug, Clone)]
+pub struct Graph {
+    pub(crate) id: u32,
+    pub(crate) nodes: Vec<Op>,
+    pub(crate) inputs: usize,
+    pub(crate) outputs: Vec<usize>,
+}
+
+impl Graph {
+    pub fn input_count(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn output_count(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Number of declared nodes, inputs included
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Plan fusion, placement and buffers for repeated execution
+    pub fn compile(&self, placement: Placement) -> Result<CompiledGraph> {
+        CompiledGraph::new(self, placement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_rejects_bad_outputs() {
+        let mut g = GraphBuilder::new();
+        let input = g.input();
+        let blurred = g.gaussian_blur(input, Size::new(3, 3), 1.0);
+        let graph = g.build(&[blurred]).unwrap();
+        assert_eq!((graph.input_count(), graph.output_count(), graph.node_count()), (1, 1, 2));
+
+        let mut g = GraphBuilder::new();
+        let input = g.input();
+        assert!(g.build(&[input]).is_err());
+
+        let mut g = GraphBuilder::new();
+        let input = g.input();
+        let out = g.bitwise_not(input);
+        assert!(g.build(&[out, out]).is_err());
+
+        let mut other = GraphBuilder::new();
+        let stranger = other.input();
+        let mut g = GraphBuilder::new();
+        let input = g.input();
+        let out = g.add(input, stranger);
+        assert!(g.build(&[out]).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Fused per-pixel kernels
+//!
+//! A chain of element-wise nodes becomes one [`Kernel`]: a short register
+//! program evaluated once per byte, so intermediate images are never
+//! written. Every step rounds and saturates exactly like the eager function
+//! it replaces, which keeps fused and unfused results identical.
+
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+use crate::core::parallel::parallel_for_rows;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::gapi::graph::{BinaryOp, UnaryOp};
+use crate::core::types::ThresholdType;
+
+/// One instruction; operands index the registers, which hold the loaded
+/// inputs followed by the results of earlier steps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Step {
+    Unary(UnaryOp, usize),
+    Binary(BinaryOp, usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Kernel {
+    loads: usize,
+    steps: Vec<Step>,
+}
+
+impl Kernel {
+    pub(crate) fn new(loads: usize, steps: Vec<Step>) -> Self {
+        Self { loads, steps }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Evaluate into `dst` for `inputs`, which must all share one shape
+    pub(crate) fn run(&self, inputs: &[&Mat], dst: &mut Mat) -> Result<()> {
+        let first = inputs[0];
+        for src in inputs {
+            if src.rows() != first.rows() || src.cols() != first.cols() {
+                return Err(Error::InvalidDimensions(
+                    "Matrices must have same dimensions".to_string(),
+                ));
+            }
+            if src.channels() != first.channels() {
+                return Err(Error::InvalidParameter(
+                    "Matrices must have same number of channels".to_string(),
+                ));
+            }
+            if src.depth() != MatDepth::U8 {
+                return Err(Error::UnsupportedOperation(
+                    "Element-wise graph nodes only support U8 depth".to_string(),
+                ));
+            }
+        }
+
+        let row_len = first.cols() * first.channels();
+        dst.create(first.rows(), first.cols(), first.channels(), MatDepth::U8)?;
+        dst.set_color_order(first.color_order());
+        let out_reg = self.loads + self.steps.len() - 1;
+        parallel_for_rows(dst.data_mut(), row_len, |row, out| {
+            let start = row * row_len;
+            let mut regs = vec![0u8; out_reg + 1];
+            for (i, o) in out.iter_mut().enumerate() {
+                for (reg, src) in regs.iter_mut().zip(inputs) {
+                    *reg = src.data()[start + i];
+                }
+                for (s, step) in self.steps.iter().enumerate() {
+                    regs[self.loads + s] = match *step {
+                        Step::Unary(op, a) => unary(op, regs[a]),
+                        Step::Binary(op, a, b) => binary(op, regs[a], regs[b]),
+                    };
+                }
+                *o = regs[out_reg];
+            }
+            Ok(())
+        })
+    }
+}
+
+fn unary(op: UnaryOp, v: u8) -> u8 {
+    match op {
+        // as convert_scale_abs: truncating after the clamp
+        UnaryOp::ConvertScaleAbs { alpha, beta } => (alpha * f64::from(v) + beta).abs().clamp(0.0, 255.0) as u8,
+        // as threshold: both levels are clamped and truncated to U8 first
+        UnaryOp::Threshold { thresh, maxval, kind } => {
+            let t = thresh.clamp(0.0, 255.0) as u8;
+            let m = maxval.clamp(0.0, 255.0) as u8;
+            let above = v > t;
+            match kind {
+                ThresholdType::Binary => if above { m } else { 0 },
+                ThresholdType::BinaryInv => if above { 0 } else { m },
+                ThresholdType::Trunc => if above { t } else { v },
+                ThresholdType::ToZero => if above { v } else { 0 },
+                ThresholdType::ToZeroInv => if above { 0 } else { v },
+            }
+        }
+        UnaryOp::BitwiseNot => !v,
+    }
+}
+
+fn binary(op: BinaryOp, a: u8, b: u8) -> u8 {
+    match op {
+        BinaryOp::Add => a.saturating_add(b),
+        BinaryOp::Subtract => a.saturating_sub(b),
+        BinaryOp::AbsDiff => a.abs_diff(b),
+        BinaryOp::Min => a.min(b),
+        BinaryOp::Max => a.max(b),
+        BinaryOp::AddWeighted { alpha, beta, gamma } => {
+            (alpha * f64::from(a) + beta * f64::from(b) + gamma).clamp(0.0, 255.0) as u8
+        }
+        BinaryOp::BitwiseAnd => a & b,
+        BinaryOp::BitwiseOr => a | b,
+        BinaryOp::BitwiseXor => a ^ b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_matches_eager_ops() {
+        let mut a = Mat::new(4, 5, 2, MatDepth::U8).unwrap();
+        let mut b = Mat::new(4, 5, 2, MatDepth::U8).unwrap();
+        for (i, (x, y)) in a.data_mut().iter_mut().zip(b.data_mut()).enumerate() {
+            *x = (i * 37 % 256) as u8;
+            *y = (i * 91 % 256) as u8;
+        }
+
+        // |a - b| * 1.7 - 20, then a weighted blend with b
+        let kernel = Kernel::new(2, vec![
+            Step::Binary(BinaryOp::AbsDiff, 0, 1),
+            Step::Unary(UnaryOp::ConvertScaleAbs { alpha: 1.7, beta: -20.0 }, 2),
+            Step::Binary(BinaryOp::AddWeighted { alpha: 0.6, beta: 0.4, gamma: 3.0 }, 3, 1),
+        ]);
+        let mut fused = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        kernel.run(&[&a, &b], &mut fused).unwrap();
+
+        let mut diff = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::core::abs_diff(&a, &b, &mut diff).unwrap();
+        let mut scaled = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::core::convert_scale_abs(&diff, &mut scaled, 1.7, -20.0).unwrap();
+        let mut expected = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        crate::core::add_weighted(&scaled, 0.6, &b, 0.4, 3.0, &mut expected).unwrap();
+        assert_eq!(fused.data(), expected.data());
+
+        let small = Mat::new(2, 5, 2, MatDepth::U8).unwrap();
+        assert!(kernel.run(&[&a, &small], &mut fused).is_err());
+    }
+}
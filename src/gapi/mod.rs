@@ -0,0 +1,38 @@
+//! Graph API: declare a pipeline once, run it on every frame
+//!
+//! Modelled on OpenCV's G-API. A [`GraphBuilder`] records operations on
+//! symbolic [`GMat`]s without running anything; [`Graph::compile`] turns the
+//! result into a [`CompiledGraph`] that fuses chains of element-wise nodes
+//! into single passes, places nodes on the CPU or GPU according to a
+//! [`Placement`], and keeps its intermediate buffers between frames.
+//!
+//! ```rust,no_run
+//! use opencv_rust::core::types::{ColorConversionCode, Size, ThresholdType};
+//! use opencv_rust::gapi::{GraphBuilder, Placement};
+//! # fn main() -> opencv_rust::error::Result<()> {
+//! # let (frame, previous) = (opencv_rust::core::Mat::new(1, 1, 3, opencv_rust::core::MatDepth::U8)?, opencv_rust::core::Mat::new(1, 1, 3, opencv_rust::core::MatDepth::U8)?);
+//! let mut g = GraphBuilder::new();
+//! let current = g.input();
+//! let last = g.input();
+//! let gray = g.cvt_color(current, ColorConversionCode::RgbToGray);
+//! let last_gray = g.cvt_color(last, ColorConversionCode::RgbToGray);
+//! let smooth = g.gaussian_blur(gray, Size::new(5, 5), 1.2);
+//! let diff = g.abs_diff(smooth, last_gray);
+//! let motion = g.threshold(diff, 25.0, 255.0, ThresholdType::Binary);
+//! let mut pipeline = g.build(&[motion])?.compile(Placement::Auto)?;
+//!
+//! let mask = &pipeline.run(&[&frame, &previous])?[0];
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! GPU placement needs the `gpu` feature and an initialized context, and is
+//! native-only: `run` is synchronous and the WASM GPU kernels are async.
+//! GPU kernels may round differently from their CPU counterparts.
+
+pub mod compiled;
+pub mod graph;
+mod kernel;
+
+pub use compiled::{CompiledGraph, Placement, Target};
+pub use graph::{GMat, Graph, GraphBuilder};
@@ -102,6 +102,39 @@ impl GpuContext {
         true
     }
 
+    /// Adopt a device the application already created (native)
+    ///
+    /// Ops then run on the application's device, so its buffers and textures
+    /// can be wrapped in a [`GpuMat`](super::GpuMat) without copies. Returns
+    /// false if a context was already initialized.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn init_with_device(adapter: wgpu::Adapter, device: wgpu::Device, queue: wgpu::Queue) -> bool {
+        pollster::block_on(Self::init_with_device_async(adapter, device, queue))
+    }
+
+    /// Adopt a device the application already created (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn init_with_device_async(adapter: wgpu::Adapter, device: wgpu::Device, queue: wgpu::Queue) -> bool {
+        if GPU_CONTEXT.get().is_some() {
+            return false;
+        }
+
+        PipelineCache::init_async(&device).await;
+        GPU_CONTEXT.set(Some(GpuContext { device, queue, adapter })).is_ok()
+    }
+
+    /// Adopt a device the application already created (WASM)
+    #[cfg(target_arch = "wasm32")]
+    pub async fn init_with_device_async(adapter: wgpu::Adapter, device: wgpu::Device, queue: wgpu::Queue) -> bool {
+        if GPU_CONTEXT.with(|ctx| ctx.borrow().is_some()) {
+            return false;
+        }
+
+        PipelineCache::init_async(&device).await;
+        GPU_CONTEXT.with(|context| *context.borrow_mut() = Some(GpuContext { device, queue, adapter }));
+        true
+    }
+
     /// Initialize GPU context asynchronously for WASM
     #[cfg(target_arch = "wasm32")]
     pub async fn init_async() -> bool {
@@ -214,6 +247,7 @@ impl GpuContext {
     where
         F: FnOnce(&GpuContext) -> R,
     {
+        let _scope = crate::core::AllocScope::Gpu.enter();
         Self::get().map(f)
     }
 
@@ -223,6 +257,7 @@ impl GpuContext {
     where
         F: FnOnce(&GpuContext) -> R,
     {
+        let _scope = crate::core::AllocScope::Gpu.enter();
         Self::with_context(f)
     }
 }
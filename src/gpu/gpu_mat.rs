@@ -0,0 +1,368 @@
+#![allow(clippy::cast_possible_truncation)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::gpu::device::GpuContext;
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// `wgpu` requires multi-row texture copies to use this row pitch
+const ROW_PITCH_ALIGNMENT: usize = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+
+/// An image held in a GPU storage buffer
+///
+/// Pixels are tightly packed in row-major order, exactly like [`Mat::data`],
+/// so the buffer can be bound by opencv-rust kernels and application shaders
+/// alike. A `GpuMat` wraps an existing `wgpu::Buffer` without copying, and
+/// converts to and from `wgpu::Texture`s with GPU-side copies, so frames
+/// rendered with wgpu never have to visit the CPU. All objects must belong
+/// to the device of the `GpuContext` passed in; see
+/// [`GpuContext::init_with_device`] for sharing the application's device.
+#[derive(Debug)]
+pub struct GpuMat {
+    buffer: wgpu::Buffer,
+    rows: usize,
+    cols: usize,
+    channels: usize,
+    depth: MatDepth,
+}
+
+impl GpuMat {
+    /// Wrap an existing buffer, without copying
+    ///
+    /// The buffer must hold at least [`byte_len`](Self::byte_len) bytes
+    /// rounded up to a multiple of 4. Give it `COPY_SRC` usage to
+    /// [`download`](Self::download) or export it, `STORAGE` to bind it in
+    /// compute passes.
+    pub fn from_buffer(buffer: wgpu::Buffer, rows: usize, cols: usize, channels: usize, depth: MatDepth) -> Result<Self> {
+        if rows == 0 || cols == 0 || channels == 0 {
+            return Err(Error::InvalidDimensions(
+                "GpuMat dimensions must be non-zero".to_string(),
+            ));
+        }
+
+        let mat = Self { buffer, rows, cols, channels, depth };
+        if mat.buffer.size() < mat.padded_len() {
+            return Err(Error::InvalidDimensions(format!(
+                "Buffer of {} bytes is too small for a {}x{}x{} image",
+                mat.buffer.size(),
+                rows,
+                cols,
+                channels
+            )));
+        }
+        Ok(mat)
+    }
+
+    /// Copy a CPU image into a new GPU buffer
+    pub fn upload(ctx: &GpuContext, mat: &Mat) -> Result<Self> {
+        let mut contents = mat.data().to_vec();
+        contents.resize(mat.data().len().div_ceil(4) * 4, 0);
+
+        let buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("GpuMat Buffer"),
+            contents: &contents,
+            usage: Self::buffer_usages(),
+        });
+        Self::from_buffer(buffer, mat.rows(), mat.cols(), mat.channels(), mat.depth())
+    }
+
+    /// Copy mip level 0 of a 2D texture into a new GPU buffer
+    ///
+    /// The texture needs `COPY_SRC` usage and an 8-bit, 16-bit integer or
+    /// 32-bit float color format. Channels keep their stored order, so a
+    /// `Bgra8Unorm` surface texture yields BGRA pixels.
+    pub fn from_texture(ctx: &GpuContext, texture: &wgpu::Texture) -> Result<Self> {
+        let (channels, depth) = texture_pixel_format(texture)?;
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(Error::InvalidParameter(
+                "Texture needs COPY_SRC usage to be read into a GpuMat".to_string(),
+            ));
+        }
+
+        let rows = texture.height() as usize;
+        let cols = texture.width() as usize;
+        let row_bytes = cols * channels * depth.size();
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuMat Buffer"),
+            size: ((rows * row_bytes).div_ceil(4) * 4) as u64,
+            usage: Self::buffer_usages(),
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("GpuMat From Texture Encoder"),
+        });
+        for region in copy_regions(rows, row_bytes) {
+            encoder.copy_texture_to_buffer(
+                region.texture(texture),
+                region.buffer(&buffer),
+                region.extent(cols),
+            );
+        }
+        ctx.queue.submit(Some(encoder.finish()));
+
+        Self::from_buffer(buffer, rows, cols, channels, depth)
+    }
+
+    /// Copy the image into mip level 0 of a 2D texture
+    ///
+    /// The texture must match the image size, have a format with the same
+    /// channel count and depth (see [`from_texture`](Self::from_texture)) and
+    /// `COPY_DST` usage.
+    pub fn copy_to_texture(&self, ctx: &GpuContext, texture: &wgpu::Texture) -> Result<()> {
+        let (channels, depth) = texture_pixel_format(texture)?;
+        if channels != self.channels || depth != self.depth {
+            return Err(Error::InvalidParameter(format!(
+                "Texture format {:?} does not match a {}-channel {:?} image",
+                texture.format(),
+                self.channels,
+                self.depth
+            )));
+        }
+
+        if texture.width() as usize != self.cols || texture.height() as usize != self.rows {
+            return Err(Error::InvalidDimensions(
+                "Texture size must match the GpuMat size".to_string(),
+            ));
+        }
+
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_DST) {
+            return Err(Error::InvalidParameter(
+                "Texture needs COPY_DST usage to receive a GpuMat".to_string(),
+            ));
+        }
+        self.require_copy_src()?;
+
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("GpuMat To Texture Encoder"),
+        });
+        for region in copy_regions(self.rows, self.row_bytes()) {
+            encoder.copy_buffer_to_texture(
+                region.buffer(&self.buffer),
+                region.texture(texture),
+                region.extent(self.cols),
+            );
+        }
+        ctx.queue.submit(Some(encoder.finish()));
+        Ok(())
+    }
+
+    /// Read the image back to the CPU
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn download(&self, ctx: &GpuContext) -> Result<Mat> {
+        pollster::block_on(self.download_async(ctx))
+    }
+
+    /// Read the image back to the CPU
+    pub async fn download_async(&self, ctx: &GpuContext) -> Result<Mat> {
+        self.require_copy_src()?;
+
+        let staging_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuMat Staging Buffer"),
+            size: self.padded_len(),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("GpuMat Download Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging_buffer, 0, self.padded_len());
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let buffer_slice = staging_buffer.slice(..);
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+
+        // Native backends only run map callbacks while the device is polled
+        #[cfg(not(target_arch = "wasm32"))]
+        ctx.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| Error::GpuError(format!("Device poll failed: {e:?}")))?;
+
+        receiver
+            .await
+            .map_err(|_| Error::GpuError("Failed to receive buffer mapping result".to_string()))?
+            .map_err(|e| Error::GpuError(format!("Buffer mapping failed: {e:?}")))?;
+
+        let mat = {
+            let data = buffer_slice.get_mapped_range();
+            Mat::from_slice(&data[..self.byte_len()], self.rows, self.cols, self.channels, self.depth)?
+        };
+        staging_buffer.unmap();
+        Ok(mat)
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[must_use]
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    #[must_use]
+    pub fn depth(&self) -> MatDepth {
+        self.depth
+    }
+
+    /// Number of bytes the pixels occupy
+    #[must_use]
+    pub fn byte_len(&self) -> usize {
+        self.rows * self.row_bytes()
+    }
+
+    /// The underlying buffer, for binding in application passes
+    #[must_use]
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Give the buffer back to the application
+    #[must_use]
+    pub fn into_buffer(self) -> wgpu::Buffer {
+        self.buffer
+    }
+
+    fn buffer_usages() -> wgpu::BufferUsages {
+        wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST
+    }
+
+    fn row_bytes(&self) -> usize {
+        self.cols * self.channels * self.depth.size()
+    }
+
+    fn padded_len(&self) -> u64 {
+        (self.byte_len().div_ceil(4) * 4) as u64
+    }
+
+    fn require_copy_src(&self) -> Result<()> {
+        if self.buffer.usage().contains(wgpu::BufferUsages::COPY_SRC) {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter(
+                "GpuMat buffer needs COPY_SRC usage".to_string(),
+            ))
+        }
+    }
+}
+
+/// Channel count and depth of the pixels in a 2D texture
+fn texture_pixel_format(texture: &wgpu::Texture) -> Result<(usize, MatDepth)> {
+    use wgpu::TextureFormat as F;
+
+    if texture.dimension() != wgpu::TextureDimension::D2 || texture.sample_count() != 1 {
+        return Err(Error::UnsupportedOperation(
+            "GpuMat only exchanges data with single-sampled 2D textures".to_string(),
+        ));
+    }
+
+    match texture.format() {
+        F::R8Unorm | F::R8Uint => Ok((1, MatDepth::U8)),
+        F::Rg8Unorm | F::Rg8Uint => Ok((2, MatDepth::U8)),
+        F::Rgba8Unorm | F::Rgba8UnormSrgb | F::Rgba8Uint | F::Bgra8Unorm | F::Bgra8UnormSrgb => {
+            Ok((4, MatDepth::U8))
+        }
+        F::R16Uint => Ok((1, MatDepth::U16)),
+        F::Rg16Uint => Ok((2, MatDepth::U16)),
+        F::Rgba16Uint => Ok((4, MatDepth::U16)),
+        F::R32Float => Ok((1, MatDepth::F32)),
+        F::Rg32Float => Ok((2, MatDepth::F32)),
+        F::Rgba32Float => Ok((4, MatDepth::F32)),
+        other => Err(Error::UnsupportedOperation(format!(
+            "Texture format {other:?} has no GpuMat equivalent"
+        ))),
+    }
+}
+
+/// A block of texture rows copied in one command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CopyRegion {
+    offset: u64,
+    first_row: u32,
+    rows: u32,
+    bytes_per_row: Option<u32>,
+}
+
+impl CopyRegion {
+    fn texture(self, texture: &wgpu::Texture) -> wgpu::TexelCopyTextureInfo<'_> {
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x: 0, y: self.first_row, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        }
+    }
+
+    fn buffer(self, buffer: &wgpu::Buffer) -> wgpu::TexelCopyBufferInfo<'_> {
+        wgpu::TexelCopyBufferInfo {
+            buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: self.offset,
+                bytes_per_row: self.bytes_per_row,
+                rows_per_image: None,
+            },
+        }
+    }
+
+    fn extent(self, cols: usize) -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width: cols as u32,
+            height: self.rows,
+            depth_or_array_layers: 1,
+        }
+    }
+}
+
+/// Split a packed image into texture copies
+///
+/// Multi-row copies need a 256-byte aligned row pitch; packed rows of any
+/// other width are copied one at a time instead of going through a padded
+/// intermediate buffer.
+fn copy_regions(rows: usize, row_bytes: usize) -> Vec<CopyRegion> {
+    if row_bytes.is_multiple_of(ROW_PITCH_ALIGNMENT) {
+        return vec![CopyRegion {
+            offset: 0,
+            first_row: 0,
+            rows: rows as u32,
+            bytes_per_row: Some(row_bytes as u32),
+        }];
+    }
+
+    (0..rows)
+        .map(|row| CopyRegion {
+            offset: (row * row_bytes) as u64,
+            first_row: row as u32,
+            rows: 1,
+            bytes_per_row: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_regions() {
+        // 64 RGBA pixels = 256 bytes per row: one copy
+        let regions = copy_regions(10, 256);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].rows, 10);
+        assert_eq!(regions[0].bytes_per_row, Some(256));
+
+        // Unaligned rows are copied individually from their packed offsets
+        let regions = copy_regions(3, 30);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[2], CopyRegion { offset: 60, first_row: 2, rows: 1, bytes_per_row: None });
+    }
+}
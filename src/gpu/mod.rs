@@ -20,6 +20,13 @@
 //!     .execute(&image)?;
 //! # Ok::<(), opencv_rust::error::Error>(())
 //! ```
+//!
+//! # Sharing a wgpu device
+//!
+//! Applications that already render with wgpu can hand their device to
+//! [`GpuContext::init_with_device`] and exchange frames through
+//! [`GpuMat`], which wraps their buffers directly and copies to and from
+//! textures on the GPU.
 
 pub mod device;
 pub mod batch;
@@ -27,9 +34,13 @@ pub mod pipeline_cache;
 
 #[cfg(feature = "gpu")]
 pub mod ops;
+#[cfg(feature = "gpu")]
+pub mod gpu_mat;
 
 #[cfg(feature = "gpu")]
 pub use device::GpuContext;
+#[cfg(feature = "gpu")]
+pub use gpu_mat::GpuMat;
 
 pub use batch::GpuBatch;
 pub use pipeline_cache::PipelineCache;
@@ -38,7 +38,7 @@ pub async fn add_weighted_gpu_async(
         ));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
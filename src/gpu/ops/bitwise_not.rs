@@ -20,7 +20,7 @@ pub async fn bitwise_not_gpu_async(src: &Mat, dst: &mut Mat) -> Result<()> {
         return Err(Error::UnsupportedOperation("GPU bitwise_not only supports U8 depth".to_string()));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -23,7 +23,7 @@ pub async fn bitwise_xor_gpu_async(src1: &Mat, src2: &Mat, dst: &mut Mat) -> Res
         return Err(Error::InvalidParameter("Input images must have same dimensions and channels".to_string()));
     }
 
-    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?;
+    *dst = Mat::new(src1.rows(), src1.cols(), src1.channels(), src1.depth())?.with_color_order(src1.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -40,7 +40,7 @@ pub async fn gaussian_blur_gpu_async(src: &Mat, dst: &mut Mat, ksize: Size, sigm
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let kernel_size = usize::try_from(ksize.width).unwrap_or(0);
     let kernel_weights = create_gaussian_kernel(kernel_size, sigma);
@@ -28,7 +28,7 @@ pub async fn box_blur_gpu_async(src: &Mat, dst: &mut Mat, ksize: i32) -> Result<
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
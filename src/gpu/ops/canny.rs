@@ -32,7 +32,7 @@ pub async fn canny_gpu_async(
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     execute_canny(src, dst, low_threshold, high_threshold).await
 }
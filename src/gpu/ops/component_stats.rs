@@ -0,0 +1,244 @@
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::gpu::device::GpuContext;
+use crate::imgproc::components::{stats_from_sums, ComponentStats};
+use wgpu;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct StatsParams {
+    width: u32,
+    height: u32,
+    num_labels: u32,
+    _pad: u32,
+}
+
+/// u32 slots per label in `component_stats.wgsl`
+const STRIDE: usize = 9;
+
+/// Per-label statistics of an S32 label image on the GPU (async version)
+///
+/// Same result as `imgproc::component_stats`: area, bounding box and
+/// centroid for labels `0..num_labels`, ignoring other values. Only
+/// `num_labels` small records are read back, not the image, so blob
+/// tracking on high-resolution masks stays cheap on the CPU side.
+pub async fn component_stats_gpu_async(labels: &Mat, num_labels: usize) -> Result<Vec<ComponentStats>> {
+    if labels.channels() != 1 || labels.depth() != MatDepth::S32 {
+        return Err(Error::InvalidParameter(
+            "Component statistics require 1-channel S32 labels".to_string(),
+        ));
+    }
+
+    // Coordinates are summed per 16x16 tile in u32
+    let limit = 1 << 24;
+    if labels.rows() >= limit || labels.cols() >= limit {
+        return Err(Error::InvalidDimensions(
+            "Label image is too large for GPU component statistics".to_string(),
+        ));
+    }
+    let Ok(label_count) = u32::try_from(num_labels) else {
+        return Err(Error::InvalidParameter(
+            "Too many labels for GPU component statistics".to_string(),
+        ));
+    };
+    if num_labels == 0 {
+        return Ok(Vec::new());
+    }
+
+    let params = StatsParams {
+        width: labels.cols() as u32,
+        height: labels.rows() as u32,
+        num_labels: label_count,
+        _pad: 0,
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let (device, queue, adapter) = GpuContext::with_gpu(|ctx| {
+            (ctx.device.clone(), ctx.queue.clone(), ctx.adapter.clone())
+        })
+        .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        let temp_ctx = GpuContext { device, queue, adapter };
+        return execute_component_stats_impl(&temp_ctx, labels, params).await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let ctx = GpuContext::get()
+            .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        return execute_component_stats_impl(ctx, labels, params).await;
+    }
+}
+
+/// Per-label statistics of an S32 label image on the GPU (sync wrapper for native)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn component_stats_gpu(labels: &Mat, num_labels: usize) -> Result<Vec<ComponentStats>> {
+    pollster::block_on(component_stats_gpu_async(labels, num_labels))
+}
+
+async fn execute_component_stats_impl(
+    ctx: &GpuContext,
+    labels: &Mat,
+    params: StatsParams,
+) -> Result<Vec<ComponentStats>> {
+    let num_labels = params.num_labels as usize;
+    if labels.total() == 0 {
+        return Ok(vec![stats_from_sums(0, 0, 0, [0; 4]); num_labels]);
+    }
+
+    let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Component Stats Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/component_stats.wgsl").into()),
+    });
+
+    let labels_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Labels Buffer"),
+        contents: labels.data(),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let params_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Params Buffer"),
+        contents: bytemuck::bytes_of(&params),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    // Bounds start empty: min at u32::MAX, max at 0
+    let initial: Vec<u32> = (0..num_labels)
+        .flat_map(|_| [0, 0, 0, 0, 0, u32::MAX, u32::MAX, 0, 0])
+        .collect();
+    let stats_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Component Stats Buffer"),
+        contents: bytemuck::cast_slice(&initial),
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+    });
+
+    let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Component Stats Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Component Stats Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: labels_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: stats_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Component Stats Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let compute_pipeline = ctx.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Component Stats Pipeline"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Component Stats Encoder"),
+    });
+
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Component Stats Pass"),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(&compute_pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        compute_pass.dispatch_workgroups(params.width.div_ceil(16), params.height.div_ceil(16), 1);
+    }
+
+    let size = stats_buffer.size();
+    let staging_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Staging Buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    encoder.copy_buffer_to_buffer(&stats_buffer, 0, &staging_buffer, 0, size);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let buffer_slice = staging_buffer.slice(..);
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+
+    // Native backends only run map callbacks while the device is polled
+    #[cfg(not(target_arch = "wasm32"))]
+    ctx.device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .map_err(|e| Error::GpuError(format!("Device poll failed: {e:?}")))?;
+
+    receiver
+        .await
+        .map_err(|_| Error::GpuError("Failed to receive map result".to_string()))?
+        .map_err(|e| Error::GpuError(format!("Buffer mapping failed: {e:?}")))?;
+
+    let words: Vec<u32> = bytemuck::pod_collect_to_vec(&buffer_slice.get_mapped_range());
+    staging_buffer.unmap();
+
+    let wide = |lo: u32, hi: u32| u64::from(lo) | (u64::from(hi) << 32);
+    Ok(words
+        .chunks_exact(STRIDE)
+        .map(|s| {
+            stats_from_sums(
+                s[0] as usize,
+                wide(s[1], s[2]),
+                wide(s[3], s[4]),
+                [s[5] as usize, s[6] as usize, s[7] as usize, s[8] as usize],
+            )
+        })
+        .collect())
+}
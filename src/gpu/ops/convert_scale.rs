@@ -31,7 +31,7 @@ pub async fn convert_scale_gpu_async(
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -0,0 +1,356 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Point;
+use crate::error::{Error, Result};
+use crate::features2d::KeyPoint;
+use crate::gpu::device::GpuContext;
+use wgpu;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct CornerParams {
+    width: u32,
+    height: u32,
+    half_block: u32,
+    nms_radius: u32,
+    k: f32,
+    threshold: f32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+/// `Corner` in corners.wgsl
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct GpuCorner {
+    x: u32,
+    y: u32,
+    score: f32,
+}
+
+/// Which scoring kernel to run before non-maximum suppression
+#[derive(Clone, Copy)]
+enum CornerScore {
+    Harris,
+    Fast,
+}
+
+/// Harris corner detection on the GPU (async version)
+///
+/// Same responses as `features2d::harris_corners` with a 3x3 Sobel, computed
+/// in f32. With `nms_radius` 0 every pixel above `threshold` is returned,
+/// like the CPU version; otherwise only pixels that no other corner within
+/// `nms_radius` (Chebyshev distance) beats. Keypoints come back in raster
+/// order.
+pub async fn harris_corners_gpu_async(
+    src: &Mat,
+    block_size: i32,
+    k: f64,
+    threshold: f64,
+    nms_radius: usize,
+) -> Result<Vec<KeyPoint>> {
+    check_gray(src)?;
+    if block_size < 1 {
+        return Err(Error::InvalidParameter(
+            "Harris block size must be positive".to_string(),
+        ));
+    }
+
+    let params = CornerParams {
+        width: u32::try_from(src.cols()).unwrap_or(u32::MAX),
+        height: u32::try_from(src.rows()).unwrap_or(u32::MAX),
+        half_block: (block_size / 2) as u32,
+        nms_radius: u32::try_from(nms_radius).unwrap_or(u32::MAX),
+        k: k as f32,
+        threshold: threshold as f32,
+        _pad0: 0,
+        _pad1: 0,
+    };
+    let corners = detect_corners(src, CornerScore::Harris, params).await?;
+    Ok(corners
+        .into_iter()
+        .map(|c| keypoint(c, block_size as f32))
+        .collect())
+}
+
+/// Harris corner detection on the GPU (sync wrapper for native)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn harris_corners_gpu(src: &Mat, block_size: i32, k: f64, threshold: f64, nms_radius: usize) -> Result<Vec<KeyPoint>> {
+    pollster::block_on(harris_corners_gpu_async(src, block_size, k, threshold, nms_radius))
+}
+
+/// FAST corner detection on the GPU (async version)
+///
+/// Same segment test and scores as `features2d::fast`, with non-maximum
+/// suppression over a radius of 3 done on the GPU. Keypoints come back in
+/// raster order.
+pub async fn fast_gpu_async(src: &Mat, threshold: i32, nonmax_suppression: bool) -> Result<Vec<KeyPoint>> {
+    check_gray(src)?;
+
+    let params = CornerParams {
+        width: u32::try_from(src.cols()).unwrap_or(u32::MAX),
+        height: u32::try_from(src.rows()).unwrap_or(u32::MAX),
+        half_block: 0,
+        nms_radius: if nonmax_suppression { 3 } else { 0 },
+        k: 0.0,
+        threshold: threshold as f32,
+        _pad0: 0,
+        _pad1: 0,
+    };
+    let corners = detect_corners(src, CornerScore::Fast, params).await?;
+    Ok(corners.into_iter().map(|c| keypoint(c, 7.0)).collect())
+}
+
+/// FAST corner detection on the GPU (sync wrapper for native)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fast_gpu(src: &Mat, threshold: i32, nonmax_suppression: bool) -> Result<Vec<KeyPoint>> {
+    pollster::block_on(fast_gpu_async(src, threshold, nonmax_suppression))
+}
+
+fn check_gray(src: &Mat) -> Result<()> {
+    if src.channels() != 1 {
+        return Err(Error::InvalidParameter(
+            "Corner detection requires grayscale image".to_string(),
+        ));
+    }
+
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "GPU corner detection only supports U8 depth".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn keypoint(corner: GpuCorner, size: f32) -> KeyPoint {
+    KeyPoint {
+        pt: Point::new(corner.x as i32, corner.y as i32),
+        size,
+        angle: -1.0,
+        response: corner.score,
+        octave: 0,
+    }
+}
+
+async fn detect_corners(src: &Mat, score: CornerScore, params: CornerParams) -> Result<Vec<GpuCorner>> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let (device, queue, adapter) = GpuContext::with_gpu(|ctx| {
+            (ctx.device.clone(), ctx.queue.clone(), ctx.adapter.clone())
+        })
+        .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        let temp_ctx = GpuContext { device, queue, adapter };
+        return execute_corners_impl(&temp_ctx, src, score, params).await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let ctx = GpuContext::get()
+            .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        return execute_corners_impl(ctx, src, score, params).await;
+    }
+}
+
+async fn execute_corners_impl(
+    ctx: &GpuContext,
+    src: &Mat,
+    score: CornerScore,
+    params: CornerParams,
+) -> Result<Vec<GpuCorner>> {
+    let (width, height) = (params.width, params.height);
+    let pixels = u64::from(width) * u64::from(height);
+    if pixels == 0 {
+        return Ok(Vec::new());
+    }
+
+    let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Corners Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/corners.wgsl").into()),
+    });
+
+    // The shader reads whole u32 words, so pad the bytes to a multiple of 4
+    let mut input_data = src.data().to_vec();
+    input_data.resize(input_data.len().div_ceil(4) * 4, 0);
+    let input_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Input Buffer"),
+        contents: &input_data,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let scores_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Corner Scores Buffer"),
+        size: pixels * 4,
+        usage: wgpu::BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    });
+
+    let params_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Params Buffer"),
+        contents: bytemuck::bytes_of(&params),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let count_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Corner Count Buffer"),
+        contents: bytemuck::bytes_of(&0u32),
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+    });
+
+    // Room for every pixel, so the append can never overflow; only the
+    // used prefix is read back
+    let corner_size = std::mem::size_of::<GpuCorner>() as u64;
+    let corners_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Corners Buffer"),
+        size: pixels * corner_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+
+    let storage = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+    let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Corners Bind Group Layout"),
+        entries: &[
+            storage(0, true),
+            storage(1, false),
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            storage(3, false),
+            storage(4, false),
+        ],
+    });
+
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Corners Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: input_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: scores_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: count_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 4,
+                resource: corners_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Corners Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = |entry_point: &str| {
+        ctx.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Corners Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache: None,
+        })
+    };
+    let score_pipeline = pipeline(match score {
+        CornerScore::Harris => "harris_response",
+        CornerScore::Fast => "fast_score",
+    });
+    let collect_pipeline = pipeline("collect");
+
+    let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Corners Encoder"),
+    });
+
+    // Separate passes so every score is written before suppression reads it
+    for (label, compute_pipeline) in [("Corner Score Pass", &score_pipeline), ("Corner Collect Pass", &collect_pipeline)] {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some(label),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(compute_pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        compute_pass.dispatch_workgroups(width.div_ceil(16), height.div_ceil(16), 1);
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let count_bytes = read_buffer(ctx, &count_buffer, 4).await?;
+    let count = u64::from(u32::from_le_bytes([count_bytes[0], count_bytes[1], count_bytes[2], count_bytes[3]])).min(pixels);
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let corner_bytes = read_buffer(ctx, &corners_buffer, count * corner_size).await?;
+    let mut corners: Vec<GpuCorner> = bytemuck::pod_collect_to_vec(&corner_bytes);
+    // The append order depends on scheduling; match the CPU detectors
+    corners.sort_unstable_by_key(|c| (c.y, c.x));
+    Ok(corners)
+}
+
+/// Copy the first `size` bytes of `buffer` back to the CPU
+pub(super) async fn read_buffer(ctx: &GpuContext, buffer: &wgpu::Buffer, size: u64) -> Result<Vec<u8>> {
+    let staging_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Staging Buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Copy Encoder"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging_buffer, 0, size);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let buffer_slice = staging_buffer.slice(..);
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+
+    // Native backends only run map callbacks while the device is polled
+    #[cfg(not(target_arch = "wasm32"))]
+    ctx.device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .map_err(|e| Error::GpuError(format!("Device poll failed: {e:?}")))?;
+
+    receiver
+        .await
+        .map_err(|_| Error::GpuError("Failed to receive map result".to_string()))?
+        .map_err(|e| Error::GpuError(format!("Buffer mapping failed: {:?}", e)))?;
+
+    let data = buffer_slice.get_mapped_range().to_vec();
+    staging_buffer.unmap();
+    Ok(data)
+}
+
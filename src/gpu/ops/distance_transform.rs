@@ -15,6 +15,12 @@ struct DistanceTransformParams {
     _pad: u32,
 }
 
+/// GPU distance from every non-zero pixel to the nearest zero pixel (async version)
+///
+/// `dst` is F32 like the CPU
+/// [`distance_transform`](crate::imgproc::advanced_filter::distance_transform),
+/// but zero pixels are only searched within 10 pixels; farther pixels get the
+/// image diagonal.
 pub async fn distance_transform_gpu_async(src: &Mat, dst: &mut Mat) -> Result<()> {
     if src.channels() != 1 {
         return Err(Error::InvalidParameter(
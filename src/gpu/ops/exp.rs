@@ -22,7 +22,7 @@ pub async fn exp_gpu_async(src: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
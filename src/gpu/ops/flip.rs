@@ -21,7 +21,7 @@ pub async fn flip_gpu_async(src: &Mat, dst: &mut Mat, flip_code: i32) -> Result<
         return Err(Error::UnsupportedOperation("GPU flip only supports U8 depth".to_string()));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
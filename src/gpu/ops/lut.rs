@@ -35,7 +35,7 @@ pub async fn lut_gpu_async(src: &Mat, dst: &mut Mat, lut: &Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -0,0 +1,302 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+use crate::error::{Error, Result};
+use crate::features2d::{DMatch, Descriptor, DistanceType};
+use crate::gpu::device::GpuContext;
+use super::corners::read_buffer;
+use wgpu;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct MatchParams {
+    query_count: u32,
+    train_count: u32,
+    words: u32,
+    _pad: u32,
+}
+
+/// `Match2` in `match_descriptors.wgsl`
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct GpuMatch2 {
+    best_idx: u32,
+    best_dist: f32,
+    second_idx: u32,
+    second_dist: f32,
+}
+
+const NO_MATCH: u32 = u32::MAX;
+
+/// Two nearest neighbours of each binary descriptor on the GPU (async version)
+///
+/// Same result as `BFMatcher::knn_match(query, train, 2)` for ORB, BRISK
+/// or AKAZE descriptors, ready for `ratio_test_filter`. The distance
+/// matrix is never materialised: each GPU thread scans one query's row
+/// and keeps only the two best candidates. `DistanceType::L2` compares the
+/// bytes as integers, like `BFMatcher`.
+pub async fn knn2_match_gpu_async(
+    query: &[Descriptor],
+    train: &[Descriptor],
+    distance_type: DistanceType,
+) -> Result<Vec<Vec<DMatch>>> {
+    let Some(len) = descriptor_len(query, train)? else {
+        return Ok(Vec::new());
+    };
+
+    match distance_type {
+        DistanceType::Hamming => {
+            // Pack each descriptor into whole u32 words, zero padded
+            let words = len.div_ceil(4);
+            let pack = |descs: &[Descriptor]| {
+                let mut bytes = Vec::with_capacity(descs.len() * words * 4);
+                for d in descs {
+                    bytes.extend_from_slice(d);
+                    bytes.resize(bytes.len() + words * 4 - len, 0);
+                }
+                bytes
+            };
+            match_on_gpu(&pack(query), &pack(train), query.len(), train.len(), words, "match_hamming").await
+        }
+        DistanceType::L2 => {
+            let widen = |descs: &[Descriptor]| -> Vec<f32> {
+                descs.iter().flat_map(|d| d.iter().map(|&b| f32::from(b))).collect()
+            };
+            match_on_gpu(
+                bytemuck::cast_slice(&widen(query)),
+                bytemuck::cast_slice(&widen(train)),
+                query.len(),
+                train.len(),
+                len,
+                "match_l2",
+            )
+            .await
+        }
+    }
+}
+
+/// Two nearest neighbours of each binary descriptor on the GPU (sync wrapper for native)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn knn2_match_gpu(query: &[Descriptor], train: &[Descriptor], distance_type: DistanceType) -> Result<Vec<Vec<DMatch>>> {
+    pollster::block_on(knn2_match_gpu_async(query, train, distance_type))
+}
+
+/// Two nearest neighbours of each float descriptor by L2 distance on the
+/// GPU (async version)
+///
+/// For SIFT and KAZE descriptors; distances agree with
+/// `DescriptorDistance` for `Vec<f32>` up to f32 rounding.
+pub async fn knn2_match_f32_gpu_async(query: &[Vec<f32>], train: &[Vec<f32>]) -> Result<Vec<Vec<DMatch>>> {
+    let Some(len) = descriptor_len(query, train)? else {
+        return Ok(Vec::new());
+    };
+    let query_data: Vec<f32> = query.concat();
+    let train_data: Vec<f32> = train.concat();
+    match_on_gpu(
+        bytemuck::cast_slice(&query_data),
+        bytemuck::cast_slice(&train_data),
+        query.len(),
+        train.len(),
+        len,
+        "match_l2",
+    )
+    .await
+}
+
+/// Two nearest neighbours of each float descriptor on the GPU (sync wrapper for native)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn knn2_match_f32_gpu(query: &[Vec<f32>], train: &[Vec<f32>]) -> Result<Vec<Vec<DMatch>>> {
+    pollster::block_on(knn2_match_f32_gpu_async(query, train))
+}
+
+/// Common descriptor length, `None` when there is nothing to match
+fn descriptor_len<T>(query: &[Vec<T>], train: &[Vec<T>]) -> Result<Option<usize>> {
+    let (Some(first), false) = (query.first(), train.is_empty()) else {
+        return Ok(None);
+    };
+    let len = first.len();
+    if query.iter().chain(train).any(|d| d.len() != len) {
+        return Err(Error::InvalidParameter(
+            "Descriptors must have same length".to_string(),
+        ));
+    }
+    if len == 0 {
+        return Err(Error::InvalidParameter(
+            "Descriptors must not be empty".to_string(),
+        ));
+    }
+    if u32::try_from(query.len().max(train.len())).is_err() {
+        return Err(Error::InvalidParameter(
+            "Too many descriptors for GPU matching".to_string(),
+        ));
+    }
+    Ok(Some(len))
+}
+
+async fn match_on_gpu(
+    query: &[u8],
+    train: &[u8],
+    query_count: usize,
+    train_count: usize,
+    words: usize,
+    entry_point: &str,
+) -> Result<Vec<Vec<DMatch>>> {
+    let params = MatchParams {
+        query_count: query_count as u32,
+        train_count: train_count as u32,
+        words: words as u32,
+        _pad: 0,
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let (device, queue, adapter) = GpuContext::with_gpu(|ctx| {
+            (ctx.device.clone(), ctx.queue.clone(), ctx.adapter.clone())
+        })
+        .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        let temp_ctx = GpuContext { device, queue, adapter };
+        return execute_match_impl(&temp_ctx, query, train, params, entry_point).await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let ctx = GpuContext::get()
+            .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        return execute_match_impl(ctx, query, train, params, entry_point).await;
+    }
+}
+
+async fn execute_match_impl(
+    ctx: &GpuContext,
+    query: &[u8],
+    train: &[u8],
+    params: MatchParams,
+    entry_point: &str,
+) -> Result<Vec<Vec<DMatch>>> {
+    let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Match Descriptors Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/match_descriptors.wgsl").into()),
+    });
+
+    let query_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Query Descriptors Buffer"),
+        contents: query,
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let train_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Train Descriptors Buffer"),
+        contents: train,
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let params_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Params Buffer"),
+        contents: bytemuck::bytes_of(&params),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let matches_size = u64::from(params.query_count) * std::mem::size_of::<GpuMatch2>() as u64;
+    let matches_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Matches Buffer"),
+        size: matches_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+
+    let storage = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+    let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Match Descriptors Bind Group Layout"),
+        entries: &[
+            storage(0, true),
+            storage(1, true),
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            storage(3, false),
+        ],
+    });
+
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Match Descriptors Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: query_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: train_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: matches_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Match Descriptors Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let compute_pipeline = ctx.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Match Descriptors Pipeline"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: Some(entry_point),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Match Descriptors Encoder"),
+    });
+
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Match Descriptors Pass"),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(&compute_pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        compute_pass.dispatch_workgroups(params.query_count.div_ceil(64), 1, 1);
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let bytes = read_buffer(ctx, &matches_buffer, matches_size).await?;
+    let gpu_matches: Vec<GpuMatch2> = bytemuck::pod_collect_to_vec(&bytes);
+    Ok(gpu_matches
+        .into_iter()
+        .enumerate()
+        .map(|(query_idx, m)| {
+            [(m.best_idx, m.best_dist), (m.second_idx, m.second_dist)]
+                .into_iter()
+                .filter(|&(idx, _)| idx != NO_MATCH)
+                .map(|(idx, dist)| DMatch::new(query_idx, idx as usize, dist))
+                .collect()
+        })
+        .collect())
+}
@@ -23,7 +23,7 @@ pub async fn median_blur_gpu_async(src: &Mat, dst: &mut Mat, ksize: i32) -> Resu
         return Err(Error::InvalidParameter("GPU median_blur only supports kernel sizes 3 and 5".to_string()));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
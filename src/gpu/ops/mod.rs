@@ -57,6 +57,9 @@ pub mod exp;
 pub mod log;
 pub mod sqrt;
 pub mod lut;
+pub mod yuv420_to_rgb;
+pub mod mog2;
+pub mod component_stats;
 
 // Batch 4 operations - Morphology composites & Histogram
 pub mod morphology_opening;
@@ -65,12 +68,16 @@ pub mod morphology_gradient;
 pub mod morphology_tophat;
 pub mod morphology_blackhat;
 pub mod calc_histogram;
+#[cfg(feature = "features2d")]
+pub mod corners;
+#[cfg(feature = "features2d")]
+pub mod match_descriptors;
 
 // Export sync versions for native
 #[cfg(not(target_arch = "wasm32"))]
 pub use blur::gaussian_blur_gpu;
 #[cfg(not(target_arch = "wasm32"))]
-pub use resize::resize_gpu;
+pub use resize::{resize_gpu, resize_gpu_with_interpolation};
 #[cfg(not(target_arch = "wasm32"))]
 pub use threshold::threshold_gpu;
 #[cfg(not(target_arch = "wasm32"))]
@@ -185,10 +192,18 @@ pub use morphology_tophat::morphology_tophat_gpu;
 pub use morphology_blackhat::morphology_blackhat_gpu;
 #[cfg(not(target_arch = "wasm32"))]
 pub use calc_histogram::calc_histogram_gpu;
+#[cfg(not(target_arch = "wasm32"))]
+pub use yuv420_to_rgb::yuv420_to_rgb_gpu;
+#[cfg(not(target_arch = "wasm32"))]
+pub use component_stats::component_stats_gpu;
+#[cfg(all(feature = "features2d", not(target_arch = "wasm32")))]
+pub use corners::{fast_gpu, harris_corners_gpu};
+#[cfg(all(feature = "features2d", not(target_arch = "wasm32")))]
+pub use match_descriptors::{knn2_match_f32_gpu, knn2_match_gpu};
 
 // Export async versions for WASM
 pub use blur::gaussian_blur_gpu_async;
-pub use resize::resize_gpu_async;
+pub use resize::{resize_gpu_async, resize_gpu_with_interpolation_async};
 pub use threshold::threshold_gpu_async;
 pub use canny::canny_gpu_async;
 pub use sobel::sobel_gpu_async;
@@ -246,3 +261,11 @@ pub use morphology_gradient::morphology_gradient_gpu_async;
 pub use morphology_tophat::morphology_tophat_gpu_async;
 pub use morphology_blackhat::morphology_blackhat_gpu_async;
 pub use calc_histogram::calc_histogram_gpu_async;
+pub use yuv420_to_rgb::yuv420_to_rgb_gpu_async;
+pub use component_stats::component_stats_gpu_async;
+#[cfg(feature = "features2d")]
+pub use corners::{fast_gpu_async, harris_corners_gpu_async};
+#[cfg(feature = "features2d")]
+pub use match_descriptors::{knn2_match_f32_gpu_async, knn2_match_gpu_async};
+
+pub use mog2::BackgroundSubtractorMOG2Gpu;
@@ -0,0 +1,349 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::gpu::device::GpuContext;
+use wgpu;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+
+/// Gaussians per pixel, fixed by the shader
+const NUM_GAUSSIANS: usize = 5;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct Mog2Params {
+    width: u32,
+    height: u32,
+    alpha: f32,
+    var_threshold: f32,
+    background_ratio: f32,
+    var_init: f32,
+    var_min: f32,
+    var_max: f32,
+}
+
+/// GPU state that lives across frames
+struct Mog2Model {
+    rows: usize,
+    cols: usize,
+    buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+/// MOG2 background subtractor running on the GPU
+///
+/// Same model and parameters as
+/// `video::background_subtraction::BackgroundSubtractorMOG2`, but the
+/// per-pixel mixtures stay in a GPU buffer between frames, so each frame
+/// costs one upload of the image and one download of the mask. The model is
+/// reset when the frame size changes.
+pub struct BackgroundSubtractorMOG2Gpu {
+    pub history: usize,
+    pub var_threshold: f64,
+    pub detect_shadows: bool,
+    background_ratio: f64,
+    var_init: f64,
+    var_min: f64,
+    var_max: f64,
+    model: Option<Mog2Model>,
+}
+
+impl Default for BackgroundSubtractorMOG2Gpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackgroundSubtractorMOG2Gpu {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_params(500, 16.0, true)
+    }
+
+    #[must_use]
+    pub fn with_params(history: usize, var_threshold: f64, detect_shadows: bool) -> Self {
+        Self {
+            history,
+            var_threshold,
+            detect_shadows,
+            background_ratio: 0.9,
+            var_init: 15.0,
+            var_min: 4.0,
+            var_max: 75.0,
+            model: None,
+        }
+    }
+
+    /// Update the model with `image` (3-channel U8) and write the foreground
+    /// mask; a negative `learning_rate` uses `1 / history`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply(&mut self, image: &Mat, fgmask: &mut Mat, learning_rate: f64) -> Result<()> {
+        pollster::block_on(self.apply_async(image, fgmask, learning_rate))
+    }
+
+    /// Update the model with `image` (3-channel U8) and write the foreground
+    /// mask; a negative `learning_rate` uses `1 / history`
+    pub async fn apply_async(&mut self, image: &Mat, fgmask: &mut Mat, learning_rate: f64) -> Result<()> {
+        if image.channels() != 3 {
+            return Err(Error::InvalidParameter(
+                "MOG2 requires 3-channel image".to_string(),
+            ));
+        }
+        if image.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "GPU MOG2 only supports U8 depth".to_string(),
+            ));
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let (device, queue, adapter) = GpuContext::with_gpu(|ctx| {
+                (ctx.device.clone(), ctx.queue.clone(), ctx.adapter.clone())
+            })
+            .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+            let temp_ctx = GpuContext { device, queue, adapter };
+            return self.execute_apply_impl(&temp_ctx, image, fgmask, learning_rate).await;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let ctx = GpuContext::get()
+                .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+            return self.execute_apply_impl(ctx, image, fgmask, learning_rate).await;
+        }
+    }
+
+    /// Read back the most probable background intensity as a grey RGB image
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn get_background_image(&self, background: &mut Mat) -> Result<()> {
+        pollster::block_on(self.get_background_image_async(background))
+    }
+
+    /// Read back the most probable background intensity as a grey RGB image
+    pub async fn get_background_image_async(&self, background: &mut Mat) -> Result<()> {
+        let model = self.model.as_ref().ok_or_else(|| {
+            Error::InvalidParameter("Model not initialized".to_string())
+        })?;
+
+        #[cfg(target_arch = "wasm32")]
+        let data = {
+            let (device, queue, adapter) = GpuContext::with_gpu(|ctx| {
+                (ctx.device.clone(), ctx.queue.clone(), ctx.adapter.clone())
+            })
+            .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+            let temp_ctx = GpuContext { device, queue, adapter };
+            read_buffer(&temp_ctx, &model.buffer).await?
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let data = {
+            let ctx = GpuContext::get()
+                .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+            read_buffer(ctx, &model.buffer).await?
+        };
+
+        let values: &[f32] = bytemuck::cast_slice(&data);
+        *background = Mat::new(model.rows, model.cols, 3, MatDepth::U8)?;
+        for (pixel, bg) in background.data_mut().chunks_exact_mut(3).enumerate() {
+            #[allow(clippy::cast_sign_loss)]
+            let v = values[pixel * 3 * NUM_GAUSSIANS].clamp(0.0, 255.0) as u8;
+            bg.fill(v);
+        }
+        Ok(())
+    }
+
+    async fn execute_apply_impl(&mut self, ctx: &GpuContext, image: &Mat, fgmask: &mut Mat, learning_rate: f64) -> Result<()> {
+        let rows = image.rows();
+        let cols = image.cols();
+        if self.model.as_ref().is_none_or(|m| m.rows != rows || m.cols != cols) {
+            self.model = Some(self.create_model(ctx, rows, cols));
+        }
+        let model = self.model.as_ref().expect("model was just created");
+
+        let alpha = if learning_rate < 0.0 {
+            1.0 / self.history as f64
+        } else {
+            learning_rate
+        };
+
+        let params = Mog2Params {
+            width: cols as u32,
+            height: rows as u32,
+            alpha: alpha as f32,
+            var_threshold: self.var_threshold as f32,
+            background_ratio: self.background_ratio as f32,
+            var_init: self.var_init as f32,
+            var_min: self.var_min as f32,
+            var_max: self.var_max as f32,
+        };
+        let params_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Params Buffer"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let input_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Input Buffer"),
+            contents: image.data(),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // One mask byte per pixel, written four pixels (one word) at a time
+        let num_pixels = rows * cols;
+        let mask_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Mask Buffer"),
+            size: (num_pixels.div_ceil(4) * 4) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("MOG2 Bind Group"),
+            layout: &model.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: input_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: model.buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: mask_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("MOG2 Encoder"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("MOG2 Compute Pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(&model.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            // One invocation per 4 pixels, 256 per workgroup; spill into y when
+            // the frame needs more than the per-dimension workgroup limit
+            let workgroups = (num_pixels as u32).div_ceil(4).div_ceil(256);
+            let workgroup_count_x = workgroups.clamp(1, 65535);
+            let workgroup_count_y = workgroups.div_ceil(workgroup_count_x);
+            compute_pass.dispatch_workgroups(workgroup_count_x, workgroup_count_y, 1);
+        }
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let data = read_buffer(ctx, &mask_buffer).await?;
+        *fgmask = Mat::from_slice(&data[..num_pixels], rows, cols, 1, MatDepth::U8)?;
+        Ok(())
+    }
+
+    fn create_model(&self, ctx: &GpuContext, rows: usize, cols: usize) -> Mog2Model {
+        // Per pixel: K means, K variances, K weights
+        let mut initial = vec![0.0f32; rows * cols * 3 * NUM_GAUSSIANS];
+        for pixel in initial.chunks_exact_mut(3 * NUM_GAUSSIANS) {
+            pixel[NUM_GAUSSIANS..2 * NUM_GAUSSIANS].fill(self.var_init as f32);
+        }
+        let buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("MOG2 Model Buffer"),
+            contents: bytemuck::cast_slice(&initial),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("MOG2 Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/mog2.wgsl").into()),
+        });
+
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("MOG2 Bind Group Layout"),
+            entries: &[
+                storage(0, true),
+                storage(1, false),
+                storage(2, false),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("MOG2 Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = ctx.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("MOG2 Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Mog2Model { rows, cols, buffer, bind_group_layout, pipeline }
+    }
+}
+
+/// Copy a whole buffer back to the CPU
+async fn read_buffer(ctx: &GpuContext, buffer: &wgpu::Buffer) -> Result<Vec<u8>> {
+    let staging_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Staging Buffer"),
+        size: buffer.size(),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("MOG2 Readback Encoder"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging_buffer, 0, buffer.size());
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let buffer_slice = staging_buffer.slice(..);
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+
+    // Native backends only run map callbacks while the device is polled
+    #[cfg(not(target_arch = "wasm32"))]
+    ctx.device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .map_err(|e| Error::GpuError(format!("Device poll failed: {e:?}")))?;
+
+    receiver
+        .await
+        .map_err(|_| Error::GpuError("Failed to receive map result".to_string()))?
+        .map_err(|e| Error::GpuError(format!("Buffer mapping failed: {e:?}")))?;
+
+    let data = buffer_slice.get_mapped_range().to_vec();
+    staging_buffer.unmap();
+    Ok(data)
+}
@@ -26,7 +26,7 @@ pub async fn pyrup_gpu_async(src: &Mat, dst: &mut Mat) -> Result<()> {
 
     let dst_width = src.cols() * 2;
     let dst_height = src.rows() * 2;
-    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?;
+    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -1,5 +1,6 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
 use crate::core::{Mat, MatDepth};
+use crate::core::types::InterpolationFlag;
 use crate::error::{Error, Result};
 use crate::gpu::device::GpuContext;
 use crate::gpu::pipeline_cache::PipelineCache;
@@ -15,30 +16,72 @@ struct ResizeParams {
     dst_width: u32,
     dst_height: u32,
     channels: u32,
-    _pad0: u32,
+    mode: u32,
     _pad1: u32,
     _pad2: u32,
 }
 
 /// GPU-accelerated bilinear resize (async version)
 pub async fn resize_gpu_async(src: &Mat, dst: &mut Mat, dst_width: usize, dst_height: usize) -> Result<()> {
+    resize_gpu_with_interpolation_async(src, dst, dst_width, dst_height, InterpolationFlag::Linear).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn resize_gpu(src: &Mat, dst: &mut Mat, dst_width: usize, dst_height: usize) -> Result<()> {
+    pollster::block_on(resize_gpu_async(src, dst, dst_width, dst_height))
+}
+
+/// GPU-accelerated resize with a selectable interpolation mode (async version)
+///
+/// Sampling matches the CPU `resize`: `Nearest` picks `floor(dst * scale)` and
+/// the other modes align pixel centres.
+pub async fn resize_gpu_with_interpolation_async(
+    src: &Mat,
+    dst: &mut Mat,
+    dst_width: usize,
+    dst_height: usize,
+    interpolation: InterpolationFlag,
+) -> Result<()> {
+    if dst_width == 0 || dst_height == 0 {
+        return Err(Error::InvalidDimensions(
+            "Destination size must be positive".to_string(),
+        ));
+    }
+
     if src.depth() != MatDepth::U8 {
         return Err(Error::UnsupportedOperation(
             "GPU resize only supports U8 depth".to_string(),
         ));
     }
 
-    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?;
+    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?.with_color_order(src.color_order());
 
-    execute_resize(src, dst).await
+    execute_resize(src, dst, interpolation_mode(interpolation)).await
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub fn resize_gpu(src: &Mat, dst: &mut Mat, dst_width: usize, dst_height: usize) -> Result<()> {
-    pollster::block_on(resize_gpu_async(src, dst, dst_width, dst_height))
+pub fn resize_gpu_with_interpolation(
+    src: &Mat,
+    dst: &mut Mat,
+    dst_width: usize,
+    dst_height: usize,
+    interpolation: InterpolationFlag,
+) -> Result<()> {
+    pollster::block_on(resize_gpu_with_interpolation_async(src, dst, dst_width, dst_height, interpolation))
+}
+
+/// Shader mode constant for an interpolation flag (see `resize.wgsl`)
+fn interpolation_mode(interpolation: InterpolationFlag) -> u32 {
+    match interpolation {
+        InterpolationFlag::Nearest => 0,
+        InterpolationFlag::Linear => 1,
+        InterpolationFlag::Cubic => 2,
+        InterpolationFlag::Area => 3,
+        InterpolationFlag::Lanczos4 => 4,
+    }
 }
 
-async fn execute_resize(src: &Mat, dst: &mut Mat) -> Result<()> {
+async fn execute_resize(src: &Mat, dst: &mut Mat, mode: u32) -> Result<()> {
     // Get GPU context with platform-specific approach
     #[cfg(not(target_arch = "wasm32"))]
     let ctx = GpuContext::get()
@@ -62,11 +105,11 @@ async fn execute_resize(src: &Mat, dst: &mut Mat) -> Result<()> {
             adapter,
         };
 
-        return execute_resize_impl(&temp_ctx, src, dst, src_width, src_height, dst_width, dst_height, channels).await;
+        return execute_resize_impl(&temp_ctx, src, dst, src_width, src_height, dst_width, dst_height, channels, mode).await;
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    return execute_resize_impl(ctx, src, dst, src_width, src_height, dst_width, dst_height, channels).await;
+    return execute_resize_impl(ctx, src, dst, src_width, src_height, dst_width, dst_height, channels, mode).await;
 }
 
 async fn execute_resize_impl(
@@ -78,6 +121,7 @@ async fn execute_resize_impl(
     dst_width: u32,
     dst_height: u32,
     channels: u32,
+    mode: u32,
 ) -> Result<()> {
     // Create input buffer
     let input_data = src.data();
@@ -103,7 +147,7 @@ async fn execute_resize_impl(
         dst_width,
         dst_height,
         channels,
-        _pad0: 0,
+        mode,
         _pad1: 0,
         _pad2: 0,
     };
@@ -178,6 +222,10 @@ async fn execute_resize_impl(
         buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
             sender.send(result).ok();
         });
+        // Native backends only run map callbacks while the device is polled
+        ctx.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| Error::GpuError(format!("Device poll failed: {e:?}")))?;
         pollster::block_on(receiver)
             .map_err(|_| Error::GpuError("Failed to receive buffer mapping result".to_string()))?
             .map_err(|e| Error::GpuError(format!("Buffer mapping failed: {:?}", e)))?;
@@ -1,5 +1,6 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
 use crate::core::{Mat, MatDepth};
+use crate::core::types::ColorOrder;
 use crate::error::{Error, Result};
 use crate::gpu::device::GpuContext;
 use crate::gpu::pipeline_cache::PipelineCache;
@@ -13,9 +14,14 @@ struct RgbToGrayParams {
     width: u32,
     height: u32,
     channels: u32,
-    _pad: u32,
+    bgr: u32,
 }
 
+/// GPU luminance conversion (async version)
+///
+/// The channel order comes from the [`ColorOrder`] tag like
+/// [`to_gray`](crate::imgproc::to_gray): `Bgr` input is read as BGR, anything
+/// else as RGB.
 pub async fn rgb_to_gray_gpu_async(src: &Mat, dst: &mut Mat) -> Result<()> {
     if src.channels() != 3 {
         return Err(Error::InvalidParameter("RGB to Gray requires 3-channel input".to_string()));
@@ -69,7 +75,8 @@ async fn execute_rgb_to_gray_impl(ctx: &GpuContext, src: &Mat, dst: &mut Mat) ->
         mapped_at_creation: false,
     });
 
-    let params = RgbToGrayParams { width, height, channels, _pad: 0 };
+    let bgr = u32::from(src.color_order() == ColorOrder::Bgr);
+    let params = RgbToGrayParams { width, height, channels, bgr };
     let params_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
         label: Some("Params Buffer"),
         contents: bytemuck::bytes_of(&params),
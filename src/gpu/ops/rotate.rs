@@ -29,7 +29,7 @@ pub async fn rotate_gpu_async(src: &Mat, dst: &mut Mat, rotate_code: i32) -> Res
         (src.cols(), src.rows())
     };
 
-    *dst = Mat::new(out_rows, out_cols, src.channels(), src.depth())?;
+    *dst = Mat::new(out_rows, out_cols, src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
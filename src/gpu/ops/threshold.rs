@@ -28,7 +28,7 @@ pub async fn threshold_gpu_async(src: &Mat, dst: &mut Mat, thresh: u8, max_value
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     execute_threshold(src, dst, thresh, max_value).await
 }
@@ -37,7 +37,7 @@ pub async fn warp_affine_gpu_async(
     }
 
     let (dst_height, dst_width) = dst_size;
-    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?;
+    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
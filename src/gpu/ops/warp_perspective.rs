@@ -41,7 +41,7 @@ pub async fn warp_perspective_gpu_async(
     }
 
     let (dst_cols, dst_rows) = dsize;
-    *dst = Mat::new(dst_rows, dst_cols, src.channels(), src.depth())?;
+    *dst = Mat::new(dst_rows, dst_cols, src.channels(), src.depth())?.with_color_order(src.color_order());
 
     #[cfg(target_arch = "wasm32")]
     {
@@ -0,0 +1,209 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::gpu::device::GpuContext;
+use crate::imgproc::color::{yuv420_frame_size, Yuv420Layout};
+use wgpu;
+use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct Yuv420ToRgbParams {
+    width: u32,
+    height: u32,
+    chroma_layout: u32,
+    channels: u32,
+}
+
+/// Convert a 4:2:0 YUV frame (NV12/NV21/I420) to RGB or RGBA on the GPU
+///
+/// Same frame layout and BT.601 matrix as `imgproc::yuv420_to_rgb`.
+pub async fn yuv420_to_rgb_gpu_async(src: &Mat, dst: &mut Mat, layout: Yuv420Layout, channels: usize) -> Result<()> {
+    let (width, height) = yuv420_frame_size(src, channels)?;
+    *dst = Mat::new(height, width, channels, MatDepth::U8)?;
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let (device, queue, adapter) = GpuContext::with_gpu(|ctx| {
+            (ctx.device.clone(), ctx.queue.clone(), ctx.adapter.clone())
+        })
+        .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        let temp_ctx = GpuContext { device, queue, adapter };
+        return execute_yuv420_to_rgb_impl(&temp_ctx, src, dst, layout).await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let ctx = GpuContext::get()
+            .ok_or_else(|| Error::GpuNotAvailable("GPU context not initialized".to_string()))?;
+        return execute_yuv420_to_rgb_impl(ctx, src, dst, layout).await;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn yuv420_to_rgb_gpu(src: &Mat, dst: &mut Mat, layout: Yuv420Layout, channels: usize) -> Result<()> {
+    pollster::block_on(yuv420_to_rgb_gpu_async(src, dst, layout, channels))
+}
+
+async fn execute_yuv420_to_rgb_impl(ctx: &GpuContext, src: &Mat, dst: &mut Mat, layout: Yuv420Layout) -> Result<()> {
+    let width = u32::try_from(dst.cols()).unwrap_or(u32::MAX);
+    let height = u32::try_from(dst.rows()).unwrap_or(u32::MAX);
+    let channels = u32::try_from(dst.channels()).unwrap_or(u32::MAX);
+
+    let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("YUV420 to RGB Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/yuv420_to_rgb.wgsl").into()),
+    });
+
+    let input_data = src.data();
+    let input_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Input Buffer"),
+        contents: input_data,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+    });
+
+    // The shader writes whole u32 words, so round the RGB(A) bytes up
+    let output_len = dst.data().len();
+    let output_buffer_size = (output_len as u64).div_ceil(4) * 4;
+    let output_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Output Buffer"),
+        size: output_buffer_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+
+    let params = Yuv420ToRgbParams {
+        width,
+        height,
+        chroma_layout: match layout {
+            Yuv420Layout::Nv12 => 0,
+            Yuv420Layout::Nv21 => 1,
+            Yuv420Layout::I420 => 2,
+        },
+        channels,
+    };
+    let params_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Params Buffer"),
+        contents: bytemuck::bytes_of(&params),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("YUV420 to RGB Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("YUV420 to RGB Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: input_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: output_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("YUV420 to RGB Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let compute_pipeline = ctx.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("YUV420 to RGB Pipeline"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("YUV420 to RGB Encoder"),
+    });
+
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("YUV420 to RGB Compute Pass"),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(&compute_pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        // One invocation per 4 pixels, 256 per workgroup; spill into y when
+        // the frame needs more than the per-dimension workgroup limit
+        let workgroups = (width * height).div_ceil(4).div_ceil(256);
+        let workgroup_count_x = workgroups.clamp(1, 65535);
+        let workgroup_count_y = workgroups.div_ceil(workgroup_count_x);
+        compute_pass.dispatch_workgroups(workgroup_count_x, workgroup_count_y, 1);
+    }
+
+    let staging_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Staging Buffer"),
+        size: output_buffer_size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    encoder.copy_buffer_to_buffer(&output_buffer, 0, &staging_buffer, 0, output_buffer_size);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let buffer_slice = staging_buffer.slice(..);
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    // ctx.device.poll(wgpu::Maintain::Wait); // No longer needed in wgpu 27
+
+    receiver
+        .await
+        .map_err(|_| Error::GpuError("Failed to receive map result".to_string()))?
+        .map_err(|e| Error::GpuError(format!("Buffer mapping failed: {:?}", e)))?;
+
+    {
+        let data = buffer_slice.get_mapped_range();
+        dst.data_mut().copy_from_slice(&data[..output_len]);
+    }
+    staging_buffer.unmap();
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// Per-label area, coordinate sums and bounding box of an S32 label image
+//
+// Each label owns STRIDE u32 slots in `stats`: area, sum x (lo, hi),
+// sum y (lo, hi), min x, min y, max x, max y. The 64-bit sums carry into
+// the high word whenever an atomic add wraps the low one. Pixels sharing
+// the label of their tile's top-left pixel (usually the background or the
+// blob the tile sits in) are first reduced in workgroup memory, so the
+// global atomics only see one update per tile for them.
+
+struct Params {
+    width: u32,
+    height: u32,
+    num_labels: u32,
+    _pad: u32,
+}
+
+@group(0) @binding(0) var<storage, read> labels: array<i32>;
+@group(0) @binding(1) var<uniform> params: Params;
+@group(0) @binding(2) var<storage, read_write> stats: array<atomic<u32>>;
+
+const STRIDE: u32 = 9u;
+const NO_LABEL: u32 = 0xFFFFFFFFu;
+
+var<workgroup> tile_label: u32;
+var<workgroup> tile_area: atomic<u32>;
+var<workgroup> tile_sum_x: atomic<u32>;
+var<workgroup> tile_sum_y: atomic<u32>;
+var<workgroup> tile_min_x: atomic<u32>;
+var<workgroup> tile_min_y: atomic<u32>;
+var<workgroup> tile_max_x: atomic<u32>;
+var<workgroup> tile_max_y: atomic<u32>;
+
+fn label_at(x: u32, y: u32) -> u32 {
+    if (x >= params.width || y >= params.height) {
+        return NO_LABEL;
+    }
+    let label = labels[y * params.width + x];
+    if (label < 0 || u32(label) >= params.num_labels) {
+        return NO_LABEL;
+    }
+    return u32(label);
+}
+
+fn add_wide(slot: u32, value: u32) {
+    let old = atomicAdd(&stats[slot], value);
+    if (old > 0xFFFFFFFFu - value) {
+        atomicAdd(&stats[slot + 1u], 1u);
+    }
+}
+
+fn add_global(label: u32, area: u32, sum_x: u32, sum_y: u32, min_x: u32, min_y: u32, max_x: u32, max_y: u32) {
+    let base = label * STRIDE;
+    atomicAdd(&stats[base], area);
+    add_wide(base + 1u, sum_x);
+    add_wide(base + 3u, sum_y);
+    atomicMin(&stats[base + 5u], min_x);
+    atomicMin(&stats[base + 6u], min_y);
+    atomicMax(&stats[base + 7u], max_x);
+    atomicMax(&stats[base + 8u], max_y);
+}
+
+@compute @workgroup_size(16, 16)
+fn main(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(workgroup_id) group: vec3<u32>,
+    @builtin(local_invocation_index) local: u32,
+) {
+    if (local == 0u) {
+        tile_label = label_at(group.x * 16u, group.y * 16u);
+        atomicStore(&tile_area, 0u);
+        atomicStore(&tile_sum_x, 0u);
+        atomicStore(&tile_sum_y, 0u);
+        atomicStore(&tile_min_x, 0xFFFFFFFFu);
+        atomicStore(&tile_min_y, 0xFFFFFFFFu);
+        atomicStore(&tile_max_x, 0u);
+        atomicStore(&tile_max_y, 0u);
+    }
+    workgroupBarrier();
+
+    let label = label_at(id.x, id.y);
+    let shared_label = workgroupUniformLoad(&tile_label);
+    if (label != NO_LABEL) {
+        if (label == shared_label) {
+            // 256 coordinates below 2^24 each, so the tile sums fit in u32
+            atomicAdd(&tile_area, 1u);
+            atomicAdd(&tile_sum_x, id.x);
+            atomicAdd(&tile_sum_y, id.y);
+            atomicMin(&tile_min_x, id.x);
+            atomicMin(&tile_min_y, id.y);
+            atomicMax(&tile_max_x, id.x);
+            atomicMax(&tile_max_y, id.y);
+        } else {
+            add_global(label, 1u, id.x, id.y, id.x, id.y, id.x, id.y);
+        }
+    }
+    workgroupBarrier();
+
+    if (local == 0u && shared_label != NO_LABEL) {
+        add_global(
+            shared_label,
+            atomicLoad(&tile_area),
+            atomicLoad(&tile_sum_x),
+            atomicLoad(&tile_sum_y),
+            atomicLoad(&tile_min_x),
+            atomicLoad(&tile_min_y),
+            atomicLoad(&tile_max_x),
+            atomicLoad(&tile_max_y),
+        );
+    }
+}
@@ -0,0 +1,167 @@
+// Harris and FAST corner detection with non-maximum suppression
+//
+// A scoring pass (harris_response or fast_score) writes one f32 score per
+// pixel, NO_CORNER where the pixel is not a candidate. The collect pass then
+// appends every candidate that no candidate within nms_radius beats to a
+// compact corner list, so only the corners are read back to the CPU.
+// Scores follow features2d::harris_corners and features2d::fast.
+
+struct Params {
+    width: u32,
+    height: u32,
+    half_block: u32,
+    nms_radius: u32,
+    k: f32,
+    threshold: f32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+struct Corner {
+    x: u32,
+    y: u32,
+    score: f32,
+}
+
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+@group(0) @binding(1) var<storage, read_write> scores: array<f32>;
+@group(0) @binding(2) var<uniform> params: Params;
+@group(0) @binding(3) var<storage, read_write> corner_count: atomic<u32>;
+@group(0) @binding(4) var<storage, read_write> corners: array<Corner>;
+
+const NO_CORNER: f32 = -3.0e38;
+
+fn pixel(x: i32, y: i32) -> f32 {
+    let i = u32(y) * params.width + u32(x);
+    return f32((input[i / 4u] >> ((i % 4u) * 8u)) & 0xFFu);
+}
+
+// |Sobel| clamped to 0..255 as imgproc::sobel stores it, zero on the border
+fn gradients(x: i32, y: i32) -> vec2<f32> {
+    let w = i32(params.width);
+    let h = i32(params.height);
+    if (x <= 0 || y <= 0 || x >= w - 1 || y >= h - 1) {
+        return vec2<f32>(0.0, 0.0);
+    }
+
+    let tl = pixel(x - 1, y - 1);
+    let t = pixel(x, y - 1);
+    let tr = pixel(x + 1, y - 1);
+    let l = pixel(x - 1, y);
+    let r = pixel(x + 1, y);
+    let bl = pixel(x - 1, y + 1);
+    let b = pixel(x, y + 1);
+    let br = pixel(x + 1, y + 1);
+
+    let gx = (tr + 2.0 * r + br) - (tl + 2.0 * l + bl);
+    let gy = (bl + 2.0 * b + br) - (tl + 2.0 * t + tr);
+    return min(abs(vec2<f32>(gx, gy)), vec2<f32>(255.0, 255.0));
+}
+
+@compute @workgroup_size(16, 16)
+fn harris_response(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) {
+        return;
+    }
+    let index = id.y * params.width + id.x;
+    let half = params.half_block;
+    if (id.x < half || id.y < half || id.x + half >= params.width || id.y + half >= params.height) {
+        scores[index] = NO_CORNER;
+        return;
+    }
+
+    var sxx = 0.0;
+    var syy = 0.0;
+    var sxy = 0.0;
+    let h = i32(half);
+    for (var dy = -h; dy <= h; dy = dy + 1) {
+        for (var dx = -h; dx <= h; dx = dx + 1) {
+            let g = gradients(i32(id.x) + dx, i32(id.y) + dy);
+            sxx = sxx + g.x * g.x;
+            syy = syy + g.y * g.y;
+            sxy = sxy + g.x * g.y;
+        }
+    }
+
+    let det = sxx * syy - sxy * sxy;
+    let trace = sxx + syy;
+    let response = det - params.k * trace * trace;
+    scores[index] = select(NO_CORNER, response, response > params.threshold);
+}
+
+@compute @workgroup_size(16, 16)
+fn fast_score(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) {
+        return;
+    }
+    let index = id.y * params.width + id.x;
+    if (id.x < 3u || id.y < 3u || id.x + 3u >= params.width || id.y + 3u >= params.height) {
+        scores[index] = NO_CORNER;
+        return;
+    }
+
+    // Bresenham circle of radius 3
+    var offsets = array<vec2<i32>, 16>(
+        vec2<i32>(0, -3), vec2<i32>(1, -3), vec2<i32>(2, -2), vec2<i32>(3, -1),
+        vec2<i32>(3, 0), vec2<i32>(3, 1), vec2<i32>(2, 2), vec2<i32>(1, 3),
+        vec2<i32>(0, 3), vec2<i32>(-1, 3), vec2<i32>(-2, 2), vec2<i32>(-3, 1),
+        vec2<i32>(-3, 0), vec2<i32>(-3, -1), vec2<i32>(-2, -2), vec2<i32>(-1, -3),
+    );
+    let x = i32(id.x);
+    let y = i32(id.y);
+    var circle: array<f32, 16>;
+    for (var i = 0u; i < 16u; i = i + 1u) {
+        circle[i] = pixel(x + offsets[i].x, y + offsets[i].y);
+    }
+
+    let center = pixel(x, y);
+    let upper = center + params.threshold;
+    let lower = center - params.threshold;
+    var run_brighter = 0u;
+    var run_darker = 0u;
+    var best = 0u;
+    // Twice around the circle so arcs crossing the start are counted whole
+    for (var i = 0u; i < 32u; i = i + 1u) {
+        let v = circle[i % 16u];
+        if (v > upper) {
+            run_brighter = run_brighter + 1u;
+            run_darker = 0u;
+        } else if (v < lower) {
+            run_darker = run_darker + 1u;
+            run_brighter = 0u;
+        } else {
+            run_brighter = 0u;
+            run_darker = 0u;
+        }
+        best = max(best, max(run_brighter, run_darker));
+    }
+
+    scores[index] = select(NO_CORNER, f32(best), best >= 12u);
+}
+
+@compute @workgroup_size(16, 16)
+fn collect(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) {
+        return;
+    }
+    let score = scores[id.y * params.width + id.x];
+    if (score == NO_CORNER) {
+        return;
+    }
+
+    let r = i32(params.nms_radius);
+    let w = i32(params.width);
+    let h = i32(params.height);
+    for (var dy = -r; dy <= r; dy = dy + 1) {
+        for (var dx = -r; dx <= r; dx = dx + 1) {
+            let nx = i32(id.x) + dx;
+            let ny = i32(id.y) + dy;
+            if (nx >= 0 && ny >= 0 && nx < w && ny < h && scores[u32(ny) * params.width + u32(nx)] > score) {
+                return;
+            }
+        }
+    }
+
+    let slot = atomicAdd(&corner_count, 1u);
+    corners[slot] = Corner(id.x, id.y, score);
+}
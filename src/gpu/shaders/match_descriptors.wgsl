@@ -0,0 +1,93 @@
+// Brute-force descriptor matching: the two nearest train descriptors for
+// every query descriptor
+//
+// Descriptors are stored as `words` u32 words each: packed bytes for the
+// Hamming distance, or f32 bit patterns for the L2 distance. Each thread
+// walks one query's row of the distance matrix, keeping the best and
+// second-best train index; ties go to the lower index, as with a stable
+// sort on the CPU.
+
+struct Params {
+    query_count: u32,
+    train_count: u32,
+    words: u32,
+    _pad: u32,
+}
+
+struct Match2 {
+    best_idx: u32,
+    best_dist: f32,
+    second_idx: u32,
+    second_dist: f32,
+}
+
+@group(0) @binding(0) var<storage, read> query: array<u32>;
+@group(0) @binding(1) var<storage, read> train: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+@group(0) @binding(3) var<storage, read_write> matches: array<Match2>;
+
+const NO_MATCH: u32 = 0xFFFFFFFFu;
+const FAR: f32 = 3.0e38;
+
+fn hamming(q: u32, t: u32) -> f32 {
+    var bits = 0u;
+    for (var w = 0u; w < params.words; w = w + 1u) {
+        bits = bits + countOneBits(query[q * params.words + w] ^ train[t * params.words + w]);
+    }
+    return f32(bits);
+}
+
+// Squared distance; the square root is taken once for the two survivors
+fn l2_squared(q: u32, t: u32) -> f32 {
+    var sum = 0.0;
+    for (var w = 0u; w < params.words; w = w + 1u) {
+        let d = bitcast<f32>(query[q * params.words + w]) - bitcast<f32>(train[t * params.words + w]);
+        sum = sum + d * d;
+    }
+    return sum;
+}
+
+fn nearest_two(q: u32, l2: bool) {
+    var best_idx = NO_MATCH;
+    var best = FAR;
+    var second_idx = NO_MATCH;
+    var second = FAR;
+    for (var t = 0u; t < params.train_count; t = t + 1u) {
+        var d: f32;
+        if (l2) {
+            d = l2_squared(q, t);
+        } else {
+            d = hamming(q, t);
+        }
+        if (d < best) {
+            second_idx = best_idx;
+            second = best;
+            best_idx = t;
+            best = d;
+        } else if (d < second) {
+            second_idx = t;
+            second = d;
+        }
+    }
+
+    var result = Match2(best_idx, best, second_idx, second);
+    if (l2) {
+        result.best_dist = sqrt(best);
+        result.second_dist = sqrt(second);
+    }
+    matches[q] = result;
+}
+
+@compute @workgroup_size(64)
+fn match_hamming(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x < params.query_count) {
+        nearest_two(id.x, false);
+    }
+}
+
+@compute @workgroup_size(64)
+fn match_l2(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x < params.query_count) {
+        nearest_two(id.x, true);
+    }
+}
@@ -0,0 +1,144 @@
+// MOG2 background subtraction: per-pixel Gaussian mixture update
+// Mirrors video::background_subtraction::BackgroundSubtractorMOG2::apply,
+// which models the mean RGB intensity
+//
+// The model holds, per pixel, K means, then K variances, then K weights.
+// Each invocation updates four consecutive pixels and writes their mask
+// bytes as one whole u32 word, so invocations never share an output word.
+
+const K: u32 = 5u;
+
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+@group(0) @binding(1) var<storage, read_write> model: array<f32>;
+@group(0) @binding(2) var<storage, read_write> mask: array<u32>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+struct Params {
+    width: u32,
+    height: u32,
+    alpha: f32,
+    var_threshold: f32,
+    background_ratio: f32,
+    var_init: f32,
+    var_min: f32,
+    var_max: f32,
+}
+
+fn read_input_byte(byte_index: u32) -> u32 {
+    let word = input[byte_index / 4u];
+    return (word >> ((byte_index % 4u) * 8u)) & 0xFFu;
+}
+
+// Update one pixel's mixture and report whether it is background
+fn update_pixel(pixel: u32) -> bool {
+    let rgb = pixel * 3u;
+    let intensity = f32(read_input_byte(rgb) + read_input_byte(rgb + 1u) + read_input_byte(rgb + 2u)) / 3.0;
+
+    let base = pixel * 3u * K;
+    var mean: array<f32, 5>;
+    var variance: array<f32, 5>;
+    var weight: array<f32, 5>;
+    for (var k = 0u; k < K; k++) {
+        mean[k] = model[base + k];
+        variance[k] = model[base + K + k];
+        weight[k] = model[base + 2u * K + k];
+    }
+
+    var matched = false;
+    var match_idx = 0u;
+    for (var k = 0u; k < K; k++) {
+        if (abs(intensity - mean[k]) < params.var_threshold * sqrt(variance[k])) {
+            matched = true;
+            match_idx = k;
+            break;
+        }
+    }
+
+    var is_background = false;
+    if (matched) {
+        let rho = params.alpha * weight[match_idx];
+        mean[match_idx] = (1.0 - rho) * mean[match_idx] + rho * intensity;
+        let diff = intensity - mean[match_idx];
+        variance[match_idx] = clamp(
+            (1.0 - rho) * variance[match_idx] + rho * diff * diff,
+            params.var_min,
+            params.var_max,
+        );
+
+        var weight_sum = 0.0;
+        for (var k = 0u; k < K; k++) {
+            weight_sum += weight[k];
+            if (weight_sum > params.background_ratio) {
+                is_background = k >= match_idx;
+                break;
+            }
+        }
+    } else {
+        // Replace the least probable Gaussian
+        mean[K - 1u] = intensity;
+        variance[K - 1u] = params.var_init;
+        weight[K - 1u] = 0.05;
+    }
+
+    var total = 0.0;
+    for (var k = 0u; k < K; k++) {
+        weight[k] = (1.0 - params.alpha) * weight[k];
+        if (matched && k == match_idx) {
+            weight[k] += params.alpha;
+        }
+        total += weight[k];
+    }
+    if (total > 0.0) {
+        for (var k = 0u; k < K; k++) {
+            weight[k] /= total;
+        }
+    }
+
+    // Stable insertion sort by weight / sigma, most probable first
+    for (var i = 1u; i < K; i++) {
+        let m = mean[i];
+        let v = variance[i];
+        let w = weight[i];
+        let score = w / sqrt(v);
+        var j = i;
+        while (j > 0u && weight[j - 1u] / sqrt(variance[j - 1u]) < score) {
+            mean[j] = mean[j - 1u];
+            variance[j] = variance[j - 1u];
+            weight[j] = weight[j - 1u];
+            j--;
+        }
+        mean[j] = m;
+        variance[j] = v;
+        weight[j] = w;
+    }
+
+    for (var k = 0u; k < K; k++) {
+        model[base + k] = mean[k];
+        model[base + K + k] = variance[k];
+        model[base + 2u * K + k] = weight[k];
+    }
+
+    return is_background;
+}
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let group = global_id.y * num_workgroups.x * 256u + global_id.x;
+    let num_pixels = params.width * params.height;
+    let first = group * 4u;
+    if (first >= num_pixels) {
+        return;
+    }
+
+    var word = 0u;
+    for (var i = 0u; i < 4u; i++) {
+        let pixel = first + i;
+        if (pixel < num_pixels && !update_pixel(pixel)) {
+            word |= 0xFFu << (i * 8u);
+        }
+    }
+    mask[group] = word;
+}
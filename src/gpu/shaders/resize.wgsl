@@ -1,5 +1,6 @@
-// Bilinear Resize Shader
-// GPU-accelerated image resizing with bilinear interpolation
+// Resize Shader
+// GPU-accelerated image resizing with nearest, bilinear, bicubic, area and
+// Lanczos4 interpolation (selected by params.mode)
 
 struct ResizeParams {
     src_width: u32,
@@ -7,7 +8,7 @@ struct ResizeParams {
     dst_width: u32,
     dst_height: u32,
     channels: u32,
-    _pad0: u32,
+    mode: u32,      // 0 = nearest, 1 = bilinear, 2 = bicubic, 3 = area, 4 = lanczos4
     _pad1: u32,
     _pad2: u32,
 }
@@ -16,8 +17,6 @@ struct ResizeParams {
 @group(0) @binding(1) var<storage, read_write> output: array<u32>;
 @group(0) @binding(2) var<uniform> params: ResizeParams;
 
-// Bilinear interpolation
-
 // === Byte Access Helpers ===
 // Required for correct RGBA byte extraction from u32 storage buffers
 
@@ -138,10 +137,118 @@ fn bilinear_sample(x: f32, y: f32, ch: u32) -> f32 {
     let v01 = f32(read_byte(&input, idx01));
     let v11 = f32(read_byte(&input, idx11));
 
-    // Bilinear interpolation
-    let v0 = mix(v00, v10, fx);
-    let v1 = mix(v01, v11, fx);
-    return mix(v0, v1, fy);
+    // Bilinear interpolation, weighted in the same order as the CPU path
+    let w00 = (1.0 - fx) * (1.0 - fy);
+    let w10 = fx * (1.0 - fy);
+    let w01 = (1.0 - fx) * fy;
+    let w11 = fx * fy;
+    return v00 * w00 + v10 * w10 + v01 * w01 + v11 * w11;
+}
+
+const MODE_NEAREST: u32 = 0u;
+const MODE_LINEAR: u32 = 1u;
+const MODE_CUBIC: u32 = 2u;
+const MODE_AREA: u32 = 3u;
+const MODE_LANCZOS4: u32 = 4u;
+const PI: f32 = 3.14159265358979;
+
+// Read a source sample with replicated borders
+fn src_value(x: i32, y: i32, ch: u32) -> f32 {
+    let cx = u32(clamp(x, 0, i32(params.src_width) - 1));
+    let cy = u32(clamp(y, 0, i32(params.src_height) - 1));
+    return f32(read_byte(&input, (cx + cy * params.src_width) * params.channels + ch));
+}
+
+// Bicubic convolution kernel, A = -0.75 (matches the CPU path)
+fn cubic_weight(t: f32) -> f32 {
+    let a = -0.75;
+    let x = abs(t);
+    if (x <= 1.0) {
+        return ((a + 2.0) * x - (a + 3.0)) * x * x + 1.0;
+    }
+    if (x < 2.0) {
+        return ((a * x - 5.0 * a) * x + 8.0 * a) * x - 4.0 * a;
+    }
+    return 0.0;
+}
+
+fn lanczos4_weight(t: f32) -> f32 {
+    if (abs(t) < 1e-6) {
+        return 1.0;
+    }
+    if (abs(t) >= 4.0) {
+        return 0.0;
+    }
+    let px = PI * t;
+    return 4.0 * sin(px) * sin(px / 4.0) / (px * px);
+}
+
+fn kernel_weight(t: f32) -> f32 {
+    if (params.mode == MODE_LANCZOS4) {
+        return lanczos4_weight(t);
+    }
+    return cubic_weight(t);
+}
+
+// Separable interpolating kernel (bicubic: radius 2, lanczos4: radius 4)
+fn kernel_sample(x: f32, y: f32, ch: u32, radius: i32) -> f32 {
+    let bx = i32(floor(x));
+    let by = i32(floor(y));
+    var sum = 0.0;
+    var weight_sum = 0.0;
+
+    for (var j = by - radius + 1; j <= by + radius; j++) {
+        let wy = kernel_weight(y - f32(j));
+        for (var i = bx - radius + 1; i <= bx + radius; i++) {
+            let w = wy * kernel_weight(x - f32(i));
+            sum += w * src_value(i, j, ch);
+            weight_sum += w;
+        }
+    }
+
+    return sum / weight_sum;
+}
+
+// First source index and tap count of the area footprint along one axis
+fn area_range(d: u32, scale: f32, src_len: u32) -> vec2<i32> {
+    if (scale < 1.0) {
+        // Enlarging: linear interpolation between the two nearest centres
+        let s = (f32(d) + 0.5) * scale - 0.5;
+        return vec2<i32>(i32(floor(s)), 2);
+    }
+    let start = f32(d) * scale;
+    let end = min(start + scale, f32(src_len));
+    let first = i32(floor(start));
+    return vec2<i32>(first, i32(ceil(end)) - first);
+}
+
+fn area_weight(i: i32, d: u32, scale: f32, src_len: u32) -> f32 {
+    if (scale < 1.0) {
+        let s = (f32(d) + 0.5) * scale - 0.5;
+        return max(1.0 - abs(s - f32(i)), 0.0);
+    }
+    let start = f32(d) * scale;
+    let end = min(start + scale, f32(src_len));
+    return max(min(f32(i + 1), end) - max(f32(i), start), 0.0);
+}
+
+// Coverage-weighted box average when shrinking, linear when enlarging
+fn area_sample(dst_x: u32, dst_y: u32, ch: u32, scale_x: f32, scale_y: f32) -> f32 {
+    let rx = area_range(dst_x, scale_x, params.src_width);
+    let ry = area_range(dst_y, scale_y, params.src_height);
+    var sum = 0.0;
+    var weight_sum = 0.0;
+
+    for (var j = ry.x; j < ry.x + ry.y; j++) {
+        let wy = area_weight(j, dst_y, scale_y, params.src_height);
+        for (var i = rx.x; i < rx.x + rx.y; i++) {
+            let w = wy * area_weight(i, dst_x, scale_x, params.src_width);
+            sum += w * src_value(i, j, ch);
+            weight_sum += w;
+        }
+    }
+
+    return sum / max(weight_sum, 1e-6);
 }
 
 @compute @workgroup_size(16, 16)
@@ -160,14 +267,34 @@ fn resize_bilinear(@builtin(global_invocation_id) id: vec3<u32>) {
     let src_x = (f32(dst_x) + 0.5) * scale_x - 0.5;
     let src_y = (f32(dst_y) + 0.5) * scale_y - 0.5;
 
-    // Clamp to valid range
+    // Linear clamps the half-pixel position to the edge, like the CPU path
     let clamped_x = clamp(src_x, 0.0, f32(params.src_width - 1u));
     let clamped_y = clamp(src_y, 0.0, f32(params.src_height - 1u));
 
     // Process each channel
     for (var ch = 0u; ch < params.channels; ch++) {
-        let value = bilinear_sample(clamped_x, clamped_y, ch);
+        var value: f32;
+        switch params.mode {
+            case MODE_NEAREST: {
+                let nx = min(u32(f32(dst_x) * scale_x), params.src_width - 1u);
+                let ny = min(u32(f32(dst_y) * scale_y), params.src_height - 1u);
+                value = src_value(i32(nx), i32(ny), ch);
+            }
+            case MODE_CUBIC: {
+                value = kernel_sample(src_x, src_y, ch, 2);
+            }
+            case MODE_AREA: {
+                value = area_sample(dst_x, dst_y, ch, scale_x, scale_y);
+            }
+            case MODE_LANCZOS4: {
+                value = kernel_sample(src_x, src_y, ch, 4);
+            }
+            default: {
+                value = bilinear_sample(clamped_x, clamped_y, ch);
+            }
+        }
         let out_idx = (dst_x + dst_y * params.dst_width) * params.channels + ch;
-        write_byte(&output, out_idx, u32(clamp(value, 0.0, 255.0)));
+        // Halves round up as on the CPU; WGSL round() goes to even
+        write_byte(&output, out_idx, u32(clamp(floor(value + 0.5), 0.0, 255.0)));
     }
 }
@@ -1,5 +1,6 @@
 // RGB to Grayscale conversion shader
-// Uses standard luminance formula: 0.299*R + 0.587*G + 0.114*B
+// Uses standard luminance formula: 0.299*R + 0.587*G + 0.114*B, reading
+// the channels in BGR order when params.bgr is set
 
 @group(0) @binding(0) var<storage, read> input: array<u32>;
 @group(0) @binding(1) var<storage, read_write> output: array<u32>;
@@ -9,7 +10,7 @@ struct Params {
     width: u32,
     height: u32,
     channels: u32,  // Should be 3 for RGB
-    _pad: u32,
+    bgr: u32,       // 1 if the input is BGR-ordered
 }
 
 
@@ -123,10 +124,12 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx_color = (y * params.width + x) * params.channels;
     let idx_gray = y * params.width + x;
 
-    // Read RGB values
-    let r = f32(read_byte(&input, idx_color));
+    // Read RGB values, swapping the outer channels for BGR input
+    let first = f32(read_byte(&input, idx_color));
     let g = f32(read_byte(&input, idx_color + 1u));
-    let b = f32(read_byte(&input, idx_color + 2u));
+    let third = f32(read_byte(&input, idx_color + 2u));
+    let r = select(first, third, params.bgr != 0u);
+    let b = select(third, first, params.bgr != 0u);
 
     // Convert to grayscale using luminance formula
     let gray = 0.299 * r + 0.587 * g + 0.114 * b;
@@ -0,0 +1,96 @@
+// 4:2:0 YUV (NV12 / NV21 / I420) to RGB or RGBA conversion
+// BT.601 limited range, matching imgproc::yuv420_to_rgb
+//
+// Each invocation converts four consecutive pixels. Their RGB(A) bytes fill
+// exactly `channels` whole u32 words, so invocations never share an output
+// word and no read-modify-write races can occur.
+
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+@group(0) @binding(1) var<storage, read_write> output: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+struct Params {
+    width: u32,
+    height: u32,
+    chroma_layout: u32,  // 0 = NV12, 1 = NV21, 2 = I420
+    channels: u32,       // 3 = RGB, 4 = RGBA
+}
+
+fn read_input_byte(byte_index: u32) -> u32 {
+    let word = input[byte_index / 4u];
+    return (word >> ((byte_index % 4u) * 8u)) & 0xFFu;
+}
+
+fn convert_pixel(pixel: u32) -> vec3<u32> {
+    let w = params.width;
+    let h = params.height;
+    let x = pixel % w;
+    let y = pixel / w;
+    let luma = w * h;
+
+    var u_index: u32;
+    var v_index: u32;
+    if (params.chroma_layout == 2u) {
+        let i = (y / 2u) * (w / 2u) + x / 2u;
+        u_index = luma + i;
+        v_index = luma + luma / 4u + i;
+    } else {
+        let i = luma + (y / 2u) * w + (x / 2u) * 2u;
+        if (params.chroma_layout == 0u) {
+            u_index = i;
+            v_index = i + 1u;
+        } else {
+            u_index = i + 1u;
+            v_index = i;
+        }
+    }
+
+    let yy = 1.164 * (f32(read_input_byte(pixel)) - 16.0);
+    let u = f32(read_input_byte(u_index)) - 128.0;
+    let v = f32(read_input_byte(v_index)) - 128.0;
+
+    let rgb = vec3<f32>(
+        yy + 1.596 * v,
+        yy - 0.813 * v - 0.391 * u,
+        yy + 2.018 * u,
+    );
+    return vec3<u32>(clamp(round(rgb), vec3<f32>(0.0), vec3<f32>(255.0)));
+}
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let group = global_id.y * num_workgroups.x * 256u + global_id.x;
+    let pixel_count = params.width * params.height;
+    let first = group * 4u;
+    if (first >= pixel_count) {
+        return;
+    }
+
+    let channels = params.channels;
+    var words = array<u32, 4>(0u, 0u, 0u, 0u);
+
+    for (var k = 0u; k < 4u; k = k + 1u) {
+        let pixel = first + k;
+        if (pixel >= pixel_count) {
+            break;
+        }
+
+        let rgb = convert_pixel(pixel);
+        let bytes = vec4<u32>(rgb, 255u);
+        for (var c = 0u; c < channels; c = c + 1u) {
+            let b = k * channels + c;
+            words[b / 4u] = words[b / 4u] | (bytes[c] << ((b % 4u) * 8u));
+        }
+    }
+
+    let total_words = (pixel_count * channels + 3u) / 4u;
+    let base = group * channels;
+    for (var i = 0u; i < channels; i = i + 1u) {
+        if (base + i < total_words) {
+            output[base + i] = words[i];
+        }
+    }
+}
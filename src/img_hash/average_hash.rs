@@ -0,0 +1,50 @@
+use crate::core::Mat;
+use crate::error::Result;
+use super::hash::{gray_thumbnail, ImageHash, ImgHash};
+
+/// Average hash (aHash): 8x8 thumbnail thresholded at its mean
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AverageHash;
+
+impl AverageHash {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ImgHash for AverageHash {
+    fn compute(&self, src: &Mat) -> Result<ImageHash> {
+        let thumb = gray_thumbnail(src, 8, 8)?;
+        #[allow(clippy::cast_precision_loss)]
+        let mean = thumb.iter().sum::<f64>() / thumb.len() as f64;
+        let bits: Vec<bool> = thumb.iter().map(|&v| v > mean).collect();
+        Ok(ImageHash::from_bits(&bits))
+    }
+}
+
+/// Compute the 64-bit average hash of an image
+pub fn average_hash(src: &Mat) -> Result<ImageHash> {
+    AverageHash::new().compute(src)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    #[test]
+    fn test_average_hash_half_split() {
+        let mut img = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 32..64 {
+                img.at_mut(row, col).unwrap()[0] = 255;
+            }
+        }
+
+        let hash = average_hash(&img).unwrap();
+        assert_eq!(hash.len_bits(), 64);
+        // Right half of every thumbnail row is above the mean
+        assert!(hash.bytes().iter().all(|&b| b == 0x0F));
+    }
+}
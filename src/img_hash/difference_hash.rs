@@ -0,0 +1,53 @@
+use crate::core::Mat;
+use crate::error::Result;
+use super::hash::{gray_thumbnail, ImageHash, ImgHash};
+
+/// Difference hash (dHash): sign of the horizontal gradient on a 9x8 thumbnail
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DifferenceHash;
+
+impl DifferenceHash {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ImgHash for DifferenceHash {
+    fn compute(&self, src: &Mat) -> Result<ImageHash> {
+        let thumb = gray_thumbnail(src, 9, 8)?;
+        let mut bits = Vec::with_capacity(64);
+        for row in thumb.chunks(9) {
+            for pair in row.windows(2) {
+                bits.push(pair[1] > pair[0]);
+            }
+        }
+        Ok(ImageHash::from_bits(&bits))
+    }
+}
+
+/// Compute the 64-bit difference hash of an image
+pub fn difference_hash(src: &Mat) -> Result<ImageHash> {
+    DifferenceHash::new().compute(src)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    #[test]
+    fn test_difference_hash_gradient() {
+        let mut img = Mat::new(32, 90, 1, MatDepth::U8).unwrap();
+        for row in 0..32 {
+            for col in 0..90 {
+                #[allow(clippy::cast_possible_truncation)]
+                { img.at_mut(row, col).unwrap()[0] = (col * 2) as u8; }
+            }
+        }
+
+        // Brightness increases left to right, so every bit is set
+        let hash = difference_hash(&img).unwrap();
+        assert!(hash.bytes().iter().all(|&b| b == 0xFF));
+    }
+}
@@ -0,0 +1,155 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::to_gray;
+
+/// Binary image hash, packed 8 bits per byte (MSB first)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageHash {
+    bytes: Vec<u8>,
+}
+
+impl ImageHash {
+    /// Pack a bit sequence into a hash
+    #[must_use]
+    pub fn from_bits(bits: &[bool]) -> Self {
+        let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+        for (i, &bit) in bits.iter().enumerate() {
+            if bit {
+                bytes[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        Self { bytes }
+    }
+
+    /// Create a hash from already packed bytes
+    #[must_use]
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    #[must_use]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Number of bits in the hash
+    #[must_use]
+    pub fn len_bits(&self) -> usize {
+        self.bytes.len() * 8
+    }
+
+    /// Number of differing bits between two hashes of the same length
+    pub fn hamming_distance(&self, other: &ImageHash) -> Result<u32> {
+        if self.bytes.len() != other.bytes.len() {
+            return Err(Error::InvalidParameter(format!(
+                "Hash lengths differ: {} vs {} bytes",
+                self.bytes.len(),
+                other.bytes.len()
+            )));
+        }
+
+        Ok(self
+            .bytes
+            .iter()
+            .zip(&other.bytes)
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum())
+    }
+
+    /// Lowercase hex representation
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        self.bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+/// Common interface of the image hash algorithms
+pub trait ImgHash {
+    /// Compute the hash of an image (gray, RGB or RGBA, U8)
+    fn compute(&self, src: &Mat) -> Result<ImageHash>;
+
+    /// Compare two hashes; lower means more similar
+    fn compare(&self, a: &ImageHash, b: &ImageHash) -> Result<f64> {
+        Ok(f64::from(a.hamming_distance(b)?))
+    }
+}
+
+/// Convert to grayscale and area-average down to `width` x `height`.
+///
+/// Returns the row-major luminance samples in [0, 255].
+pub(crate) fn gray_thumbnail(src: &Mat, width: usize, height: usize) -> Result<Vec<f64>> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "Image hashing only supports U8 depth".to_string(),
+        ));
+    }
+
+    let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+    to_gray(src, &mut gray)?;
+
+    let rows = gray.rows();
+    let cols = gray.cols();
+    let data = gray.data();
+    let scale_x = cols as f64 / width as f64;
+    let scale_y = rows as f64 / height as f64;
+    let mut out = Vec::with_capacity(width * height);
+
+    for ty in 0..height {
+        let y0 = ty as f64 * scale_y;
+        let y1 = (ty + 1) as f64 * scale_y;
+        for tx in 0..width {
+            let x0 = tx as f64 * scale_x;
+            let x1 = (tx + 1) as f64 * scale_x;
+
+            // Area-weighted average of every source pixel overlapping the cell
+            let mut sum = 0.0;
+            let mut weight = 0.0;
+            let row_end = (y1.ceil() as usize).min(rows);
+            let col_end = (x1.ceil() as usize).min(cols);
+            for row in (y0.floor() as usize)..row_end {
+                let wy = (y1.min((row + 1) as f64) - y0.max(row as f64)).max(0.0);
+                for col in (x0.floor() as usize)..col_end {
+                    let wx = (x1.min((col + 1) as f64) - x0.max(col as f64)).max(0.0);
+                    let w = wx * wy;
+                    sum += w * f64::from(data[row * cols + col]);
+                    weight += w;
+                }
+            }
+            out.push(if weight > 0.0 { sum / weight } else { 0.0 });
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bits_packing() {
+        let hash = ImageHash::from_bits(&[true, false, false, false, false, false, false, true, true]);
+        assert_eq!(hash.bytes(), &[0x81, 0x80]);
+        assert_eq!(hash.to_hex(), "8180");
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let a = ImageHash::from_bytes(vec![0xFF, 0x00]);
+        let b = ImageHash::from_bytes(vec![0x0F, 0x01]);
+        assert_eq!(a.hamming_distance(&b).unwrap(), 5);
+        assert!(a.hamming_distance(&ImageHash::from_bytes(vec![0])).is_err());
+    }
+
+    #[test]
+    fn test_gray_thumbnail_bgr_tagged() {
+        use crate::core::types::ColorOrder;
+
+        let rgb = Mat::from_raw([255u8, 0, 0].repeat(4), 2, 2, 3, MatDepth::U8).unwrap();
+        let bgr = Mat::from_raw([0u8, 0, 255].repeat(4), 2, 2, 3, MatDepth::U8)
+            .unwrap()
+            .with_color_order(ColorOrder::Bgr);
+        assert_eq!(gray_thumbnail(&bgr, 1, 1).unwrap(), gray_thumbnail(&rgb, 1, 1).unwrap());
+    }
+}
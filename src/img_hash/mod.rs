@@ -0,0 +1,15 @@
+//! Perceptual image hashing
+//!
+//! Compact fingerprints for de-duplication and near-duplicate search.
+//! Hashes are compared with the Hamming distance: identical images give 0,
+//! visually similar images give small distances.
+
+pub mod hash;
+pub mod average_hash;
+pub mod phash;
+pub mod difference_hash;
+
+pub use hash::*;
+pub use average_hash::*;
+pub use phash::*;
+pub use difference_hash::*;
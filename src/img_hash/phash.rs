@@ -0,0 +1,107 @@
+#![allow(clippy::cast_precision_loss)]
+use crate::core::Mat;
+use crate::error::Result;
+use super::hash::{gray_thumbnail, ImageHash, ImgHash};
+
+const DCT_SIZE: usize = 32;
+const HASH_SIZE: usize = 8;
+
+/// Perceptual hash (pHash): low-frequency DCT coefficients of a 32x32
+/// thumbnail thresholded at their mean (DC term excluded)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PHash;
+
+impl PHash {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ImgHash for PHash {
+    fn compute(&self, src: &Mat) -> Result<ImageHash> {
+        let thumb = gray_thumbnail(src, DCT_SIZE, DCT_SIZE)?;
+        let coeffs = dct_top_left(&thumb, DCT_SIZE, HASH_SIZE);
+
+        // The DC term only carries overall brightness; zero it like OpenCV does
+        let mut low = coeffs;
+        low[0] = 0.0;
+        let mean = low.iter().sum::<f64>() / low.len() as f64;
+
+        let bits: Vec<bool> = low.iter().map(|&c| c > mean).collect();
+        Ok(ImageHash::from_bits(&bits))
+    }
+}
+
+/// Compute the 64-bit perceptual hash of an image
+pub fn p_hash(src: &Mat) -> Result<ImageHash> {
+    PHash::new().compute(src)
+}
+
+/// Orthonormal 2D DCT-II of an `n`x`n` block, keeping only the top-left
+/// `keep`x`keep` coefficients (row-major)
+fn dct_top_left(data: &[f64], n: usize, keep: usize) -> Vec<f64> {
+    let nf = n as f64;
+    let basis: Vec<f64> = (0..keep)
+        .flat_map(|k| {
+            (0..n).map(move |i| {
+                let scale = if k == 0 { (1.0 / nf).sqrt() } else { (2.0 / nf).sqrt() };
+                scale * (std::f64::consts::PI * (2.0 * i as f64 + 1.0) * k as f64 / (2.0 * nf)).cos()
+            })
+        })
+        .collect();
+
+    // Rows first, then columns
+    let mut rows_dct = vec![0.0; n * keep];
+    for row in 0..n {
+        for k in 0..keep {
+            rows_dct[row * keep + k] = (0..n).map(|i| data[row * n + i] * basis[k * n + i]).sum();
+        }
+    }
+
+    let mut out = vec![0.0; keep * keep];
+    for ky in 0..keep {
+        for kx in 0..keep {
+            out[ky * keep + kx] = (0..n).map(|row| rows_dct[row * keep + kx] * basis[ky * n + row]).sum();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    fn pattern(offset: u8) -> Mat {
+        let mut img = Mat::new(64, 64, 3, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 0..64 {
+                let v = if (row / 16 + col / 8) % 2 == 0 { 40 } else { 200 };
+                let pixel = img.at_mut(row, col).unwrap();
+                pixel.fill(v + offset);
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_phash_brightness_invariant() {
+        let a = p_hash(&pattern(0)).unwrap();
+        let b = p_hash(&pattern(30)).unwrap();
+        assert_eq!(a.hamming_distance(&b).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_phash_distinguishes_images() {
+        let a = p_hash(&pattern(0)).unwrap();
+        let flat = Mat::new(64, 64, 3, MatDepth::U8).unwrap();
+        let mut noisy = flat.clone_mat();
+        for (i, v) in noisy.data_mut().iter_mut().enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            { *v = ((i * 7919) % 251) as u8; }
+        }
+        let b = p_hash(&noisy).unwrap();
+        assert!(PHash::new().compare(&a, &b).unwrap() > 10.0);
+    }
+}
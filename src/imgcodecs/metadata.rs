@@ -0,0 +1,437 @@
+//! Exif and XMP passthrough for JPEG and PNG
+//!
+//! The image decoder drops metadata, so a read-process-write round trip loses
+//! capture settings, lens data and GPS tags. [`imread_with_metadata`] keeps
+//! the raw Exif and XMP blocks next to the pixels, and
+//! [`imwrite_with_metadata`] embeds them again: as APP1 segments in JPEG and
+//! as `eXIf` / `iTXt` chunks in PNG. The blocks are copied verbatim except
+//! for the orientation tag, which [`ImageMetadata::apply_orientation`] resets
+//! once the rotation has been baked into the pixels.
+
+use super::{imread, imwrite};
+use crate::core::Mat;
+use crate::error::{Error, Result};
+use std::path::Path;
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const ORIENTATION_TAG: u16 = 0x0112;
+/// Largest JPEG segment payload: 65535 minus the two length bytes
+const MAX_SEGMENT: usize = 65533;
+
+/// Raw metadata blocks of an image file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageMetadata {
+    /// Exif block as a TIFF structure (starting with `II*\0` or `MM\0*`),
+    /// without the JPEG `Exif\0\0` prefix
+    pub exif: Option<Vec<u8>>,
+    /// XMP packet
+    pub xmp: Option<String>,
+}
+
+impl ImageMetadata {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.exif.is_none() && self.xmp.is_none()
+    }
+
+    /// Exif orientation (1-8, 1 = upright), if the Exif block has one
+    #[must_use]
+    pub fn orientation(&self) -> Option<u16> {
+        let exif = self.exif.as_deref()?;
+        let offset = find_orientation(exif)?;
+        let value = read_u16(exif, offset, exif[0] == b'I')?;
+        (1..=8).contains(&value).then_some(value)
+    }
+
+    /// Mark the image as upright: set the Exif and XMP orientation to 1
+    pub fn reset_orientation(&mut self) {
+        if let Some(exif) = self.exif.as_mut() {
+            if let Some(offset) = find_orientation(exif) {
+                let bytes = if exif[0] == b'I' { 1u16.to_le_bytes() } else { 1u16.to_be_bytes() };
+                exif[offset..offset + 2].copy_from_slice(&bytes);
+            }
+        }
+        if let Some(xmp) = self.xmp.as_mut() {
+            *xmp = reset_xmp_orientation(xmp);
+        }
+    }
+
+    /// Rotate and flip `img` so that it displays upright according to the
+    /// Exif orientation, then reset the orientation tag so viewers do not
+    /// apply it a second time
+    pub fn apply_orientation(&mut self, img: &Mat) -> Result<Mat> {
+        let oriented = match self.orientation() {
+            Some(orientation) if orientation != 1 => orient(img, orientation)?,
+            _ => return Ok(img.clone()),
+        };
+        self.reset_orientation();
+        Ok(oriented)
+    }
+}
+
+/// Read the Exif and XMP blocks of a JPEG or PNG file
+///
+/// Other formats, and files without metadata, give empty metadata.
+pub fn read_metadata<P: AsRef<Path>>(path: P) -> Result<ImageMetadata> {
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        read_jpeg_metadata(&bytes)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        read_png_metadata(&bytes)
+    } else {
+        Ok(ImageMetadata::default())
+    }
+}
+
+/// [`imread`] that also returns the file's metadata
+pub fn imread_with_metadata<P: AsRef<Path>>(path: P) -> Result<(Mat, ImageMetadata)> {
+    let path = path.as_ref();
+    Ok((imread(path)?, read_metadata(path)?))
+}
+
+/// [`imwrite`] that embeds `metadata` in the written file
+///
+/// Supported for `.jpg`/`.jpeg` and `.png` paths; other formats fail unless
+/// `metadata` is empty.
+pub fn imwrite_with_metadata<P: AsRef<Path>>(path: P, mat: &Mat, metadata: &ImageMetadata) -> Result<()> {
+    let path = path.as_ref();
+    if metadata.is_empty() {
+        return imwrite(path, mat);
+    }
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let is_jpeg = match extension.as_str() {
+        "jpg" | "jpeg" => true,
+        "png" => false,
+        _ => {
+            return Err(Error::UnsupportedOperation(format!(
+                "Metadata can only be written to JPEG and PNG files, not '.{extension}'"
+            )))
+        }
+    };
+    if is_jpeg && metadata.exif.as_ref().is_some_and(|e| e.len() + EXIF_HEADER.len() > MAX_SEGMENT) {
+        return Err(Error::InvalidParameter(
+            "Exif block too large for a JPEG APP1 segment".to_string(),
+        ));
+    }
+
+    imwrite(path, mat)?;
+    let encoded = std::fs::read(path)?;
+    let with_metadata = if is_jpeg {
+        insert_jpeg_metadata(&encoded, metadata)?
+    } else {
+        insert_png_metadata(&encoded, metadata)?
+    };
+    std::fs::write(path, with_metadata)?;
+    Ok(())
+}
+
+fn read_jpeg_metadata(bytes: &[u8]) -> Result<ImageMetadata> {
+    let mut metadata = ImageMetadata::default();
+    for (marker, payload) in jpeg_segments(bytes)? {
+        if marker != 0xE1 {
+            continue;
+        }
+        if let Some(exif) = payload.strip_prefix(EXIF_HEADER) {
+            metadata.exif.get_or_insert_with(|| exif.to_vec());
+        } else if let Some(xmp) = payload.strip_prefix(XMP_HEADER) {
+            metadata.xmp.get_or_insert_with(|| String::from_utf8_lossy(xmp).into_owned());
+        }
+    }
+    Ok(metadata)
+}
+
+/// Marker and payload of each JPEG segment before the image data
+fn jpeg_segments(bytes: &[u8]) -> Result<Vec<(u8, &[u8])>> {
+    let invalid = || Error::InvalidFormat("Truncated JPEG segment".to_string());
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return Err(Error::InvalidFormat("Expected a JPEG marker".to_string()));
+        }
+        let marker = bytes[pos + 1];
+        // Start of scan: entropy-coded data follows
+        if marker == 0xDA {
+            break;
+        }
+        let length = usize::from(u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]));
+        let end = pos + 2 + length;
+        if length < 2 || end > bytes.len() {
+            return Err(invalid());
+        }
+        segments.push((marker, &bytes[pos + 4..end]));
+        pos = end;
+    }
+    Ok(segments)
+}
+
+fn insert_jpeg_metadata(encoded: &[u8], metadata: &ImageMetadata) -> Result<Vec<u8>> {
+    // Keep a leading JFIF APP0 segment first, as its specification requires
+    let mut insert_at = 2;
+    if let Some(&(0xE0, payload)) = jpeg_segments(encoded)?.first() {
+        insert_at += 4 + payload.len();
+    }
+
+    let mut out = Vec::with_capacity(encoded.len() + 1024);
+    out.extend_from_slice(&encoded[..insert_at]);
+    if let Some(exif) = &metadata.exif {
+        push_jpeg_segment(&mut out, 0xE1, &[EXIF_HEADER, exif])?;
+    }
+    if let Some(xmp) = &metadata.xmp {
+        push_jpeg_segment(&mut out, 0xE1, &[XMP_HEADER, xmp.as_bytes()])?;
+    }
+    out.extend_from_slice(&encoded[insert_at..]);
+    Ok(out)
+}
+
+fn push_jpeg_segment(out: &mut Vec<u8>, marker: u8, parts: &[&[u8]]) -> Result<()> {
+    let length = parts.iter().map(|p| p.len()).sum::<usize>();
+    let length = u16::try_from(length + 2)
+        .map_err(|_| Error::InvalidParameter("Metadata block too large for a JPEG segment".to_string()))?;
+    out.extend_from_slice(&[0xFF, marker]);
+    out.extend_from_slice(&length.to_be_bytes());
+    for part in parts {
+        out.extend_from_slice(part);
+    }
+    Ok(())
+}
+
+fn read_png_metadata(bytes: &[u8]) -> Result<ImageMetadata> {
+    let mut metadata = ImageMetadata::default();
+    for (kind, data) in png_chunks(bytes)? {
+        match kind {
+            b"eXIf" => {
+                metadata.exif.get_or_insert_with(|| data.to_vec());
+            }
+            b"iTXt" => {
+                // keyword \0 compression-flag method language \0 translated \0 text
+                let Some(text) = data.strip_prefix(XMP_KEYWORD).and_then(|d| d.strip_prefix(b"\0\0")) else {
+                    continue;
+                };
+                let mut fields = text.get(1..).unwrap_or_default().splitn(3, |&b| b == 0);
+                let (_, _, Some(xmp)) = (fields.next(), fields.next(), fields.next()) else {
+                    continue;
+                };
+                metadata.xmp.get_or_insert_with(|| String::from_utf8_lossy(xmp).into_owned());
+            }
+            _ => {}
+        }
+    }
+    Ok(metadata)
+}
+
+/// Type and data of each PNG chunk
+fn png_chunks(bytes: &[u8]) -> Result<Vec<(&[u8], &[u8])>> {
+    let mut chunks = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 12 <= bytes.len() {
+        let length = u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 12 + length;
+        if end > bytes.len() {
+            return Err(Error::InvalidFormat("Truncated PNG chunk".to_string()));
+        }
+        chunks.push((&bytes[pos + 4..pos + 8], &bytes[pos + 8..pos + 8 + length]));
+        pos = end;
+    }
+    Ok(chunks)
+}
+
+fn insert_png_metadata(encoded: &[u8], metadata: &ImageMetadata) -> Result<Vec<u8>> {
+    // Metadata goes right after IHDR, which is always the first chunk
+    let ihdr_length = png_chunks(encoded)?
+        .first()
+        .filter(|(kind, _)| *kind == b"IHDR")
+        .map(|(_, data)| data.len())
+        .ok_or_else(|| Error::InvalidFormat("PNG without IHDR chunk".to_string()))?;
+    let insert_at = PNG_SIGNATURE.len() + 12 + ihdr_length;
+
+    let mut out = Vec::with_capacity(encoded.len() + 1024);
+    out.extend_from_slice(&encoded[..insert_at]);
+    if let Some(exif) = &metadata.exif {
+        push_png_chunk(&mut out, b"eXIf", &[exif])?;
+    }
+    if let Some(xmp) = &metadata.xmp {
+        // Uncompressed, no language tag or translated keyword
+        push_png_chunk(&mut out, b"iTXt", &[XMP_KEYWORD, b"\0\0\0\0\0", xmp.as_bytes()])?;
+    }
+    out.extend_from_slice(&encoded[insert_at..]);
+    Ok(out)
+}
+
+fn push_png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], parts: &[&[u8]]) -> Result<()> {
+    let length = u32::try_from(parts.iter().map(|p| p.len()).sum::<usize>())
+        .map_err(|_| Error::InvalidParameter("Metadata block too large for a PNG chunk".to_string()))?;
+    out.extend_from_slice(&length.to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    for part in parts {
+        out.extend_from_slice(part);
+    }
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+    Ok(())
+}
+
+/// CRC-32 (ISO 3309) as used by PNG chunks
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn read_u16(bytes: &[u8], offset: usize, little_endian: bool) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(if little_endian { u16::from_le_bytes([b[0], b[1]]) } else { u16::from_be_bytes([b[0], b[1]]) })
+}
+
+fn read_u32(bytes: &[u8], offset: usize, little_endian: bool) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    let b = [b[0], b[1], b[2], b[3]];
+    Some(if little_endian { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) })
+}
+
+/// Offset of the orientation value in IFD0 of a TIFF structure
+fn find_orientation(tiff: &[u8]) -> Option<usize> {
+    let little_endian = match tiff.get(..4)? {
+        b"II*\0" => true,
+        b"MM\0*" => false,
+        _ => return None,
+    };
+    let ifd = read_u32(tiff, 4, little_endian)? as usize;
+    let count = usize::from(read_u16(tiff, ifd, little_endian)?);
+    (0..count).map(|i| ifd + 2 + 12 * i).find_map(|entry| {
+        // A SHORT value is stored inline at the start of the value field
+        (read_u16(tiff, entry, little_endian)? == ORIENTATION_TAG && read_u16(tiff, entry + 2, little_endian)? == 3)
+            .then_some(entry + 8)
+            .filter(|&offset| offset + 2 <= tiff.len())
+    })
+}
+
+/// Set `tiff:Orientation` to 1 in both the attribute and element forms
+fn reset_xmp_orientation(xmp: &str) -> String {
+    let mut out = xmp.to_string();
+    for (open, close) in [("tiff:Orientation=\"", "\""), ("<tiff:Orientation>", "</tiff:Orientation>")] {
+        if let Some(start) = out.find(open).map(|i| i + open.len()) {
+            if let Some(len) = out[start..].find(close) {
+                out.replace_range(start..start + len, "1");
+            }
+        }
+    }
+    out
+}
+
+/// Apply an Exif orientation so that the result displays upright
+fn orient(img: &Mat, orientation: u16) -> Result<Mat> {
+    let (rows, cols) = (img.rows(), img.cols());
+    let transposed = orientation >= 5;
+    let (out_rows, out_cols) = if transposed { (cols, rows) } else { (rows, cols) };
+    let mut out = Mat::new(out_rows, out_cols, img.channels(), img.depth())?;
+
+    for row in 0..out_rows {
+        for col in 0..out_cols {
+            let (src_row, src_col) = match orientation {
+                2 => (row, cols - 1 - col),
+                3 => (rows - 1 - row, cols - 1 - col),
+                4 => (rows - 1 - row, col),
+                5 => (col, row),
+                6 => (rows - 1 - col, row),
+                7 => (rows - 1 - col, cols - 1 - row),
+                8 => (col, cols - 1 - row),
+                _ => (row, col),
+            };
+            out.at_mut(row, col)?.copy_from_slice(img.at(src_row, src_col)?);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    /// Little-endian TIFF with IFD0 holding Orientation and a Make string
+    fn exif_block(orientation: u16) -> Vec<u8> {
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        // Make (ASCII, 4 bytes inline)
+        tiff.extend_from_slice(&0x010Fu16.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&4u32.to_le_bytes());
+        tiff.extend_from_slice(b"Cam\0");
+        tiff.extend_from_slice(&ORIENTATION_TAG.to_le_bytes());
+        tiff.extend_from_slice(&3u16.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        tiff.extend_from_slice(&orientation.to_le_bytes());
+        tiff.extend_from_slice(&[0, 0]);
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff
+    }
+
+    fn test_image() -> Mat {
+        let mut img = Mat::new(2, 3, 3, MatDepth::U8).unwrap();
+        for (i, v) in img.data_mut().iter_mut().enumerate() {
+            *v = (i * 10) as u8;
+        }
+        img
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let metadata = ImageMetadata {
+            exif: Some(exif_block(6)),
+            xmp: Some("<x:xmpmeta><rdf:Description tiff:Orientation=\"6\"/></x:xmpmeta>".to_string()),
+        };
+        let dir = std::env::temp_dir();
+        for name in ["opencv_rust_metadata.png", "opencv_rust_metadata.jpg"] {
+            let path = dir.join(name);
+            imwrite_with_metadata(&path, &test_image(), &metadata).unwrap();
+            let (img, read) = imread_with_metadata(&path).unwrap();
+            assert_eq!((img.rows(), img.cols()), (2, 3));
+            assert_eq!(read, metadata, "{name}");
+        }
+
+        assert!(imwrite_with_metadata(dir.join("opencv_rust_metadata.bmp"), &test_image(), &metadata).is_err());
+    }
+
+    #[test]
+    fn test_apply_orientation() {
+        let img = test_image();
+        let mut metadata = ImageMetadata {
+            exif: Some(exif_block(6)),
+            xmp: Some("<tiff:Orientation>6</tiff:Orientation>".to_string()),
+        };
+        assert_eq!(metadata.orientation(), Some(6));
+
+        // Rotated 90 degrees clockwise: the bottom-left pixel becomes top-left
+        let upright = metadata.apply_orientation(&img).unwrap();
+        assert_eq!((upright.rows(), upright.cols()), (3, 2));
+        assert_eq!(upright.at(0, 0).unwrap(), img.at(1, 0).unwrap());
+        assert_eq!(upright.at(0, 1).unwrap(), img.at(0, 0).unwrap());
+        assert_eq!(metadata.orientation(), Some(1));
+        assert_eq!(metadata.xmp.as_deref(), Some("<tiff:Orientation>1</tiff:Orientation>"));
+        // The rest of the Exif block is untouched
+        assert_eq!(&metadata.exif.as_ref().unwrap()[18..22], b"Cam\0");
+
+        // Applying again is a no-op
+        let again = metadata.apply_orientation(&upright).unwrap();
+        assert_eq!(again.data(), upright.data());
+
+        let mut flipped = ImageMetadata { exif: Some(exif_block(2)), xmp: None };
+        let mirrored = flipped.apply_orientation(&img).unwrap();
+        assert_eq!(mirrored.at(0, 0).unwrap(), img.at(0, 2).unwrap());
+    }
+}
@@ -1,14 +1,20 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::{Mat, MatDepth};
+use crate::core::{ColorOrder, Mat, MatDepth};
 use crate::error::{Error, Result};
 use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb, Rgba, Luma};
 use std::path::Path;
 
+pub mod metadata;
+
+pub use metadata::*;
+
 /// Read an image from file
+///
+/// Color images are RGB or RGBA and tagged [`ColorOrder::Rgb`].
 pub fn imread<P: AsRef<Path>>(path: P) -> Result<Mat> {
     let img = image::open(path)?;
 
-    match img {
+    let mat = match img {
         DynamicImage::ImageRgb8(buffer) => {
             let (width, height) = buffer.dimensions();
             let data = buffer.into_raw();
@@ -31,10 +37,13 @@ pub fn imread<P: AsRef<Path>>(path: P) -> Result<Mat> {
             let data = rgb_img.into_raw();
             Mat::from_raw(data, height as usize, width as usize, 3, MatDepth::U8)
         }
-    }
+    }?;
+    Ok(if mat.channels() == 1 { mat } else { mat.with_color_order(ColorOrder::Rgb) })
 }
 
 /// Write an image to file
+///
+/// Images tagged [`ColorOrder::Bgr`] are swapped to RGB before encoding.
 pub fn imwrite<P: AsRef<Path>>(path: P, mat: &Mat) -> Result<()> {
     if mat.depth() != MatDepth::U8 {
         return Err(Error::UnsupportedOperation(
@@ -42,6 +51,18 @@ pub fn imwrite<P: AsRef<Path>>(path: P, mat: &Mat) -> Result<()> {
         ));
     }
 
+    let swapped;
+    let mat = if mat.channels() >= 3 && mat.color_order() == ColorOrder::Bgr {
+        let mut rgb = mat.clone();
+        for px in rgb.data_mut().chunks_exact_mut(mat.channels()) {
+            px.swap(0, 2);
+        }
+        swapped = rgb;
+        &swapped
+    } else {
+        mat
+    };
+
     match mat.channels() {
         1 => {
             let buffer = ImageBuffer::<Luma<u8>, Vec<u8>>::from_raw(
@@ -109,4 +130,17 @@ mod tests {
         assert_eq!(loaded.rows(), mat.rows());
         assert_eq!(loaded.cols(), mat.cols());
     }
+
+    #[test]
+    fn test_bgr_written_as_rgb() {
+        let mut mat = Mat::new(4, 4, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+        mat.at_mut(0, 0).unwrap().copy_from_slice(&[10, 20, 30]);
+
+        let temp_path = "/tmp/test_opencv_rust_bgr.png";
+        imwrite(temp_path, &mat).unwrap();
+
+        let loaded = imread(temp_path).unwrap();
+        assert_eq!(loaded.color_order(), ColorOrder::Rgb);
+        assert_eq!(loaded.at(0, 0).unwrap(), &[30, 20, 10]);
+    }
 }
@@ -1,10 +1,11 @@
 use crate::core::{Mat, MatDepth};
 use crate::error::{Error, Result};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
-#[cfg(feature = "rayon")]
-use rayon::prelude::*;
+use crate::core::parallel::{parallel_for_rows, parallel_map};
 
-/// Bilateral filter for edge-preserving smoothing - optimized with rayon parallelization
+/// Bilateral filter for edge-preserving smoothing - rows run in parallel
 pub fn bilateral_filter(
     src: &Mat,
     dst: &mut Mat,
@@ -18,7 +19,7 @@ pub fn bilateral_filter(
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let rows = src.rows();
     let cols = src.cols();
@@ -41,74 +42,70 @@ pub fn bilateral_filter(
 
     let color_coeff = -0.5 / (sigma_color * sigma_color);
 
-    // Use rayon::scope to safely share references
-    rayon::scope(|_s| {
-        let dst_data = dst.data_mut();
-        let src_data = src.data();
-        let row_size = cols * channels;
+    let src_data = src.data();
+    parallel_for_rows(dst.data_mut(), cols * channels, |row, dst_row| {
+        // Stack arrays for temporary storage (max 4 channels)
+        let mut sum = [0.0f64; 4];
+        let mut center = [0u8; 4];
+        let rows_i32 = i32::try_from(rows).unwrap_or(i32::MAX);
+        let cols_i32 = i32::try_from(cols).unwrap_or(i32::MAX);
+        let row_i32 = i32::try_from(row).unwrap_or(i32::MAX);
 
-        dst_data.par_chunks_mut(row_size).enumerate().for_each(|(row, dst_row)| {
-            // Stack arrays for temporary storage (max 4 channels)
-            let mut sum = [0.0f64; 4];
-            let mut center = [0u8; 4];
-            let rows_i32 = i32::try_from(rows).unwrap_or(i32::MAX);
-            let cols_i32 = i32::try_from(cols).unwrap_or(i32::MAX);
-            let row_i32 = i32::try_from(row).unwrap_or(i32::MAX);
+        for col in 0..cols {
+            // Get center pixel
+            let center_idx = (row * cols + col) * channels;
+            center[..channels].copy_from_slice(&src_data[center_idx..center_idx + channels]);
 
-            for col in 0..cols {
-                // Get center pixel
-                let center_idx = (row * cols + col) * channels;
-                for ch in 0..channels {
-                    center[ch] = src_data[center_idx + ch];
-                }
+            sum.fill(0.0);
+            let mut weight_sum = 0.0f64;
 
-                sum.fill(0.0);
-                let mut weight_sum = 0.0f64;
-
-                // Process neighborhood
-                let col_i32 = i32::try_from(col).unwrap_or(i32::MAX);
+            // Process neighborhood
+            let col_i32 = i32::try_from(col).unwrap_or(i32::MAX);
 
-                for i in -radius..=radius {
-                    let y = usize::try_from((row_i32 + i).max(0).min(rows_i32 - 1)).unwrap_or(0);
-                    for j in -radius..=radius {
-                        let x = usize::try_from((col_i32 + j).max(0).min(cols_i32 - 1)).unwrap_or(0);
+            for i in -radius..=radius {
+                let y = usize::try_from((row_i32 + i).max(0).min(rows_i32 - 1)).unwrap_or(0);
+                for j in -radius..=radius {
+                    let x = usize::try_from((col_i32 + j).max(0).min(cols_i32 - 1)).unwrap_or(0);
 
-                        let neighbor_idx = (y * cols + x) * channels;
+                    let neighbor_idx = (y * cols + x) * channels;
 
-                        // Calculate color distance
-                        let mut color_dist = 0.0f64;
-                        for ch in 0..channels {
-                            let diff = f64::from(center[ch]) - f64::from(src_data[neighbor_idx + ch]);
-                            color_dist += diff * diff;
-                        }
+                    // Calculate color distance
+                    let mut color_dist = 0.0f64;
+                    for ch in 0..channels {
+                        let diff = f64::from(center[ch]) - f64::from(src_data[neighbor_idx + ch]);
+                        color_dist += diff * diff;
+                    }
 
-                        // Combined weight
-                        let i_idx = usize::try_from(i + radius).unwrap_or(0);
-                        let j_idx = usize::try_from(j + radius).unwrap_or(0);
-                        let weight = spatial_kernel[i_idx][j_idx] * (color_dist * color_coeff).exp();
+                    // Combined weight
+                    let i_idx = usize::try_from(i + radius).unwrap_or(0);
+                    let j_idx = usize::try_from(j + radius).unwrap_or(0);
+                    let weight = spatial_kernel[i_idx][j_idx] * (color_dist * color_coeff).exp();
 
-                        for ch in 0..channels {
-                            sum[ch] += f64::from(src_data[neighbor_idx + ch]) * weight;
-                        }
-                        weight_sum += weight;
+                    for ch in 0..channels {
+                        sum[ch] += f64::from(src_data[neighbor_idx + ch]) * weight;
                     }
-                }
-
-                // Write result
-                let dst_idx = col * channels;
-                for ch in 0..channels {
-                    let clamped = (sum[ch] / weight_sum).clamp(0.0, 255.0);
-                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                    { dst_row[dst_idx + ch] = clamped as u8; }
+                    weight_sum += weight;
                 }
             }
-        });
-    });
 
-    Ok(())
+            // Write result
+            let dst_idx = col * channels;
+            for ch in 0..channels {
+                let clamped = (sum[ch] / weight_sum).clamp(0.0, 255.0);
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                { dst_row[dst_idx + ch] = clamped as u8; }
+            }
+        }
+        Ok(())
+    })
 }
 
 /// Guided filter for edge-preserving smoothing
+///
+/// Each channel of `src` is filtered with the grayscale `guide` (He et al.).
+/// All window statistics are computed in F32, so `eps` regularizes the guide
+/// variance in the image's own units (e.g. squared 0-255 intensities for U8).
+/// `src` and `guide` may be U8 or F32; `dst` has the depth of `src`.
 pub fn guided_filter(
     src: &Mat,
     guide: &Mat,
@@ -116,291 +113,779 @@ pub fn guided_filter(
     radius: i32,
     eps: f64,
 ) -> Result<()> {
-    if src.rows() != guide.rows() || src.cols() != guide.cols() {
-        return Err(Error::InvalidDimensions(
-            "Source and guide must have same dimensions".to_string(),
+    if guide.channels() != 1 {
+        return Err(Error::InvalidParameter(
+            "Guide image must be grayscale".to_string(),
         ));
     }
+    check_guided_inputs(src, guide, radius)?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let eps = eps as f32;
+    let guide = channel_f32(guide, 0)?;
+    let mean_i = box_filter(&guide, radius)?;
+    let mean_ii = box_filter(&combine(&guide, &guide, |i, j| i * j)?, radius)?;
+    let var_i = combine(&mean_ii, &mean_i, |ii, i| ii - i * i)?;
+
+    let mut filtered = Vec::with_capacity(src.channels());
+    for ch in 0..src.channels() {
+        let p = channel_f32(src, ch)?;
+        let mean_p = box_filter(&p, radius)?;
+        let mean_ip = box_filter(&combine(&guide, &p, |i, p| i * p)?, radius)?;
+        let cov_ip = combine(&mean_ip, &combine(&mean_i, &mean_p, |i, p| i * p)?, |ip, i_p| ip - i_p)?;
+
+        let a = combine(&cov_ip, &var_i, |cov, var| cov / (var + eps))?;
+        let b = combine(&mean_p, &combine(&a, &mean_i, |a, i| a * i)?, |p, a_i| p - a_i)?;
+
+        let mean_a = box_filter(&a, radius)?;
+        let mean_b = box_filter(&b, radius)?;
+        filtered.push(combine(&combine(&mean_a, &guide, |a, i| a * i)?, &mean_b, |a_i, b| a_i + b)?);
+    }
 
-    if guide.channels() != 1 {
+    *dst = merge_planes(&filtered, src.depth())?.with_color_order(src.color_order());
+    Ok(())
+}
+
+/// Guided filter with a 3-channel color guide
+///
+/// Like [`guided_filter`], but the local linear model uses all three guide
+/// channels, with `eps` added to the diagonal of each window's 3x3 color
+/// covariance. This preserves edges that differ in hue but not brightness.
+pub fn guided_filter_color(
+    src: &Mat,
+    guide: &Mat,
+    dst: &mut Mat,
+    radius: i32,
+    eps: f64,
+) -> Result<()> {
+    if guide.channels() != 3 {
         return Err(Error::InvalidParameter(
-            "Guide image must be grayscale".to_string(),
+            "Color guide must have 3 channels".to_string(),
         ));
     }
+    check_guided_inputs(src, guide, radius)?;
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    #[allow(clippy::cast_possible_truncation)]
+    let eps = eps as f32;
+    let rows = src.rows();
+    let cols = src.cols();
 
-    // Compute mean of guide image
-    let mut mean_i = Mat::new(guide.rows(), guide.cols(), 1, MatDepth::U8)?;
-    box_filter(guide, &mut mean_i, radius)?;
+    let guide: Vec<Mat> = (0..3).map(|ch| channel_f32(guide, ch)).collect::<Result<_>>()?;
+    let mean_i: Vec<Mat> = guide.iter().map(|g| box_filter(g, radius)).collect::<Result<_>>()?;
 
-    // Compute mean of source image
-    let mut mean_p = Mat::new(src.rows(), src.cols(), src.channels(), MatDepth::U8)?;
-    box_filter(src, &mut mean_p, radius)?;
+    // Upper triangle of the window covariance: rr, rg, rb, gg, gb, bb
+    let pairs = [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2), (2, 2)];
+    let mut sigma = Vec::with_capacity(pairs.len());
+    for &(j, k) in &pairs {
+        let mean_jk = box_filter(&combine(&guide[j], &guide[k], |a, b| a * b)?, radius)?;
+        sigma.push(combine(&mean_jk, &combine(&mean_i[j], &mean_i[k], |a, b| a * b)?, |jk, j_k| jk - j_k)?);
+    }
 
-    // Compute correlation of guide
-    let mut corr_i = Mat::new(guide.rows(), guide.cols(), 1, MatDepth::U8)?;
-    for row in 0..guide.rows() {
-        for col in 0..guide.cols() {
-            let val = f64::from(guide.at(row, col)?[0]);
-            let corr_pixel = corr_i.at_mut(row, col)?;
-            let clamped = ((val * val) / 255.0).clamp(0.0, 255.0);
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            { corr_pixel[0] = clamped as u8; }
+    let mut filtered = Vec::with_capacity(src.channels());
+    for ch in 0..src.channels() {
+        let p = channel_f32(src, ch)?;
+        let mean_p = box_filter(&p, radius)?;
+        let mut cov_ip = Vec::with_capacity(3);
+        for (g, m) in guide.iter().zip(&mean_i) {
+            let mean_gp = box_filter(&combine(g, &p, |a, b| a * b)?, radius)?;
+            cov_ip.push(combine(&mean_gp, &combine(m, &mean_p, |a, b| a * b)?, |gp, g_p| gp - g_p)?);
         }
+
+        let mut a: Vec<Mat> = (0..3).map(|_| Mat::new(rows, cols, 1, MatDepth::F32)).collect::<Result<_>>()?;
+        let mut b = Mat::new(rows, cols, 1, MatDepth::F32)?;
+        for row in 0..rows {
+            for col in 0..cols {
+                let s = |i: usize| sigma[i].at_f32(row, col, 0);
+                let (rr, rg, rb) = (s(0)? + eps, s(1)?, s(2)?);
+                let (gg, gb, bb) = (s(3)? + eps, s(4)?, s(5)?);
+                let bb = bb + eps;
+
+                // Inverse of the symmetric covariance via its adjugate
+                let inv = [
+                    [gg * bb - gb * gb, gb * rb - rg * bb, rg * gb - gg * rb],
+                    [gb * rb - rg * bb, rr * bb - rb * rb, rb * rg - rr * gb],
+                    [rg * gb - gg * rb, rb * rg - rr * gb, rr * gg - rg * rg],
+                ];
+                let det = rr * inv[0][0] + rg * inv[0][1] + rb * inv[0][2];
+
+                let cov = [
+                    cov_ip[0].at_f32(row, col, 0)?,
+                    cov_ip[1].at_f32(row, col, 0)?,
+                    cov_ip[2].at_f32(row, col, 0)?,
+                ];
+                let mut offset = mean_p.at_f32(row, col, 0)?;
+                for (k, inv_row) in inv.iter().enumerate() {
+                    let coef = if det.abs() > f32::EPSILON {
+                        (inv_row[0] * cov[0] + inv_row[1] * cov[1] + inv_row[2] * cov[2]) / det
+                    } else {
+                        0.0
+                    };
+                    a[k].set_f32(row, col, 0, coef)?;
+                    offset -= coef * mean_i[k].at_f32(row, col, 0)?;
+                }
+                b.set_f32(row, col, 0, offset)?;
+            }
+        }
+
+        let mut q = box_filter(&b, radius)?;
+        for (a_k, g) in a.iter().zip(&guide) {
+            let term = combine(&box_filter(a_k, radius)?, g, |a, i| a * i)?;
+            q = combine(&q, &term, |q, t| q + t)?;
+        }
+        filtered.push(q);
     }
-    let mut mean_ii = Mat::new(guide.rows(), guide.cols(), 1, MatDepth::U8)?;
-    box_filter(&corr_i, &mut mean_ii, radius)?;
 
-    // Compute variance
-    let mut var_i = Mat::new(guide.rows(), guide.cols(), 1, MatDepth::U8)?;
-    for row in 0..guide.rows() {
-        for col in 0..guide.cols() {
-            let mean_val = f64::from(mean_i.at(row, col)?[0]);
-            let mean_sq = f64::from(mean_ii.at(row, col)?[0]);
-            let variance = mean_sq - (mean_val * mean_val) / 255.0;
+    *dst = merge_planes(&filtered, src.depth())?.with_color_order(src.color_order());
+    Ok(())
+}
 
-            let var_pixel = var_i.at_mut(row, col)?;
-            let clamped = variance.max(0.0).clamp(0.0, 255.0);
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            { var_pixel[0] = clamped as u8; }
+/// Joint (cross) bilateral filter
+///
+/// Like [`bilateral_filter`], but the range weights compare pixels of
+/// `guide` rather than of `src`, so `src` is smoothed everywhere except
+/// across the guide's edges: a depth map follows the edges of its color
+/// image, and a noisy no-flash photo is denoised along the edges of its
+/// flash counterpart. `src` and `guide` may be U8 or F32 with any channel
+/// count; `sigma_color` is in the guide's units and `dst` has the depth of
+/// `src`. NaN pixels of an F32 `src` are treated as missing and filled from
+/// their valid neighbours.
+pub fn joint_bilateral_filter(
+    src: &Mat,
+    guide: &Mat,
+    dst: &mut Mat,
+    d: i32,
+    sigma_color: f64,
+    sigma_space: f64,
+) -> Result<()> {
+    check_joint_bilateral_inputs(src, guide)?;
+    *dst = joint_bilateral(src, guide, d, sigma_space, |_| sigma_color)?.with_color_order(src.color_order());
+    Ok(())
+}
+
+/// Joint bilateral filter with a per-pixel range sigma
+///
+/// `sigma_color` is a single-channel U8 or F32 map the size of `src`
+/// giving the range sigma around each output pixel, e.g. larger in flat or
+/// low-confidence areas and smaller near detail that must be kept. A sigma
+/// of 0 only averages neighbours with exactly the same guide value. Pass
+/// `src` as `guide` for a plain bilateral filter with varying strength;
+/// other inputs are as for [`joint_bilateral_filter`].
+pub fn adaptive_bilateral_filter(
+    src: &Mat,
+    guide: &Mat,
+    dst: &mut Mat,
+    d: i32,
+    sigma_color: &Mat,
+    sigma_space: f64,
+) -> Result<()> {
+    check_joint_bilateral_inputs(src, guide)?;
+    check_joint_bilateral_inputs(src, sigma_color)?;
+    if sigma_color.channels() != 1 {
+        return Err(Error::InvalidParameter(
+            "Sigma map must be single-channel".to_string(),
+        ));
+    }
+    let sigmas = values_f64(sigma_color)?;
+    *dst = joint_bilateral(src, guide, d, sigma_space, |i| sigmas[i])?.with_color_order(src.color_order());
+    Ok(())
+}
+
+fn check_joint_bilateral_inputs(src: &Mat, other: &Mat) -> Result<()> {
+    if src.rows() != other.rows() || src.cols() != other.cols() {
+        return Err(Error::InvalidDimensions(
+            "Source and guide must have same dimensions".to_string(),
+        ));
+    }
+    for mat in [src, other] {
+        if !matches!(mat.depth(), MatDepth::U8 | MatDepth::F32) {
+            return Err(Error::UnsupportedOperation(
+                "joint bilateral filter only supports U8 and F32 depth".to_string(),
+            ));
         }
     }
+    Ok(())
+}
 
-    // Compute coefficients a and b
-    for row in 0..src.rows() {
-        for col in 0..src.cols() {
-            let var = f64::from(var_i.at(row, col)?[0]);
-            let mean_guide = f64::from(mean_i.at(row, col)?[0]);
+/// Every element of a U8 or F32 Mat as f64, in storage order
+fn values_f64(mat: &Mat) -> Result<Vec<f64>> {
+    if mat.depth() == MatDepth::U8 {
+        return Ok(mat.data().iter().map(|&v| f64::from(v)).collect());
+    }
+    let channels = mat.channels();
+    let mut values = Vec::with_capacity(mat.rows() * mat.cols() * channels);
+    for row in 0..mat.rows() {
+        for col in 0..mat.cols() {
+            for ch in 0..channels {
+                values.push(f64::from(mat.at_f32(row, col, ch)?));
+            }
+        }
+    }
+    Ok(values)
+}
 
-            let a = var / (var + eps);
-            let mean_src = f64::from(mean_p.at(row, col)?[0]);
-            let b = mean_src - a * mean_guide;
+/// Shared core of the joint bilateral filters; `sigma_color(i)` is the
+/// range sigma at pixel index `i`
+fn joint_bilateral<S>(src: &Mat, guide: &Mat, d: i32, sigma_space: f64, sigma_color: S) -> Result<Mat>
+where
+    S: Fn(usize) -> f64 + Sync + Send,
+{
+    let (rows, cols) = (src.rows(), src.cols());
+    let (channels, guide_channels) = (src.channels(), guide.channels());
+    let radius = if d <= 0 { 5 } else { d / 2 };
+    let space_coeff = -0.5 / (sigma_space * sigma_space);
+    let offsets: Vec<(i32, i32, f64)> = (-radius..=radius)
+        .flat_map(|i| (-radius..=radius).map(move |j| (i, j, (f64::from(i * i + j * j) * space_coeff).exp())))
+        .collect();
+
+    let src_values = values_f64(src)?;
+    let guide_values = values_f64(guide)?;
+    let clamp = |v: i64, len: usize| usize::try_from(v.clamp(0, i64::try_from(len).unwrap_or(i64::MAX) - 1)).unwrap_or(0);
+
+    let filtered = parallel_map(rows, |row| {
+        let mut out = vec![0.0f64; cols * channels];
+        let mut sum = vec![0.0f64; channels];
+        for col in 0..cols {
+            let index = row * cols + col;
+            let center = &guide_values[index * guide_channels..(index + 1) * guide_channels];
+            let sigma = sigma_color(index);
+            let color_coeff = -0.5 / (sigma * sigma);
+
+            sum.fill(0.0);
+            let mut weight_sum = 0.0;
+            for &(i, j, spatial) in &offsets {
+                let y = clamp(row as i64 + i64::from(i), rows);
+                let x = clamp(col as i64 + i64::from(j), cols);
+                let neighbor = y * cols + x;
+                let value = &src_values[neighbor * channels..(neighbor + 1) * channels];
+                if value.iter().any(|v| v.is_nan()) {
+                    continue;
+                }
 
-            // Apply linear transform
-            let guide_val = f64::from(guide.at(row, col)?[0]);
-            let result = a * guide_val + b;
+                let color_dist: f64 = center
+                    .iter()
+                    .zip(&guide_values[neighbor * guide_channels..(neighbor + 1) * guide_channels])
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum();
+                let range = if sigma > 0.0 { (color_dist * color_coeff).exp() } else { f64::from(u8::from(color_dist == 0.0)) };
+                let weight = spatial * range;
+                for (s, v) in sum.iter_mut().zip(value) {
+                    *s += v * weight;
+                }
+                weight_sum += weight;
+            }
 
-            let dst_pixel = dst.at_mut(row, col)?;
-            let clamped = result.clamp(0.0, 255.0);
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let clamped_u8 = clamped as u8;
-            for ch in 0..src.channels() {
-                dst_pixel[ch] = clamped_u8;
+            let own = &src_values[index * channels..(index + 1) * channels];
+            for ((o, s), &v) in out[col * channels..(col + 1) * channels].iter_mut().zip(&sum).zip(own) {
+                *o = if weight_sum > 0.0 { s / weight_sum } else { v };
+            }
+        }
+        Ok(out)
+    })?;
+
+    let mut dst = Mat::new(rows, cols, channels, src.depth())?;
+    for (row, values) in filtered.iter().enumerate() {
+        for (k, &v) in values.iter().enumerate() {
+            let (col, ch) = (k / channels, k % channels);
+            if src.depth() == MatDepth::U8 {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                { dst.at_mut(row, col)?[ch] = v.round().clamp(0.0, 255.0) as u8; }
+            } else {
+                #[allow(clippy::cast_possible_truncation)]
+                dst.set_f32(row, col, ch, v as f32)?;
             }
         }
     }
+    Ok(dst)
+}
 
+fn check_guided_inputs(src: &Mat, guide: &Mat, radius: i32) -> Result<()> {
+    if src.rows() != guide.rows() || src.cols() != guide.cols() {
+        return Err(Error::InvalidDimensions(
+            "Source and guide must have same dimensions".to_string(),
+        ));
+    }
+
+    if radius < 0 {
+        return Err(Error::InvalidParameter(
+            "radius must be non-negative".to_string(),
+        ));
+    }
+
+    for mat in [src, guide] {
+        if !matches!(mat.depth(), MatDepth::U8 | MatDepth::F32) {
+            return Err(Error::UnsupportedOperation(
+                "guided filter only supports U8 and F32 depth".to_string(),
+            ));
+        }
+    }
     Ok(())
 }
 
-fn box_filter(src: &Mat, dst: &mut Mat, radius: i32) -> Result<()> {
+/// One channel of a U8 or F32 Mat as a single-channel F32 Mat, unscaled
+fn channel_f32(src: &Mat, ch: usize) -> Result<Mat> {
+    let mut out = Mat::new(src.rows(), src.cols(), 1, MatDepth::F32)?;
+    for row in 0..src.rows() {
+        for col in 0..src.cols() {
+            let value = match src.depth() {
+                MatDepth::U8 => f32::from(src.at(row, col)?[ch]),
+                _ => src.at_f32(row, col, ch)?,
+            };
+            out.set_f32(row, col, 0, value)?;
+        }
+    }
+    Ok(out)
+}
+
+/// Pixel-wise combination of two single-channel F32 Mats
+fn combine(a: &Mat, b: &Mat, f: impl Fn(f32, f32) -> f32) -> Result<Mat> {
+    let mut out = Mat::new(a.rows(), a.cols(), 1, MatDepth::F32)?;
+    for row in 0..a.rows() {
+        for col in 0..a.cols() {
+            out.set_f32(row, col, 0, f(a.at_f32(row, col, 0)?, b.at_f32(row, col, 0)?))?;
+        }
+    }
+    Ok(out)
+}
+
+/// Interleave F32 planes into a U8 (rounded, saturated) or F32 Mat
+fn merge_planes(planes: &[Mat], depth: MatDepth) -> Result<Mat> {
+    let (rows, cols) = (planes[0].rows(), planes[0].cols());
+    let mut out = Mat::new(rows, cols, planes.len(), depth)?;
+    for row in 0..rows {
+        for col in 0..cols {
+            for (ch, plane) in planes.iter().enumerate() {
+                let value = plane.at_f32(row, col, 0)?;
+                if depth == MatDepth::U8 {
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    { out.at_mut(row, col)?[ch] = value.round().clamp(0.0, 255.0) as u8; }
+                } else {
+                    out.set_f32(row, col, ch, value)?;
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn box_filter(src: &Mat, radius: i32) -> Result<Mat> {
     use crate::imgproc::blur;
     use crate::core::types::Size;
 
     let ksize = 2 * radius + 1;
-    blur(src, dst, Size::new(ksize, ksize))
+    let mut dst = Mat::new(1, 1, 1, MatDepth::F32)?;
+    blur(src, &mut dst, Size::new(ksize, ksize))?;
+    Ok(dst)
 }
 
-/// Distance transform using chamfer distance
+/// Distance from every non-zero pixel to the nearest zero pixel
+///
+/// `dst` is F32 and holds exact distances for every metric: L1 (city block)
+/// and C (chessboard) come from two raster passes, L2 from Felzenszwalb and
+/// Huttenlocher's separable squared-distance transform. `mask_size` is
+/// accepted for OpenCV compatibility (0, 3 or 5) but, since no chamfer
+/// approximation is involved, does not change the result. Without any zero
+/// pixels every distance is `f32::MAX`.
 pub fn distance_transform(
     src: &Mat,
     dst: &mut Mat,
     distance_type: DistanceType,
     mask_size: i32,
 ) -> Result<()> {
-    if src.channels() != 1 {
+    if !matches!(mask_size, 0 | 3 | 5) {
         return Err(Error::InvalidParameter(
-            "distance_transform requires single-channel image".to_string(),
+            "mask_size must be 0, 3 or 5".to_string(),
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
-
-    // Initialize distance map
-    let mut dist = vec![vec![f32::MAX; src.cols()]; src.rows()];
+    let (dist, _) = nearest_zero(src, distance_type)?;
+    *dst = distance_mat(src, &dist)?;
+    Ok(())
+}
 
-    // Set zero distance for foreground pixels
-    for row in 0..src.rows() {
-        for col in 0..src.cols() {
-            let pixel = src.at(row, col)?;
-            if pixel[0] > 0 {
-                dist[row][col] = 0.0;
-            }
-        }
-    }
+/// How [`distance_transform_with_labels`] numbers the zero pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceLabelType {
+    /// Each 8-connected component of zero pixels is one label
+    ConnectedComponent,
+    /// Each zero pixel is its own label
+    Pixel,
+}
 
-    // Forward pass
-    for row in 1..src.rows() {
-        for col in 1..src.cols() {
-            if dist[row][col] > 0.0 {
-                let d1 = dist[row - 1][col] + 1.0;
-                let d2 = dist[row][col - 1] + 1.0;
-                let d3 = dist[row - 1][col - 1] + 1.414;
+/// [`distance_transform`] that also reports which zero pixel is nearest
+///
+/// `labels` is an S32 image: zero pixels are numbered from 1 in raster order
+/// (per component or per pixel, see [`DistanceLabelType`]) and every other
+/// pixel takes the label of its nearest zero pixel, giving a discrete
+/// Voronoi partition. Ties go to an arbitrary nearest seed. Without any zero
+/// pixels all labels are 0.
+pub fn distance_transform_with_labels(
+    src: &Mat,
+    dst: &mut Mat,
+    labels: &mut Mat,
+    distance_type: DistanceType,
+    label_type: DistanceLabelType,
+) -> Result<()> {
+    let (dist, nearest) = nearest_zero(src, distance_type)?;
+    let rows = src.rows();
+    let cols = src.cols();
 
-                dist[row][col] = dist[row][col].min(d1).min(d2).min(d3);
-            }
+    let seed_labels = match label_type {
+        DistanceLabelType::Pixel => {
+            let mut next = 0;
+            nearest
+                .iter()
+                .enumerate()
+                .map(|(i, &n)| {
+                    if n == i {
+                        next += 1;
+                        next
+                    } else {
+                        0
+                    }
+                })
+                .collect()
         }
-    }
+        DistanceLabelType::ConnectedComponent => zero_components(&nearest, rows, cols),
+    };
 
-    // Backward pass
-    for row in (0..src.rows() - 1).rev() {
-        for col in (0..src.cols() - 1).rev() {
-            if dist[row][col] > 0.0 {
-                let d1 = dist[row + 1][col] + 1.0;
-                let d2 = dist[row][col + 1] + 1.0;
-                let d3 = dist[row + 1][col + 1] + 1.414;
-
-                dist[row][col] = dist[row][col].min(d1).min(d2).min(d3);
-            }
-        }
-    }
-
-    // Normalize to 0-255
-    let max_dist = dist.iter()
-        .flat_map(|row| row.iter())
-        .fold(0.0f32, |a, &b| a.max(b));
-
-    for row in 0..src.rows() {
-        for col in 0..src.cols() {
-            let dst_pixel = dst.at_mut(row, col)?;
-            if max_dist > 0.0 {
-                let clamped = ((dist[row][col] / max_dist) * 255.0).clamp(0.0, 255.0);
-                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                { dst_pixel[0] = clamped as u8; }
-            } else {
-                dst_pixel[0] = 0;
-            }
+    let mut out = Mat::new(rows, cols, 1, MatDepth::S32)?;
+    for (i, &n) in nearest.iter().enumerate() {
+        if n != NO_SEED {
+            out.set_i32(i / cols, i % cols, 0, seed_labels[n])?;
         }
     }
 
+    *dst = distance_mat(src, &dist)?;
+    *labels = out;
     Ok(())
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Metric for [`distance_transform`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DistanceType {
+    /// `|dx| + |dy|`
     L1,
+    /// Euclidean distance
     L2,
+    /// Chessboard distance `max(|dx|, |dy|)`
     C,
 }
 
-/// Watershed segmentation algorithm
-pub fn watershed(image: &Mat, markers: &mut Mat) -> Result<()> {
-    if image.channels() != 3 || markers.channels() != 1 {
-        return Err(Error::InvalidParameter(
-            "Watershed requires 3-channel image and 1-channel markers".to_string(),
-        ));
-    }
+/// Marks pixels with no zero pixel to measure from
+const NO_SEED: usize = usize::MAX;
 
-    if image.rows() != markers.rows() || image.cols() != markers.cols() {
-        return Err(Error::InvalidDimensions(
-            "Image and markers must have same size".to_string(),
+/// Exact distance to, and raster index of, the nearest zero pixel
+fn nearest_zero(src: &Mat, distance_type: DistanceType) -> Result<(Vec<f32>, Vec<usize>)> {
+    if src.channels() != 1 || src.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "distance_transform requires a single-channel U8 image".to_string(),
         ));
     }
 
-    // Compute gradient magnitude
-    use crate::imgproc::sobel;
-    use crate::imgproc::cvt_color;
-    use crate::core::types::ColorConversionCode;
+    let rows = src.rows();
+    let cols = src.cols();
+    let nearest: Vec<usize> = src
+        .data()
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| if v == 0 { i } else { NO_SEED })
+        .collect();
+
+    let (dist, nearest) = match distance_type {
+        DistanceType::L2 => euclidean_nearest(nearest, rows, cols),
+        DistanceType::L1 | DistanceType::C => {
+            let dist = raster_nearest(nearest, rows, cols, distance_type == DistanceType::C);
+            (dist.0.into_iter().map(|d| d as f32).collect(), dist.1)
+        }
+    };
+
+    let dist = dist
+        .into_iter()
+        .zip(&nearest)
+        .map(|(d, &n)| if n == NO_SEED { f32::MAX } else { d })
+        .collect();
+    Ok((dist, nearest))
+}
 
-    let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
-    cvt_color(image, &mut gray, ColorConversionCode::RgbToGray)?;
+/// Two-pass propagation, exact for the city block (4-neighbour) and
+/// chessboard (8-neighbour) metrics
+fn raster_nearest(mut nearest: Vec<usize>, rows: usize, cols: usize, diagonal: bool) -> (Vec<u32>, Vec<usize>) {
+    let mut dist: Vec<u32> = nearest.iter().map(|&n| if n == NO_SEED { u32::MAX } else { 0 }).collect();
 
-    let mut grad_x = Mat::new(1, 1, 1, MatDepth::U8)?;
-    let mut grad_y = Mat::new(1, 1, 1, MatDepth::U8)?;
-    sobel(&gray, &mut grad_x, 1, 0, 3)?;
-    sobel(&gray, &mut grad_y, 0, 1, 3)?;
+    let relax = |dist: &mut Vec<u32>, nearest: &mut Vec<usize>, i: usize, j: usize| {
+        if dist[j] != u32::MAX && dist[j] + 1 < dist[i] {
+            dist[i] = dist[j] + 1;
+            nearest[i] = nearest[j];
+        }
+    };
 
-    let mut gradient = Mat::new(gray.rows(), gray.cols(), 1, MatDepth::U8)?;
-    for row in 0..gray.rows() {
-        for col in 0..gray.cols() {
-            let gx = f32::from(grad_x.at(row, col)?[0]);
-            let gy = f32::from(grad_y.at(row, col)?[0]);
-            let mag = (gx * gx + gy * gy).sqrt();
+    for row in 0..rows {
+        for col in 0..cols {
+            let i = row * cols + col;
+            if col > 0 {
+                relax(&mut dist, &mut nearest, i, i - 1);
+            }
+            if row > 0 {
+                relax(&mut dist, &mut nearest, i, i - cols);
+                if diagonal && col > 0 {
+                    relax(&mut dist, &mut nearest, i, i - cols - 1);
+                }
+                if diagonal && col + 1 < cols {
+                    relax(&mut dist, &mut nearest, i, i - cols + 1);
+                }
+            }
+        }
+    }
 
-            let grad_pixel = gradient.at_mut(row, col)?;
-            let clamped = mag.min(255.0).clamp(0.0, 255.0);
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            { grad_pixel[0] = clamped as u8; }
+    for row in (0..rows).rev() {
+        for col in (0..cols).rev() {
+            let i = row * cols + col;
+            if col + 1 < cols {
+                relax(&mut dist, &mut nearest, i, i + 1);
+            }
+            if row + 1 < rows {
+                relax(&mut dist, &mut nearest, i, i + cols);
+                if diagonal && col + 1 < cols {
+                    relax(&mut dist, &mut nearest, i, i + cols + 1);
+                }
+                if diagonal && col > 0 {
+                    relax(&mut dist, &mut nearest, i, i + cols - 1);
+                }
+            }
         }
     }
 
-    // Priority queue for watershed
-    let mut queue: Vec<(u8, usize, usize)> = Vec::new();
+    (dist, nearest)
+}
 
-    // Initialize queue with marker boundaries
-    for row in 1..markers.rows() - 1 {
-        for col in 1..markers.cols() - 1 {
-            let label = markers.at(row, col)?[0];
+/// Felzenszwalb–Huttenlocher exact Euclidean transform: nearest seed within
+/// each column, then the lower envelope of parabolas along each row
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn euclidean_nearest(seeds: Vec<usize>, rows: usize, cols: usize) -> (Vec<f32>, Vec<usize>) {
+    // Column pass: squared vertical distance to the nearest seed row
+    let mut column_dist = vec![f64::INFINITY; rows * cols];
+    let mut column_seed = vec![NO_SEED; rows * cols];
+    for col in 0..cols {
+        let mut last = None;
+        for row in 0..rows {
+            if seeds[row * cols + col] != NO_SEED {
+                last = Some(row);
+            }
+            if let Some(r) = last {
+                column_dist[row * cols + col] = ((row - r) * (row - r)) as f64;
+                column_seed[row * cols + col] = r * cols + col;
+            }
+        }
+        let mut last = None;
+        for row in (0..rows).rev() {
+            if seeds[row * cols + col] != NO_SEED {
+                last = Some(row);
+            }
+            if let Some(r) = last {
+                let d = ((r - row) * (r - row)) as f64;
+                if d < column_dist[row * cols + col] {
+                    column_dist[row * cols + col] = d;
+                    column_seed[row * cols + col] = r * cols + col;
+                }
+            }
+        }
+    }
 
-            if label > 0 {
-                // Check if on boundary
-                let mut is_boundary = false;
-                let row_i32 = i32::try_from(row).unwrap_or(i32::MAX);
-                let col_i32 = i32::try_from(col).unwrap_or(i32::MAX);
+    // Row pass over the finite column distances
+    let mut dist = vec![0.0f32; rows * cols];
+    let mut nearest = vec![NO_SEED; rows * cols];
+    let mut apex: Vec<usize> = Vec::with_capacity(cols);
+    let mut bound: Vec<f64> = Vec::with_capacity(cols + 1);
+    for row in 0..rows {
+        let f = &column_dist[row * cols..(row + 1) * cols];
+        let parabola = |q: usize| f[q] + (q * q) as f64;
+
+        apex.clear();
+        bound.clear();
+        for q in (0..cols).filter(|&q| f[q].is_finite()) {
+            let mut s = f64::NEG_INFINITY;
+            while let Some(&v) = apex.last() {
+                s = (parabola(q) - parabola(v)) / (2.0 * (q as f64 - v as f64));
+                if s <= bound[apex.len() - 1] {
+                    apex.pop();
+                    bound.pop();
+                    s = f64::NEG_INFINITY;
+                } else {
+                    break;
+                }
+            }
+            apex.push(q);
+            bound.push(s);
+        }
 
-                for dy in -1..=1 {
-                    for dx in -1..=1 {
-                        if dy == 0 && dx == 0 { continue; }
+        if apex.is_empty() {
+            continue;
+        }
+        let mut k = 0;
+        for q in 0..cols {
+            while k + 1 < apex.len() && bound[k + 1] < q as f64 {
+                k += 1;
+            }
+            let v = apex[k];
+            let dq = q as f64 - v as f64;
+            dist[row * cols + q] = (dq * dq + f[v]).sqrt() as f32;
+            nearest[row * cols + q] = column_seed[row * cols + v];
+        }
+    }
 
-                        let ny = usize::try_from(row_i32 + dy).unwrap_or(0);
-                        let nx = usize::try_from(col_i32 + dx).unwrap_or(0);
+    (dist, nearest)
+}
 
-                        let neighbor_label = markers.at(ny, nx)?[0];
-                        if neighbor_label == 0 {
-                            is_boundary = true;
-                            break;
-                        }
+/// Label 8-connected components of the zero pixels (seeds map to themselves
+/// in `nearest`), numbered from 1 in raster order
+fn zero_components(nearest: &[usize], rows: usize, cols: usize) -> Vec<i32> {
+    let mut labels = vec![0i32; rows * cols];
+    let mut next = 0;
+    let mut stack = Vec::new();
+    for start in 0..rows * cols {
+        if nearest[start] != start || labels[start] != 0 {
+            continue;
+        }
+        next += 1;
+        labels[start] = next;
+        stack.push(start);
+        while let Some(i) = stack.pop() {
+            let (row, col) = (i / cols, i % cols);
+            for nr in row.saturating_sub(1)..(row + 2).min(rows) {
+                for nc in col.saturating_sub(1)..(col + 2).min(cols) {
+                    let j = nr * cols + nc;
+                    if nearest[j] == j && labels[j] == 0 {
+                        labels[j] = next;
+                        stack.push(j);
                     }
-                    if is_boundary { break; }
-                }
-
-                if is_boundary {
-                    let grad_val = gradient.at(row, col)?[0];
-                    queue.push((grad_val, row, col));
                 }
             }
         }
     }
+    labels
+}
 
-    // Sort by gradient (priority queue simulation)
-    queue.sort_by_key(|(grad, _, _)| *grad);
+fn distance_mat(src: &Mat, dist: &[f32]) -> Result<Mat> {
+    let mut out = Mat::new(src.rows(), src.cols(), 1, MatDepth::F32)?;
+    for (i, &d) in dist.iter().enumerate() {
+        out.set_f32(i / src.cols(), i % src.cols(), 0, d)?;
+    }
+    Ok(out)
+}
 
-    // Flood fill from markers
-    while let Some((_grad, row, col)) = queue.pop() {
-        let current_label = markers.at(row, col)?[0];
-        let row_i32 = i32::try_from(row).unwrap_or(i32::MAX);
-        let col_i32 = i32::try_from(col).unwrap_or(i32::MAX);
-        let markers_rows_i32 = i32::try_from(markers.rows()).unwrap_or(i32::MAX);
-        let markers_cols_i32 = i32::try_from(markers.cols()).unwrap_or(i32::MAX);
+/// Boundary label written between regions by [`watershed`]
+pub const WATERSHED_BOUNDARY: i32 = -1;
 
-        for dy in -1..=1 {
-            for dx in -1..=1 {
-                if dy == 0 && dx == 0 { continue; }
+/// Watershed segmentation algorithm
+///
+/// `image` is a 3-channel U8 image and `markers` a 1-channel S32 Mat of the
+/// same size. Positive marker values seed regions and 0 marks unknown pixels.
+/// Unknown pixels are flooded in order of increasing color difference to
+/// their labelled neighbor (4-connected), taking the label of the region that
+/// reaches them. Pixels where two regions meet, and the one-pixel image
+/// frame, are set to [`WATERSHED_BOUNDARY`] as in OpenCV.
+pub fn watershed(image: &Mat, markers: &mut Mat) -> Result<()> {
+    if image.channels() != 3 || image.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "Watershed requires a 3-channel U8 image".to_string(),
+        ));
+    }
 
-                let ny = row_i32 + dy;
-                let nx = col_i32 + dx;
+    if markers.channels() != 1 || markers.depth() != MatDepth::S32 {
+        return Err(Error::InvalidParameter(
+            "Watershed requires 1-channel S32 markers".to_string(),
+        ));
+    }
 
-                if ny >= 0 && ny < markers_rows_i32 && nx >= 0 && nx < markers_cols_i32 {
-                    let ny = usize::try_from(ny).unwrap_or(0);
-                    let nx = usize::try_from(nx).unwrap_or(0);
+    if image.rows() != markers.rows() || image.cols() != markers.cols() {
+        return Err(Error::InvalidDimensions(
+            "Image and markers must have same size".to_string(),
+        ));
+    }
 
-                    let neighbor_label = markers.at(ny, nx)?[0];
+    const IN_QUEUE: i32 = -2;
 
-                    if neighbor_label == 0 {
-                        // Assign label
-                        let neighbor_pixel = markers.at_mut(ny, nx)?;
-                        neighbor_pixel[0] = current_label;
+    let rows = markers.rows();
+    let cols = markers.cols();
+    let pixels = image.data();
 
-                        // Add to queue
-                        let grad_val = gradient.at(ny, nx)?[0];
-                        queue.push((grad_val, ny, nx));
-                    }
+    let mut labels = Vec::with_capacity(rows * cols);
+    for row in 0..rows {
+        for col in 0..cols {
+            let on_frame = row == 0 || col == 0 || row + 1 == rows || col + 1 == cols;
+            labels.push(if on_frame { WATERSHED_BOUNDARY } else { markers.at_i32(row, col, 0)? });
+        }
+    }
+
+    // Largest per-channel difference between two pixels
+    let color_diff = |a: usize, b: usize| -> u8 {
+        (0..3)
+            .map(|c| pixels[a * 3 + c].abs_diff(pixels[b * 3 + c]))
+            .max()
+            .unwrap_or(0)
+    };
+
+    // Min-heap on (difference, insertion order) so equal priorities flood FIFO
+    let mut heap = BinaryHeap::new();
+    let mut order = 0usize;
+
+    // Seed the queue with unknown pixels touching a marker, scanning rows in
+    // parallel but queueing them in raster order
+    let interior_rows = rows.saturating_sub(2);
+    let seeds = parallel_map(interior_rows, |i| {
+        let row = i + 1;
+        let mut row_seeds = Vec::new();
+        for col in 1..cols.saturating_sub(1) {
+            let idx = row * cols + col;
+            if labels[idx] != 0 {
+                continue;
+            }
+            let priority = [idx - 1, idx + 1, idx - cols, idx + cols]
+                .into_iter()
+                .filter(|&n| labels[n] > 0)
+                .map(|n| color_diff(idx, n))
+                .min();
+            if let Some(priority) = priority {
+                row_seeds.push((priority, idx));
+            }
+        }
+        Ok(row_seeds)
+    })?;
+    for (priority, idx) in seeds.into_iter().flatten() {
+        heap.push(Reverse((priority, order, idx)));
+        order += 1;
+        labels[idx] = IN_QUEUE;
+    }
+
+    while let Some(Reverse((_, _, idx))) = heap.pop() {
+        let mut label = 0;
+        for n in [idx - 1, idx + 1, idx - cols, idx + cols] {
+            let neighbor = labels[n];
+            if neighbor > 0 {
+                if label == 0 {
+                    label = neighbor;
+                } else if neighbor != label {
+                    label = WATERSHED_BOUNDARY;
                 }
             }
         }
+        labels[idx] = label;
+
+        if label == WATERSHED_BOUNDARY {
+            continue;
+        }
+
+        for n in [idx - 1, idx + 1, idx - cols, idx + cols] {
+            if labels[n] == 0 {
+                heap.push(Reverse((color_diff(idx, n), order, n)));
+                order += 1;
+                labels[n] = IN_QUEUE;
+            }
+        }
+    }
 
-        queue.sort_by_key(|(grad, _, _)| *grad);
+    for (i, &label) in labels.iter().enumerate() {
+        markers.set_i32(i / cols, i % cols, 0, label)?;
     }
 
     Ok(())
@@ -433,10 +918,11 @@ pub fn gabor_filter(
     let src_rows_i32 = i32::try_from(src.rows()).unwrap_or(i32::MAX);
     let src_cols_i32 = i32::try_from(src.cols()).unwrap_or(i32::MAX);
 
-    for row in 0..src.rows() {
+    let cols = src.cols();
+    parallel_for_rows(dst.data_mut(), cols, |row, dst_row| {
         let row_i32 = i32::try_from(row).unwrap_or(i32::MAX);
 
-        for col in 0..src.cols() {
+        for (col, dst_pixel) in dst_row.iter_mut().enumerate() {
             let col_i32 = i32::try_from(col).unwrap_or(i32::MAX);
             let mut sum = 0.0f64;
 
@@ -454,14 +940,12 @@ pub fn gabor_filter(
                 }
             }
 
-            let dst_pixel = dst.at_mut(row, col)?;
             let clamped = sum.abs().min(255.0).clamp(0.0, 255.0);
             #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            { dst_pixel[0] = clamped as u8; }
+            { *dst_pixel = clamped as u8; }
         }
-    }
-
-    Ok(())
+        Ok(())
+    })
 }
 
 fn generate_gabor_kernel(
@@ -580,7 +1064,7 @@ fn generate_log_kernel(ksize: i32, sigma: f64) -> Vec<Vec<f64>> {
     kernel
 }
 
-/// Non-local means denoising
+/// Non-local means denoising - rows run in parallel
 pub fn non_local_means_denoising(
     src: &Mat,
     dst: &mut Mat,
@@ -594,7 +1078,7 @@ pub fn non_local_means_denoising(
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let t_half = template_window_size / 2;
     let s_half = search_window_size / 2;
@@ -602,10 +1086,11 @@ pub fn non_local_means_denoising(
     let src_rows_i32 = i32::try_from(src.rows()).unwrap_or(i32::MAX);
     let src_cols_i32 = i32::try_from(src.cols()).unwrap_or(i32::MAX);
 
-    for row in 0..src.rows() {
+    let channels = src.channels();
+    parallel_for_rows(dst.data_mut(), src.cols() * channels, |row, dst_row| {
         let row_i32 = i32::try_from(row).unwrap_or(i32::MAX);
 
-        for col in 0..src.cols() {
+        for (col, dst_pixel) in dst_row.chunks_exact_mut(channels).enumerate() {
             let col_i32 = i32::try_from(col).unwrap_or(i32::MAX);
             let mut sum = vec![0.0f32; src.channels()];
             let mut weight_sum = 0.0f32;
@@ -656,16 +1141,14 @@ pub fn non_local_means_denoising(
                 }
             }
 
-            let dst_pixel = dst.at_mut(row, col)?;
             for ch in 0..src.channels() {
                 let clamped = (sum[ch] / weight_sum).clamp(0.0, 255.0);
                 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
                 { dst_pixel[ch] = clamped as u8; }
             }
         }
-    }
-
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Anisotropic diffusion (Perona-Malik)
@@ -746,6 +1229,118 @@ mod tests {
         assert_eq!(dst.rows(), src.rows());
     }
 
+    #[test]
+    fn test_guided_filter_float_statistics() {
+        // A guide that varies by less than one U8 step inside each window must
+        // still be followed exactly when src equals guide and eps is tiny
+        let mut guide = Mat::new(12, 12, 1, MatDepth::F32).unwrap();
+        for row in 0..12 {
+            for col in 0..12 {
+                guide.set_f32(row, col, 0, 100.0 + 0.1 * col as f32).unwrap();
+            }
+        }
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        guided_filter(&guide, &guide, &mut dst, 2, 1e-4).unwrap();
+
+        assert_eq!(dst.depth(), MatDepth::F32);
+        for row in 0..12 {
+            for col in 0..12 {
+                let expected = guide.at_f32(row, col, 0).unwrap();
+                assert!((dst.at_f32(row, col, 0).unwrap() - expected).abs() < 1e-2);
+            }
+        }
+
+        // Every channel of a multi-channel source is filtered
+        let mut src = Mat::new(12, 12, 2, MatDepth::U8).unwrap();
+        let mut gray = Mat::new(12, 12, 1, MatDepth::U8).unwrap();
+        for row in 0..12 {
+            for col in 0..12 {
+                let v = if col < 6 { 40 } else { 200 };
+                src.at_mut(row, col).unwrap().copy_from_slice(&[v, 255 - v]);
+                gray.at_mut(row, col).unwrap()[0] = v;
+            }
+        }
+        guided_filter(&src, &gray, &mut dst, 2, 1.0).unwrap();
+        assert_eq!(dst.at(5, 0).unwrap(), &[40, 215]);
+        assert_eq!(dst.at(5, 11).unwrap(), &[200, 55]);
+    }
+
+    #[test]
+    fn test_guided_filter_color() {
+        // Two regions with equal brightness but different hue
+        let mut guide = Mat::new(16, 16, 3, MatDepth::U8).unwrap();
+        let mut src = Mat::new(16, 16, 1, MatDepth::U8).unwrap();
+        for row in 0..16 {
+            for col in 0..16 {
+                let (color, value) = if col < 8 { ([200, 0, 100], 30) } else { ([0, 200, 100], 220) };
+                guide.at_mut(row, col).unwrap().copy_from_slice(&color);
+                src.at_mut(row, col).unwrap()[0] = value;
+            }
+        }
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        guided_filter_color(&src, &guide, &mut dst, 3, 10.0).unwrap();
+        assert_eq!(dst.channels(), 1);
+        for row in 0..16 {
+            assert!(dst.at(row, 7).unwrap()[0].abs_diff(30) <= 2);
+            assert!(dst.at(row, 8).unwrap()[0].abs_diff(220) <= 2);
+        }
+
+        let mut gray = Mat::new(16, 16, 1, MatDepth::U8).unwrap();
+        assert!(guided_filter_color(&src, &gray, &mut dst, 3, 10.0).is_err());
+        gray.at_mut(0, 0).unwrap()[0] = 1;
+        assert!(guided_filter(&src, &guide, &mut dst, 3, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_joint_bilateral_filter() {
+        // Noisy step in src, clean step in the guide
+        let mut src = Mat::new(16, 16, 1, MatDepth::F32).unwrap();
+        let mut guide = Mat::new(16, 16, 1, MatDepth::U8).unwrap();
+        for row in 0..16 {
+            for col in 0..16 {
+                let (base, g) = if col < 8 { (10.0, 20) } else { (50.0, 220) };
+                let noise = if (row + col) % 2 == 0 { 2.0 } else { -2.0 };
+                src.set_f32(row, col, 0, base + noise).unwrap();
+                guide.at_mut(row, col).unwrap()[0] = g;
+            }
+        }
+        // A missing depth sample is filled from its neighbours
+        src.set_f32(4, 3, 0, f32::NAN).unwrap();
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        joint_bilateral_filter(&src, &guide, &mut dst, 5, 10.0, 3.0).unwrap();
+        assert_eq!(dst.depth(), MatDepth::F32);
+        for row in 2..14 {
+            assert!((dst.at_f32(row, 7, 0).unwrap() - 10.0).abs() < 0.5);
+            assert!((dst.at_f32(row, 8, 0).unwrap() - 50.0).abs() < 0.5);
+        }
+        assert!((dst.at_f32(4, 3, 0).unwrap() - 10.0).abs() < 0.5);
+
+        let flat = Mat::new(16, 16, 1, MatDepth::U8).unwrap();
+        joint_bilateral_filter(&src, &flat, &mut dst, 5, 10.0, 3.0).unwrap();
+        assert!(dst.at_f32(8, 7, 0).unwrap() > 15.0);
+        assert!(joint_bilateral_filter(&src, &Mat::new(8, 8, 1, MatDepth::U8).unwrap(), &mut dst, 5, 10.0, 3.0).is_err());
+    }
+
+    #[test]
+    fn test_adaptive_bilateral_filter() {
+        let mut src = Mat::new(12, 12, 1, MatDepth::U8).unwrap();
+        let mut sigma = Mat::new(12, 12, 1, MatDepth::F32).unwrap();
+        for row in 0..12 {
+            for col in 0..12 {
+                src.at_mut(row, col).unwrap()[0] = if (row + col) % 2 == 0 { 100 } else { 110 };
+                sigma.set_f32(row, col, 0, if col < 6 { 0.0 } else { 100.0 }).unwrap();
+            }
+        }
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        adaptive_bilateral_filter(&src, &src, &mut dst, 3, &sigma, 2.0).unwrap();
+        // Zero sigma keeps only equal neighbours; a large one averages
+        assert_eq!(dst.at(5, 2).unwrap()[0], src.at(5, 2).unwrap()[0]);
+        assert!(dst.at(5, 9).unwrap()[0].abs_diff(105) <= 2);
+    }
+
     #[test]
     fn test_distance_transform() {
         let src = Mat::new_with_default(50, 50, 1, MatDepth::U8, Scalar::all(255.0)).unwrap();
@@ -755,6 +1350,103 @@ mod tests {
         assert_eq!(dst.rows(), src.rows());
     }
 
+    #[test]
+    fn test_distance_transform_exact_metrics() {
+        let seeds = [(3usize, 4usize), (17, 25), (9, 9), (10, 9)];
+        let mut src = Mat::new_with_default(20, 30, 1, MatDepth::U8, Scalar::all(255.0)).unwrap();
+        for &(r, c) in &seeds {
+            src.at_mut(r, c).unwrap()[0] = 0;
+        }
+
+        let metric = |distance_type, dx: f64, dy: f64| match distance_type {
+            DistanceType::L1 => dx.abs() + dy.abs(),
+            DistanceType::L2 => dx.hypot(dy),
+            DistanceType::C => dx.abs().max(dy.abs()),
+        };
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        for distance_type in [DistanceType::L1, DistanceType::L2, DistanceType::C] {
+            distance_transform(&src, &mut dst, distance_type, 0).unwrap();
+            assert_eq!(dst.depth(), MatDepth::F32);
+            for row in 0..20 {
+                for col in 0..30 {
+                    let expected = seeds
+                        .iter()
+                        .map(|&(r, c)| metric(distance_type, col as f64 - c as f64, row as f64 - r as f64))
+                        .fold(f64::INFINITY, f64::min);
+                    let got = f64::from(dst.at_f32(row, col, 0).unwrap());
+                    assert!((got - expected).abs() < 1e-4, "{distance_type:?} at ({row}, {col}): {got} vs {expected}");
+                }
+            }
+        }
+
+        assert!(distance_transform(&src, &mut dst, DistanceType::L2, 7).is_err());
+    }
+
+    #[test]
+    fn test_distance_transform_labels() {
+        // Two seed blobs: a vertical pair at the left, a single pixel at the right
+        let mut src = Mat::new_with_default(9, 21, 1, MatDepth::U8, Scalar::all(255.0)).unwrap();
+        src.at_mut(4, 2).unwrap()[0] = 0;
+        src.at_mut(5, 2).unwrap()[0] = 0;
+        src.at_mut(4, 18).unwrap()[0] = 0;
+
+        let mut dist = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let mut labels = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        for distance_type in [DistanceType::L1, DistanceType::L2, DistanceType::C] {
+            distance_transform_with_labels(&src, &mut dist, &mut labels, distance_type, DistanceLabelType::ConnectedComponent)
+                .unwrap();
+            assert_eq!(labels.depth(), MatDepth::S32);
+            assert_eq!(labels.at_i32(0, 0, 0).unwrap(), 1);
+            assert_eq!(labels.at_i32(8, 8, 0).unwrap(), 1);
+            assert_eq!(labels.at_i32(0, 20, 0).unwrap(), 2);
+            assert_eq!(labels.at_i32(8, 12, 0).unwrap(), 2);
+        }
+
+        distance_transform_with_labels(&src, &mut dist, &mut labels, DistanceType::L2, DistanceLabelType::Pixel).unwrap();
+        assert_eq!(labels.at_i32(0, 2, 0).unwrap(), 1);
+        assert_eq!(labels.at_i32(8, 2, 0).unwrap(), 3);
+        assert_eq!(labels.at_i32(4, 18, 0).unwrap(), 2);
+        assert!((dist.at_f32(8, 2, 0).unwrap() - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_watershed() {
+        // Dark left half, bright right half, one seed in each
+        let mut image = Mat::new(10, 12, 3, MatDepth::U8).unwrap();
+        for row in 0..10 {
+            for col in 6..12 {
+                image.at_mut(row, col).unwrap().fill(200);
+            }
+        }
+        let mut markers = Mat::new(10, 12, 1, MatDepth::S32).unwrap();
+        markers.set_i32(5, 2, 0, 1).unwrap();
+        markers.set_i32(5, 9, 0, 300).unwrap();
+
+        watershed(&image, &mut markers).unwrap();
+
+        for row in 0..10 {
+            for col in 0..12 {
+                let label = markers.at_i32(row, col, 0).unwrap();
+                if row == 0 || col == 0 || row == 9 || col == 11 {
+                    assert_eq!(label, WATERSHED_BOUNDARY);
+                } else if col < 5 {
+                    assert_eq!(label, 1, "({row}, {col})");
+                } else if col > 6 {
+                    assert_eq!(label, 300, "({row}, {col})");
+                } else {
+                    assert!([1, 300, WATERSHED_BOUNDARY].contains(&label));
+                }
+            }
+        }
+        // Each interior row crosses from one region to the other via a boundary
+        for row in 1..9 {
+            assert!((1..11).any(|col| markers.at_i32(row, col, 0).unwrap() == WATERSHED_BOUNDARY));
+        }
+
+        let mut u8_markers = Mat::new(10, 12, 1, MatDepth::U8).unwrap();
+        assert!(watershed(&image, &mut u8_markers).is_err());
+    }
+
     #[test]
     fn test_gabor_filter() {
         let src = Mat::new_with_default(50, 50, 1, MatDepth::U8, Scalar::all(128.0)).unwrap();
@@ -0,0 +1,110 @@
+//! Batch processing across images
+//!
+//! [`map_into`] runs one operation on every image of a batch, spreading the
+//! images over the rayon pool under the same rules as the loops in
+//! [`crate::core::parallel`]. Outputs are written into a caller-owned
+//! `Vec<Mat>`; operations that size their output with
+//! [`Mat::create`](crate::core::Mat::create), such as [`resize`],
+//! [`cvt_color`] and [`normalize`], then reuse the previous call's buffers
+//! when the batch shape repeats, as it does across epochs of a dataset.
+
+use crate::core::parallel::parallel_for_rows;
+use crate::core::types::{ColorConversionCode, InterpolationFlag, Size};
+use crate::core::{Mat, MatDepth};
+use crate::error::Result;
+
+/// Apply `op(src, dst)` to every image, returning the outputs in order
+///
+/// Fails with the first error in image order.
+pub fn map<F>(images: &[Mat], op: F) -> Result<Vec<Mat>>
+where
+    F: Fn(&Mat, &mut Mat) -> Result<()> + Sync + Send,
+{
+    let mut outputs = Vec::with_capacity(images.len());
+    map_into(images, &mut outputs, op)?;
+    Ok(outputs)
+}
+
+/// [`map`] writing into `outputs`, which is resized to one entry per image
+/// and whose existing matrices are passed to `op` as destinations
+pub fn map_into<F>(images: &[Mat], outputs: &mut Vec<Mat>, op: F) -> Result<()>
+where
+    F: Fn(&Mat, &mut Mat) -> Result<()> + Sync + Send,
+{
+    outputs.truncate(images.len());
+    while outputs.len() < images.len() {
+        outputs.push(Mat::new(1, 1, 1, MatDepth::U8)?);
+    }
+    parallel_for_rows(outputs, 1, |i, dst| op(&images[i], &mut dst[0]))
+}
+
+/// Resize every image to `dsize`
+pub fn resize(images: &[Mat], outputs: &mut Vec<Mat>, dsize: Size, interpolation: InterpolationFlag) -> Result<()> {
+    map_into(images, outputs, |src, dst| super::resize(src, dst, dsize, interpolation))
+}
+
+/// Convert the color space of every image
+pub fn cvt_color(images: &[Mat], outputs: &mut Vec<Mat>, code: ColorConversionCode) -> Result<()> {
+    map_into(images, outputs, |src, dst| super::cvt_color(src, dst, code))
+}
+
+/// Stretch every image to `[alpha, beta]` independently
+pub fn normalize(images: &[Mat], outputs: &mut Vec<Mat>, alpha: f64, beta: f64) -> Result<()> {
+    map_into(images, outputs, |src, dst| crate::core::normalize(src, dst, alpha, beta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    fn batch() -> Vec<Mat> {
+        (0..6u8)
+            .map(|i| {
+                let mut mat = Mat::new(8, 12, 3, MatDepth::U8).unwrap();
+                for (j, v) in mat.data_mut().iter_mut().enumerate() {
+                    *v = (j as u8).wrapping_mul(i + 1);
+                }
+                mat
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_matches_per_image() {
+        let images = batch();
+        let mut outputs = Vec::new();
+        resize(&images, &mut outputs, Size::new(5, 4), InterpolationFlag::Area).unwrap();
+        assert_eq!(outputs.len(), images.len());
+        for (src, out) in images.iter().zip(&outputs) {
+            let mut expected = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            crate::imgproc::resize(src, &mut expected, Size::new(5, 4), InterpolationFlag::Area).unwrap();
+            assert_eq!(out.data(), expected.data());
+        }
+
+        let gray = map(&images, |src, dst| crate::imgproc::cvt_color(src, dst, ColorConversionCode::RgbToGray)).unwrap();
+        assert!(gray.iter().all(|g| g.channels() == 1 && g.rows() == 8));
+    }
+
+    #[test]
+    fn test_batch_reuses_buffers() {
+        let images = batch();
+        let mut outputs = Vec::new();
+        normalize(&images, &mut outputs, 0.0, 255.0).unwrap();
+        let buffers: Vec<*const u8> = outputs.iter().map(|m| m.data().as_ptr()).collect();
+
+        normalize(&images[..4], &mut outputs, 0.0, 255.0).unwrap();
+        assert_eq!(outputs.len(), 4);
+        assert!(outputs.iter().zip(&buffers).all(|(m, &p)| m.data().as_ptr() == p));
+    }
+
+    #[test]
+    fn test_batch_propagates_errors() {
+        let mut images = batch();
+        images[2] = Mat::new(8, 12, 1, MatDepth::U8).unwrap();
+        images[4] = Mat::new(8, 12, 1, MatDepth::U8).unwrap();
+        let mut outputs = Vec::new();
+        let err = cvt_color(&images, &mut outputs, ColorConversionCode::RgbToHsv);
+        assert!(matches!(err, Err(Error::InvalidParameter(_))));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::core::{Mat, MatDepth};
-use crate::core::types::ColorConversionCode;
+use crate::core::types::{ColorConversionCode, ColorOrder};
 use crate::error::{Error, Result};
 
 /// Convert color space of an image with GPU acceleration (async for WASM)
@@ -14,6 +14,7 @@ pub async fn cvt_color_async(
             "cvt_color only supports U8 depth".to_string(),
         ));
     }
+    check_color_order(src, code)?;
 
     // Try GPU if requested and available
     if use_gpu {
@@ -23,14 +24,20 @@ pub async fn cvt_color_async(
                 ColorConversionCode::RgbToGray => {
                     use crate::gpu::ops::rgb_to_gray_gpu_async;
                     match rgb_to_gray_gpu_async(src, dst).await {
-                        Ok(()) => return Ok(()),
+                        Ok(()) => {
+                            dst.set_color_order(ColorOrder::Unknown);
+                            return Ok(());
+                        }
                         Err(_) => { /* Fall through to CPU */ }
                     }
                 }
                 ColorConversionCode::RgbToHsv => {
                     use crate::gpu::ops::rgb_to_hsv_gpu_async;
                     match rgb_to_hsv_gpu_async(src, dst).await {
-                        Ok(()) => return Ok(()),
+                        Ok(()) => {
+                            dst.set_color_order(ColorOrder::Unknown);
+                            return Ok(());
+                        }
                         Err(_) => { /* Fall through to CPU */ }
                     }
                 }
@@ -44,18 +51,22 @@ pub async fn cvt_color_async(
 }
 
 /// Convert color space of an image (CPU-only, sync)
+///
+/// Fails if `src` is tagged with a [`ColorOrder`] other than the one `code`
+/// expects; `dst` is tagged with the order `code` produces.
 pub fn cvt_color(src: &Mat, dst: &mut Mat, code: ColorConversionCode) -> Result<()> {
     if src.depth() != MatDepth::U8 {
         return Err(Error::UnsupportedOperation(
             "cvt_color only supports U8 depth".to_string(),
         ));
     }
+    check_color_order(src, code)?;
 
     // GPU acceleration handled by individual conversion functions
     // (rgb_to_gray_gpu, rgb_to_hsv_gpu, etc.) - no generic cvt_color_gpu
 
     // CPU fallback
-    match code {
+    let converted = match code {
         ColorConversionCode::BgrToGray | ColorConversionCode::RgbToGray => {
             bgr_to_gray(src, dst, code == ColorConversionCode::BgrToGray)
         }
@@ -86,6 +97,46 @@ pub fn cvt_color(src: &Mat, dst: &mut Mat, code: ColorConversionCode) -> Result<
         ColorConversionCode::YCrCbToBgr | ColorConversionCode::YCrCbToRgb => {
             ycrcb_to_rgb(src, dst, code == ColorConversionCode::YCrCbToBgr)
         }
+    };
+    converted?;
+    dst.set_color_order(code.destination_order().unwrap_or_default());
+    Ok(())
+}
+
+/// Convert a 1, 3 or 4-channel image to grayscale, reading the channel
+/// order from its [`ColorOrder`] tag
+///
+/// Use this instead of picking `BgrToGray` or `RgbToGray` by hand when the
+/// image's origin isn't known.
+pub fn to_gray(src: &Mat, dst: &mut Mat) -> Result<()> {
+    let bgr = src.color_order().or_convention() == ColorOrder::Bgr;
+    let code = match (src.channels(), bgr) {
+        (1, _) => {
+            *dst = src.clone();
+            dst.set_color_order(ColorOrder::Unknown);
+            return Ok(());
+        }
+        (3, true) => ColorConversionCode::BgrToGray,
+        (3, false) => ColorConversionCode::RgbToGray,
+        (4, true) => ColorConversionCode::BgraToGray,
+        (4, false) => ColorConversionCode::RgbaToGray,
+        (n, _) => {
+            return Err(Error::InvalidParameter(format!(
+                "Cannot convert a {n}-channel image to grayscale"
+            )))
+        }
+    };
+    cvt_color(src, dst, code)
+}
+
+fn check_color_order(src: &Mat, code: ColorConversionCode) -> Result<()> {
+    match (code.source_order(), src.color_order()) {
+        (Some(expected), actual) if actual != ColorOrder::Unknown && actual != expected => {
+            Err(Error::InvalidParameter(format!(
+                "{code:?} expects {expected:?} input but the image is tagged {actual:?}"
+            )))
+        }
+        _ => Ok(()),
     }
 }
 
@@ -97,7 +148,7 @@ fn bgr_to_gray(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 1, MatDepth::U8)?;
 
     #[cfg(feature = "rayon")]
     {
@@ -155,7 +206,7 @@ fn rgba_to_gray(src: &Mat, dst: &mut Mat, is_bgra: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 1, MatDepth::U8)?;
 
     #[cfg(feature = "rayon")]
     {
@@ -211,7 +262,7 @@ fn gray_to_bgr(src: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -236,7 +287,7 @@ fn swap_rb_channels(src: &Mat, dst: &mut Mat) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -261,7 +312,7 @@ fn rgb_to_hsv(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -321,7 +372,7 @@ fn hsv_to_rgb(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -379,7 +430,7 @@ fn rgb_to_lab(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -440,7 +491,7 @@ fn lab_to_rgb(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -496,7 +547,7 @@ fn rgb_to_ycrcb(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -533,7 +584,7 @@ fn ycrcb_to_rgb(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)?;
+    dst.create(src.rows(), src.cols(), 3, MatDepth::U8)?;
 
     for row in 0..src.rows() {
         for col in 0..src.cols() {
@@ -564,6 +615,145 @@ fn ycrcb_to_rgb(src: &Mat, dst: &mut Mat, is_bgr: bool) -> Result<()> {
     Ok(())
 }
 
+/// Memory layout of a planar 4:2:0 YUV frame
+///
+/// All layouts start with the full-resolution Y plane (`width × height`
+/// bytes) followed by chroma at half resolution in both directions. In a
+/// [`Mat`] the frame is stored as a single-channel U8 image of
+/// `height · 3/2` rows and `width` columns, as delivered by most camera and
+/// video decoder APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Yuv420Layout {
+    /// Interleaved UV plane (most hardware decoders, Apple, WebCodecs)
+    Nv12,
+    /// Interleaved VU plane (Android camera)
+    Nv21,
+    /// Separate U then V planes (a.k.a. YUV420p, common in software codecs)
+    I420,
+}
+
+impl Yuv420Layout {
+    /// Byte offsets of the U and V samples for pixel (`row`, `col`)
+    fn chroma_offsets(self, width: usize, height: usize, row: usize, col: usize) -> (usize, usize) {
+        let luma = width * height;
+        match self {
+            Self::Nv12 => {
+                let i = luma + (row / 2) * width + (col / 2) * 2;
+                (i, i + 1)
+            }
+            Self::Nv21 => {
+                let i = luma + (row / 2) * width + (col / 2) * 2;
+                (i + 1, i)
+            }
+            Self::I420 => {
+                let i = (row / 2) * (width / 2) + col / 2;
+                (luma + i, luma + luma / 4 + i)
+            }
+        }
+    }
+}
+
+/// Convert a 4:2:0 YUV frame to RGB (`channels` = 3) or RGBA (`channels` = 4,
+/// opaque alpha)
+///
+/// Uses the BT.601 limited-range matrix, as OpenCV's `COLOR_YUV2RGB_NV12`
+/// family does. Width and frame height must be even.
+pub fn yuv420_to_rgb(src: &Mat, dst: &mut Mat, layout: Yuv420Layout, channels: usize) -> Result<()> {
+    let (width, height) = yuv420_frame_size(src, channels)?;
+    let data = src.data();
+
+    dst.create(height, width, channels, MatDepth::U8)?;
+    let out = dst.data_mut();
+
+    for row in 0..height {
+        for col in 0..width {
+            let (u_idx, v_idx) = layout.chroma_offsets(width, height, row, col);
+            let rgb = yuv_to_rgb_pixel(data[row * width + col], data[u_idx], data[v_idx]);
+
+            let base = (row * width + col) * channels;
+            out[base..base + 3].copy_from_slice(&rgb);
+            if channels == 4 {
+                out[base + 3] = 255;
+            }
+        }
+    }
+
+    dst.set_color_order(ColorOrder::Rgb);
+    Ok(())
+}
+
+/// Convert a 4:2:0 YUV frame to RGB/RGBA, on the GPU when requested and
+/// available (async for WASM)
+///
+/// Uploading the compact YUV frame and converting on the device saves the
+/// CPU conversion and halves the upload compared with sending RGB.
+pub async fn yuv420_to_rgb_async(
+    src: &Mat,
+    dst: &mut Mat,
+    layout: Yuv420Layout,
+    channels: usize,
+    use_gpu: bool,
+) -> Result<()> {
+    if use_gpu {
+        #[cfg(feature = "gpu")]
+        {
+            use crate::gpu::ops::yuv420_to_rgb_gpu_async;
+            if let Ok(()) = yuv420_to_rgb_gpu_async(src, dst, layout, channels).await {
+                return Ok(());
+            }
+            // Fall through to CPU
+        }
+    }
+
+    yuv420_to_rgb(src, dst, layout, channels)
+}
+
+/// Validate a 4:2:0 frame and return its image `(width, height)`
+pub(crate) fn yuv420_frame_size(src: &Mat, channels: usize) -> Result<(usize, usize)> {
+    if src.channels() != 1 || src.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "YUV 4:2:0 frames must be single-channel U8".to_string(),
+        ));
+    }
+
+    if channels != 3 && channels != 4 {
+        return Err(Error::InvalidParameter(
+            "Output must have 3 (RGB) or 4 (RGBA) channels".to_string(),
+        ));
+    }
+
+    let width = src.cols();
+    if !src.rows().is_multiple_of(3) || !width.is_multiple_of(2) {
+        return Err(Error::InvalidDimensions(
+            "YUV 4:2:0 frame must have height·3/2 rows and an even width".to_string(),
+        ));
+    }
+
+    let height = src.rows() / 3 * 2;
+    if !height.is_multiple_of(2) || height == 0 {
+        return Err(Error::InvalidDimensions(
+            "YUV 4:2:0 image height must be even and non-zero".to_string(),
+        ));
+    }
+
+    Ok((width, height))
+}
+
+/// BT.601 limited-range YUV to RGB
+fn yuv_to_rgb_pixel(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let y = 1.164 * (f32::from(y) - 16.0);
+    let u = f32::from(u) - 128.0;
+    let v = f32::from(v) - 128.0;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let to_byte = |x: f32| x.round().clamp(0.0, 255.0) as u8;
+    [
+        to_byte(y + 1.596 * v),
+        to_byte(y - 0.813 * v - 0.391 * u),
+        to_byte(y + 2.018 * u),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -599,4 +789,112 @@ mod tests {
         assert_eq!(result[1], 150);
         assert_eq!(result[2], 100);
     }
+
+    #[test]
+    fn test_color_order_tracking() {
+        let mut src = Mat::new(2, 2, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+        src.at_mut(0, 0).unwrap().copy_from_slice(&[255, 0, 0]);
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        assert!(cvt_color(&src, &mut dst, ColorConversionCode::RgbToGray).is_err());
+        assert!(cvt_color(&src, &mut dst, ColorConversionCode::RgbToHsv).is_err());
+
+        cvt_color(&src, &mut dst, ColorConversionCode::BgrToRgb).unwrap();
+        assert_eq!(dst.color_order(), ColorOrder::Rgb);
+        assert_eq!(dst.at(0, 0).unwrap(), &[0, 0, 255]);
+
+        // to_gray follows the tag: pure blue either way round
+        let (mut from_bgr, mut from_rgb) = (Mat::new(1, 1, 1, MatDepth::U8).unwrap(), Mat::new(1, 1, 1, MatDepth::U8).unwrap());
+        to_gray(&src, &mut from_bgr).unwrap();
+        to_gray(&dst, &mut from_rgb).unwrap();
+        assert_eq!(from_bgr.at(0, 0).unwrap()[0], 29);
+        assert_eq!(from_rgb.at(0, 0).unwrap()[0], 29);
+        assert_eq!(from_bgr.color_order(), ColorOrder::Unknown);
+
+        // Untagged images are accepted in either order
+        let untagged = Mat::new(2, 2, 3, MatDepth::U8).unwrap();
+        cvt_color(&untagged, &mut dst, ColorConversionCode::BgrToGray).unwrap();
+        let gray = dst.clone();
+        cvt_color(&gray, &mut dst, ColorConversionCode::GrayToBgr).unwrap();
+        assert_eq!(dst.color_order(), ColorOrder::Bgr);
+    }
+
+    #[test]
+    fn test_color_order_survives_processing() {
+        use crate::imgproc::{
+            bilateral_filter, blur, dilate, erode, flip, gaussian_blur, get_structuring_element, median_blur,
+            pyr_down, pyr_up, resize, rotate, threshold, warp_affine, warp_perspective, MorphShape, RotateCode,
+        };
+        use crate::core::types::{InterpolationFlag, Size, ThresholdType};
+
+        let mut src = Mat::new(16, 16, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+        src.at_mut(4, 4).unwrap().copy_from_slice(&[255, 0, 0]);
+        let kernel = get_structuring_element(MorphShape::Rect, Size::new(3, 3));
+        let affine = [[1.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+        let perspective = [[1.0, 0.0, 1.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let size = Size::new(16, 16);
+
+        type Op<'a> = Box<dyn Fn(&Mat, &mut Mat) -> Result<()> + 'a>;
+        let ops: Vec<(&str, Op)> = vec![
+            ("resize", Box::new(|s, d| resize(s, d, Size::new(8, 8), InterpolationFlag::Linear))),
+            ("gaussian_blur", Box::new(|s, d| gaussian_blur(s, d, Size::new(3, 3), 1.0))),
+            ("blur", Box::new(|s, d| blur(s, d, Size::new(3, 3)))),
+            ("median_blur", Box::new(|s, d| median_blur(s, d, 3))),
+            ("bilateral_filter", Box::new(|s, d| bilateral_filter(s, d, 5, 20.0, 5.0))),
+            ("flip", Box::new(|s, d| flip(s, d, 1))),
+            ("rotate", Box::new(|s, d| rotate(s, d, RotateCode::Rotate90Clockwise))),
+            ("warp_affine", Box::new(|s, d| warp_affine(s, d, &affine, size))),
+            ("warp_perspective", Box::new(|s, d| warp_perspective(s, d, &perspective, size))),
+            ("threshold", Box::new(|s, d| threshold(s, d, 100.0, 255.0, ThresholdType::Binary).map(|_| ()))),
+            ("erode", Box::new(|s, d| erode(s, d, &kernel))),
+            ("dilate", Box::new(|s, d| dilate(s, d, &kernel))),
+            ("pyr_down", Box::new(pyr_down)),
+            ("pyr_up", Box::new(pyr_up)),
+            ("normalize", Box::new(|s, d| crate::core::normalize(s, d, 0.0, 255.0))),
+            ("convert_scale_abs", Box::new(|s, d| crate::core::convert_scale_abs(s, d, 1.0, 0.0))),
+            ("add", Box::new(|s, d| crate::core::add(s, s, d))),
+        ];
+        for (name, op) in ops {
+            // A reused destination must not keep its own tag either
+            let mut dst = Mat::new(16, 16, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Rgb);
+            op(&src, &mut dst).unwrap();
+            assert_eq!(dst.color_order(), ColorOrder::Bgr, "{name} dropped the tag");
+        }
+    }
+
+    /// 4x2 frame: left half red, right half blue, in the given layout
+    fn yuv_test_frame(layout: Yuv420Layout) -> Mat {
+        // BT.601 limited range: red = (81, 90, 240), blue = (41, 240, 110)
+        let y = [81u8, 81, 41, 41, 81, 81, 41, 41];
+        let (u, v) = ([90u8, 240], [240u8, 110]);
+        let chroma: Vec<u8> = match layout {
+            Yuv420Layout::Nv12 => vec![u[0], v[0], u[1], v[1]],
+            Yuv420Layout::Nv21 => vec![v[0], u[0], v[1], u[1]],
+            Yuv420Layout::I420 => vec![u[0], u[1], v[0], v[1]],
+        };
+
+        let mut frame = Mat::new(3, 4, 1, MatDepth::U8).unwrap();
+        frame.data_mut()[..8].copy_from_slice(&y);
+        frame.data_mut()[8..].copy_from_slice(&chroma);
+        frame
+    }
+
+    #[test]
+    fn test_yuv420_to_rgb_layouts() {
+        for layout in [Yuv420Layout::Nv12, Yuv420Layout::Nv21, Yuv420Layout::I420] {
+            let frame = yuv_test_frame(layout);
+            let mut rgb = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            yuv420_to_rgb(&frame, &mut rgb, layout, 4).unwrap();
+            assert_eq!((rgb.rows(), rgb.cols(), rgb.channels()), (2, 4, 4));
+
+            let red = rgb.at(1, 0).unwrap();
+            assert!(red[0] > 250 && red[1] < 5 && red[2] < 5 && red[3] == 255, "{layout:?} {red:?}");
+            let blue = rgb.at(0, 3).unwrap();
+            assert!(blue[0] < 5 && blue[1] < 5 && blue[2] > 250, "{layout:?} {blue:?}");
+        }
+
+        let odd = Mat::new(4, 4, 1, MatDepth::U8).unwrap();
+        let mut rgb = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        assert!(yuv420_to_rgb(&odd, &mut rgb, Yuv420Layout::Nv12, 3).is_err());
+    }
 }
@@ -0,0 +1,178 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Rect;
+use crate::error::{Error, Result};
+
+/// Area, bounding box and centroid of one connected component
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComponentStats {
+    pub area: usize,
+    pub bounding_box: Rect,
+    /// Mean pixel position `(x, y)`; `(0, 0)` for an empty label
+    pub centroid: (f64, f64),
+}
+
+/// Label the connected components of the non-zero pixels of `src`
+///
+/// `src` is a 1-channel U8 image and `connectivity` 4 or 8. `labels`
+/// becomes an S32 image with the background 0 and components numbered from
+/// 1 in raster order of their first pixel. Returns the number of labels,
+/// counting the background, as OpenCV's `connectedComponents` does.
+pub fn connected_components(src: &Mat, labels: &mut Mat, connectivity: i32) -> Result<usize> {
+    if src.channels() != 1 || src.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "Connected components require a 1-channel U8 image".to_string(),
+        ));
+    }
+    if connectivity != 4 && connectivity != 8 {
+        return Err(Error::InvalidParameter(
+            "Connectivity must be 4 or 8".to_string(),
+        ));
+    }
+
+    let (rows, cols) = (src.rows(), src.cols());
+    let pixels = src.data();
+    let mut label_data = vec![0i32; rows * cols];
+    let mut next = 0;
+    let mut stack = Vec::new();
+    for start in 0..rows * cols {
+        if pixels[start] == 0 || label_data[start] != 0 {
+            continue;
+        }
+        next += 1;
+        label_data[start] = next;
+        stack.push(start);
+        while let Some(i) = stack.pop() {
+            let (row, col) = (i / cols, i % cols);
+            for nr in row.saturating_sub(1)..(row + 2).min(rows) {
+                for nc in col.saturating_sub(1)..(col + 2).min(cols) {
+                    if connectivity == 4 && nr != row && nc != col {
+                        continue;
+                    }
+                    let j = nr * cols + nc;
+                    if pixels[j] != 0 && label_data[j] == 0 {
+                        label_data[j] = next;
+                        stack.push(j);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out = Mat::new(rows, cols, 1, MatDepth::S32)?;
+    for (i, &label) in label_data.iter().enumerate() {
+        out.set_i32(i / cols, i % cols, 0, label)?;
+    }
+    *labels = out;
+    Ok(next as usize + 1)
+}
+
+/// Per-label statistics of an S32 label image, indexed by label
+///
+/// Covers labels `0..num_labels`; other values, such as the -1 boundaries
+/// from [`watershed`](super::watershed), are ignored.
+pub fn component_stats(labels: &Mat, num_labels: usize) -> Result<Vec<ComponentStats>> {
+    if labels.channels() != 1 || labels.depth() != MatDepth::S32 {
+        return Err(Error::InvalidParameter(
+            "Component statistics require 1-channel S32 labels".to_string(),
+        ));
+    }
+
+    let cols = labels.cols();
+    // (area, sum x, sum y, min x, min y, max x, max y)
+    let mut acc = vec![(0usize, 0u64, 0u64, usize::MAX, usize::MAX, 0usize, 0usize); num_labels];
+    for i in 0..labels.rows() * cols {
+        let label = labels.at_i32(i / cols, i % cols, 0)?;
+        let Some(a) = usize::try_from(label).ok().and_then(|l| acc.get_mut(l)) else {
+            continue;
+        };
+        let (x, y) = (i % cols, i / cols);
+        a.0 += 1;
+        a.1 += x as u64;
+        a.2 += y as u64;
+        a.3 = a.3.min(x);
+        a.4 = a.4.min(y);
+        a.5 = a.5.max(x);
+        a.6 = a.6.max(y);
+    }
+
+    Ok(acc
+        .into_iter()
+        .map(|(area, sum_x, sum_y, min_x, min_y, max_x, max_y)| {
+            stats_from_sums(area, sum_x, sum_y, [min_x, min_y, max_x, max_y])
+        })
+        .collect())
+}
+
+/// [`connected_components`] followed by [`component_stats`], like
+/// OpenCV's `connectedComponentsWithStats`; entry 0 is the background
+pub fn connected_components_with_stats(src: &Mat, labels: &mut Mat, connectivity: i32) -> Result<Vec<ComponentStats>> {
+    let num_labels = connected_components(src, labels, connectivity)?;
+    component_stats(labels, num_labels)
+}
+
+/// Stats from a label's pixel count, coordinate sums and inclusive
+/// `[min x, min y, max x, max y]` bounds
+pub(crate) fn stats_from_sums(area: usize, sum_x: u64, sum_y: u64, bounds: [usize; 4]) -> ComponentStats {
+    if area == 0 {
+        return ComponentStats {
+            area,
+            bounding_box: Rect::new(0, 0, 0, 0),
+            centroid: (0.0, 0.0),
+        };
+    }
+    let [min_x, min_y, max_x, max_y] = bounds;
+    ComponentStats {
+        area,
+        bounding_box: Rect::new(min_x as i32, min_y as i32, (max_x - min_x + 1) as i32, (max_y - min_y + 1) as i32),
+        centroid: (sum_x as f64 / area as f64, sum_y as f64 / area as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connected_components_with_stats() {
+        // A 3x4 block and two diagonal pixels that only touch 8-connected
+        let mut src = Mat::new(8, 10, 1, MatDepth::U8).unwrap();
+        for row in 1..4 {
+            for col in 2..6 {
+                src.at_mut(row, col).unwrap()[0] = 255;
+            }
+        }
+        src.at_mut(6, 7).unwrap()[0] = 1;
+        src.at_mut(7, 8).unwrap()[0] = 1;
+
+        let mut labels = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let stats = connected_components_with_stats(&src, &mut labels, 8).unwrap();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(labels.depth(), MatDepth::S32);
+        assert_eq!(labels.at_i32(2, 3, 0).unwrap(), 1);
+        assert_eq!(labels.at_i32(7, 8, 0).unwrap(), 2);
+        assert_eq!(stats[0].area, 80 - 14);
+        assert_eq!(stats[1].area, 12);
+        assert_eq!(stats[1].bounding_box, Rect::new(2, 1, 4, 3));
+        assert_eq!(stats[1].centroid, (3.5, 2.0));
+        assert_eq!(stats[2].bounding_box, Rect::new(7, 6, 2, 2));
+        assert_eq!(stats[2].centroid, (7.5, 6.5));
+
+        assert_eq!(connected_components(&src, &mut labels, 4).unwrap(), 4);
+        assert!(connected_components(&src, &mut labels, 6).is_err());
+    }
+
+    #[test]
+    fn test_component_stats_ignores_other_labels() {
+        let mut labels = Mat::new(3, 3, 1, MatDepth::S32).unwrap();
+        labels.set_i32(0, 0, 0, -1).unwrap();
+        labels.set_i32(1, 1, 0, 2).unwrap();
+        labels.set_i32(2, 2, 0, 7).unwrap();
+
+        let stats = component_stats(&labels, 3).unwrap();
+        assert_eq!(stats[0].area, 6);
+        assert_eq!(stats[1].area, 0);
+        assert_eq!(stats[1].bounding_box, Rect::new(0, 0, 0, 0));
+        assert_eq!(stats[2].centroid, (1.0, 1.0));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::core::{Mat, MatDepth};
-use crate::core::types::Point;
+use crate::core::types::{Point, Point2f, RotatedRect};
 use crate::error::{Error, Result};
 
 /// Contour retrieval modes
@@ -254,6 +254,156 @@ pub fn bounding_rect(contour: &Contour) -> crate::core::types::Rect {
     crate::core::types::Rect::new(min_x, min_y, max_x - min_x, max_y - min_y)
 }
 
+/// Least-squares ellipse through `points` (Fitzgibbon's direct method in
+/// Halir and Flusser's numerically stable form)
+///
+/// Unlike a general conic fit, the result is always an ellipse. `width` and
+/// `height` of the returned rectangle are the full axis lengths and `angle`
+/// is the direction of the `width` axis. Sub-pixel points (e.g. from
+/// [`crate::imgproc::find_edge_subpixel`]) give sub-pixel ellipses; integer
+/// contours can be converted with `Point2f::new(p.x as f32, p.y as f32)`.
+/// Needs at least 5 points.
+pub fn fit_ellipse(points: &[Point2f]) -> Result<RotatedRect> {
+    if points.len() < 5 {
+        return Err(Error::InvalidParameter(
+            "fit_ellipse needs at least 5 points".to_string(),
+        ));
+    }
+
+    // Centre and scale the points for conditioning
+    let n = points.len() as f64;
+    let mx = points.iter().map(|p| f64::from(p.x)).sum::<f64>() / n;
+    let my = points.iter().map(|p| f64::from(p.y)).sum::<f64>() / n;
+    let scale = points
+        .iter()
+        .map(|p| (f64::from(p.x) - mx).hypot(f64::from(p.y) - my))
+        .sum::<f64>()
+        / n;
+    if scale < 1e-12 {
+        return Err(Error::InvalidParameter(
+            "fit_ellipse needs distinct points".to_string(),
+        ));
+    }
+
+    // Scatter matrices of the quadratic [x², xy, y²] and linear [x, y, 1] parts
+    let (mut s1, mut s2, mut s3) = ([[0.0f64; 3]; 3], [[0.0f64; 3]; 3], [[0.0f64; 3]; 3]);
+    for p in points {
+        let x = (f64::from(p.x) - mx) / scale;
+        let y = (f64::from(p.y) - my) / scale;
+        let quad = [x * x, x * y, y * y];
+        let lin = [x, y, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                s1[i][j] += quad[i] * quad[j];
+                s2[i][j] += quad[i] * lin[j];
+                s3[i][j] += lin[i] * lin[j];
+            }
+        }
+    }
+
+    // Linear part as a function of the quadratic part: a2 = T a1
+    let s3_inv = invert_3x3(&s3).ok_or_else(|| {
+        Error::InvalidParameter("fit_ellipse: degenerate point configuration".to_string())
+    })?;
+    let s2_t: [[f64; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| s2[j][i]));
+    let t = mul_3x3(&s3_inv, &s2_t).map(|row| row.map(|v| -v));
+    let reduced = mul_3x3(&s2, &t);
+    let m: [[f64; 3]; 3] = std::array::from_fn(|i| std::array::from_fn(|j| s1[i][j] + reduced[i][j]));
+    // Premultiply by the inverse of the ellipse constraint 4ac - b² = 1
+    let m = [m[2].map(|v| v / 2.0), m[1].map(|v| -v), m[0].map(|v| v / 2.0)];
+
+    let a1 = real_eigenvalues_3x3(&m)
+        .into_iter()
+        .filter_map(|lambda| null_vector_3x3(&m, lambda))
+        .find(|v| 4.0 * v[0] * v[2] - v[1] * v[1] > 0.0)
+        .ok_or_else(|| Error::InvalidParameter("fit_ellipse: points do not fit an ellipse".to_string()))?;
+    let a2: [f64; 3] = std::array::from_fn(|i| (0..3).map(|j| t[i][j] * a1[j]).sum());
+    let (a, b, c, d, e, f) = (a1[0], a1[1], a1[2], a2[0], a2[1], a2[2]);
+
+    // Conic coefficients to centre, semi-axes and orientation
+    let den = b * b - 4.0 * a * c;
+    let x0 = (2.0 * c * d - b * e) / den;
+    let y0 = (2.0 * a * e - b * d) / den;
+    let f0 = a * x0 * x0 + b * x0 * y0 + c * y0 * y0 + d * x0 + e * y0 + f;
+    let theta = 0.5 * b.atan2(a - c);
+    let (sin, cos) = theta.sin_cos();
+    let lambda1 = a * cos * cos + b * sin * cos + c * sin * sin;
+    let lambda2 = a * sin * sin - b * sin * cos + c * cos * cos;
+    if -f0 / lambda1 <= 0.0 || -f0 / lambda2 <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "fit_ellipse: points do not fit an ellipse".to_string(),
+        ));
+    }
+    let semi1 = (-f0 / lambda1).sqrt() * scale;
+    let semi2 = (-f0 / lambda2).sqrt() * scale;
+
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(RotatedRect::new(
+        Point2f::new((x0 * scale + mx) as f32, (y0 * scale + my) as f32),
+        (2.0 * semi1) as f32,
+        (2.0 * semi2) as f32,
+        theta.to_degrees() as f32,
+    ))
+}
+
+fn mul_3x3(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    std::array::from_fn(|i| std::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn invert_3x3(m: &[[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let cof = |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let det = m[0][0] * cof(1, 2, 1, 2) - m[0][1] * cof(1, 2, 0, 2) + m[0][2] * cof(1, 2, 0, 1);
+    if det.abs() < 1e-300 {
+        return None;
+    }
+    Some([
+        [cof(1, 2, 1, 2) / det, -cof(0, 2, 1, 2) / det, cof(0, 1, 1, 2) / det],
+        [-cof(1, 2, 0, 2) / det, cof(0, 2, 0, 2) / det, -cof(0, 1, 0, 2) / det],
+        [cof(1, 2, 0, 1) / det, -cof(0, 2, 0, 1) / det, cof(0, 1, 0, 1) / det],
+    ])
+}
+
+/// Real roots of the characteristic polynomial of `m`
+fn real_eigenvalues_3x3(m: &[[f64; 3]; 3]) -> Vec<f64> {
+    // λ³ + p λ² + q λ + r = 0
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    let minors = m[0][0] * m[1][1] - m[0][1] * m[1][0] + m[0][0] * m[2][2] - m[0][2] * m[2][0] + m[1][1] * m[2][2]
+        - m[1][2] * m[2][1];
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    let (p, q, r) = (-trace, minors, -det);
+
+    // Depressed cubic t³ + a t + b with λ = t - p/3
+    let a = q - p * p / 3.0;
+    let b = 2.0 * p * p * p / 27.0 - p * q / 3.0 + r;
+    let shift = -p / 3.0;
+    let disc = b * b / 4.0 + a * a * a / 27.0;
+    if disc > 0.0 {
+        let sq = disc.sqrt();
+        vec![(-b / 2.0 + sq).cbrt() + (-b / 2.0 - sq).cbrt() + shift]
+    } else {
+        let rho = (-a / 3.0).max(0.0).sqrt();
+        let phi = if rho > 0.0 { (-b / (2.0 * rho * rho * rho)).clamp(-1.0, 1.0).acos() } else { 0.0 };
+        (0..3)
+            .map(|k| 2.0 * rho * ((phi + 2.0 * std::f64::consts::PI * f64::from(k)) / 3.0).cos() + shift)
+            .collect()
+    }
+}
+
+/// Vector spanning the null space of `m - lambda I`, from the largest cross
+/// product of two of its rows
+fn null_vector_3x3(m: &[[f64; 3]; 3], lambda: f64) -> Option<[f64; 3]> {
+    let rows: [[f64; 3]; 3] =
+        std::array::from_fn(|i| std::array::from_fn(|j| m[i][j] - if i == j { lambda } else { 0.0 }));
+    let cross = |u: [f64; 3], v: [f64; 3]| [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    [cross(rows[0], rows[1]), cross(rows[0], rows[2]), cross(rows[1], rows[2])]
+        .into_iter()
+        .map(|v| (v, v[0] * v[0] + v[1] * v[1] + v[2] * v[2]))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .filter(|&(_, norm)| norm > 1e-300)
+        .map(|(v, _)| v)
+}
+
 /// Calculate moments of a contour
 pub struct Moments {
     pub m00: f64,
@@ -343,4 +493,32 @@ mod tests {
         assert_eq!(rect.x, 5);
         assert_eq!(rect.y, 5);
     }
+
+    #[test]
+    fn test_fit_ellipse() {
+        let (cx, cy, a, b, angle) = (42.5f64, -7.25f64, 30.0f64, 12.0f64, 35f64.to_radians());
+        let points: Vec<Point2f> = (0..40)
+            .map(|i| {
+                let t = f64::from(i) * 0.157;
+                let (x, y) = (a * t.cos(), b * t.sin());
+                Point2f::new(
+                    (cx + x * angle.cos() - y * angle.sin()) as f32,
+                    (cy + x * angle.sin() + y * angle.cos()) as f32,
+                )
+            })
+            .collect();
+
+        let ellipse = fit_ellipse(&points).unwrap();
+        assert!((f64::from(ellipse.center.x) - cx).abs() < 1e-3 && (f64::from(ellipse.center.y) - cy).abs() < 1e-3);
+        // Either axis may come first
+        let (major, minor, major_angle) = if ellipse.width > ellipse.height {
+            (ellipse.width, ellipse.height, f64::from(ellipse.angle))
+        } else {
+            (ellipse.height, ellipse.width, f64::from(ellipse.angle) + 90.0)
+        };
+        assert!((f64::from(major) - 2.0 * a).abs() < 1e-2 && (f64::from(minor) - 2.0 * b).abs() < 1e-2);
+        assert!(((major_angle - 35.0).rem_euclid(180.0)).min((35.0 - major_angle).rem_euclid(180.0)) < 0.01);
+
+        assert!(fit_ellipse(&points[..4]).is_err());
+    }
 }
@@ -1,4 +1,5 @@
 use crate::core::{Mat, MatDepth};
+use crate::core::types::Rect;
 use crate::error::{Error, Result};
 
 #[cfg(feature = "rayon")]
@@ -342,6 +343,57 @@ pub fn canny(
     Ok(())
 }
 
+/// Pixels around a ROI that influence [`canny`] inside it: 5x5 blur, 3x3
+/// Sobel, non-maximum suppression and one step of hysteresis
+const CANNY_ROI_MARGIN: usize = 5;
+
+/// Canny edge detection restricted to `roi` and, if given, the non-zero
+/// pixels of `mask`
+///
+/// Only the ROI plus a small border is processed, and the edges inside it
+/// are the same as those of [`canny`] on the whole image. `dst` is full
+/// size, so coordinates need no translation, and zero outside the ROI and
+/// mask.
+pub fn canny_roi(
+    src: &Mat,
+    dst: &mut Mat,
+    threshold1: f64,
+    threshold2: f64,
+    roi: Rect,
+    mask: Option<&Mat>,
+) -> Result<()> {
+    if let Some(mask) = mask {
+        if mask.rows() != src.rows() || mask.cols() != src.cols() || mask.channels() != 1 || mask.depth() != MatDepth::U8 {
+            return Err(Error::InvalidParameter(
+                "Mask must be a single-channel U8 image the size of the source".to_string(),
+            ));
+        }
+    }
+
+    let (crop, covered) = src.roi_with_margin(roi, CANNY_ROI_MARGIN)?;
+    let mut edges = Mat::new(1, 1, 1, MatDepth::U8)?;
+    canny(&crop, &mut edges, threshold1, threshold2)?;
+
+    *dst = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
+    #[allow(clippy::cast_sign_loss)]
+    let (x0, y0, dx, dy) = (roi.x as usize, roi.y as usize, (roi.x - covered.x) as usize, (roi.y - covered.y) as usize);
+    #[allow(clippy::cast_sign_loss)]
+    let (width, height) = (roi.width as usize, roi.height as usize);
+    let (cols, crop_cols) = (src.cols(), crop.cols());
+    let edge_data = edges.data();
+    let dst_data = dst.data_mut();
+    for row in 0..height {
+        let src_row = &edge_data[(row + dy) * crop_cols + dx..][..width];
+        let out_row = &mut dst_data[(row + y0) * cols + x0..][..width];
+        for (col, (out, &edge)) in out_row.iter_mut().zip(src_row).enumerate() {
+            let allowed = mask.is_none_or(|m| m.data()[(row + y0) * cols + col + x0] != 0);
+            *out = if allowed { edge } else { 0 };
+        }
+    }
+
+    Ok(())
+}
+
 /// Scharr derivative filter with GPU acceleration (async for WASM)
 pub async fn scharr_async(
     src: &Mat,
@@ -457,4 +509,41 @@ mod tests {
         canny(&src, &mut dst, 50.0, 150.0).unwrap();
         assert_eq!(dst.rows(), src.rows());
     }
+
+    #[test]
+    fn test_canny_roi_matches_full_image() {
+        let mut src = Mat::new(60, 80, 1, MatDepth::U8).unwrap();
+        for row in 0..60 {
+            for col in 0..80 {
+                let inside = (row as i32 - 30).pow(2) + (col as i32 - 38).pow(2) < 15 * 15;
+                src.at_mut(row, col).unwrap()[0] = if inside { 200 } else { 40 } + ((row * 7 + col * 13) % 11) as u8;
+            }
+        }
+        let mut full = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        canny(&src, &mut full, 50.0, 150.0).unwrap();
+
+        let roi = Rect::new(20, 12, 30, 25);
+        let mut mask = Mat::new(60, 80, 1, MatDepth::U8).unwrap();
+        for row in 0..60 {
+            mask.at_mut(row, 30).unwrap()[0] = 255;
+        }
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        canny_roi(&src, &mut dst, 50.0, 150.0, roi, None).unwrap();
+        let mut masked = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        canny_roi(&src, &mut masked, 50.0, 150.0, roi, Some(&mask)).unwrap();
+
+        assert_eq!((dst.rows(), dst.cols()), (60, 80));
+        let mut edges = 0;
+        for row in 0..60 {
+            for col in 0..80 {
+                let inside = roi.contains(crate::core::types::Point::new(col as i32, row as i32));
+                let expected = if inside { full.at(row, col).unwrap()[0] } else { 0 };
+                assert_eq!(dst.at(row, col).unwrap()[0], expected, "({row}, {col})");
+                assert_eq!(masked.at(row, col).unwrap()[0], if col == 30 { expected } else { 0 });
+                edges += usize::from(expected != 0);
+            }
+        }
+        assert!(edges > 0);
+        assert!(canny_roi(&src, &mut dst, 50.0, 150.0, Rect::new(70, 0, 20, 10), None).is_err());
+    }
 }
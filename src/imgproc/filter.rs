@@ -35,7 +35,7 @@ pub fn gaussian_blur(src: &Mat, dst: &mut Mat, ksize: Size, sigma_x: f64) -> Res
 }
 
 /// CPU implementation of Gaussian blur
-fn gaussian_blur_cpu(src: &Mat, dst: &mut Mat, ksize: Size, sigma_x: f64) -> Result<()> {
+pub(crate) fn gaussian_blur_cpu(src: &Mat, dst: &mut Mat, ksize: Size, sigma_x: f64) -> Result<()> {
     let kernel = create_gaussian_kernel(ksize, sigma_x)?;
     apply_separable_filter(src, dst, &kernel, &kernel)
 }
@@ -61,10 +61,16 @@ pub async fn blur_async(src: &Mat, dst: &mut Mat, ksize: Size, use_gpu: bool) ->
 }
 
 /// Apply box blur (simple averaging) - optimized with separable filter (CPU-only, sync)
+///
+/// U8 and F32 images are supported; F32 results are not rounded.
 pub fn blur(src: &Mat, dst: &mut Mat, ksize: Size) -> Result<()> {
+    if src.depth() == MatDepth::F32 {
+        return blur_f32(src, dst, ksize);
+    }
+
     if src.depth() != MatDepth::U8 {
         return Err(Error::UnsupportedOperation(
-            "blur only supports U8 depth".to_string(),
+            "blur only supports U8 and F32 depth".to_string(),
         ));
     }
 
@@ -79,6 +85,56 @@ pub fn blur(src: &Mat, dst: &mut Mat, ksize: Size) -> Result<()> {
     apply_separable_filter(src, dst, &kernel_x, &kernel_y)
 }
 
+/// Box blur in float precision, replicating border pixels like the U8 path
+fn blur_f32(src: &Mat, dst: &mut Mat, ksize: Size) -> Result<()> {
+    if ksize.width <= 0 || ksize.height <= 0 {
+        return Err(Error::InvalidParameter(
+            "Kernel size must be positive".to_string(),
+        ));
+    }
+
+    let rows = src.rows();
+    let cols = src.cols();
+    let channels = src.channels();
+    let kw = usize::try_from(ksize.width).unwrap_or(1);
+    let kh = usize::try_from(ksize.height).unwrap_or(1);
+
+    // Horizontal pass
+    let mut temp = vec![0f32; rows * cols * channels];
+    for row in 0..rows {
+        for col in 0..cols {
+            for ch in 0..channels {
+                let mut sum = 0.0;
+                for i in 0..kw {
+                    let c = (col + i).saturating_sub(kw / 2).min(cols - 1);
+                    sum += src.at_f32(row, c, ch)?;
+                }
+                #[allow(clippy::cast_precision_loss)]
+                { temp[(row * cols + col) * channels + ch] = sum / kw as f32; }
+            }
+        }
+    }
+
+    // Vertical pass
+    let mut out = Mat::new(rows, cols, channels, MatDepth::F32)?;
+    for row in 0..rows {
+        for col in 0..cols {
+            for ch in 0..channels {
+                let mut sum = 0.0;
+                for i in 0..kh {
+                    let r = (row + i).saturating_sub(kh / 2).min(rows - 1);
+                    sum += temp[(r * cols + col) * channels + ch];
+                }
+                #[allow(clippy::cast_precision_loss)]
+                out.set_f32(row, col, ch, sum / kh as f32)?;
+            }
+        }
+    }
+
+    *dst = out.with_color_order(src.color_order());
+    Ok(())
+}
+
 /// Apply median blur - optimized with rayon parallelization
 pub fn median_blur(src: &Mat, dst: &mut Mat, ksize: i32) -> Result<()> {
     if src.depth() != MatDepth::U8 {
@@ -99,7 +155,7 @@ pub fn median_blur(src: &Mat, dst: &mut Mat, ksize: i32) -> Result<()> {
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let rows = src.rows();
     let cols = src.cols();
@@ -152,6 +208,88 @@ pub fn median_blur(src: &Mat, dst: &mut Mat, ksize: i32) -> Result<()> {
     Ok(())
 }
 
+/// Sharpen by unsharp masking: `src + amount · (src − gaussian_blur(src, sigma))`
+///
+/// Differences from the blurred image smaller than `threshold` (in pixel
+/// levels) are left alone so flat, noisy areas are not amplified. Typical
+/// settings are `sigma` 1–3, `amount` 0.5–1.5 and `threshold` 0–10. The kernel
+/// size follows from `sigma` (±3σ).
+pub fn unsharp_mask(src: &Mat, dst: &mut Mat, sigma: f64, amount: f64, threshold: f64) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "unsharp_mask only supports U8 depth".to_string(),
+        ));
+    }
+
+    if sigma <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "unsharp_mask sigma must be positive".to_string(),
+        ));
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let radius = (sigma * 3.0).ceil() as i32;
+    let ksize = Size::new(2 * radius + 1, 2 * radius + 1);
+
+    let mut blurred = Mat::new(1, 1, 1, MatDepth::U8)?;
+    gaussian_blur(src, &mut blurred, ksize, sigma)?;
+
+    let mut out = Mat::new(src.rows(), src.cols(), src.channels(), MatDepth::U8)?;
+    for ((o, &s), &b) in out.data_mut().iter_mut().zip(src.data()).zip(blurred.data()) {
+        let detail = f64::from(s) - f64::from(b);
+        let value = if detail.abs() < threshold {
+            f64::from(s)
+        } else {
+            f64::from(s) + amount * detail
+        };
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        { *o = value.round().clamp(0.0, 255.0) as u8; }
+    }
+
+    *dst = out.with_color_order(src.color_order());
+    Ok(())
+}
+
+/// Sharpen with a 3x3 high-pass kernel
+///
+/// Adds `strength` times the negative 4-neighbour Laplacian to each pixel;
+/// `strength = 1` gives the classic `[0 -1 0; -1 5 -1; 0 -1 0]` kernel.
+/// Borders are replicated. Cheaper but noisier than [`unsharp_mask`].
+pub fn sharpen(src: &Mat, dst: &mut Mat, strength: f64) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "sharpen only supports U8 depth".to_string(),
+        ));
+    }
+
+    let rows = src.rows();
+    let cols = src.cols();
+    let channels = src.channels();
+    let mut out = Mat::new(rows, cols, channels, MatDepth::U8)?;
+
+    let src_data = src.data();
+    let at = |r: usize, c: usize, ch: usize| f64::from(src_data[(r * cols + c) * channels + ch]);
+
+    for row in 0..rows {
+        let up = row.saturating_sub(1);
+        let down = (row + 1).min(rows - 1);
+        for col in 0..cols {
+            let left = col.saturating_sub(1);
+            let right = (col + 1).min(cols - 1);
+            for ch in 0..channels {
+                let center = at(row, col, ch);
+                let laplacian = at(up, col, ch) + at(down, col, ch) + at(row, left, ch) + at(row, right, ch) - 4.0 * center;
+                let value = center - strength * laplacian;
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                { out.at_mut(row, col)?[ch] = value.round().clamp(0.0, 255.0) as u8; }
+            }
+        }
+    }
+
+    *dst = out.with_color_order(src.color_order());
+    Ok(())
+}
+
 /// Create a 1D Gaussian kernel
 fn create_gaussian_kernel(ksize: Size, sigma: f64) -> Result<Vec<f32>> {
     let size = ksize.width.max(ksize.height);
@@ -299,7 +437,8 @@ fn apply_separable_filter(
     });
 
     // Then apply vertical kernel - PARALLEL
-    *dst = Mat::new(rows, cols, channels, src.depth())?;
+    dst.create(rows, cols, channels, src.depth())?;
+    dst.set_color_order(src.color_order());
 
     let half_y = kernel_y.len() / 2;
 
@@ -413,6 +552,24 @@ mod tests {
         assert_eq!(dst.cols(), src.cols());
     }
 
+    #[test]
+    fn test_blur_f32() {
+        let mut src = Mat::new(4, 5, 1, MatDepth::F32).unwrap();
+        src.set_f32(2, 2, 0, 9.0).unwrap();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        blur(&src, &mut dst, Size::new(3, 3)).unwrap();
+
+        assert_eq!(dst.depth(), MatDepth::F32);
+        for row in 0..4usize {
+            for col in 0..5usize {
+                let near = row.abs_diff(2) <= 1 && col.abs_diff(2) <= 1;
+                let expected = if near { 1.0 } else { 0.0 };
+                assert!((dst.at_f32(row, col, 0).unwrap() - expected).abs() < 1e-6);
+            }
+        }
+    }
+
     #[test]
     fn test_gaussian_blur() {
         let src = Mat::new_with_default(100, 100, 3, MatDepth::U8, Scalar::all(128.0)).unwrap();
@@ -423,4 +580,48 @@ mod tests {
         assert_eq!(dst.rows(), src.rows());
         assert_eq!(dst.cols(), src.cols());
     }
+
+    /// Vertical step edge from 80 to 170 at column 10
+    fn step_edge() -> Mat {
+        let mut img = Mat::new(20, 20, 1, MatDepth::U8).unwrap();
+        for row in 0..20 {
+            for col in 0..20 {
+                img.at_mut(row, col).unwrap()[0] = if col < 10 { 80 } else { 170 };
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_unsharp_mask_boosts_edges() {
+        let src = step_edge();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        unsharp_mask(&src, &mut dst, 1.5, 1.0, 0.0).unwrap();
+
+        // Overshoot on both sides of the edge, flat areas untouched
+        assert!(dst.at(10, 9).unwrap()[0] < 80);
+        assert!(dst.at(10, 10).unwrap()[0] > 170);
+        assert_eq!(dst.at(10, 0).unwrap()[0], 80);
+        assert_eq!(dst.at(10, 19).unwrap()[0], 170);
+
+        // A threshold above the edge contrast leaves the image unchanged
+        unsharp_mask(&src, &mut dst, 1.5, 1.0, 100.0).unwrap();
+        assert_eq!(dst.data(), src.data());
+
+        assert!(unsharp_mask(&src, &mut dst, 0.0, 1.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_sharpen() {
+        let src = step_edge();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        sharpen(&src, &mut dst, 1.0).unwrap();
+
+        assert_eq!(dst.at(5, 9).unwrap()[0], 0);
+        assert_eq!(dst.at(5, 10).unwrap()[0], 255);
+        assert_eq!(dst.at(5, 3).unwrap()[0], 80);
+
+        sharpen(&src, &mut dst, 0.0).unwrap();
+        assert_eq!(dst.data(), src.data());
+    }
 }
@@ -0,0 +1,245 @@
+#![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Rect;
+use crate::error::{Error, Result};
+
+/// Sharpness metric used by [`focus_measure`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocusMeasure {
+    /// Variance of the 3x3 Laplacian response (Pech-Pacheco)
+    VarianceOfLaplacian,
+    /// Mean squared Sobel gradient magnitude over pixels above `threshold`
+    Tenengrad { threshold: f64 },
+    /// Mean squared difference between pixels two columns apart
+    Brenner,
+}
+
+/// Compute a sharpness score for an image or a region of it
+///
+/// Larger values mean a sharper image. Scores are only comparable between
+/// images of similar content and exposure, which is the autofocus case: the
+/// same scene captured at different lens positions.
+///
+/// Multi-channel images are reduced to luminance first, reading the channel
+/// order from their [`ColorOrder`](crate::core::types::ColorOrder) tag; U8,
+/// U16, F32 and F64 depths are accepted. `roi` restricts the measure to a rectangle, which must
+/// be at least 3x3 and lie inside the image.
+pub fn focus_measure(src: &Mat, method: FocusMeasure, roi: Option<Rect>) -> Result<f64> {
+    let rect = roi.unwrap_or_else(|| Rect::new(0, 0, src.cols() as i32, src.rows() as i32));
+
+    if rect.x < 0
+        || rect.y < 0
+        || rect.width < 3
+        || rect.height < 3
+        || (rect.x + rect.width) as usize > src.cols()
+        || (rect.y + rect.height) as usize > src.rows()
+    {
+        return Err(Error::InvalidDimensions(
+            "Focus ROI must be at least 3x3 and inside the image".to_string(),
+        ));
+    }
+
+    let gray = luminance(src, rect)?;
+    let width = rect.width as usize;
+    let height = rect.height as usize;
+
+    let score = match method {
+        FocusMeasure::VarianceOfLaplacian => variance_of_laplacian_impl(&gray, width, height),
+        FocusMeasure::Tenengrad { threshold } => tenengrad_impl(&gray, width, height, threshold),
+        FocusMeasure::Brenner => brenner_impl(&gray, width, height),
+    };
+
+    Ok(score)
+}
+
+/// Variance of the Laplacian over the whole image
+pub fn variance_of_laplacian(src: &Mat) -> Result<f64> {
+    focus_measure(src, FocusMeasure::VarianceOfLaplacian, None)
+}
+
+/// Tenengrad (Sobel gradient energy) over the whole image
+pub fn tenengrad(src: &Mat, threshold: f64) -> Result<f64> {
+    focus_measure(src, FocusMeasure::Tenengrad { threshold }, None)
+}
+
+/// Brenner gradient over the whole image
+pub fn brenner(src: &Mat) -> Result<f64> {
+    focus_measure(src, FocusMeasure::Brenner, None)
+}
+
+/// Index of the sharpest frame in a focus sweep
+///
+/// Returns `None` for an empty slice. Useful for picking the best lens position
+/// in an autofocus loop or the sharpest frame out of a burst.
+pub fn best_focus_index(frames: &[Mat], method: FocusMeasure, roi: Option<Rect>) -> Result<Option<usize>> {
+    let mut best: Option<(usize, f64)> = None;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let score = focus_measure(frame, method, roi)?;
+        if best.is_none_or(|(_, s)| score > s) {
+            best = Some((i, score));
+        }
+    }
+
+    Ok(best.map(|(i, _)| i))
+}
+
+fn luminance(src: &Mat, rect: Rect) -> Result<Vec<f64>> {
+    let channels = src.channels();
+    let x0 = rect.x as usize;
+    let y0 = rect.y as usize;
+    let width = rect.width as usize;
+    let height = rect.height as usize;
+
+    let weights = src.color_order().luma_weights();
+    let mut gray = Vec::with_capacity(width * height);
+    let mut px = vec![0.0f64; channels];
+
+    for row in y0..y0 + height {
+        for col in x0..x0 + width {
+            for (ch, v) in px.iter_mut().enumerate() {
+                *v = match src.depth() {
+                    MatDepth::U8 => f64::from(src.at(row, col)?[ch]),
+                    MatDepth::U16 => f64::from(src.at_u16(row, col, ch)?),
+                    MatDepth::S32 => f64::from(src.at_i32(row, col, ch)?),
+                    MatDepth::F32 => f64::from(src.at_f32(row, col, ch)?),
+                    MatDepth::F64 => src.at_f64(row, col, ch)?,
+                };
+            }
+
+            gray.push(match channels {
+                1 | 2 => px[0],
+                _ => weights[0] * px[0] + weights[1] * px[1] + weights[2] * px[2],
+            });
+        }
+    }
+
+    Ok(gray)
+}
+
+fn variance_of_laplacian_impl(gray: &[f64], width: usize, height: usize) -> f64 {
+    let mut sum = 0.0;
+    let mut sum_sq = 0.0;
+    let mut count = 0.0;
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let c = y * width + x;
+            let lap = gray[c - width] + gray[c + width] + gray[c - 1] + gray[c + 1] - 4.0 * gray[c];
+            sum += lap;
+            sum_sq += lap * lap;
+            count += 1.0;
+        }
+    }
+
+    let mean = sum / count;
+    sum_sq / count - mean * mean
+}
+
+fn tenengrad_impl(gray: &[f64], width: usize, height: usize, threshold: f64) -> f64 {
+    let mut energy = 0.0;
+    let mut count = 0.0;
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let p = |dy: usize, dx: usize| gray[(y + dy - 1) * width + (x + dx - 1)];
+
+            let gx = p(0, 2) + 2.0 * p(1, 2) + p(2, 2) - p(0, 0) - 2.0 * p(1, 0) - p(2, 0);
+            let gy = p(2, 0) + 2.0 * p(2, 1) + p(2, 2) - p(0, 0) - 2.0 * p(0, 1) - p(0, 2);
+            let mag_sq = gx * gx + gy * gy;
+
+            if mag_sq > threshold * threshold {
+                energy += mag_sq;
+            }
+            count += 1.0;
+        }
+    }
+
+    energy / count
+}
+
+fn brenner_impl(gray: &[f64], width: usize, height: usize) -> f64 {
+    let mut energy = 0.0;
+    let mut count = 0.0;
+
+    for y in 0..height {
+        for x in 0..width - 2 {
+            let d = gray[y * width + x + 2] - gray[y * width + x];
+            energy += d * d;
+            count += 1.0;
+        }
+    }
+
+    energy / count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Scalar, Size};
+    use crate::imgproc::gaussian_blur;
+
+    fn checkerboard(size: usize, cell: usize) -> Mat {
+        let mut img = Mat::new(size, size, 1, MatDepth::U8).unwrap();
+        for row in 0..size {
+            for col in 0..size {
+                img.at_mut(row, col).unwrap()[0] = if (row / cell + col / cell).is_multiple_of(2) { 220 } else { 30 };
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_flat_image_has_zero_focus() {
+        let flat = Mat::new_with_default(32, 32, 3, MatDepth::U8, Scalar::all(100.0)).unwrap();
+
+        assert!(variance_of_laplacian(&flat).unwrap().abs() < 1e-9);
+        assert!(tenengrad(&flat, 0.0).unwrap().abs() < 1e-9);
+        assert!(brenner(&flat).unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_blur_lowers_every_measure() {
+        let sharp = checkerboard(64, 4);
+        let mut blurred = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        gaussian_blur(&sharp, &mut blurred, Size::new(7, 7), 2.0).unwrap();
+
+        for method in [
+            FocusMeasure::VarianceOfLaplacian,
+            FocusMeasure::Tenengrad { threshold: 0.0 },
+            FocusMeasure::Brenner,
+        ] {
+            let s = focus_measure(&sharp, method, None).unwrap();
+            let b = focus_measure(&blurred, method, None).unwrap();
+            assert!(s > b, "{method:?}: sharp {s} <= blurred {b}");
+        }
+    }
+
+    #[test]
+    fn test_best_focus_index_and_roi() {
+        let sharp = checkerboard(32, 4);
+        let mut blurred = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        gaussian_blur(&sharp, &mut blurred, Size::new(5, 5), 1.5).unwrap();
+
+        let frames = vec![blurred.clone_mat(), sharp.clone_mat(), blurred];
+        let best = best_focus_index(&frames, FocusMeasure::Brenner, Some(Rect::new(4, 4, 16, 16))).unwrap();
+        assert_eq!(best, Some(1));
+        assert_eq!(best_focus_index(&[], FocusMeasure::Brenner, None).unwrap(), None);
+
+        assert!(focus_measure(&sharp, FocusMeasure::Brenner, Some(Rect::new(30, 30, 8, 8))).is_err());
+    }
+
+    #[test]
+    fn test_bgr_tagged_luminance() {
+        use crate::core::types::ColorOrder;
+
+        let gray = checkerboard(32, 4);
+        let rgb: Vec<u8> = gray.data().iter().flat_map(|&v| [v, v / 2, 0]).collect();
+        let bgr: Vec<u8> = gray.data().iter().flat_map(|&v| [0, v / 2, v]).collect();
+        let rgb = Mat::from_raw(rgb, 32, 32, 3, MatDepth::U8).unwrap();
+        let bgr = Mat::from_raw(bgr, 32, 32, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+
+        let expected = focus_measure(&rgb, FocusMeasure::Brenner, None).unwrap();
+        assert!((focus_measure(&bgr, FocusMeasure::Brenner, None).unwrap() - expected).abs() < 1e-9);
+    }
+}
@@ -1,11 +1,18 @@
 use crate::core::{Mat, MatDepth};
-use crate::core::types::{Size, InterpolationFlag, Point2f};
+use crate::core::types::{BorderType, Size, InterpolationFlag, Point2f};
 use crate::error::{Error, Result};
+use super::sampling::{read_element, PixelSampler};
 
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
 /// Resize an image
+///
+/// All [`InterpolationFlag`] modes are supported. `Nearest` picks source pixel
+/// `floor(dst * scale)` like `OpenCV`'s `INTER_NEAREST`; the other modes align
+/// pixel centres (`src = (dst + 0.5) * scale - 0.5`) like `OpenCV`. Prefer `Area` when shrinking: the interpolating
+/// kernels only look at a fixed neighbourhood and will alias on large
+/// reductions.
 pub fn resize(src: &Mat, dst: &mut Mat, dsize: Size, interpolation: InterpolationFlag) -> Result<()> {
     if src.depth() != MatDepth::U8 {
         return Err(Error::UnsupportedOperation(
@@ -24,14 +31,27 @@ pub fn resize(src: &Mat, dst: &mut Mat, dsize: Size, interpolation: Interpolatio
     #[allow(clippy::cast_sign_loss)]
     let new_cols = dsize.width as usize;
 
-    *dst = Mat::new(new_rows, new_cols, src.channels(), src.depth())?;
+    dst.create(new_rows, new_cols, src.channels(), src.depth())?;
+    dst.set_color_order(src.color_order());
 
     match interpolation {
         InterpolationFlag::Nearest => resize_nearest(src, dst),
         InterpolationFlag::Linear => resize_bilinear(src, dst),
-        _ => Err(Error::UnsupportedOperation(format!(
-            "Interpolation method {interpolation:?} not yet implemented"
-        ))),
+        InterpolationFlag::Area => {
+            let x_taps = area_taps(src.cols(), dst.cols());
+            let y_taps = area_taps(src.rows(), dst.rows());
+            resize_separable(src, dst, &x_taps, &y_taps)
+        }
+        InterpolationFlag::Cubic => {
+            let x_taps = kernel_taps(src.cols(), dst.cols(), 2, cubic_weight);
+            let y_taps = kernel_taps(src.rows(), dst.rows(), 2, cubic_weight);
+            resize_separable(src, dst, &x_taps, &y_taps)
+        }
+        InterpolationFlag::Lanczos4 => {
+            let x_taps = kernel_taps(src.cols(), dst.cols(), 4, lanczos4_weight);
+            let y_taps = kernel_taps(src.rows(), dst.rows(), 4, lanczos4_weight);
+            resize_separable(src, dst, &x_taps, &y_taps)
+        }
     }
 }
 
@@ -75,19 +95,11 @@ fn resize_nearest(src: &Mat, dst: &mut Mat) -> Result<()> {
 
 /// Bilinear interpolation - optimized parallel version
 fn resize_bilinear(src: &Mat, dst: &mut Mat) -> Result<()> {
-    // Map corners exactly: dst pixel 0 -> src pixel 0, dst pixel (n-1) -> src pixel (m-1)
+    // Align pixel centres like the kernel-based modes and OpenCV's INTER_LINEAR
     #[allow(clippy::cast_precision_loss)]
-    let x_ratio = if dst.cols() > 1 {
-        (src.cols() - 1) as f32 / (dst.cols() - 1) as f32
-    } else {
-        0.0
-    };
+    let x_ratio = src.cols() as f32 / dst.cols() as f32;
     #[allow(clippy::cast_precision_loss)]
-    let y_ratio = if dst.rows() > 1 {
-        (src.rows() - 1) as f32 / (dst.rows() - 1) as f32
-    } else {
-        0.0
-    };
+    let y_ratio = src.rows() as f32 / dst.rows() as f32;
 
     let src_rows = src.rows();
     let src_cols = src.cols();
@@ -104,9 +116,9 @@ fn resize_bilinear(src: &Mat, dst: &mut Mat) -> Result<()> {
         dst_data.par_chunks_mut(row_size).enumerate().for_each(|(dst_row, dst_row_data)| {
             for dst_col in 0..dst_cols {
                 #[allow(clippy::cast_precision_loss)]
-                let src_x = dst_col as f32 * x_ratio;
+                let src_x = ((dst_col as f32 + 0.5) * x_ratio - 0.5).clamp(0.0, (src_cols - 1) as f32);
                 #[allow(clippy::cast_precision_loss)]
-                let src_y = dst_row as f32 * y_ratio;
+                let src_y = ((dst_row as f32 + 0.5) * y_ratio - 0.5).clamp(0.0, (src_rows - 1) as f32);
 
                 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
                 let x1 = src_x.floor() as usize;
@@ -191,6 +203,155 @@ fn resize_bilinear(src: &Mat, dst: &mut Mat) -> Result<()> {
     Ok(())
 }
 
+/// Source pixels and normalized weights contributing to one destination pixel along an axis
+type AxisTaps = Vec<Vec<(usize, f32)>>;
+
+/// Bicubic convolution kernel with `A = -0.75`, matching `OpenCV`'s `INTER_CUBIC`
+pub(crate) fn cubic_weight(x: f64) -> f64 {
+    const A: f64 = -0.75;
+    let x = x.abs();
+    if x <= 1.0 {
+        ((A + 2.0) * x - (A + 3.0)) * x * x + 1.0
+    } else if x < 2.0 {
+        ((A * x - 5.0 * A) * x + 8.0 * A) * x - 4.0 * A
+    } else {
+        0.0
+    }
+}
+
+/// Lanczos kernel with an 8-tap (a = 4) window, matching `OpenCV`'s `INTER_LANCZOS4`
+pub(crate) fn lanczos4_weight(x: f64) -> f64 {
+    const A: f64 = 4.0;
+    if x.abs() < 1e-9 {
+        return 1.0;
+    }
+    if x.abs() >= A {
+        return 0.0;
+    }
+    let px = std::f64::consts::PI * x;
+    A * px.sin() * (px / A).sin() / (px * px)
+}
+
+/// Build per-destination taps for an interpolating kernel of the given radius.
+///
+/// Pixel centres are aligned (`src = (dst + 0.5) * scale - 0.5`) and out-of-range
+/// source indices are clamped to the border.
+fn kernel_taps(src_len: usize, dst_len: usize, radius: i64, kernel: fn(f64) -> f64) -> AxisTaps {
+    #[allow(clippy::cast_precision_loss)]
+    let scale = src_len as f64 / dst_len as f64;
+    #[allow(clippy::cast_possible_wrap)]
+    let last = src_len as i64 - 1;
+
+    (0..dst_len)
+        .map(|d| {
+            #[allow(clippy::cast_precision_loss)]
+            let s = (d as f64 + 0.5) * scale - 0.5;
+            #[allow(clippy::cast_possible_truncation)]
+            let base = s.floor() as i64;
+
+            let mut taps: Vec<(usize, f64)> = Vec::with_capacity(2 * radius as usize);
+            for i in (base - radius + 1)..=(base + radius) {
+                #[allow(clippy::cast_precision_loss)]
+                let w = kernel(s - i as f64);
+                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                let idx = i.clamp(0, last) as usize;
+                taps.push((idx, w));
+            }
+            normalize_taps(taps)
+        })
+        .collect()
+}
+
+/// Build per-destination taps for area interpolation.
+///
+/// When shrinking, each destination pixel is the coverage-weighted average of the
+/// source pixels its footprint overlaps, which avoids aliasing. When enlarging,
+/// the footprint is smaller than a source pixel and this degenerates to linear
+/// interpolation, as in `OpenCV`.
+fn area_taps(src_len: usize, dst_len: usize) -> AxisTaps {
+    if src_len < dst_len {
+        return kernel_taps(src_len, dst_len, 1, |x| (1.0 - x.abs()).max(0.0));
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let scale = src_len as f64 / dst_len as f64;
+
+    (0..dst_len)
+        .map(|d| {
+            #[allow(clippy::cast_precision_loss)]
+            let start = d as f64 * scale;
+            let end = (start + scale).min(src_len as f64);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let first = start.floor() as usize;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let last = (end.ceil() as usize).min(src_len);
+
+            let taps = (first..last)
+                .filter_map(|i| {
+                    #[allow(clippy::cast_precision_loss)]
+                    let lo = (i as f64).max(start);
+                    #[allow(clippy::cast_precision_loss)]
+                    let hi = ((i + 1) as f64).min(end);
+                    (hi - lo > 1e-9).then_some((i, hi - lo))
+                })
+                .collect();
+            normalize_taps(taps)
+        })
+        .collect()
+}
+
+fn normalize_taps(taps: Vec<(usize, f64)>) -> Vec<(usize, f32)> {
+    let sum: f64 = taps.iter().map(|&(_, w)| w).sum();
+    let norm = if sum.abs() > 1e-12 { 1.0 / sum } else { 1.0 };
+    #[allow(clippy::cast_possible_truncation)]
+    taps.into_iter().map(|(i, w)| (i, (w * norm) as f32)).collect()
+}
+
+/// Separable resampling: a horizontal pass into an f32 buffer, then a vertical pass
+fn resize_separable(src: &Mat, dst: &mut Mat, x_taps: &AxisTaps, y_taps: &AxisTaps) -> Result<()> {
+    let src_cols = src.cols();
+    let dst_cols = dst.cols();
+    let channels = src.channels();
+    let src_row_size = src_cols * channels;
+    let dst_row_size = dst_cols * channels;
+
+    let mut horizontal = vec![0.0f32; src.rows() * dst_row_size];
+    horizontal
+        .par_chunks_mut(dst_row_size)
+        .zip(src.data().par_chunks(src_row_size))
+        .for_each(|(out_row, src_row)| {
+            for (dst_col, taps) in x_taps.iter().enumerate() {
+                let out = &mut out_row[dst_col * channels..(dst_col + 1) * channels];
+                for &(src_col, w) in taps {
+                    let px = &src_row[src_col * channels..(src_col + 1) * channels];
+                    for (o, &v) in out.iter_mut().zip(px) {
+                        *o += f32::from(v) * w;
+                    }
+                }
+            }
+        });
+
+    dst.data_mut()
+        .par_chunks_mut(dst_row_size)
+        .zip(y_taps.par_iter())
+        .for_each(|(dst_row, taps)| {
+            let mut acc = vec![0.0f32; dst_row_size];
+            for &(src_row, w) in taps {
+                let row = &horizontal[src_row * dst_row_size..(src_row + 1) * dst_row_size];
+                for (a, &v) in acc.iter_mut().zip(row) {
+                    *a += v * w;
+                }
+            }
+            for (d, a) in dst_row.iter_mut().zip(acc) {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let v = a.round().clamp(0.0, 255.0) as u8;
+                *d = v;
+            }
+        });
+
+    Ok(())
+}
+
 /// Flip an image
 /// Flip image with GPU acceleration (async for WASM)
 pub async fn flip_async(src: &Mat, dst: &mut Mat, flip_code: i32, use_gpu: bool) -> Result<()> {
@@ -214,7 +375,7 @@ pub async fn flip_async(src: &Mat, dst: &mut Mat, flip_code: i32, use_gpu: bool)
 
 /// Flip image (CPU-only, sync)
 pub fn flip(src: &Mat, dst: &mut Mat, flip_code: i32) -> Result<()> {
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     match flip_code {
         0 => {
@@ -258,105 +419,72 @@ pub fn flip(src: &Mat, dst: &mut Mat, flip_code: i32) -> Result<()> {
 }
 
 /// Warp affine transformation
+///
+/// `m` maps destination to source coordinates. Uses nearest-neighbour
+/// sampling, rounding source coordinates to the closest pixel centre like
+/// `OpenCV`'s `INTER_NEAREST` warps, with a constant zero border; see [`warp_affine_with_sampler`]
+/// for other interpolation and border modes.
 pub fn warp_affine(
     src: &Mat,
     dst: &mut Mat,
     m: &[[f64; 3]; 2],
     dsize: Size,
 ) -> Result<()> {
-    #[allow(clippy::cast_sign_loss)]
-    let dst_height = dsize.height as usize;
-    #[allow(clippy::cast_sign_loss)]
-    let dst_width = dsize.width as usize;
-    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?;
-
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-    let src_rows_i32 = src.rows() as i32;
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-    let src_cols_i32 = src.cols() as i32;
-
-    for row in 0..dst.rows() {
-        for col in 0..dst.cols() {
-            #[allow(clippy::cast_precision_loss)]
-            let x = col as f64;
-            #[allow(clippy::cast_precision_loss)]
-            let y = row as f64;
-
-            // Apply transformation
-            let src_x = m[0][0] * x + m[0][1] * y + m[0][2];
-            let src_y = m[1][0] * x + m[1][1] * y + m[1][2];
-
-            #[allow(clippy::cast_possible_truncation)]
-            let src_col = src_x as i32;
-            #[allow(clippy::cast_possible_truncation)]
-            let src_row = src_y as i32;
-
-            if src_row >= 0 && src_row < src_rows_i32
-                && src_col >= 0 && src_col < src_cols_i32
-            {
-                #[allow(clippy::cast_sign_loss)]
-                let src_pixel = src.at(src_row as usize, src_col as usize)?;
-                let dst_pixel = dst.at_mut(row, col)?;
-                dst_pixel.copy_from_slice(src_pixel);
-            }
-        }
-    }
+    let sampler = PixelSampler::new(InterpolationFlag::Nearest, BorderType::Constant);
+    warp_affine_with_sampler(src, dst, m, dsize, &sampler)
+}
 
-    Ok(())
+/// [`warp_affine`] with explicit interpolation and border handling
+pub fn warp_affine_with_sampler(
+    src: &Mat,
+    dst: &mut Mat,
+    m: &[[f64; 3]; 2],
+    dsize: Size,
+    sampler: &PixelSampler,
+) -> Result<()> {
+    sampler.warp(src, dst, dsize, |x, y| {
+        Some((
+            m[0][0] * x + m[0][1] * y + m[0][2],
+            m[1][0] * x + m[1][1] * y + m[1][2],
+        ))
+    })
 }
 
 /// Warp perspective transformation
+///
+/// `m` maps destination to source coordinates. Uses nearest-neighbour
+/// sampling, rounding source coordinates to the closest pixel centre like
+/// `OpenCV`'s `INTER_NEAREST` warps, with a constant zero border; see
+/// [`warp_perspective_with_sampler`] for other modes.
 pub fn warp_perspective(
     src: &Mat,
     dst: &mut Mat,
     m: &[[f64; 3]; 3],
     dsize: Size,
 ) -> Result<()> {
-    #[allow(clippy::cast_sign_loss)]
-    let dst_height = dsize.height as usize;
-    #[allow(clippy::cast_sign_loss)]
-    let dst_width = dsize.width as usize;
-    *dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?;
-
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-    let src_rows_i32 = src.rows() as i32;
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-    let src_cols_i32 = src.cols() as i32;
-
-    for row in 0..dst.rows() {
-        for col in 0..dst.cols() {
-            #[allow(clippy::cast_precision_loss)]
-            let x = col as f64;
-            #[allow(clippy::cast_precision_loss)]
-            let y = row as f64;
-
-            // Apply homography
-            let w = m[2][0] * x + m[2][1] * y + m[2][2];
-
-            if w.abs() < 1e-10 {
-                continue;
-            }
-
-            let src_x = (m[0][0] * x + m[0][1] * y + m[0][2]) / w;
-            let src_y = (m[1][0] * x + m[1][1] * y + m[1][2]) / w;
+    let sampler = PixelSampler::new(InterpolationFlag::Nearest, BorderType::Constant);
+    warp_perspective_with_sampler(src, dst, m, dsize, &sampler)
+}
 
-            #[allow(clippy::cast_possible_truncation)]
-            let src_col = src_x as i32;
-            #[allow(clippy::cast_possible_truncation)]
-            let src_row = src_y as i32;
-
-            if src_row >= 0 && src_row < src_rows_i32
-                && src_col >= 0 && src_col < src_cols_i32
-            {
-                #[allow(clippy::cast_sign_loss)]
-                let src_pixel = src.at(src_row as usize, src_col as usize)?;
-                let dst_pixel = dst.at_mut(row, col)?;
-                dst_pixel.copy_from_slice(src_pixel);
-            }
+/// [`warp_perspective`] with explicit interpolation and border handling
+pub fn warp_perspective_with_sampler(
+    src: &Mat,
+    dst: &mut Mat,
+    m: &[[f64; 3]; 3],
+    dsize: Size,
+    sampler: &PixelSampler,
+) -> Result<()> {
+    sampler.warp(src, dst, dsize, |x, y| {
+        // Apply homography
+        let w = m[2][0] * x + m[2][1] * y + m[2][2];
+        if w.abs() < 1e-10 {
+            return None;
         }
-    }
-
-    Ok(())
+        Some((
+            (m[0][0] * x + m[0][1] * y + m[0][2]) / w,
+            (m[1][0] * x + m[1][1] * y + m[1][2]) / w,
+        ))
+    })
 }
 
 /// Get rotation matrix for 2D rotation
@@ -405,6 +533,194 @@ pub fn get_affine_transform(src: &[Point2f; 3], dst: &[Point2f; 3]) -> [[f64; 3]
     [[a11, a12, a13], [a21, a22, a23]]
 }
 
+/// Invert a 2x3 affine transformation
+///
+/// Returns the zero matrix if the transform is singular.
+#[must_use]
+pub fn invert_affine_transform(m: &[[f64; 3]; 2]) -> [[f64; 3]; 2] {
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    if det.abs() < 1e-12 {
+        return [[0.0; 3]; 2];
+    }
+
+    let a = m[1][1] / det;
+    let b = -m[0][1] / det;
+    let c = -m[1][0] / det;
+    let d = m[0][0] / det;
+
+    [
+        [a, b, -(a * m[0][2] + b * m[1][2])],
+        [c, d, -(c * m[0][2] + d * m[1][2])],
+    ]
+}
+
+/// Size of the smallest canvas that holds `size` rotated by `angle` degrees
+/// and scaled by `scale` without cropping any corner
+#[must_use]
+pub fn rotation_bounding_size(size: Size, angle: f64, scale: f64) -> Size {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let w = f64::from(size.width) * scale;
+    let h = f64::from(size.height) * scale;
+
+    // Tolerance keeps exact multiples of 90 degrees from growing by a pixel
+    #[allow(clippy::cast_possible_truncation)]
+    let bound_w = (w * cos.abs() + h * sin.abs() - 1e-6).ceil().max(1.0) as i32;
+    #[allow(clippy::cast_possible_truncation)]
+    let bound_h = (w * sin.abs() + h * cos.abs() - 1e-6).ceil().max(1.0) as i32;
+
+    Size::new(bound_w, bound_h)
+}
+
+/// Rotation matrix about the image centre whose translation is adjusted so the
+/// rotated image fits entirely inside the returned output size
+///
+/// Like `get_rotation_matrix_2d`, the matrix maps source to destination
+/// coordinates.
+#[must_use]
+pub fn get_rotation_matrix_2d_bounded(size: Size, angle: f64, scale: f64) -> ([[f64; 3]; 2], Size) {
+    #[allow(clippy::cast_possible_truncation)]
+    let center = Point2f::new(
+        ((f64::from(size.width) - 1.0) * 0.5) as f32,
+        ((f64::from(size.height) - 1.0) * 0.5) as f32,
+    );
+    let mut m = get_rotation_matrix_2d(center, angle, scale);
+    let bound = rotation_bounding_size(size, angle, scale);
+
+    // Move the rotation centre to the centre of the enlarged canvas
+    m[0][2] += (f64::from(bound.width) - f64::from(size.width)) * 0.5;
+    m[1][2] += (f64::from(bound.height) - f64::from(size.height)) * 0.5;
+
+    (m, bound)
+}
+
+/// Rotate an image by an arbitrary angle (degrees, counter-clockwise),
+/// enlarging the output so no corner is cropped
+pub fn rotate_bound(src: &Mat, dst: &mut Mat, angle: f64, scale: f64) -> Result<()> {
+    if scale <= 0.0 {
+        return Err(Error::InvalidParameter(
+            "Rotation scale must be positive".to_string(),
+        ));
+    }
+
+    let (m, bound) = get_rotation_matrix_2d_bounded(src.size(), angle, scale);
+    // warp_affine samples the source through a destination -> source map
+    let inverse = invert_affine_transform(&m);
+    warp_affine(src, dst, &inverse, bound)
+}
+
+/// Polar remapping mode for `warp_polar`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarpPolarMode {
+    /// Radius maps linearly to the output column
+    Linear,
+    /// Radius maps logarithmically to the output column (semi-log-polar)
+    Log,
+}
+
+/// Remap an image to polar or log-polar coordinates
+///
+/// In the forward direction output columns are radius (0 to `max_radius`)
+/// and output rows are angle (0 to 360 degrees). With `inverse` set, `src` is
+/// a polar image and `dsize` is the size of the reconstructed Cartesian image.
+/// If `dsize` is zero in the forward direction, it defaults to
+/// `max_radius` x `max_radius * PI` like `OpenCV`.
+pub fn warp_polar(
+    src: &Mat,
+    dst: &mut Mat,
+    dsize: Size,
+    center: Point2f,
+    max_radius: f64,
+    mode: WarpPolarMode,
+    inverse: bool,
+) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "warp_polar only supports U8 depth".to_string(),
+        ));
+    }
+    if max_radius <= 0.0 || (mode == WarpPolarMode::Log && max_radius <= 1.0) {
+        return Err(Error::InvalidParameter(
+            "max_radius must be positive (and > 1 for log-polar)".to_string(),
+        ));
+    }
+
+    let dsize = if dsize.width <= 0 || dsize.height <= 0 {
+        if inverse {
+            return Err(Error::InvalidDimensions(
+                "Inverse warp_polar needs an explicit destination size".to_string(),
+            ));
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let size = Size::new(
+            max_radius.round() as i32,
+            (max_radius * std::f64::consts::PI).round() as i32,
+        );
+        size
+    } else {
+        dsize
+    };
+
+    // Polar image geometry: columns = radius, rows = angle
+    #[allow(clippy::cast_precision_loss)]
+    let (polar_w, polar_h) = if inverse {
+        (src.cols() as f64, src.rows() as f64)
+    } else {
+        (f64::from(dsize.width), f64::from(dsize.height))
+    };
+    let k_mag = match mode {
+        WarpPolarMode::Linear => polar_w / max_radius,
+        WarpPolarMode::Log => polar_w / max_radius.ln(),
+    };
+    let k_angle = polar_h / (2.0 * std::f64::consts::PI);
+    let cx = f64::from(center.x);
+    let cy = f64::from(center.y);
+
+    // Bilinear with a zero border; the angle axis of a polar source is periodic
+    let mut sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Constant);
+    if inverse {
+        sampler = sampler.with_vertical_border(BorderType::Wrap);
+    }
+
+    sampler.warp(src, dst, dsize, |x, y| {
+        if inverse {
+            let dx = x - cx;
+            let dy = y - cy;
+            let r = dx.hypot(dy);
+            let rho = match mode {
+                WarpPolarMode::Linear => r * k_mag,
+                WarpPolarMode::Log => {
+                    if r < 1.0 {
+                        return None;
+                    }
+                    r.ln() * k_mag
+                }
+            };
+            let mut phi = dy.atan2(dx);
+            if phi < 0.0 {
+                phi += 2.0 * std::f64::consts::PI;
+            }
+            Some((rho, phi * k_angle))
+        } else {
+            let r = match mode {
+                WarpPolarMode::Linear => x / k_mag,
+                WarpPolarMode::Log => (x / k_mag).exp(),
+            };
+            let (sin, cos) = (y / k_angle).sin_cos();
+            Some((cx + r * cos, cy + r * sin))
+        }
+    })
+}
+
+/// Linear polar unwrap; `warp_polar` with the source size as output size
+pub fn linear_polar(src: &Mat, dst: &mut Mat, center: Point2f, max_radius: f64) -> Result<()> {
+    warp_polar(src, dst, src.size(), center, max_radius, WarpPolarMode::Linear, false)
+}
+
+/// Log-polar unwrap; `warp_polar` with the source size as output size
+pub fn log_polar(src: &Mat, dst: &mut Mat, center: Point2f, max_radius: f64) -> Result<()> {
+    warp_polar(src, dst, src.size(), center, max_radius, WarpPolarMode::Log, false)
+}
+
 /// Rotate image by 90, 180, or 270 degrees
 /// Rotate image with GPU acceleration (async for WASM)
 pub async fn rotate_async(
@@ -440,7 +756,7 @@ pub async fn rotate_async(
 pub fn rotate(src: &Mat, dst: &mut Mat, rotate_code: RotateCode) -> Result<()> {
     match rotate_code {
         RotateCode::Rotate90Clockwise => {
-            *dst = Mat::new(src.cols(), src.rows(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.cols(), src.rows(), src.channels(), src.depth())?.with_color_order(src.color_order());
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
                     let dst_row = col;
@@ -452,7 +768,7 @@ pub fn rotate(src: &Mat, dst: &mut Mat, rotate_code: RotateCode) -> Result<()> {
             }
         }
         RotateCode::Rotate180 => {
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
                     let dst_row = src.rows() - 1 - row;
@@ -464,7 +780,7 @@ pub fn rotate(src: &Mat, dst: &mut Mat, rotate_code: RotateCode) -> Result<()> {
             }
         }
         RotateCode::Rotate90CounterClockwise => {
-            *dst = Mat::new(src.cols(), src.rows(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.cols(), src.rows(), src.channels(), src.depth())?.with_color_order(src.color_order());
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
                     let dst_row = src.cols() - 1 - col;
@@ -480,6 +796,64 @@ pub fn rotate(src: &Mat, dst: &mut Mat, rotate_code: RotateCode) -> Result<()> {
     Ok(())
 }
 
+/// Remove the horizontal shear of a glyph or text block using its
+/// second-order intensity moments, returning the shear that was removed
+///
+/// The skew is `mu11 / mu02`, the slope of the ink's principal axis
+/// against the vertical; `dst` is `src` sheared back along x about the
+/// centroid row with bilinear sampling, so upright strokes stay put and
+/// leaning ones are straightened. Expects a single-channel image with
+/// bright ink on a dark background, as produced by an inverted
+/// [`threshold`](super::threshold::threshold). Images with (almost) no
+/// vertical spread are copied unchanged and report a skew of 0.
+///
+/// For rotating whole pages of text back to level, see
+/// [`text::deskew`](crate::text::deskew) instead.
+pub fn deskew_moments(src: &Mat, dst: &mut Mat) -> Result<f64> {
+    if src.channels() != 1 {
+        return Err(Error::InvalidParameter(
+            "deskew_moments requires a single-channel image".to_string(),
+        ));
+    }
+
+    let depth = src.depth();
+    let (mut m00, mut m01, mut m10, mut m11, mut m02) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (row, line) in src.data().chunks_exact(src.cols() * depth.size()).enumerate() {
+        let y = row as f64;
+        for (col, bytes) in line.chunks_exact(depth.size()).enumerate() {
+            let v = read_element(bytes, depth);
+            let x = col as f64;
+            m00 += v;
+            m10 += v * x;
+            m01 += v * y;
+            m11 += v * x * y;
+            m02 += v * y * y;
+        }
+    }
+
+    if m00 <= 0.0 {
+        *dst = src.clone_mat();
+        return Ok(0.0);
+    }
+    let (cx, cy) = (m10 / m00, m01 / m00);
+    let mu11 = m11 / m00 - cx * cy;
+    let mu02 = m02 / m00 - cy * cy;
+    if mu02 < 1e-2 {
+        *dst = src.clone_mat();
+        return Ok(0.0);
+    }
+
+    let skew = mu11 / mu02;
+    let shear = [[1.0, skew, -skew * cy], [0.0, 1.0, 0.0]];
+    let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Constant);
+    let dsize = Size::new(
+        i32::try_from(src.cols()).unwrap_or(i32::MAX),
+        i32::try_from(src.rows()).unwrap_or(i32::MAX),
+    );
+    warp_affine_with_sampler(src, dst, &shear, dsize, &sampler)?;
+    Ok(skew)
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum RotateCode {
     Rotate90Clockwise,
@@ -503,6 +877,102 @@ mod tests {
         assert_eq!(dst.cols(), 50);
     }
 
+    #[test]
+    fn test_resize_area_averages_blocks() {
+        let mut src = Mat::new(4, 4, 1, MatDepth::U8).unwrap();
+        for r in 0..4 {
+            for c in 0..4 {
+                src.at_mut(r, c).unwrap()[0] = if (r + c) % 2 == 0 { 200 } else { 0 };
+            }
+        }
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        resize(&src, &mut dst, Size::new(2, 2), InterpolationFlag::Area).unwrap();
+
+        // A checkerboard must average out rather than alias to one phase
+        for r in 0..2 {
+            for c in 0..2 {
+                assert_eq!(dst.at(r, c).unwrap()[0], 100);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resize_area_fractional_scale() {
+        let mut src = Mat::new(1, 3, 1, MatDepth::U8).unwrap();
+        src.data_mut().copy_from_slice(&[0, 90, 180]);
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        resize(&src, &mut dst, Size::new(2, 1), InterpolationFlag::Area).unwrap();
+
+        // Each output covers 1.5 source pixels: (0 + 0.5*90)/1.5 and (0.5*90 + 180)/1.5
+        assert_eq!(dst.at(0, 0).unwrap()[0], 30);
+        assert_eq!(dst.at(0, 1).unwrap()[0], 150);
+    }
+
+    #[test]
+    fn test_resize_kernels_preserve_constant() {
+        let src = Mat::new_with_default(17, 23, 3, MatDepth::U8, Scalar::all(77.0)).unwrap();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        for interp in [InterpolationFlag::Area, InterpolationFlag::Cubic, InterpolationFlag::Lanczos4] {
+            for dsize in [Size::new(40, 31), Size::new(9, 6)] {
+                resize(&src, &mut dst, dsize, interp).unwrap();
+                assert_eq!(dst.rows(), dsize.height as usize);
+                assert_eq!(dst.cols(), dsize.width as usize);
+                assert_eq!(dst.channels(), 3);
+                assert!(dst.data().iter().all(|&v| v == 77), "{interp:?} at {dsize:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_resize_cubic_lanczos_identity() {
+        let mut src = Mat::new(8, 8, 1, MatDepth::U8).unwrap();
+        for (i, v) in src.data_mut().iter_mut().enumerate() {
+            *v = (i * 37 % 251) as u8;
+        }
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        for interp in [InterpolationFlag::Cubic, InterpolationFlag::Lanczos4] {
+            resize(&src, &mut dst, Size::new(8, 8), interp).unwrap();
+            assert_eq!(dst.data(), src.data(), "{interp:?}");
+        }
+    }
+
+    #[test]
+    fn test_resize_linear_matches_area_upscale() {
+        let mut src = Mat::new(7, 9, 1, MatDepth::U8).unwrap();
+        for (i, v) in src.data_mut().iter_mut().enumerate() {
+            *v = (i * 53 % 241) as u8;
+        }
+
+        // Area degenerates to a linear kernel when enlarging, so both modes
+        // must place the source pixels at the same positions
+        let mut linear = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let mut area = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        resize(&src, &mut linear, Size::new(20, 17), InterpolationFlag::Linear).unwrap();
+        resize(&src, &mut area, Size::new(20, 17), InterpolationFlag::Area).unwrap();
+        for (&a, &b) in linear.data().iter().zip(area.data()) {
+            assert!(a.abs_diff(b) <= 1, "{a} vs {b}");
+        }
+    }
+
+    #[test]
+    fn test_resize_cubic_upscale_is_smooth() {
+        let mut src = Mat::new(1, 4, 1, MatDepth::U8).unwrap();
+        src.data_mut().copy_from_slice(&[0, 0, 255, 255]);
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        resize(&src, &mut dst, Size::new(16, 1), InterpolationFlag::Cubic).unwrap();
+
+        let row = dst.data();
+        assert_eq!(row[0], 0);
+        assert_eq!(row[15], 255);
+        // The edge is centred between source pixels 1 and 2
+        assert!(row[7] < 128 && row[8] > 128);
+    }
+
     #[test]
     fn test_flip() {
         let mut src = Mat::new(10, 10, 3, MatDepth::U8).unwrap();
@@ -530,6 +1000,17 @@ mod tests {
         assert_eq!(dst.cols(), 100);
     }
 
+    #[test]
+    fn test_warp_affine_nearest_rounds() {
+        let mut src = Mat::new(1, 4, 1, MatDepth::U8).unwrap();
+        src.data_mut().copy_from_slice(&[0, 10, 20, 30]);
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        // Source x = dst x + 0.6 rounds up; truncation would read one pixel left
+        warp_affine(&src, &mut dst, &[[1.0, 0.0, 0.6], [0.0, 1.0, 0.0]], Size::new(4, 1)).unwrap();
+        assert_eq!(dst.data(), &[10, 20, 30, 0]);
+    }
+
     #[test]
     fn test_get_rotation_matrix_2d() {
         let center = Point2f::new(50.0, 50.0);
@@ -539,6 +1020,76 @@ mod tests {
         assert!(m[0][0].abs() > 0.0);
     }
 
+    #[test]
+    fn test_rotation_bounding_size() {
+        assert_eq!(rotation_bounding_size(Size::new(100, 50), 90.0, 1.0), Size::new(50, 100));
+        assert_eq!(rotation_bounding_size(Size::new(100, 100), 0.0, 1.0), Size::new(100, 100));
+        // 45 degrees: 100 * sqrt(2) = 141.42
+        assert_eq!(rotation_bounding_size(Size::new(100, 100), 45.0, 1.0), Size::new(142, 142));
+    }
+
+    #[test]
+    fn test_invert_affine_transform() {
+        let m = get_rotation_matrix_2d(Point2f::new(10.0, 20.0), 30.0, 2.0);
+        let inv = invert_affine_transform(&m);
+        let (x, y) = (3.0, 7.0);
+        let u = m[0][0] * x + m[0][1] * y + m[0][2];
+        let v = m[1][0] * x + m[1][1] * y + m[1][2];
+        let bx = inv[0][0] * u + inv[0][1] * v + inv[0][2];
+        let by = inv[1][0] * u + inv[1][1] * v + inv[1][2];
+        assert!((bx - x).abs() < 1e-9 && (by - y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotate_bound_keeps_corners() {
+        let src = Mat::new_with_default(40, 60, 1, MatDepth::U8, Scalar::all(255.0)).unwrap();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        rotate_bound(&src, &mut dst, 30.0, 1.0).unwrap();
+
+        let expected = rotation_bounding_size(src.size(), 30.0, 1.0);
+        assert_eq!(dst.size(), expected);
+
+        // Every source pixel lands somewhere, so the white area is preserved
+        let white = dst.data().iter().filter(|&&v| v == 255).count();
+        assert!((white as f64 - 2400.0).abs() < 2400.0 * 0.05, "white area {white}");
+    }
+
+    #[test]
+    fn test_warp_polar_round_trip() {
+        let mut src = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 0..64 {
+                let r = ((row as f64 - 32.0).powi(2) + (col as f64 - 32.0).powi(2)).sqrt();
+                src.at_mut(row, col).unwrap()[0] = if r < 16.0 { 200 } else { 50 };
+            }
+        }
+
+        let center = Point2f::new(32.0, 32.0);
+        let mut polar = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        warp_polar(&src, &mut polar, Size::new(32, 90), center, 32.0, WarpPolarMode::Linear, false).unwrap();
+        assert_eq!((polar.rows(), polar.cols()), (90, 32));
+
+        // A disc becomes a vertical band in polar space
+        for row in 0..90 {
+            assert_eq!(polar.at(row, 5).unwrap()[0], 200);
+            assert_eq!(polar.at(row, 25).unwrap()[0], 50);
+        }
+
+        let mut back = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        warp_polar(&polar, &mut back, Size::new(64, 64), center, 32.0, WarpPolarMode::Linear, true).unwrap();
+        assert_eq!(back.at(32, 40).unwrap()[0], 200);
+        assert_eq!(back.at(32, 60).unwrap()[0], 50);
+    }
+
+    #[test]
+    fn test_log_polar_defaults() {
+        let src = Mat::new_with_default(32, 32, 3, MatDepth::U8, Scalar::all(10.0)).unwrap();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        log_polar(&src, &mut dst, Point2f::new(16.0, 16.0), 16.0).unwrap();
+        assert_eq!(dst.size(), src.size());
+        assert!(warp_polar(&src, &mut dst, Size::new(0, 0), Point2f::new(16.0, 16.0), 1.0, WarpPolarMode::Log, false).is_err());
+    }
+
     #[test]
     fn test_rotate() {
         let src = Mat::new_with_default(50, 100, 3, MatDepth::U8, Scalar::all(128.0)).unwrap();
@@ -549,4 +1100,37 @@ mod tests {
         assert_eq!(dst.rows(), 100);
         assert_eq!(dst.cols(), 50);
     }
+
+    /// Slanted bar of ink: x = 10 + slope * y for each row
+    fn slanted_bar(slope: f64) -> Mat {
+        let mut img = Mat::new(40, 40, 1, MatDepth::U8).unwrap();
+        for row in 5..35 {
+            let center = 20.0 + slope * (row as f64 - 20.0);
+            for col in 0..40 {
+                if (col as f64 - center).abs() <= 2.0 {
+                    img.at_mut(row, col).unwrap()[0] = 255;
+                }
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_deskew_moments() {
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let skew = deskew_moments(&slanted_bar(0.3), &mut dst).unwrap();
+        assert!((skew - 0.3).abs() < 0.02, "skew {skew}");
+
+        // The straightened bar is upright and centred where the slanted one was
+        assert!(deskew_moments(&dst.clone_mat(), &mut Mat::new(1, 1, 1, MatDepth::U8).unwrap()).unwrap().abs() < 0.02);
+        for row in [6, 20, 33] {
+            assert!(dst.at(row, 20).unwrap()[0] > 200, "row {row}");
+            assert!(dst.at(row, 25).unwrap()[0] < 50, "row {row}");
+        }
+
+        let blank = Mat::new(10, 10, 1, MatDepth::U8).unwrap();
+        assert_eq!(deskew_moments(&blank, &mut dst).unwrap(), 0.0);
+        assert_eq!(dst.data(), blank.data());
+        assert!(deskew_moments(&Mat::new(4, 4, 3, MatDepth::U8).unwrap(), &mut dst).is_err());
+    }
 }
@@ -124,6 +124,183 @@ pub fn equalize_hist(src: &Mat, dst: &mut Mat) -> Result<()> {
     Ok(())
 }
 
+/// Stretch each channel's contrast to the full range ("auto levels")
+///
+/// Per channel, the darkest and brightest `clip_percent` % of pixels are
+/// saturated and the values between those percentiles are mapped linearly
+/// onto the full range of the depth (0–255 for U8, 0–65535 for U16, 0–1 for
+/// float). A `clip_percent` of 0 stretches between the exact min and max.
+/// Constant channels are left unchanged, as is the alpha channel of
+/// 4-channel images.
+pub fn auto_contrast(src: &Mat, dst: &mut Mat, clip_percent: f64) -> Result<()> {
+    if !(0.0..50.0).contains(&clip_percent) {
+        return Err(Error::InvalidParameter(
+            "clip_percent must be in [0, 50)".to_string(),
+        ));
+    }
+
+    let full_range = match src.depth() {
+        MatDepth::U8 => 255.0,
+        MatDepth::U16 => 65535.0,
+        MatDepth::F32 | MatDepth::F64 => 1.0,
+        MatDepth::S32 => {
+            return Err(Error::UnsupportedOperation(
+                "auto_contrast does not support S32 depth".to_string(),
+            ))
+        }
+    };
+
+    let channels = src.channels();
+    let stretched = if channels == 4 { 3 } else { channels };
+    let total = src.rows() * src.cols();
+
+    let mut out = src.clone_mat();
+    if total == 0 {
+        *dst = out.with_color_order(src.color_order());
+        return Ok(());
+    }
+
+    // How many pixels to saturate at each end
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    let clipped = ((clip_percent / 100.0 * total as f64) as usize).min(total - 1);
+
+    let mut values = Vec::with_capacity(total);
+    for ch in 0..stretched {
+        values.clear();
+        for row in 0..src.rows() {
+            for col in 0..src.cols() {
+                values.push(read_channel(src, row, col, ch)?);
+            }
+        }
+
+        let low = *values.select_nth_unstable_by(clipped, f64::total_cmp).1;
+        let high = *values.select_nth_unstable_by(total - 1 - clipped, f64::total_cmp).1;
+        if high <= low {
+            continue;
+        }
+
+        let scale = full_range / (high - low);
+        for row in 0..src.rows() {
+            for col in 0..src.cols() {
+                let v = ((read_channel(src, row, col, ch)? - low) * scale).clamp(0.0, full_range);
+                write_channel(&mut out, row, col, ch, v)?;
+            }
+        }
+    }
+
+    *dst = out.with_color_order(src.color_order());
+    Ok(())
+}
+
+fn read_channel(img: &Mat, row: usize, col: usize, ch: usize) -> Result<f64> {
+    match img.depth() {
+        MatDepth::U8 => Ok(f64::from(img.at(row, col)?[ch])),
+        MatDepth::U16 => Ok(f64::from(img.at_u16(row, col, ch)?)),
+        MatDepth::S32 => Ok(f64::from(img.at_i32(row, col, ch)?)),
+        MatDepth::F32 => Ok(f64::from(img.at_f32(row, col, ch)?)),
+        MatDepth::F64 => img.at_f64(row, col, ch),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn write_channel(img: &mut Mat, row: usize, col: usize, ch: usize, v: f64) -> Result<()> {
+    match img.depth() {
+        MatDepth::U8 => img.at_mut(row, col)?[ch] = v.round() as u8,
+        MatDepth::U16 => img.set_u16(row, col, ch, v.round() as u16)?,
+        MatDepth::S32 => img.set_i32(row, col, ch, v.round() as i32)?,
+        MatDepth::F32 => img.set_f32(row, col, ch, v as f32)?,
+        MatDepth::F64 => img.set_f64(row, col, ch, v)?,
+    }
+    Ok(())
+}
+
+/// Exposure statistics of an image, from [`analyze_exposure`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExposureStats {
+    /// 256-bin luminance histogram
+    pub histogram: Vec<f32>,
+    /// Fraction of pixels crushed to black (luminance <= 2)
+    pub shadow_clip: f64,
+    /// Fraction of pixels blown to white (luminance >= 253)
+    pub highlight_clip: f64,
+    /// Median luminance, 0–255
+    pub median_luminance: f64,
+    /// Mean luminance, 0–255
+    pub mean_luminance: f64,
+    /// Exposure change in stops that brings the median to middle gray;
+    /// positive means brighten
+    pub suggested_ev: f64,
+}
+
+impl ExposureStats {
+    /// Linear gain equivalent to [`suggested_ev`](Self::suggested_ev)
+    #[must_use]
+    pub fn suggested_gain(&self) -> f64 {
+        self.suggested_ev.exp2()
+    }
+}
+
+/// sRGB value of 18 % reflectance middle gray
+const MIDDLE_GRAY: f64 = 118.0;
+/// Clipped fraction above which the EV suggestion protects that end
+const MAX_CLIP: f64 = 0.01;
+
+/// Measure exposure from the luminance histogram of a U8 image
+///
+/// Color images are converted with [`to_gray`](crate::imgproc::to_gray).
+/// The EV suggestion compares the median with middle gray in linear light;
+/// when more than 1 % of pixels are blown it is at most -1/3 EV, and when
+/// more than 1 % are crushed (but highlights are fine) it is never negative,
+/// so a control loop backs off clipped ends before chasing the median.
+pub fn analyze_exposure(src: &Mat) -> Result<ExposureStats> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "analyze_exposure only supports U8 depth".to_string(),
+        ));
+    }
+    if src.rows() == 0 || src.cols() == 0 {
+        return Err(Error::InvalidDimensions(
+            "analyze_exposure needs a non-empty image".to_string(),
+        ));
+    }
+
+    let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+    crate::imgproc::to_gray(src, &mut gray)?;
+    let histogram = calc_hist(&gray, 256, (0.0, 256.0))?;
+
+    #[allow(clippy::cast_precision_loss)]
+    let total = (gray.rows() * gray.cols()) as f64;
+    let fraction = |bins: &[f32]| bins.iter().map(|&c| f64::from(c)).sum::<f64>() / total;
+    let shadow_clip = fraction(&histogram[..=2]);
+    let highlight_clip = fraction(&histogram[253..]);
+    let mean_luminance = histogram.iter().enumerate().map(|(v, &c)| v as f64 * f64::from(c)).sum::<f64>() / total;
+
+    let mut seen = 0.0;
+    let mut median = 255;
+    for (v, &count) in histogram.iter().enumerate() {
+        seen += f64::from(count);
+        if seen >= total / 2.0 {
+            median = v;
+            break;
+        }
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let median_luminance = median as f64;
+
+    let linear = |v: f64| {
+        let c = v / 255.0;
+        if c <= 0.040_45 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    let mut suggested_ev = (linear(MIDDLE_GRAY) / linear(median_luminance.max(1.0))).log2();
+    if highlight_clip > MAX_CLIP {
+        suggested_ev = suggested_ev.min(-1.0 / 3.0);
+    } else if shadow_clip > MAX_CLIP {
+        suggested_ev = suggested_ev.max(0.0);
+    }
+
+    Ok(ExposureStats { histogram, shadow_clip, highlight_clip, median_luminance, mean_luminance, suggested_ev })
+}
+
 /// Compare two histograms using different methods
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HistCompMethod {
@@ -263,6 +440,160 @@ pub fn calc_back_project(
     Ok(())
 }
 
+/// Histogram over one or more channels of a U8 image
+///
+/// Bins are stored row-major with the last dimension varying fastest, so a
+/// 2-D hue/saturation histogram is `bins[h * sizes[1] + s]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramNd {
+    /// Image channel feeding each dimension
+    pub channels: Vec<usize>,
+    /// Number of bins per dimension
+    pub sizes: Vec<usize>,
+    /// Half-open value range `[min, max)` per dimension
+    pub ranges: Vec<(f32, f32)>,
+    pub bins: Vec<f32>,
+}
+
+impl HistogramNd {
+    /// Value of the bin at a multi-dimensional index
+    #[must_use]
+    pub fn get(&self, index: &[usize]) -> f32 {
+        let flat = index
+            .iter()
+            .zip(&self.sizes)
+            .fold(0, |acc, (&i, &size)| acc * size + i);
+        self.bins[flat]
+    }
+
+    /// Scale the bins so that the largest one equals `max_value`
+    pub fn normalize(&mut self, max_value: f32) {
+        let max = self.bins.iter().copied().fold(0.0f32, f32::max);
+        if max > 0.0 {
+            for bin in &mut self.bins {
+                *bin *= max_value / max;
+            }
+        }
+    }
+
+    /// Flat bin index of a pixel, `None` if any value falls outside its range
+    fn bin_index(&self, pixel: &[u8]) -> Option<usize> {
+        let mut flat = 0;
+        for ((&ch, &size), &(min_val, max_val)) in self.channels.iter().zip(&self.sizes).zip(&self.ranges) {
+            let val = f32::from(pixel[ch]);
+            if val < min_val || val >= max_val {
+                return None;
+            }
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let bin = ((val - min_val) / (max_val - min_val) * size as f32) as usize;
+            flat = flat * size + bin.min(size - 1);
+        }
+        Some(flat)
+    }
+}
+
+/// Calculate a histogram over several channels of a U8 image
+///
+/// `channels`, `hist_size` and `ranges` give one entry per dimension; a hue/
+/// saturation histogram of an HSV image is `&[0, 1]`, `&[30, 32]`,
+/// `&[(0.0, 180.0), (0.0, 256.0)]`. Only pixels where `mask` is non-zero are
+/// counted.
+pub fn calc_hist_nd(
+    image: &Mat,
+    channels: &[usize],
+    hist_size: &[usize],
+    ranges: &[(f32, f32)],
+    mask: Option<&Mat>,
+) -> Result<HistogramNd> {
+    if image.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "calc_hist_nd only supports U8 depth".to_string(),
+        ));
+    }
+
+    if channels.is_empty() || channels.len() != hist_size.len() || channels.len() != ranges.len() {
+        return Err(Error::InvalidParameter(
+            "channels, hist_size and ranges must be non-empty and of equal length".to_string(),
+        ));
+    }
+
+    if channels.iter().any(|&ch| ch >= image.channels())
+        || hist_size.contains(&0)
+        || ranges.iter().any(|&(min_val, max_val)| max_val <= min_val)
+    {
+        return Err(Error::InvalidParameter(
+            "Invalid histogram channel, size or range".to_string(),
+        ));
+    }
+
+    if let Some(mask) = mask {
+        if mask.rows() != image.rows() || mask.cols() != image.cols() || mask.channels() != 1 {
+            return Err(Error::InvalidDimensions(
+                "Mask must be single-channel and match the image size".to_string(),
+            ));
+        }
+    }
+
+    let mut hist = HistogramNd {
+        channels: channels.to_vec(),
+        sizes: hist_size.to_vec(),
+        ranges: ranges.to_vec(),
+        bins: vec![0.0; hist_size.iter().product()],
+    };
+
+    for row in 0..image.rows() {
+        for col in 0..image.cols() {
+            if let Some(mask) = mask {
+                if mask.at(row, col)?[0] == 0 {
+                    continue;
+                }
+            }
+
+            if let Some(bin) = hist.bin_index(image.at(row, col)?) {
+                hist.bins[bin] += 1.0;
+            }
+        }
+    }
+
+    Ok(hist)
+}
+
+/// Back-project a multi-channel histogram onto an image
+///
+/// Each pixel of the single-channel U8 output is its bin value scaled so the
+/// largest bin maps to 255; pixels outside the histogram ranges are 0.
+pub fn calc_back_project_nd(image: &Mat, hist: &HistogramNd, dst: &mut Mat) -> Result<()> {
+    if image.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "calc_back_project_nd only supports U8 depth".to_string(),
+        ));
+    }
+
+    if hist.channels.iter().any(|&ch| ch >= image.channels()) {
+        return Err(Error::InvalidParameter(
+            "Histogram refers to a channel the image does not have".to_string(),
+        ));
+    }
+
+    let max_hist = hist.bins.iter().copied().fold(0.0f32, f32::max);
+    let mut out = Mat::new(image.rows(), image.cols(), 1, MatDepth::U8)?;
+
+    if max_hist > 0.0 {
+        for row in 0..image.rows() {
+            for col in 0..image.cols() {
+                if let Some(bin) = hist.bin_index(image.at(row, col)?) {
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let val = (hist.bins[bin] / max_hist * 255.0).round() as u8;
+                    out.at_mut(row, col)?[0] = val;
+                }
+            }
+        }
+    }
+
+    *dst = out;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,6 +617,61 @@ mod tests {
         assert_eq!(dst.rows(), src.rows());
     }
 
+    #[test]
+    fn test_auto_contrast() {
+        // Channel 0 spans 100..=199, channel 1 is constant, 1 % outliers in 0
+        let mut src = Mat::new(10, 100, 2, MatDepth::U8).unwrap();
+        for row in 0..10 {
+            for col in 0..100 {
+                let px = src.at_mut(row, col).unwrap();
+                px[0] = if row == 0 && col < 10 { 255 } else { 100 + col as u8 };
+                px[1] = 42;
+            }
+        }
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        auto_contrast(&src, &mut dst, 0.0).unwrap();
+        assert_eq!(dst.at(5, 0).unwrap(), &[0, 42]);
+        assert_eq!(dst.at(0, 0).unwrap()[0], 255);
+        assert_eq!(dst.at(5, 99).unwrap()[0], 163);
+
+        // Clipping 1 % ignores the outliers and stretches the real range
+        auto_contrast(&src, &mut dst, 1.0).unwrap();
+        assert_eq!(dst.at(5, 0).unwrap()[0], 0);
+        assert_eq!(dst.at(5, 99).unwrap()[0], 255);
+        assert_eq!(dst.at(5, 2).unwrap()[0], 3);
+
+        assert!(auto_contrast(&src, &mut dst, 50.0).is_err());
+    }
+
+    #[test]
+    fn test_analyze_exposure() {
+        // Middle gray needs no correction
+        let gray = Mat::new_with_default(20, 20, 1, MatDepth::U8, Scalar::all(118.0)).unwrap();
+        let stats = analyze_exposure(&gray).unwrap();
+        assert_eq!(stats.median_luminance, 118.0);
+        assert!(stats.suggested_ev.abs() < 1e-9);
+        assert!((stats.suggested_gain() - 1.0).abs() < 1e-9);
+
+        // Dark frame: linear 0.18 / 0.045 is two stops
+        let dark = Mat::new_with_default(20, 20, 3, MatDepth::U8, Scalar::all(60.0)).unwrap();
+        let stats = analyze_exposure(&dark).unwrap();
+        assert!((stats.suggested_ev - 1.96).abs() < 0.05, "{}", stats.suggested_ev);
+        assert_eq!((stats.shadow_clip, stats.highlight_clip), (0.0, 0.0));
+
+        // A dark median with a blown window still backs off
+        let mut window = Mat::new_with_default(20, 20, 1, MatDepth::U8, Scalar::all(60.0)).unwrap();
+        for col in 0..20 {
+            window.at_mut(0, col).unwrap()[0] = 255;
+        }
+        let stats = analyze_exposure(&window).unwrap();
+        assert!((stats.highlight_clip - 0.05).abs() < 1e-9);
+        assert!((stats.mean_luminance - 69.75).abs() < 1e-9);
+        assert!((stats.suggested_ev + 1.0 / 3.0).abs() < 1e-9);
+
+        assert!(analyze_exposure(&Mat::new(4, 4, 1, MatDepth::F32).unwrap()).is_err());
+    }
+
     #[test]
     fn test_compare_hist() {
         let h1 = vec![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -294,4 +680,35 @@ mod tests {
         let corr = compare_hist(&h1, &h2, HistCompMethod::Correlation).unwrap();
         assert!((corr - 1.0).abs() < 0.01); // Should be perfectly correlated
     }
+
+    #[test]
+    fn test_calc_hist_nd_and_back_project() {
+        // Left half (10, 200), right half (100, 50)
+        let mut img = Mat::new(10, 20, 3, MatDepth::U8).unwrap();
+        for row in 0..10 {
+            for col in 0..20 {
+                let px = if col < 10 { [10, 200, 0] } else { [100, 50, 0] };
+                img.at_mut(row, col).unwrap().copy_from_slice(&px);
+            }
+        }
+
+        let mut mask = Mat::new(10, 20, 1, MatDepth::U8).unwrap();
+        for row in 0..10 {
+            for col in 0..5 {
+                mask.at_mut(row, col).unwrap()[0] = 255;
+            }
+        }
+
+        let hist = calc_hist_nd(&img, &[0, 1], &[18, 8], &[(0.0, 180.0), (0.0, 256.0)], Some(&mask)).unwrap();
+        assert_eq!(hist.bins.len(), 18 * 8);
+        assert_eq!(hist.get(&[1, 6]), 50.0);
+        assert_eq!(hist.bins.iter().sum::<f32>(), 50.0);
+
+        let mut back = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        calc_back_project_nd(&img, &hist, &mut back).unwrap();
+        assert_eq!(back.at(3, 2).unwrap()[0], 255);
+        assert_eq!(back.at(3, 15).unwrap()[0], 0);
+
+        assert!(calc_hist_nd(&img, &[0, 3], &[8, 8], &[(0.0, 256.0), (0.0, 256.0)], None).is_err());
+    }
 }
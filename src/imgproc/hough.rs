@@ -1,4 +1,5 @@
 use crate::core::{Mat, MatDepth};
+use crate::core::parallel::{parallel_fold_rows, parallel_map};
 use crate::core::types::Point;
 use crate::error::{Error, Result};
 use std::f64::consts::PI;
@@ -30,42 +31,46 @@ pub fn hough_lines(
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     let num_theta = (PI / theta) as usize;
 
-    // Initialize accumulator
-    let mut accumulator = vec![vec![0i32; num_theta]; num_rho];
-
-    // Vote in Hough space
-    for row in 0..image.rows() {
-        for col in 0..image.cols() {
-            let pixel = image.at(row, col)?;
-
-            if pixel[0] > 128 {
-                // Edge pixel
-                #[allow(clippy::cast_precision_loss)]
-                let x = col as f64;
-                #[allow(clippy::cast_precision_loss)]
-                let y = row as f64;
-
-                for t_idx in 0..num_theta {
+    #[allow(clippy::cast_precision_loss)]
+    let trig: Vec<(f64, f64)> = (0..num_theta).map(|t| (t as f64 * theta).sin_cos()).collect();
+
+    // Vote in Hough space, one flat rho-major accumulator per worker
+    let accumulator = parallel_fold_rows(
+        image.rows(),
+        || vec![0i32; num_rho * num_theta],
+        |votes, row| {
+            for col in 0..image.cols() {
+                if image.at(row, col)?[0] > 128 {
+                    // Edge pixel
                     #[allow(clippy::cast_precision_loss)]
-                    let angle = t_idx as f64 * theta;
-                    let r = x * angle.cos() + y * angle.sin();
-                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                    let r_idx = ((r + max_rho) / rho) as usize;
-
-                    if r_idx < num_rho {
-                        accumulator[r_idx][t_idx] += 1;
+                    let (x, y) = (col as f64, row as f64);
+                    for (t_idx, &(sin, cos)) in trig.iter().enumerate() {
+                        let r = x * cos + y * sin;
+                        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                        let r_idx = ((r + max_rho) / rho) as usize;
+
+                        if r_idx < num_rho {
+                            votes[r_idx * num_theta + t_idx] += 1;
+                        }
                     }
                 }
             }
-        }
-    }
+            Ok(())
+        },
+        |mut a, b| {
+            for (acc, vote) in a.iter_mut().zip(b) {
+                *acc += vote;
+            }
+            a
+        },
+    )?;
 
     // Find peaks in accumulator
     let mut lines = Vec::new();
 
     for r_idx in 0..num_rho {
         for t_idx in 0..num_theta {
-            if accumulator[r_idx][t_idx] >= threshold {
+            if accumulator[r_idx * num_theta + t_idx] >= threshold {
                 #[allow(clippy::cast_precision_loss)]
                 let r = r_idx as f64 * rho - max_rho;
                 #[allow(clippy::cast_precision_loss)]
@@ -180,13 +185,14 @@ pub fn hough_circles(
     let mut edges = Mat::new(1, 1, 1, MatDepth::U8)?;
     canny(image, &mut edges, param1, param1 * 2.0)?;
 
-    // Find circles
-    let mut circles = Vec::new();
-
-    // Simple circle detection using edge gradients
+    // Simple circle detection using edge gradients, one candidate centre row
+    // per task
     #[allow(clippy::cast_sign_loss)]
     let min_radius_usize = min_radius as usize;
-    for row in min_radius_usize..(image.rows() - min_radius_usize) {
+    let center_rows = image.rows().saturating_sub(2 * min_radius_usize);
+    let row_circles = parallel_map(center_rows, |i| {
+        let row = min_radius_usize + i;
+        let mut circles = Vec::new();
         for col in min_radius_usize..(image.cols() - min_radius_usize) {
             for r in min_radius..=max_radius {
                 let mut votes = 0;
@@ -235,7 +241,9 @@ pub fn hough_circles(
                 }
             }
         }
-    }
+        Ok(circles)
+    })?;
+    let mut circles: Vec<Circle> = row_circles.into_iter().flatten().collect();
 
     // Non-maximum suppression
     let mut filtered_circles: Vec<Circle> = Vec::new();
@@ -0,0 +1,248 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Point;
+use crate::error::{Error, Result};
+
+/// A pixel visited by a [`LineIterator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinePixel<'a> {
+    pub pos: Point,
+    /// Raw bytes of the pixel, as returned by `Mat::at`
+    pub value: &'a [u8],
+}
+
+/// Walks the pixels of a digital line segment
+///
+/// The segment from `p1` to `p2` is clipped to the image first, so every
+/// yielded position is valid. With `connectivity` 8 consecutive pixels may
+/// touch diagonally (classic Bresenham, `max(|dx|, |dy|) + 1` pixels); with 4
+/// they always share an edge (`|dx| + |dy| + 1` pixels). Pixels are visited
+/// from `p1` towards `p2`.
+pub struct LineIterator<'a> {
+    img: &'a Mat,
+    pos: Point,
+    step_x: i32,
+    step_y: i32,
+    dx: i32,
+    dy: i32,
+    err: i32,
+    four_connected: bool,
+    remaining: usize,
+}
+
+impl<'a> LineIterator<'a> {
+    pub fn new(img: &'a Mat, p1: Point, p2: Point, connectivity: u8) -> Result<Self> {
+        if connectivity != 4 && connectivity != 8 {
+            return Err(Error::InvalidParameter(
+                "connectivity must be 4 or 8".to_string(),
+            ));
+        }
+
+        let clipped = clip_line(img.cols() as i32, img.rows() as i32, p1, p2);
+        let (start, end) = clipped.unwrap_or((p1, p1));
+
+        let dx = (end.x - start.x).abs();
+        let dy = -(end.y - start.y).abs();
+        let four_connected = connectivity == 4;
+        let remaining = match clipped {
+            None => 0,
+            Some(_) if four_connected => (dx - dy) as usize + 1,
+            Some(_) => dx.max(-dy) as usize + 1,
+        };
+
+        Ok(Self {
+            img,
+            pos: start,
+            step_x: if start.x < end.x { 1 } else { -1 },
+            step_y: if start.y < end.y { 1 } else { -1 },
+            dx,
+            dy,
+            err: dx + dy,
+            four_connected,
+            remaining,
+        })
+    }
+
+    /// Number of pixels left to visit
+    #[must_use]
+    pub fn count_remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn advance(&mut self) {
+        if self.four_connected {
+            // Take whichever single step keeps closer to the ideal line
+            if 2 * self.err - self.dy > self.dx {
+                self.err += self.dy;
+                self.pos.x += self.step_x;
+            } else {
+                self.err += self.dx;
+                self.pos.y += self.step_y;
+            }
+        } else {
+            let e2 = 2 * self.err;
+            if e2 >= self.dy {
+                self.err += self.dy;
+                self.pos.x += self.step_x;
+            }
+            if e2 <= self.dx {
+                self.err += self.dx;
+                self.pos.y += self.step_y;
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for LineIterator<'a> {
+    type Item = LinePixel<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let pos = self.pos;
+        let value = self.img.at(pos.y as usize, pos.x as usize).ok()?;
+
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.advance();
+        }
+
+        Some(LinePixel { pos, value })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for LineIterator<'_> {}
+
+/// Intensity profile of one channel along a segment
+///
+/// Samples every 8-connected pixel from `p1` to `p2` (clipped to the image)
+/// and returns the channel values as `f64`, for any depth.
+pub fn line_profile(img: &Mat, p1: Point, p2: Point, channel: usize) -> Result<Vec<f64>> {
+    if channel >= img.channels() {
+        return Err(Error::InvalidParameter(
+            format!("Channel {channel} out of range for {}-channel image", img.channels()),
+        ));
+    }
+
+    LineIterator::new(img, p1, p2, 8)?
+        .map(|px| pixel_value(img, &px, channel))
+        .collect()
+}
+
+/// Value of `channel` at a visited pixel, for any depth
+pub(crate) fn pixel_value(img: &Mat, px: &LinePixel<'_>, channel: usize) -> Result<f64> {
+    let (row, col) = (px.pos.y as usize, px.pos.x as usize);
+    Ok(match img.depth() {
+        MatDepth::U8 => f64::from(px.value[channel]),
+        MatDepth::U16 => f64::from(img.at_u16(row, col, channel)?),
+        MatDepth::S32 => f64::from(img.at_i32(row, col, channel)?),
+        MatDepth::F32 => f64::from(img.at_f32(row, col, channel)?),
+        MatDepth::F64 => img.at_f64(row, col, channel)?,
+    })
+}
+
+/// Clip a segment to `[0, width) × [0, height)` (Liang-Barsky), rounding the
+/// new endpoints to pixels. `None` if the segment misses the image.
+fn clip_line(width: i32, height: i32, p1: Point, p2: Point) -> Option<(Point, Point)> {
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    let (x0, y0) = (f64::from(p1.x), f64::from(p1.y));
+    let (dx, dy) = (f64::from(p2.x - p1.x), f64::from(p2.y - p1.y));
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+
+    for (p, q) in [
+        (-dx, x0),
+        (dx, f64::from(width - 1) - x0),
+        (-dy, y0),
+        (dy, f64::from(height - 1) - y0),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    let at = |t: f64| Point::new((x0 + t * dx).round() as i32, (y0 + t * dy).round() as i32);
+    Some((at(t0), at(t1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Scalar;
+
+    #[test]
+    fn test_line_iterator_connectivity() {
+        let img = Mat::new_with_default(20, 20, 1, MatDepth::U8, Scalar::all(7.0)).unwrap();
+
+        let eight: Vec<Point> = LineIterator::new(&img, Point::new(2, 3), Point::new(10, 6), 8)
+            .unwrap()
+            .map(|px| px.pos)
+            .collect();
+        assert_eq!(eight.len(), 9);
+        assert_eq!(eight[0], Point::new(2, 3));
+        assert_eq!(*eight.last().unwrap(), Point::new(10, 6));
+
+        let four: Vec<Point> = LineIterator::new(&img, Point::new(2, 3), Point::new(10, 6), 4)
+            .unwrap()
+            .map(|px| px.pos)
+            .collect();
+        assert_eq!(four.len(), 8 + 3 + 1);
+        assert_eq!(*four.last().unwrap(), Point::new(10, 6));
+        assert!(four.windows(2).all(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs() == 1));
+
+        // Reversed direction visits the pixels from the other end
+        let back: Vec<Point> = LineIterator::new(&img, Point::new(10, 6), Point::new(2, 3), 8)
+            .unwrap()
+            .map(|px| px.pos)
+            .collect();
+        assert_eq!(back[0], Point::new(10, 6));
+        assert_eq!(back.len(), 9);
+
+        assert!(LineIterator::new(&img, Point::new(0, 0), Point::new(1, 1), 6).is_err());
+    }
+
+    #[test]
+    fn test_line_iterator_clips_to_image() {
+        let img = Mat::new_with_default(10, 10, 1, MatDepth::U8, Scalar::all(0.0)).unwrap();
+
+        let it = LineIterator::new(&img, Point::new(-5, 4), Point::new(20, 4), 8).unwrap();
+        assert_eq!(it.len(), 10);
+        assert!(it.map(|px| px.pos).eq((0..10).map(|x| Point::new(x, 4))));
+
+        let miss = LineIterator::new(&img, Point::new(-5, -5), Point::new(-1, 20), 8).unwrap();
+        assert_eq!(miss.count(), 0);
+    }
+
+    #[test]
+    fn test_line_profile() {
+        let mut img = Mat::new(5, 8, 1, MatDepth::F32).unwrap();
+        for col in 0..8 {
+            img.set_f32(2, col, 0, col as f32 * 0.5).unwrap();
+        }
+
+        let profile = line_profile(&img, Point::new(1, 2), Point::new(6, 2), 0).unwrap();
+        assert_eq!(profile, vec![0.5, 1.0, 1.5, 2.0, 2.5, 3.0]);
+        assert!(line_profile(&img, Point::new(1, 2), Point::new(6, 2), 1).is_err());
+    }
+}
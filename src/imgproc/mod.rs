@@ -11,6 +11,18 @@ pub mod hough;
 pub mod advanced_filter;
 pub mod gradient;
 pub mod integral;
+pub mod overlay;
+pub mod focus;
+pub mod pyramid;
+pub mod noise;
+pub mod line_iterator;
+pub mod tiled;
+pub mod sampling;
+pub mod subpixel;
+pub mod spherical;
+pub mod components;
+pub mod batch;
+pub mod smart_crop;
 
 pub use color::*;
 pub use filter::*;
@@ -23,3 +35,14 @@ pub use contours::*;
 pub use histogram::*;
 pub use hough::*;
 pub use advanced_filter::*;
+pub use overlay::*;
+pub use focus::*;
+pub use pyramid::*;
+pub use noise::*;
+pub use line_iterator::*;
+pub use tiled::*;
+pub use sampling::PixelSampler;
+pub use subpixel::*;
+pub use spherical::*;
+pub use components::*;
+pub use smart_crop::*;
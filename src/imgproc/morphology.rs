@@ -85,7 +85,7 @@ pub fn erode(src: &Mat, dst: &mut Mat, kernel: &[Vec<bool>]) -> Result<()> {
         return Err(Error::InvalidParameter("Kernel is empty".to_string()));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let k_height = kernel.len();
     let k_width = kernel[0].len();
@@ -144,7 +144,7 @@ pub fn dilate(src: &Mat, dst: &mut Mat, kernel: &[Vec<bool>]) -> Result<()> {
         return Err(Error::InvalidParameter("Kernel is empty".to_string()));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let k_height = kernel.len();
     let k_width = kernel[0].len();
@@ -220,7 +220,7 @@ pub fn morphology_ex(
             dilate(src, &mut dilated, kernel)?;
             erode(src, &mut eroded, kernel)?;
 
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
@@ -240,7 +240,7 @@ pub fn morphology_ex(
             let mut opened = Mat::new(1, 1, 1, MatDepth::U8)?;
             morphology_ex(src, &mut opened, MorphType::Open, kernel)?;
 
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
@@ -260,7 +260,7 @@ pub fn morphology_ex(
             let mut closed = Mat::new(1, 1, 1, MatDepth::U8)?;
             morphology_ex(src, &mut closed, MorphType::Close, kernel)?;
 
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
@@ -370,7 +370,7 @@ pub async fn morphology_ex_async(
             dilate_async(src, &mut dilated, kernel, use_gpu).await?;
             erode_async(src, &mut eroded, kernel, use_gpu).await?;
 
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
@@ -390,7 +390,7 @@ pub async fn morphology_ex_async(
             let mut opened = Mat::new(1, 1, 1, MatDepth::U8)?;
             Box::pin(morphology_ex_async(src, &mut opened, MorphType::Open, kernel, use_gpu)).await?;
 
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
@@ -410,7 +410,7 @@ pub async fn morphology_ex_async(
             let mut closed = Mat::new(1, 1, 1, MatDepth::U8)?;
             Box::pin(morphology_ex_async(src, &mut closed, MorphType::Close, kernel, use_gpu)).await?;
 
-            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+            *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
             for row in 0..src.rows() {
                 for col in 0..src.cols() {
@@ -0,0 +1,206 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::{Point, Rect, Scalar};
+use crate::error::{Error, Result};
+use crate::imgproc::drawing::{circle, circle_filled, fill_poly, line, polylines, put_text, rectangle};
+
+/// Transparent RGBA drawing layer composited over a base image
+///
+/// Drawing calls go to the layer instead of the image, so annotations such as
+/// tracking boxes can be redrawn every frame without destroying the pixels
+/// underneath. Shapes are drawn opaque on the layer; `alpha` controls the
+/// opacity of the whole layer when composited.
+pub struct Overlay {
+    layer: Mat,
+    alpha: f64,
+}
+
+impl Overlay {
+    /// Create an empty (fully transparent) overlay
+    pub fn new(rows: usize, cols: usize) -> Result<Self> {
+        Ok(Self {
+            layer: Mat::new(rows, cols, 4, MatDepth::U8)?,
+            alpha: 1.0,
+        })
+    }
+
+    /// Create an overlay matching the size and colour order of `base`
+    pub fn for_image(base: &Mat) -> Result<Self> {
+        let mut overlay = Self::new(base.rows(), base.cols())?;
+        overlay.layer.set_color_order(base.color_order());
+        Ok(overlay)
+    }
+
+    /// Set the global opacity used when compositing (0.0 - 1.0)
+    #[must_use]
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.set_alpha(alpha);
+        self
+    }
+
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// The underlying RGBA layer
+    #[must_use]
+    pub fn layer(&self) -> &Mat {
+        &self.layer
+    }
+
+    /// Mutable access to the RGBA layer for custom drawing.
+    ///
+    /// Channel 3 is per-pixel coverage; drawing functions take it from
+    /// `color.val[3]`, so use `Scalar::from_rgba` here.
+    pub fn layer_mut(&mut self) -> &mut Mat {
+        &mut self.layer
+    }
+
+    /// Erase everything drawn so far
+    pub fn clear(&mut self) {
+        self.layer.data_mut().fill(0);
+    }
+
+    pub fn line(&mut self, pt1: Point, pt2: Point, color: Scalar, thickness: i32) -> Result<()> {
+        line(&mut self.layer, pt1, pt2, opaque(color), thickness)
+    }
+
+    pub fn rectangle(&mut self, rect: Rect, color: Scalar, thickness: i32) -> Result<()> {
+        rectangle(&mut self.layer, rect, opaque(color), thickness)
+    }
+
+    pub fn circle(&mut self, center: Point, radius: i32, color: Scalar) -> Result<()> {
+        circle(&mut self.layer, center, radius, opaque(color))
+    }
+
+    pub fn circle_filled(&mut self, center: Point, radius: i32, color: Scalar) -> Result<()> {
+        circle_filled(&mut self.layer, center, radius, opaque(color))
+    }
+
+    pub fn polylines(&mut self, pts: &[Point], is_closed: bool, color: Scalar, thickness: i32) -> Result<()> {
+        polylines(&mut self.layer, pts, is_closed, opaque(color), thickness)
+    }
+
+    pub fn fill_poly(&mut self, pts: &[Point], color: Scalar) -> Result<()> {
+        fill_poly(&mut self.layer, pts, opaque(color))
+    }
+
+    pub fn put_text(&mut self, text: &str, org: Point, font_scale: f64, color: Scalar) -> Result<()> {
+        put_text(&mut self.layer, text, org, font_scale, opaque(color))
+    }
+
+    /// Blend the layer over `base`, writing the result to `dst`
+    pub fn composite(&self, base: &Mat, dst: &mut Mat) -> Result<()> {
+        *dst = base.clone_mat();
+        self.composite_in_place(dst)
+    }
+
+    /// Blend the layer over `img` in place
+    ///
+    /// Gray images receive the layer's luminance, weighted by the layer's
+    /// [`ColorOrder`](crate::core::types::ColorOrder) tag; a base alpha channel
+    /// is left untouched.
+    pub fn composite_in_place(&self, img: &mut Mat) -> Result<()> {
+        if img.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "Overlay compositing only supports U8 depth".to_string(),
+            ));
+        }
+        if img.rows() != self.layer.rows() || img.cols() != self.layer.cols() {
+            return Err(Error::InvalidDimensions(
+                "Overlay and image must have the same dimensions".to_string(),
+            ));
+        }
+
+        let channels = img.channels();
+        if !matches!(channels, 1 | 3 | 4) {
+            return Err(Error::InvalidParameter(format!(
+                "Overlay compositing requires 1, 3 or 4 channels, got {channels}"
+            )));
+        }
+
+        let global = self.alpha as f32;
+        let weights = self.layer.color_order().luma_weights().map(|w| w as f32);
+        let layer = self.layer.data();
+        let data = img.data_mut();
+
+        for (src, dst) in layer.chunks_exact(4).zip(data.chunks_exact_mut(channels)) {
+            let a = f32::from(src[3]) / 255.0 * global;
+            if a <= 0.0 {
+                continue;
+            }
+
+            let blend = |under: u8, over: f32| -> u8 {
+                (f32::from(under) * (1.0 - a) + over * a).round().clamp(0.0, 255.0) as u8
+            };
+
+            if channels == 1 {
+                let luma = weights[0] * f32::from(src[0]) + weights[1] * f32::from(src[1]) + weights[2] * f32::from(src[2]);
+                dst[0] = blend(dst[0], luma);
+            } else {
+                for ch in 0..3 {
+                    dst[ch] = blend(dst[ch], f32::from(src[ch]));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Force full coverage for shapes drawn through the overlay helpers
+fn opaque(color: Scalar) -> Scalar {
+    Scalar::new(color.val[0], color.val[1], color.val[2], 255.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_preserves_base() {
+        let base = Mat::new_with_default(20, 20, 3, MatDepth::U8, Scalar::all(100.0)).unwrap();
+        let mut overlay = Overlay::for_image(&base).unwrap().with_alpha(0.5);
+        overlay.rectangle(Rect::new(5, 5, 5, 5), Scalar::from_rgb(200, 0, 0), -1).unwrap();
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        overlay.composite(&base, &mut dst).unwrap();
+
+        assert_eq!(dst.at(7, 7).unwrap(), &[150, 50, 50]);
+        assert_eq!(dst.at(0, 0).unwrap(), &[100, 100, 100]);
+        // Base untouched
+        assert_eq!(base.at(7, 7).unwrap(), &[100, 100, 100]);
+
+        overlay.clear();
+        overlay.composite(&base, &mut dst).unwrap();
+        assert_eq!(dst.at(7, 7).unwrap(), &[100, 100, 100]);
+    }
+
+    #[test]
+    fn test_overlay_gray_luma_follows_color_order() {
+        use crate::core::types::ColorOrder;
+
+        let mut gray = Mat::new(4, 4, 1, MatDepth::U8).unwrap();
+        let mut overlay = Overlay::new(4, 4).unwrap();
+        overlay.rectangle(Rect::new(0, 0, 4, 4), Scalar::new(200.0, 0.0, 0.0, 255.0), -1).unwrap();
+        overlay.composite_in_place(&mut gray).unwrap();
+        assert_eq!(gray.at(1, 1).unwrap(), &[60]);
+
+        let mut gray = Mat::new(4, 4, 1, MatDepth::U8).unwrap();
+        overlay.layer_mut().set_color_order(ColorOrder::Bgr);
+        overlay.composite_in_place(&mut gray).unwrap();
+        assert_eq!(gray.at(1, 1).unwrap(), &[23]);
+    }
+
+    #[test]
+    fn test_overlay_size_mismatch() {
+        let overlay = Overlay::new(10, 10).unwrap();
+        let mut img = Mat::new(5, 5, 3, MatDepth::U8).unwrap();
+        assert!(overlay.composite_in_place(&mut img).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// 5-tap binomial kernel used by `pyrDown`/`pyrUp`
+const KERNEL: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+/// Blur and downsample an image by two
+///
+/// Output size is `((rows + 1) / 2, (cols + 1) / 2)` like `OpenCV`'s
+/// `pyrDown`. Borders are replicated. U8 and F32 images with any number of
+/// channels are accepted; the depth is preserved.
+pub fn pyr_down(src: &Mat, dst: &mut Mat) -> Result<()> {
+    let plane = Plane::from_mat(src)?;
+    *dst = plane.down().to_mat(src.depth())?.with_color_order(src.color_order());
+    Ok(())
+}
+
+/// Upsample an image by two and smooth it
+///
+/// Output size is `(2·rows, 2·cols)`; use [`pyr_up_to`] to hit an odd size
+/// when inverting [`pyr_down`]. Accepts U8 and F32 images.
+pub fn pyr_up(src: &Mat, dst: &mut Mat) -> Result<()> {
+    pyr_up_to(src, dst, src.rows() * 2, src.cols() * 2)
+}
+
+/// Upsample an image to `rows`×`cols`, which must be `2n` or `2n - 1` of the
+/// source size along each axis
+pub fn pyr_up_to(src: &Mat, dst: &mut Mat, rows: usize, cols: usize) -> Result<()> {
+    if rows.div_ceil(2) != src.rows() || cols.div_ceil(2) != src.cols() {
+        return Err(Error::InvalidDimensions(
+            "pyr_up target size must be twice the source size (or one less)".to_string(),
+        ));
+    }
+
+    let plane = Plane::from_mat(src)?;
+    *dst = plane.up(rows, cols).to_mat(src.depth())?.with_color_order(src.color_order());
+    Ok(())
+}
+
+/// Gaussian pyramid with up to `levels` levels, finest first, as F32 images
+///
+/// Stops early once a level is smaller than 4 pixels on either side.
+pub fn build_gaussian_pyramid(src: &Mat, levels: usize) -> Result<Vec<Mat>> {
+    gaussian_planes(src, levels)?
+        .iter()
+        .map(|p| p.to_mat(MatDepth::F32))
+        .collect()
+}
+
+/// Laplacian pyramid with up to `levels` levels, finest first, as F32 images
+///
+/// Each level holds the detail lost between two Gaussian levels; the last
+/// level is the coarsest Gaussian level itself, so
+/// [`collapse_laplacian_pyramid`] reconstructs the input exactly (up to
+/// rounding).
+pub fn build_laplacian_pyramid(src: &Mat, levels: usize) -> Result<Vec<Mat>> {
+    laplacian_planes(src, levels)?
+        .iter()
+        .map(|p| p.to_mat(MatDepth::F32))
+        .collect()
+}
+
+/// Rebuild an F32 image from a Laplacian pyramid
+pub fn collapse_laplacian_pyramid(pyramid: &[Mat]) -> Result<Mat> {
+    let planes = pyramid.iter().map(Plane::from_mat).collect::<Result<Vec<_>>>()?;
+    collapse(&planes)?.to_mat(MatDepth::F32)
+}
+
+/// Blend two images with a soft mask using Laplacian pyramids
+///
+/// Each frequency band is blended with a correspondingly blurred copy of the
+/// mask (Burt & Adelson), so coarse structure transitions smoothly while fine
+/// detail keeps a sharp seam. Suited to exposure fusion, face swaps and other
+/// compositing outside the panorama pipeline.
+///
+/// `a` and `b` must have the same size, channels and depth (U8 or F32).
+/// `mask` is single-channel and gives the weight of `a`: 0–255 for U8 masks,
+/// 0–1 for F32. `levels` is clamped to what the image size allows. The result
+/// has the depth of the inputs.
+pub fn blend_laplacian(a: &Mat, b: &Mat, mask: &Mat, dst: &mut Mat, levels: usize) -> Result<()> {
+    if a.rows() != b.rows() || a.cols() != b.cols() || a.channels() != b.channels() || a.depth() != b.depth() {
+        return Err(Error::InvalidDimensions(
+            "Images to blend must have the same size, channels and depth".to_string(),
+        ));
+    }
+
+    if mask.rows() != a.rows() || mask.cols() != a.cols() || mask.channels() != 1 {
+        return Err(Error::InvalidDimensions(
+            "Blend mask must be single-channel and the size of the images".to_string(),
+        ));
+    }
+
+    if levels == 0 {
+        return Err(Error::InvalidParameter("levels must be at least 1".to_string()));
+    }
+
+    let la = laplacian_planes(a, levels)?;
+    let lb = laplacian_planes(b, levels)?;
+
+    let mut weights = Plane::from_mat(mask)?;
+    if mask.depth() == MatDepth::U8 {
+        weights.data.iter_mut().for_each(|w| *w /= 255.0);
+    }
+    let mut mask_levels = vec![weights];
+    while mask_levels.len() < la.len() {
+        let next = mask_levels[mask_levels.len() - 1].down();
+        mask_levels.push(next);
+    }
+
+    let channels = a.channels();
+    let blended: Vec<Plane> = la
+        .iter()
+        .zip(&lb)
+        .zip(&mask_levels)
+        .map(|((pa, pb), m)| {
+            let mut out = pa.clone();
+            for (i, v) in out.data.iter_mut().enumerate() {
+                let w = m.data[i / channels].clamp(0.0, 1.0);
+                *v = w * pa.data[i] + (1.0 - w) * pb.data[i];
+            }
+            out
+        })
+        .collect();
+
+    *dst = collapse(&blended)?.to_mat(a.depth())?.with_color_order(a.color_order());
+    Ok(())
+}
+
+/// Interleaved F32 image buffer used while building pyramids
+#[derive(Clone)]
+struct Plane {
+    rows: usize,
+    cols: usize,
+    channels: usize,
+    data: Vec<f32>,
+}
+
+impl Plane {
+    fn from_mat(src: &Mat) -> Result<Self> {
+        let channels = src.channels();
+        let mut data = Vec::with_capacity(src.rows() * src.cols() * channels);
+
+        for row in 0..src.rows() {
+            for col in 0..src.cols() {
+                for ch in 0..channels {
+                    data.push(match src.depth() {
+                        MatDepth::U8 => f32::from(src.at(row, col)?[ch]),
+                        MatDepth::F32 => src.at_f32(row, col, ch)?,
+                        _ => {
+                            return Err(Error::UnsupportedOperation(
+                                "Image pyramids support U8 and F32 depth".to_string(),
+                            ))
+                        }
+                    });
+                }
+            }
+        }
+
+        Ok(Self { rows: src.rows(), cols: src.cols(), channels, data })
+    }
+
+    fn to_mat(&self, depth: MatDepth) -> Result<Mat> {
+        let mut out = Mat::new(self.rows, self.cols, self.channels, depth)?;
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                for ch in 0..self.channels {
+                    let v = self.data[(row * self.cols + col) * self.channels + ch];
+                    match depth {
+                        MatDepth::F32 => out.set_f32(row, col, ch, v)?,
+                        _ => out.at_mut(row, col)?[ch] = v.round().clamp(0.0, 255.0) as u8,
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn get(&self, row: usize, col: usize, ch: usize) -> f32 {
+        self.data[(row * self.cols + col) * self.channels + ch]
+    }
+
+    fn down(&self) -> Self {
+        let rows = self.rows.div_ceil(2);
+        let cols = self.cols.div_ceil(2);
+        let clamp = |v: isize, n: usize| v.clamp(0, n as isize - 1) as usize;
+
+        // Horizontal pass at the even columns, then vertical at the even rows
+        let mut tmp = vec![0.0f32; self.rows * cols * self.channels];
+        for row in 0..self.rows {
+            for col in 0..cols {
+                for ch in 0..self.channels {
+                    tmp[(row * cols + col) * self.channels + ch] = KERNEL
+                        .iter()
+                        .enumerate()
+                        .map(|(k, w)| w * self.get(row, clamp((2 * col) as isize + k as isize - 2, self.cols), ch))
+                        .sum();
+                }
+            }
+        }
+
+        let mut data = vec![0.0f32; rows * cols * self.channels];
+        for row in 0..rows {
+            for col in 0..cols {
+                for ch in 0..self.channels {
+                    data[(row * cols + col) * self.channels + ch] = KERNEL
+                        .iter()
+                        .enumerate()
+                        .map(|(k, w)| {
+                            let r = clamp((2 * row) as isize + k as isize - 2, self.rows);
+                            w * tmp[(r * cols + col) * self.channels + ch]
+                        })
+                        .sum();
+                }
+            }
+        }
+
+        Self { rows, cols, channels: self.channels, data }
+    }
+
+    fn up(&self, rows: usize, cols: usize) -> Self {
+        // Zero insertion followed by the kernel scaled by 2 per axis: each
+        // output sample only sees the source taps at even offsets
+        let taps = |dst: usize, n: usize| -> Vec<(usize, f32)> {
+            (0..5)
+                .filter_map(|k| {
+                    let pos = dst as isize + k as isize - 2;
+                    (pos.rem_euclid(2) == 0).then(|| ((pos / 2).clamp(0, n as isize - 1) as usize, 2.0 * KERNEL[k]))
+                })
+                .collect()
+        };
+
+        let mut tmp = vec![0.0f32; self.rows * cols * self.channels];
+        for col in 0..cols {
+            let col_taps = taps(col, self.cols);
+            for row in 0..self.rows {
+                for ch in 0..self.channels {
+                    tmp[(row * cols + col) * self.channels + ch] =
+                        col_taps.iter().map(|&(c, w)| w * self.get(row, c, ch)).sum();
+                }
+            }
+        }
+
+        let mut data = vec![0.0f32; rows * cols * self.channels];
+        for row in 0..rows {
+            let row_taps = taps(row, self.rows);
+            for col in 0..cols {
+                for ch in 0..self.channels {
+                    data[(row * cols + col) * self.channels + ch] = row_taps
+                        .iter()
+                        .map(|&(r, w)| w * tmp[(r * cols + col) * self.channels + ch])
+                        .sum();
+                }
+            }
+        }
+
+        Self { rows, cols, channels: self.channels, data }
+    }
+}
+
+fn gaussian_planes(src: &Mat, levels: usize) -> Result<Vec<Plane>> {
+    let mut pyramid = vec![Plane::from_mat(src)?];
+    while pyramid.len() < levels {
+        let last = &pyramid[pyramid.len() - 1];
+        if last.rows < 4 || last.cols < 4 {
+            break;
+        }
+        let next = last.down();
+        pyramid.push(next);
+    }
+    Ok(pyramid)
+}
+
+fn laplacian_planes(src: &Mat, levels: usize) -> Result<Vec<Plane>> {
+    let gaussian = gaussian_planes(src, levels)?;
+    let mut pyramid = Vec::with_capacity(gaussian.len());
+
+    for pair in gaussian.windows(2) {
+        let up = pair[1].up(pair[0].rows, pair[0].cols);
+        let mut detail = pair[0].clone();
+        detail.data.iter_mut().zip(&up.data).for_each(|(d, u)| *d -= u);
+        pyramid.push(detail);
+    }
+
+    if let Some(coarsest) = gaussian.last() {
+        pyramid.push(coarsest.clone());
+    }
+    Ok(pyramid)
+}
+
+fn collapse(pyramid: &[Plane]) -> Result<Plane> {
+    let (coarsest, details) = pyramid
+        .split_last()
+        .ok_or_else(|| Error::InvalidParameter("Empty pyramid".to_string()))?;
+
+    let mut result = coarsest.clone();
+    for detail in details.iter().rev() {
+        if detail.rows.div_ceil(2) != result.rows || detail.cols.div_ceil(2) != result.cols || detail.channels != result.channels {
+            return Err(Error::InvalidDimensions(
+                "Pyramid levels must halve in size and share channels".to_string(),
+            ));
+        }
+        result = result.up(detail.rows, detail.cols);
+        result.data.iter_mut().zip(&detail.data).for_each(|(r, d)| *r += d);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Scalar;
+
+    fn gradient_image(rows: usize, cols: usize) -> Mat {
+        let mut img = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let px = img.at_mut(row, col).unwrap();
+                px[0] = (row * 3 % 256) as u8;
+                px[1] = (col * 5 % 256) as u8;
+                px[2] = ((row * col) % 256) as u8;
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_pyr_down_up_sizes() {
+        let img = gradient_image(33, 50);
+        let mut down = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        pyr_down(&img, &mut down).unwrap();
+        assert_eq!((down.rows(), down.cols()), (17, 25));
+        assert_eq!(down.depth(), MatDepth::U8);
+
+        let mut up = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        pyr_up(&down, &mut up).unwrap();
+        assert_eq!((up.rows(), up.cols()), (34, 50));
+
+        pyr_up_to(&down, &mut up, 33, 50).unwrap();
+        assert_eq!((up.rows(), up.cols()), (33, 50));
+        assert!(pyr_up_to(&down, &mut up, 30, 50).is_err());
+
+        // A flat image stays flat
+        let flat = Mat::new_with_default(16, 16, 1, MatDepth::U8, Scalar::all(77.0)).unwrap();
+        pyr_down(&flat, &mut down).unwrap();
+        pyr_up(&down, &mut up).unwrap();
+        assert_eq!(up.at(7, 9).unwrap()[0], 77);
+    }
+
+    #[test]
+    fn test_laplacian_pyramid_round_trip() {
+        let img = gradient_image(37, 29);
+        let pyramid = build_laplacian_pyramid(&img, 4).unwrap();
+        assert_eq!(pyramid.len(), 4);
+        assert_eq!(build_gaussian_pyramid(&img, 4).unwrap()[3].rows(), 5);
+
+        let rebuilt = collapse_laplacian_pyramid(&pyramid).unwrap();
+        for (row, col) in [(0, 0), (18, 14), (36, 28)] {
+            for ch in 0..3 {
+                let v = rebuilt.at_f32(row, col, ch).unwrap();
+                assert!((v - f32::from(img.at(row, col).unwrap()[ch])).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_blend_laplacian_seam() {
+        let a = Mat::new_with_default(32, 64, 1, MatDepth::U8, Scalar::all(200.0)).unwrap();
+        let b = Mat::new_with_default(32, 64, 1, MatDepth::U8, Scalar::all(40.0)).unwrap();
+        let mut mask = Mat::new(32, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..32 {
+            for col in 0..32 {
+                mask.at_mut(row, col).unwrap()[0] = 255;
+            }
+        }
+
+        let mut out = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        blend_laplacian(&a, &b, &mask, &mut out, 4).unwrap();
+
+        // Far from the seam each side keeps its source
+        assert_eq!(out.at(16, 2).unwrap()[0], 200);
+        assert_eq!(out.at(16, 61).unwrap()[0], 40);
+
+        // Across the seam the transition is gradual and monotonic
+        let row: Vec<u8> = (24..40).map(|c| out.at(16, c).unwrap()[0]).collect();
+        assert!(row.windows(2).all(|w| w[0] >= w[1]), "{row:?}");
+        assert!(row.iter().filter(|&&v| v > 45 && v < 195).count() >= 4, "{row:?}");
+
+        let small = Mat::new(8, 8, 1, MatDepth::U8).unwrap();
+        assert!(blend_laplacian(&a, &small, &mask, &mut out, 4).is_err());
+    }
+}
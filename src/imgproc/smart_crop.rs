@@ -0,0 +1,375 @@
+#![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::{InterpolationFlag, Rect, Size};
+use crate::error::{Error, Result};
+
+/// Long side of the working copy the crop energy is computed on
+const WORK_SIZE: usize = 256;
+/// Gray levels per entropy histogram
+const ENTROPY_BINS: usize = 16;
+/// Side of the blocks entropy is measured over, in working pixels
+const ENTROPY_BLOCK: usize = 8;
+
+/// Weights and hints for [`smart_crop_with_options`]
+///
+/// Each cue is normalized to `[0, 1]` per pixel before weighting, so the
+/// weights set their relative importance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartCropOptions {
+    /// Edges and colors that stand out from the image average
+    pub saliency_weight: f64,
+    /// Local texture, as the gray-level entropy of small blocks
+    pub entropy_weight: f64,
+    /// Pixels inside [`faces`](Self::faces)
+    pub face_weight: f64,
+    /// Face (or other must-keep) rectangles from any detector, in source
+    /// pixels
+    pub faces: Vec<Rect>,
+    /// Maximum number of proposals returned
+    pub max_proposals: usize,
+}
+
+impl Default for SmartCropOptions {
+    fn default() -> Self {
+        Self {
+            saliency_weight: 1.0,
+            entropy_weight: 0.5,
+            face_weight: 4.0,
+            faces: Vec::new(),
+            max_proposals: 3,
+        }
+    }
+}
+
+impl SmartCropOptions {
+    #[must_use]
+    pub fn with_faces(mut self, faces: Vec<Rect>) -> Self {
+        self.faces = faces;
+        self
+    }
+
+    #[must_use]
+    pub fn with_weights(mut self, saliency: f64, entropy: f64, face: f64) -> Self {
+        self.saliency_weight = saliency;
+        self.entropy_weight = entropy;
+        self.face_weight = face;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_proposals(mut self, max_proposals: usize) -> Self {
+        self.max_proposals = max_proposals;
+        self
+    }
+}
+
+/// A candidate crop and its score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CropProposal {
+    pub rect: Rect,
+    /// Fraction of the image's interest energy inside `rect`, in `[0, 1]`
+    pub score: f64,
+}
+
+/// Propose crops of aspect ratio `target_aspect` (width / height) that keep
+/// the most interesting part of `src`, best first
+///
+/// Uses [`SmartCropOptions::default`]; see [`smart_crop_with_options`].
+pub fn smart_crop(src: &Mat, target_aspect: f64) -> Result<Vec<CropProposal>> {
+    smart_crop_with_options(src, target_aspect, &SmartCropOptions::default())
+}
+
+/// Propose crops of aspect ratio `target_aspect` (width / height), best first
+///
+/// Crops are the largest rectangle of that aspect that fits in `src`, slid
+/// along the free axis. Each position is scored by the share of the image's
+/// interest energy it contains, a weighted sum of saliency, block entropy
+/// and coverage of the given faces. Proposals after the first overlap each
+/// earlier one by at most half (intersection over union), and ties go to the
+/// most central position. `src` is a 1, 3 or 4-channel U8 image.
+pub fn smart_crop_with_options(src: &Mat, target_aspect: f64, options: &SmartCropOptions) -> Result<Vec<CropProposal>> {
+    if src.depth() != MatDepth::U8 || !matches!(src.channels(), 1 | 3 | 4) {
+        return Err(Error::InvalidParameter(
+            "Smart crop requires a 1, 3 or 4-channel U8 image".to_string(),
+        ));
+    }
+    if !(target_aspect.is_finite() && target_aspect > 0.0) {
+        return Err(Error::InvalidParameter(
+            "Target aspect ratio must be positive".to_string(),
+        ));
+    }
+
+    let (cols, rows) = (src.cols(), src.rows());
+    let scale = (WORK_SIZE as f64 / cols.max(rows) as f64).min(1.0);
+    let (w, h) = (
+        ((cols as f64 * scale).round() as usize).max(1),
+        ((rows as f64 * scale).round() as usize).max(1),
+    );
+    let small = if scale < 1.0 {
+        let mut small = Mat::new(1, 1, 1, MatDepth::U8)?;
+        super::resize(src, &mut small, Size::new(w as i32, h as i32), InterpolationFlag::Area)?;
+        small
+    } else {
+        src.clone()
+    };
+
+    let energy = crop_energy(&small, options, (w as f64 / cols as f64, h as f64 / rows as f64))?;
+    let sums = SummedArea::new(&energy, w, h);
+    let total = sums.sum(0, 0, w, h);
+
+    // Largest crop of the target aspect, in source and working pixels
+    let (crop_w, crop_h) = if cols as f64 / rows as f64 > target_aspect {
+        (((rows as f64 * target_aspect).round() as usize).clamp(1, cols), rows)
+    } else {
+        (cols, ((cols as f64 / target_aspect).round() as usize).clamp(1, rows))
+    };
+    let work_w = ((crop_w as f64 * scale).round() as usize).clamp(1, w);
+    let work_h = ((crop_h as f64 * scale).round() as usize).clamp(1, h);
+    let (free_x, free_y) = (w - work_w, h - work_h);
+
+    let mut candidates: Vec<CropProposal> = (0..=free_x.max(free_y))
+        .map(|offset| {
+            let (x, y) = if free_x > 0 { (offset, 0) } else { (0, offset) };
+            let captured = if total > 0.0 { sums.sum(x, y, work_w, work_h) / total } else { 1.0 };
+            // Map back to source pixels, keeping the exact crop size
+            let src_x = ((x as f64 / scale).round() as usize).min(cols - crop_w);
+            let src_y = ((y as f64 / scale).round() as usize).min(rows - crop_h);
+            // Ties go to the centre
+            let free = free_x.max(free_y).max(1) as f64;
+            let off_centre = (offset as f64 - free / 2.0).abs() / free;
+            CropProposal {
+                rect: Rect::new(src_x as i32, src_y as i32, crop_w as i32, crop_h as i32),
+                score: captured.min(1.0) - 1e-6 * off_centre,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut proposals: Vec<CropProposal> = Vec::new();
+    for candidate in candidates {
+        if proposals.len() >= options.max_proposals.max(1) {
+            break;
+        }
+        if proposals.iter().all(|p| iou(&p.rect, &candidate.rect) <= 0.5) {
+            proposals.push(CropProposal { score: candidate.score.max(0.0), ..candidate });
+        }
+    }
+    Ok(proposals)
+}
+
+/// Crop `src` to the best [`smart_crop`] proposal for the aspect of `dsize`
+/// and resize it to `dsize`
+pub fn smart_thumbnail(src: &Mat, dsize: Size, options: &SmartCropOptions) -> Result<Mat> {
+    if dsize.width <= 0 || dsize.height <= 0 {
+        return Err(Error::InvalidDimensions(
+            "Thumbnail size must be positive".to_string(),
+        ));
+    }
+    let aspect = f64::from(dsize.width) / f64::from(dsize.height);
+    let best = smart_crop_with_options(src, aspect, options)?
+        .first()
+        .map(|p| p.rect)
+        .ok_or_else(|| Error::InvalidParameter("No crop proposal".to_string()))?;
+
+    let mut thumbnail = Mat::new(1, 1, 1, MatDepth::U8)?;
+    super::resize(&src.roi(best)?, &mut thumbnail, dsize, InterpolationFlag::Area)?;
+    Ok(thumbnail.with_color_order(src.color_order()))
+}
+
+/// Weighted per-pixel interest of the working image; `face_scale` maps
+/// source face rectangles to working pixels
+fn crop_energy(small: &Mat, options: &SmartCropOptions, face_scale: (f64, f64)) -> Result<Vec<f64>> {
+    let (w, h) = (small.cols(), small.rows());
+    let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+    super::to_gray(small, &mut gray)?;
+    let luma = gray.data();
+
+    // Saliency: gradient magnitude plus distance from the mean color
+    let channels = small.channels().min(3);
+    let pixels = small.data();
+    let stride = small.channels();
+    let mut mean = [0.0f64; 3];
+    for px in pixels.chunks_exact(stride) {
+        for (m, &v) in mean.iter_mut().zip(&px[..channels]) {
+            *m += f64::from(v);
+        }
+    }
+    mean.iter_mut().for_each(|m| *m /= (w * h) as f64);
+
+    let mut gradient = vec![0.0f64; w * h];
+    let mut distinct = vec![0.0f64; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let at = |xx: usize, yy: usize| f64::from(luma[yy * w + xx]);
+            let gx = at((x + 1).min(w - 1), y) - at(x.saturating_sub(1), y);
+            let gy = at(x, (y + 1).min(h - 1)) - at(x, y.saturating_sub(1));
+            gradient[y * w + x] = gx.hypot(gy);
+
+            let px = &pixels[(y * w + x) * stride..][..channels];
+            distinct[y * w + x] = px.iter().zip(&mean).map(|(&v, m)| (f64::from(v) - m).abs()).sum();
+        }
+    }
+    normalize_max(&mut gradient);
+    normalize_max(&mut distinct);
+    let saliency: Vec<f64> = gradient.iter().zip(&distinct).map(|(g, d)| (g + d) / 2.0).collect();
+    let saliency = box_blur(&saliency, w, h, (w.max(h) / 32).max(1));
+
+    let entropy = block_entropy(luma, w, h);
+
+    let mut faces = vec![0.0f64; w * h];
+    for face in &options.faces {
+        let x0 = ((f64::from(face.x) * face_scale.0).floor().max(0.0) as usize).min(w);
+        let y0 = ((f64::from(face.y) * face_scale.1).floor().max(0.0) as usize).min(h);
+        let x1 = ((f64::from(face.x + face.width) * face_scale.0).ceil().max(0.0) as usize).min(w);
+        let y1 = ((f64::from(face.y + face.height) * face_scale.1).ceil().max(0.0) as usize).min(h);
+        for y in y0..y1 {
+            faces[y * w + x0..y * w + x1].fill(1.0);
+        }
+    }
+
+    Ok((0..w * h)
+        .map(|i| {
+            options.saliency_weight * saliency[i] + options.entropy_weight * entropy[i] + options.face_weight * faces[i]
+        })
+        .map(|e| e.max(0.0))
+        .collect())
+}
+
+fn normalize_max(values: &mut [f64]) {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    if max > 0.0 {
+        values.iter_mut().for_each(|v| *v /= max);
+    }
+}
+
+/// Mean over a `(2 radius + 1)`-wide square, clipped at the borders
+fn box_blur(values: &[f64], w: usize, h: usize, radius: usize) -> Vec<f64> {
+    let sums = SummedArea::new(values, w, h);
+    let mut out = vec![0.0; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let (x0, y0) = (x.saturating_sub(radius), y.saturating_sub(radius));
+            let (x1, y1) = ((x + radius + 1).min(w), (y + radius + 1).min(h));
+            out[y * w + x] = sums.sum(x0, y0, x1 - x0, y1 - y0) / ((x1 - x0) * (y1 - y0)) as f64;
+        }
+    }
+    out
+}
+
+/// Gray-level entropy of each block, scaled to `[0, 1]` and spread over the
+/// block's pixels
+fn block_entropy(luma: &[u8], w: usize, h: usize) -> Vec<f64> {
+    let max_entropy = (ENTROPY_BINS as f64).log2();
+    let mut out = vec![0.0; w * h];
+    for by in (0..h).step_by(ENTROPY_BLOCK) {
+        for bx in (0..w).step_by(ENTROPY_BLOCK) {
+            let (x1, y1) = ((bx + ENTROPY_BLOCK).min(w), (by + ENTROPY_BLOCK).min(h));
+            let mut histogram = [0usize; ENTROPY_BINS];
+            for y in by..y1 {
+                for &v in &luma[y * w + bx..y * w + x1] {
+                    histogram[usize::from(v) * ENTROPY_BINS / 256] += 1;
+                }
+            }
+            let count = ((x1 - bx) * (y1 - by)) as f64;
+            let entropy: f64 = histogram
+                .iter()
+                .filter(|&&n| n > 0)
+                .map(|&n| {
+                    let p = n as f64 / count;
+                    -p * p.log2()
+                })
+                .sum();
+            for y in by..y1 {
+                out[y * w + bx..y * w + x1].fill(entropy / max_entropy);
+            }
+        }
+    }
+    out
+}
+
+fn iou(a: &Rect, b: &Rect) -> f64 {
+    let overlap = a.intersection(b).map_or(0, |r| r.area());
+    f64::from(overlap) / f64::from(a.area() + b.area() - overlap)
+}
+
+/// Summed-area table for constant-time rectangle sums
+struct SummedArea {
+    table: Vec<f64>,
+    stride: usize,
+}
+
+impl SummedArea {
+    fn new(values: &[f64], w: usize, h: usize) -> Self {
+        let stride = w + 1;
+        let mut table = vec![0.0; stride * (h + 1)];
+        for y in 0..h {
+            let mut row = 0.0;
+            for x in 0..w {
+                row += values[y * w + x];
+                table[(y + 1) * stride + x + 1] = table[y * stride + x + 1] + row;
+            }
+        }
+        Self { table, stride }
+    }
+
+    fn sum(&self, x: usize, y: usize, w: usize, h: usize) -> f64 {
+        let s = self.stride;
+        self.table[(y + h) * s + x + w] - self.table[y * s + x + w] - self.table[(y + h) * s + x] + self.table[y * s + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat gray 300x100 image with a textured block at columns `x..x + 60`
+    fn scene(x: usize) -> Mat {
+        let mut mat = Mat::new(100, 300, 3, MatDepth::U8).unwrap();
+        mat.data_mut().fill(120);
+        for row in 20..80 {
+            for col in x..x + 60 {
+                let v = if (row / 4 + col / 4) % 2 == 0 { 250 } else { 10 };
+                mat.at_mut(row, col).unwrap().copy_from_slice(&[v, 40, 255 - v]);
+            }
+        }
+        mat
+    }
+
+    #[test]
+    fn test_smart_crop_follows_content() {
+        let proposals = smart_crop(&scene(220), 1.0).unwrap();
+        let best = proposals[0].rect;
+        assert_eq!((best.width, best.height), (100, 100));
+        assert!(best.x <= 220 && best.x + best.width >= 280, "{best:?}");
+        assert!(proposals[0].score > 0.5);
+        assert!(proposals.len() > 1 && proposals.len() <= 3);
+        assert!(proposals.windows(2).all(|p| p[0].score >= p[1].score));
+    }
+
+    #[test]
+    fn test_smart_crop_faces_and_ties() {
+        // A face hint outweighs texture elsewhere
+        let options = SmartCropOptions::default().with_faces(vec![Rect::new(10, 30, 40, 40)]);
+        let best = smart_crop_with_options(&scene(220), 1.0, &options).unwrap()[0].rect;
+        assert!(best.x <= 10, "{best:?}");
+
+        // Nothing to prefer: centered crop
+        let mut flat = Mat::new(100, 300, 1, MatDepth::U8).unwrap();
+        flat.data_mut().fill(90);
+        let best = smart_crop(&flat, 1.0).unwrap()[0].rect;
+        assert_eq!(best, Rect::new(100, 0, 100, 100));
+
+        // Taller target on a wide image keeps the full height
+        assert_eq!(smart_crop(&flat, 0.5).unwrap()[0].rect.height, 100);
+        assert!(smart_crop(&flat, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_smart_thumbnail() {
+        let thumbnail = smart_thumbnail(&scene(0), Size::new(32, 32), &SmartCropOptions::default()).unwrap();
+        assert_eq!((thumbnail.cols(), thumbnail.rows(), thumbnail.channels()), (32, 32, 3));
+        // Cropped around the texture on the left, not the flat middle
+        let corner = thumbnail.at(12, 6).unwrap();
+        assert_ne!(corner, &[120, 120, 120]);
+    }
+}
@@ -0,0 +1,376 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Reprojection between 360° image formats: equirectangular panoramas,
+//! cubemaps, perspective views and equidistant fisheye images.
+//!
+//! All functions share one camera frame: `x` right, `y` down, `z` forward.
+//! Longitude 0 is straight ahead and grows to the right; latitude grows
+//! upwards. An equirectangular image spans longitude -180° to 180° across
+//! its width and latitude 90° to -90° down its height; its left and right
+//! edges wrap around. Angles are in degrees.
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::core::types::{BorderType, InterpolationFlag, Point2f, Size};
+use crate::core::Mat;
+use crate::error::{Error, Result};
+use super::sampling::PixelSampler;
+
+type Vec3 = [f64; 3];
+
+/// Face of a cubemap, in the order returned by [`equirect_to_cubemap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeFace {
+    Front,
+    Right,
+    Back,
+    Left,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::Front,
+        CubeFace::Right,
+        CubeFace::Back,
+        CubeFace::Left,
+        CubeFace::Up,
+        CubeFace::Down,
+    ];
+
+    /// Forward, right and down axes of the face image
+    ///
+    /// The side faces share the horizon, `Up` has `Front` below it and
+    /// `Down` has `Front` above it, as in the common skybox layout.
+    fn axes(self) -> (Vec3, Vec3, Vec3) {
+        match self {
+            CubeFace::Front => ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            CubeFace::Right => ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            CubeFace::Back => ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            CubeFace::Left => ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            CubeFace::Up => ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            CubeFace::Down => ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        }
+    }
+
+    /// Face hit by `dir` and the face coordinates in `[-1, 1]`
+    fn locate(dir: Vec3) -> (CubeFace, f64, f64) {
+        let [x, y, z] = dir;
+        let face = if x.abs() >= y.abs() && x.abs() >= z.abs() {
+            if x > 0.0 { CubeFace::Right } else { CubeFace::Left }
+        } else if y.abs() >= z.abs() {
+            if y > 0.0 { CubeFace::Down } else { CubeFace::Up }
+        } else if z > 0.0 {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        };
+        let (forward, right, down) = face.axes();
+        let depth = dot(dir, forward);
+        (face, dot(dir, right) / depth, dot(dir, down) / depth)
+    }
+}
+
+/// Render a perspective view of an equirectangular panorama
+///
+/// The virtual pinhole camera looks `yaw` degrees right and `pitch` degrees
+/// up from the panorama's forward direction, with a horizontal field of view
+/// of `fov` degrees and square pixels. Works on any depth and channel count.
+pub fn equirect_to_perspective(src: &Mat, dst: &mut Mat, dsize: Size, yaw: f64, pitch: f64, fov: f64) -> Result<()> {
+    let focal = focal_length(dsize, fov)?;
+    let (cx, cy) = centre(dsize);
+    let rotation = view_rotation(yaw, pitch);
+    let (width, height) = (src.cols() as f64, src.rows() as f64);
+    equirect_sampler().warp(src, dst, dsize, |x, y| {
+        let dir = rotate(&rotation, [(x - cx) / focal, (y - cy) / focal, 1.0]);
+        Some(direction_to_equirect(dir, width, height))
+    })
+}
+
+/// Paint a perspective image into an equirectangular canvas of `dsize`
+///
+/// The inverse of [`equirect_to_perspective`] with the same `yaw`, `pitch`
+/// and `fov`; pixels outside the view are zero.
+pub fn perspective_to_equirect(src: &Mat, dst: &mut Mat, dsize: Size, yaw: f64, pitch: f64, fov: f64) -> Result<()> {
+    let focal = focal_length(src.size(), fov)?;
+    let (cx, cy) = centre(src.size());
+    let rotation = view_rotation(yaw, pitch);
+    let (width, height) = (f64::from(dsize.width), f64::from(dsize.height));
+    let (src_w, src_h) = (src.cols() as f64, src.rows() as f64);
+    PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate).warp(src, dst, dsize, |x, y| {
+        // The transpose undoes the view rotation
+        let [dx, dy, dz] = rotate_transposed(&rotation, equirect_to_direction(x, y, width, height));
+        if dz <= 1e-9 {
+            return None;
+        }
+        let (u, v) = (cx + focal * dx / dz, cy + focal * dy / dz);
+        (u > -0.5 && v > -0.5 && u < src_w - 0.5 && v < src_h - 0.5).then_some((u, v))
+    })
+}
+
+/// Split an equirectangular panorama into six `face_size` x `face_size`
+/// cube faces, in [`CubeFace::ALL`] order
+pub fn equirect_to_cubemap(src: &Mat, face_size: usize) -> Result<Vec<Mat>> {
+    if face_size == 0 {
+        return Err(Error::InvalidDimensions(
+            "Cube face size must be positive".to_string(),
+        ));
+    }
+    let n = face_size as f64;
+    let (width, height) = (src.cols() as f64, src.rows() as f64);
+    let sampler = equirect_sampler();
+    CubeFace::ALL
+        .iter()
+        .map(|face| {
+            let (forward, right, down) = face.axes();
+            let mut out = Mat::new(1, 1, src.channels(), src.depth())?;
+            sampler.warp(src, &mut out, Size::new(face_size as i32, face_size as i32), |x, y| {
+                let (a, b) = (2.0 * (x + 0.5) / n - 1.0, 2.0 * (y + 0.5) / n - 1.0);
+                let dir = std::array::from_fn(|i| forward[i] + a * right[i] + b * down[i]);
+                Some(direction_to_equirect(dir, width, height))
+            })?;
+            Ok(out)
+        })
+        .collect()
+}
+
+/// Assemble an equirectangular panorama of `dsize` from six cube faces in
+/// [`CubeFace::ALL`] order
+///
+/// Faces must be square, of equal size and of the same type.
+pub fn cubemap_to_equirect(faces: &[Mat], dst: &mut Mat, dsize: Size) -> Result<()> {
+    if faces.len() != 6 {
+        return Err(Error::InvalidParameter(format!(
+            "A cubemap has 6 faces, got {}",
+            faces.len()
+        )));
+    }
+    let first = &faces[0];
+    if faces.iter().any(|f| {
+        f.rows() != first.cols() || f.cols() != first.cols() || f.channels() != first.channels() || f.depth() != first.depth()
+    }) {
+        return Err(Error::InvalidDimensions(
+            "Cube faces must be square and of the same size and type".to_string(),
+        ));
+    }
+    if dsize.width <= 0 || dsize.height <= 0 {
+        return Err(Error::InvalidDimensions(
+            "Destination size must be positive".to_string(),
+        ));
+    }
+
+    let n = first.cols() as f64;
+    let (width, height) = (f64::from(dsize.width), f64::from(dsize.height));
+    *dst = Mat::new(dsize.height as usize, dsize.width as usize, first.channels(), first.depth())?.with_color_order(first.color_order());
+    let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate);
+    for row in 0..dst.rows() {
+        for col in 0..dst.cols() {
+            let (face, a, b) = CubeFace::locate(equirect_to_direction(col as f64, row as f64, width, height));
+            let index = CubeFace::ALL.iter().position(|&f| f == face).unwrap_or(0);
+            let (x, y) = ((a + 1.0) * n / 2.0 - 0.5, (b + 1.0) * n / 2.0 - 0.5);
+            sampler.sample_pixel(&faces[index], x, y, dst.at_mut(row, col)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Unwrap an equidistant fisheye image into an equirectangular panorama
+/// of `dsize`
+///
+/// The fisheye circle is centred at `center` with radius `radius` pixels and
+/// covers `fov` degrees (e.g. 190 for a lens of a dual-fisheye 360° camera);
+/// the lens looks `yaw` degrees right of the panorama's forward direction.
+/// Directions outside the lens' coverage are zero.
+pub fn fisheye_to_equirect(
+    src: &Mat,
+    dst: &mut Mat,
+    dsize: Size,
+    center: Point2f,
+    radius: f64,
+    fov: f64,
+    yaw: f64,
+) -> Result<()> {
+    if radius <= 0.0 || !(fov > 0.0 && fov <= 360.0) {
+        return Err(Error::InvalidParameter(
+            "Fisheye radius must be positive and fov in (0, 360]".to_string(),
+        ));
+    }
+    let half_fov = fov.to_radians() / 2.0;
+    let rotation = view_rotation(yaw, 0.0);
+    let (cx, cy) = (f64::from(center.x), f64::from(center.y));
+    let (width, height) = (f64::from(dsize.width), f64::from(dsize.height));
+    PixelSampler::new(InterpolationFlag::Linear, BorderType::Constant).warp(src, dst, dsize, |x, y| {
+        let [dx, dy, dz] = rotate_transposed(&rotation, equirect_to_direction(x, y, width, height));
+        let theta = dx.hypot(dy).atan2(dz);
+        if theta > half_fov {
+            return None;
+        }
+        let r = radius * theta / half_fov;
+        let phi = dy.atan2(dx);
+        Some((cx + r * phi.cos(), cy + r * phi.sin()))
+    })
+}
+
+/// Bilinear, wrapping around horizontally and clamped at the poles
+fn equirect_sampler() -> PixelSampler {
+    PixelSampler::new(InterpolationFlag::Linear, BorderType::Wrap).with_vertical_border(BorderType::Replicate)
+}
+
+fn focal_length(size: Size, fov: f64) -> Result<f64> {
+    if size.width <= 0 || size.height <= 0 {
+        return Err(Error::InvalidDimensions(
+            "View size must be positive".to_string(),
+        ));
+    }
+    if !(fov > 0.0 && fov < 180.0) {
+        return Err(Error::InvalidParameter(
+            "Perspective field of view must be in (0, 180) degrees".to_string(),
+        ));
+    }
+    Ok(f64::from(size.width) / 2.0 / (fov.to_radians() / 2.0).tan())
+}
+
+fn centre(size: Size) -> (f64, f64) {
+    ((f64::from(size.width) - 1.0) / 2.0, (f64::from(size.height) - 1.0) / 2.0)
+}
+
+/// Rotation taking view coordinates to panorama coordinates: pitch about
+/// the view's x axis, then yaw about the vertical
+fn view_rotation(yaw: f64, pitch: f64) -> [Vec3; 3] {
+    let (sy, cy) = yaw.to_radians().sin_cos();
+    let (sp, cp) = pitch.to_radians().sin_cos();
+    [[cy, sy * sp, sy * cp], [0.0, cp, -sp], [-sy, cy * sp, cy * cp]]
+}
+
+fn rotate(r: &[Vec3; 3], v: Vec3) -> Vec3 {
+    std::array::from_fn(|i| dot(r[i], v))
+}
+
+fn rotate_transposed(r: &[Vec3; 3], v: Vec3) -> Vec3 {
+    std::array::from_fn(|i| r[0][i] * v[0] + r[1][i] * v[1] + r[2][i] * v[2])
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Equirectangular pixel coordinates of direction `dir`
+fn direction_to_equirect(dir: Vec3, width: f64, height: f64) -> (f64, f64) {
+    let [x, y, z] = dir;
+    let lon = x.atan2(z);
+    let lat = (-y).atan2(x.hypot(z));
+    ((lon + PI) / (2.0 * PI) * width - 0.5, (FRAC_PI_2 - lat) / PI * height - 0.5)
+}
+
+/// Unit direction of equirectangular pixel `(x, y)`
+fn equirect_to_direction(x: f64, y: f64, width: f64, height: f64) -> Vec3 {
+    let lon = (x + 0.5) / width * 2.0 * PI - PI;
+    let lat = FRAC_PI_2 - (y + 0.5) / height * PI;
+    let (sin_lat, cos_lat) = lat.sin_cos();
+    let (sin_lon, cos_lon) = lon.sin_cos();
+    [cos_lat * sin_lon, -sin_lat, cos_lat * cos_lon]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    /// Equirectangular F32 image whose value is a smooth function of the
+    /// direction, continuous across the seam and the poles
+    fn smooth_panorama(width: usize, height: usize) -> Mat {
+        let mut pano = Mat::new(height, width, 1, MatDepth::F32).unwrap();
+        for row in 0..height {
+            for col in 0..width {
+                let [x, y, z] = equirect_to_direction(col as f64, row as f64, width as f64, height as f64);
+                pano.set_f32(row, col, 0, (100.0 + 40.0 * x + 30.0 * y - 20.0 * z + 10.0 * x * z) as f32).unwrap();
+            }
+        }
+        pano
+    }
+
+    /// U8 panorama with one bright pixel in direction `(lon, lat)`
+    fn spot_panorama(lon: f64, lat: f64) -> Mat {
+        let (width, height) = (720usize, 360usize);
+        let mut pano = Mat::new(height, width, 1, MatDepth::U8).unwrap();
+        let (x, y) = ((lon + 180.0) * 2.0, (90.0 - lat) * 2.0);
+        pano.at_mut(y as usize, x as usize).unwrap()[0] = 255;
+        pano
+    }
+
+    fn brightest(img: &Mat) -> (usize, usize) {
+        let i = (0..img.data().len()).max_by_key(|&i| img.data()[i]).unwrap();
+        (i % img.cols(), i / img.cols())
+    }
+
+    #[test]
+    fn test_equirect_to_perspective() {
+        // Spot 30° right and 10° up, at a pixel centre of the panorama
+        let pano = spot_panorama(30.25, 9.75);
+        let mut view = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        equirect_to_perspective(&pano, &mut view, Size::new(101, 101), 30.25, 9.75, 60.0).unwrap();
+        assert_eq!(brightest(&view), (50, 50));
+
+        // Looking straight ahead the spot is up and to the right
+        equirect_to_perspective(&pano, &mut view, Size::new(201, 151), 0.0, 0.0, 90.0).unwrap();
+        let focal = 100.0 / 45f64.to_radians().tan();
+        let (lon, lat) = (30.25f64.to_radians(), 9.75f64.to_radians());
+        let expected = (100.0 + focal * lon.tan(), 75.0 - focal * lat.tan() / lon.cos());
+        let (x, y) = brightest(&view);
+        assert!((x as f64 - expected.0).abs() <= 1.0 && (y as f64 - expected.1).abs() <= 1.0, "{:?} vs {expected:?}", (x, y));
+
+        assert!(equirect_to_perspective(&pano, &mut view, Size::new(10, 10), 0.0, 0.0, 180.0).is_err());
+    }
+
+    #[test]
+    fn test_cubemap_round_trip() {
+        let pano = smooth_panorama(256, 128);
+        let faces = equirect_to_cubemap(&pano, 64).unwrap();
+        assert_eq!(faces.len(), 6);
+
+        // The centre of the Up face looks at the zenith
+        let expected_up = 100.0 - 30.0;
+        assert!((faces[4].at_f32(32, 32, 0).unwrap() - expected_up).abs() < 1.0);
+
+        let mut back = Mat::new(1, 1, 1, MatDepth::F32).unwrap();
+        cubemap_to_equirect(&faces, &mut back, Size::new(256, 128)).unwrap();
+        let mut worst = 0.0f32;
+        for row in 0..128 {
+            for col in 0..256 {
+                worst = worst.max((back.at_f32(row, col, 0).unwrap() - pano.at_f32(row, col, 0).unwrap()).abs());
+            }
+        }
+        assert!(worst < 1.5, "{worst}");
+        assert!(cubemap_to_equirect(&faces[..5], &mut back, Size::new(256, 128)).is_err());
+    }
+
+    #[test]
+    fn test_perspective_round_trip() {
+        let pano = smooth_panorama(512, 256);
+        let mut view = Mat::new(1, 1, 1, MatDepth::F32).unwrap();
+        equirect_to_perspective(&pano, &mut view, Size::new(80, 60), -40.0, 20.0, 70.0).unwrap();
+
+        let mut canvas = Mat::new(1, 1, 1, MatDepth::F32).unwrap();
+        perspective_to_equirect(&view, &mut canvas, Size::new(512, 256), -40.0, 20.0, 70.0).unwrap();
+
+        // Looking ahead-left and up is covered; behind is empty
+        let (col, row) = ((180.0 - 40.0) / 360.0 * 512.0, (90.0 - 20.0) / 180.0 * 256.0);
+        let (col, row) = (col as usize, row as usize);
+        assert!((canvas.at_f32(row, col, 0).unwrap() - pano.at_f32(row, col, 0).unwrap()).abs() < 0.5);
+        assert_eq!(canvas.at_f32(128, 500, 0).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_fisheye_to_equirect() {
+        // 180° equidistant fisheye with a spot 45° right of the axis
+        let mut fisheye = Mat::new(201, 201, 1, MatDepth::U8).unwrap();
+        fisheye.at_mut(100, 150).unwrap()[0] = 255;
+
+        let mut pano = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        fisheye_to_equirect(&fisheye, &mut pano, Size::new(720, 360), Point2f::new(100.0, 100.0), 100.0, 180.0, 0.0).unwrap();
+        let (x, y) = brightest(&pano);
+        assert!((x as f64 - (45.0 + 180.0) * 2.0).abs() <= 1.0 && (y as f64 - 180.0).abs() <= 1.0, "{:?}", (x, y));
+        // Behind the lens
+        assert_eq!(pano.at(180, 5).unwrap()[0], 0);
+    }
+}
@@ -0,0 +1,299 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+use crate::core::types::{Point, Point2f, RotatedRect};
+use crate::core::Mat;
+use crate::error::{Error, Result};
+use super::contours::fit_ellipse;
+use super::line_iterator::{pixel_value, LineIterator};
+
+/// How an edge position is interpolated from the gradient profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeFitMethod {
+    /// Centroid of the gradient peak; robust to non-Gaussian blur
+    Moment,
+    /// Vertex of a parabola through the log-gradient around the peak; exact
+    /// for Gaussian-blurred step edges
+    #[default]
+    Gaussian,
+}
+
+/// Which intensity transitions count as edges, walking from `p1` to `p2`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgePolarity {
+    #[default]
+    Any,
+    /// Dark to bright
+    Rising,
+    /// Bright to dark
+    Falling,
+}
+
+/// Edge found along a scanline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileEdge {
+    /// Sub-pixel image position of the edge
+    pub position: Point2f,
+    /// Distance from the scanline start
+    pub distance: f64,
+    /// Peak intensity gradient per pixel, in the direction of the polarity
+    pub strength: f64,
+}
+
+/// Strongest edge in an intensity profile sampled at increasing
+/// `positions`
+///
+/// Returns the sub-pixel edge position and its gradient, or `None` when the
+/// profile has no transition of the requested polarity.
+#[must_use]
+pub fn profile_edge(positions: &[f64], values: &[f64], polarity: EdgePolarity, method: EdgeFitMethod) -> Option<(f64, f64)> {
+    let n = positions.len().min(values.len());
+    if n < 2 {
+        return None;
+    }
+
+    // Forward differences, located halfway between samples
+    let mut mids = Vec::with_capacity(n - 1);
+    let mut grads = Vec::with_capacity(n - 1);
+    for i in 0..n - 1 {
+        let dt = positions[i + 1] - positions[i];
+        if dt <= 0.0 {
+            return None;
+        }
+        mids.push(0.5 * (positions[i] + positions[i + 1]));
+        grads.push((values[i + 1] - values[i]) / dt);
+    }
+
+    let sign = match polarity {
+        EdgePolarity::Rising => 1.0,
+        EdgePolarity::Falling => -1.0,
+        EdgePolarity::Any => {
+            let peak = grads.iter().copied().max_by(|a, b| a.abs().total_cmp(&b.abs()))?;
+            if peak >= 0.0 { 1.0 } else { -1.0 }
+        }
+    };
+    let g: Vec<f64> = grads.iter().map(|v| sign * v).collect();
+    let k = (0..g.len()).max_by(|&a, &b| g[a].total_cmp(&g[b]))?;
+    if g[k] <= 0.0 {
+        return None;
+    }
+
+    let position = match method {
+        EdgeFitMethod::Moment => {
+            // Contiguous positive gradient within 3 samples of the peak
+            let lo = (k.saturating_sub(3)..k).rev().take_while(|&i| g[i] > 0.0).last().unwrap_or(k);
+            let hi = (k + 1..(k + 4).min(g.len())).take_while(|&i| g[i] > 0.0).last().unwrap_or(k);
+            let weight: f64 = g[lo..=hi].iter().sum();
+            (lo..=hi).map(|i| mids[i] * g[i]).sum::<f64>() / weight
+        }
+        EdgeFitMethod::Gaussian => {
+            if k == 0 || k + 1 >= g.len() || g[k - 1] <= 0.0 || g[k + 1] <= 0.0 {
+                mids[k]
+            } else {
+                parabola_vertex(
+                    [mids[k - 1], mids[k], mids[k + 1]],
+                    [g[k - 1].ln(), g[k].ln(), g[k + 1].ln()],
+                )
+                .map_or(mids[k], |x| x.clamp(mids[k - 1], mids[k + 1]))
+            }
+        }
+    };
+    Some((position, g[k]))
+}
+
+/// Sub-pixel position of the strongest edge crossed by the segment from
+/// `p1` to `p2`
+///
+/// Pixels are visited with an 8-connected [`LineIterator`] and placed at
+/// the projection of their centre onto the segment, so for best accuracy
+/// scan roughly perpendicular to the edge. Works on any depth; `channel`
+/// selects the channel of multi-channel images. Combined with
+/// `calib3d::corner_sub_pix` for corners, Gaussian-blurred edges are
+/// located to well under 0.1 px.
+pub fn find_edge_subpixel(
+    img: &Mat,
+    p1: Point,
+    p2: Point,
+    channel: usize,
+    polarity: EdgePolarity,
+    method: EdgeFitMethod,
+) -> Result<Option<ProfileEdge>> {
+    if channel >= img.channels() {
+        return Err(Error::InvalidParameter(
+            format!("Channel {channel} out of range for {}-channel image", img.channels()),
+        ));
+    }
+    let (dx, dy) = (f64::from(p2.x - p1.x), f64::from(p2.y - p1.y));
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return Ok(None);
+    }
+    let (ux, uy) = (dx / length, dy / length);
+
+    let mut positions = Vec::new();
+    let mut values = Vec::new();
+    for px in LineIterator::new(img, p1, p2, 8)? {
+        positions.push(f64::from(px.pos.x - p1.x) * ux + f64::from(px.pos.y - p1.y) * uy);
+        values.push(pixel_value(img, &px, channel)?);
+    }
+
+    Ok(profile_edge(&positions, &values, polarity, method).map(|(distance, strength)| ProfileEdge {
+        position: Point2f::new(
+            (f64::from(p1.x) + distance * ux) as f32,
+            (f64::from(p1.y) + distance * uy) as f32,
+        ),
+        distance,
+        strength,
+    }))
+}
+
+/// Refine the outline of an elliptical blob or hole to sub-pixel accuracy
+///
+/// `num_rays` scanlines are cast from the centre of `initial` through its
+/// outline, reaching 40% (at least 4 px) either side of it; the edge on each
+/// is located with [`find_edge_subpixel`] and an ellipse is fitted with
+/// [`fit_ellipse`]. Two passes are made, discarding edges far from the
+/// first fit, so `initial` may be a coarse estimate such as a contour's
+/// bounding ellipse.
+pub fn refine_ellipse_subpixel(
+    img: &Mat,
+    initial: &RotatedRect,
+    channel: usize,
+    method: EdgeFitMethod,
+    num_rays: usize,
+) -> Result<RotatedRect> {
+    if num_rays < 5 {
+        return Err(Error::InvalidParameter(
+            "Ellipse refinement needs at least 5 rays".to_string(),
+        ));
+    }
+
+    let mut ellipse = *initial;
+    for pass in 0..2 {
+        let (cx, cy) = (f64::from(ellipse.center.x), f64::from(ellipse.center.y));
+        let mut edges = Vec::with_capacity(num_rays);
+        for ray in 0..num_rays {
+            let phi = 2.0 * std::f64::consts::PI * ray as f64 / num_rays as f64;
+            let (uy, ux) = phi.sin_cos();
+            let r = ellipse_radius(&ellipse, ux, uy);
+            let reach = (0.4 * r).max(4.0);
+            let at = |d: f64| Point::new((cx + d * ux).round() as i32, (cy + d * uy).round() as i32);
+            if let Some(edge) = find_edge_subpixel(img, at((r - reach).max(0.0)), at(r + reach), channel, EdgePolarity::Any, method)? {
+                edges.push(edge.position);
+            }
+        }
+
+        if pass == 1 {
+            // Drop edges that disagree with the first-pass ellipse
+            let deviation = |p: &Point2f| {
+                let (dx, dy) = (f64::from(p.x) - cx, f64::from(p.y) - cy);
+                let d = dx.hypot(dy);
+                if d == 0.0 { f64::INFINITY } else { (d - ellipse_radius(&ellipse, dx / d, dy / d)).abs() }
+            };
+            let mut deviations: Vec<f64> = edges.iter().map(deviation).collect();
+            deviations.sort_by(f64::total_cmp);
+            let limit = 3.0 * deviations.get(deviations.len() / 2).copied().unwrap_or(0.0) + 0.5;
+            edges.retain(|p| deviation(p) <= limit);
+        }
+
+        ellipse = fit_ellipse(&edges)?;
+    }
+    Ok(ellipse)
+}
+
+/// Distance from the centre of `ellipse` to its outline along the unit
+/// direction `(ux, uy)`
+fn ellipse_radius(ellipse: &RotatedRect, ux: f64, uy: f64) -> f64 {
+    let (sin, cos) = f64::from(ellipse.angle).to_radians().sin_cos();
+    let (a, b) = (0.5 * f64::from(ellipse.width), 0.5 * f64::from(ellipse.height));
+    // Direction in the ellipse's own frame
+    let (x, y) = (ux * cos + uy * sin, -ux * sin + uy * cos);
+    1.0 / ((x / a).powi(2) + (y / b).powi(2)).sqrt()
+}
+
+/// Abscissa of the vertex of the parabola through three points, if it
+/// opens downwards
+fn parabola_vertex(x: [f64; 3], y: [f64; 3]) -> Option<f64> {
+    let denom = (x[0] - x[1]) * (x[0] - x[2]) * (x[1] - x[2]);
+    let a = (x[2] * (y[1] - y[0]) + x[1] * (y[0] - y[2]) + x[0] * (y[2] - y[1])) / denom;
+    let b = (x[2] * x[2] * (y[0] - y[1]) + x[1] * x[1] * (y[2] - y[0]) + x[0] * x[0] * (y[1] - y[2])) / denom;
+    (a < 0.0).then(|| -b / (2.0 * a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    /// Standard normal CDF (Abramowitz-Stegun 7.1.26 erf)
+    fn phi(x: f64) -> f64 {
+        let z = x.abs() / std::f64::consts::SQRT_2;
+        let t = 1.0 / (1.0 + 0.327_591_1 * z);
+        let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+        let erf = 1.0 - poly * (-z * z).exp();
+        if x >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+    }
+
+    /// U8 image of a Gaussian-blurred step: 40 where `signed(x, y) < 0`, 220
+    /// beyond, with `signed` the signed distance to the edge
+    fn render(rows: usize, cols: usize, signed: impl Fn(f64, f64) -> f64) -> Mat {
+        let mut img = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let v = 40.0 + 180.0 * phi(signed(col as f64, row as f64) / 1.2);
+                img.at_mut(row, col).unwrap()[0] = v.round() as u8;
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_find_edge_subpixel() {
+        let edge_x = 20.37;
+        let img = render(12, 40, |x, _| x - edge_x);
+
+        for method in [EdgeFitMethod::Gaussian, EdgeFitMethod::Moment] {
+            let edge = find_edge_subpixel(&img, Point::new(5, 6), Point::new(35, 6), 0, EdgePolarity::Any, method)
+                .unwrap()
+                .unwrap();
+            assert!((f64::from(edge.position.x) - edge_x).abs() < 0.1, "{method:?}: {:?}", edge.position);
+            assert!((edge.distance - (edge_x - 5.0)).abs() < 0.1);
+            assert!(edge.strength > 40.0);
+        }
+        let gaussian = find_edge_subpixel(&img, Point::new(5, 6), Point::new(35, 6), 0, EdgePolarity::Rising, EdgeFitMethod::Gaussian)
+            .unwrap()
+            .unwrap();
+        assert!((f64::from(gaussian.position.x) - edge_x).abs() < 0.03);
+
+        // Scanning backwards the same edge is falling
+        assert!(find_edge_subpixel(&img, Point::new(35, 6), Point::new(5, 6), 0, EdgePolarity::Rising, EdgeFitMethod::Gaussian)
+            .unwrap()
+            .is_none());
+        let back = find_edge_subpixel(&img, Point::new(35, 6), Point::new(5, 6), 0, EdgePolarity::Falling, EdgeFitMethod::Gaussian)
+            .unwrap()
+            .unwrap();
+        assert!((f64::from(back.position.x) - edge_x).abs() < 0.03);
+        assert!(find_edge_subpixel(&img, Point::new(5, 6), Point::new(35, 6), 1, EdgePolarity::Any, EdgeFitMethod::Gaussian).is_err());
+    }
+
+    #[test]
+    fn test_refine_ellipse_subpixel() {
+        let (cx, cy, a, b) = (40.3, 35.7, 22.0, 14.0);
+        let (sin, cos) = 25f64.to_radians().sin_cos();
+        let img = render(72, 84, |x, y| {
+            let (dx, dy) = (x - cx, y - cy);
+            let (u, v) = (dx * cos + dy * sin, -dx * sin + dy * cos);
+            // First-order signed distance to the outline
+            let f = (u / a).powi(2) + (v / b).powi(2) - 1.0;
+            let grad = 2.0 * ((u / (a * a)).powi(2) + (v / (b * b)).powi(2)).sqrt();
+            -f / grad
+        });
+
+        let coarse = RotatedRect::new(Point2f::new(41.5, 34.5), 40.0, 32.0, 0.0);
+        let refined = refine_ellipse_subpixel(&img, &coarse, 0, EdgeFitMethod::Gaussian, 64).unwrap();
+        assert!((f64::from(refined.center.x) - cx).abs() < 0.1, "{refined:?}");
+        assert!((f64::from(refined.center.y) - cy).abs() < 0.1, "{refined:?}");
+        let (major, minor) = (refined.width.max(refined.height), refined.width.min(refined.height));
+        assert!((f64::from(major) - 2.0 * a).abs() < 0.2 && (f64::from(minor) - 2.0 * b).abs() < 0.2, "{refined:?}");
+
+        assert!(refine_ellipse_subpixel(&img, &coarse, 0, EdgeFitMethod::Gaussian, 4).is_err());
+    }
+}
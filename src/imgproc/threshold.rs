@@ -19,7 +19,7 @@ pub fn threshold(
         ));
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     // Clamp and convert f64 threshold values to u8 range
     let thresh_u8 = thresh.clamp(0.0, 255.0);
@@ -143,7 +143,7 @@ pub fn adaptive_threshold(
         }
     }
 
-    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?;
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
 
     let rows = src.rows();
     let cols = src.cols();
@@ -0,0 +1,189 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::parallel::{parallel_for_tiles, Tile};
+use crate::core::types::Rect;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use super::sampling::{read_element, write_element};
+
+/// Run `f` over overlapping tiles of `src` and stitch the results
+///
+/// `src` is split into `tile_size` x `tile_size` tiles, each grown by
+/// `overlap` pixels on every side that has a neighbour, and `f` is called on
+/// a copy of each grown tile, in parallel where the scheduler allows. `f`
+/// must return a Mat of the same size as its input; channel count and depth
+/// may differ from `src` but must be the same for every tile. Across each
+/// seam the two results are cross-faded over the central `overlap` pixels
+/// of the shared region, ignoring the outer `overlap / 2` pixels of each
+/// tile, so neighbourhood operations with a radius up to `overlap / 2` give
+/// the same result as on the whole image and tile borders do not show.
+///
+/// Tiles are processed one row of tiles at a time, so peak memory is about
+/// one output image plus one band of tiles, however much the closure
+/// allocates per tile.
+pub fn process_tiled<F>(src: &Mat, tile_size: usize, overlap: usize, f: F) -> Result<Mat>
+where
+    F: Fn(&Mat) -> Result<Mat> + Sync + Send,
+{
+    if tile_size == 0 {
+        return Err(Error::InvalidParameter(
+            "Tile size must be positive".to_string(),
+        ));
+    }
+    let (rows, cols) = (src.rows(), src.cols());
+
+    // Weighted sums, allocated once the output type is known
+    let mut accum: Vec<f64> = Vec::new();
+    let mut weights = vec![0.0f64; rows * cols];
+    let mut out_type: Option<(usize, MatDepth)> = None;
+
+    for band_row in (0..rows).step_by(tile_size) {
+        let band_height = tile_size.min(rows - band_row);
+        let results = parallel_for_tiles(band_height, cols, band_height, tile_size, |tile| {
+            let core = Tile { row: tile.row + band_row, ..tile };
+            let grown = grow(core, overlap, rows, cols);
+            let input = src.roi(Rect::new(grown.col as i32, grown.row as i32, grown.width as i32, grown.height as i32))?;
+            let output = f(&input)?;
+            if output.rows() != grown.height || output.cols() != grown.width {
+                return Err(Error::InvalidDimensions(format!(
+                    "Tile closure returned {}x{} for a {}x{} tile",
+                    output.cols(),
+                    output.rows(),
+                    grown.width,
+                    grown.height
+                )));
+            }
+            Ok((core, grown, output))
+        })?;
+
+        for (core, grown, output) in results {
+            let (channels, depth) = *out_type.get_or_insert((output.channels(), output.depth()));
+            if (output.channels(), output.depth()) != (channels, depth) {
+                return Err(Error::InvalidParameter(
+                    "Tile closure must return the same channel count and depth for every tile".to_string(),
+                ));
+            }
+            if accum.is_empty() {
+                accum = vec![0.0; rows * cols * channels];
+            }
+
+            let wy = ramp(core.row, core.height, grown.row, grown.height, overlap, rows);
+            let wx = ramp(core.col, core.width, grown.col, grown.width, overlap, cols);
+            let elem = depth.size();
+            let data = output.data();
+            for (y, &weight_y) in wy.iter().enumerate() {
+                for (x, &weight_x) in wx.iter().enumerate() {
+                    let w = weight_y * weight_x;
+                    let dst = (grown.row + y) * cols + grown.col + x;
+                    weights[dst] += w;
+                    let src_px = (y * grown.width + x) * channels;
+                    for ch in 0..channels {
+                        let offset = (src_px + ch) * elem;
+                        accum[dst * channels + ch] += w * read_element(&data[offset..offset + elem], depth);
+                    }
+                }
+            }
+        }
+    }
+
+    let Some((channels, depth)) = out_type else {
+        return Mat::new(rows, cols, src.channels(), src.depth());
+    };
+    let mut out = Mat::new(rows, cols, channels, depth)?;
+    let elem = depth.size();
+    let data = out.data_mut();
+    for (i, &w) in weights.iter().enumerate() {
+        for ch in 0..channels {
+            let value = accum[i * channels + ch] / w;
+            let offset = (i * channels + ch) * elem;
+            write_element(&mut data[offset..offset + elem], depth, value);
+        }
+    }
+    Ok(out)
+}
+
+/// `tile` grown by `overlap` on each side, clamped to the image
+fn grow(tile: Tile, overlap: usize, rows: usize, cols: usize) -> Tile {
+    let row = tile.row.saturating_sub(overlap);
+    let col = tile.col.saturating_sub(overlap);
+    Tile {
+        row,
+        col,
+        height: (tile.row + tile.height + overlap).min(rows) - row,
+        width: (tile.col + tile.width + overlap).min(cols) - col,
+    }
+}
+
+/// Blend weights along one axis of a grown tile: 1 inside, ramping linearly
+/// across the `overlap`-wide band centred on each edge shared with a
+/// neighbouring tile, and 0 beyond it
+fn ramp(core_start: usize, core_len: usize, grown_start: usize, grown_len: usize, overlap: usize, extent: usize) -> Vec<f64> {
+    let band = overlap as f64;
+    let core_end = core_start + core_len;
+    (grown_start..grown_start + grown_len)
+        .map(|i| {
+            let p = i as f64 + 0.5;
+            let mut w = 1.0f64;
+            if overlap > 0 && core_start > 0 {
+                w = w.min((p - (core_start as f64 - band / 2.0)) / band);
+            }
+            if overlap > 0 && core_end < extent {
+                w = w.min((core_end as f64 + band / 2.0 - p) / band);
+            }
+            w.clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imgproc::blur;
+    use crate::core::types::Size;
+
+    fn gradient(rows: usize, cols: usize) -> Mat {
+        let mut img = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let px = img.at_mut(row, col).unwrap();
+                px[0] = ((row * 3 + col) % 256) as u8;
+                px[1] = ((col * col) % 256) as u8;
+                px[2] = if (row / 5 + col / 7).is_multiple_of(2) { 40 } else { 210 };
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_process_tiled_matches_whole_image() {
+        let src = gradient(70, 93);
+        let box_blur = |m: &Mat| {
+            let mut out = Mat::new(1, 1, 1, MatDepth::U8)?;
+            blur(m, &mut out, Size::new(5, 5))?;
+            Ok(out)
+        };
+        let whole = box_blur(&src).unwrap();
+
+        // With enough overlap for the kernel radius, seams are invisible
+        let tiled = process_tiled(&src, 32, 6, box_blur).unwrap();
+        assert_eq!((tiled.rows(), tiled.cols(), tiled.channels()), (70, 93, 3));
+        let max_diff = whole.data().iter().zip(tiled.data()).map(|(&a, &b)| a.abs_diff(b)).max().unwrap();
+        assert!(max_diff <= 1, "max difference {max_diff}");
+
+        // Output type can differ from the input
+        let gray = process_tiled(&src, 16, 4, |m| {
+            let mut g = Mat::new(m.rows(), m.cols(), 1, MatDepth::F32)?;
+            for row in 0..m.rows() {
+                for col in 0..m.cols() {
+                    g.set_f32(row, col, 0, f32::from(m.at(row, col)?[2]))?;
+                }
+            }
+            Ok(g)
+        })
+        .unwrap();
+        assert_eq!(gray.depth(), MatDepth::F32);
+        assert!((gray.at_f32(12, 40, 0).unwrap() - f32::from(src.at(12, 40).unwrap()[2])).abs() < 1e-4);
+
+        assert!(process_tiled(&src, 0, 4, box_blur).is_err());
+        assert!(process_tiled(&src, 16, 4, |_| Mat::new(1, 1, 1, MatDepth::U8)).is_err());
+    }
+}
@@ -11,6 +11,18 @@
 //! - **Image I/O**: Reading and writing images in various formats
 //! - **Image Processing**: Color conversion, filtering, geometric transformations
 //! - **Thresholding**: Binary and adaptive thresholding
+//! - **Parity**: Per-operation tolerances against `OpenCV` ([`parity::report`])
+//!
+//! ## Cargo features
+//!
+//! Everything is enabled by default via the `full` feature. Each top-level
+//! module can also be selected on its own through a feature of the same name
+//! (`imgproc` is `imgproc-core`); features pull in the modules they depend on.
+//! `core`, `imgcodecs` and `parity` are always available. See
+//! `docs/design/feature-flags.md` for the dependency graph.
+//!
+//! `alloc-tracking` installs [`core::TrackingAllocator`] as the global
+//! allocator so [`core::allocation_stats`] attributes heap use per module.
 
 // Allow unused code - many modules have stub/incomplete implementations
 #![allow(unused)]
@@ -39,22 +51,52 @@
 pub mod core;
 pub mod error;
 pub mod imgcodecs;
+pub mod parity;
+
+#[cfg(feature = "imgproc-core")]
 pub mod imgproc;
+#[cfg(feature = "features2d")]
 pub mod features2d;
+#[cfg(feature = "video")]
 pub mod video;
+#[cfg(feature = "videoio")]
 pub mod videoio;
+#[cfg(feature = "ml")]
 pub mod ml;
+#[cfg(feature = "objdetect")]
 pub mod objdetect;
+#[cfg(feature = "photo")]
 pub mod photo;
+#[cfg(feature = "calib3d")]
 pub mod calib3d;
+#[cfg(feature = "dnn")]
 pub mod dnn;
+#[cfg(feature = "features2d")]
 pub mod flann;
+#[cfg(feature = "stitching")]
 pub mod stitching;
+#[cfg(feature = "shape")]
 pub mod shape;
+#[cfg(feature = "img-hash")]
+pub mod img_hash;
+#[cfg(feature = "text")]
+pub mod text;
+#[cfg(feature = "augment")]
+pub mod augment;
+#[cfg(feature = "analytics")]
+pub mod analytics;
+#[cfg(feature = "gapi")]
+pub mod gapi;
+#[cfg(feature = "testutils")]
+pub mod testutils;
 
 #[cfg(feature = "gpu")]
 pub mod gpu;
 
+#[cfg(feature = "alloc-tracking")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: core::TrackingAllocator = core::TrackingAllocator::system();
+
 #[cfg(all(target_arch = "wasm32", feature = "wasm-bindgen"))]
 pub mod wasm;
 
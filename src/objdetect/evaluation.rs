@@ -0,0 +1,335 @@
+#![allow(clippy::cast_precision_loss)]
+use std::collections::BTreeMap;
+
+use crate::core::types::{Point2f, Rect, RotatedRect};
+
+/// Intersection over union of two axis-aligned rectangles
+#[must_use]
+pub fn rect_iou(a: &Rect, b: &Rect) -> f64 {
+    let x0 = a.x.max(b.x);
+    let y0 = a.y.max(b.y);
+    let x1 = (a.x + a.width).min(b.x + b.width);
+    let y1 = (a.y + a.height).min(b.y + b.height);
+
+    let intersection = f64::from((x1 - x0).max(0)) * f64::from((y1 - y0).max(0));
+    let union = f64::from(a.area()) + f64::from(b.area()) - intersection;
+
+    if union > 0.0 {
+        intersection / union
+    } else {
+        0.0
+    }
+}
+
+/// Intersection over union of two rotated rectangles
+#[must_use]
+pub fn rotated_rect_iou(a: &RotatedRect, b: &RotatedRect) -> f64 {
+    polygon_iou(&a.points(), &b.points())
+}
+
+/// Intersection over union of two convex polygons
+///
+/// Vertices may be given in either winding order. Returns 0 for degenerate
+/// polygons (fewer than 3 vertices or zero area).
+#[must_use]
+pub fn polygon_iou(a: &[Point2f], b: &[Point2f]) -> f64 {
+    let area_a = polygon_area(a);
+    let area_b = polygon_area(b);
+    if area_a <= 0.0 || area_b <= 0.0 {
+        return 0.0;
+    }
+
+    let intersection = polygon_area(&convex_intersection(a, b));
+    let union = area_a + area_b - intersection;
+
+    if union > 0.0 {
+        (intersection / union).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Area enclosed by a simple polygon (shoelace formula)
+#[must_use]
+pub fn polygon_area(polygon: &[Point2f]) -> f64 {
+    signed_area(polygon).abs()
+}
+
+fn signed_area(polygon: &[Point2f]) -> f64 {
+    if polygon.len() < 3 {
+        return 0.0;
+    }
+
+    let n = polygon.len();
+    let twice: f64 = (0..n)
+        .map(|i| {
+            let p = polygon[i];
+            let q = polygon[(i + 1) % n];
+            f64::from(p.x) * f64::from(q.y) - f64::from(q.x) * f64::from(p.y)
+        })
+        .sum();
+    twice / 2.0
+}
+
+/// Sutherland-Hodgman clipping of `subject` by the convex polygon `clip`
+fn convex_intersection(subject: &[Point2f], clip: &[Point2f]) -> Vec<Point2f> {
+    let to_f64 = |p: &Point2f| (f64::from(p.x), f64::from(p.y));
+    let reversed = signed_area(clip) < 0.0;
+    let mut clip: Vec<(f64, f64)> = clip.iter().map(to_f64).collect();
+    // Wind the clip polygon so that its interior is left of every edge
+    if reversed {
+        clip.reverse();
+    }
+
+    let mut output: Vec<(f64, f64)> = subject.iter().map(to_f64).collect();
+    let n = clip.len();
+
+    for i in 0..n {
+        if output.is_empty() {
+            break;
+        }
+
+        let (ax, ay) = clip[i];
+        let (bx, by) = clip[(i + 1) % n];
+        let side = |p: (f64, f64)| (bx - ax) * (p.1 - ay) - (by - ay) * (p.0 - ax);
+
+        let input = std::mem::take(&mut output);
+        for (j, &current) in input.iter().enumerate() {
+            let previous = input[(j + input.len() - 1) % input.len()];
+            let (sc, sp) = (side(current), side(previous));
+
+            if sc >= 0.0 {
+                if sp < 0.0 {
+                    output.push(crossing(previous, current, sp, sc));
+                }
+                output.push(current);
+            } else if sp >= 0.0 {
+                output.push(crossing(previous, current, sp, sc));
+            }
+        }
+    }
+
+    clip_points(&output)
+}
+
+fn crossing(p: (f64, f64), q: (f64, f64), sp: f64, sq: f64) -> (f64, f64) {
+    let t = sp / (sp - sq);
+    (p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn clip_points(points: &[(f64, f64)]) -> Vec<Point2f> {
+    points.iter().map(|&(x, y)| Point2f::new(x as f32, y as f32)).collect()
+}
+
+/// A scored detection, as produced by a detector
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub rect: Rect,
+    pub class_id: usize,
+    pub score: f32,
+}
+
+impl Detection {
+    #[must_use]
+    pub fn new(rect: Rect, class_id: usize, score: f32) -> Self {
+        Self { rect, class_id, score }
+    }
+}
+
+/// An annotated object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroundTruth {
+    pub rect: Rect,
+    pub class_id: usize,
+}
+
+impl GroundTruth {
+    #[must_use]
+    pub fn new(rect: Rect, class_id: usize) -> Self {
+        Self { rect, class_id }
+    }
+}
+
+/// Accumulates detections over a dataset and reports average precision
+///
+/// Detections are matched to ground truth per image and class, highest score
+/// first; a detection is a true positive when its IoU with a not yet matched
+/// ground-truth box reaches the threshold. AP is the area under the
+/// interpolated precision/recall curve (the all-point PASCAL VOC definition).
+#[derive(Debug, Clone)]
+pub struct DetectionEvaluator {
+    iou_threshold: f64,
+    /// Per class: (score, true positive) of every detection
+    results: BTreeMap<usize, Vec<(f32, bool)>>,
+    /// Per class: number of ground-truth objects
+    positives: BTreeMap<usize, usize>,
+}
+
+impl DetectionEvaluator {
+    /// Evaluator matching at the given IoU (0.5 for PASCAL VOC)
+    #[must_use]
+    pub fn new(iou_threshold: f64) -> Self {
+        Self {
+            iou_threshold,
+            results: BTreeMap::new(),
+            positives: BTreeMap::new(),
+        }
+    }
+
+    /// Add the detections and annotations of one image
+    pub fn add_image(&mut self, detections: &[Detection], ground_truth: &[GroundTruth]) {
+        for gt in ground_truth {
+            *self.positives.entry(gt.class_id).or_insert(0) += 1;
+        }
+
+        let mut order: Vec<&Detection> = detections.iter().collect();
+        order.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut matched = vec![false; ground_truth.len()];
+        for det in order {
+            let best = ground_truth
+                .iter()
+                .enumerate()
+                .filter(|(i, gt)| gt.class_id == det.class_id && !matched[*i])
+                .map(|(i, gt)| (i, rect_iou(&det.rect, &gt.rect)))
+                .filter(|&(_, iou)| iou >= self.iou_threshold)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((i, _)) = best {
+                matched[i] = true;
+            }
+            self.results.entry(det.class_id).or_default().push((det.score, best.is_some()));
+        }
+    }
+
+    /// Average precision for one class, `None` if it has no ground truth
+    #[must_use]
+    pub fn average_precision(&self, class_id: usize) -> Option<f64> {
+        let positives = *self.positives.get(&class_id)?;
+        if positives == 0 {
+            return None;
+        }
+
+        let mut results = self.results.get(&class_id).cloned().unwrap_or_default();
+        results.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut recall = Vec::with_capacity(results.len());
+        let mut precision = Vec::with_capacity(results.len());
+        let mut tp = 0usize;
+        for (i, &(_, is_tp)) in results.iter().enumerate() {
+            if is_tp {
+                tp += 1;
+            }
+            recall.push(tp as f64 / positives as f64);
+            precision.push(tp as f64 / (i + 1) as f64);
+        }
+
+        // Precision envelope: best precision at this recall or any higher one
+        for i in (0..precision.len().saturating_sub(1)).rev() {
+            precision[i] = precision[i].max(precision[i + 1]);
+        }
+
+        let mut ap = 0.0;
+        let mut previous_recall = 0.0;
+        for (r, p) in recall.iter().zip(&precision) {
+            ap += (r - previous_recall) * p;
+            previous_recall = *r;
+        }
+
+        Some(ap)
+    }
+
+    /// Mean of the per-class average precisions over classes with ground truth
+    #[must_use]
+    pub fn mean_average_precision(&self) -> f64 {
+        let aps: Vec<f64> = self
+            .positives
+            .keys()
+            .filter_map(|&c| self.average_precision(c))
+            .collect();
+
+        if aps.is_empty() {
+            0.0
+        } else {
+            aps.iter().sum::<f64>() / aps.len() as f64
+        }
+    }
+}
+
+impl Default for DetectionEvaluator {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_iou() {
+        let a = Rect::new(0, 0, 10, 10);
+        assert!((rect_iou(&a, &a) - 1.0).abs() < 1e-12);
+        assert!((rect_iou(&a, &Rect::new(5, 0, 10, 10)) - 50.0 / 150.0).abs() < 1e-12);
+        assert_eq!(rect_iou(&a, &Rect::new(20, 20, 5, 5)), 0.0);
+    }
+
+    #[test]
+    fn test_rotated_rect_iou() {
+        let a = RotatedRect::new(Point2f::new(0.0, 0.0), 2.0, 2.0, 0.0);
+        let b = RotatedRect::new(Point2f::new(0.0, 0.0), 2.0, 2.0, 45.0);
+        // Square and the same square rotated 45°: the overlap is a regular octagon
+        let octagon = 8.0 * (std::f64::consts::SQRT_2 - 1.0);
+        let expected = octagon / (8.0 - octagon);
+        assert!((rotated_rect_iou(&a, &b) - expected).abs() < 1e-5);
+
+        let shifted = RotatedRect::new(Point2f::new(1.0, 0.0), 2.0, 2.0, 90.0);
+        assert!((rotated_rect_iou(&a, &shifted) - 1.0 / 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_polygon_iou_winding_and_degenerate() {
+        let square = [
+            Point2f::new(0.0, 0.0),
+            Point2f::new(4.0, 0.0),
+            Point2f::new(4.0, 4.0),
+            Point2f::new(0.0, 4.0),
+        ];
+        let triangle = [Point2f::new(0.0, 0.0), Point2f::new(0.0, 4.0), Point2f::new(4.0, 0.0)];
+
+        assert!((polygon_area(&triangle) - 8.0).abs() < 1e-9);
+        assert!((polygon_iou(&square, &triangle) - 0.5).abs() < 1e-6);
+        assert!((polygon_iou(&triangle, &square) - 0.5).abs() < 1e-6);
+        assert_eq!(polygon_iou(&square, &square[..2]), 0.0);
+    }
+
+    #[test]
+    fn test_average_precision() {
+        let mut eval = DetectionEvaluator::new(0.5);
+
+        let gt = [GroundTruth::new(Rect::new(0, 0, 10, 10), 0), GroundTruth::new(Rect::new(50, 50, 10, 10), 0)];
+        let dets = [
+            Detection::new(Rect::new(1, 1, 10, 10), 0, 0.9),
+            // False positive with a higher score than the second hit
+            Detection::new(Rect::new(100, 100, 10, 10), 0, 0.8),
+            Detection::new(Rect::new(50, 50, 10, 10), 0, 0.7),
+            // Duplicate of the first object
+            Detection::new(Rect::new(0, 0, 10, 10), 0, 0.6),
+        ];
+        eval.add_image(&dets, &gt);
+
+        // Recall 0.5 at precision 1, recall 1.0 at precision 2/3
+        let ap = eval.average_precision(0).unwrap();
+        assert!((ap - (0.5 + 0.5 * 2.0 / 3.0)).abs() < 1e-9, "ap {ap}");
+
+        // A second class detected perfectly
+        eval.add_image(
+            &[Detection::new(Rect::new(0, 0, 8, 8), 1, 0.5)],
+            &[GroundTruth::new(Rect::new(0, 0, 8, 8), 1)],
+        );
+        assert!((eval.average_precision(1).unwrap() - 1.0).abs() < 1e-12);
+        assert!((eval.mean_average_precision() - (ap + 1.0) / 2.0).abs() < 1e-9);
+        assert_eq!(eval.average_precision(7), None);
+    }
+}
@@ -0,0 +1,715 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Facial landmark fitting with an ensemble of regression trees
+//!
+//! [`Facemark`] refines a mean face shape inside a detected face box through
+//! a cascade of stages (Kazemi & Sullivan, "One Millisecond Face Alignment
+//! with an Ensemble of Regression Trees", 2014). Each stage reads pixel
+//! intensities at points anchored to the current landmark estimate and sums
+//! the shape updates stored in the leaves of its gradient-boosted trees.
+//!
+//! Landmarks are expressed relative to the face box, so the model is
+//! invariant to face position and scale but not to in-plane rotation. Any
+//! number of landmarks is supported; the usual iBUG 300-W annotation has
+//! [`FACEMARK_68_LANDMARKS`] points. Models are trained with
+//! [`FacemarkTrainer`] and stored with [`Facemark::save`] /
+//! [`Facemark::load`].
+
+use crate::core::types::{BorderType, InterpolationFlag, Point2f, Rect};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::noise::SplitMix64;
+use crate::imgproc::PixelSampler;
+use std::path::Path;
+
+/// Number of points in the iBUG 300-W / dlib face annotation
+pub const FACEMARK_68_LANDMARKS: usize = 68;
+
+const MAGIC: &[u8; 4] = b"FMRK";
+const FORMAT_VERSION: u32 = 1;
+
+/// A training image with one annotated face
+#[derive(Debug, Clone)]
+pub struct FaceSample {
+    /// Grayscale or 3-channel U8 image
+    pub image: Mat,
+    /// Face box as returned by the face detector used at run time
+    pub face: Rect,
+    /// Landmark positions in image pixels
+    pub landmarks: Vec<Point2f>,
+}
+
+/// Trained landmark regression model
+#[derive(Debug, Clone)]
+pub struct Facemark {
+    /// Mean shape, interleaved `x, y` in face-box units
+    mean_shape: Vec<f32>,
+    stages: Vec<Stage>,
+}
+
+/// Pixels read by one cascade stage, and the trees that use them
+#[derive(Debug, Clone)]
+struct Stage {
+    features: Vec<ShapeFeature>,
+    trees: Vec<RegressionTree>,
+}
+
+/// A pixel at a fixed offset (face-box units) from one landmark
+#[derive(Debug, Clone, Copy)]
+struct ShapeFeature {
+    anchor: usize,
+    dx: f32,
+    dy: f32,
+}
+
+/// Complete binary tree; node `i` has children `2i + 1` and `2i + 2`
+#[derive(Debug, Clone)]
+struct RegressionTree {
+    splits: Vec<Split>,
+    /// `2^depth` shape increments, each `2 * landmarks` long
+    leaves: Vec<Vec<f32>>,
+}
+
+/// Go right when `pixel[a] - pixel[b] > threshold`
+#[derive(Debug, Clone, Copy)]
+struct Split {
+    a: usize,
+    b: usize,
+    threshold: f32,
+}
+
+/// Intensity lookup with clamped coordinates
+/// Channel-averaged F32 copy of the input image
+struct GrayImage(Mat);
+
+impl Facemark {
+    /// Number of landmarks the model predicts
+    #[must_use]
+    pub fn landmark_count(&self) -> usize {
+        self.mean_shape.len() / 2
+    }
+
+    /// Mean shape placed inside `face`
+    #[must_use]
+    pub fn mean_shape(&self, face: Rect) -> Vec<Point2f> {
+        to_image_points(&self.mean_shape, face)
+    }
+
+    /// Fit the landmarks of the face inside `face`
+    pub fn fit(&self, image: &Mat, face: Rect) -> Result<Vec<Point2f>> {
+        check_face(face)?;
+        let gray = GrayImage::from_mat(image)?;
+        let mut shape = self.mean_shape.clone();
+        for stage in &self.stages {
+            let pixels = stage.sample(&gray, &shape, face)?;
+            for tree in &stage.trees {
+                for (s, d) in shape.iter_mut().zip(tree.leaf(&pixels)) {
+                    *s += d;
+                }
+            }
+        }
+        Ok(to_image_points(&shape, face))
+    }
+
+    /// Fit every face in `faces`, in order
+    pub fn fit_all(&self, image: &Mat, faces: &[Rect]) -> Result<Vec<Vec<Point2f>>> {
+        faces.iter().map(|&face| self.fit(image, face)).collect()
+    }
+
+    /// Serialize the model to a compact little-endian binary format
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        put_u32(&mut out, FORMAT_VERSION);
+        put_u32(&mut out, self.landmark_count() as u32);
+        self.mean_shape.iter().for_each(|&v| put_f32(&mut out, v));
+
+        put_u32(&mut out, self.stages.len() as u32);
+        for stage in &self.stages {
+            put_u32(&mut out, stage.features.len() as u32);
+            for f in &stage.features {
+                put_u32(&mut out, f.anchor as u32);
+                put_f32(&mut out, f.dx);
+                put_f32(&mut out, f.dy);
+            }
+            put_u32(&mut out, stage.trees.len() as u32);
+            for tree in &stage.trees {
+                put_u32(&mut out, tree.leaves.len().trailing_zeros());
+                for split in &tree.splits {
+                    put_u32(&mut out, split.a as u32);
+                    put_u32(&mut out, split.b as u32);
+                    put_f32(&mut out, split.threshold);
+                }
+                tree.leaves.iter().flatten().for_each(|&v| put_f32(&mut out, v));
+            }
+        }
+        out
+    }
+
+    /// Parse a model written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(Error::InvalidFormat("Not a facemark model".to_string()));
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(Error::InvalidFormat(format!(
+                "Unsupported facemark model version {version}"
+            )));
+        }
+
+        let landmarks = reader.count()?;
+        let shape_len = 2 * landmarks;
+        let mean_shape = reader.f32s(shape_len)?;
+
+        let mut stages = Vec::new();
+        for _ in 0..reader.count()? {
+            let mut features = Vec::new();
+            for _ in 0..reader.count()? {
+                let anchor = reader.count()?;
+                if anchor >= landmarks {
+                    return Err(Error::InvalidFormat("Feature anchor out of range".to_string()));
+                }
+                features.push(ShapeFeature { anchor, dx: reader.f32()?, dy: reader.f32()? });
+            }
+
+            let mut trees = Vec::new();
+            for _ in 0..reader.count()? {
+                let depth = reader.count()?;
+                if depth > 16 {
+                    return Err(Error::InvalidFormat("Tree too deep".to_string()));
+                }
+                let mut splits = Vec::new();
+                for _ in 0..(1usize << depth) - 1 {
+                    let (a, b) = (reader.count()?, reader.count()?);
+                    if a >= features.len() || b >= features.len() {
+                        return Err(Error::InvalidFormat("Split feature out of range".to_string()));
+                    }
+                    splits.push(Split { a, b, threshold: reader.f32()? });
+                }
+                let leaves = (0..1usize << depth)
+                    .map(|_| reader.f32s(shape_len))
+                    .collect::<Result<_>>()?;
+                trees.push(RegressionTree { splits, leaves });
+            }
+            stages.push(Stage { features, trees });
+        }
+
+        if reader.pos != bytes.len() {
+            return Err(Error::InvalidFormat("Trailing bytes after facemark model".to_string()));
+        }
+        Ok(Self { mean_shape, stages })
+    }
+
+    /// Write the model to `path`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Read a model from `path`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+/// Trains a [`Facemark`] model from annotated faces
+#[derive(Debug, Clone)]
+pub struct FacemarkTrainer {
+    stages: usize,
+    trees_per_stage: usize,
+    tree_depth: usize,
+    feature_pool_size: usize,
+    split_tests: usize,
+    oversampling: usize,
+    learning_rate: f32,
+    feature_radius: f32,
+    seed: u64,
+}
+
+impl Default for FacemarkTrainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FacemarkTrainer {
+    /// Defaults close to the ERT paper: 10 stages of 500 depth-4 trees, a
+    /// 400-pixel feature pool, 20 split candidates per node, 20 initial
+    /// shapes per face and a learning rate of 0.1
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stages: 10,
+            trees_per_stage: 500,
+            tree_depth: 4,
+            feature_pool_size: 400,
+            split_tests: 20,
+            oversampling: 20,
+            learning_rate: 0.1,
+            feature_radius: 0.1,
+            seed: 0,
+        }
+    }
+
+    /// Number of cascade stages
+    #[must_use]
+    pub fn with_stages(mut self, stages: usize) -> Self {
+        self.stages = stages;
+        self
+    }
+
+    /// Number of boosted trees per stage
+    #[must_use]
+    pub fn with_trees_per_stage(mut self, trees: usize) -> Self {
+        self.trees_per_stage = trees;
+        self
+    }
+
+    /// Depth of each tree (`2^depth` leaves)
+    #[must_use]
+    pub fn with_tree_depth(mut self, depth: usize) -> Self {
+        self.tree_depth = depth;
+        self
+    }
+
+    /// Number of shape-indexed pixels sampled per stage
+    #[must_use]
+    pub fn with_feature_pool_size(mut self, size: usize) -> Self {
+        self.feature_pool_size = size;
+        self
+    }
+
+    /// Random split candidates evaluated at each tree node
+    #[must_use]
+    pub fn with_split_tests(mut self, tests: usize) -> Self {
+        self.split_tests = tests;
+        self
+    }
+
+    /// Initial shapes generated per training face
+    #[must_use]
+    pub fn with_oversampling(mut self, oversampling: usize) -> Self {
+        self.oversampling = oversampling;
+        self
+    }
+
+    /// Shrinkage applied to every leaf
+    #[must_use]
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Largest feature offset from its anchor landmark, in face-box units
+    #[must_use]
+    pub fn with_feature_radius(mut self, radius: f32) -> Self {
+        self.feature_radius = radius;
+        self
+    }
+
+    /// Seed for feature sampling, split candidates and initial shapes
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Train a model on `samples`
+    pub fn train(&self, samples: &[FaceSample]) -> Result<Facemark> {
+        let landmarks = self.check_samples(samples)?;
+        let shape_len = 2 * landmarks;
+
+        let images = samples
+            .iter()
+            .map(|s| GrayImage::from_mat(&s.image))
+            .collect::<Result<Vec<_>>>()?;
+        let targets: Vec<Vec<f32>> = samples
+            .iter()
+            .map(|s| to_face_units(&s.landmarks, s.face))
+            .collect();
+
+        let mut mean_shape = vec![0.0f32; shape_len];
+        for target in &targets {
+            for (m, t) in mean_shape.iter_mut().zip(target) {
+                *m += t / targets.len() as f32;
+            }
+        }
+
+        // Each face is trained from the mean shape and from the shapes of
+        // other faces, so the cascade learns to correct realistic errors
+        let mut rng = SplitMix64::new(self.seed);
+        let mut instances: Vec<(usize, Vec<f32>)> = Vec::new();
+        for i in 0..samples.len() {
+            instances.push((i, mean_shape.clone()));
+            for _ in 1..self.oversampling {
+                let mut other = rng.next_u64() as usize % samples.len();
+                if other == i && samples.len() > 1 {
+                    other = (other + 1) % samples.len();
+                }
+                instances.push((i, targets[other].clone()));
+            }
+        }
+
+        let mut stages = Vec::with_capacity(self.stages);
+        for _ in 0..self.stages {
+            let features: Vec<ShapeFeature> = (0..self.feature_pool_size)
+                .map(|_| ShapeFeature {
+                    anchor: rng.next_u64() as usize % landmarks,
+                    dx: (rng.next_f64() as f32 * 2.0 - 1.0) * self.feature_radius,
+                    dy: (rng.next_f64() as f32 * 2.0 - 1.0) * self.feature_radius,
+                })
+                .collect();
+            let stage = Stage { features, trees: Vec::new() };
+
+            let pixels: Vec<Vec<f32>> = instances
+                .iter()
+                .map(|(i, shape)| stage.sample(&images[*i], shape, samples[*i].face))
+                .collect::<Result<_>>()?;
+            let mut residuals: Vec<Vec<f32>> = instances
+                .iter()
+                .map(|(i, shape)| targets[*i].iter().zip(shape).map(|(t, s)| t - s).collect())
+                .collect();
+
+            let mut trees = Vec::with_capacity(self.trees_per_stage);
+            for _ in 0..self.trees_per_stage {
+                let tree = self.fit_tree(&pixels, &residuals, shape_len, &mut rng);
+                for ((_, shape), (p, r)) in instances.iter_mut().zip(pixels.iter().zip(&mut residuals)) {
+                    for ((s, r), d) in shape.iter_mut().zip(r.iter_mut()).zip(tree.leaf(p)) {
+                        *s += d;
+                        *r -= d;
+                    }
+                }
+                trees.push(tree);
+            }
+            stages.push(Stage { trees, ..stage });
+        }
+
+        Ok(Facemark { mean_shape, stages })
+    }
+
+    fn check_samples(&self, samples: &[FaceSample]) -> Result<usize> {
+        if self.stages == 0
+            || self.trees_per_stage == 0
+            || self.tree_depth == 0
+            || self.tree_depth > 16
+            || self.feature_pool_size < 2
+            || self.split_tests == 0
+            || self.oversampling == 0
+        {
+            return Err(Error::InvalidParameter(
+                "Trainer needs at least one stage, tree, split test and initial shape, \
+                 a tree depth of 1-16 and two pool features"
+                    .to_string(),
+            ));
+        }
+
+        let Some(first) = samples.first() else {
+            return Err(Error::InvalidParameter("No training samples".to_string()));
+        };
+        let landmarks = first.landmarks.len();
+        if landmarks == 0 {
+            return Err(Error::InvalidParameter("Samples have no landmarks".to_string()));
+        }
+        for sample in samples {
+            if sample.landmarks.len() != landmarks {
+                return Err(Error::InvalidParameter(
+                    "All samples must have the same number of landmarks".to_string(),
+                ));
+            }
+            check_face(sample.face)?;
+        }
+        Ok(landmarks)
+    }
+
+    /// Grow one depth-first regression tree on the current residuals
+    fn fit_tree(&self, pixels: &[Vec<f32>], residuals: &[Vec<f32>], shape_len: usize, rng: &mut SplitMix64) -> RegressionTree {
+        let pool = pixels[0].len();
+        let node_count = (1usize << self.tree_depth) - 1;
+        let mut splits = Vec::with_capacity(node_count);
+        // Instance indices reaching each node of the current level
+        let mut level: Vec<Vec<usize>> = vec![(0..pixels.len()).collect()];
+
+        for _ in 0..self.tree_depth {
+            let mut next = Vec::with_capacity(level.len() * 2);
+            for members in &level {
+                let mut best: Option<(f32, Split, Vec<usize>, Vec<usize>)> = None;
+                for _ in 0..self.split_tests {
+                    let a = rng.next_u64() as usize % pool;
+                    let mut b = rng.next_u64() as usize % pool;
+                    if b == a {
+                        b = (b + 1) % pool;
+                    }
+                    // Random intensity-difference threshold, as in the
+                    // ERT paper
+                    let threshold = (rng.next_f64() as f32 * 2.0 - 1.0) * 64.0;
+                    let split = Split { a, b, threshold };
+
+                    let (left, right): (Vec<usize>, Vec<usize>) =
+                        members.iter().partition(|&&i| !split.goes_right(&pixels[i]));
+                    let score = split_score(&left, residuals, shape_len) + split_score(&right, residuals, shape_len);
+                    if best.as_ref().is_none_or(|(s, ..)| score > *s) {
+                        best = Some((score, split, left, right));
+                    }
+                }
+                let (_, split, left, right) = best.unwrap_or_else(|| unreachable!("split_tests > 0"));
+                splits.push(split);
+                next.push(left);
+                next.push(right);
+            }
+            level = next;
+        }
+
+        let leaves = level
+            .iter()
+            .map(|members| {
+                let mut leaf = vec![0.0f32; shape_len];
+                if !members.is_empty() {
+                    for &i in members {
+                        for (l, r) in leaf.iter_mut().zip(&residuals[i]) {
+                            *l += r;
+                        }
+                    }
+                    let scale = self.learning_rate / members.len() as f32;
+                    leaf.iter_mut().for_each(|l| *l *= scale);
+                }
+                leaf
+            })
+            .collect();
+
+        RegressionTree { splits, leaves }
+    }
+}
+
+/// Variance reduction proxy: `|sum of residuals|^2 / count`
+fn split_score(members: &[usize], residuals: &[Vec<f32>], shape_len: usize) -> f32 {
+    if members.is_empty() {
+        return 0.0;
+    }
+    let mut sum = vec![0.0f32; shape_len];
+    for &i in members {
+        for (s, r) in sum.iter_mut().zip(&residuals[i]) {
+            *s += r;
+        }
+    }
+    sum.iter().map(|s| s * s).sum::<f32>() / members.len() as f32
+}
+
+impl Stage {
+    fn sample(&self, image: &GrayImage, shape: &[f32], face: Rect) -> Result<Vec<f32>> {
+        self.features
+            .iter()
+            .map(|f| {
+                let u = shape[2 * f.anchor] + f.dx;
+                let v = shape[2 * f.anchor + 1] + f.dy;
+                image.at(
+                    face.x as f32 + u * face.width as f32,
+                    face.y as f32 + v * face.height as f32,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Split {
+    fn goes_right(&self, pixels: &[f32]) -> bool {
+        pixels[self.a] - pixels[self.b] > self.threshold
+    }
+}
+
+impl RegressionTree {
+    fn leaf(&self, pixels: &[f32]) -> &[f32] {
+        let mut node = 0;
+        while node < self.splits.len() {
+            node = 2 * node + if self.splits[node].goes_right(pixels) { 2 } else { 1 };
+        }
+        &self.leaves[node - self.splits.len()]
+    }
+}
+
+impl GrayImage {
+    fn from_mat(mat: &Mat) -> Result<Self> {
+        if mat.depth() != MatDepth::U8 || !matches!(mat.channels(), 1 | 3) {
+            return Err(Error::UnsupportedOperation(
+                "Facemark requires a 1- or 3-channel U8 image".to_string(),
+            ));
+        }
+        let channels = mat.channels();
+        let mut gray = Mat::new(mat.rows(), mat.cols(), 1, MatDepth::F32)?;
+        for (i, px) in mat.data().chunks_exact(channels).enumerate() {
+            let v = px.iter().map(|&v| f32::from(v)).sum::<f32>() / channels as f32;
+            gray.set_f32(i / mat.cols(), i % mat.cols(), 0, v)?;
+        }
+        Ok(Self(gray))
+    }
+
+    /// Intensity of the pixel nearest `(x, y)`, clamped to the image
+    fn at(&self, x: f32, y: f32) -> Result<f32> {
+        let mut v = [0.0];
+        PixelSampler::new(InterpolationFlag::Nearest, BorderType::Replicate).sample(&self.0, f64::from(x), f64::from(y), &mut v)?;
+        Ok(v[0] as f32)
+    }
+}
+
+fn check_face(face: Rect) -> Result<()> {
+    if face.width <= 0 || face.height <= 0 {
+        return Err(Error::InvalidParameter("Face box must be non-empty".to_string()));
+    }
+    Ok(())
+}
+
+fn to_face_units(points: &[Point2f], face: Rect) -> Vec<f32> {
+    points
+        .iter()
+        .flat_map(|p| {
+            [
+                (p.x - face.x as f32) / face.width as f32,
+                (p.y - face.y as f32) / face.height as f32,
+            ]
+        })
+        .collect()
+}
+
+fn to_image_points(shape: &[f32], face: Rect) -> Vec<Point2f> {
+    shape
+        .chunks_exact(2)
+        .map(|uv| {
+            Point2f::new(
+                face.x as f32 + uv[0] * face.width as f32,
+                face.y as f32 + uv[1] * face.height as f32,
+            )
+        })
+        .collect()
+}
+
+fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put_f32(out: &mut Vec<u8>, v: f32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.bytes.len());
+        let Some(end) = end else {
+            return Err(Error::InvalidFormat("Truncated facemark model".to_string()));
+        };
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn count(&mut self) -> Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn f32(&mut self) -> Result<f32> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn f32s(&mut self, n: usize) -> Result<Vec<f32>> {
+        (0..n).map(|_| self.f32()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dark 64x64 image with a bright dot at each landmark of a randomly
+    /// shifted 5-point "face"
+    fn synthetic_face(rng: &mut SplitMix64) -> FaceSample {
+        let base = [(20.0, 24.0), (44.0, 24.0), (32.0, 34.0), (24.0, 44.0), (40.0, 44.0)];
+        let (shift_x, shift_y) = (rng.next_f64() as f32 * 8.0 - 4.0, rng.next_f64() as f32 * 8.0 - 4.0);
+        let landmarks: Vec<Point2f> = base
+            .iter()
+            .map(|&(x, y)| Point2f::new(x + shift_x, y + shift_y))
+            .collect();
+
+        let mut image = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 0..64 {
+                let glow: f32 = landmarks
+                    .iter()
+                    .map(|p| {
+                        let d2 = (col as f32 - p.x).powi(2) + (row as f32 - p.y).powi(2);
+                        200.0 * (-d2 / 8.0).exp()
+                    })
+                    .sum();
+                image.at_mut(row, col).unwrap()[0] = (30.0 + glow).min(255.0) as u8;
+            }
+        }
+        FaceSample { image, face: Rect::new(8, 8, 48, 48), landmarks }
+    }
+
+    fn mean_error(predicted: &[Point2f], truth: &[Point2f]) -> f32 {
+        predicted
+            .iter()
+            .zip(truth)
+            .map(|(p, t)| (p.x - t.x).hypot(p.y - t.y))
+            .sum::<f32>()
+            / truth.len() as f32
+    }
+
+    #[test]
+    fn test_facemark_train_and_fit() {
+        let mut rng = SplitMix64::new(7);
+        let samples: Vec<FaceSample> = (0..40).map(|_| synthetic_face(&mut rng)).collect();
+        let model = FacemarkTrainer::new()
+            .with_stages(5)
+            .with_trees_per_stage(20)
+            .with_tree_depth(3)
+            .with_feature_pool_size(100)
+            .with_oversampling(5)
+            .train(&samples)
+            .unwrap();
+        assert_eq!(model.landmark_count(), 5);
+
+        let (mut fitted, mut baseline) = (0.0, 0.0);
+        for _ in 0..10 {
+            let test = synthetic_face(&mut rng);
+            let landmarks = model.fit(&test.image, test.face).unwrap();
+            fitted += mean_error(&landmarks, &test.landmarks);
+            baseline += mean_error(&model.mean_shape(test.face), &test.landmarks);
+        }
+        assert!(fitted < 0.5 * baseline, "fitted {fitted} vs mean shape {baseline}");
+    }
+
+    #[test]
+    fn test_facemark_serialization() {
+        let mut rng = SplitMix64::new(3);
+        let samples: Vec<FaceSample> = (0..8).map(|_| synthetic_face(&mut rng)).collect();
+        let model = FacemarkTrainer::new()
+            .with_stages(2)
+            .with_trees_per_stage(3)
+            .with_feature_pool_size(20)
+            .with_oversampling(2)
+            .train(&samples)
+            .unwrap();
+
+        let bytes = model.to_bytes();
+        let restored = Facemark::from_bytes(&bytes).unwrap();
+        let face = Rect::new(8, 8, 48, 48);
+        assert_eq!(
+            model.fit(&samples[0].image, face).unwrap(),
+            restored.fit(&samples[0].image, face).unwrap()
+        );
+
+        assert!(Facemark::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Facemark::from_bytes(b"nope").is_err());
+        assert!(FacemarkTrainer::new().train(&[]).is_err());
+    }
+}
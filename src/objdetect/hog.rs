@@ -4,6 +4,9 @@ use crate::core::types::{Size, Rect};
 use crate::error::{Error, Result};
 use std::f64::consts::PI;
 
+/// Per-pixel gradient magnitudes and orientations, indexed `[row][col]`
+type Gradients = (Vec<Vec<f32>>, Vec<Vec<f32>>);
+
 /// HOG (Histogram of Oriented Gradients) Descriptor
 pub struct HOGDescriptor {
     pub win_size: Size,
@@ -11,6 +14,12 @@ pub struct HOGDescriptor {
     pub block_stride: Size,
     pub cell_size: Size,
     pub nbins: usize,
+    /// Take the square root of intensities before computing gradients, which
+    /// reduces the influence of illumination (Dalal & Triggs)
+    pub gamma_correction: bool,
+    /// Bin orientations over 0–360° instead of 0–180°, keeping the sign of the
+    /// gradient (dark-to-light vs light-to-dark)
+    pub signed_gradient: bool,
 }
 
 impl HOGDescriptor {
@@ -22,40 +31,98 @@ impl HOGDescriptor {
             block_stride: Size::new(8, 8),
             cell_size: Size::new(8, 8),
             nbins: 9,
+            gamma_correction: false,
+            signed_gradient: false,
         }
     }
 
-    /// Compute HOG descriptor for an image
-    pub fn compute(&self, img: &Mat) -> Result<Vec<f32>> {
-        if img.channels() != 1 {
-            return Err(Error::InvalidParameter(
-                "HOG requires grayscale image".to_string(),
-            ));
-        }
+    #[must_use]
+    pub fn with_win_size(mut self, win_size: Size) -> Self {
+        self.win_size = win_size;
+        self
+    }
 
-        // Calculate gradients
-        use crate::imgproc::sobel;
+    #[must_use]
+    pub fn with_block_size(mut self, block_size: Size) -> Self {
+        self.block_size = block_size;
+        self
+    }
 
-        let mut grad_x = Mat::new(1, 1, 1, MatDepth::U8)?;
-        let mut grad_y = Mat::new(1, 1, 1, MatDepth::U8)?;
+    #[must_use]
+    pub fn with_block_stride(mut self, block_stride: Size) -> Self {
+        self.block_stride = block_stride;
+        self
+    }
 
-        sobel(img, &mut grad_x, 1, 0, 3)?;
-        sobel(img, &mut grad_y, 0, 1, 3)?;
+    #[must_use]
+    pub fn with_cell_size(mut self, cell_size: Size) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
 
-        // Calculate magnitude and orientation
-        let mut magnitudes = vec![vec![0.0f32; img.cols()]; img.rows()];
-        let mut orientations = vec![vec![0.0f32; img.cols()]; img.rows()];
+    #[must_use]
+    pub fn with_nbins(mut self, nbins: usize) -> Self {
+        self.nbins = nbins;
+        self
+    }
 
-        for row in 0..img.rows() {
-            for col in 0..img.cols() {
-                let gx = f32::from(grad_x.at(row, col)?[0]);
-                let gy = f32::from(grad_y.at(row, col)?[0]);
+    #[must_use]
+    pub fn with_gamma_correction(mut self, gamma_correction: bool) -> Self {
+        self.gamma_correction = gamma_correction;
+        self
+    }
 
-                magnitudes[row][col] = (gx * gx + gy * gy).sqrt();
-                orientations[row][col] = gy.atan2(gx);
-            }
+    #[must_use]
+    pub fn with_signed_gradient(mut self, signed_gradient: bool) -> Self {
+        self.signed_gradient = signed_gradient;
+        self
+    }
+
+    /// Check that the geometry is consistent: blocks are a whole number of
+    /// cells, and blocks tile the detection window at the given stride
+    pub fn validate(&self) -> Result<()> {
+        let positive = |s: Size| s.width > 0 && s.height > 0;
+        if !positive(self.win_size) || !positive(self.block_size) || !positive(self.block_stride) || !positive(self.cell_size) {
+            return Err(Error::InvalidParameter("HOG sizes must be positive".to_string()));
+        }
+
+        if self.nbins == 0 {
+            return Err(Error::InvalidParameter("HOG needs at least one bin".to_string()));
+        }
+
+        if self.block_size.width % self.cell_size.width != 0 || self.block_size.height % self.cell_size.height != 0 {
+            return Err(Error::InvalidParameter(
+                "HOG block size must be a multiple of the cell size".to_string(),
+            ));
+        }
+
+        if self.block_size.width > self.win_size.width
+            || self.block_size.height > self.win_size.height
+            || (self.win_size.width - self.block_size.width) % self.block_stride.width != 0
+            || (self.win_size.height - self.block_size.height) % self.block_stride.height != 0
+        {
+            return Err(Error::InvalidParameter(
+                "HOG blocks must tile the window at the block stride".to_string(),
+            ));
         }
 
+        Ok(())
+    }
+
+    /// Length of the descriptor for one detection window
+    #[must_use]
+    pub fn descriptor_size(&self) -> usize {
+        let blocks_x = (self.win_size.width - self.block_size.width) / self.block_stride.width + 1;
+        let blocks_y = (self.win_size.height - self.block_size.height) / self.block_stride.height + 1;
+        let cells = (self.block_size.width / self.cell_size.width) * (self.block_size.height / self.cell_size.height);
+        (blocks_x * blocks_y * cells).max(0) as usize * self.nbins
+    }
+
+    /// Compute HOG descriptor for an image
+    pub fn compute(&self, img: &Mat) -> Result<Vec<f32>> {
+        self.validate()?;
+        let (magnitudes, orientations) = self.gradients(img)?;
+
         // Compute cell histograms
         let cells_per_block_x = self.block_size.width / self.cell_size.width;
         let cells_per_block_y = self.block_size.height / self.cell_size.height;
@@ -105,6 +172,110 @@ impl HOGDescriptor {
         Ok(descriptors)
     }
 
+    /// Render per-cell orientation histograms as star glyphs
+    ///
+    /// Every cell of the image gets one line segment per bin through its
+    /// centre, drawn along the edge direction (perpendicular to the gradient)
+    /// with length and brightness proportional to the bin's share of the
+    /// strongest bin in the image. The result is a single-channel U8 image of
+    /// the input's size on a black background, ready to overlay.
+    pub fn visualize(&self, img: &Mat) -> Result<Mat> {
+        self.validate()?;
+        let (magnitudes, orientations) = self.gradients(img)?;
+
+        let cell_w = self.cell_size.width as usize;
+        let cell_h = self.cell_size.height as usize;
+        let cells_x = img.cols() / cell_w;
+        let cells_y = img.rows() / cell_h;
+
+        let mut histograms = Vec::with_capacity(cells_x * cells_y);
+        for cy in 0..cells_y {
+            for cx in 0..cells_x {
+                histograms.push(self.compute_cell_histogram(&magnitudes, &orientations, cx * cell_w, cy * cell_h));
+            }
+        }
+
+        let max_bin = histograms.iter().flatten().copied().fold(0.0f32, f32::max);
+        let mut out = Mat::new(img.rows(), img.cols(), 1, MatDepth::U8)?;
+        if max_bin <= 0.0 {
+            return Ok(out);
+        }
+
+        let range = self.orientation_range();
+        let half_len = 0.5 * cell_w.min(cell_h) as f32;
+
+        for (i, hist) in histograms.iter().enumerate() {
+            let center_x = ((i % cells_x) * cell_w) as f32 + cell_w as f32 / 2.0;
+            let center_y = ((i / cells_x) * cell_h) as f32 + cell_h as f32 / 2.0;
+
+            for (bin, &value) in hist.iter().enumerate() {
+                let strength = value / max_bin;
+                if strength < 0.05 {
+                    continue;
+                }
+
+                // Bin centre orientation, rotated 90° to show the edge
+                let angle = (bin as f32 + 0.5) * range / self.nbins as f32 + std::f32::consts::FRAC_PI_2;
+                let dx = angle.cos() * half_len * strength;
+                let dy = angle.sin() * half_len * strength;
+                let intensity = (strength * 255.0).round() as u8;
+
+                draw_glyph_line(&mut out, (center_x - dx, center_y - dy), (center_x + dx, center_y + dy), intensity)?;
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Gradient magnitude and orientation with centred [-1, 0, 1] differences
+    fn gradients(&self, img: &Mat) -> Result<Gradients> {
+        if img.channels() != 1 {
+            return Err(Error::InvalidParameter(
+                "HOG requires grayscale image".to_string(),
+            ));
+        }
+
+        if img.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "HOG only supports U8 depth".to_string(),
+            ));
+        }
+
+        let rows = img.rows();
+        let cols = img.cols();
+
+        let mut intensity = vec![vec![0.0f32; cols]; rows];
+        for (row, line) in intensity.iter_mut().enumerate() {
+            for (col, v) in line.iter_mut().enumerate() {
+                let raw = f32::from(img.at(row, col)?[0]);
+                *v = if self.gamma_correction { raw.sqrt() } else { raw };
+            }
+        }
+
+        let mut magnitudes = vec![vec![0.0f32; cols]; rows];
+        let mut orientations = vec![vec![0.0f32; cols]; rows];
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let gx = intensity[row][(col + 1).min(cols - 1)] - intensity[row][col.saturating_sub(1)];
+                let gy = intensity[(row + 1).min(rows - 1)][col] - intensity[row.saturating_sub(1)][col];
+
+                magnitudes[row][col] = (gx * gx + gy * gy).sqrt();
+                orientations[row][col] = gy.atan2(gx);
+            }
+        }
+
+        Ok((magnitudes, orientations))
+    }
+
+    fn orientation_range(&self) -> f32 {
+        if self.signed_gradient {
+            2.0 * PI as f32
+        } else {
+            PI as f32
+        }
+    }
+
     fn compute_cell_histogram(
         &self,
         magnitudes: &[Vec<f32>],
@@ -113,24 +284,25 @@ impl HOGDescriptor {
         start_y: usize,
     ) -> Vec<f32> {
         let mut histogram = vec![0.0f32; self.nbins];
-        let angle_per_bin = PI as f32 / self.nbins as f32;
+        let range = self.orientation_range();
+        let angle_per_bin = range / self.nbins as f32;
 
         for y in start_y..(start_y + self.cell_size.height as usize) {
             for x in start_x..(start_x + self.cell_size.width as usize) {
                 if y < magnitudes.len() && x < magnitudes[0].len() {
                     let mag = magnitudes[y][x];
-                    let mut angle = orientations[y][x];
-
-                    // Convert to [0, PI]
-                    if angle < 0.0 {
-                        angle += PI as f32;
-                    }
-
-                    // Find bin
-                    let bin_idx = (angle / angle_per_bin) as usize;
-                    let bin_idx = bin_idx.min(self.nbins - 1);
-
-                    histogram[bin_idx] += mag;
+                    // Fold into [0, range)
+                    let angle = orientations[y][x].rem_euclid(range);
+
+                    // Split the vote between the two nearest bin centres
+                    let pos = angle / angle_per_bin - 0.5;
+                    let lower = pos.floor();
+                    let frac = pos - lower;
+                    let lower_bin = (lower as i64).rem_euclid(self.nbins as i64) as usize;
+                    let upper_bin = (lower_bin + 1) % self.nbins;
+
+                    histogram[lower_bin] += mag * (1.0 - frac);
+                    histogram[upper_bin] += mag * frac;
                 }
             }
         }
@@ -181,6 +353,28 @@ impl HOGDescriptor {
 
         Ok(detections)
     }
+
+    /// [`detect_multi_scale`](Self::detect_multi_scale) over `roi` only, for
+    /// when a tracker already knows where to look
+    ///
+    /// Only windows that fit inside the ROI are tried, starting from its top
+    /// left corner; detections are returned in full-image coordinates.
+    pub fn detect_multi_scale_roi(
+        &self,
+        img: &Mat,
+        roi: Rect,
+        hit_threshold: f64,
+        win_stride: Size,
+        scale: f64,
+    ) -> Result<Vec<Rect>> {
+        let (crop, _) = img.roi_with_margin(roi, 0)?;
+        let mut detections = self.detect_multi_scale(&crop, hit_threshold, win_stride, scale)?;
+        for rect in &mut detections {
+            rect.x += roi.x;
+            rect.y += roi.y;
+        }
+        Ok(detections)
+    }
 }
 
 impl Default for HOGDescriptor {
@@ -189,6 +383,24 @@ impl Default for HOGDescriptor {
     }
 }
 
+/// Draw a 1px line keeping the brighter value where glyphs overlap
+fn draw_glyph_line(img: &mut Mat, from: (f32, f32), to: (f32, f32), intensity: u8) -> Result<()> {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as usize;
+
+    for i in 0..=steps {
+        let t = i as f32 / steps as f32;
+        let x = (from.0 + (to.0 - from.0) * t).round();
+        let y = (from.1 + (to.1 - from.1) * t).round();
+
+        if x >= 0.0 && y >= 0.0 && (x as usize) < img.cols() && (y as usize) < img.rows() {
+            let px = &mut img.at_mut(y as usize, x as usize)?[0];
+            *px = (*px).max(intensity);
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,4 +415,91 @@ mod tests {
 
         assert!(!descriptor.is_empty());
     }
+
+    /// Vertical bars: the gradient is horizontal everywhere
+    fn vertical_edges(rows: usize, cols: usize) -> Mat {
+        let mut img = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                img.at_mut(row, col).unwrap()[0] = if (col / 4) % 2 == 0 { 40 } else { 200 };
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_configurable_geometry() {
+        let hog = HOGDescriptor::new()
+            .with_win_size(Size::new(32, 32))
+            .with_block_size(Size::new(16, 16))
+            .with_block_stride(Size::new(8, 8))
+            .with_cell_size(Size::new(8, 8))
+            .with_nbins(12);
+
+        assert_eq!(hog.descriptor_size(), 3 * 3 * 4 * 12);
+        let descriptor = hog.compute(&vertical_edges(32, 32)).unwrap();
+        assert_eq!(descriptor.len(), hog.descriptor_size());
+
+        let bad = HOGDescriptor::new().with_cell_size(Size::new(6, 6));
+        assert!(bad.compute(&vertical_edges(32, 32)).is_err());
+        let bad = HOGDescriptor::new().with_win_size(Size::new(30, 30)).with_block_size(Size::new(16, 16));
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_signed_gradient_separates_directions() {
+        let img = vertical_edges(16, 16);
+        let geometry = |hog: HOGDescriptor| {
+            hog.with_win_size(Size::new(16, 16)).with_block_size(Size::new(8, 8)).with_block_stride(Size::new(8, 8))
+        };
+
+        // Unsigned: all energy sits around 0°/180°, the first and last bins
+        let unsigned = geometry(HOGDescriptor::new().with_nbins(4)).compute(&img).unwrap();
+        assert!(unsigned[0] + unsigned[3] > 0.99 * unsigned[..4].iter().sum::<f32>());
+
+        // Signed: rising and falling edges land in opposite half-circles
+        let signed = geometry(HOGDescriptor::new().with_nbins(4).with_signed_gradient(true))
+            .compute(&img)
+            .unwrap();
+        let right = signed[0] + signed[3];
+        let left = signed[1] + signed[2];
+        assert!(right > 0.1 && left > 0.1, "{signed:?}");
+
+        let gamma = geometry(HOGDescriptor::new().with_gamma_correction(true)).compute(&img).unwrap();
+        assert_eq!(gamma.len(), unsigned.len() / 4 * 9);
+    }
+
+    #[test]
+    fn test_visualize_draws_edge_glyphs() {
+        let img = vertical_edges(32, 32);
+        let hog = HOGDescriptor::new().with_win_size(Size::new(32, 32));
+        let vis = hog.visualize(&img).unwrap();
+
+        assert_eq!((vis.rows(), vis.cols(), vis.channels()), (32, 32, 1));
+        // Horizontal gradients give vertical glyphs through each cell centre
+        assert_eq!(vis.at(12, 12).unwrap()[0], 255);
+        assert!(vis.at(10, 12).unwrap()[0] > 0);
+        assert_eq!(vis.at(12, 9).unwrap()[0], 0);
+
+        let flat = Mat::new_with_default(32, 32, 1, MatDepth::U8, Scalar::all(90.0)).unwrap();
+        let vis = hog.visualize(&flat).unwrap();
+        assert!(vis.data().iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_detect_multi_scale_roi() {
+        let hog = HOGDescriptor::new().with_win_size(Size::new(32, 32));
+        let img = vertical_edges(96, 128);
+        let (stride, scale) = (Size::new(16, 16), 2.0);
+        let full = hog.detect_multi_scale(&img, 1.0, stride, scale).unwrap();
+
+        // Aligned with the stride grid, so the ROI windows are a subset
+        let roi = Rect::new(32, 16, 64, 48);
+        let found = hog.detect_multi_scale_roi(&img, roi, 1.0, stride, scale).unwrap();
+        let inside = |r: &Rect| r.intersection(&roi) == Some(*r);
+        let expected: Vec<Rect> = full.into_iter().filter(inside).collect();
+        assert!(!found.is_empty());
+        assert_eq!(found, expected);
+        assert!(hog.detect_multi_scale_roi(&img, Rect::new(100, 0, 64, 32), 1.0, stride, scale).is_err());
+    }
 }
@@ -2,8 +2,14 @@ pub mod hog;
 pub mod cascade;
 pub mod qr_detector;
 pub mod aruco;
+pub mod evaluation;
+pub mod facemark;
+pub mod sliding_window;
 
 pub use hog::*;
 pub use cascade::*;
 pub use qr_detector::*;
 pub use aruco::*;
+pub use evaluation::*;
+pub use facemark::*;
+pub use sliding_window::{PyramidLevel, SlidingWindow, SlidingWindows, Window};
@@ -0,0 +1,285 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::parallel::parallel_map;
+use crate::core::types::{InterpolationFlag, Rect, Size};
+use crate::core::Mat;
+use crate::error::{Error, Result};
+use crate::imgproc::resize;
+
+/// One level of the scale space scanned by [`SlidingWindow`]
+#[derive(Debug, Clone)]
+pub struct PyramidLevel {
+    pub image: Mat,
+    /// Original image size over level size; 1.0 for the full-resolution level
+    pub scale: f64,
+}
+
+/// A window position produced by [`SlidingWindow::windows`]
+#[derive(Debug, Clone)]
+pub struct Window {
+    /// Window pixels, `window_size` at the level's resolution
+    pub image: Mat,
+    /// Window footprint in original image coordinates
+    pub rect: Rect,
+    /// Window position within the pyramid level
+    pub level_rect: Rect,
+    pub level: usize,
+    pub scale: f64,
+}
+
+/// Fixed-size window scanned over an image pyramid
+///
+/// Detectors trained on a fixed input size (HOG+SVM, small CNNs) find objects
+/// of any size by sliding their window over successively downscaled copies
+/// of the image. Level `k` is the image shrunk by `scale_factor^k`; levels
+/// stop once the window no longer fits. Window rectangles are mapped back to
+/// original image coordinates, ready for non-maximum suppression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlidingWindow {
+    pub window_size: Size,
+    /// Step between window positions, in level pixels
+    pub stride: Size,
+    /// Downscale between consecutive levels, greater than 1
+    pub scale_factor: f64,
+    /// Upper bound on the number of levels; `None` scans down to the window
+    /// size
+    pub max_levels: Option<usize>,
+    pub interpolation: InterpolationFlag,
+}
+
+impl SlidingWindow {
+    #[must_use]
+    pub fn new(window_size: Size) -> Self {
+        Self {
+            window_size,
+            stride: Size::new(8, 8),
+            scale_factor: 1.25,
+            max_levels: None,
+            interpolation: InterpolationFlag::Area,
+        }
+    }
+
+    #[must_use]
+    pub fn with_stride(mut self, stride: Size) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    #[must_use]
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = Some(max_levels);
+        self
+    }
+
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: InterpolationFlag) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.window_size.width <= 0 || self.window_size.height <= 0 || self.stride.width <= 0 || self.stride.height <= 0 {
+            return Err(Error::InvalidParameter(
+                "Window size and stride must be positive".to_string(),
+            ));
+        }
+        if self.scale_factor.is_nan() || self.scale_factor <= 1.0 {
+            return Err(Error::InvalidParameter(
+                "Scale factor must be greater than 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The pyramid levels the window is scanned over, finest first
+    ///
+    /// The first level is `image` itself; downscaling needs a U8 image.
+    pub fn levels(&self, image: &Mat) -> Result<Vec<PyramidLevel>> {
+        self.validate()?;
+        let (win_w, win_h) = (self.window_size.width as usize, self.window_size.height as usize);
+        let mut levels = Vec::new();
+        let mut scale = 1.0;
+        while self.max_levels.is_none_or(|max| levels.len() < max) {
+            let cols = (image.cols() as f64 / scale).round() as usize;
+            let rows = (image.rows() as f64 / scale).round() as usize;
+            if cols < win_w || rows < win_h {
+                break;
+            }
+            let level = if levels.is_empty() {
+                image.clone()
+            } else {
+                let mut resized = Mat::new(1, 1, image.channels(), image.depth())?;
+                resize(image, &mut resized, Size::new(cols as i32, rows as i32), self.interpolation)?;
+                resized
+            };
+            levels.push(PyramidLevel { image: level, scale });
+            scale *= self.scale_factor;
+        }
+        Ok(levels)
+    }
+
+    /// Iterate over every window position of every level, finest level
+    /// first and in raster order within a level
+    pub fn windows(&self, image: &Mat) -> Result<SlidingWindows> {
+        Ok(SlidingWindows {
+            levels: self.levels(image)?,
+            window_size: self.window_size,
+            stride: self.stride,
+            level: 0,
+            position: 0,
+        })
+    }
+
+    /// Run `classify` on every window and keep those it scores
+    ///
+    /// `classify` returns `Some(score)` for a hit. Windows are evaluated in
+    /// parallel when the `rayon` feature is enabled; the hits come back as
+    /// original-image rectangles in scan order, before any grouping or
+    /// non-maximum suppression.
+    pub fn detect<F>(&self, image: &Mat, classify: F) -> Result<Vec<(Rect, f64)>>
+    where
+        F: Fn(&Mat) -> Result<Option<f64>> + Sync + Send,
+    {
+        let levels = self.levels(image)?;
+        let mut hits = Vec::new();
+        for (index, level) in levels.iter().enumerate() {
+            let (across, down) = positions(&level.image, self.window_size, self.stride);
+            let scored = parallel_map(across * down, |i| {
+                let window = window_at(level, index, i, across, self.window_size, self.stride)?;
+                Ok(classify(&window.image)?.map(|score| (window.rect, score)))
+            })?;
+            hits.extend(scored.into_iter().flatten());
+        }
+        Ok(hits)
+    }
+}
+
+/// Iterator returned by [`SlidingWindow::windows`]
+pub struct SlidingWindows {
+    levels: Vec<PyramidLevel>,
+    window_size: Size,
+    stride: Size,
+    level: usize,
+    position: usize,
+}
+
+impl SlidingWindows {
+    /// The pyramid being scanned
+    #[must_use]
+    pub fn levels(&self) -> &[PyramidLevel] {
+        &self.levels
+    }
+}
+
+impl Iterator for SlidingWindows {
+    type Item = Result<Window>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(level) = self.levels.get(self.level) {
+            let (across, down) = positions(&level.image, self.window_size, self.stride);
+            if self.position < across * down {
+                let window = window_at(level, self.level, self.position, across, self.window_size, self.stride);
+                self.position += 1;
+                return Some(window);
+            }
+            self.level += 1;
+            self.position = 0;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.levels[self.level.min(self.levels.len())..]
+            .iter()
+            .map(|level| {
+                let (across, down) = positions(&level.image, self.window_size, self.stride);
+                across * down
+            })
+            .sum::<usize>()
+            .saturating_sub(self.position);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for SlidingWindows {}
+
+/// Window positions across and down a level
+fn positions(image: &Mat, window_size: Size, stride: Size) -> (usize, usize) {
+    let count = |extent: usize, window: i32, step: i32| {
+        extent.checked_sub(window as usize).map_or(0, |room| room / step as usize + 1)
+    };
+    (
+        count(image.cols(), window_size.width, stride.width),
+        count(image.rows(), window_size.height, stride.height),
+    )
+}
+
+fn window_at(level: &PyramidLevel, index: usize, position: usize, across: usize, window_size: Size, stride: Size) -> Result<Window> {
+    let x = (position % across) as i32 * stride.width;
+    let y = (position / across) as i32 * stride.height;
+    let level_rect = Rect::new(x, y, window_size.width, window_size.height);
+    let to_original = |v: i32| (f64::from(v) * level.scale).round() as i32;
+    Ok(Window {
+        image: level.image.roi(level_rect)?,
+        rect: Rect::new(to_original(x), to_original(y), to_original(window_size.width), to_original(window_size.height)),
+        level_rect,
+        level: index,
+        scale: level.scale,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    #[test]
+    fn test_sliding_window_scan() {
+        let image = Mat::new(100, 80, 1, MatDepth::U8).unwrap();
+        let scanner = SlidingWindow::new(Size::new(32, 32)).with_stride(Size::new(16, 16)).with_scale_factor(1.5);
+
+        let levels = scanner.levels(&image).unwrap();
+        let sizes: Vec<(usize, usize)> = levels.iter().map(|l| (l.image.cols(), l.image.rows())).collect();
+        assert_eq!(sizes, vec![(80, 100), (53, 67), (36, 44)]);
+
+        let windows = scanner.windows(&image).unwrap();
+        // 4x5 + 2x3 + 1x1 positions
+        assert_eq!(windows.len(), 20 + 6 + 1);
+        let windows: Vec<Window> = windows.map(Result::unwrap).collect();
+        assert_eq!(windows[1].rect, Rect::new(16, 0, 32, 32));
+        let last = windows.last().unwrap();
+        assert_eq!((last.level, last.level_rect), (2, Rect::new(0, 0, 32, 32)));
+        assert_eq!(last.rect, Rect::new(0, 0, 72, 72));
+        assert!(windows.iter().all(|w| w.image.cols() == 32 && w.image.rows() == 32));
+
+        assert_eq!(scanner.with_max_levels(1).windows(&image).unwrap().count(), 20);
+        assert!(SlidingWindow::new(Size::new(32, 32)).with_scale_factor(1.0).levels(&image).is_err());
+    }
+
+    #[test]
+    fn test_sliding_window_detect() {
+        // A bright 40x40 square; the 20x20 window only covers it entirely at
+        // the levels that shrink it to the window size
+        let mut image = Mat::new(96, 96, 1, MatDepth::U8).unwrap();
+        for row in 32..72 {
+            for col in 20..60 {
+                image.at_mut(row, col).unwrap()[0] = 255;
+            }
+        }
+        let scanner = SlidingWindow::new(Size::new(20, 20)).with_stride(Size::new(2, 2)).with_scale_factor(2.0);
+        let hits = scanner
+            .detect(&image, |window| {
+                let mean = window.data().iter().map(|&v| f64::from(v)).sum::<f64>() / window.data().len() as f64;
+                Ok((mean > 240.0).then_some(mean))
+            })
+            .unwrap();
+
+        assert!(!hits.is_empty());
+        assert!(hits.iter().any(|(rect, _)| *rect == Rect::new(20, 32, 40, 40)), "{hits:?}");
+    }
+}
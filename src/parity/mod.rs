@@ -0,0 +1,246 @@
+//! Numerical parity with `OpenCV`
+//!
+//! Most operations here are reimplementations rather than ports, so their
+//! outputs can differ from `OpenCV`'s in rounding, constants or sampling.
+//! The parity manifest (`src/parity/manifest.tsv`) records, per operation,
+//! how far results may diverge and why; the conformance tests check every
+//! entry against `OpenCV` references using [`compare`], so the manifest
+//! cannot silently drift from the code.
+//!
+//! ```rust
+//! let report = opencv_rust::parity::report();
+//! for entry in report.divergent() {
+//!     println!("{}: up to {} ({})", entry.op, entry.tolerance.max_abs_diff, entry.notes);
+//! }
+//! ```
+
+#![allow(clippy::cast_precision_loss)]
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+const MANIFEST: &str = include_str!("manifest.tsv");
+
+/// How much an operation's output may differ from `OpenCV`'s
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Largest difference allowed for any single value
+    pub max_abs_diff: f64,
+    /// Largest mean absolute difference over all values
+    pub max_mean_diff: f64,
+    /// Differences above this count as outliers
+    pub outlier_diff: f64,
+    /// Largest share of outlier values, in percent
+    pub max_outlier_percent: f64,
+}
+
+impl Tolerance {
+    /// Whether the tolerance requires bit-exact output
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        self.max_abs_diff == 0.0
+    }
+}
+
+/// One operation in the parity manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParityEntry {
+    /// Operation name used by the conformance harnesses
+    pub op: &'static str,
+    /// The `OpenCV` function it is compared against
+    pub opencv: &'static str,
+    /// `"native"` or `"opencv.js"`, the harness that checks the entry
+    pub checked_by: &'static str,
+    pub tolerance: Tolerance,
+    /// Where and why results diverge, `"-"` if not yet characterised
+    pub notes: &'static str,
+}
+
+/// Differences between an output and its `OpenCV` reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParityCheck {
+    pub max_abs_diff: f64,
+    pub mean_abs_diff: f64,
+    pub outlier_percent: f64,
+    /// Whether all three are within the tolerance
+    pub passed: bool,
+}
+
+/// All manifest entries, in manifest order
+#[must_use]
+pub fn manifest() -> &'static [ParityEntry] {
+    static ENTRIES: OnceLock<Vec<ParityEntry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| parse_manifest(MANIFEST).expect("parity manifest is malformed"))
+}
+
+/// The manifest entry for `op`
+#[must_use]
+pub fn entry(op: &str) -> Option<&'static ParityEntry> {
+    manifest().iter().find(|e| e.op == op)
+}
+
+/// Human-readable summary of the manifest
+#[must_use]
+pub fn report() -> ParityReport {
+    ParityReport { entries: manifest() }
+}
+
+/// Compare `actual` with the `OpenCV` output `expected` under `tolerance`
+///
+/// Both Mats must have the same size, channels and depth; U8 and F32 are
+/// supported.
+pub fn compare(actual: &Mat, expected: &Mat, tolerance: &Tolerance) -> Result<ParityCheck> {
+    if actual.rows() != expected.rows() || actual.cols() != expected.cols() || actual.channels() != expected.channels() {
+        return Err(Error::InvalidDimensions(
+            "Parity comparison needs Mats of the same size and channels".to_string(),
+        ));
+    }
+    if actual.depth() != expected.depth() {
+        return Err(Error::InvalidParameter(
+            "Parity comparison needs Mats of the same depth".to_string(),
+        ));
+    }
+
+    let diffs: Vec<f64> = match actual.depth() {
+        MatDepth::U8 => actual.data().iter().zip(expected.data()).map(|(a, e)| f64::from(a.abs_diff(*e))).collect(),
+        MatDepth::F32 => f32_values(actual).zip(f32_values(expected)).map(|(a, e)| f64::from((a - e).abs())).collect(),
+        _ => {
+            return Err(Error::UnsupportedOperation(
+                "Parity comparison supports U8 and F32 Mats".to_string(),
+            ))
+        }
+    };
+
+    let count = diffs.len().max(1) as f64;
+    let max_abs_diff = diffs.iter().copied().fold(0.0, f64::max);
+    let mean_abs_diff = diffs.iter().sum::<f64>() / count;
+    let outlier_percent = 100.0 * diffs.iter().filter(|&&d| d > tolerance.outlier_diff).count() as f64 / count;
+    Ok(ParityCheck {
+        max_abs_diff,
+        mean_abs_diff,
+        outlier_percent,
+        passed: max_abs_diff <= tolerance.max_abs_diff
+            && mean_abs_diff <= tolerance.max_mean_diff
+            && outlier_percent <= tolerance.max_outlier_percent,
+    })
+}
+
+/// Values of an F32 Mat; the caller has checked the depth
+fn f32_values(mat: &Mat) -> impl Iterator<Item = f32> + '_ {
+    let (cols, channels) = (mat.cols(), mat.channels());
+    (0..mat.rows() * cols * channels)
+        .map(move |i| mat.at_f32(i / (cols * channels), i / channels % cols, i % channels).unwrap_or(f32::NAN))
+}
+
+fn parse_manifest(text: &'static str) -> Result<Vec<ParityEntry>> {
+    let mut entries: Vec<ParityEntry> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let malformed = |what: &str| Error::InvalidParameter(format!("Parity manifest line {}: {what}", number + 1));
+
+        let fields: Vec<&'static str> = line.split('\t').collect();
+        let [op, opencv, checked_by, max_abs, max_mean, outlier, outlier_percent, notes] = fields[..] else {
+            return Err(malformed("expected 8 tab-separated fields"));
+        };
+        if checked_by != "native" && checked_by != "opencv.js" {
+            return Err(malformed("checked_by must be native or opencv.js"));
+        }
+        if entries.iter().any(|e| e.op == op) {
+            return Err(malformed("duplicate operation"));
+        }
+        let number = |field: &str| field.parse::<f64>().map_err(|_| malformed("expected a number"));
+        entries.push(ParityEntry {
+            op,
+            opencv,
+            checked_by,
+            tolerance: Tolerance {
+                max_abs_diff: number(max_abs)?,
+                max_mean_diff: number(max_mean)?,
+                outlier_diff: number(outlier)?,
+                max_outlier_percent: number(outlier_percent)?,
+            },
+            notes,
+        });
+    }
+    Ok(entries)
+}
+
+/// The parity manifest as returned by [`report`]
+///
+/// Its `Display` output is a table of every operation's tolerance and notes.
+#[derive(Debug, Clone, Copy)]
+pub struct ParityReport {
+    entries: &'static [ParityEntry],
+}
+
+impl ParityReport {
+    #[must_use]
+    pub fn entries(&self) -> &'static [ParityEntry] {
+        self.entries
+    }
+
+    /// Entries whose output is not bit-exact with `OpenCV`
+    pub fn divergent(&self) -> impl Iterator<Item = &'static ParityEntry> {
+        self.entries.iter().filter(|e| !e.tolerance.is_exact())
+    }
+}
+
+impl fmt::Display for ParityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.entries.iter().map(|e| e.op.len()).max().unwrap_or(2);
+        writeln!(f, "{:width$}  {:>7}  {:>8}  {:>9}  checked by  notes", "op", "max abs", "max mean", "outliers")?;
+        for e in self.entries {
+            let t = &e.tolerance;
+            let outliers = format!("{}%>{}", t.max_outlier_percent, t.outlier_diff);
+            writeln!(
+                f,
+                "{:width$}  {:>7}  {:>8}  {:>9}  {:10}  {}",
+                e.op, t.max_abs_diff, t.max_mean_diff, outliers, e.checked_by, e.notes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_parses() {
+        assert!(!manifest().is_empty());
+        assert!(entry("flip").unwrap().tolerance.is_exact());
+        assert!(entry("no_such_op").is_none());
+
+        let text = report().to_string();
+        assert_eq!(text.lines().count(), manifest().len() + 1);
+        assert!(text.contains("cvt_color_lab"));
+
+        assert!(parse_manifest("flip\tflip\tnative\t0\t0\t0\n").is_err());
+        assert!(parse_manifest("flip\tflip\tbrowser\t0\t0\t0\t0\t-\n").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut expected = Mat::new(2, 5, 1, MatDepth::U8).unwrap();
+        expected.data_mut().fill(100);
+        let mut actual = expected.clone();
+        actual.data_mut()[0] = 103;
+        actual.data_mut()[1] = 101;
+
+        let tolerance = Tolerance { max_abs_diff: 3.0, max_mean_diff: 0.5, outlier_diff: 1.0, max_outlier_percent: 10.0 };
+        let check = compare(&actual, &expected, &tolerance).unwrap();
+        assert_eq!((check.max_abs_diff, check.outlier_percent), (3.0, 10.0));
+        assert!((check.mean_abs_diff - 0.4).abs() < 1e-12);
+        assert!(check.passed);
+
+        let strict = Tolerance { max_outlier_percent: 5.0, ..tolerance };
+        assert!(!compare(&actual, &expected, &strict).unwrap().passed);
+        assert!(compare(&actual, &Mat::new(5, 2, 1, MatDepth::U8).unwrap(), &tolerance).is_err());
+    }
+}
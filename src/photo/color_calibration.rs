@@ -0,0 +1,561 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Colour calibration with a 24-patch ColorChecker chart
+//!
+//! [`ColorCheckerDetector`] finds the chart's 4x6 grid of patches in a
+//! photograph and measures their colours; [`ColorCorrectionMatrix`] fits
+//! the transform taking those measurements to the chart's published sRGB
+//! values, and [`apply_ccm`] applies it to any image from the same camera
+//! and lighting. Fitting and correction happen in linear light, so the
+//! matrix models the sensor rather than the tone curve.
+//!
+//! ```rust,no_run
+//! # use opencv_rust::core::{Mat, MatDepth};
+//! # use opencv_rust::photo::{apply_ccm, CcmModel, ColorCheckerDetector, ColorCorrectionMatrix};
+//! # fn main() -> opencv_rust::error::Result<()> {
+//! # let shot = Mat::new(480, 640, 3, MatDepth::U8)?;
+//! if let Some(chart) = ColorCheckerDetector::new().detect(&shot)? {
+//!     let ccm = ColorCorrectionMatrix::fit_chart(&chart, CcmModel::Linear)?;
+//!     let mut corrected = Mat::new(1, 1, 1, MatDepth::U8)?;
+//!     apply_ccm(&shot, &mut corrected, &ccm)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::types::{ColorOrder, Point2f};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::connected_components_with_stats;
+
+/// sRGB values of the 24 ColorChecker patches in chart order: the top row
+/// starts at dark skin, the bottom row runs from white to black
+pub const COLORCHECKER_SRGB: [[u8; 3]; 24] = [
+    [115, 82, 68],
+    [194, 150, 130],
+    [98, 122, 157],
+    [87, 108, 67],
+    [133, 128, 177],
+    [103, 189, 170],
+    [214, 126, 44],
+    [80, 91, 166],
+    [193, 90, 99],
+    [94, 60, 108],
+    [157, 188, 64],
+    [224, 163, 46],
+    [56, 61, 150],
+    [70, 148, 73],
+    [175, 54, 60],
+    [231, 199, 31],
+    [187, 86, 149],
+    [8, 133, 161],
+    [243, 243, 242],
+    [200, 200, 200],
+    [160, 160, 160],
+    [122, 122, 121],
+    [85, 85, 85],
+    [52, 52, 52],
+];
+
+const CHART_ROWS: usize = 4;
+const CHART_COLS: usize = 6;
+
+/// One measured chart patch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartPatch {
+    /// Patch centre in image coordinates
+    pub center: Point2f,
+    /// Side length of the patch, in pixels
+    pub size: f32,
+    /// Mean RGB of the patch interior, 0-255
+    pub color: [f64; 3],
+}
+
+/// A detected chart: 24 patches in [`COLORCHECKER_SRGB`] order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorChecker {
+    pub patches: Vec<ChartPatch>,
+}
+
+impl ColorChecker {
+    /// Measured colours in chart order
+    #[must_use]
+    pub fn colors(&self) -> Vec<[f64; 3]> {
+        self.patches.iter().map(|p| p.color).collect()
+    }
+}
+
+/// Finds a ColorChecker chart by its grid of uniform square patches
+///
+/// Patches are the regions enclosed by colour edges; the 24 that are
+/// square, solid and of similar size must form a 4x6 grid. The grid may
+/// be rotated; which way up the chart is follows from the patch colours.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorCheckerDetector {
+    edge_threshold: u8,
+    min_patch_area: usize,
+}
+
+impl Default for ColorCheckerDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ColorCheckerDetector {
+    /// Detector with an edge threshold of 12 and patches of at least 64 pixels
+    #[must_use]
+    pub fn new() -> Self {
+        Self { edge_threshold: 12, min_patch_area: 64 }
+    }
+
+    /// Smallest difference between neighbouring pixels, in any channel,
+    /// that separates two patches
+    #[must_use]
+    pub fn with_edge_threshold(mut self, threshold: u8) -> Self {
+        self.edge_threshold = threshold.max(1);
+        self
+    }
+
+    #[must_use]
+    pub fn with_min_patch_area(mut self, area: usize) -> Self {
+        self.min_patch_area = area.max(4);
+        self
+    }
+
+    /// Locate the chart in a U8 RGB or BGR image (read from its colour order
+    /// tag) and measure its patches, or `None` if no chart is found
+    pub fn detect(&self, src: &Mat) -> Result<Option<ColorChecker>> {
+        check_color_image(src, "ColorChecker detection")?;
+        let (rows, cols, channels) = (src.rows(), src.cols(), src.channels());
+        let data = src.data();
+
+        // Regions free of edges, shrunk by one pixel so patches separate
+        let mut regions = Mat::new(rows, cols, 1, MatDepth::U8)?;
+        let edge = |a: usize, b: usize| {
+            (0..3).any(|ch| data[a * channels + ch].abs_diff(data[b * channels + ch]) >= self.edge_threshold)
+        };
+        for r in 0..rows {
+            for c in 0..cols {
+                let p = r * cols + c;
+                let on_edge = (c + 1 < cols && edge(p, p + 1))
+                    || (r + 1 < rows && edge(p, p + cols))
+                    || (c > 0 && edge(p, p - 1))
+                    || (r > 0 && edge(p, p - cols));
+                regions.data_mut()[p] = if on_edge { 0 } else { 255 };
+            }
+        }
+        let mut labels = Mat::new(1, 1, 1, MatDepth::U8)?;
+        let stats = connected_components_with_stats(&regions, &mut labels, 4)?;
+
+        // Solid, roughly square components
+        let mut candidates: Vec<(Point2f, f32, usize)> = stats
+            .iter()
+            .skip(1)
+            .filter(|s| {
+                let (w, h) = (s.bounding_box.width as f64, s.bounding_box.height as f64);
+                s.area >= self.min_patch_area && s.area as f64 >= 0.8 * w * h && (0.6..=1.67).contains(&(w / h))
+            })
+            .map(|s| {
+                let center = Point2f::new(s.centroid.0 as f32, s.centroid.1 as f32);
+                (center, (s.area as f32).sqrt(), s.area)
+            })
+            .collect();
+        if candidates.len() < CHART_ROWS * CHART_COLS {
+            return Ok(None);
+        }
+
+        // The 24 closest in size to the median
+        let mut areas: Vec<usize> = candidates.iter().map(|c| c.2).collect();
+        areas.sort_unstable();
+        let median = areas[areas.len() / 2] as f64;
+        candidates.sort_by(|a, b| (a.2 as f64 / median).ln().abs().total_cmp(&(b.2 as f64 / median).ln().abs()));
+        candidates.truncate(CHART_ROWS * CHART_COLS);
+        if candidates.iter().any(|c| !(0.5..=2.0).contains(&(c.2 as f64 / median))) {
+            return Ok(None);
+        }
+
+        let Some(grid) = arrange_grid(&candidates) else {
+            return Ok(None);
+        };
+
+        let bgr = src.color_order().or_convention() == ColorOrder::Bgr;
+        let mut measured = Vec::with_capacity(grid.len());
+        for &i in &grid {
+            let (center, size, _) = candidates[i];
+            let mut color = mean_color(src, center, size * 0.5);
+            if bgr {
+                color.swap(0, 2);
+            }
+            measured.push(ChartPatch { center, size, color });
+        }
+
+        // The grid is read either way up; keep the reading that fits the chart
+        let rotated: Vec<ChartPatch> = measured.iter().rev().copied().collect();
+        let residual = |patches: &[ChartPatch]| {
+            let colors: Vec<[f64; 3]> = patches.iter().map(|p| p.color).collect();
+            fit_residual(&colors)
+        };
+        let patches = if residual(&rotated) < residual(&measured) { rotated } else { measured };
+        Ok(Some(ColorChecker { patches }))
+    }
+}
+
+/// Order 24 patch centres into chart rows and columns, or `None` if they
+/// do not form a 4x6 grid
+fn arrange_grid(candidates: &[(Point2f, f32, usize)]) -> Option<Vec<usize>> {
+    let n = candidates.len() as f64;
+    let (mx, my) = candidates.iter().fold((0.0, 0.0), |(x, y), c| (x + f64::from(c.0.x) / n, y + f64::from(c.0.y) / n));
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for c in candidates {
+        let (dx, dy) = (f64::from(c.0.x) - mx, f64::from(c.0.y) - my);
+        sxx += dx * dx;
+        syy += dy * dy;
+        sxy += dx * dy;
+    }
+    // Columns run along the principal axis, rows along its normal
+    let theta = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+    let (cos, sin) = (theta.cos(), theta.sin());
+    let along = |i: usize| f64::from(candidates[i].0.x) * cos + f64::from(candidates[i].0.y) * sin;
+    let across = |i: usize| -f64::from(candidates[i].0.x) * sin + f64::from(candidates[i].0.y) * cos;
+
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by(|&a, &b| along(a).total_cmp(&along(b)));
+    let columns: Vec<Vec<usize>> = order.chunks(CHART_ROWS).map(<[usize]>::to_vec).collect();
+    if !separated(&columns, along) {
+        return None;
+    }
+
+    let mut grid = vec![0; CHART_ROWS * CHART_COLS];
+    let mut rows: Vec<Vec<usize>> = vec![Vec::new(); CHART_ROWS];
+    for (c, mut column) in columns.into_iter().enumerate() {
+        column.sort_by(|&a, &b| across(a).total_cmp(&across(b)));
+        for (r, &i) in column.iter().enumerate() {
+            grid[r * CHART_COLS + c] = i;
+            rows[r].push(i);
+        }
+    }
+    separated(&rows, across).then_some(grid)
+}
+
+/// Whether consecutive groups are further apart along `key` than any group
+/// is wide
+fn separated(groups: &[Vec<usize>], key: impl Fn(usize) -> f64) -> bool {
+    let spans: Vec<(f64, f64)> = groups
+        .iter()
+        .map(|g| g.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &i| (lo.min(key(i)), hi.max(key(i)))))
+        .collect();
+    let widest = spans.iter().map(|(lo, hi)| hi - lo).fold(0.0, f64::max);
+    spans.windows(2).all(|w| {
+        let gap = (w[1].0 + w[1].1) / 2.0 - (w[0].0 + w[0].1) / 2.0;
+        gap > 2.0 * widest
+    })
+}
+
+/// Mean of the channels 0-2 over a square of side `side` around `center`
+fn mean_color(src: &Mat, center: Point2f, side: f32) -> [f64; 3] {
+    let half = (side / 2.0).max(0.5);
+    let (x0, x1) = ((center.x - half).round().max(0.0) as usize, ((center.x + half).round() as usize).min(src.cols() - 1));
+    let (y0, y1) = ((center.y - half).round().max(0.0) as usize, ((center.y + half).round() as usize).min(src.rows() - 1));
+    let channels = src.channels();
+    let mut sum = [0.0; 3];
+    let mut count = 0.0;
+    for r in y0..=y1 {
+        for c in x0..=x1 {
+            let p = (r * src.cols() + c) * channels;
+            for (ch, s) in sum.iter_mut().enumerate() {
+                *s += f64::from(src.data()[p + ch]);
+            }
+            count += 1.0;
+        }
+    }
+    sum.map(|s| s / count)
+}
+
+/// Least-squares residual of a linear fit of `measured` to the chart
+fn fit_residual(measured: &[[f64; 3]]) -> f64 {
+    let reference = chart_reference();
+    ColorCorrectionMatrix::fit(measured, &reference, CcmModel::Linear).map_or(f64::INFINITY, |ccm| {
+        measured
+            .iter()
+            .zip(&reference)
+            .map(|(m, r)| {
+                let c = ccm.correct_linear(m.map(srgb_to_linear));
+                (0..3).map(|ch| (c[ch] - srgb_to_linear(r[ch])).powi(2)).sum::<f64>()
+            })
+            .sum()
+    })
+}
+
+fn chart_reference() -> Vec<[f64; 3]> {
+    COLORCHECKER_SRGB.iter().map(|c| c.map(f64::from)).collect()
+}
+
+/// Form of a [`ColorCorrectionMatrix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CcmModel {
+    /// 3x3 matrix on linear RGB
+    Linear,
+    /// 3x3 matrix plus an offset, for flare or black-level error
+    Affine,
+    /// Second-order polynomial in linear RGB (10 terms), for sensors a
+    /// matrix cannot describe; needs more care outside the chart's gamut
+    Polynomial,
+}
+
+impl CcmModel {
+    fn terms(self, [r, g, b]: [f64; 3]) -> Vec<f64> {
+        match self {
+            CcmModel::Linear => vec![r, g, b],
+            CcmModel::Affine => vec![r, g, b, 1.0],
+            CcmModel::Polynomial => vec![r, g, b, r * g, r * b, g * b, r * r, g * g, b * b, 1.0],
+        }
+    }
+}
+
+/// Colour correction fitted from measured and reference colours
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorCorrectionMatrix {
+    model: CcmModel,
+    /// One RGB output weight per model term
+    coeffs: Vec<[f64; 3]>,
+}
+
+impl ColorCorrectionMatrix {
+    /// Least-squares fit taking `measured` to `reference`, both sRGB 0-255
+    ///
+    /// Needs at least as many colour pairs as the model has terms.
+    pub fn fit(measured: &[[f64; 3]], reference: &[[f64; 3]], model: CcmModel) -> Result<Self> {
+        if measured.len() != reference.len() {
+            return Err(Error::InvalidParameter(
+                "Measured and reference colours must pair up".to_string(),
+            ));
+        }
+        let n = model.terms([0.0; 3]).len();
+        if measured.len() < n {
+            return Err(Error::InvalidParameter(format!(
+                "{model:?} colour correction needs at least {n} colours"
+            )));
+        }
+
+        let mut ata = vec![vec![0.0; n]; n];
+        let mut atb = vec![[0.0; 3]; n];
+        for (m, r) in measured.iter().zip(reference) {
+            let terms = model.terms(m.map(srgb_to_linear));
+            let target = r.map(srgb_to_linear);
+            for i in 0..n {
+                for j in 0..n {
+                    ata[i][j] += terms[i] * terms[j];
+                }
+                for ch in 0..3 {
+                    atb[i][ch] += terms[i] * target[ch];
+                }
+            }
+        }
+        let coeffs = solve(ata, atb).ok_or_else(|| {
+            Error::InvalidParameter("Colours are too similar to fit a colour correction".to_string())
+        })?;
+        Ok(Self { model, coeffs })
+    }
+
+    /// Fit to a detected chart's published colours
+    pub fn fit_chart(chart: &ColorChecker, model: CcmModel) -> Result<Self> {
+        Self::fit(&chart.colors(), &chart_reference(), model)
+    }
+
+    #[must_use]
+    pub fn model(&self) -> CcmModel {
+        self.model
+    }
+
+    /// The 3x3 matrix on linear RGB, rows giving output channels, for the
+    /// linear and affine models
+    #[must_use]
+    pub fn matrix(&self) -> Option<[[f64; 3]; 3]> {
+        (self.model != CcmModel::Polynomial).then(|| {
+            let mut m = [[0.0; 3]; 3];
+            for (out, row) in m.iter_mut().enumerate() {
+                for (input, v) in row.iter_mut().enumerate() {
+                    *v = self.coeffs[input][out];
+                }
+            }
+            m
+        })
+    }
+
+    /// Correct one sRGB colour (0-255); results may leave that range
+    #[must_use]
+    pub fn correct(&self, rgb: [f64; 3]) -> [f64; 3] {
+        self.correct_linear(rgb.map(srgb_to_linear)).map(linear_to_srgb)
+    }
+
+    fn correct_linear(&self, linear: [f64; 3]) -> [f64; 3] {
+        let mut out = [0.0; 3];
+        for (t, w) in self.model.terms(linear).iter().zip(&self.coeffs) {
+            for ch in 0..3 {
+                out[ch] += t * w[ch];
+            }
+        }
+        out
+    }
+}
+
+/// Apply a colour correction to a U8 RGB or BGR image
+///
+/// The channel order comes from the colour order tag; an alpha channel is
+/// copied unchanged. `dst` has the type and tag of `src`.
+pub fn apply_ccm(src: &Mat, dst: &mut Mat, ccm: &ColorCorrectionMatrix) -> Result<()> {
+    check_color_image(src, "Colour correction")?;
+    let bgr = src.color_order().or_convention() == ColorOrder::Bgr;
+    let decode: Vec<f64> = (0..=255u8).map(|v| srgb_to_linear(f64::from(v))).collect();
+
+    *dst = src.clone();
+    let channels = src.channels();
+    for pixel in dst.data_mut().chunks_exact_mut(channels) {
+        let mut rgb = [decode[pixel[0] as usize], decode[pixel[1] as usize], decode[pixel[2] as usize]];
+        if bgr {
+            rgb.swap(0, 2);
+        }
+        let mut out = ccm.correct_linear(rgb).map(|v| linear_to_srgb(v).round().clamp(0.0, 255.0) as u8);
+        if bgr {
+            out.swap(0, 2);
+        }
+        pixel[..3].copy_from_slice(&out);
+    }
+    Ok(())
+}
+
+fn check_color_image(src: &Mat, what: &str) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(format!("{what} supports U8 images")));
+    }
+    if !matches!(src.channels(), 3 | 4) {
+        return Err(Error::InvalidParameter(format!("{what} requires a 3 or 4-channel image")));
+    }
+    Ok(())
+}
+
+/// sRGB 0-255 to linear 0-1
+fn srgb_to_linear(v: f64) -> f64 {
+    let c = v / 255.0;
+    if c <= 0.040_45 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// Linear 0-1 to sRGB 0-255, extrapolating linearly below 0
+fn linear_to_srgb(v: f64) -> f64 {
+    255.0 * if v <= 0.003_130_8 { 12.92 * v } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Solve the normal equations `a x = b` for three right-hand sides
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<[f64; 3]>) -> Option<Vec<[f64; 3]>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            let (pivot_row, pivot_b) = (a[col].clone(), b[col]);
+            for (v, p) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                *v -= factor * p;
+            }
+            for ch in 0..3 {
+                b[row][ch] -= factor * pivot_b[ch];
+            }
+        }
+    }
+    let mut x = vec![[0.0; 3]; n];
+    for row in (0..n).rev() {
+        for ch in 0..3 {
+            let tail: f64 = (row + 1..n).map(|k| a[row][k] * x[k][ch]).sum();
+            x[row][ch] = (b[row][ch] - tail) / a[row][row];
+        }
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Scalar;
+    use crate::imgproc::flip;
+
+    /// A camera with a colour cast: linear RGB through `CAST`
+    const CAST: [[f64; 3]; 3] = [[0.85, 0.1, 0.0], [0.05, 0.9, 0.1], [0.0, 0.15, 0.7]];
+
+    fn shoot(rgb: [u8; 3]) -> [u8; 3] {
+        let lin = rgb.map(|v| srgb_to_linear(f64::from(v)));
+        CAST.map(|row| linear_to_srgb(row[0] * lin[0] + row[1] * lin[1] + row[2] * lin[2]).round().clamp(0.0, 255.0) as u8)
+    }
+
+    /// 6x4 chart of 30 px patches with 8 px dark gaps on a gray background
+    fn chart_image() -> Mat {
+        let mut img = Mat::new_with_default(200, 280, 3, MatDepth::U8, Scalar::all(90.0)).unwrap();
+        for r in 16..170 {
+            for c in 20..254 {
+                img.at_mut(r, c).unwrap().copy_from_slice(&[20, 20, 20]);
+            }
+        }
+        for (i, &color) in COLORCHECKER_SRGB.iter().enumerate() {
+            let (row, col) = (i / 6, i % 6);
+            for r in 0..30 {
+                for c in 0..30 {
+                    let pixel = img.at_mut(24 + row * 38 + r, 28 + col * 38 + c).unwrap();
+                    pixel.copy_from_slice(&shoot(color));
+                }
+            }
+        }
+        img.with_color_order(ColorOrder::Rgb)
+    }
+
+    #[test]
+    fn test_detect_and_correct_chart() {
+        let img = chart_image();
+        let chart = ColorCheckerDetector::new().detect(&img).unwrap().expect("chart");
+        assert_eq!(chart.patches.len(), 24);
+        assert!((chart.patches[0].center.x - 42.5).abs() < 1.0 && (chart.patches[0].center.y - 38.5).abs() < 1.0);
+        assert!((chart.patches[23].center.x - 232.5).abs() < 1.0 && (chart.patches[23].center.y - 152.5).abs() < 1.0);
+
+        let ccm = ColorCorrectionMatrix::fit_chart(&chart, CcmModel::Linear).unwrap();
+        for (patch, reference) in chart.patches.iter().zip(COLORCHECKER_SRGB) {
+            let corrected = ccm.correct(patch.color);
+            for ch in 0..3 {
+                assert!((corrected[ch] - f64::from(reference[ch])).abs() < 3.0, "{corrected:?} vs {reference:?}");
+            }
+        }
+        assert!(ccm.matrix().is_some());
+
+        let mut corrected = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        apply_ccm(&img, &mut corrected, &ccm).unwrap();
+        let blue = corrected.at(24 + 2 * 38 + 15, 28 + 15).unwrap();
+        assert!(blue.iter().zip(COLORCHECKER_SRGB[12]).all(|(&a, b)| a.abs_diff(b) <= 3), "{blue:?}");
+
+        // Upside down and in BGR order: same chart order and colours
+        let mut turned = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        flip(&img, &mut turned, -1).unwrap();
+        let mut bgr = turned.clone();
+        for p in bgr.data_mut().chunks_exact_mut(3) {
+            p.swap(0, 2);
+        }
+        let chart_bgr = ColorCheckerDetector::new().detect(&bgr.with_color_order(ColorOrder::Bgr)).unwrap().expect("chart");
+        assert_eq!(chart_bgr.colors(), chart.colors());
+        assert!((chart_bgr.patches[0].center.x - (279.0 - 42.5)).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_ccm_models() {
+        let reference = chart_reference();
+        for model in [CcmModel::Linear, CcmModel::Affine, CcmModel::Polynomial] {
+            let identity = ColorCorrectionMatrix::fit(&reference, &reference, model).unwrap();
+            let c = identity.correct([120.0, 60.0, 200.0]);
+            assert!(c.iter().zip([120.0, 60.0, 200.0]).all(|(a, b)| (a - b).abs() < 1e-6), "{model:?}: {c:?}");
+        }
+        assert!(ColorCorrectionMatrix::fit(&reference, &reference, CcmModel::Polynomial).unwrap().matrix().is_none());
+        assert!(ColorCorrectionMatrix::fit(&reference[..5], &reference[..5], CcmModel::Polynomial).is_err());
+
+        let blank = Mat::new(50, 50, 3, MatDepth::U8).unwrap();
+        assert!(ColorCheckerDetector::new().detect(&blank).unwrap().is_none());
+    }
+}
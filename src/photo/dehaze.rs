@@ -0,0 +1,211 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::guided_filter;
+
+/// Patch radius of the dark channel (15x15 patches, as in He et al.)
+const DARK_CHANNEL_RADIUS: usize = 7;
+/// Guided filter window radius used to refine the transmission map
+const REFINE_RADIUS: i32 = 30;
+/// Guided filter regularization: 1e-3 on a 0-1 scale, in squared U8 units
+const REFINE_EPS: f64 = 1e-3 * 255.0 * 255.0;
+
+/// Dark channel of a U8 image: per-pixel minimum over channels, followed by
+/// a minimum filter over `(2 * radius + 1)` square patches
+///
+/// In haze-free outdoor images most patches contain some pixel that is dark
+/// in at least one channel, so a bright dark channel indicates haze. Returns
+/// a single-channel U8 Mat.
+pub fn dark_channel(src: &Mat, radius: usize) -> Result<Mat> {
+    check_dehaze_input(src)?;
+    let channels = src.channels();
+    let min_rgb: Vec<f32> = src
+        .data()
+        .chunks_exact(channels)
+        .map(|px| f32::from(*px.iter().min().unwrap_or(&0)))
+        .collect();
+
+    let dark = min_filter(&min_rgb, src.rows(), src.cols(), radius);
+    let mut out = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8)?;
+    for (d, v) in out.data_mut().iter_mut().zip(dark) {
+        *d = v as u8;
+    }
+    Ok(out)
+}
+
+/// Remove haze with the dark channel prior (He, Sun & Tang, 2009)
+///
+/// The atmospheric light is taken from the brightest of the 0.1% haziest
+/// pixels, the transmission is estimated as `1 - omega * dark(I / A)` and
+/// refined with a guided filter over the image luminance, and the scene is
+/// recovered as `(I - A) / max(t, t0) + A`. `omega` (typically 0.95) keeps a
+/// little haze for depth perception; `t0` (typically 0.1) bounds the
+/// amplification in dense haze. `src` is 1- or 3-channel U8.
+pub fn dehaze(src: &Mat, dst: &mut Mat, omega: f64, t0: f64) -> Result<()> {
+    check_dehaze_input(src)?;
+    if !((0.0..=1.0).contains(&omega) && t0 > 0.0 && t0 <= 1.0) {
+        return Err(Error::InvalidParameter(
+            "omega must be in [0, 1] and t0 in (0, 1]".to_string(),
+        ));
+    }
+
+    let (rows, cols, channels) = (src.rows(), src.cols(), src.channels());
+    let data = src.data();
+    let dark = dark_channel(src, DARK_CHANNEL_RADIUS)?;
+    let airlight = estimate_airlight(data, dark.data(), channels);
+
+    // Transmission from the dark channel of the airlight-normalized image
+    let normalized_min: Vec<f32> = data
+        .chunks_exact(channels)
+        .map(|px| {
+            px.iter()
+                .zip(&airlight)
+                .map(|(&v, &a)| f32::from(v) / a)
+                .fold(f32::INFINITY, f32::min)
+        })
+        .collect();
+    let omega = omega as f32;
+    let mut transmission = Mat::new(rows, cols, 1, MatDepth::F32)?;
+    for (i, d) in min_filter(&normalized_min, rows, cols, DARK_CHANNEL_RADIUS).into_iter().enumerate() {
+        transmission.set_f32(i / cols, i % cols, 0, 1.0 - omega * d)?;
+    }
+
+    // Edge-aware refinement guided by luminance
+    let mut guide = Mat::new(rows, cols, 1, MatDepth::U8)?;
+    for (g, px) in guide.data_mut().iter_mut().zip(data.chunks_exact(channels)) {
+        *g = (px.iter().map(|&v| u32::from(v)).sum::<u32>() / channels as u32) as u8;
+    }
+    let mut refined = Mat::new(1, 1, 1, MatDepth::F32)?;
+    guided_filter(&transmission, &guide, &mut refined, REFINE_RADIUS, REFINE_EPS)?;
+
+    let t0 = t0 as f32;
+    *dst = Mat::new(rows, cols, channels, MatDepth::U8)?.with_color_order(src.color_order());
+    let out = dst.data_mut();
+    for (i, (px, o)) in data.chunks_exact(channels).zip(out.chunks_exact_mut(channels)).enumerate() {
+        let t = refined.at_f32(i / cols, i % cols, 0)?.clamp(t0, 1.0);
+        for ((&v, o), &a) in px.iter().zip(o.iter_mut()).zip(&airlight) {
+            *o = ((f32::from(v) - a) / t + a).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    Ok(())
+}
+
+fn check_dehaze_input(src: &Mat) -> Result<()> {
+    if src.depth() != MatDepth::U8 || !matches!(src.channels(), 1 | 3) {
+        return Err(Error::UnsupportedOperation(
+            "Dehazing requires a 1- or 3-channel U8 image".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Per-channel atmospheric light: the brightest (by channel sum) pixel among
+/// the 0.1% with the highest dark channel
+fn estimate_airlight(data: &[u8], dark: &[u8], channels: usize) -> Vec<f32> {
+    let mut order: Vec<usize> = (0..dark.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(dark[i]));
+    let candidates = (dark.len() / 1000).max(1);
+
+    let brightest = order[..candidates.min(order.len())]
+        .iter()
+        .copied()
+        .max_by_key(|&i| data[i * channels..(i + 1) * channels].iter().map(|&v| u32::from(v)).sum::<u32>())
+        .unwrap_or(0);
+    data[brightest * channels..(brightest + 1) * channels]
+        .iter()
+        // A zero component would make I / A undefined
+        .map(|&v| f32::from(v).max(1.0))
+        .collect()
+}
+
+/// Separable minimum filter with clamped borders
+fn min_filter(plane: &[f32], rows: usize, cols: usize, radius: usize) -> Vec<f32> {
+    let mut horizontal = vec![0.0f32; plane.len()];
+    for row in 0..rows {
+        let line = &plane[row * cols..(row + 1) * cols];
+        for col in 0..cols {
+            let (lo, hi) = (col.saturating_sub(radius), (col + radius).min(cols - 1));
+            horizontal[row * cols + col] = line[lo..=hi].iter().copied().fold(f32::INFINITY, f32::min);
+        }
+    }
+
+    let mut out = vec![0.0f32; plane.len()];
+    for row in 0..rows {
+        let (lo, hi) = (row.saturating_sub(radius), (row + radius).min(rows - 1));
+        for col in 0..cols {
+            out[row * cols + col] = (lo..=hi)
+                .map(|r| horizontal[r * cols + col])
+                .fold(f32::INFINITY, f32::min);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Rect;
+
+    /// Dark textured scene `J` blended with bright airlight: I = J t + A (1 - t),
+    /// with transmission falling from 0.9 at the bottom to 0.4 below a band of
+    /// sky at the top
+    fn hazy_scene() -> (Mat, Mat) {
+        let (rows, cols) = (48, 48);
+        let mut clear = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        let mut hazy = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        let airlight = [220.0f32, 225.0, 230.0];
+        for row in 0..rows {
+            let t = 0.4 + 0.5 * row.saturating_sub(8) as f32 / (rows - 9) as f32;
+            for col in 0..cols {
+                let j = if row < 8 {
+                    airlight
+                } else {
+                    [
+                        if (row / 4 + col / 4) % 2 == 0 { 20.0 } else { 120.0 },
+                        60.0 + col as f32,
+                        if col % 8 < 2 { 0.0 } else { 90.0 },
+                    ]
+                };
+                for ch in 0..3 {
+                    clear.at_mut(row, col).unwrap()[ch] = j[ch] as u8;
+                    hazy.at_mut(row, col).unwrap()[ch] = (j[ch] * t + airlight[ch] * (1.0 - t)).round() as u8;
+                }
+            }
+        }
+        (clear, hazy)
+    }
+
+    fn mean_abs_diff(a: &Mat, b: &Mat) -> f64 {
+        a.data()
+            .iter()
+            .zip(b.data())
+            .map(|(&x, &y)| f64::from(x.abs_diff(y)))
+            .sum::<f64>()
+            / a.data().len() as f64
+    }
+
+    #[test]
+    fn test_dark_channel() {
+        let (clear, hazy) = hazy_scene();
+        let mean = |m: &Mat| m.data().iter().map(|&v| f64::from(v)).sum::<f64>() / m.data().len() as f64;
+        let clear_dark = dark_channel(&clear.roi(Rect::new(0, 8, 48, 40)).unwrap(), 3).unwrap();
+        assert_eq!(clear_dark.channels(), 1);
+        assert!(mean(&clear_dark) < 10.0);
+        assert!(mean(&dark_channel(&hazy, 3).unwrap()) > 50.0);
+    }
+
+    #[test]
+    fn test_dehaze() {
+        let (clear, hazy) = hazy_scene();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        dehaze(&hazy, &mut dst, 0.95, 0.1).unwrap();
+        assert_eq!((dst.rows(), dst.cols(), dst.channels()), (48, 48, 3));
+
+        let before = mean_abs_diff(&hazy, &clear);
+        let after = mean_abs_diff(&dst, &clear);
+        assert!(after < 0.5 * before, "error {after} vs hazy {before}");
+
+        assert!(dehaze(&hazy, &mut dst, 1.5, 0.1).is_err());
+        assert!(dehaze(&hazy, &mut dst, 0.95, 0.0).is_err());
+    }
+}
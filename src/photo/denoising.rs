@@ -1,4 +1,5 @@
 use crate::core::Mat;
+use crate::core::parallel::parallel_for_rows;
 use crate::error::{Error, Result};
 
 /// Non-local Means Denoising for color images
@@ -20,8 +21,8 @@ pub fn fast_nl_means_denoising_colored(
     let half_template = template_window_size / 2;
     let half_search = search_window_size / 2;
 
-    for row in 0..src.rows() {
-        for col in 0..src.cols() {
+    parallel_for_rows(result.data_mut(), src.cols() * 3, |row, result_row| {
+        for (col, result_pixel) in result_row.chunks_exact_mut(3).enumerate() {
             let mut weight_sum = 0.0f32;
             let mut pixel_sum = [0.0f32; 3];
 
@@ -71,7 +72,6 @@ pub fn fast_nl_means_denoising_colored(
                 }
             }
 
-            let result_pixel = result.at_mut(row, col)?;
             #[allow(clippy::cast_possible_truncation)]
             for ch in 0..3 {
                 result_pixel[ch] = if weight_sum > 0.0 {
@@ -84,7 +84,8 @@ pub fn fast_nl_means_denoising_colored(
                 };
             }
         }
-    }
+        Ok(())
+    })?;
 
     Ok(result)
 }
@@ -171,10 +172,11 @@ pub fn bilateral_filter(
         }
     }
 
-    for row in 0..src.rows() {
-        for col in 0..src.cols() {
+    let channels = src.channels();
+    parallel_for_rows(result.data_mut(), src.cols() * channels, |row, result_row| {
+        for (col, result_pixel) in result_row.chunks_exact_mut(channels).enumerate() {
             #[allow(clippy::cast_possible_truncation)]
-            for ch in 0..src.channels() {
+            for (ch, out) in result_pixel.iter_mut().enumerate() {
                 let center_val = f32::from(src.at(row, col)?[ch]);
                 let mut sum = 0.0f32;
                 let mut weight_sum = 0.0f32;
@@ -214,10 +216,11 @@ pub fn bilateral_filter(
                 let clamped = (sum / weight_sum).clamp(0.0, 255.0);
                 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
                 let pixel_val = clamped as u8;
-                result.at_mut(row, col)?[ch] = pixel_val;
+                *out = pixel_val;
             }
         }
-    }
+        Ok(())
+    })?;
 
     Ok(result)
 }
@@ -1,5 +1,7 @@
 use crate::core::{Mat, MatDepth};
+use crate::core::types::Point;
 use crate::error::{Error, Result};
+use crate::imgproc::to_gray;
 
 /// Merge exposures using Debevec method to create HDR image
 pub struct MergeDebevec {
@@ -22,15 +24,24 @@ impl MergeDebevec {
     /// exposures: Vec of images at different exposures
     /// times: Exposure times in seconds
     pub fn process(&self, exposures: &[Mat], times: &[f32]) -> Result<Mat> {
-        if exposures.is_empty() || times.is_empty() {
-            return Err(Error::InvalidParameter(
-                "Need at least one exposure".to_string(),
-            ));
-        }
+        validate_exposures(exposures, times)?;
+
+        // Estimate camera response curve (simplified)
+        let response_curve = self.estimate_response_curve(exposures, times)?;
 
-        if exposures.len() != times.len() {
+        self.process_with_response(exposures, times, &response_curve)
+    }
+
+    /// Merge exposures using a known camera response curve
+    ///
+    /// `response` holds one 256-entry log-exposure table per channel, e.g. from
+    /// [`calibrate_debevec`].
+    pub fn process_with_response(&self, exposures: &[Mat], times: &[f32], response: &[Vec<f32>]) -> Result<Mat> {
+        validate_exposures(exposures, times)?;
+
+        if response.len() != exposures[0].channels() || response.iter().any(|c| c.len() != 256) {
             return Err(Error::InvalidParameter(
-                "Number of exposures must match number of times".to_string(),
+                "Need one 256-entry response curve per channel".to_string(),
             ));
         }
 
@@ -51,14 +62,10 @@ impl MergeDebevec {
             }
         };
 
-        // Estimate camera response curve (simplified)
-        let response_curve = self.estimate_response_curve(exposures, times)?;
-
         // Merge exposures
         for row in 0..rows {
             for col in 0..cols {
-                #[allow(clippy::cast_possible_truncation)]
-                for ch in 0..channels {
+                for (ch, curve) in response.iter().enumerate() {
                     let mut weighted_sum = 0.0f32;
                     let mut weight_sum = 0.0f32;
 
@@ -67,7 +74,7 @@ impl MergeDebevec {
                         let w = weight(pixel_val);
 
                         if w > 0.0 {
-                            let radiance = response_curve[ch][usize::from(pixel_val)] - times[i].ln();
+                            let radiance = curve[usize::from(pixel_val)] - times[i].ln();
                             weighted_sum += w * radiance;
                             weight_sum += w;
                         }
@@ -131,6 +138,32 @@ impl MergeDebevec {
     }
 }
 
+fn validate_exposures(exposures: &[Mat], times: &[f32]) -> Result<()> {
+    if exposures.is_empty() || times.is_empty() {
+        return Err(Error::InvalidParameter(
+            "Need at least one exposure".to_string(),
+        ));
+    }
+
+    if exposures.len() != times.len() {
+        return Err(Error::InvalidParameter(
+            "Number of exposures must match number of times".to_string(),
+        ));
+    }
+
+    let (rows, cols, channels) = (exposures[0].rows(), exposures[0].cols(), exposures[0].channels());
+    if exposures
+        .iter()
+        .any(|e| e.rows() != rows || e.cols() != cols || e.channels() != channels || e.depth() != MatDepth::U8)
+    {
+        return Err(Error::InvalidParameter(
+            "Exposures must be U8 images of the same size and channel count".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Tonemap HDR image to LDR using Reinhard method
 pub struct TonemapReinhard {
     intensity: f32,
@@ -382,6 +415,361 @@ pub fn calibrate_debevec(
     Ok(response_curves)
 }
 
+/// Align exposures with median threshold bitmaps (Ward's MTB)
+///
+/// Each image is reduced to a bitmap of "brighter than its own median", which is
+/// nearly invariant to exposure, and the translation between bitmaps is searched
+/// coarse-to-fine over an image pyramid. Only translation is recovered.
+pub struct AlignMtb {
+    max_bits: u32,
+    exclude_range: u8,
+}
+
+impl Default for AlignMtb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlignMtb {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_bits: 6,
+            exclude_range: 4,
+        }
+    }
+
+    /// Number of pyramid levels; the largest recoverable shift is `2^max_bits - 1`
+    #[must_use]
+    pub fn with_max_bits(mut self, max_bits: u32) -> Self {
+        self.max_bits = max_bits;
+        self
+    }
+
+    /// Pixels within this distance of the median are ignored as noise
+    #[must_use]
+    pub fn with_exclude_range(mut self, exclude_range: u8) -> Self {
+        self.exclude_range = exclude_range;
+        self
+    }
+
+    /// Shift that moves `img` onto `reference` (apply it with [`shift_mat`])
+    pub fn calculate_shift(&self, reference: &Mat, img: &Mat) -> Result<Point> {
+        if reference.rows() != img.rows() || reference.cols() != img.cols() {
+            return Err(Error::InvalidDimensions(
+                "Images to align must have the same size".to_string(),
+            ));
+        }
+
+        let pyr0 = gray_pyramid(reference, self.max_bits)?;
+        let pyr1 = gray_pyramid(img, self.max_bits)?;
+
+        let mut shift = Point::new(0, 0);
+        for (level0, level1) in pyr0.iter().zip(&pyr1).rev() {
+            let (tb0, eb0) = level0.bitmaps(self.exclude_range);
+            let (tb1, eb1) = level1.bitmaps(self.exclude_range);
+
+            shift = Point::new(shift.x * 2, shift.y * 2);
+            let mut best = (f64::INFINITY, shift);
+
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let candidate = Point::new(shift.x + dx, shift.y + dy);
+                    let err = bitmap_error(&tb0, &eb0, &tb1, &eb1, level0.cols, level0.rows, candidate);
+                    if err < best.0 {
+                        best = (err, candidate);
+                    }
+                }
+            }
+
+            shift = best.1;
+        }
+
+        Ok(shift)
+    }
+
+    /// Align every exposure to the middle one
+    ///
+    /// Returns the aligned images and the shift applied to each. Uncovered borders
+    /// are black, which the Debevec weighting ignores when merging.
+    pub fn process(&self, exposures: &[Mat]) -> Result<(Vec<Mat>, Vec<Point>)> {
+        if exposures.is_empty() {
+            return Err(Error::InvalidParameter("Need at least one exposure".to_string()));
+        }
+
+        let pivot = exposures.len() / 2;
+        let mut aligned = Vec::with_capacity(exposures.len());
+        let mut shifts = Vec::with_capacity(exposures.len());
+
+        for (i, exposure) in exposures.iter().enumerate() {
+            let shift = if i == pivot {
+                Point::new(0, 0)
+            } else {
+                self.calculate_shift(&exposures[pivot], exposure)?
+            };
+
+            let mut dst = Mat::new(1, 1, 1, MatDepth::U8)?;
+            shift_mat(exposure, &mut dst, shift)?;
+            aligned.push(dst);
+            shifts.push(shift);
+        }
+
+        Ok((aligned, shifts))
+    }
+}
+
+/// Translate an image by whole pixels, filling uncovered areas with zeros
+pub fn shift_mat(src: &Mat, dst: &mut Mat, shift: Point) -> Result<()> {
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), src.depth())?.with_color_order(src.color_order());
+
+    let elem = src.elem_size();
+    let row_bytes = src.cols() * elem;
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    let (rows, cols) = (src.rows() as i32, src.cols() as i32);
+    let (sx, sy) = (shift.x, shift.y);
+
+    if sx.abs() >= cols || sy.abs() >= rows {
+        return Ok(());
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    let span = (cols - sx.abs()) as usize * elem;
+    #[allow(clippy::cast_sign_loss)]
+    let (src_x, dst_x) = ((-sx).max(0) as usize * elem, sx.max(0) as usize * elem);
+
+    for dst_row in sy.max(0)..rows.min(rows + sy) {
+        #[allow(clippy::cast_sign_loss)]
+        let src_off = (dst_row - sy) as usize * row_bytes + src_x;
+        #[allow(clippy::cast_sign_loss)]
+        let dst_off = dst_row as usize * row_bytes + dst_x;
+        dst.data_mut()[dst_off..dst_off + span].copy_from_slice(&src.data()[src_off..src_off + span]);
+    }
+
+    Ok(())
+}
+
+struct GrayLevel {
+    data: Vec<u8>,
+    rows: usize,
+    cols: usize,
+}
+
+impl GrayLevel {
+    /// Threshold bitmap (above median) and exclusion bitmap (far from median)
+    fn bitmaps(&self, exclude_range: u8) -> (Vec<bool>, Vec<bool>) {
+        let mut hist = [0usize; 256];
+        for &v in &self.data {
+            hist[usize::from(v)] += 1;
+        }
+
+        let half = self.data.len() / 2;
+        let mut acc = 0;
+        let mut median = 0u8;
+        for (v, &count) in hist.iter().enumerate() {
+            acc += count;
+            if acc > half {
+                #[allow(clippy::cast_possible_truncation)]
+                let m = v as u8;
+                median = m;
+                break;
+            }
+        }
+
+        let threshold = self.data.iter().map(|&v| v > median).collect();
+        let exclusion = self.data.iter().map(|&v| v.abs_diff(median) > exclude_range).collect();
+        (threshold, exclusion)
+    }
+}
+
+fn gray_pyramid(img: &Mat, levels: u32) -> Result<Vec<GrayLevel>> {
+    if img.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "MTB alignment only supports U8 depth".to_string(),
+        ));
+    }
+
+    let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+    to_gray(img, &mut gray)?;
+    let data = gray.data().to_vec();
+
+    let mut pyramid = vec![GrayLevel { data, rows: img.rows(), cols: img.cols() }];
+
+    for _ in 0..levels {
+        let prev = &pyramid[pyramid.len() - 1];
+        if prev.rows < 16 || prev.cols < 16 {
+            break;
+        }
+
+        let (rows, cols) = (prev.rows / 2, prev.cols / 2);
+        let mut data = Vec::with_capacity(rows * cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                let at = |rr: usize, cc: usize| u16::from(prev.data[rr * prev.cols + cc]);
+                let sum = at(2 * r, 2 * c) + at(2 * r, 2 * c + 1) + at(2 * r + 1, 2 * c) + at(2 * r + 1, 2 * c + 1);
+                #[allow(clippy::cast_possible_truncation)]
+                data.push(((sum + 2) / 4) as u8);
+            }
+        }
+
+        pyramid.push(GrayLevel { data, rows, cols });
+    }
+
+    Ok(pyramid)
+}
+
+/// Fraction of differing bits between bitmap 0 and bitmap 1 translated by `shift`
+///
+/// Normalizing by the overlap keeps large shifts, which compare fewer pixels,
+/// from looking artificially good on the small pyramid levels.
+fn bitmap_error(
+    tb0: &[bool],
+    eb0: &[bool],
+    tb1: &[bool],
+    eb1: &[bool],
+    cols: usize,
+    rows: usize,
+    shift: Point,
+) -> f64 {
+    let mut err = 0usize;
+    let mut overlap = 0usize;
+
+    for r in 0..rows {
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+        let src_r = r as i32 - shift.y;
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+        if src_r < 0 || src_r >= rows as i32 {
+            continue;
+        }
+
+        for c in 0..cols {
+            #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+            let src_c = c as i32 - shift.x;
+            #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+            if src_c < 0 || src_c >= cols as i32 {
+                continue;
+            }
+
+            let i0 = r * cols + c;
+            #[allow(clippy::cast_sign_loss)]
+            let i1 = src_r as usize * cols + src_c as usize;
+            if eb0[i0] && eb1[i1] {
+                overlap += 1;
+                if tb0[i0] != tb1[i1] {
+                    err += 1;
+                }
+            }
+        }
+    }
+
+    if overlap == 0 {
+        return f64::INFINITY;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let ratio = err as f64 / overlap as f64;
+    ratio
+}
+
+/// Tonemapping operator used by [`HdrPipeline`]
+pub enum HdrTonemap {
+    Reinhard(TonemapReinhard),
+    Drago(TonemapDrago),
+}
+
+impl HdrTonemap {
+    fn process(&self, hdr: &Mat) -> Result<Mat> {
+        match self {
+            Self::Reinhard(t) => t.process(hdr),
+            Self::Drago(t) => t.process(hdr),
+        }
+    }
+}
+
+/// Everything produced by [`HdrPipeline::process`]
+pub struct HdrResult {
+    /// Merged radiance map (F32)
+    pub hdr: Mat,
+    /// Tonemapped display image (U8)
+    pub ldr: Mat,
+    /// Translation applied to each exposure during alignment
+    pub shifts: Vec<Point>,
+    /// Calibrated camera response, one 256-entry log curve per channel
+    pub response: Vec<Vec<f32>>,
+}
+
+/// Bracketed exposures to a tonemapped image in one call
+///
+/// Runs MTB alignment, response calibration, Debevec merging and tonemapping.
+/// Handheld brackets are rarely registered to the pixel, and merging them
+/// unaligned produces ghosted edges.
+pub struct HdrPipeline {
+    align: Option<AlignMtb>,
+    samples: usize,
+    tonemap: HdrTonemap,
+}
+
+impl Default for HdrPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HdrPipeline {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            align: Some(AlignMtb::new()),
+            samples: 256,
+            tonemap: HdrTonemap::Reinhard(TonemapReinhard::new()),
+        }
+    }
+
+    /// Use a custom aligner, or `None` for frames that are already registered
+    #[must_use]
+    pub fn with_align(mut self, align: Option<AlignMtb>) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Number of pixels sampled per exposure for response calibration
+    #[must_use]
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    #[must_use]
+    pub fn with_tonemap(mut self, tonemap: HdrTonemap) -> Self {
+        self.tonemap = tonemap;
+        self
+    }
+
+    pub fn process(&self, exposures: &[Mat], times: &[f32]) -> Result<HdrResult> {
+        validate_exposures(exposures, times)?;
+
+        let (aligned, shifts) = match &self.align {
+            Some(align) => align.process(exposures)?,
+            None => (
+                exposures.iter().map(Mat::clone_mat).collect(),
+                vec![Point::new(0, 0); exposures.len()],
+            ),
+        };
+
+        let response = calibrate_debevec(&aligned, times, self.samples)?;
+        let hdr = MergeDebevec::new().process_with_response(&aligned, times, &response)?;
+        let ldr = self.tonemap.process(&hdr)?;
+
+        Ok(HdrResult {
+            hdr,
+            ldr,
+            shifts,
+            response,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -454,4 +842,62 @@ mod tests {
         assert_eq!(curves.len(), 3); // 3 channels
         assert_eq!(curves[0].len(), 256);
     }
+
+    fn textured(rows: usize, cols: usize, offset: (usize, usize), gain: f32) -> Mat {
+        let mut img = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let (y, x) = (row + 64 - offset.1, col + 64 - offset.0);
+                // Random 3x3 blocks: structure at every pyramid level, no periodicity
+                let h = ((x / 3) as u32).wrapping_mul(73_856_093) ^ ((y / 3) as u32).wrapping_mul(19_349_663);
+                let base = 10.0 + (h.wrapping_mul(2_654_435_761) >> 26) as f32;
+                let v = (base * gain).min(255.0) as u8;
+                img.at_mut(row, col).unwrap().copy_from_slice(&[v, v, v]);
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_align_mtb_recovers_shift() {
+        let reference = textured(96, 128, (0, 0), 1.0);
+        // Same scene, brighter exposure and moved 3 right / 2 down
+        let moved = textured(96, 128, (3, 2), 2.5);
+
+        let shift = AlignMtb::new().calculate_shift(&reference, &moved).unwrap();
+        assert_eq!(shift, Point::new(-3, -2));
+    }
+
+    #[test]
+    fn test_shift_mat() {
+        let mut src = Mat::new(4, 4, 1, MatDepth::U8).unwrap();
+        src.at_mut(1, 1).unwrap()[0] = 9;
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        shift_mat(&src, &mut dst, Point::new(2, -1)).unwrap();
+
+        assert_eq!(dst.at(0, 3).unwrap()[0], 9);
+        assert_eq!(dst.data().iter().filter(|&&v| v != 0).count(), 1);
+    }
+
+    #[test]
+    fn test_hdr_pipeline() {
+        let exposures = vec![
+            textured(64, 64, (1, 0), 1.0),
+            textured(64, 64, (0, 0), 2.0),
+            textured(64, 64, (0, 2), 4.0),
+        ];
+        let times = vec![0.01, 0.02, 0.04];
+
+        let result = HdrPipeline::new()
+            .with_tonemap(HdrTonemap::Drago(TonemapDrago::new()))
+            .process(&exposures, &times)
+            .unwrap();
+
+        assert_eq!(result.shifts, vec![Point::new(-1, 0), Point::new(0, 0), Point::new(0, -2)]);
+        assert_eq!(result.hdr.depth(), MatDepth::F32);
+        assert_eq!(result.ldr.depth(), MatDepth::U8);
+        assert_eq!(result.ldr.rows(), 64);
+        assert_eq!(result.response.len(), 3);
+    }
 }
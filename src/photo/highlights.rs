@@ -0,0 +1,463 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use std::collections::VecDeque;
+
+use crate::core::types::{Point2f, Rect};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+const NEIGHBORS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A connected blob of blown-out pixels found by [`HighlightDetector`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HighlightRegion {
+    pub bounding_box: Rect,
+    /// Pixel count, including the dilation fringe
+    pub area: usize,
+    pub centroid: Point2f,
+    pub mean_luminance: f64,
+    /// Fraction of the region with every channel clipped; such pixels carry
+    /// no colour information and can only be inpainted
+    pub clipped_fraction: f64,
+}
+
+/// Result of [`HighlightDetector::detect`]
+#[derive(Debug, Clone)]
+pub struct Highlights {
+    /// U8 mask, 255 inside highlight regions
+    pub mask: Mat,
+    pub regions: Vec<HighlightRegion>,
+    /// Channel value treated as clipped during detection
+    pub clip_level: u8,
+}
+
+/// How [`recover_highlights`] fills highlight regions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HighlightRecovery {
+    /// Replace the whole region by diffusing the surrounding pixels inwards,
+    /// e.g. to remove glare or specular reflections before stitching
+    Inpaint,
+    /// Rebuild clipped channels from the unclipped ones, assuming the
+    /// region has the colour of its surroundings; pixels with every channel
+    /// clipped are inpainted
+    #[default]
+    ChannelReconstruction,
+}
+
+/// Blown highlight and specular glare detection
+///
+/// A pixel is a highlight when its luminance reaches `luminance_threshold`
+/// or any of its channels reaches `clip_level`. The highlight mask is grown
+/// by `dilation` pixels to cover the bloom fringe and split into 8-connected
+/// regions. Regions with fewer than `min_area` highlight pixels before
+/// dilation (sensor noise, isolated hot pixels) are dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HighlightDetector {
+    pub luminance_threshold: f64,
+    pub clip_level: u8,
+    pub min_area: usize,
+    pub dilation: usize,
+}
+
+impl Default for HighlightDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HighlightDetector {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            luminance_threshold: 240.0,
+            clip_level: 250,
+            min_area: 4,
+            dilation: 1,
+        }
+    }
+
+    #[must_use]
+    pub fn with_luminance_threshold(mut self, threshold: f64) -> Self {
+        self.luminance_threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub fn with_clip_level(mut self, clip_level: u8) -> Self {
+        self.clip_level = clip_level;
+        self
+    }
+
+    #[must_use]
+    pub fn with_min_area(mut self, min_area: usize) -> Self {
+        self.min_area = min_area;
+        self
+    }
+
+    #[must_use]
+    pub fn with_dilation(mut self, dilation: usize) -> Self {
+        self.dilation = dilation;
+        self
+    }
+
+    /// Find the highlight regions of a 1- or 3-channel U8 image
+    ///
+    /// Colour luminance follows the image's
+    /// [`ColorOrder`](crate::core::types::ColorOrder) tag.
+    pub fn detect(&self, src: &Mat) -> Result<Highlights> {
+        check_highlight_input(src)?;
+        let (rows, cols, channels) = (src.rows(), src.cols(), src.channels());
+        let data = src.data();
+        let clip = self.clip_level;
+        let weights = src.color_order().luma_weights();
+
+        let seeds: Vec<bool> = data
+            .chunks_exact(channels)
+            .map(|px| luminance(px, weights) >= self.luminance_threshold || px.iter().any(|&v| v >= clip))
+            .collect();
+        let grown = dilate_mask(&seeds, rows, cols, self.dilation);
+        let (labels, count) = label_regions(&grown, rows, cols);
+
+        let mut stats = vec![RegionStats::default(); count];
+        for (i, label) in labels.iter().enumerate() {
+            if let Some(label) = label {
+                let px = &data[i * channels..(i + 1) * channels];
+                stats[*label].add(i % cols, i / cols, luminance(px, weights), seeds[i], px.iter().all(|&v| v >= clip));
+            }
+        }
+
+        let mut mask = Mat::new(rows, cols, 1, MatDepth::U8)?;
+        let keep: Vec<bool> = stats.iter().map(|s| s.seeds >= self.min_area).collect();
+        for (m, label) in mask.data_mut().iter_mut().zip(&labels) {
+            if label.is_some_and(|l| keep[l]) {
+                *m = 255;
+            }
+        }
+        let regions = stats.iter().zip(&keep).filter(|(_, &k)| k).map(|(s, _)| s.region()).collect();
+        Ok(Highlights { mask, regions, clip_level: clip })
+    }
+}
+
+/// Fill the highlight regions of `src`
+///
+/// `dst` is F32 on the 0-255 scale, so that reconstructed channels can
+/// exceed the sensor's clipping point, as HDR merging expects. `src` is the
+/// image `highlights` was detected on; 1-channel images are always
+/// inpainted since there is no other channel to reconstruct from.
+pub fn recover_highlights(src: &Mat, dst: &mut Mat, highlights: &Highlights, method: HighlightRecovery) -> Result<()> {
+    check_highlight_input(src)?;
+    let (rows, cols, channels) = (src.rows(), src.cols(), src.channels());
+    if highlights.mask.rows() != rows || highlights.mask.cols() != cols || highlights.mask.channels() != 1 {
+        return Err(Error::InvalidDimensions(
+            "Highlight mask must be single-channel and match the image size".to_string(),
+        ));
+    }
+    let masked: Vec<bool> = highlights.mask.data().iter().map(|&m| m > 0).collect();
+    let mut values: Vec<f64> = src.data().iter().map(|&v| f64::from(v)).collect();
+    let clip = highlights.clip_level;
+
+    // Pixels whose value is known after the reconstruction pass
+    let mut known: Vec<bool> = masked.iter().map(|m| !m).collect();
+    if method == HighlightRecovery::ChannelReconstruction && channels > 1 {
+        let (labels, count) = label_regions(&masked, rows, cols);
+        let surround = surrounding_colours(&values, &labels, count, rows, cols, channels);
+        for (i, label) in labels.iter().enumerate() {
+            let Some(colour) = label.and_then(|l| surround[l].as_ref()) else {
+                continue;
+            };
+            let px = &mut values[i * channels..(i + 1) * channels];
+            let unclipped = |v: f64| v < f64::from(clip);
+            let (observed, expected) = px
+                .iter()
+                .zip(colour)
+                .filter(|&(&v, _)| unclipped(v))
+                .fold((0.0, 0.0), |(o, e), (v, c)| (o + v, e + c));
+            if expected <= 0.0 {
+                continue;
+            }
+            let scale = observed / expected;
+            for (v, c) in px.iter_mut().zip(colour) {
+                if !unclipped(*v) {
+                    *v = v.max(scale * c);
+                }
+            }
+            known[i] = true;
+        }
+    }
+
+    let filled = diffuse(&values, &known, rows, cols, channels);
+    let inpaint = method == HighlightRecovery::Inpaint || channels == 1;
+    for (i, px) in values.chunks_exact_mut(channels).enumerate() {
+        if known[i] {
+            continue;
+        }
+        let Some(fill) = &filled[i] else {
+            continue;
+        };
+        for (v, &f) in px.iter_mut().zip(fill) {
+            // Fully clipped pixels are at least as bright as the clip level
+            *v = if inpaint { f } else { v.max(f) };
+        }
+    }
+
+    *dst = Mat::new(rows, cols, channels, MatDepth::F32)?.with_color_order(src.color_order());
+    for (i, &v) in values.iter().enumerate() {
+        dst.set_f32(i / (cols * channels), (i / channels) % cols, i % channels, v as f32)?;
+    }
+    Ok(())
+}
+
+fn check_highlight_input(src: &Mat) -> Result<()> {
+    if src.depth() != MatDepth::U8 || !matches!(src.channels(), 1 | 3) {
+        return Err(Error::UnsupportedOperation(
+            "Highlight detection requires a 1- or 3-channel U8 image".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rec. 601 luma of a colour or gray pixel, colour weighted by `weights`
+fn luminance(px: &[u8], weights: [f64; 3]) -> f64 {
+    match px {
+        [a, b, c] => weights[0] * f64::from(*a) + weights[1] * f64::from(*b) + weights[2] * f64::from(*c),
+        _ => f64::from(px[0]),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct RegionStats {
+    area: usize,
+    seeds: usize,
+    min: (usize, usize),
+    max: (usize, usize),
+    sum: (f64, f64),
+    luminance: f64,
+    clipped: usize,
+}
+
+impl RegionStats {
+    fn add(&mut self, x: usize, y: usize, luminance: f64, seed: bool, clipped: bool) {
+        if self.area == 0 {
+            self.min = (x, y);
+            self.max = (x, y);
+        }
+        self.area += 1;
+        self.seeds += usize::from(seed);
+        self.min = (self.min.0.min(x), self.min.1.min(y));
+        self.max = (self.max.0.max(x), self.max.1.max(y));
+        self.sum = (self.sum.0 + x as f64, self.sum.1 + y as f64);
+        self.luminance += luminance;
+        self.clipped += usize::from(clipped);
+    }
+
+    fn region(&self) -> HighlightRegion {
+        let area = self.area as f64;
+        HighlightRegion {
+            bounding_box: Rect::new(
+                self.min.0 as i32,
+                self.min.1 as i32,
+                (self.max.0 - self.min.0 + 1) as i32,
+                (self.max.1 - self.min.1 + 1) as i32,
+            ),
+            area: self.area,
+            centroid: Point2f::new((self.sum.0 / area) as f32, (self.sum.1 / area) as f32),
+            mean_luminance: self.luminance / area,
+            clipped_fraction: self.clipped as f64 / area,
+        }
+    }
+}
+
+fn neighbors(i: usize, rows: usize, cols: usize) -> impl Iterator<Item = usize> {
+    let (row, col) = (i / cols, i % cols);
+    NEIGHBORS.iter().filter_map(move |&(dr, dc)| {
+        let r = row.checked_add_signed(dr).filter(|&r| r < rows)?;
+        let c = col.checked_add_signed(dc).filter(|&c| c < cols)?;
+        Some(r * cols + c)
+    })
+}
+
+/// Grow `mask` by `radius` pixels with a square structuring element
+fn dilate_mask(mask: &[bool], rows: usize, cols: usize, radius: usize) -> Vec<bool> {
+    let mut grown = mask.to_vec();
+    for _ in 0..radius {
+        let previous = grown.clone();
+        for (i, g) in grown.iter_mut().enumerate() {
+            *g = previous[i] || neighbors(i, rows, cols).any(|n| previous[n]);
+        }
+    }
+    grown
+}
+
+/// 8-connected component labels of `mask`, and the number of components
+fn label_regions(mask: &[bool], rows: usize, cols: usize) -> (Vec<Option<usize>>, usize) {
+    let mut labels = vec![None; mask.len()];
+    let mut count = 0;
+    let mut queue = VecDeque::new();
+    for start in 0..mask.len() {
+        if !mask[start] || labels[start].is_some() {
+            continue;
+        }
+        labels[start] = Some(count);
+        queue.push_back(start);
+        while let Some(i) = queue.pop_front() {
+            for n in neighbors(i, rows, cols) {
+                if mask[n] && labels[n].is_none() {
+                    labels[n] = Some(count);
+                    queue.push_back(n);
+                }
+            }
+        }
+        count += 1;
+    }
+    (labels, count)
+}
+
+/// Mean colour of the unmasked pixels bordering each region, `None` for a
+/// region with no border (the whole image)
+fn surrounding_colours(
+    values: &[f64],
+    labels: &[Option<usize>],
+    count: usize,
+    rows: usize,
+    cols: usize,
+    channels: usize,
+) -> Vec<Option<Vec<f64>>> {
+    let mut sums = vec![vec![0.0; channels]; count];
+    let mut counts = vec![0usize; count];
+    for i in (0..labels.len()).filter(|&i| labels[i].is_none()) {
+        let mut seen: Vec<usize> = neighbors(i, rows, cols).filter_map(|n| labels[n]).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for label in seen {
+            for (s, v) in sums[label].iter_mut().zip(&values[i * channels..(i + 1) * channels]) {
+                *s += v;
+            }
+            counts[label] += 1;
+        }
+    }
+    sums.into_iter()
+        .zip(counts)
+        .map(|(sum, n)| (n > 0).then(|| sum.into_iter().map(|s| s / n as f64).collect()))
+        .collect()
+}
+
+/// Onion-peel fill: unknown pixels take the mean of their known neighbours,
+/// one ring at a time from the region border inwards
+fn diffuse(values: &[f64], known: &[bool], rows: usize, cols: usize, channels: usize) -> Vec<Option<Vec<f64>>> {
+    let mut filled: Vec<Option<Vec<f64>>> = known
+        .iter()
+        .enumerate()
+        .map(|(i, &k)| k.then(|| values[i * channels..(i + 1) * channels].to_vec()))
+        .collect();
+    let mut pending: Vec<usize> = (0..known.len()).filter(|&i| !known[i]).collect();
+    while !pending.is_empty() {
+        let ring: Vec<(usize, Vec<f64>)> = pending
+            .iter()
+            .filter_map(|&i| {
+                let mut sum = vec![0.0; channels];
+                let mut n = 0.0;
+                for px in neighbors(i, rows, cols).filter_map(|n| filled[n].as_ref()) {
+                    for (s, v) in sum.iter_mut().zip(px) {
+                        *s += v;
+                    }
+                    n += 1.0;
+                }
+                (n > 0.0).then(|| (i, sum.into_iter().map(|s| s / n).collect()))
+            })
+            .collect();
+        if ring.is_empty() {
+            break;
+        }
+        for (i, value) in ring {
+            filled[i] = Some(value);
+        }
+        pending.retain(|&i| filled[i].is_none());
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(rows: usize, cols: usize, colour: [u8; 3]) -> Mat {
+        let mut img = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        for px in img.data_mut().chunks_exact_mut(3) {
+            px.copy_from_slice(&colour);
+        }
+        img
+    }
+
+    fn paint(img: &mut Mat, rows: std::ops::Range<usize>, cols: std::ops::Range<usize>, colour: [u8; 3]) {
+        for row in rows {
+            for col in cols.clone() {
+                img.at_mut(row, col).unwrap().copy_from_slice(&colour);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_highlights() {
+        let mut img = solid(40, 40, [90, 100, 110]);
+        paint(&mut img, 10..20, 10..20, [255, 255, 255]);
+        // A hot pixel is below the minimum area
+        paint(&mut img, 30..31, 30..31, [255, 255, 255]);
+
+        let highlights = HighlightDetector::new().detect(&img).unwrap();
+        assert_eq!(highlights.regions.len(), 1);
+        let region = highlights.regions[0];
+        assert_eq!(region.bounding_box, Rect::new(9, 9, 12, 12));
+        assert_eq!(region.area, 144);
+        assert!((region.centroid.x - 14.5).abs() < 1e-4 && (region.centroid.y - 14.5).abs() < 1e-4);
+        assert!((region.clipped_fraction - 100.0 / 144.0).abs() < 1e-9);
+        assert_eq!(highlights.mask.at(9, 9).unwrap()[0], 255);
+        assert_eq!(highlights.mask.at(30, 30).unwrap()[0], 0);
+        assert!(HighlightDetector::new().detect(&Mat::new(4, 4, 3, MatDepth::F32).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_detect_highlights_bgr_tagged() {
+        use crate::core::types::ColorOrder;
+
+        // Bright yellow: luma 243 read as BGR, but 234 if read as RGB
+        let mut img = solid(20, 20, [40, 40, 40]);
+        paint(&mut img, 5..15, 5..15, [200, 249, 249]);
+        assert!(HighlightDetector::new().detect(&img).unwrap().regions.is_empty());
+
+        let img = img.with_color_order(ColorOrder::Bgr);
+        assert_eq!(HighlightDetector::new().detect(&img).unwrap().regions.len(), 1);
+    }
+
+    #[test]
+    fn test_recover_highlights_reconstruction() {
+        // An orange surface lit brighter in the middle; red clips there
+        let mut img = solid(30, 30, [200, 100, 50]);
+        paint(&mut img, 10..20, 10..20, [255, 180, 90]);
+        let highlights = HighlightDetector::new().detect(&img).unwrap();
+        assert_eq!(highlights.regions.len(), 1);
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        recover_highlights(&img, &mut dst, &highlights, HighlightRecovery::ChannelReconstruction).unwrap();
+        assert_eq!(dst.depth(), MatDepth::F32);
+        // Green and blue are 1.8x the surroundings, so red was 360
+        assert!((dst.at_f32(15, 15, 0).unwrap() - 360.0).abs() < 1e-3);
+        assert!((dst.at_f32(15, 15, 1).unwrap() - 180.0).abs() < 1e-3);
+        assert!((dst.at_f32(2, 2, 0).unwrap() - 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_recover_highlights_inpaint() {
+        let mut img = solid(30, 30, [60, 80, 100]);
+        paint(&mut img, 8..16, 12..22, [255, 255, 255]);
+        let highlights = HighlightDetector::new().detect(&img).unwrap();
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        recover_highlights(&img, &mut dst, &highlights, HighlightRecovery::Inpaint).unwrap();
+        for (ch, expected) in [60.0, 80.0, 100.0].into_iter().enumerate() {
+            assert!((dst.at_f32(12, 16, ch).unwrap() - expected).abs() < 1e-3);
+        }
+
+        // With nothing left unclipped, reconstruction keeps the clipped value
+        recover_highlights(&img, &mut dst, &highlights, HighlightRecovery::ChannelReconstruction).unwrap();
+        assert!((dst.at_f32(12, 16, 0).unwrap() - 255.0).abs() < 1e-3);
+    }
+}
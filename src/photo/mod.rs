@@ -2,11 +2,27 @@ pub mod hdr;
 pub mod seam_carving;
 pub mod super_resolution;
 pub mod denoising;
+pub mod vignetting;
+pub mod deconvolution;
+pub mod retinex;
+pub mod dehaze;
+pub mod highlights;
+pub mod patch_match;
+pub mod poisson;
+pub mod color_calibration;
 
 pub use hdr::*;
 pub use seam_carving::*;
 pub use super_resolution::*;
 pub use denoising::*;
+pub use vignetting::*;
+pub use deconvolution::*;
+pub use retinex::*;
+pub use dehaze::*;
+pub use highlights::*;
+pub use patch_match::{NnField, PatchMatch};
+pub use poisson::{divergence, gradient_field, poisson_solve, seamless_clone, CloneMode};
+pub use color_calibration::{apply_ccm, CcmModel, ChartPatch, ColorChecker, ColorCheckerDetector, ColorCorrectionMatrix, COLORCHECKER_SRGB};
 
 use crate::core::Mat;
 use crate::error::{Error, Result};
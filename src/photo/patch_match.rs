@@ -0,0 +1,372 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! PatchMatch approximate nearest-neighbour fields
+//!
+//! For every pixel of an image `a`, [`PatchMatch`] finds a pixel of `b` whose
+//! surrounding patch looks alike (Barnes et al., 2009). Starting from random
+//! guesses it alternates two cheap steps: *propagation*, which tries the
+//! neighbours' matches shifted by one pixel since coherent regions map
+//! coherently, and *random search* in exponentially shrinking windows around
+//! the current match. A few iterations converge to a field good enough for
+//! inpainting, reshuffling, texture transfer or dense correspondence.
+
+use crate::core::types::Point;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::noise::SplitMix64;
+
+/// Per-pixel matches from an image `a` into an image `b`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NnField {
+    rows: usize,
+    cols: usize,
+    targets: Vec<Point>,
+    distances: Vec<f32>,
+}
+
+impl NnField {
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Centre of the matching patch in `b` for pixel `(row, col)` of `a`
+    #[must_use]
+    pub fn target(&self, row: usize, col: usize) -> Point {
+        self.targets[row * self.cols + col]
+    }
+
+    /// Mean squared difference per value between the two patches
+    #[must_use]
+    pub fn distance(&self, row: usize, col: usize) -> f32 {
+        self.distances[row * self.cols + col]
+    }
+
+    /// Average patch distance over the field
+    #[must_use]
+    pub fn mean_distance(&self) -> f64 {
+        self.distances.iter().map(|&d| f64::from(d)).sum::<f64>() / self.distances.len().max(1) as f64
+    }
+
+    /// The field as offsets: a 2-channel F32 Mat of `(dx, dy)` from each
+    /// pixel of `a` to its match in `b`, in the layout of dense optical flow
+    pub fn to_flow(&self) -> Result<Mat> {
+        let mut flow = Mat::new(self.rows, self.cols, 2, MatDepth::F32)?;
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let t = self.target(row, col);
+                flow.set_f32(row, col, 0, (t.x - col as i32) as f32)?;
+                flow.set_f32(row, col, 1, (t.y - row as i32) as f32)?;
+            }
+        }
+        Ok(flow)
+    }
+
+    /// Rebuild `a` from `b` by copying each pixel's match
+    pub fn reconstruct(&self, b: &Mat, dst: &mut Mat) -> Result<()> {
+        let channels = b.channels();
+        let mut out = Mat::new(self.rows, self.cols, channels, b.depth())?;
+        out.set_color_order(b.color_order());
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let t = self.target(row, col);
+                out.at_mut(row, col)?.copy_from_slice(b.at(t.y as usize, t.x as usize)?);
+            }
+        }
+        *dst = out;
+        Ok(())
+    }
+}
+
+/// PatchMatch nearest-neighbour field search
+///
+/// ```rust,no_run
+/// # use opencv_rust::prelude::*;
+/// # use opencv_rust::photo::PatchMatch;
+/// # fn main() -> opencv_rust::error::Result<()> {
+/// # let (a, b) = (Mat::new(64, 64, 3, MatDepth::U8)?, Mat::new(64, 64, 3, MatDepth::U8)?);
+/// let field = PatchMatch::new(7)?.with_iterations(5).compute(&a, &b)?;
+/// let mut rebuilt = Mat::new(1, 1, 1, MatDepth::U8)?;
+/// field.reconstruct(&b, &mut rebuilt)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatchMatch {
+    patch_size: usize,
+    iterations: usize,
+    seed: u64,
+}
+
+impl PatchMatch {
+    /// Search with square patches of odd side `patch_size`, 5 iterations
+    pub fn new(patch_size: usize) -> Result<Self> {
+        if patch_size.is_multiple_of(2) {
+            return Err(Error::InvalidParameter(
+                "PatchMatch patch size must be odd".to_string(),
+            ));
+        }
+        Ok(Self { patch_size, iterations: 5, seed: 0x5EED })
+    }
+
+    /// Number of propagation/random-search sweeps
+    #[must_use]
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Seed for the random initialisation and search
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Match every pixel of `a` to a pixel of `b`
+    ///
+    /// Both images are U8 with the same number of channels; patches reaching
+    /// past the border replicate the edge pixels.
+    pub fn compute(&self, a: &Mat, b: &Mat) -> Result<NnField> {
+        self.search(a, b, None)
+    }
+
+    /// Like [`compute`](Self::compute), but only pixels of `b` where the
+    /// 1-channel U8 `allowed` mask is non-zero may be matched, e.g. to keep
+    /// an inpainting hole from matching itself
+    pub fn compute_masked(&self, a: &Mat, b: &Mat, allowed: &Mat) -> Result<NnField> {
+        if allowed.channels() != 1 || allowed.depth() != MatDepth::U8 {
+            return Err(Error::InvalidParameter(
+                "PatchMatch mask must be a 1-channel U8 Mat".to_string(),
+            ));
+        }
+        if allowed.rows() != b.rows() || allowed.cols() != b.cols() {
+            return Err(Error::InvalidDimensions(
+                "PatchMatch mask must match the target image size".to_string(),
+            ));
+        }
+        self.search(a, b, Some(allowed.data()))
+    }
+
+    fn search(&self, a: &Mat, b: &Mat, allowed: Option<&[u8]>) -> Result<NnField> {
+        if a.depth() != MatDepth::U8 || b.depth() != MatDepth::U8 {
+            return Err(Error::UnsupportedOperation(
+                "PatchMatch only supports U8 images".to_string(),
+            ));
+        }
+        if a.channels() != b.channels() {
+            return Err(Error::InvalidParameter(
+                "PatchMatch images must have the same number of channels".to_string(),
+            ));
+        }
+        if a.is_empty() || b.is_empty() {
+            return Err(Error::InvalidDimensions(
+                "PatchMatch needs non-empty images".to_string(),
+            ));
+        }
+
+        let candidates: Vec<Point> = match allowed {
+            Some(mask) => (0..b.rows() * b.cols())
+                .filter(|&i| mask[i] != 0)
+                .map(|i| Point::new((i % b.cols()) as i32, (i / b.cols()) as i32))
+                .collect(),
+            None => Vec::new(),
+        };
+        if allowed.is_some() && candidates.is_empty() {
+            return Err(Error::InvalidParameter(
+                "PatchMatch mask allows no target pixels".to_string(),
+            ));
+        }
+
+        let patches = Patches::new(a, b, self.patch_size);
+        let is_allowed = |p: Point| allowed.is_none_or(|m| m[p.y as usize * b.cols() + p.x as usize] != 0);
+        let (rows, cols) = (a.rows(), a.cols());
+        let mut rng = SplitMix64::new(self.seed);
+
+        // Random initialisation
+        let mut targets = Vec::with_capacity(rows * cols);
+        let mut distances = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let t = if candidates.is_empty() {
+                    Point::new((rng.next_u64() % b.cols() as u64) as i32, (rng.next_u64() % b.rows() as u64) as i32)
+                } else {
+                    candidates[(rng.next_u64() % candidates.len() as u64) as usize]
+                };
+                targets.push(t);
+                distances.push(patches.distance(row, col, t, u64::MAX));
+            }
+        }
+
+        let max_radius = b.rows().max(b.cols()) as i32;
+        for iteration in 0..self.iterations {
+            // Alternate the scan direction so matches spread both ways
+            let forward = iteration % 2 == 0;
+            let step: i32 = if forward { 1 } else { -1 };
+            for i in 0..rows * cols {
+                let i = if forward { i } else { rows * cols - 1 - i };
+                let (row, col) = (i / cols, i % cols);
+                let mut best = (targets[i], distances[i]);
+                let consider = |best: &mut (Point, u64), t: Point| {
+                    if t != best.0 && t.x >= 0 && t.y >= 0 && (t.x as usize) < b.cols() && (t.y as usize) < b.rows() && is_allowed(t) {
+                        let d = patches.distance(row, col, t, best.1);
+                        if d < best.1 {
+                            *best = (t, d);
+                        }
+                    }
+                };
+
+                // Propagation from the already visited neighbours
+                let (prev_col, prev_row) = (col as i32 - step, row as i32 - step);
+                if prev_col >= 0 && prev_col < cols as i32 {
+                    let n = targets[row * cols + prev_col as usize];
+                    consider(&mut best, Point::new(n.x + step, n.y));
+                }
+                if prev_row >= 0 && prev_row < rows as i32 {
+                    let n = targets[prev_row as usize * cols + col];
+                    consider(&mut best, Point::new(n.x, n.y + step));
+                }
+
+                // Random search in halving windows
+                let mut radius = max_radius;
+                while radius >= 1 {
+                    let span = 2 * radius as u64 + 1;
+                    let dx = (rng.next_u64() % span) as i32 - radius;
+                    let dy = (rng.next_u64() % span) as i32 - radius;
+                    let t = Point::new(best.0.x + dx, best.0.y + dy);
+                    consider(&mut best, t);
+                    radius /= 2;
+                }
+
+                (targets[i], distances[i]) = best;
+            }
+        }
+
+        let norm = patches.values_per_patch() as f32;
+        let distances = distances.into_iter().map(|d| d as f32 / norm).collect();
+        Ok(NnField { rows, cols, targets, distances })
+    }
+}
+
+/// Patch SSD between `a` and `b` with replicated borders
+struct Patches<'a> {
+    a: &'a Mat,
+    b: &'a Mat,
+    half: i32,
+    channels: usize,
+}
+
+impl<'a> Patches<'a> {
+    fn new(a: &'a Mat, b: &'a Mat, patch_size: usize) -> Self {
+        Self { a, b, half: (patch_size / 2) as i32, channels: a.channels() }
+    }
+
+    fn values_per_patch(&self) -> usize {
+        let side = 2 * self.half as usize + 1;
+        side * side * self.channels
+    }
+
+    /// Sum of squared differences, abandoned once it reaches `limit`
+    fn distance(&self, row: usize, col: usize, target: Point, limit: u64) -> u64 {
+        let (a_data, b_data) = (self.a.data(), self.b.data());
+        let clamp = |v: i32, len: usize| v.clamp(0, len as i32 - 1) as usize;
+        let mut sum = 0u64;
+        for dy in -self.half..=self.half {
+            let ay = clamp(row as i32 + dy, self.a.rows());
+            let by = clamp(target.y + dy, self.b.rows());
+            for dx in -self.half..=self.half {
+                let ax = clamp(col as i32 + dx, self.a.cols());
+                let bx = clamp(target.x + dx, self.b.cols());
+                let ai = (ay * self.a.cols() + ax) * self.channels;
+                let bi = (by * self.b.cols() + bx) * self.channels;
+                for ch in 0..self.channels {
+                    let d = i64::from(a_data[ai + ch]) - i64::from(b_data[bi + ch]);
+                    sum += (d * d) as u64;
+                }
+            }
+            if sum >= limit {
+                return sum;
+            }
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Random texture so every patch is distinctive
+    fn texture(rows: usize, cols: usize, seed: u64) -> Mat {
+        let mut rng = SplitMix64::new(seed);
+        let mut img = Mat::new(rows, cols, 3, MatDepth::U8).unwrap();
+        for v in img.data_mut() {
+            *v = (rng.next_u64() % 256) as u8;
+        }
+        img
+    }
+
+    /// `a` is the window of `b` starting at (dx, dy)
+    fn crop(b: &Mat, dx: usize, dy: usize, rows: usize, cols: usize) -> Mat {
+        let mut a = Mat::new(rows, cols, b.channels(), MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                a.at_mut(row, col).unwrap().copy_from_slice(b.at(row + dy, col + dx).unwrap());
+            }
+        }
+        a
+    }
+
+    #[test]
+    fn test_patch_match_finds_shift() {
+        let b = texture(48, 48, 1);
+        let a = crop(&b, 5, 3, 32, 32);
+        let field = PatchMatch::new(5).unwrap().compute(&a, &b).unwrap();
+
+        let exact = (0..32 * 32).filter(|&i| field.target(i / 32, i % 32) == Point::new((i % 32) as i32 + 5, (i / 32) as i32 + 3)).count();
+        assert!(exact > 32 * 32 * 95 / 100, "only {exact} pixels matched");
+        // Border patches replicate the crop's edges, interior ones match exactly
+        assert_eq!(field.distance(16, 16), 0.0);
+        assert!(field.mean_distance() < f64::from(field.distance(0, 0)));
+
+        let flow = field.to_flow().unwrap();
+        assert_eq!((flow.at_f32(10, 10, 0).unwrap(), flow.at_f32(10, 10, 1).unwrap()), (5.0, 3.0));
+
+        let mut rebuilt = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        field.reconstruct(&b, &mut rebuilt).unwrap();
+        assert_eq!(rebuilt.at(16, 16).unwrap(), a.at(16, 16).unwrap());
+
+        // Same seed, same field
+        assert_eq!(PatchMatch::new(5).unwrap().compute(&a, &b).unwrap(), field);
+    }
+
+    #[test]
+    fn test_patch_match_masked() {
+        let b = texture(40, 40, 2);
+        let a = crop(&b, 0, 0, 40, 40);
+        let mut allowed = Mat::new(40, 40, 1, MatDepth::U8).unwrap();
+        allowed.data_mut().fill(255);
+        for row in 10..30 {
+            for col in 10..30 {
+                allowed.at_mut(row, col).unwrap()[0] = 0;
+            }
+        }
+
+        let field = PatchMatch::new(3).unwrap().compute_masked(&a, &b, &allowed).unwrap();
+        for row in 0..40 {
+            for col in 0..40 {
+                let t = field.target(row, col);
+                assert_ne!(allowed.at(t.y as usize, t.x as usize).unwrap()[0], 0);
+            }
+        }
+        // Outside the hole the identity is still found
+        assert_eq!(field.target(2, 35), Point::new(35, 2));
+
+        assert!(PatchMatch::new(4).is_err());
+        assert!(PatchMatch::new(3).unwrap().compute_masked(&a, &b, &Mat::new(40, 40, 1, MatDepth::U8).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Point2f;
+use crate::error::{Error, Result};
+
+/// Radial vignetting model
+///
+/// Relative brightness at normalized radius `r` (1.0 at the image corner
+/// furthest from `center`) is `1 + k1·r² + k2·r⁴ + k3·r⁶`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VignettingModel {
+    pub center: Point2f,
+    pub coeffs: [f64; 3],
+}
+
+impl VignettingModel {
+    #[must_use]
+    pub fn new(center: Point2f, coeffs: [f64; 3]) -> Self {
+        Self { center, coeffs }
+    }
+
+    /// Fit the model to a flat-field frame (an evenly lit, featureless target)
+    ///
+    /// The optical centre is assumed to be the image centre. Multi-channel frames
+    /// are averaged across channels; U8, U16, F32 and F64 depths are accepted.
+    pub fn fit_flat_field(flat: &Mat) -> Result<Self> {
+        let center = Point2f::new(flat.cols() as f32 / 2.0, flat.rows() as f32 / 2.0);
+        Self::fit_flat_field_with_center(flat, center)
+    }
+
+    /// Fit the model to a flat-field frame with a known optical centre
+    pub fn fit_flat_field_with_center(flat: &Mat, center: Point2f) -> Result<Self> {
+        if flat.rows() < 2 || flat.cols() < 2 {
+            return Err(Error::InvalidDimensions(
+                "Flat-field frame must be at least 2x2".to_string(),
+            ));
+        }
+
+        let max_radius = max_radius(flat.rows(), flat.cols(), center);
+        // Subsample large frames; a few tens of thousands of points is plenty
+        let step = ((flat.rows() * flat.cols()) as f64 / 40_000.0).sqrt().max(1.0) as usize;
+
+        // Least squares for I(r) = a0 + a1·r² + a2·r⁴ + a3·r⁶, so that
+        // k_i = a_i / a0 (a0 is the brightness at the centre)
+        let mut ata = [[0.0f64; 4]; 4];
+        let mut atb = [0.0f64; 4];
+
+        for row in (0..flat.rows()).step_by(step) {
+            for col in (0..flat.cols()).step_by(step) {
+                let value = mean_value(flat, row, col)?;
+                let r2 = radius_sq(row, col, center) / (max_radius * max_radius);
+                let basis = [1.0, r2, r2 * r2, r2 * r2 * r2];
+
+                for i in 0..4 {
+                    for j in 0..4 {
+                        ata[i][j] += basis[i] * basis[j];
+                    }
+                    atb[i] += basis[i] * value;
+                }
+            }
+        }
+
+        let a = solve_4x4(ata, atb)?;
+        if a[0].abs() < 1e-12 {
+            return Err(Error::InvalidParameter(
+                "Flat-field frame is black at the centre".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            center,
+            coeffs: [a[1] / a[0], a[2] / a[0], a[3] / a[0]],
+        })
+    }
+
+    /// Relative brightness at pixel `(x, y)` for an image of the given size
+    #[must_use]
+    pub fn gain(&self, x: f64, y: f64, rows: usize, cols: usize) -> f64 {
+        let max_radius = max_radius(rows, cols, self.center);
+        let dx = x - f64::from(self.center.x);
+        let dy = y - f64::from(self.center.y);
+        let r2 = (dx * dx + dy * dy) / (max_radius * max_radius);
+        let [k1, k2, k3] = self.coeffs;
+        1.0 + r2 * (k1 + r2 * (k2 + r2 * k3))
+    }
+}
+
+/// Remove vignetting by dividing every pixel by the model gain
+///
+/// Works on any channel count at U8, U16, F32 or F64 depth. Integer results are
+/// rounded and saturated.
+pub fn correct_vignetting(src: &Mat, dst: &mut Mat, model: &VignettingModel) -> Result<()> {
+    let rows = src.rows();
+    let cols = src.cols();
+    let channels = src.channels();
+
+    *dst = Mat::new(rows, cols, channels, src.depth())?.with_color_order(src.color_order());
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let gain = model.gain(col as f64, row as f64, rows, cols);
+            // Guard against a badly fitted model going through zero
+            let scale = 1.0 / gain.max(1e-3);
+
+            for ch in 0..channels {
+                match src.depth() {
+                    MatDepth::U8 => {
+                        let v = f64::from(src.at(row, col)?[ch]) * scale;
+                        dst.at_mut(row, col)?[ch] = v.round().clamp(0.0, 255.0) as u8;
+                    }
+                    MatDepth::U16 => {
+                        let v = f64::from(src.at_u16(row, col, ch)?) * scale;
+                        dst.set_u16(row, col, ch, v.round().clamp(0.0, 65535.0) as u16)?;
+                    }
+                    MatDepth::S32 => {
+                        let v = f64::from(src.at_i32(row, col, ch)?) * scale;
+                        dst.set_i32(row, col, ch, v.round() as i32)?;
+                    }
+                    MatDepth::F32 => {
+                        let v = f64::from(src.at_f32(row, col, ch)?) * scale;
+                        dst.set_f32(row, col, ch, v as f32)?;
+                    }
+                    MatDepth::F64 => {
+                        let v = src.at_f64(row, col, ch)? * scale;
+                        dst.set_f64(row, col, ch, v)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Map U8 pixel values to linear relative radiance through a camera response curve
+///
+/// `curves` holds one 256-entry log-exposure table per channel, as returned by
+/// [`calibrate_debevec`](super::calibrate_debevec). The output is F32. Vignetting
+/// is a multiplicative effect on radiance, so correcting in this space avoids the
+/// tone shifts of dividing gamma-encoded values.
+pub fn linearize_response(src: &Mat, dst: &mut Mat, curves: &[Vec<f32>]) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "linearize_response only supports U8 depth".to_string(),
+        ));
+    }
+
+    if curves.len() != src.channels() || curves.iter().any(|c| c.len() != 256) {
+        return Err(Error::InvalidParameter(
+            "Need one 256-entry response curve per channel".to_string(),
+        ));
+    }
+
+    *dst = Mat::new(src.rows(), src.cols(), src.channels(), MatDepth::F32)?.with_color_order(src.color_order());
+
+    for row in 0..src.rows() {
+        for col in 0..src.cols() {
+            for (ch, curve) in curves.iter().enumerate() {
+                let v = src.at(row, col)?[ch];
+                dst.set_f32(row, col, ch, curve[usize::from(v)].exp())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn max_radius(rows: usize, cols: usize, center: Point2f) -> f64 {
+    let cx = f64::from(center.x);
+    let cy = f64::from(center.y);
+    let dx = cx.max(cols as f64 - cx);
+    let dy = cy.max(rows as f64 - cy);
+    (dx * dx + dy * dy).sqrt().max(1.0)
+}
+
+fn radius_sq(row: usize, col: usize, center: Point2f) -> f64 {
+    let dx = col as f64 - f64::from(center.x);
+    let dy = row as f64 - f64::from(center.y);
+    dx * dx + dy * dy
+}
+
+fn mean_value(img: &Mat, row: usize, col: usize) -> Result<f64> {
+    let channels = img.channels();
+    let mut sum = 0.0;
+    for ch in 0..channels {
+        sum += match img.depth() {
+            MatDepth::U8 => f64::from(img.at(row, col)?[ch]),
+            MatDepth::U16 => f64::from(img.at_u16(row, col, ch)?),
+            MatDepth::S32 => f64::from(img.at_i32(row, col, ch)?),
+            MatDepth::F32 => f64::from(img.at_f32(row, col, ch)?),
+            MatDepth::F64 => img.at_f64(row, col, ch)?,
+        };
+    }
+    Ok(sum / channels as f64)
+}
+
+/// Solve a 4x4 linear system with partial pivoting
+fn solve_4x4(mut a: [[f64; 4]; 4], mut b: [f64; 4]) -> Result<[f64; 4]> {
+    for i in 0..4 {
+        let pivot = (i..4)
+            .max_by(|&p, &q| a[p][i].abs().total_cmp(&a[q][i].abs()))
+            .unwrap_or(i);
+
+        if a[pivot][i].abs() < 1e-12 {
+            return Err(Error::InvalidParameter(
+                "Flat-field fit is degenerate".to_string(),
+            ));
+        }
+
+        a.swap(i, pivot);
+        b.swap(i, pivot);
+
+        let pivot_row = a[i];
+        for j in i + 1..4 {
+            let factor = a[j][i] / pivot_row[i];
+            for (v, p) in a[j].iter_mut().zip(pivot_row).skip(i) {
+                *v -= factor * p;
+            }
+            b[j] -= factor * b[i];
+        }
+    }
+
+    let mut x = [0.0f64; 4];
+    for i in (0..4).rev() {
+        let mut sum = b[i];
+        for j in i + 1..4 {
+            sum -= a[i][j] * x[j];
+        }
+        x[i] = sum / a[i][i];
+    }
+
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Scalar;
+
+    fn synthetic_flat(rows: usize, cols: usize, model: &VignettingModel) -> Mat {
+        let mut flat = Mat::new(rows, cols, 1, MatDepth::F32).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                let gain = model.gain(col as f64, row as f64, rows, cols);
+                flat.set_f32(row, col, 0, (200.0 * gain) as f32).unwrap();
+            }
+        }
+        flat
+    }
+
+    #[test]
+    fn test_fit_flat_field_recovers_model() {
+        let truth = VignettingModel::new(Point2f::new(40.0, 30.0), [-0.3, 0.05, -0.02]);
+        let flat = synthetic_flat(60, 80, &truth);
+
+        let fitted = VignettingModel::fit_flat_field(&flat).unwrap();
+
+        for (a, b) in fitted.coeffs.iter().zip(truth.coeffs.iter()) {
+            assert!((a - b).abs() < 1e-3, "{:?} vs {:?}", fitted.coeffs, truth.coeffs);
+        }
+    }
+
+    #[test]
+    fn test_correct_vignetting_flattens_frame() {
+        let truth = VignettingModel::new(Point2f::new(32.0, 32.0), [-0.4, 0.0, 0.0]);
+        let flat = synthetic_flat(64, 64, &truth);
+
+        let mut u8_flat = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 0..64 {
+                u8_flat.at_mut(row, col).unwrap()[0] = flat.at_f32(row, col, 0).unwrap().round() as u8;
+            }
+        }
+
+        let model = VignettingModel::fit_flat_field(&u8_flat).unwrap();
+        let mut corrected = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        correct_vignetting(&u8_flat, &mut corrected, &model).unwrap();
+
+        let corner = i32::from(corrected.at(0, 0).unwrap()[0]);
+        let centre = i32::from(corrected.at(32, 32).unwrap()[0]);
+        assert!((corner - centre).abs() <= 2, "corner {corner}, centre {centre}");
+        assert!(u8_flat.at(0, 0).unwrap()[0] < 130);
+    }
+
+    #[test]
+    fn test_linearize_response() {
+        let src = Mat::new_with_default(4, 4, 1, MatDepth::U8, Scalar::all(10.0)).unwrap();
+        let curve: Vec<f32> = (0..256).map(|v| (v as f32 + 1.0).ln()).collect();
+
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        linearize_response(&src, &mut dst, &[curve]).unwrap();
+
+        assert_eq!(dst.depth(), MatDepth::F32);
+        assert!((dst.at_f32(2, 2, 0).unwrap() - 11.0).abs() < 1e-4);
+        assert!(linearize_response(&src, &mut dst, &[]).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::Mat;
+use crate::core::{Mat, MatDepth};
 use crate::error::{Error, Result};
 use crate::core::types::Point;
 
@@ -118,61 +118,100 @@ impl Moments {
 }
 
 /// Compute moments for a binary or grayscale image
+///
+/// Pixel values act as weights. All depths are accepted, so F32 intensity
+/// images give sub-pixel accurate weighted centroids.
 pub fn compute_moments(image: &Mat) -> Result<Moments> {
+    raster_moments(image, None)
+}
+
+/// Compute moments of the pixels of `image` where `mask` is non-zero
+///
+/// `mask` must be a single-channel U8 image of the same size. This restricts a
+/// weighted centroid to one region (e.g. a single spot in a microscopy frame)
+/// without zeroing the rest of the image.
+pub fn moments_with_mask(image: &Mat, mask: &Mat) -> Result<Moments> {
+    if mask.channels() != 1 || mask.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "Mask must be a single-channel U8 image".to_string(),
+        ));
+    }
+
+    if mask.rows() != image.rows() || mask.cols() != image.cols() {
+        return Err(Error::InvalidDimensions(
+            "Mask size must match image size".to_string(),
+        ));
+    }
+
+    raster_moments(image, Some(mask))
+}
+
+fn pixel_value(image: &Mat, row: usize, col: usize) -> Result<f64> {
+    match image.depth() {
+        MatDepth::U8 => Ok(f64::from(image.at(row, col)?[0])),
+        MatDepth::U16 => Ok(f64::from(image.at_u16(row, col, 0)?)),
+        MatDepth::S32 => Ok(f64::from(image.at_i32(row, col, 0)?)),
+        MatDepth::F32 => Ok(f64::from(image.at_f32(row, col, 0)?)),
+        MatDepth::F64 => image.at_f64(row, col, 0),
+    }
+}
+
+fn raster_moments(image: &Mat, mask: Option<&Mat>) -> Result<Moments> {
     if image.channels() != 1 {
         return Err(Error::InvalidParameter(
             "Moments require single-channel image".to_string(),
         ));
     }
 
-    let mut moments = Moments::new();
-
-    // Compute spatial moments
+    // Collect the weighted pixels once so the central pass doesn't re-decode them
+    let mut samples = Vec::new();
     for row in 0..image.rows() {
         for col in 0..image.cols() {
-            let intensity = f64::from(image.at(row, col)?[0]);
-
-            if intensity > 0.0 {
-                let x = col as f64;
-                let y = row as f64;
-
-                // Spatial moments
-                moments.m00 += intensity;
-                moments.m10 += x * intensity;
-                moments.m01 += y * intensity;
-                moments.m20 += x * x * intensity;
-                moments.m11 += x * y * intensity;
-                moments.m02 += y * y * intensity;
-                moments.m30 += x * x * x * intensity;
-                moments.m21 += x * x * y * intensity;
-                moments.m12 += x * y * y * intensity;
-                moments.m03 += y * y * y * intensity;
+            if let Some(mask) = mask {
+                if mask.at(row, col)?[0] == 0 {
+                    continue;
+                }
+            }
+
+            let intensity = pixel_value(image, row, col)?;
+            if intensity != 0.0 {
+                samples.push((col as f64, row as f64, intensity));
             }
         }
     }
 
+    let mut moments = Moments::new();
+
+    // Compute spatial moments
+    for &(x, y, intensity) in &samples {
+        moments.m00 += intensity;
+        moments.m10 += x * intensity;
+        moments.m01 += y * intensity;
+        moments.m20 += x * x * intensity;
+        moments.m11 += x * y * intensity;
+        moments.m02 += y * y * intensity;
+        moments.m30 += x * x * x * intensity;
+        moments.m21 += x * x * y * intensity;
+        moments.m12 += x * y * y * intensity;
+        moments.m03 += y * y * y * intensity;
+    }
+
     // Compute central moments
     if moments.m00 != 0.0 {
         let x_bar = moments.m10 / moments.m00;
         let y_bar = moments.m01 / moments.m00;
 
-        for row in 0..image.rows() {
-            for col in 0..image.cols() {
-                let intensity = f64::from(image.at(row, col)?[0]);
-
-                if intensity > 0.0 {
-                    let x = col as f64 - x_bar;
-                    let y = row as f64 - y_bar;
-
-                    moments.mu20 += x * x * intensity;
-                    moments.mu11 += x * y * intensity;
-                    moments.mu02 += y * y * intensity;
-                    moments.mu30 += x * x * x * intensity;
-                    moments.mu21 += x * x * y * intensity;
-                    moments.mu12 += x * y * y * intensity;
-                    moments.mu03 += y * y * y * intensity;
-                }
-            }
+        for &(x, y, intensity) in &samples {
+            let x = x - x_bar;
+            let y = y - y_bar;
+
+            moments.mu20 += x * x * intensity;
+            moments.mu11 += x * y * intensity;
+            moments.mu02 += y * y * intensity;
+            moments.mu30 += x * x * x * intensity;
+            moments.mu21 += x * x * y * intensity;
+            moments.mu12 += x * y * y * intensity;
+            moments.mu03 += y * y * y * intensity;
         }
 
         // Compute normalized central moments
@@ -310,6 +349,57 @@ mod tests {
         assert!(moments.m01 > 0.0);
     }
 
+    #[test]
+    fn test_compute_moments_f32_weighted_centroid() {
+        let mut img = Mat::new(10, 10, 1, MatDepth::F32).unwrap();
+        img.set_f32(4, 2, 0, 1.0).unwrap();
+        img.set_f32(4, 3, 0, 3.0).unwrap();
+
+        let moments = compute_moments(&img).unwrap();
+        let (cx, cy) = moments.centroid();
+
+        assert!((moments.m00 - 4.0).abs() < 1e-9);
+        assert!((cx - 2.75).abs() < 1e-9);
+        assert!((cy - 4.0).abs() < 1e-9);
+        // Variance of {2 (w=1), 3 (w=3)} around 2.75
+        assert!((moments.mu20 - 0.75).abs() < 1e-9);
+        assert!((moments.nu20 - 0.75 / 16.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_moments_with_mask() {
+        let mut img = Mat::new(20, 20, 1, MatDepth::F32).unwrap();
+        // Two spots; the mask selects only the second one
+        img.set_f32(3, 3, 0, 5.0).unwrap();
+        img.set_f32(12, 14, 0, 2.0).unwrap();
+        img.set_f32(12, 15, 0, 2.0).unwrap();
+
+        let mut mask = Mat::new(20, 20, 1, MatDepth::U8).unwrap();
+        for row in 10..15 {
+            for col in 10..18 {
+                mask.at_mut(row, col).unwrap()[0] = 255;
+            }
+        }
+
+        let moments = moments_with_mask(&img, &mask).unwrap();
+        let (cx, cy) = moments.centroid();
+
+        assert!((moments.m00 - 4.0).abs() < 1e-9);
+        assert!((cx - 14.5).abs() < 1e-9);
+        assert!((cy - 12.0).abs() < 1e-9);
+        assert!(moments.mu02.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_moments_with_mask_rejects_bad_mask() {
+        let img = Mat::new(10, 10, 1, MatDepth::F32).unwrap();
+        let small = Mat::new(5, 5, 1, MatDepth::U8).unwrap();
+        let float_mask = Mat::new(10, 10, 1, MatDepth::F32).unwrap();
+
+        assert!(moments_with_mask(&img, &small).is_err());
+        assert!(moments_with_mask(&img, &float_mask).is_err());
+    }
+
     #[test]
     fn test_hu_moments() {
         let mut moments = Moments::new();
@@ -1,6 +1,7 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
 use crate::core::{Mat, MatDepth};
 use crate::error::{Error, Result};
+use crate::imgproc::{build_gaussian_pyramid, build_laplacian_pyramid, collapse_laplacian_pyramid};
 
 /// Multi-band blending for seamless image composition
 pub struct MultiBandBlender {
@@ -42,21 +43,22 @@ impl MultiBandBlender {
         // Build Laplacian pyramids for each image
         let mut pyramids = Vec::new();
         for img in images {
-            let pyramid = self.build_laplacian_pyramid(img, self.num_bands)?;
+            let pyramid = build_laplacian_pyramid(img, self.num_bands)?;
             pyramids.push(pyramid);
         }
 
         // Build Gaussian pyramids for masks
         let mut mask_pyramids = Vec::new();
         for mask in masks {
-            let pyramid = self.build_gaussian_pyramid(mask, self.num_bands)?;
+            let pyramid = build_gaussian_pyramid(mask, self.num_bands)?;
             mask_pyramids.push(pyramid);
         }
 
-        // Blend each band
+        // Blend each band; small images may have fewer than `num_bands` levels
+        let num_bands = pyramids[0].len();
         let mut blended_pyramid = Vec::new();
 
-        for band in 0..self.num_bands {
+        for band in 0..num_bands {
             let band_rows = pyramids[0][band].rows();
             let band_cols = pyramids[0][band].cols();
 
@@ -101,7 +103,7 @@ impl MultiBandBlender {
         }
 
         // Reconstruct from Laplacian pyramid
-        let result = self.reconstruct_from_laplacian_pyramid(&blended_pyramid)?;
+        let result = collapse_laplacian_pyramid(&blended_pyramid)?;
 
         // Convert back to U8
         let mut output = Mat::new(result.rows(), result.cols(), channels, MatDepth::U8)?;
@@ -116,136 +118,6 @@ impl MultiBandBlender {
 
         Ok(output)
     }
-
-    fn build_laplacian_pyramid(&self, image: &Mat, num_levels: usize) -> Result<Vec<Mat>> {
-        // First build Gaussian pyramid
-        let gaussian_pyramid = self.build_gaussian_pyramid(image, num_levels)?;
-
-        let mut laplacian_pyramid = Vec::new();
-
-        // Compute Laplacian = Gaussian[i] - upsample(Gaussian[i+1])
-        for i in 0..gaussian_pyramid.len() - 1 {
-            let current = &gaussian_pyramid[i];
-            let next_upsampled = self.upsample(&gaussian_pyramid[i + 1], current.rows(), current.cols())?;
-
-            let mut laplacian = Mat::new(current.rows(), current.cols(), current.channels(), MatDepth::F32)?;
-
-            for row in 0..current.rows() {
-                for col in 0..current.cols() {
-                    for ch in 0..current.channels() {
-                        let val1 = current.at_f32(row, col, ch)?;
-                        let val2 = next_upsampled.at_f32(row, col, ch)?;
-                        laplacian.set_f32(row, col, ch, val1 - val2)?;
-                    }
-                }
-            }
-
-            laplacian_pyramid.push(laplacian);
-        }
-
-        // Add the smallest Gaussian level
-        laplacian_pyramid.push(gaussian_pyramid.last().unwrap().clone_mat());
-
-        Ok(laplacian_pyramid)
-    }
-
-    fn build_gaussian_pyramid(&self, image: &Mat, num_levels: usize) -> Result<Vec<Mat>> {
-        let mut pyramid = Vec::new();
-
-        // Convert to F32
-        let mut current = Mat::new(image.rows(), image.cols(), image.channels(), MatDepth::F32)?;
-        for row in 0..image.rows() {
-            for col in 0..image.cols() {
-                for ch in 0..image.channels() {
-                    let val = f32::from(image.at(row, col)?[ch]);
-                    current.set_f32(row, col, ch, val)?;
-                }
-            }
-        }
-
-        pyramid.push(current.clone_mat());
-
-        // Build pyramid
-        for _ in 1..num_levels {
-            current = self.downsample(&current)?;
-            pyramid.push(current.clone_mat());
-
-            if current.rows() < 2 || current.cols() < 2 {
-                break;
-            }
-        }
-
-        Ok(pyramid)
-    }
-
-    fn downsample(&self, image: &Mat) -> Result<Mat> {
-        let new_rows = (image.rows() / 2).max(1);
-        let new_cols = (image.cols() / 2).max(1);
-
-        let mut result = Mat::new(new_rows, new_cols, image.channels(), MatDepth::F32)?;
-
-        for row in 0..new_rows {
-            for col in 0..new_cols {
-                for ch in 0..image.channels() {
-                    let src_row = (row * 2).min(image.rows() - 1);
-                    let src_col = (col * 2).min(image.cols() - 1);
-                    let val = image.at_f32(src_row, src_col, ch)?;
-                    result.set_f32(row, col, ch, val)?;
-                }
-            }
-        }
-
-        Ok(result)
-    }
-
-    fn upsample(&self, image: &Mat, target_rows: usize, target_cols: usize) -> Result<Mat> {
-        let mut result = Mat::new(target_rows, target_cols, image.channels(), MatDepth::F32)?;
-
-        let row_scale = image.rows() as f32 / target_rows as f32;
-        let col_scale = image.cols() as f32 / target_cols as f32;
-
-        for row in 0..target_rows {
-            for col in 0..target_cols {
-                let src_row = (row as f32 * row_scale).min(image.rows() as f32 - 1.0) as usize;
-                let src_col = (col as f32 * col_scale).min(image.cols() as f32 - 1.0) as usize;
-
-                for ch in 0..image.channels() {
-                    let val = image.at_f32(src_row, src_col, ch)?;
-                    result.set_f32(row, col, ch, val)?;
-                }
-            }
-        }
-
-        Ok(result)
-    }
-
-    fn reconstruct_from_laplacian_pyramid(&self, pyramid: &[Mat]) -> Result<Mat> {
-        if pyramid.is_empty() {
-            return Err(Error::InvalidParameter("Empty pyramid".to_string()));
-        }
-
-        // Start from smallest level
-        let mut result = pyramid.last().unwrap().clone_mat();
-
-        // Progressively add Laplacian levels
-        for i in (0..pyramid.len() - 1).rev() {
-            let laplacian = &pyramid[i];
-            result = self.upsample(&result, laplacian.rows(), laplacian.cols())?;
-
-            // Add Laplacian
-            for row in 0..result.rows() {
-                for col in 0..result.cols() {
-                    for ch in 0..result.channels() {
-                        let val1 = result.at_f32(row, col, ch)?;
-                        let val2 = laplacian.at_f32(row, col, ch)?;
-                        result.set_f32(row, col, ch, val1 + val2)?;
-                    }
-                }
-            }
-        }
-
-        Ok(result)
-    }
 }
 
 /// Feather blending with distance transform
@@ -0,0 +1,196 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgcodecs::imwrite;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Edge of the match graph: feature matches between two input images
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairMatchInfo {
+    pub src: usize,
+    pub dst: usize,
+    pub matches: usize,
+    /// Too few matches to estimate a homography; identity was used instead
+    pub fallback: bool,
+}
+
+/// Intermediate artifacts of one [`PanoramaStitcher`](super::PanoramaStitcher)
+/// run
+///
+/// Stages fill in their fields as the pipeline progresses, so after a failed
+/// run the artifacts up to the failing stage are still available.
+#[derive(Debug, Clone, Default)]
+pub struct StitchDebug {
+    /// Width and height of each input image
+    pub image_sizes: Vec<(usize, usize)>,
+    /// Keypoints found in each input image
+    pub keypoint_counts: Vec<usize>,
+    /// Match graph between consecutive images
+    pub match_graph: Vec<PairMatchInfo>,
+    /// Homography of each image into the panorama frame
+    pub homographies: Vec<[[f64; 3]; 3]>,
+    /// 1-channel U8 masks (255 = covered) of each warped image
+    pub warped_masks: Vec<Mat>,
+    /// Seam column per row between each pair of consecutive images
+    pub seams: Vec<Vec<usize>>,
+    /// Exposure gain of each image relative to the first, estimated from
+    /// mean intensities in the overlaps; far from 1 means visible banding
+    pub gains: Vec<f64>,
+    /// 1-channel F32 maps holding each image's gain inside its warped mask
+    pub gain_maps: Vec<Mat>,
+    /// Panorama with the seams drawn in red (white for grayscale input)
+    pub seam_overlay: Option<Mat>,
+    /// Problems noticed along the way, such as missing overlaps
+    pub warnings: Vec<String>,
+}
+
+impl StitchDebug {
+    /// Match graph, homographies, seams, gains and warnings as a JSON object
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n  \"images\": [");
+        for (i, &(width, height)) in self.image_sizes.iter().enumerate() {
+            let keypoints = self.keypoint_counts.get(i).map_or_else(|| "null".to_string(), ToString::to_string);
+            let homography = self.homographies.get(i).map_or_else(
+                || "null".to_string(),
+                |h| {
+                    let rows: Vec<String> = h.iter().map(|r| format!("[{}]", join(r.iter().map(|&v| number(v))))).collect();
+                    format!("[{}]", rows.join(", "))
+                },
+            );
+            let gain = self.gains.get(i).map_or_else(|| "null".to_string(), |&g| number(g));
+            let _ = write!(
+                out,
+                "{}\n    {{\"index\": {i}, \"width\": {width}, \"height\": {height}, \"keypoints\": {keypoints}, \
+                 \"homography\": {homography}, \"gain\": {gain}}}",
+                if i == 0 { "" } else { "," }
+            );
+        }
+        out.push_str("\n  ],\n  \"matches\": [");
+        for (i, edge) in self.match_graph.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n    {{\"src\": {}, \"dst\": {}, \"matches\": {}, \"fallback\": {}}}",
+                if i == 0 { "" } else { "," },
+                edge.src,
+                edge.dst,
+                edge.matches,
+                edge.fallback
+            );
+        }
+        out.push_str("\n  ],\n  \"seams\": [");
+        out.push_str(&join(self.seams.iter().map(|s| format!("[{}]", join(s.iter().map(ToString::to_string))))));
+        out.push_str("],\n  \"warnings\": [");
+        out.push_str(&join(self.warnings.iter().map(|w| quote(w))));
+        out.push_str("]\n}\n");
+        out
+    }
+
+    /// Write the artifacts into `dir`, creating it if needed
+    ///
+    /// Produces `stitch_debug.json`, `mask_<i>.png`, `gain_<i>.png` (gain
+    /// 1.0 maps to gray 128) and `seams.png`.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("stitch_debug.json"), self.to_json())?;
+
+        for (i, mask) in self.warped_masks.iter().enumerate() {
+            imwrite(dir.join(format!("mask_{i}.png")), mask)?;
+        }
+        for (i, gain_map) in self.gain_maps.iter().enumerate() {
+            let mut scaled = Mat::new(gain_map.rows(), gain_map.cols(), 1, MatDepth::U8)?;
+            for row in 0..gain_map.rows() {
+                for col in 0..gain_map.cols() {
+                    let g = gain_map.at_f32(row, col, 0)?;
+                    scaled.at_mut(row, col)?[0] = (g * 128.0).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+            imwrite(dir.join(format!("gain_{i}.png")), &scaled)?;
+        }
+        if let Some(overlay) = &self.seam_overlay {
+            imwrite(dir.join("seams.png"), overlay)?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-image exposure gains from mean intensities in consecutive overlaps,
+/// chained from the first image
+pub(crate) fn estimate_gains(images: &[Mat], masks: &[Mat], warnings: &mut Vec<String>) -> Result<Vec<f64>> {
+    if images.len() != masks.len() {
+        return Err(Error::InvalidParameter(
+            "Need one mask per image".to_string(),
+        ));
+    }
+    let mut gains = vec![1.0; images.len()];
+    for i in 1..images.len() {
+        let (a, b) = (&images[i - 1], &images[i]);
+        let channels = a.channels().min(b.channels());
+        let (mut sum_a, mut sum_b, mut count) = (0.0, 0.0, 0usize);
+        for row in 0..a.rows().min(b.rows()) {
+            for col in 0..a.cols().min(b.cols()) {
+                if masks[i - 1].at(row, col)?[0] == 0 || masks[i].at(row, col)?[0] == 0 {
+                    continue;
+                }
+                let (pa, pb) = (a.at(row, col)?, b.at(row, col)?);
+                sum_a += pa[..channels].iter().map(|&v| f64::from(v)).sum::<f64>();
+                sum_b += pb[..channels].iter().map(|&v| f64::from(v)).sum::<f64>();
+                count += 1;
+            }
+        }
+
+        gains[i] = gains[i - 1];
+        if count == 0 {
+            warnings.push(format!("images {} and {i} do not overlap after warping", i - 1));
+        } else if sum_b > 0.0 {
+            gains[i] *= sum_a / sum_b;
+        } else {
+            warnings.push(format!("image {i} is black in its overlap with image {}", i - 1));
+        }
+    }
+    Ok(gains)
+}
+
+/// Copy of `panorama` with each seam drawn over it
+pub(crate) fn draw_seams(panorama: &Mat, seams: &[Vec<usize>]) -> Result<Mat> {
+    let mut overlay = panorama.clone();
+    let color: &[u8] = if overlay.channels() >= 3 { &[255, 0, 0] } else { &[255] };
+    for seam in seams {
+        for (row, &col) in seam.iter().enumerate() {
+            if row < overlay.rows() && col < overlay.cols() {
+                let px = overlay.at_mut(row, col)?;
+                px[..color.len()].copy_from_slice(color);
+            }
+        }
+    }
+    Ok(overlay)
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// JSON number; non-finite values become `null`
+fn number(v: f64) -> String {
+    if v.is_finite() { format!("{v}") } else { "null".to_string() }
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -1,7 +1,9 @@
 pub mod panorama;
 pub mod seam_finding;
 pub mod blending;
+pub mod debug;
 
 pub use panorama::*;
 pub use seam_finding::*;
 pub use blending::*;
+pub use debug::*;
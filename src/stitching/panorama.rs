@@ -1,14 +1,17 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
-use crate::core::Mat;
+use crate::core::{Mat, MatDepth};
 use crate::error::{Error, Result};
 use crate::features2d::KeyPoint;
 use crate::core::types::Point;
+use super::debug::{draw_seams, estimate_gains, PairMatchInfo, StitchDebug};
+use std::path::PathBuf;
 
 /// Panorama stitcher for creating panoramic images from multiple images
 pub struct PanoramaStitcher {
     confidence_threshold: f32,
     blend_strength: f32,
     pub warper_type: WarpType,
+    debug_dir: Option<PathBuf>,
 }
 
 #[derive(Clone, Copy)]
@@ -31,6 +34,7 @@ impl PanoramaStitcher {
             confidence_threshold: 1.0,
             blend_strength: 5.0,
             warper_type: WarpType::Cylindrical,
+            debug_dir: None,
         }
     }
 
@@ -46,8 +50,38 @@ impl PanoramaStitcher {
         self
     }
 
+    /// Save the intermediate artifacts of every [`stitch`](Self::stitch)
+    /// call into `dir` (see [`StitchDebug::save`]), including failed ones
+    #[must_use]
+    pub fn with_debug_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.debug_dir = Some(dir.into());
+        self
+    }
+
     /// Stitch multiple images into a panorama
     pub fn stitch(&self, images: &[Mat]) -> Result<Mat> {
+        if let Some(dir) = &self.debug_dir {
+            let (result, debug) = self.stitch_debug(images);
+            debug.save(dir)?;
+            return result;
+        }
+        self.run(images, None)
+    }
+
+    /// Stitch and also return the intermediate artifacts
+    ///
+    /// The artifacts are returned even when stitching fails, holding
+    /// everything produced before the failing stage.
+    pub fn stitch_debug(&self, images: &[Mat]) -> (Result<Mat>, StitchDebug) {
+        let mut debug = StitchDebug::default();
+        let result = self.run(images, Some(&mut debug));
+        (result, debug)
+    }
+
+    fn run(&self, images: &[Mat], mut debug: Option<&mut StitchDebug>) -> Result<Mat> {
+        if let Some(debug) = debug.as_deref_mut() {
+            debug.image_sizes = images.iter().map(|img| (img.cols(), img.rows())).collect();
+        }
         if images.len() < 2 {
             return Err(Error::InvalidParameter(
                 "Need at least 2 images for stitching".to_string(),
@@ -64,20 +98,61 @@ impl PanoramaStitcher {
             all_descriptors.push(descs);
         }
 
+        if let Some(debug) = debug.as_deref_mut() {
+            debug.keypoint_counts = all_keypoints.iter().map(Vec::len).collect();
+        }
+
         // 2. Match features between adjacent images
         let matches = self.match_images(&all_descriptors)?;
+        if let Some(debug) = debug.as_deref_mut() {
+            for (i, pair) in matches.iter().enumerate() {
+                let fallback = pair.len() < 4;
+                if fallback {
+                    debug.warnings.push(format!(
+                        "images {i} and {}: only {} matches, identity homography used",
+                        i + 1,
+                        pair.len()
+                    ));
+                }
+                debug.match_graph.push(PairMatchInfo { src: i, dst: i + 1, matches: pair.len(), fallback });
+            }
+        }
 
         // 3. Estimate homographies
         let homographies = self.estimate_homographies(&all_keypoints, &matches)?;
+        if let Some(debug) = debug.as_deref_mut() {
+            debug.homographies.clone_from(&homographies);
+        }
 
         // 4. Warp images to common coordinate frame
-        let warped_images = self.warp_images(images, &homographies)?;
+        let (warped_images, warped_masks) = self.warp_images(images, &homographies)?;
+        if let Some(debug) = debug.as_deref_mut() {
+            debug.gains = estimate_gains(&warped_images, &warped_masks, &mut debug.warnings)?;
+            for (mask, &gain) in warped_masks.iter().zip(&debug.gains) {
+                let mut gain_map = Mat::new(mask.rows(), mask.cols(), 1, MatDepth::F32)?;
+                for row in 0..mask.rows() {
+                    for col in 0..mask.cols() {
+                        if mask.at(row, col)?[0] != 0 {
+                            gain_map.set_f32(row, col, 0, gain as f32)?;
+                        }
+                    }
+                }
+                debug.gain_maps.push(gain_map);
+            }
+            debug.warped_masks = warped_masks;
+        }
 
         // 5. Find optimal seams
         let seams = self.find_seams(&warped_images)?;
+        if let Some(debug) = debug.as_deref_mut() {
+            debug.seams.clone_from(&seams);
+        }
 
         // 6. Blend images
         let panorama = self.blend_images(&warped_images, &seams)?;
+        if let Some(debug) = debug {
+            debug.seam_overlay = Some(draw_seams(&panorama, &seams)?);
+        }
 
         Ok(panorama)
     }
@@ -252,8 +327,9 @@ impl PanoramaStitcher {
         &self,
         images: &[Mat],
         homographies: &[[[f64; 3]; 3]],
-    ) -> Result<Vec<Mat>> {
+    ) -> Result<(Vec<Mat>, Vec<Mat>)> {
         let mut warped = Vec::new();
+        let mut masks = Vec::new();
 
         // Determine output size
         let max_width = images.iter().map(super::super::core::mat::Mat::cols).max().unwrap_or(0);
@@ -263,11 +339,12 @@ impl PanoramaStitcher {
         let output_height = max_height;
 
         for (img, h) in images.iter().zip(homographies.iter()) {
-            let warped_img = self.warp_perspective(img, h, output_width, output_height)?;
+            let (warped_img, mask) = self.warp_perspective(img, h, output_width, output_height)?;
             warped.push(warped_img);
+            masks.push(mask);
         }
 
-        Ok(warped)
+        Ok((warped, masks))
     }
 
     fn warp_perspective(
@@ -276,8 +353,9 @@ impl PanoramaStitcher {
         h: &[[f64; 3]; 3],
         dst_width: usize,
         dst_height: usize,
-    ) -> Result<Mat> {
+    ) -> Result<(Mat, Mat)> {
         let mut dst = Mat::new(dst_height, dst_width, src.channels(), src.depth())?;
+        let mut mask = Mat::new(dst_height, dst_width, 1, MatDepth::U8)?;
 
         for row in 0..dst_height {
             for col in 0..dst_width {
@@ -302,11 +380,12 @@ impl PanoramaStitcher {
                     for ch in 0..src.channels() {
                         dst.at_mut(row, col)?[ch] = src.at(src_row as usize, src_col as usize)?[ch];
                     }
+                    mask.at_mut(row, col)?[0] = 255;
                 }
             }
         }
 
-        Ok(dst)
+        Ok((dst, mask))
     }
 
     fn find_seams(&self, images: &[Mat]) -> Result<Vec<Vec<usize>>> {
@@ -409,4 +488,43 @@ mod tests {
         assert_eq!(gray.channels(), 1);
         assert_eq!(gray.rows(), 50);
     }
+
+    #[test]
+    fn test_stitch_debug() {
+        let mut img = Mat::new(100, 100, 3, MatDepth::U8).unwrap();
+        for row in 0..100 {
+            for col in 0..100 {
+                img.at_mut(row, col).unwrap().fill(((row * 7 + col * 3) % 256) as u8);
+            }
+        }
+        let images = [img.clone(), img];
+
+        let (result, debug) = PanoramaStitcher::new().stitch_debug(&images);
+        let panorama = result.unwrap();
+        assert_eq!(debug.image_sizes, [(100, 100), (100, 100)]);
+        assert_eq!(debug.match_graph.len(), 1);
+        assert_eq!((debug.match_graph[0].src, debug.match_graph[0].dst), (0, 1));
+        assert_eq!(debug.homographies.len(), 2);
+        assert_eq!(debug.warped_masks.len(), 2);
+        assert_eq!(debug.warped_masks[0].at(50, 50).unwrap()[0], 255);
+        assert_eq!(debug.gain_maps.len(), 2);
+        assert_eq!(debug.seams.len(), 1);
+        let overlay = debug.seam_overlay.as_ref().unwrap();
+        assert_eq!((overlay.rows(), overlay.cols()), (panorama.rows(), panorama.cols()));
+        let json = debug.to_json();
+        assert!(json.contains("\"matches\": ["));
+        assert!(json.contains("\"src\": 0, \"dst\": 1"));
+
+        let dir = std::env::temp_dir().join("opencv_rust_stitch_debug");
+        debug.save(&dir).unwrap();
+        assert!(dir.join("stitch_debug.json").exists());
+        assert!(dir.join("mask_1.png").exists());
+        assert!(dir.join("seams.png").exists());
+
+        // A failed run still reports what it saw
+        let (result, debug) = PanoramaStitcher::new().stitch_debug(&images[..1]);
+        assert!(result.is_err());
+        assert_eq!(debug.image_sizes.len(), 1);
+        assert!(debug.match_graph.is_empty());
+    }
 }
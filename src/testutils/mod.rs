@@ -0,0 +1,13 @@
+//! Synthetic test data with known ground truth
+//!
+//! Generators for calibration targets, resolution charts and noise
+//! textures, and a renderer for textured planes seen from known camera
+//! poses, so calib3d and video algorithms can be checked quantitatively
+//! against the exact corners, poses and optical flow. Enabled by the
+//! `testutils` feature.
+
+pub mod patterns;
+pub mod scene;
+
+pub use patterns::*;
+pub use scene::{PlanarScene, ScenePose};
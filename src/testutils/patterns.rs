@@ -0,0 +1,217 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use std::f64::consts::PI;
+
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Point2f;
+use crate::error::{Error, Result};
+use crate::imgproc::noise::SplitMix64;
+use crate::objdetect::{ArucoDetector, ArucoDictionary, ArucoMarker};
+
+/// Black and white checkerboard, black in the top-left square
+pub fn checkerboard(rows: usize, cols: usize, square: usize) -> Result<Mat> {
+    if square == 0 {
+        return Err(Error::InvalidParameter(
+            "Square size must be positive".to_string(),
+        ));
+    }
+
+    let mut img = Mat::new(rows, cols, 1, MatDepth::U8)?;
+    for (row, line) in img.data_mut().chunks_exact_mut(cols.max(1)).enumerate() {
+        for (col, px) in line.iter_mut().enumerate() {
+            *px = if (row / square + col / square).is_multiple_of(2) { 0 } else { 255 };
+        }
+    }
+    Ok(img)
+}
+
+/// Grid of `ArUco` markers on a white background, with the markers'
+/// ground-truth corners
+///
+/// Markers are `marker_pixels` wide, numbered from 0 in raster order and
+/// separated (and surrounded) by `separation` white pixels. Corners are
+/// listed clockwise from the top-left, on the outer edge of each marker's
+/// black border.
+pub fn aruco_board(
+    dictionary: ArucoDictionary,
+    markers_x: usize,
+    markers_y: usize,
+    marker_pixels: usize,
+    separation: usize,
+) -> Result<(Mat, Vec<ArucoMarker>)> {
+    if markers_x == 0 || markers_y == 0 || markers_x * markers_y > dictionary.dict_size() {
+        return Err(Error::InvalidParameter(format!(
+            "A {markers_x}x{markers_y} board needs between 1 and {} markers",
+            dictionary.dict_size()
+        )));
+    }
+
+    let pitch = marker_pixels + separation;
+    let mut board = Mat::new(markers_y * pitch + separation, markers_x * pitch + separation, 1, MatDepth::U8)?;
+    board.data_mut().fill(255);
+
+    let generator = ArucoDetector::new(dictionary);
+    let mut markers = Vec::with_capacity(markers_x * markers_y);
+    for my in 0..markers_y {
+        for mx in 0..markers_x {
+            let id = (my * markers_x + mx) as i32;
+            let marker = generator.generate_marker(id, marker_pixels)?;
+            let (top, left) = (separation + my * pitch, separation + mx * pitch);
+            for row in 0..marker_pixels {
+                let start = (top + row) * board.cols() + left;
+                board.data_mut()[start..start + marker_pixels]
+                    .copy_from_slice(&marker.data()[row * marker_pixels..(row + 1) * marker_pixels]);
+            }
+
+            let (x0, y0) = (left as f32, top as f32);
+            let (x1, y1) = ((left + marker_pixels) as f32, (top + marker_pixels) as f32);
+            markers.push(ArucoMarker {
+                id,
+                corners: vec![Point2f::new(x0, y0), Point2f::new(x1, y0), Point2f::new(x1, y1), Point2f::new(x0, y1)],
+            });
+        }
+    }
+    Ok((board, markers))
+}
+
+/// Siemens star resolution target: `spokes` black and `spokes` white
+/// wedges around the image centre, on a mid-grey background
+///
+/// Edges are 4x4 supersampled, so the contrast falls off toward the
+/// centre the way a sharp lens would resolve it, which makes the chart
+/// useful for measuring blur and resolution.
+pub fn siemens_star(size: usize, spokes: usize) -> Result<Mat> {
+    if spokes < 2 || size == 0 {
+        return Err(Error::InvalidParameter(
+            "Siemens star needs at least 2 spokes and a non-zero size".to_string(),
+        ));
+    }
+
+    let centre = size as f64 / 2.0;
+    let radius = centre - 1.0;
+    let mut img = Mat::new(size, size, 1, MatDepth::U8)?;
+    for (row, line) in img.data_mut().chunks_exact_mut(size).enumerate() {
+        for (col, px) in line.iter_mut().enumerate() {
+            let mut sum = 0.0f64;
+            for sy in 0..4 {
+                for sx in 0..4 {
+                    let x = col as f64 + (f64::from(sx) + 0.5) / 4.0 - centre;
+                    let y = row as f64 + (f64::from(sy) + 0.5) / 4.0 - centre;
+                    sum += if x.hypot(y) > radius {
+                        128.0
+                    } else {
+                        let wedge = ((y.atan2(x) + PI) / PI * spokes as f64).floor() as usize;
+                        if wedge.is_multiple_of(2) { 0.0 } else { 255.0 }
+                    };
+                }
+            }
+            *px = (sum / 16.0).round() as u8;
+        }
+    }
+    Ok(img)
+}
+
+/// Kind of random field produced by [`noise_field`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseField {
+    /// Independent pixels, uniform over 0..=255
+    Uniform,
+    /// Independent pixels, normally distributed and clipped to 0..=255
+    Gaussian { mean: f64, sigma: f64 },
+    /// Value noise: uniform random values on a lattice `scale` pixels apart,
+    /// smoothly interpolated; a texture with features about `scale` pixels
+    /// across, suited to optical flow and stereo matching
+    Smooth { scale: usize },
+}
+
+/// Seeded random U8 image; the same seed always gives the same image
+pub fn noise_field(rows: usize, cols: usize, kind: NoiseField, seed: u64) -> Result<Mat> {
+    let mut rng = SplitMix64::new(seed);
+    let mut img = Mat::new(rows, cols, 1, MatDepth::U8)?;
+    match kind {
+        NoiseField::Uniform => {
+            for px in img.data_mut() {
+                *px = (rng.next_u64() >> 56) as u8;
+            }
+        }
+        NoiseField::Gaussian { mean, sigma } => {
+            for px in img.data_mut() {
+                *px = (mean + sigma * rng.next_gaussian()).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+        NoiseField::Smooth { scale } => {
+            if scale == 0 {
+                return Err(Error::InvalidParameter(
+                    "Noise scale must be positive".to_string(),
+                ));
+            }
+            let (lattice_rows, lattice_cols) = (rows / scale + 2, cols / scale + 2);
+            let lattice: Vec<f64> = (0..lattice_rows * lattice_cols).map(|_| rng.next_f64() * 255.0).collect();
+            let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+
+            for (row, line) in img.data_mut().chunks_exact_mut(cols.max(1)).enumerate() {
+                let (ly, ty) = (row / scale, smooth((row % scale) as f64 / scale as f64));
+                for (col, px) in line.iter_mut().enumerate() {
+                    let (lx, tx) = (col / scale, smooth((col % scale) as f64 / scale as f64));
+                    let at = |y: usize, x: usize| lattice[y * lattice_cols + x];
+                    let top = at(ly, lx) + (at(ly, lx + 1) - at(ly, lx)) * tx;
+                    let bottom = at(ly + 1, lx) + (at(ly + 1, lx + 1) - at(ly + 1, lx)) * tx;
+                    *px = (top + (bottom - top) * ty).round() as u8;
+                }
+            }
+        }
+    }
+    Ok(img)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkerboard_and_star() {
+        let board = checkerboard(8, 12, 4).unwrap();
+        assert_eq!(board.at(0, 0).unwrap()[0], 0);
+        assert_eq!(board.at(0, 4).unwrap()[0], 255);
+        assert_eq!(board.at(5, 5).unwrap()[0], 0);
+        assert!(checkerboard(8, 8, 0).is_err());
+
+        // Opposite points across the centre of a star with an odd number
+        // of spokes lie in wedges of opposite colour; these are 10 degrees
+        // either side of the horizontal, mid-wedge
+        let star = siemens_star(101, 9).unwrap();
+        let a = star.at(57, 89).unwrap()[0];
+        let b = star.at(43, 11).unwrap()[0];
+        assert_eq!(u16::from(a) + u16::from(b), 255);
+        assert_eq!(star.at(0, 0).unwrap()[0], 128);
+    }
+
+    #[test]
+    fn test_aruco_board_layout() {
+        let (board, markers) = aruco_board(ArucoDictionary::Dict4X4_50, 3, 2, 60, 10).unwrap();
+        assert_eq!((board.rows(), board.cols()), (2 * 70 + 10, 3 * 70 + 10));
+        assert_eq!(markers.len(), 6);
+        assert_eq!(markers[4].id, 4);
+        assert_eq!(markers[4].corners[0], Point2f::new(80.0, 80.0));
+        assert_eq!(markers[4].corners[2], Point2f::new(140.0, 140.0));
+        // Black marker border just inside the corner, white separation outside
+        assert_eq!(board.at(80, 80).unwrap()[0], 0);
+        assert_eq!(board.at(79, 79).unwrap()[0], 255);
+        assert!(aruco_board(ArucoDictionary::Dict4X4_50, 10, 10, 60, 10).is_err());
+    }
+
+    #[test]
+    fn test_noise_fields_are_seeded() {
+        let a = noise_field(32, 48, NoiseField::Uniform, 7).unwrap();
+        assert_eq!(a.data(), noise_field(32, 48, NoiseField::Uniform, 7).unwrap().data());
+        assert_ne!(a.data(), noise_field(32, 48, NoiseField::Uniform, 8).unwrap().data());
+
+        let gaussian = noise_field(64, 64, NoiseField::Gaussian { mean: 100.0, sigma: 10.0 }, 1).unwrap();
+        let mean = gaussian.data().iter().map(|&v| f64::from(v)).sum::<f64>() / 4096.0;
+        assert!((mean - 100.0).abs() < 1.0);
+
+        // Neighbouring pixels of smooth noise differ by far less than the full range
+        let smooth = noise_field(40, 40, NoiseField::Smooth { scale: 8 }, 3).unwrap();
+        let max_step = smooth.data().windows(2).take(39).map(|w| w[0].abs_diff(w[1])).max().unwrap();
+        assert!(max_step < 64);
+    }
+}
@@ -0,0 +1,236 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::calib3d::{project_points, rodrigues, CameraMatrix, DistortionCoefficients};
+use crate::core::{Mat, MatDepth};
+use crate::core::types::{BorderType, InterpolationFlag, Point3f, Size};
+use crate::error::{Error, Result};
+use crate::imgproc::PixelSampler;
+
+/// Pose of the scene in camera coordinates, as used by
+/// [`project_points`](crate::calib3d::project_points): a world point `p`
+/// appears at `R(rvec) p + tvec` in the camera frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScenePose {
+    pub rvec: [f64; 3],
+    pub tvec: [f64; 3],
+}
+
+impl ScenePose {
+    #[must_use]
+    pub fn new(rvec: [f64; 3], tvec: [f64; 3]) -> Self {
+        Self { rvec, tvec }
+    }
+}
+
+/// Textured plane rendered through a pinhole camera, for testing
+/// algorithms against exact poses, correspondences and optical flow
+///
+/// The plane is `z = 0` in world coordinates with the texture's top-left
+/// corner at the origin, x to the right and y down, `width` world units
+/// across. Pixels that miss the plane get the background value.
+#[derive(Debug, Clone)]
+pub struct PlanarScene {
+    texture: Mat,
+    width: f64,
+    background: u8,
+    supersampling: usize,
+}
+
+impl PlanarScene {
+    /// Plane showing `texture`, a single-channel U8 image such as one from
+    /// [`noise_field`](super::noise_field) or [`checkerboard`](super::checkerboard)
+    pub fn new(texture: Mat, width: f64) -> Result<Self> {
+        if texture.channels() != 1 || texture.depth() != MatDepth::U8 || texture.total() == 0 {
+            return Err(Error::InvalidParameter(
+                "Scene texture must be a non-empty single-channel U8 image".to_string(),
+            ));
+        }
+        if width.is_nan() || width <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "Scene width must be positive".to_string(),
+            ));
+        }
+        Ok(Self { texture, width, background: 0, supersampling: 3 })
+    }
+
+    #[must_use]
+    pub fn with_background(mut self, background: u8) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Samples per pixel along each axis when rendering (default 3)
+    #[must_use]
+    pub fn with_supersampling(mut self, supersampling: usize) -> Self {
+        self.supersampling = supersampling.max(1);
+        self
+    }
+
+    /// Height of the plane in world units
+    #[must_use]
+    pub fn height(&self) -> f64 {
+        self.width * self.texture.rows() as f64 / self.texture.cols() as f64
+    }
+
+    /// World point of the plane at texture pixel `(x, y)`
+    #[must_use]
+    pub fn texture_to_world(&self, x: f64, y: f64) -> Point3f {
+        let scale = self.width / self.texture.cols() as f64;
+        Point3f::new((x * scale) as f32, (y * scale) as f32, 0.0)
+    }
+
+    /// Render the scene as seen by `camera` (no lens distortion) from `pose`
+    pub fn render(&self, camera: &CameraMatrix, pose: &ScenePose, size: Size) -> Result<Mat> {
+        let (rows, cols) = image_size(size)?;
+        let n = self.supersampling;
+        let scale = self.texture.cols() as f64 / self.width;
+        let mut img = Mat::new(rows, cols, 1, MatDepth::U8)?;
+
+        for (row, line) in img.data_mut().chunks_exact_mut(cols).enumerate() {
+            for (col, px) in line.iter_mut().enumerate() {
+                let mut sum = 0.0;
+                for sy in 0..n {
+                    for sx in 0..n {
+                        let u = col as f64 + (sx as f64 + 0.5) / n as f64 - 0.5;
+                        let v = row as f64 + (sy as f64 + 0.5) / n as f64 - 0.5;
+                        sum += intersect_plane(camera, pose, u, v)
+                            .and_then(|(x, y)| self.sample(x * scale, y * scale))
+                            .unwrap_or(f64::from(self.background));
+                    }
+                }
+                *px = (sum / (n * n) as f64).round() as u8;
+            }
+        }
+        Ok(img)
+    }
+
+    /// Ground-truth optical flow from the view at `from` to the view at
+    /// `to`, as a 2-channel F32 image of `(dx, dy)` per pixel of the first
+    /// view; NaN where the pixel doesn't see the plane
+    pub fn flow(&self, camera: &CameraMatrix, from: &ScenePose, to: &ScenePose, size: Size) -> Result<Mat> {
+        let (rows, cols) = image_size(size)?;
+        let height = self.height();
+        let mut flow = Mat::new(rows, cols, 2, MatDepth::F32)?;
+        let no_distortion = DistortionCoefficients::new(0.0, 0.0, 0.0, 0.0, 0.0);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let (u, v) = (col as f64, row as f64);
+                let target = intersect_plane(camera, from, u, v)
+                    .filter(|&(x, y)| (0.0..self.width).contains(&x) && (0.0..height).contains(&y))
+                    .map(|(x, y)| {
+                        let world = Point3f::new(x as f32, y as f32, 0.0);
+                        project_points(&[world], &to.rvec, &to.tvec, camera, &no_distortion)[0]
+                    });
+                let (dx, dy) = target.map_or((f32::NAN, f32::NAN), |p| (p.x - u as f32, p.y - v as f32));
+                flow.set_f32(row, col, 0, dx)?;
+                flow.set_f32(row, col, 1, dy)?;
+            }
+        }
+        Ok(flow)
+    }
+
+    /// Bilinear texture lookup at texture pixel coordinates, `None` off the
+    /// texture
+    fn sample(&self, x: f64, y: f64) -> Option<f64> {
+        let (rows, cols) = (self.texture.rows(), self.texture.cols());
+        if x < 0.0 || y < 0.0 || x >= cols as f64 || y >= rows as f64 {
+            return None;
+        }
+        // Texel centres sit at half-integer coordinates
+        let mut v = [0.0];
+        PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate)
+            .sample(&self.texture, x - 0.5, y - 0.5, &mut v)
+            .ok()?;
+        Some(v[0])
+    }
+}
+
+fn image_size(size: Size) -> Result<(usize, usize)> {
+    if size.width <= 0 || size.height <= 0 {
+        return Err(Error::InvalidDimensions(
+            "Image size must be positive".to_string(),
+        ));
+    }
+    Ok((size.height as usize, size.width as usize))
+}
+
+/// World `(x, y)` where the ray through pixel `(u, v)` meets the plane
+/// `z = 0` in front of the camera
+fn intersect_plane(camera: &CameraMatrix, pose: &ScenePose, u: f64, v: f64) -> Option<(f64, f64)> {
+    let r = rodrigues(&pose.rvec);
+    let t = pose.tvec;
+    // Camera centre and ray direction in world coordinates: C = -R^T t, d = R^T K^-1 (u, v, 1)
+    let ray = [(u - camera.cx) / camera.fx, (v - camera.cy) / camera.fy, 1.0];
+    let centre: [f64; 3] = std::array::from_fn(|i| -(0..3).map(|j| r[j][i] * t[j]).sum::<f64>());
+    let dir: [f64; 3] = std::array::from_fn(|i| (0..3).map(|j| r[j][i] * ray[j]).sum::<f64>());
+
+    if dir[2].abs() < 1e-12 {
+        return None;
+    }
+    let s = -centre[2] / dir[2];
+    (s > 0.0).then(|| (centre[0] + s * dir[0], centre[1] + s * dir[1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calib3d::{find_chessboard_corners, ChessboardPattern};
+    use crate::testutils::checkerboard;
+
+    fn camera() -> CameraMatrix {
+        CameraMatrix::new(400.0, 400.0, 160.0, 120.0)
+    }
+
+    #[test]
+    fn test_render_matches_projection() {
+        // 7x5 squares of 30 px, 0.3 units wide; a one-square white margin
+        // is needed around the board for corner detection
+        let mut texture = checkerboard(210, 270, 30).unwrap();
+        for row in 0..210 {
+            for col in 0..270 {
+                if !(30..180).contains(&row) || !(30..240).contains(&col) {
+                    texture.at_mut(row, col).unwrap()[0] = 255;
+                }
+            }
+        }
+        let scene = PlanarScene::new(texture, 0.9).unwrap().with_background(128);
+        let pose = ScenePose::new([0.1, -0.15, 0.05], [-0.4, -0.3, 1.2]);
+        let img = scene.render(&camera(), &pose, Size::new(320, 240)).unwrap();
+
+        let corners = find_chessboard_corners(&img, Size::new(6, 4)).unwrap().expect("board found");
+        // Inner corners start one square in from the black squares' origin
+        let board = ChessboardPattern::new(Size::new(6, 4), 0.1);
+        let objects: Vec<Point3f> = board
+            .object_points()
+            .iter()
+            .map(|p| Point3f::new(p.x + 0.2, p.y + 0.2, 0.0))
+            .collect();
+        let no_distortion = DistortionCoefficients::new(0.0, 0.0, 0.0, 0.0, 0.0);
+        let expected = project_points(&objects, &pose.rvec, &pose.tvec, &camera(), &no_distortion);
+        for (found, truth) in corners.iter().zip(&expected) {
+            assert!((found.x - truth.x).hypot(found.y - truth.y) < 1.5, "{found:?} vs {truth:?}");
+        }
+    }
+
+    #[test]
+    fn test_flow_of_a_translation() {
+        let texture = crate::testutils::noise_field(100, 100, crate::testutils::NoiseField::Smooth { scale: 6 }, 5).unwrap();
+        let scene = PlanarScene::new(texture, 1.0).unwrap();
+        let from = ScenePose::new([0.0; 3], [-0.5, -0.5, 2.0]);
+        let to = ScenePose::new([0.0; 3], [-0.45, -0.5, 2.0]);
+        let flow = scene.flow(&camera(), &from, &to, Size::new(320, 240)).unwrap();
+
+        // Fronto-parallel plane at depth 2 moved 0.05 units: 400 * 0.05 / 2 = 10 px
+        assert!((flow.at_f32(120, 160, 0).unwrap() - 10.0).abs() < 1e-3);
+        assert!(flow.at_f32(120, 160, 1).unwrap().abs() < 1e-3);
+        assert!(flow.at_f32(0, 0, 0).unwrap().is_nan());
+
+        // The rendered frames agree with the flow
+        let a = scene.render(&camera(), &from, Size::new(320, 240)).unwrap();
+        let b = scene.render(&camera(), &to, Size::new(320, 240)).unwrap();
+        for (row, col) in [(100, 120), (130, 170), (140, 150)] {
+            let diff = i32::from(a.at(row, col).unwrap()[0]) - i32::from(b.at(row, col + 10).unwrap()[0]);
+            assert!(diff.abs() <= 1, "({row}, {col}): {diff}");
+        }
+    }
+}
@@ -0,0 +1,158 @@
+#![allow(clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Local thresholding rule used by [`local_threshold`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocalBinarization {
+    /// `T = m + k·s`, with `k` around -0.2 for dark text
+    Niblack { k: f64 },
+    /// `T = m·(1 + k·(s/r − 1))`, with `k` around 0.34 and `r` the dynamic
+    /// range of the standard deviation (128 for U8 images)
+    Sauvola { k: f64, r: f64 },
+}
+
+impl LocalBinarization {
+    /// Sauvola with the commonly used `k = 0.34`, `r = 128`
+    #[must_use]
+    pub fn sauvola() -> Self {
+        Self::Sauvola { k: 0.34, r: 128.0 }
+    }
+
+    /// Niblack with the commonly used `k = -0.2`
+    #[must_use]
+    pub fn niblack() -> Self {
+        Self::Niblack { k: -0.2 }
+    }
+
+    fn threshold(self, mean: f64, std_dev: f64) -> f64 {
+        match self {
+            Self::Niblack { k } => mean + k * std_dev,
+            Self::Sauvola { k, r } => mean * (1.0 + k * (std_dev / r - 1.0)),
+        }
+    }
+}
+
+impl Default for LocalBinarization {
+    fn default() -> Self {
+        Self::sauvola()
+    }
+}
+
+/// Binarize dark text on a light background with a per-pixel threshold
+///
+/// The threshold is computed from the mean `m` and standard deviation `s` of
+/// the `window_size`×`window_size` neighbourhood (clipped at the image
+/// border), so shading and uneven illumination do not swallow text the way a
+/// global threshold does. The window should span a few characters; 15–31
+/// pixels suits typical 300 dpi scans.
+///
+/// `src` must be single-channel U8. The result is a text mask: pixels darker
+/// than their threshold become 255, everything else 0.
+pub fn local_threshold(src: &Mat, dst: &mut Mat, window_size: usize, method: LocalBinarization) -> Result<()> {
+    if src.channels() != 1 || src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "local_threshold requires a single-channel U8 image".to_string(),
+        ));
+    }
+
+    if window_size < 3 || window_size.is_multiple_of(2) {
+        return Err(Error::InvalidParameter(
+            "window_size must be odd and at least 3".to_string(),
+        ));
+    }
+
+    let rows = src.rows();
+    let cols = src.cols();
+    let stride = cols + 1;
+
+    // Integral images of values and squared values
+    let mut sum = vec![0.0f64; (rows + 1) * stride];
+    let mut sum_sq = vec![0.0f64; (rows + 1) * stride];
+    for row in 0..rows {
+        let mut row_sum = 0.0;
+        let mut row_sum_sq = 0.0;
+        for col in 0..cols {
+            let v = f64::from(src.at(row, col)?[0]);
+            row_sum += v;
+            row_sum_sq += v * v;
+            sum[(row + 1) * stride + col + 1] = sum[row * stride + col + 1] + row_sum;
+            sum_sq[(row + 1) * stride + col + 1] = sum_sq[row * stride + col + 1] + row_sum_sq;
+        }
+    }
+
+    let half = window_size / 2;
+    let mut out = Mat::new(rows, cols, 1, MatDepth::U8)?;
+
+    for row in 0..rows {
+        let y0 = row.saturating_sub(half);
+        let y1 = (row + half + 1).min(rows);
+        for col in 0..cols {
+            let x0 = col.saturating_sub(half);
+            let x1 = (col + half + 1).min(cols);
+
+            let area = ((y1 - y0) * (x1 - x0)) as f64;
+            let box_sum = |t: &[f64]| t[y1 * stride + x1] - t[y0 * stride + x1] - t[y1 * stride + x0] + t[y0 * stride + x0];
+            let mean = box_sum(&sum) / area;
+            let variance = (box_sum(&sum_sq) / area - mean * mean).max(0.0);
+
+            let threshold = method.threshold(mean, variance.sqrt());
+            let v = f64::from(src.at(row, col)?[0]);
+            out.at_mut(row, col)?[0] = if v < threshold { 255 } else { 0 };
+        }
+    }
+
+    *dst = out;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dark strokes on a background that brightens from left to right
+    fn shaded_page() -> Mat {
+        let mut img = Mat::new(40, 120, 1, MatDepth::U8).unwrap();
+        for row in 0..40 {
+            for col in 0..120 {
+                let paper = 80 + col;
+                let ink = col % 12 < 2 && (10..30).contains(&row);
+                img.at_mut(row, col).unwrap()[0] = if ink { (paper / 3) as u8 } else { paper as u8 };
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_sauvola_handles_shading() {
+        let img = shaded_page();
+        let mut mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        local_threshold(&img, &mut mask, 15, LocalBinarization::sauvola()).unwrap();
+
+        // Strokes are found on both the dark and the bright side
+        assert_eq!(mask.at(20, 12).unwrap()[0], 255);
+        assert_eq!(mask.at(20, 108).unwrap()[0], 255);
+        // Paper between strokes stays background
+        assert_eq!(mask.at(20, 6).unwrap()[0], 0);
+        assert_eq!(mask.at(20, 114).unwrap()[0], 0);
+        assert_eq!(mask.at(2, 60).unwrap()[0], 0);
+    }
+
+    #[test]
+    fn test_niblack_marks_strokes() {
+        let img = shaded_page();
+        let mut mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        local_threshold(&img, &mut mask, 15, LocalBinarization::niblack()).unwrap();
+
+        assert_eq!(mask.at(20, 36).unwrap()[0], 255);
+        assert_eq!(mask.at(20, 84).unwrap()[0], 255);
+        assert_eq!(mask.at(20, 42).unwrap()[0], 0);
+    }
+
+    #[test]
+    fn test_local_threshold_rejects_bad_window() {
+        let img = shaded_page();
+        let mut mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        assert!(local_threshold(&img, &mut mask, 14, LocalBinarization::default()).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::{BorderType, InterpolationFlag, Scalar, Size};
+use crate::error::{Error, Result};
+use crate::imgproc::{warp_affine_with_sampler, PixelSampler};
+
+/// How [`estimate_skew`] measures the text angle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkewMethod {
+    /// Pick the angle whose horizontal projection profile of the text mask is
+    /// sharpest. Robust for pages of running text.
+    ProjectionProfile,
+    /// Vote the bottom edges of text strokes into a Hough accumulator and take
+    /// the angle of the strongest baselines. Copes better with sparse text.
+    Hough,
+}
+
+/// Estimate the skew of text lines in a text mask
+///
+/// Returns the angle in degrees of the text lines relative to the image
+/// x-axis, positive when lines rise to the right. Only angles within
+/// `±max_angle` degrees are considered; the search resolution is 0.1°.
+pub fn estimate_skew(mask: &Mat, method: SkewMethod, max_angle: f64) -> Result<f64> {
+    if mask.channels() != 1 || mask.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "estimate_skew requires a single-channel U8 text mask".to_string(),
+        ));
+    }
+
+    if !(0.0..90.0).contains(&max_angle) {
+        return Err(Error::InvalidParameter(
+            "max_angle must be in [0, 90) degrees".to_string(),
+        ));
+    }
+
+    let points = match method {
+        SkewMethod::ProjectionProfile => foreground_points(mask, false)?,
+        SkewMethod::Hough => foreground_points(mask, true)?,
+    };
+
+    if points.is_empty() {
+        return Ok(0.0);
+    }
+
+    let steps = (max_angle * 10.0).round() as i32;
+    let max_offset = ((mask.rows() * mask.rows() + mask.cols() * mask.cols()) as f64).sqrt();
+    let bins = 2 * max_offset.ceil() as usize + 1;
+    let mut histogram = vec![0u32; bins];
+
+    let mut best = (f64::MIN, 0.0f64);
+    for step in -steps..=steps {
+        let angle = f64::from(step) * 0.1;
+        let (sin, cos) = angle.to_radians().sin_cos();
+
+        histogram.iter_mut().for_each(|h| *h = 0);
+        for &(x, y) in &points {
+            // Offset of the point along the normal of a line rising at `angle`
+            let offset = y * cos + x * sin;
+            histogram[(offset + max_offset).round() as usize] += 1;
+        }
+
+        let score = match method {
+            SkewMethod::ProjectionProfile => histogram.iter().map(|&h| f64::from(h) * f64::from(h)).sum(),
+            SkewMethod::Hough => {
+                // Sum of the strongest accumulator cells, one per baseline
+                let mut cells: Vec<u32> = histogram.iter().copied().filter(|&h| h > 0).collect();
+                cells.sort_unstable_by(|a, b| b.cmp(a));
+                cells.iter().take(8).map(|&h| f64::from(h)).sum()
+            }
+        };
+
+        // Ties go to the smallest correction
+        if score > best.0 || (score == best.0 && angle.abs() < best.1.abs()) {
+            best = (score, angle);
+        }
+    }
+
+    Ok(best.1)
+}
+
+/// Rotate an image to undo a skew of `angle` degrees
+///
+/// `angle` uses the convention of [`estimate_skew`]. The image is rotated
+/// about its centre with bilinear interpolation and keeps its size and
+/// colour-order tag; areas uncovered by the rotation are filled with `border`.
+/// Accepts U8 images with any number of channels.
+///
+/// To straighten the lean of individual glyphs rather than the rotation of
+/// text lines, see [`deskew_moments`](crate::imgproc::deskew_moments).
+pub fn deskew(src: &Mat, dst: &mut Mat, angle: f64, border: Scalar) -> Result<()> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "deskew only supports U8 depth".to_string(),
+        ));
+    }
+
+    let cx = (src.cols() as f64 - 1.0) / 2.0;
+    let cy = (src.rows() as f64 - 1.0) / 2.0;
+    // Destination pixels look up the source rotated back by the skew
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let m = [
+        [cos, sin, cx - cx * cos - cy * sin],
+        [-sin, cos, cy + cx * sin - cy * cos],
+    ];
+
+    let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Constant).with_border_value(border);
+    let dsize = Size::new(src.cols() as i32, src.rows() as i32);
+    warp_affine_with_sampler(src, dst, &m, dsize, &sampler)
+}
+
+/// Foreground pixel coordinates; with `bottom_edges` only the lowest pixel of
+/// each vertical run, which traces the baselines
+fn foreground_points(mask: &Mat, bottom_edges: bool) -> Result<Vec<(f64, f64)>> {
+    let mut points = Vec::new();
+    for row in 0..mask.rows() {
+        for col in 0..mask.cols() {
+            if mask.at(row, col)?[0] == 0 {
+                continue;
+            }
+            if bottom_edges && row + 1 < mask.rows() && mask.at(row + 1, col)?[0] != 0 {
+                continue;
+            }
+            points.push((col as f64, row as f64));
+        }
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text mask of dashed lines ("words") rising at `angle` degrees
+    fn skewed_lines(angle: f64) -> Mat {
+        let mut mask = Mat::new(160, 200, 1, MatDepth::U8).unwrap();
+        let slope = angle.to_radians().tan();
+        for base in [40.0, 70.0, 100.0, 130.0] {
+            for col in 20..180 {
+                if col % 30 > 24 {
+                    continue;
+                }
+                let y = base - (col as f64 - 100.0) * slope;
+                for dy in 0..5 {
+                    let row = (y - f64::from(dy)).round() as usize;
+                    mask.at_mut(row, col).unwrap()[0] = 255;
+                }
+            }
+        }
+        mask
+    }
+
+    #[test]
+    fn test_estimate_skew_projection_profile() {
+        let mask = skewed_lines(4.0);
+        let angle = estimate_skew(&mask, SkewMethod::ProjectionProfile, 10.0).unwrap();
+        assert!((angle - 4.0).abs() <= 0.3, "angle {angle}");
+
+        let mask = skewed_lines(-2.5);
+        let angle = estimate_skew(&mask, SkewMethod::ProjectionProfile, 10.0).unwrap();
+        assert!((angle + 2.5).abs() <= 0.3, "angle {angle}");
+    }
+
+    #[test]
+    fn test_estimate_skew_hough() {
+        let mask = skewed_lines(-3.0);
+        let angle = estimate_skew(&mask, SkewMethod::Hough, 10.0).unwrap();
+        assert!((angle + 3.0).abs() <= 0.3, "angle {angle}");
+    }
+
+    #[test]
+    fn test_deskew_levels_lines() {
+        let mask = skewed_lines(5.0);
+        let mut level = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        deskew(&mask, &mut level, 5.0, Scalar::all(0.0)).unwrap();
+
+        let residual = estimate_skew(&level, SkewMethod::ProjectionProfile, 10.0).unwrap();
+        assert!(residual.abs() <= 0.3, "residual {residual}");
+    }
+
+    #[test]
+    fn test_deskew_keeps_color_order() {
+        use crate::core::types::ColorOrder;
+
+        let src = Mat::new(8, 8, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        deskew(&src, &mut dst, 30.0, Scalar::all(255.0)).unwrap();
+        assert_eq!(dst.color_order(), ColorOrder::Bgr);
+        assert_eq!(dst.at(0, 7).unwrap(), &[255, 255, 255]);
+    }
+}
@@ -0,0 +1,20 @@
+//! Document and text image preprocessing
+//!
+//! Building blocks for OCR front-ends: local binarization that copes with
+//! uneven lighting, skew estimation and correction, and segmentation of a
+//! page into line and word rectangles, and stroke width transform text
+//! detection for natural images.
+//!
+//! Functions in this module work on *text masks*: single-channel U8 images in
+//! which text pixels are 255 and background pixels are 0, as produced by
+//! [`local_threshold`].
+
+pub mod binarize;
+pub mod deskew;
+pub mod segment;
+pub mod swt;
+
+pub use binarize::*;
+pub use deskew::*;
+pub use segment::*;
+pub use swt::*;
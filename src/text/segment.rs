@@ -0,0 +1,220 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Rect;
+use crate::error::{Error, Result};
+
+/// Split a deskewed text mask into text line rectangles
+///
+/// Lines are runs of rows containing text in the horizontal projection
+/// profile. Runs separated by fewer than `min_gap` empty rows are merged (so
+/// dots and accents stay with their line) and runs shorter than `min_height`
+/// rows are dropped as noise. Each rectangle is tight around its text and the
+/// result is ordered top to bottom.
+pub fn segment_lines(mask: &Mat, min_gap: usize, min_height: usize) -> Result<Vec<Rect>> {
+    check_mask(mask)?;
+
+    let full = Rect::new(0, 0, mask.cols() as i32, mask.rows() as i32);
+    let profile = row_profile(mask, full)?;
+    let mut lines = Vec::new();
+
+    for (start, end) in runs(&profile, min_gap) {
+        if end - start < min_height {
+            continue;
+        }
+        let band = Rect::new(0, start as i32, mask.cols() as i32, (end - start) as i32);
+        if let Some(rect) = tight_bounds(mask, band)? {
+            lines.push(rect);
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Split one text line into word rectangles
+///
+/// Words are runs of columns containing text inside `line`; gaps narrower
+/// than `min_gap` columns are treated as letter spacing and bridged. A
+/// `min_gap` of about a third of the line height separates words in most
+/// fonts. The result is ordered left to right.
+pub fn segment_words(mask: &Mat, line: Rect, min_gap: usize) -> Result<Vec<Rect>> {
+    check_mask(mask)?;
+
+    if line.x < 0
+        || line.y < 0
+        || line.width <= 0
+        || line.height <= 0
+        || (line.x + line.width) as usize > mask.cols()
+        || (line.y + line.height) as usize > mask.rows()
+    {
+        return Err(Error::InvalidDimensions(
+            "Line rectangle must lie inside the mask".to_string(),
+        ));
+    }
+
+    let profile = col_profile(mask, line)?;
+    let mut words = Vec::new();
+
+    for (start, end) in runs(&profile, min_gap) {
+        let band = Rect::new(line.x + start as i32, line.y, (end - start) as i32, line.height);
+        if let Some(rect) = tight_bounds(mask, band)? {
+            words.push(rect);
+        }
+    }
+
+    Ok(words)
+}
+
+/// Lines of a text mask, each with its words
+///
+/// Convenience wrapper over [`segment_lines`] and [`segment_words`] that
+/// picks the gaps from each line's height.
+pub fn segment_text(mask: &Mat) -> Result<Vec<(Rect, Vec<Rect>)>> {
+    let lines = segment_lines(mask, 2, 3)?;
+    lines
+        .into_iter()
+        .map(|line| {
+            let words = segment_words(mask, line, (line.height as usize / 3).max(2))?;
+            Ok((line, words))
+        })
+        .collect()
+}
+
+fn check_mask(mask: &Mat) -> Result<()> {
+    if mask.channels() != 1 || mask.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "Text segmentation requires a single-channel U8 text mask".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn row_profile(mask: &Mat, rect: Rect) -> Result<Vec<usize>> {
+    let (x0, y0) = (rect.x as usize, rect.y as usize);
+    let mut profile = vec![0; rect.height as usize];
+    for (i, count) in profile.iter_mut().enumerate() {
+        for col in x0..x0 + rect.width as usize {
+            if mask.at(y0 + i, col)?[0] != 0 {
+                *count += 1;
+            }
+        }
+    }
+    Ok(profile)
+}
+
+fn col_profile(mask: &Mat, rect: Rect) -> Result<Vec<usize>> {
+    let (x0, y0) = (rect.x as usize, rect.y as usize);
+    let mut profile = vec![0; rect.width as usize];
+    for row in y0..y0 + rect.height as usize {
+        for (i, count) in profile.iter_mut().enumerate() {
+            if mask.at(row, x0 + i)?[0] != 0 {
+                *count += 1;
+            }
+        }
+    }
+    Ok(profile)
+}
+
+/// Half-open index ranges of non-zero runs, bridging gaps shorter than `min_gap`
+fn runs(profile: &[usize], min_gap: usize) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut start = None;
+
+    for (i, &count) in profile.iter().enumerate() {
+        match (count > 0, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                runs.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        runs.push((s, profile.len()));
+    }
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(runs.len());
+    for run in runs {
+        match merged.last_mut() {
+            Some(last) if run.0 - last.1 < min_gap => last.1 = run.1,
+            _ => merged.push(run),
+        }
+    }
+    merged
+}
+
+/// Smallest rectangle inside `band` containing all of its text pixels
+fn tight_bounds(mask: &Mat, band: Rect) -> Result<Option<Rect>> {
+    let rows = row_profile(mask, band)?;
+    let cols = col_profile(mask, band)?;
+
+    let (Some(top), Some(bottom)) = (rows.iter().position(|&c| c > 0), rows.iter().rposition(|&c| c > 0)) else {
+        return Ok(None);
+    };
+    let (Some(left), Some(right)) = (cols.iter().position(|&c| c > 0), cols.iter().rposition(|&c| c > 0)) else {
+        return Ok(None);
+    };
+
+    Ok(Some(Rect::new(
+        band.x + left as i32,
+        band.y + top as i32,
+        (right - left + 1) as i32,
+        (bottom - top + 1) as i32,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(mask: &mut Mat, rect: Rect) {
+        for row in rect.y..rect.y + rect.height {
+            for col in rect.x..rect.x + rect.width {
+                mask.at_mut(row as usize, col as usize).unwrap()[0] = 255;
+            }
+        }
+    }
+
+    /// Two lines; letters 4 px wide, 2 px apart, words 8 px apart
+    fn page() -> Mat {
+        let mut mask = Mat::new(60, 100, 1, MatDepth::U8).unwrap();
+        for (y, words) in [(10, vec![(5, 3), (47, 2)]), (35, vec![(10, 4)])] {
+            for (x, letters) in words {
+                for l in 0..letters {
+                    fill(&mut mask, Rect::new(x + l * 6, y, 4, 10));
+                }
+            }
+        }
+        // An "i" dot above the first line, separated by a 1-row gap
+        fill(&mut mask, Rect::new(6, 7, 2, 2));
+        mask
+    }
+
+    #[test]
+    fn test_segment_lines() {
+        let lines = segment_lines(&page(), 2, 3).unwrap();
+        assert_eq!(lines, vec![Rect::new(5, 7, 52, 13), Rect::new(10, 35, 22, 10)]);
+    }
+
+    #[test]
+    fn test_segment_words() {
+        let mask = page();
+        let lines = segment_lines(&mask, 2, 3).unwrap();
+
+        let words = segment_words(&mask, lines[0], 4).unwrap();
+        assert_eq!(words, vec![Rect::new(5, 7, 16, 13), Rect::new(47, 10, 10, 10)]);
+
+        // With a gap threshold below the letter spacing every letter is a word
+        assert_eq!(segment_words(&mask, lines[1], 2).unwrap().len(), 4);
+
+        assert!(segment_words(&mask, Rect::new(90, 0, 20, 10), 4).is_err());
+    }
+
+    #[test]
+    fn test_segment_text() {
+        let result = segment_text(&page()).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].1.len(), 2);
+        assert_eq!(result[1].1.len(), 1);
+    }
+}
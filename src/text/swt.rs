@@ -0,0 +1,443 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::{Mat, MatDepth};
+use crate::core::types::Rect;
+use crate::error::{Error, Result};
+
+/// Parameters for [`stroke_width_transform`] and [`detect_text_swt`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwtParams {
+    /// Dark text on a light background; run twice with both values when the
+    /// polarity is unknown
+    pub dark_on_light: bool,
+    /// Canny hysteresis thresholds for the edge map
+    pub canny_low: f64,
+    pub canny_high: f64,
+    /// Longest ray followed across a stroke, in pixels
+    pub max_stroke_width: usize,
+    /// Letter height range in pixels
+    pub min_letter_height: usize,
+    pub max_letter_height: usize,
+    /// Largest standard deviation of a letter's stroke widths, relative to
+    /// their mean
+    pub max_stroke_variation: f64,
+    /// Largest gap between neighbouring letters of a word, relative to the
+    /// taller letter's height
+    pub max_letter_gap: f64,
+    /// Fewest letters a word may have
+    pub min_word_letters: usize,
+}
+
+impl Default for SwtParams {
+    fn default() -> Self {
+        Self {
+            dark_on_light: true,
+            canny_low: 50.0,
+            canny_high: 150.0,
+            max_stroke_width: 50,
+            min_letter_height: 8,
+            max_letter_height: 300,
+            max_stroke_variation: 0.5,
+            max_letter_gap: 0.5,
+            min_word_letters: 2,
+        }
+    }
+}
+
+impl SwtParams {
+    #[must_use]
+    pub fn with_dark_on_light(mut self, dark_on_light: bool) -> Self {
+        self.dark_on_light = dark_on_light;
+        self
+    }
+
+    #[must_use]
+    pub fn with_canny_thresholds(mut self, low: f64, high: f64) -> Self {
+        self.canny_low = low;
+        self.canny_high = high;
+        self
+    }
+
+    #[must_use]
+    pub fn with_letter_height(mut self, min: usize, max: usize) -> Self {
+        self.min_letter_height = min;
+        self.max_letter_height = max;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_stroke_width(mut self, max_stroke_width: usize) -> Self {
+        self.max_stroke_width = max_stroke_width;
+        self
+    }
+
+    #[must_use]
+    pub fn with_min_word_letters(mut self, min_word_letters: usize) -> Self {
+        self.min_word_letters = min_word_letters;
+        self
+    }
+}
+
+/// A letter candidate found by [`detect_text_swt`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwtLetter {
+    pub rect: Rect,
+    /// Median stroke width in pixels
+    pub stroke_width: f64,
+}
+
+/// Letter and word candidates, each ordered top to bottom, then left to right
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SwtDetection {
+    pub letters: Vec<SwtLetter>,
+    pub words: Vec<Rect>,
+}
+
+/// Stroke width transform (Epshtein, Ofek and Wexler 2010)
+///
+/// From every Canny edge pixel a ray is cast against the gradient (with it
+/// for light text) until it meets an edge whose gradient points roughly the
+/// other way; the pixels on the ray get the ray's length as their stroke
+/// width, and each ray is then capped at its median to clean up corners.
+/// Returns a 1-channel F32 image with 0 where no stroke was found. `src` is a
+/// 1, 3 or 4-channel U8 image.
+pub fn stroke_width_transform(src: &Mat, params: &SwtParams) -> Result<Mat> {
+    let gray = prepare(src)?;
+    let (widths, _) = swt(&gray, params)?;
+    let mut out = Mat::new(gray.rows(), gray.cols(), 1, MatDepth::F32)?;
+    for (dst, w) in out.data_mut().chunks_exact_mut(4).zip(&widths) {
+        dst.copy_from_slice(&w.to_le_bytes());
+    }
+    Ok(out)
+}
+
+/// Find letter and word candidates with the stroke width transform
+///
+/// Pixels of similar stroke width are grouped into components, which are
+/// kept as letters when their size, aspect ratio and stroke width
+/// consistency are plausible for text. Letters of similar height and stroke
+/// width that sit side by side on a line are chained into words. No model or
+/// training data is involved, which makes this a fallback for targets
+/// without a DNN text detector.
+pub fn detect_text_swt(src: &Mat, params: &SwtParams) -> Result<SwtDetection> {
+    let gray = prepare(src)?;
+    let (widths, w) = swt(&gray, params)?;
+    let mut letters = find_letters(&widths, w, params);
+    letters.sort_by_key(|l| (l.rect.y, l.rect.x));
+    let mut words = chain_words(&letters, params);
+    words.sort_by_key(|r| (r.y, r.x));
+    Ok(SwtDetection { letters, words })
+}
+
+fn prepare(src: &Mat) -> Result<Mat> {
+    if src.depth() != MatDepth::U8 {
+        return Err(Error::UnsupportedOperation(
+            "Stroke width transform only supports U8 depth".to_string(),
+        ));
+    }
+    let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+    crate::imgproc::to_gray(src, &mut gray)?;
+    Ok(gray)
+}
+
+/// Stroke widths per pixel, 0 for none, and the image width
+fn swt(gray: &Mat, params: &SwtParams) -> Result<(Vec<f32>, usize)> {
+    let (w, h) = (gray.cols(), gray.rows());
+    let mut edges = Mat::new(1, 1, 1, MatDepth::U8)?;
+    crate::imgproc::canny(gray, &mut edges, params.canny_low, params.canny_high)?;
+    let luma = gray.data();
+
+    // Sobel gradients, also used to thin the edges to one pixel
+    let mut gx = vec![0.0f32; w * h];
+    let mut gy = vec![0.0f32; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let at = |dx: isize, dy: isize| {
+                let xx = (x as isize + dx).clamp(0, w as isize - 1) as usize;
+                let yy = (y as isize + dy).clamp(0, h as isize - 1) as usize;
+                f32::from(luma[yy * w + xx])
+            };
+            gx[y * w + x] = at(1, -1) + 2.0 * at(1, 0) + at(1, 1) - at(-1, -1) - 2.0 * at(-1, 0) - at(-1, 1);
+            gy[y * w + x] = at(-1, 1) + 2.0 * at(0, 1) + at(1, 1) - at(-1, -1) - 2.0 * at(0, -1) - at(1, -1);
+        }
+    }
+    let magnitude = |i: usize| gx[i].hypot(gy[i]);
+    let edges: Vec<bool> = edges
+        .data()
+        .iter()
+        .enumerate()
+        .map(|(i, &e)| {
+            if e == 0 {
+                return false;
+            }
+            let (x, y) = ((i % w) as isize, (i / w) as isize);
+            let m = magnitude(i);
+            // Neighbours across the edge, along the gradient rounded to 45 degrees
+            let (sx, sy) = (gx[i].signum() * f32::from(u8::from(gx[i].abs() * 2.414 > gy[i].abs())),
+                            gy[i].signum() * f32::from(u8::from(gy[i].abs() * 2.414 > gx[i].abs())));
+            let neighbour = |k: isize| {
+                let (nx, ny) = (x + k * sx as isize, y + k * sy as isize);
+                if nx < 0 || ny < 0 || nx >= w as isize || ny >= h as isize {
+                    0.0
+                } else {
+                    magnitude(ny as usize * w + nx as usize)
+                }
+            };
+            m >= neighbour(1) && m > neighbour(-1)
+        })
+        .collect();
+
+    // Unit step into the stroke at each edge pixel
+    let sign = if params.dark_on_light { -1.0 } else { 1.0 };
+    let direction = |i: usize| -> Option<(f32, f32)> {
+        let norm = magnitude(i);
+        (norm > 0.0).then(|| (sign * gx[i] / norm, sign * gy[i] / norm))
+    };
+
+    let mut widths = vec![f32::INFINITY; w * h];
+    let mut rays: Vec<Vec<usize>> = Vec::new();
+    let max_steps = params.max_stroke_width * 2;
+    for y in 0..h {
+        for x in 0..w {
+            if !edges[y * w + x] {
+                continue;
+            }
+            let Some((dx, dy)) = direction(y * w + x) else { continue };
+
+            let mut ray = vec![y * w + x];
+            let (mut fx, mut fy) = (x as f32 + 0.5, y as f32 + 0.5);
+            // Half-pixel steps so diagonal rays can't skip thin edges
+            for _ in 0..max_steps {
+                fx += dx * 0.5;
+                fy += dy * 0.5;
+                if fx < 0.0 || fy < 0.0 || fx >= w as f32 || fy >= h as f32 {
+                    break;
+                }
+                let i = fy as usize * w + fx as usize;
+                if ray.last() == Some(&i) {
+                    continue;
+                }
+                ray.push(i);
+                if !edges[i] {
+                    continue;
+                }
+                let Some((qx, qy)) = direction(i) else { break };
+                let alignment = dx * qx + dy * qy;
+                // Still crossing the starting edge, which may be several
+                // pixels thick
+                if alignment > 0.0 {
+                    continue;
+                }
+                // Opposite side of the stroke: gradients within 30 degrees of
+                // antiparallel
+                if alignment < -(std::f32::consts::FRAC_PI_6.cos()) {
+                    let width = ((i % w) as f32 - x as f32).hypot((i / w) as f32 - y as f32);
+                    for &p in &ray {
+                        widths[p] = widths[p].min(width);
+                    }
+                    rays.push(ray);
+                }
+                break;
+            }
+        }
+    }
+
+    for ray in &rays {
+        let mut values: Vec<f32> = ray.iter().map(|&p| widths[p]).collect();
+        // Lower median, so a ray through a junction that is half inside
+        // thinner strokes is capped to their width
+        let mid = (values.len() - 1) / 2;
+        let median = *values.select_nth_unstable_by(mid, f32::total_cmp).1;
+        for &p in ray {
+            widths[p] = widths[p].min(median);
+        }
+    }
+    for v in &mut widths {
+        if !v.is_finite() {
+            *v = 0.0;
+        }
+    }
+    Ok((widths, w))
+}
+
+/// Components of similar stroke width that pass the letter filters
+fn find_letters(widths: &[f32], w: usize, params: &SwtParams) -> Vec<SwtLetter> {
+    let h = widths.len() / w;
+    let mut visited = vec![false; widths.len()];
+    let mut letters = Vec::new();
+    let mut stack = Vec::new();
+    let mut component = Vec::new();
+
+    for start in 0..widths.len() {
+        if widths[start] <= 0.0 || visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        stack.push(start);
+        component.clear();
+        while let Some(i) = stack.pop() {
+            component.push(i);
+            let (x, y) = (i % w, i / w);
+            for ny in y.saturating_sub(1)..(y + 2).min(h) {
+                for nx in x.saturating_sub(1)..(x + 2).min(w) {
+                    let j = ny * w + nx;
+                    let (a, b) = (widths[i], widths[j]);
+                    if !visited[j] && b > 0.0 && a.max(b) <= 3.0 * a.min(b) {
+                        visited[j] = true;
+                        stack.push(j);
+                    }
+                }
+            }
+        }
+        if let Some(letter) = letter_candidate(&component, widths, w, params) {
+            letters.push(letter);
+        }
+    }
+    letters
+}
+
+fn letter_candidate(component: &[usize], widths: &[f32], w: usize, params: &SwtParams) -> Option<SwtLetter> {
+    let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+    for &i in component {
+        x0 = x0.min(i % w);
+        y0 = y0.min(i / w);
+        x1 = x1.max(i % w);
+        y1 = y1.max(i / w);
+    }
+    let (width, height) = (x1 - x0 + 1, y1 - y0 + 1);
+    if height < params.min_letter_height || height > params.max_letter_height {
+        return None;
+    }
+    let aspect = width as f64 / height as f64;
+    if !(0.1..=10.0).contains(&aspect) {
+        return None;
+    }
+
+    let mut values: Vec<f64> = component.iter().map(|&i| f64::from(widths[i])).collect();
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if std > params.max_stroke_variation * mean {
+        return None;
+    }
+    let mid = values.len() / 2;
+    let median = *values.select_nth_unstable_by(mid, f64::total_cmp).1;
+    // Long thin shapes relative to their stroke are lines, not letters
+    if (width as f64).hypot(height as f64) > 10.0 * median {
+        return None;
+    }
+
+    Some(SwtLetter {
+        rect: Rect::new(x0 as i32, y0 as i32, width as i32, height as i32),
+        stroke_width: median,
+    })
+}
+
+/// Union of chains of letters that could belong to one word
+fn chain_words(letters: &[SwtLetter], params: &SwtParams) -> Vec<Rect> {
+    let mut parent: Vec<usize> = (0..letters.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..letters.len() {
+        for j in i + 1..letters.len() {
+            let (a, b) = (&letters[i], &letters[j]);
+            let (ha, hb) = (f64::from(a.rect.height), f64::from(b.rect.height));
+            let taller = ha.max(hb);
+            let similar = taller <= 2.0 * ha.min(hb)
+                && a.stroke_width.max(b.stroke_width) <= 2.0 * a.stroke_width.min(b.stroke_width);
+            let centre = |r: &Rect| f64::from(r.y) + f64::from(r.height) / 2.0;
+            let aligned = (centre(&a.rect) - centre(&b.rect)).abs() <= taller / 2.0;
+            let gap = f64::from((a.rect.x.max(b.rect.x) - (a.rect.x + a.rect.width).min(b.rect.x + b.rect.width)).max(0));
+            if similar && aligned && gap <= params.max_letter_gap * taller {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+    }
+
+    let mut groups: std::collections::BTreeMap<usize, (Rect, usize)> = std::collections::BTreeMap::new();
+    for (i, letter) in letters.iter().enumerate() {
+        let r = root(&mut parent, i);
+        groups
+            .entry(r)
+            .and_modify(|(rect, count)| {
+                let x = rect.x.min(letter.rect.x);
+                let y = rect.y.min(letter.rect.y);
+                let right = (rect.x + rect.width).max(letter.rect.x + letter.rect.width);
+                let bottom = (rect.y + rect.height).max(letter.rect.y + letter.rect.height);
+                *rect = Rect::new(x, y, right - x, bottom - y);
+                *count += 1;
+            })
+            .or_insert((letter.rect, 1));
+    }
+    groups
+        .into_values()
+        .filter(|&(_, count)| count >= params.min_word_letters.max(1))
+        .map(|(rect, _)| rect)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::Scalar;
+    use crate::imgproc::rectangle;
+
+    /// Draw block letters from `stroke`-wide bars; each glyph is a list of
+    /// bars in a 3x5 grid of `stroke` cells
+    fn draw_word(img: &mut Mat, x: i32, y: i32, glyphs: &[&[(i32, i32, i32, i32)]], stroke: i32) -> i32 {
+        let mut cursor = x;
+        for glyph in glyphs {
+            for &(gx, gy, gw, gh) in *glyph {
+                let bar = Rect::new(cursor + gx * stroke, y + gy * stroke, gw * stroke, gh * stroke);
+                rectangle(img, bar, Scalar::all(0.0), -1).unwrap();
+            }
+            cursor += 4 * stroke;
+        }
+        cursor
+    }
+
+    const L: &[(i32, i32, i32, i32)] = &[(0, 0, 1, 5), (1, 4, 2, 1)];
+    const T: &[(i32, i32, i32, i32)] = &[(0, 0, 3, 1), (1, 1, 1, 4)];
+    const H: &[(i32, i32, i32, i32)] = &[(0, 0, 1, 5), (2, 0, 1, 5), (1, 2, 1, 1)];
+
+    fn page() -> Mat {
+        let mut img = Mat::new(140, 260, 1, MatDepth::U8).unwrap();
+        img.data_mut().fill(230);
+        let end = draw_word(&mut img, 20, 20, &[H, L, T], 4);
+        draw_word(&mut img, end + 24, 20, &[T, H], 4);
+        // A solid block wider than `max_stroke_width` in both directions
+        rectangle(&mut img, Rect::new(150, 60, 90, 70), Scalar::all(0.0), -1).unwrap();
+        img
+    }
+
+    #[test]
+    fn test_stroke_width_transform() {
+        let widths = stroke_width_transform(&page(), &SwtParams::default()).unwrap();
+        assert_eq!(widths.depth(), MatDepth::F32);
+        // Inside the first letter's vertical bar
+        let w = widths.at_f32(30, 21, 0).unwrap();
+        assert!((3.0..=6.0).contains(&w), "{w}");
+        // Background and block interior have no stroke
+        assert_eq!(widths.at_f32(100, 10, 0).unwrap(), 0.0);
+        assert_eq!(widths.at_f32(85, 195, 0).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_detect_text_swt() {
+        let detection = detect_text_swt(&page(), &SwtParams::default()).unwrap();
+        assert_eq!(detection.letters.len(), 5, "{:?}", detection.letters);
+        assert!(detection.letters.iter().all(|l| (l.rect.height - 20).abs() <= 2));
+        assert_eq!(detection.words.len(), 2, "{:?}", detection.words);
+        assert!(detection.words[0].x <= 21 && detection.words[0].width >= 40);
+
+        // Wrong polarity finds nothing
+        let light = detect_text_swt(&page(), &SwtParams::default().with_dark_on_light(false)).unwrap();
+        assert!(light.words.is_empty());
+    }
+}
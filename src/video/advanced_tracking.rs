@@ -1,6 +1,8 @@
 use crate::core::{Mat, MatDepth};
 use crate::core::types::{Rect, Point};
 use crate::error::{Error, Result};
+use crate::core::parallel::parallel_map;
+use crate::imgproc::build_gaussian_pyramid;
 
 /// MOSSE (Minimum Output Sum of Squared Error) tracker
 pub struct MOSSETracker {
@@ -313,6 +315,203 @@ impl CSRTTracker {
     }
 }
 
+/// Pyramidal normalized cross-correlation tracker for rigid targets
+///
+/// The template captured by [`init`](Self::init) is located again in every
+/// frame: a full search within `search_radius` pixels at the coarsest pyramid
+/// level, then a ±2 pixel refinement at each finer level. The template is
+/// never updated, so the tracker does not drift, but it also does not follow
+/// scale or rotation changes; it suits fiducials, logos and UI elements.
+/// Frames must be single-channel U8 or F32.
+pub struct TrackerNCC {
+    levels: usize,
+    search_radius: usize,
+    templates: Vec<NccTemplate>,
+    bbox: Rect,
+    score: f32,
+}
+
+/// One pyramid level of the template, zero-mean with its L2 norm
+struct NccTemplate {
+    data: Vec<f32>,
+    rows: usize,
+    cols: usize,
+    norm: f32,
+}
+
+impl Default for TrackerNCC {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrackerNCC {
+    /// Create an NCC tracker with 3 pyramid levels and a 32 pixel search radius
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            levels: 3,
+            search_radius: 32,
+            templates: Vec::new(),
+            bbox: Rect::new(0, 0, 0, 0),
+            score: 0.0,
+        }
+    }
+
+    /// Maximum number of pyramid levels (at least 1)
+    ///
+    /// Fewer levels are used when the template would shrink below 4 pixels.
+    #[must_use]
+    pub fn with_levels(mut self, levels: usize) -> Self {
+        self.levels = levels.max(1);
+        self
+    }
+
+    /// Largest displacement between frames, in full-resolution pixels
+    #[must_use]
+    pub fn with_search_radius(mut self, radius: usize) -> Self {
+        self.search_radius = radius;
+        self
+    }
+
+    /// Capture the template inside `bbox`, which must lie within `frame`
+    pub fn init(&mut self, frame: &Mat, bbox: Rect) -> Result<()> {
+        check_ncc_frame(frame)?;
+
+        let inside = bbox.x >= 0
+            && bbox.y >= 0
+            && bbox.width > 0
+            && bbox.height > 0
+            && usize::try_from(bbox.x + bbox.width).is_ok_and(|right| right <= frame.cols())
+            && usize::try_from(bbox.y + bbox.height).is_ok_and(|bottom| bottom <= frame.rows());
+        if !inside {
+            return Err(Error::InvalidParameter(
+                "Bounding box must be non-empty and inside the frame".to_string(),
+            ));
+        }
+
+        let patch = extract_patch(frame, bbox)?;
+        self.templates = build_gaussian_pyramid(&patch, self.levels)?
+            .iter()
+            .map(NccTemplate::from_mat)
+            .collect::<Result<_>>()?;
+        self.bbox = bbox;
+        self.score = 1.0;
+        Ok(())
+    }
+
+    /// Locate the template in `frame` and return its new bounding box
+    pub fn update(&mut self, frame: &Mat) -> Result<Rect> {
+        if self.templates.is_empty() {
+            return Err(Error::InvalidParameter("Tracker not initialized".to_string()));
+        }
+        check_ncc_frame(frame)?;
+
+        let pyramid = build_gaussian_pyramid(frame, self.templates.len())?;
+        let top = pyramid.len().min(self.templates.len()) - 1;
+
+        let (mut x, mut y) = (i64::from(self.bbox.x) >> top, i64::from(self.bbox.y) >> top);
+        let mut radius = i64::try_from((self.search_radius >> top).max(1)).unwrap_or(i64::MAX);
+        let mut score = 0.0;
+
+        for level in (0..=top).rev() {
+            if level < top {
+                (x, y, radius) = (x * 2, y * 2, 2);
+            }
+            (x, y, score) = ncc_search(&pyramid[level], &self.templates[level], x, y, radius)?;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            self.bbox.x = x as i32;
+            self.bbox.y = y as i32;
+        }
+        self.score = score;
+        Ok(self.bbox)
+    }
+
+    /// Normalized cross-correlation of the last match, in [-1, 1]
+    ///
+    /// Low values mean the target was probably lost or occluded.
+    #[must_use]
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+}
+
+impl NccTemplate {
+    fn from_mat(mat: &Mat) -> Result<Self> {
+        let mut data = Vec::with_capacity(mat.rows() * mat.cols());
+        for row in 0..mat.rows() {
+            for col in 0..mat.cols() {
+                data.push(mat.at_f32(row, col, 0)?);
+            }
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let mean = data.iter().sum::<f32>() / data.len() as f32;
+        for v in &mut data {
+            *v -= mean;
+        }
+        let norm = data.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+        Ok(Self { data, rows: mat.rows(), cols: mat.cols(), norm })
+    }
+}
+
+fn check_ncc_frame(frame: &Mat) -> Result<()> {
+    if frame.channels() != 1 || !matches!(frame.depth(), MatDepth::U8 | MatDepth::F32) {
+        return Err(Error::InvalidParameter(
+            "TrackerNCC requires a single-channel U8 or F32 image".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Best NCC position within `radius` of (`x`, `y`), as (x, y, score)
+///
+/// Ties keep the first candidate in raster order.
+fn ncc_search(frame: &Mat, template: &NccTemplate, x: i64, y: i64, radius: i64) -> Result<(i64, i64, f32)> {
+    let max_x = i64::try_from(frame.cols()).unwrap_or(0) - i64::try_from(template.cols).unwrap_or(0);
+    let max_y = i64::try_from(frame.rows()).unwrap_or(0) - i64::try_from(template.rows).unwrap_or(0);
+    if max_x < 0 || max_y < 0 {
+        return Ok((x, y, 0.0));
+    }
+
+    let (x0, x1) = ((x - radius).clamp(0, max_x), (x + radius).clamp(0, max_x));
+    let (y0, y1) = ((y - radius).clamp(0, max_y), (y + radius).clamp(0, max_y));
+    let candidate_rows = usize::try_from(y1 - y0 + 1).unwrap_or(0);
+
+    #[allow(clippy::cast_precision_loss)]
+    let n = template.data.len() as f32;
+    let rows = parallel_map(candidate_rows, |i| {
+        let cy = y0 + i64::try_from(i).unwrap_or(0);
+        let mut best = (x0, cy, f32::NEG_INFINITY);
+        for cx in x0..=x1 {
+            let (ox, oy) = (usize::try_from(cx).unwrap_or(0), usize::try_from(cy).unwrap_or(0));
+            let (mut sum, mut sum_sq, mut cross) = (0.0f32, 0.0f32, 0.0f32);
+            for ty in 0..template.rows {
+                for tx in 0..template.cols {
+                    let f = frame.at_f32(oy + ty, ox + tx, 0)?;
+                    sum += f;
+                    sum_sq += f * f;
+                    cross += f * template.data[ty * template.cols + tx];
+                }
+            }
+            let denom = (sum_sq - sum * sum / n).max(0.0).sqrt() * template.norm;
+            let score = if denom > f32::EPSILON { cross / denom } else { 0.0 };
+            if score > best.2 {
+                best = (cx, cy, score);
+            }
+        }
+        Ok(best)
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .fold((x, y, f32::NEG_INFINITY), |best, row| if row.2 > best.2 { row } else { best }))
+}
+
 // Helper functions
 
 fn extract_patch(frame: &Mat, bbox: Rect) -> Result<Mat> {
@@ -507,6 +706,37 @@ mod tests {
         assert_eq!(new_bbox.height, 20);
     }
 
+    /// Textured frame whose content is shifted by (`dx`, `dy`)
+    fn textured_frame(dx: i32, dy: i32) -> Mat {
+        let mut frame = Mat::new(120, 160, 1, MatDepth::U8).unwrap();
+        for row in 0..120 {
+            for col in 0..160 {
+                let (x, y) = (col as i32 - dx, row as i32 - dy);
+                let v = ((x * 7 + y * 13) ^ (x * y)).rem_euclid(251);
+                frame.at_mut(row, col).unwrap()[0] = u8::try_from(v).unwrap();
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn test_tracker_ncc() {
+        let bbox = Rect::new(60, 40, 24, 20);
+        let mut tracker = TrackerNCC::new().with_levels(3).with_search_radius(32);
+        tracker.init(&textured_frame(0, 0), bbox).unwrap();
+
+        let moved = tracker.update(&textured_frame(17, -9)).unwrap();
+        assert_eq!((moved.x, moved.y), (77, 31));
+        assert_eq!((moved.width, moved.height), (24, 20));
+        assert!(tracker.score() > 0.99);
+
+        let back = tracker.update(&textured_frame(-5, 4)).unwrap();
+        assert_eq!((back.x, back.y), (55, 44));
+
+        assert!(TrackerNCC::new().update(&textured_frame(0, 0)).is_err());
+        assert!(tracker.init(&textured_frame(0, 0), Rect::new(150, 40, 24, 20)).is_err());
+    }
+
     #[test]
     fn test_csrt_tracker() {
         let frame = Mat::new_with_default(100, 100, 3, MatDepth::U8, Scalar::all(128.0)).unwrap();
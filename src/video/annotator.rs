@@ -0,0 +1,336 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+//! Burn-in annotations for recorded or streamed video
+//!
+//! [`Annotator`] collects a frame's overlays (tracker boxes, labels, free
+//! text) between processing and encoding, and renders them together with
+//! an on-screen display of the timestamp and measured frame rate. Shapes
+//! are drawn into one reused [`Overlay`] layer and blended over the frame
+//! in a single pass, so the cost per frame is independent of how many
+//! annotations there are beyond the pixels they cover.
+//!
+//! ```rust,no_run
+//! # use std::time::Duration;
+//! # use opencv_rust::core::{Mat, MatDepth};
+//! # use opencv_rust::core::types::Rect;
+//! # use opencv_rust::video::{Annotator, OsdCorner};
+//! # use opencv_rust::videoio::{FourCC, VideoWriter};
+//! # fn main() -> opencv_rust::error::Result<()> {
+//! let mut writer = VideoWriter::new("out.avi", FourCC::from_str("MJPG")?, 30.0, 640, 480, true)?;
+//! let mut annotator = Annotator::new().with_timestamp(OsdCorner::TopLeft).with_fps(OsdCorner::TopRight);
+//! let mut frame = Mat::new(480, 640, 3, MatDepth::U8)?;
+//! for i in 0..30u64 {
+//!     annotator.add_track(7, Rect::new(100, 100, 60, 40));
+//!     annotator.render(&mut frame, Duration::from_millis(i * 33))?;
+//!     writer.write(&frame)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::core::types::{Point, Rect, Scalar};
+use crate::core::Mat;
+use crate::error::Result;
+use crate::imgproc::Overlay;
+
+/// Width and height of one character of `put_text` at scale 1
+const GLYPH_WIDTH: f64 = 8.0;
+const GLYPH_HEIGHT: f64 = 12.0;
+
+/// Padding around label and OSD text, in pixels
+const TEXT_PADDING: i32 = 2;
+
+/// Frame timestamps kept for the frame rate estimate
+const FPS_WINDOW: usize = 30;
+
+/// Distinct colours assigned to track ids
+const TRACK_PALETTE: [(u8, u8, u8); 8] = [
+    (230, 25, 75),
+    (60, 180, 75),
+    (255, 225, 25),
+    (0, 130, 200),
+    (245, 130, 48),
+    (145, 30, 180),
+    (70, 240, 240),
+    (240, 50, 230),
+];
+
+/// Frame corner used for on-screen display text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// One queued annotation
+#[derive(Debug, Clone, PartialEq)]
+enum Annotation {
+    Box { rect: Rect, label: Option<String>, color: Scalar },
+    Text { text: String, org: Point, color: Scalar },
+}
+
+/// Per-frame overlay composer for the frames handed to a `VideoWriter`
+///
+/// Queue the frame's annotations with the `add_*` methods, then call
+/// [`Annotator::render`] with the frame and its timestamp; the queue is
+/// emptied by every render. Timestamps come from the caller, so recorded
+/// footage shows its own time and frame rate, not the processing speed.
+pub struct Annotator {
+    font_scale: f64,
+    thickness: i32,
+    alpha: f64,
+    text_color: Scalar,
+    timestamp_corner: Option<OsdCorner>,
+    fps_corner: Option<OsdCorner>,
+    annotations: Vec<Annotation>,
+    recent: VecDeque<Duration>,
+    overlay: Option<Overlay>,
+}
+
+impl Default for Annotator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Annotator {
+    /// Annotator with scale 1 text, 2 pixel boxes, an opaque layer and no OSD
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            font_scale: 1.0,
+            thickness: 2,
+            alpha: 1.0,
+            text_color: Scalar::from_rgb(255, 255, 255),
+            timestamp_corner: None,
+            fps_corner: None,
+            annotations: Vec::new(),
+            recent: VecDeque::with_capacity(FPS_WINDOW),
+            overlay: None,
+        }
+    }
+
+    /// Scale of label and OSD text
+    #[must_use]
+    pub fn with_font_scale(mut self, font_scale: f64) -> Self {
+        self.font_scale = font_scale.max(0.25);
+        self
+    }
+
+    /// Line thickness of boxes, in pixels
+    #[must_use]
+    pub fn with_thickness(mut self, thickness: i32) -> Self {
+        self.thickness = thickness.max(1);
+        self
+    }
+
+    /// Opacity of all annotations over the frame (0.0 - 1.0)
+    #[must_use]
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Colour of label and OSD text
+    #[must_use]
+    pub fn with_text_color(mut self, color: Scalar) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Show the frame timestamp as `HH:MM:SS.mmm` in `corner`
+    #[must_use]
+    pub fn with_timestamp(mut self, corner: OsdCorner) -> Self {
+        self.timestamp_corner = Some(corner);
+        self
+    }
+
+    /// Show the measured frame rate in `corner`
+    #[must_use]
+    pub fn with_fps(mut self, corner: OsdCorner) -> Self {
+        self.fps_corner = Some(corner);
+        self
+    }
+
+    /// Queue a box, with an optional label above it
+    pub fn add_box(&mut self, rect: Rect, label: Option<&str>, color: Scalar) {
+        self.annotations.push(Annotation::Box { rect, label: label.map(str::to_string), color });
+    }
+
+    /// Queue a tracker box labelled `#id`, coloured consistently per id
+    pub fn add_track(&mut self, id: u64, rect: Rect) {
+        let (r, g, b) = TRACK_PALETTE[(id % TRACK_PALETTE.len() as u64) as usize];
+        self.add_box(rect, Some(&format!("#{id}")), Scalar::from_rgb(r, g, b));
+    }
+
+    /// Queue free text with its top-left corner at `org`
+    pub fn add_text(&mut self, text: &str, org: Point, color: Scalar) {
+        self.annotations.push(Annotation::Text { text: text.to_string(), org, color });
+    }
+
+    /// Number of annotations queued for the next render
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// Frame rate over the last frames rendered, once two have been seen
+    #[must_use]
+    pub fn fps(&self) -> Option<f64> {
+        let (first, last) = (self.recent.front()?, self.recent.back()?);
+        let span = last.saturating_sub(*first).as_secs_f64();
+        (span > 0.0).then(|| (self.recent.len() - 1) as f64 / span)
+    }
+
+    /// Burn the queued annotations and the OSD into `frame`
+    ///
+    /// `frame` must be U8 with 1, 3 or 4 channels; gray frames receive the
+    /// luminance of the annotation colours.
+    pub fn render(&mut self, frame: &mut Mat, timestamp: Duration) -> Result<()> {
+        if self.recent.back().is_some_and(|&last| timestamp <= last) {
+            // Time went backwards, e.g. after a seek: restart the estimate
+            self.recent.clear();
+        }
+        if self.recent.len() == FPS_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(timestamp);
+
+        let mut overlay = match self.overlay.take() {
+            Some(mut overlay) if overlay.layer().rows() == frame.rows() && overlay.layer().cols() == frame.cols() => {
+                overlay.clear();
+                overlay
+            }
+            _ => Overlay::for_image(frame)?,
+        };
+        overlay.set_alpha(self.alpha);
+
+        let result = self.draw(&mut overlay, frame.rows(), frame.cols(), timestamp);
+        let result = result.and_then(|()| overlay.composite_in_place(frame));
+        self.overlay = Some(overlay);
+        self.annotations.clear();
+        result
+    }
+
+    fn draw(&self, overlay: &mut Overlay, rows: usize, cols: usize, timestamp: Duration) -> Result<()> {
+        for annotation in &self.annotations {
+            match annotation {
+                Annotation::Box { rect, label, color } => {
+                    overlay.rectangle(*rect, *color, self.thickness)?;
+                    if let Some(label) = label {
+                        // Label chip above the box, or inside it at the top edge
+                        let (_, h) = self.text_size(label);
+                        let y = if rect.y >= h { rect.y - h } else { rect.y };
+                        self.draw_chip(overlay, label, Point::new(rect.x, y), *color)?;
+                    }
+                }
+                Annotation::Text { text, org, color } => {
+                    overlay.put_text(text, *org, self.font_scale, *color)?;
+                }
+            }
+        }
+
+        let mut osd = Vec::new();
+        if let Some(corner) = self.timestamp_corner {
+            osd.push((corner, format_timestamp(timestamp)));
+        }
+        if let Some(corner) = self.fps_corner {
+            let text = self.fps().map_or_else(|| "-- fps".to_string(), |fps| format!("{fps:.1} fps"));
+            osd.push((corner, text));
+        }
+        // Entries sharing a corner stack away from the frame edge
+        for (i, (corner, text)) in osd.iter().enumerate() {
+            let stacked = osd[..i].iter().filter(|(c, _)| c == corner).count() as i32;
+            let (w, h) = self.text_size(text);
+            let x = match corner {
+                OsdCorner::TopLeft | OsdCorner::BottomLeft => 0,
+                OsdCorner::TopRight | OsdCorner::BottomRight => cols as i32 - w,
+            };
+            let y = match corner {
+                OsdCorner::TopLeft | OsdCorner::TopRight => stacked * h,
+                OsdCorner::BottomLeft | OsdCorner::BottomRight => rows as i32 - (stacked + 1) * h,
+            };
+            self.draw_chip(overlay, text, Point::new(x, y), Scalar::from_rgb(0, 0, 0))?;
+        }
+        Ok(())
+    }
+
+    /// Text on a filled background, with `org` the chip's top-left corner
+    fn draw_chip(&self, overlay: &mut Overlay, text: &str, org: Point, background: Scalar) -> Result<()> {
+        let (w, h) = self.text_size(text);
+        overlay.rectangle(Rect::new(org.x, org.y, w, h), background, -1)?;
+        let text_org = Point::new(org.x + TEXT_PADDING, org.y + TEXT_PADDING);
+        overlay.put_text(text, text_org, self.font_scale, self.text_color)
+    }
+
+    /// Size of a padded text chip
+    fn text_size(&self, text: &str) -> (i32, i32) {
+        let chars = text.chars().count() as f64;
+        let w = (GLYPH_WIDTH * self.font_scale * chars) as i32 + 2 * TEXT_PADDING;
+        let h = (GLYPH_HEIGHT * self.font_scale) as i32 + 2 * TEXT_PADDING;
+        (w, h)
+    }
+}
+
+fn format_timestamp(timestamp: Duration) -> String {
+    let secs = timestamp.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        timestamp.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+
+    #[test]
+    fn test_annotator_renders_and_clears() {
+        let mut annotator = Annotator::new()
+            .with_timestamp(OsdCorner::TopLeft)
+            .with_fps(OsdCorner::TopLeft);
+        let mut frame = Mat::new_with_default(120, 160, 3, MatDepth::U8, Scalar::all(50.0)).unwrap();
+
+        annotator.add_box(Rect::new(60, 60, 40, 30), Some("car"), Scalar::from_rgb(0, 255, 0));
+        assert_eq!(annotator.pending(), 1);
+        annotator.render(&mut frame, Duration::from_millis(3_723_004)).unwrap();
+        assert_eq!(annotator.pending(), 0);
+
+        // Box edge, label chip above it, stacked OSD chips in the corner
+        assert_eq!(frame.at(75, 60).unwrap(), &[0, 255, 0]);
+        assert_eq!(frame.at(58, 85).unwrap(), &[0, 255, 0]);
+        assert_eq!(frame.at(0, 0).unwrap(), &[0, 0, 0]);
+        assert_eq!(frame.at(17, 0).unwrap(), &[0, 0, 0]);
+        assert_eq!(frame.at(110, 10).unwrap(), &[50, 50, 50]);
+
+        // Nothing queued: only the OSD is redrawn
+        let mut clean = Mat::new_with_default(120, 160, 3, MatDepth::U8, Scalar::all(50.0)).unwrap();
+        annotator.render(&mut clean, Duration::from_millis(3_723_104)).unwrap();
+        assert_eq!(clean.at(75, 60).unwrap(), &[50, 50, 50]);
+        assert!((annotator.fps().unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_annotator_fps_window() {
+        let mut annotator = Annotator::new();
+        let mut frame = Mat::new(20, 20, 1, MatDepth::U8).unwrap();
+        assert!(annotator.fps().is_none());
+        for i in 0..100u64 {
+            annotator.render(&mut frame, Duration::from_millis(i * 40)).unwrap();
+        }
+        assert!((annotator.fps().unwrap() - 25.0).abs() < 1e-9);
+
+        // A seek backwards restarts the estimate
+        annotator.render(&mut frame, Duration::ZERO).unwrap();
+        assert!(annotator.fps().is_none());
+        assert_eq!(format_timestamp(Duration::from_millis(3_723_004)), "01:02:03.004");
+    }
+}
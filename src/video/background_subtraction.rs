@@ -1,5 +1,6 @@
 use crate::core::{Mat, MatDepth};
 use crate::error::{Error, Result};
+use super::motion_compensation::{remap_model, source_pixels, MotionCompensator};
 
 /// Mixture of Gaussians (MOG2) background subtractor
 pub struct BackgroundSubtractorMOG2 {
@@ -18,6 +19,7 @@ pub struct BackgroundSubtractorMOG2 {
     variance: Vec<Vec<Vec<f32>>>,
     weight: Vec<Vec<Vec<f32>>>,
     frame_count: usize,
+    motion: Option<MotionCompensator>,
 }
 
 impl Default for BackgroundSubtractorMOG2 {
@@ -50,9 +52,49 @@ impl BackgroundSubtractorMOG2 {
             variance: Vec::new(),
             weight: Vec::new(),
             frame_count: 0,
+            motion: None,
         }
     }
 
+    /// Compensate global camera motion before each model update
+    ///
+    /// The model is re-aligned with every new frame using the motion
+    /// estimated by `compensator`; pixels that come into view are seeded from
+    /// the frame itself and so start out as background.
+    #[must_use]
+    pub fn with_motion_compensation(mut self, compensator: MotionCompensator) -> Self {
+        self.motion = Some(compensator);
+        self
+    }
+
+    fn compensate_motion(&mut self, image: &Mat) -> Result<()> {
+        let Some(transform) = self.motion.as_mut().map(|m| m.estimate(image)).transpose()?.flatten() else {
+            return Ok(());
+        };
+        if self.mean.len() != image.rows() || self.mean.first().map_or(0, Vec::len) != image.cols() {
+            return Ok(());
+        }
+
+        let intensities = frame_intensities(image)?;
+        let cols = image.cols();
+        let sources = source_pixels(image.rows(), cols, &transform);
+        let n = self.num_gaussians;
+        remap_model(&mut self.mean, &sources, |row, col| {
+            let mut mean = vec![0.0; n];
+            mean[0] = intensities[row * cols + col];
+            mean
+        });
+        #[allow(clippy::cast_possible_truncation)]
+        let var_init = self.var_init as f32;
+        remap_model(&mut self.variance, &sources, |_, _| vec![var_init; n]);
+        remap_model(&mut self.weight, &sources, |_, _| {
+            let mut weight = vec![0.0; n];
+            weight[0] = 1.0;
+            weight
+        });
+        Ok(())
+    }
+
     /// Apply background subtraction
     pub fn apply(&mut self, image: &Mat, fgmask: &mut Mat, learning_rate: f64) -> Result<()> {
         if image.channels() != 3 {
@@ -61,6 +103,8 @@ impl BackgroundSubtractorMOG2 {
             ));
         }
 
+        self.compensate_motion(image)?;
+
         let rows = image.rows();
         let cols = image.cols();
 
@@ -245,6 +289,7 @@ pub struct BackgroundSubtractorKNN {
     samples: Vec<Vec<Vec<f32>>>,  // [row][col][sample_idx]
     sample_idx: Vec<Vec<usize>>,  // Current write position
     frame_count: usize,
+    motion: Option<MotionCompensator>,
 }
 
 impl Default for BackgroundSubtractorKNN {
@@ -271,9 +316,39 @@ impl BackgroundSubtractorKNN {
             samples: Vec::new(),
             sample_idx: Vec::new(),
             frame_count: 0,
+            motion: None,
         }
     }
 
+    /// Compensate global camera motion before each model update
+    ///
+    /// The stored samples are re-aligned with every new frame using the
+    /// motion estimated by `compensator`; pixels that come into view are
+    /// seeded from the frame itself and so start out as background.
+    #[must_use]
+    pub fn with_motion_compensation(mut self, compensator: MotionCompensator) -> Self {
+        self.motion = Some(compensator);
+        self
+    }
+
+    fn compensate_motion(&mut self, image: &Mat) -> Result<()> {
+        let Some(transform) = self.motion.as_mut().map(|m| m.estimate(image)).transpose()?.flatten() else {
+            return Ok(());
+        };
+        if self.samples.len() != image.rows() || self.samples.first().map_or(0, Vec::len) != image.cols() {
+            return Ok(());
+        }
+
+        let intensities = frame_intensities(image)?;
+        let cols = image.cols();
+        let sources = source_pixels(image.rows(), cols, &transform);
+        let max_samples = self.samples[0][0].len();
+        let seeded = self.k_nn_samples.min(max_samples);
+        remap_model(&mut self.samples, &sources, |row, col| vec![intensities[row * cols + col]; max_samples]);
+        remap_model(&mut self.sample_idx, &sources, |_, _| seeded);
+        Ok(())
+    }
+
     /// Apply background subtraction
     pub fn apply(&mut self, image: &Mat, fgmask: &mut Mat, learning_rate: f64) -> Result<()> {
         if image.channels() != 3 {
@@ -282,6 +357,8 @@ impl BackgroundSubtractorKNN {
             ));
         }
 
+        self.compensate_motion(image)?;
+
         let rows = image.rows();
         let cols = image.cols();
 
@@ -387,6 +464,18 @@ impl BackgroundSubtractorKNN {
     }
 }
 
+/// Mean of the three channels per pixel, as the subtractors model it
+fn frame_intensities(image: &Mat) -> Result<Vec<f32>> {
+    let mut intensities = Vec::with_capacity(image.rows() * image.cols());
+    for row in 0..image.rows() {
+        for col in 0..image.cols() {
+            let pixel = image.at(row, col)?;
+            intensities.push((f32::from(pixel[0]) + f32::from(pixel[1]) + f32::from(pixel[2])) / 3.0);
+        }
+    }
+    Ok(intensities)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -457,4 +546,55 @@ mod tests {
         assert_eq!(background.rows(), 50);
         assert_eq!(background.cols(), 50);
     }
+
+    /// RGB frames of a blocky texture panning 2 pixels left per frame
+    fn panning_frames(count: usize) -> Vec<Mat> {
+        use crate::imgproc::noise::SplitMix64;
+
+        let mut rng = SplitMix64::new(8);
+        let blocks: Vec<u8> = (0..32 * 32).map(|_| (rng.next_u64() % 200 + 30) as u8).collect();
+        (0..count)
+            .map(|i| {
+                let mut frame = Mat::new(72, 96, 3, MatDepth::U8).unwrap();
+                for row in 0..72 {
+                    for col in 0..96 {
+                        let v = blocks[(row / 6) * 32 + (col + 2 * i) / 6];
+                        frame.at_mut(row, col).unwrap().copy_from_slice(&[v, v, v]);
+                    }
+                }
+                frame
+            })
+            .collect()
+    }
+
+    fn foreground_fraction(mask: &Mat) -> f64 {
+        mask.data().iter().filter(|&&v| v != 0).count() as f64 / mask.data().len() as f64
+    }
+
+    #[test]
+    fn test_subtractors_with_motion_compensation() {
+        let frames = panning_frames(8);
+        let mut fgmask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+        let mut fixed = BackgroundSubtractorMOG2::new();
+        let mut compensated = BackgroundSubtractorMOG2::new().with_motion_compensation(MotionCompensator::new());
+        let (mut fixed_fg, mut compensated_fg) = (0.0, 0.0);
+        for frame in &frames {
+            fixed.apply(frame, &mut fgmask, -1.0).unwrap();
+            fixed_fg = foreground_fraction(&fgmask);
+            compensated.apply(frame, &mut fgmask, -1.0).unwrap();
+            compensated_fg = foreground_fraction(&fgmask);
+        }
+        assert!(compensated_fg < 0.05 && fixed_fg > 0.2, "MOG2: {compensated_fg} vs {fixed_fg}");
+
+        let mut fixed = BackgroundSubtractorKNN::new();
+        let mut compensated = BackgroundSubtractorKNN::new().with_motion_compensation(MotionCompensator::new());
+        for frame in &frames {
+            fixed.apply(frame, &mut fgmask, -1.0).unwrap();
+            fixed_fg = foreground_fraction(&fgmask);
+            compensated.apply(frame, &mut fgmask, -1.0).unwrap();
+            compensated_fg = foreground_fraction(&fgmask);
+        }
+        assert!(compensated_fg < 0.05 && fixed_fg > 0.2, "KNN: {compensated_fg} vs {fixed_fg}");
+    }
 }
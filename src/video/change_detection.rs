@@ -0,0 +1,371 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+//! Event-driven change detection for surveillance
+//!
+//! [`ChangeDetector`] packages the usual recipe on top of a background
+//! subtractor's foreground mask: drop shadow pixels, open away speckle and
+//! close gaps, keep connected blobs above a minimum size, test them against
+//! alarm zones, and debounce each zone so that an alarm is raised only after
+//! change persists and cleared only after the zone has been quiet for a
+//! while. Timestamps come from the caller, so recorded footage can be
+//! processed faster than real time.
+
+use std::time::Duration;
+
+use crate::core::types::{Point, Point2f, Rect, Scalar, Size};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::{connected_components_with_stats, fill_poly, get_structuring_element, morphology_ex, MorphShape, MorphType};
+
+/// A named polygonal area watched for change
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlarmZone {
+    pub name: String,
+    polygon: Vec<Point2f>,
+}
+
+impl AlarmZone {
+    /// Create a zone from at least 3 vertices, in pixel coordinates
+    pub fn new(name: impl Into<String>, polygon: Vec<Point2f>) -> Result<Self> {
+        if polygon.len() < 3 {
+            return Err(Error::InvalidParameter(
+                "An alarm zone needs at least 3 vertices".to_string(),
+            ));
+        }
+        Ok(Self { name: name.into(), polygon })
+    }
+
+    #[must_use]
+    pub fn polygon(&self) -> &[Point2f] {
+        &self.polygon
+    }
+}
+
+/// What happened in a [`ChangeEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeEventKind {
+    /// Change persisted in the zone for the trigger delay
+    AlarmRaised,
+    /// The zone has been free of change for the clear delay
+    AlarmCleared,
+}
+
+/// An alarm transition raised by [`ChangeDetector::process`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub zone: String,
+    pub kind: ChangeEventKind,
+    /// Timestamp of the frame that completed the transition
+    pub timestamp: Duration,
+    /// Bounding boxes of the blobs in the zone at that frame
+    pub blobs: Vec<Rect>,
+    /// Changed pixels inside the zone at that frame
+    pub changed_area: usize,
+}
+
+type EventCallback = Box<dyn FnMut(&ChangeEvent)>;
+
+/// Debounce state of one zone
+#[derive(Debug, Clone, Copy, Default)]
+struct ZoneState {
+    active: bool,
+    /// Since when the zone has disagreed with `active`
+    pending_since: Option<Duration>,
+}
+
+/// Foreground mask to debounced per-zone alarm events
+///
+/// Feed the mask of every frame with its timestamp. Without zones the whole
+/// frame is watched as a zone named `"frame"`.
+pub struct ChangeDetector {
+    mask_threshold: u8,
+    open_size: i32,
+    close_size: i32,
+    min_blob_area: usize,
+    trigger_delay: Duration,
+    clear_delay: Duration,
+    zones: Vec<AlarmZone>,
+    callbacks: Vec<EventCallback>,
+    states: Vec<ZoneState>,
+    zone_masks: Option<(Size, Vec<Mat>)>,
+    cleaned: Option<Mat>,
+}
+
+impl Default for ChangeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChangeDetector {
+    /// Detector with defaults suited to MOG2/KNN masks: pixels below 200
+    /// (including shadows, marked 127) are ignored, a 3x3 opening and 7x7
+    /// closing clean the mask, blobs need 150 pixels in a zone, alarms are
+    /// raised after 0.5 s of change and cleared after 2 s without
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            mask_threshold: 200,
+            open_size: 3,
+            close_size: 7,
+            min_blob_area: 150,
+            trigger_delay: Duration::from_millis(500),
+            clear_delay: Duration::from_secs(2),
+            zones: Vec::new(),
+            callbacks: Vec::new(),
+            states: vec![ZoneState::default()],
+            zone_masks: None,
+            cleaned: None,
+        }
+    }
+
+    /// Mask values at or above `threshold` count as foreground
+    #[must_use]
+    pub fn with_mask_threshold(mut self, threshold: u8) -> Self {
+        self.mask_threshold = threshold;
+        self
+    }
+
+    /// Sizes of the opening and closing kernels; 0 or 1 disables a step
+    #[must_use]
+    pub fn with_morphology(mut self, open_size: i32, close_size: i32) -> Self {
+        self.open_size = open_size;
+        self.close_size = close_size;
+        self
+    }
+
+    /// Minimum pixels a blob must have inside a zone to count as change
+    #[must_use]
+    pub fn with_min_blob_area(mut self, pixels: usize) -> Self {
+        self.min_blob_area = pixels.max(1);
+        self
+    }
+
+    /// How long change must persist before an alarm is raised, and how long
+    /// a zone must stay quiet before it is cleared
+    #[must_use]
+    pub fn with_debounce(mut self, trigger_delay: Duration, clear_delay: Duration) -> Self {
+        self.trigger_delay = trigger_delay;
+        self.clear_delay = clear_delay;
+        self
+    }
+
+    #[must_use]
+    pub fn with_zone(mut self, zone: AlarmZone) -> Self {
+        self.zones.push(zone);
+        self.reset();
+        self
+    }
+
+    /// Call `callback` for every event
+    #[must_use]
+    pub fn on_event(mut self, callback: impl FnMut(&ChangeEvent) + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Whether the named zone is currently in alarm
+    #[must_use]
+    pub fn is_active(&self, zone: &str) -> bool {
+        self.zone_names().position(|name| name == zone).is_some_and(|i| self.states[i].active)
+    }
+
+    /// The cleaned binary mask of the last processed frame
+    #[must_use]
+    pub fn cleaned_mask(&self) -> Option<&Mat> {
+        self.cleaned.as_ref()
+    }
+
+    /// Clear all alarms and pending transitions
+    pub fn reset(&mut self) {
+        self.states = vec![ZoneState::default(); self.zones.len().max(1)];
+        self.cleaned = None;
+    }
+
+    /// Process the foreground mask of the frame taken at `timestamp`
+    ///
+    /// `foreground` is a 1-channel U8 mask such as the output of
+    /// [`BackgroundSubtractorMOG2::apply`](crate::video::BackgroundSubtractorMOG2::apply).
+    /// Returns the alarm transitions this frame completed.
+    pub fn process(&mut self, foreground: &Mat, timestamp: Duration) -> Result<Vec<ChangeEvent>> {
+        if foreground.channels() != 1 || foreground.depth() != MatDepth::U8 {
+            return Err(Error::InvalidParameter(
+                "Foreground mask must be a 1-channel U8 image".to_string(),
+            ));
+        }
+
+        let cleaned = self.clean(foreground)?;
+        let mut labels = Mat::new(1, 1, 1, MatDepth::S32)?;
+        let stats = connected_components_with_stats(&cleaned, &mut labels, 8)?;
+        let labels: Vec<usize> = (0..labels.rows() * labels.cols())
+            .map(|i| labels.at_i32(i / labels.cols(), i % labels.cols(), 0).map(|l| l as usize))
+            .collect::<Result<_>>()?;
+
+        let size = Size::new(cleaned.cols() as i32, cleaned.rows() as i32);
+        let zone_masks = match self.zone_masks.take() {
+            Some((cached, masks)) if cached == size => masks,
+            _ => self.rasterize_zones(size)?,
+        };
+
+        let mut events = Vec::new();
+        let names: Vec<String> = self.zone_names().map(str::to_string).collect();
+        for (z, name) in names.into_iter().enumerate() {
+            // In-zone pixels of each blob
+            let mut in_zone = vec![0usize; stats.len()];
+            for (i, &label) in labels.iter().enumerate() {
+                if label != 0 && zone_masks.get(z).is_none_or(|m| m.data()[i] != 0) {
+                    in_zone[label] += 1;
+                }
+            }
+            let blobs: Vec<usize> = (1..stats.len()).filter(|&l| in_zone[l] >= self.min_blob_area).collect();
+            let changed = !blobs.is_empty();
+
+            if let Some(kind) = self.debounce(z, changed, timestamp) {
+                events.push(ChangeEvent {
+                    zone: name,
+                    kind,
+                    timestamp,
+                    blobs: blobs.iter().map(|&l| stats[l].bounding_box).collect(),
+                    changed_area: blobs.iter().map(|&l| in_zone[l]).sum(),
+                });
+            }
+        }
+
+        self.zone_masks = Some((size, zone_masks));
+        self.cleaned = Some(cleaned);
+        for event in &events {
+            for callback in &mut self.callbacks {
+                callback(event);
+            }
+        }
+        Ok(events)
+    }
+
+    fn zone_names(&self) -> impl Iterator<Item = &str> {
+        let fallback = self.zones.is_empty().then_some("frame");
+        self.zones.iter().map(|z| z.name.as_str()).chain(fallback)
+    }
+
+    /// Threshold, then open and close the mask
+    fn clean(&self, foreground: &Mat) -> Result<Mat> {
+        let mut mask = foreground.clone();
+        for v in mask.data_mut() {
+            *v = if *v >= self.mask_threshold { 255 } else { 0 };
+        }
+        for (op, size) in [(MorphType::Open, self.open_size), (MorphType::Close, self.close_size)] {
+            if size > 1 {
+                let kernel = get_structuring_element(MorphShape::Ellipse, Size::new(size, size));
+                let mut out = Mat::new(1, 1, 1, MatDepth::U8)?;
+                morphology_ex(&mask, &mut out, op, &kernel)?;
+                mask = out;
+            }
+        }
+        Ok(mask)
+    }
+
+    fn rasterize_zones(&self, size: Size) -> Result<Vec<Mat>> {
+        self.zones
+            .iter()
+            .map(|zone| {
+                let mut mask = Mat::new(size.height as usize, size.width as usize, 1, MatDepth::U8)?;
+                let points: Vec<Point> = zone.polygon.iter().map(|p| Point::new(p.x.round() as i32, p.y.round() as i32)).collect();
+                fill_poly(&mut mask, &points, Scalar::all(255.0))?;
+                Ok(mask)
+            })
+            .collect()
+    }
+
+    /// Advance a zone's state; returns the transition completed, if any
+    fn debounce(&mut self, zone: usize, changed: bool, timestamp: Duration) -> Option<ChangeEventKind> {
+        let state = &mut self.states[zone];
+        if changed == state.active {
+            state.pending_since = None;
+            return None;
+        }
+
+        let since = *state.pending_since.get_or_insert(timestamp);
+        let delay = if state.active { self.clear_delay } else { self.trigger_delay };
+        if timestamp.saturating_sub(since) < delay {
+            return None;
+        }
+
+        state.active = changed;
+        state.pending_since = None;
+        Some(if changed { ChangeEventKind::AlarmRaised } else { ChangeEventKind::AlarmCleared })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imgproc::rectangle;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 120x160 mask with an optional 30x30 object at `x` and speckle noise
+    fn mask(object_x: Option<i32>) -> Mat {
+        let mut mask = Mat::new(120, 160, 1, MatDepth::U8).unwrap();
+        for i in (0..mask.data().len()).step_by(97) {
+            mask.data_mut()[i] = 255;
+        }
+        // A shadow strip
+        rectangle(&mut mask, Rect::new(0, 100, 160, 10), Scalar::all(127.0), -1).unwrap();
+        if let Some(x) = object_x {
+            rectangle(&mut mask, Rect::new(x, 40, 30, 30), Scalar::all(255.0), -1).unwrap();
+        }
+        mask
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_change_detector_debounces() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&events);
+        let mut detector = ChangeDetector::new().on_event(move |e| sink.borrow_mut().push(e.kind));
+
+        // Noise, shadows and a one-frame blip raise nothing
+        assert!(detector.process(&mask(None), ms(0)).unwrap().is_empty());
+        assert!(detector.process(&mask(Some(50)), ms(100)).unwrap().is_empty());
+        assert!(detector.process(&mask(None), ms(200)).unwrap().is_empty());
+
+        // Persistent change raises once the trigger delay has passed
+        assert!(detector.process(&mask(Some(50)), ms(300)).unwrap().is_empty());
+        let raised = detector.process(&mask(Some(52)), ms(800)).unwrap();
+        assert_eq!(raised.len(), 1);
+        assert_eq!((raised[0].zone.as_str(), raised[0].kind), ("frame", ChangeEventKind::AlarmRaised));
+        assert_eq!(raised[0].blobs.len(), 1);
+        assert!(detector.is_active("frame"));
+        let cleaned = detector.cleaned_mask().unwrap();
+        assert_eq!(cleaned.at(105, 10).unwrap()[0], 0);
+
+        // Brief gaps don't clear; two quiet seconds do
+        assert!(detector.process(&mask(None), ms(900)).unwrap().is_empty());
+        assert!(detector.process(&mask(Some(54)), ms(1000)).unwrap().is_empty());
+        assert!(detector.process(&mask(None), ms(1100)).unwrap().is_empty());
+        let cleared = detector.process(&mask(None), ms(3200)).unwrap();
+        assert_eq!(cleared[0].kind, ChangeEventKind::AlarmCleared);
+        assert_eq!(*events.borrow(), vec![ChangeEventKind::AlarmRaised, ChangeEventKind::AlarmCleared]);
+    }
+
+    #[test]
+    fn test_change_detector_zones() {
+        let left = AlarmZone::new("left", vec![Point2f::new(0.0, 0.0), Point2f::new(79.0, 0.0), Point2f::new(79.0, 119.0), Point2f::new(0.0, 119.0)]).unwrap();
+        let right = AlarmZone::new("right", vec![Point2f::new(80.0, 0.0), Point2f::new(159.0, 0.0), Point2f::new(159.0, 119.0), Point2f::new(80.0, 119.0)]).unwrap();
+        let mut detector = ChangeDetector::new().with_zone(left).with_zone(right).with_debounce(Duration::ZERO, Duration::ZERO);
+
+        let events = detector.process(&mask(Some(100)), ms(0)).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].zone, "right");
+        assert!(events[0].changed_area >= 800);
+        assert!(detector.is_active("right") && !detector.is_active("left"));
+
+        // Straddling the border: too little of the blob is in the left zone
+        detector.process(&mask(Some(75)), ms(10)).unwrap();
+        assert!(!detector.is_active("left"));
+
+        assert!(AlarmZone::new("line", vec![Point2f::new(0.0, 0.0), Point2f::new(1.0, 1.0)]).is_err());
+        assert!(detector.process(&Mat::new(4, 4, 3, MatDepth::U8).unwrap(), ms(20)).is_err());
+    }
+}
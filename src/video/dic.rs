@@ -0,0 +1,515 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Digital image correlation
+//!
+//! [`DigitalImageCorrelation`] measures the displacement of a grid of
+//! square subsets between a reference and a deformed image, as used in
+//! experimental mechanics to map the surface deformation of a speckled
+//! specimen. Each subset is located by an integer zero-normalised
+//! cross-correlation (ZNCC) search, then refined to sub-pixel accuracy with
+//! the inverse-compositional Gauss-Newton algorithm on a first-order
+//! (affine) subset shape, sampling the deformed image through a
+//! [`PixelSampler`]. [`DisplacementField::strain`] turns the result into
+//! small-strain components.
+
+use crate::core::types::{BorderType, InterpolationFlag, Point2f, Rect};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::PixelSampler;
+
+/// Length of the parameter update below which a subset has converged
+const CONVERGENCE: f64 = 1e-3;
+
+/// One grid point of a [`DisplacementField`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DicPoint {
+    /// Subset centre in the reference image
+    pub position: Point2f,
+    /// Displacement `(u, v)` of the subset centre into the deformed image
+    pub displacement: Point2f,
+    /// Zero-normalised cross-correlation of the matched subsets, -1 to 1
+    pub zncc: f32,
+    /// Whether the subset converged with a correlation above the minimum
+    pub valid: bool,
+}
+
+/// Displacements on a regular grid of subset centres
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplacementField {
+    grid_rows: usize,
+    grid_cols: usize,
+    step: usize,
+    points: Vec<DicPoint>,
+}
+
+impl DisplacementField {
+    #[must_use]
+    pub fn grid_rows(&self) -> usize {
+        self.grid_rows
+    }
+
+    #[must_use]
+    pub fn grid_cols(&self) -> usize {
+        self.grid_cols
+    }
+
+    /// Spacing of the grid, in pixels
+    #[must_use]
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Grid points in row-major order
+    #[must_use]
+    pub fn points(&self) -> &[DicPoint] {
+        &self.points
+    }
+
+    #[must_use]
+    pub fn point(&self, row: usize, col: usize) -> &DicPoint {
+        &self.points[row * self.grid_cols + col]
+    }
+
+    /// Number of points that correlated successfully
+    #[must_use]
+    pub fn valid_count(&self) -> usize {
+        self.points.iter().filter(|p| p.valid).count()
+    }
+
+    /// The field as a 2-channel F32 Mat of `(u, v)` per grid point, NaN
+    /// where the point is invalid
+    pub fn to_mat(&self) -> Result<Mat> {
+        let mut mat = Mat::new(self.grid_rows, self.grid_cols, 2, MatDepth::F32)?;
+        for i in 0..self.points.len() {
+            let (u, v) = self.uv(i);
+            mat.set_f32(i / self.grid_cols, i % self.grid_cols, 0, u as f32)?;
+            mat.set_f32(i / self.grid_cols, i % self.grid_cols, 1, v as f32)?;
+        }
+        Ok(mat)
+    }
+
+    /// Small-strain components as a 3-channel F32 Mat of
+    /// `(exx, eyy, exy)` per grid point
+    ///
+    /// Displacement gradients are central differences over the grid
+    /// (one-sided on its edges); `exy` is the tensor shear
+    /// `(du/dy + dv/dx) / 2`. Points next to invalid ones, and every point
+    /// of a grid one row or column wide, get NaN where a gradient is missing.
+    pub fn strain(&self) -> Result<Mat> {
+        let mut strain = Mat::new(self.grid_rows, self.grid_cols, 3, MatDepth::F32)?;
+        let step = self.step as f64;
+        for r in 0..self.grid_rows {
+            for c in 0..self.grid_cols {
+                let (dux, dvx) = self.difference(r, c, true, step);
+                let (duy, dvy) = self.difference(r, c, false, step);
+                strain.set_f32(r, c, 0, dux as f32)?;
+                strain.set_f32(r, c, 1, dvy as f32)?;
+                strain.set_f32(r, c, 2, (0.5 * (duy + dvx)) as f32)?;
+            }
+        }
+        Ok(strain)
+    }
+
+    /// Derivative of `(u, v)` along the grid rows (x) or columns (y)
+    fn difference(&self, r: usize, c: usize, along_x: bool, step: f64) -> (f64, f64) {
+        let (len, pos) = if along_x { (self.grid_cols, c) } else { (self.grid_rows, r) };
+        if len < 2 {
+            return (f64::NAN, f64::NAN);
+        }
+        let lo = pos.saturating_sub(1);
+        let hi = (pos + 1).min(len - 1);
+        let at = |k: usize| if along_x { self.uv(r * self.grid_cols + k) } else { self.uv(k * self.grid_cols + c) };
+        let ((u0, v0), (u1, v1)) = (at(lo), at(hi));
+        let span = (hi - lo) as f64 * step;
+        ((u1 - u0) / span, (v1 - v0) / span)
+    }
+
+    fn uv(&self, i: usize) -> (f64, f64) {
+        let p = &self.points[i];
+        if p.valid {
+            (f64::from(p.displacement.x), f64::from(p.displacement.y))
+        } else {
+            (f64::NAN, f64::NAN)
+        }
+    }
+}
+
+/// Subset-based sub-pixel displacement measurement
+///
+/// ```rust,no_run
+/// # use opencv_rust::core::{Mat, MatDepth};
+/// # use opencv_rust::video::DigitalImageCorrelation;
+/// # fn main() -> opencv_rust::error::Result<()> {
+/// # let (reference, deformed) = (Mat::new(200, 200, 1, MatDepth::U8)?, Mat::new(200, 200, 1, MatDepth::U8)?);
+/// let dic = DigitalImageCorrelation::new(31, 10)?.with_search_radius(15);
+/// let field = dic.correlate(&reference, &deformed)?;
+/// let strain = field.strain()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigitalImageCorrelation {
+    subset_size: usize,
+    step: usize,
+    search_radius: usize,
+    max_iterations: usize,
+    min_zncc: f64,
+    roi: Option<Rect>,
+    sampler: PixelSampler,
+}
+
+impl DigitalImageCorrelation {
+    /// Correlation with odd `subset_size` (at least 5) subsets every `step`
+    /// pixels, a 10 pixel integer search, 50 refinement iterations, a
+    /// minimum ZNCC of 0.8 and Lanczos interpolation
+    pub fn new(subset_size: usize, step: usize) -> Result<Self> {
+        if subset_size < 5 || subset_size.is_multiple_of(2) {
+            return Err(Error::InvalidParameter(
+                "DIC subset size must be odd and at least 5".to_string(),
+            ));
+        }
+        if step == 0 {
+            return Err(Error::InvalidParameter("DIC grid step must be positive".to_string()));
+        }
+        Ok(Self {
+            subset_size,
+            step,
+            search_radius: 10,
+            max_iterations: 50,
+            min_zncc: 0.8,
+            roi: None,
+            sampler: PixelSampler::new(InterpolationFlag::Lanczos4, BorderType::Replicate),
+        })
+    }
+
+    /// Largest integer displacement searched before refinement, in pixels
+    #[must_use]
+    pub fn with_search_radius(mut self, radius: usize) -> Self {
+        self.search_radius = radius;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = iterations.max(1);
+        self
+    }
+
+    /// Correlation below which a point is reported invalid
+    #[must_use]
+    pub fn with_min_zncc(mut self, min_zncc: f64) -> Self {
+        self.min_zncc = min_zncc.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Only place subsets whose centres lie inside `roi`
+    #[must_use]
+    pub fn with_roi(mut self, roi: Rect) -> Self {
+        self.roi = Some(roi);
+        self
+    }
+
+    /// Interpolation used to sample the deformed image
+    ///
+    /// Defaults to [`InterpolationFlag::Lanczos4`]: `OpenCV`'s bicubic
+    /// kernel is not interpolation-accurate enough for DIC and biases
+    /// displacements by several hundredths of a pixel.
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: InterpolationFlag) -> Self {
+        self.sampler = PixelSampler::new(interpolation, BorderType::Replicate);
+        self
+    }
+
+    /// Track the subset grid from `reference` into `deformed`
+    ///
+    /// Both images must be single-channel U8 or F32 of the same size. Grid
+    /// points start half a subset in from the image (or ROI) edge.
+    pub fn correlate(&self, reference: &Mat, deformed: &Mat) -> Result<DisplacementField> {
+        if reference.rows() != deformed.rows() || reference.cols() != deformed.cols() {
+            return Err(Error::InvalidDimensions(
+                "DIC reference and deformed images must have the same size".to_string(),
+            ));
+        }
+        if reference.channels() != 1 || deformed.channels() != 1 {
+            return Err(Error::InvalidParameter(
+                "DIC requires single-channel images".to_string(),
+            ));
+        }
+        let f = plane(reference)?;
+        let g = plane(deformed)?;
+        let (rows, cols) = (reference.rows(), reference.cols());
+
+        let half = self.subset_size / 2;
+        let roi = self.roi.unwrap_or_else(|| Rect::new(0, 0, cols as i32, rows as i32));
+        let x_range = grid_range(roi.x, roi.width, half, cols);
+        let y_range = grid_range(roi.y, roi.height, half, rows);
+        let xs: Vec<usize> = x_range.step_by(self.step).collect();
+        let ys: Vec<usize> = y_range.step_by(self.step).collect();
+        if xs.is_empty() || ys.is_empty() {
+            return Err(Error::InvalidDimensions(
+                "Region is smaller than one DIC subset".to_string(),
+            ));
+        }
+
+        let mut points = Vec::with_capacity(xs.len() * ys.len());
+        for &y in &ys {
+            for &x in &xs {
+                points.push(self.track(&f, &g, deformed, (rows, cols), x, y)?);
+            }
+        }
+        Ok(DisplacementField { grid_rows: ys.len(), grid_cols: xs.len(), step: self.step, points })
+    }
+
+    /// Correlate the subset centred at `(x0, y0)`
+    fn track(&self, f: &[f64], g: &[f64], deformed: &Mat, (rows, cols): (usize, usize), x0: usize, y0: usize) -> Result<DicPoint> {
+        let half = (self.subset_size / 2) as i64;
+        let mut point = DicPoint {
+            position: Point2f::new(x0 as f32, y0 as f32),
+            displacement: Point2f::new(0.0, 0.0),
+            zncc: 0.0,
+            valid: false,
+        };
+
+        // Reference subset, its zero-mean values and steepest-descent images
+        let mut offsets = Vec::with_capacity(self.subset_size * self.subset_size);
+        let mut values = Vec::with_capacity(offsets.capacity());
+        let mut jacobian = Vec::with_capacity(offsets.capacity());
+        for dy in -half..=half {
+            for dx in -half..=half {
+                let (x, y) = ((x0 as i64 + dx) as usize, (y0 as i64 + dy) as usize);
+                let (gx, gy) = central_gradient(f, rows, cols, x, y);
+                let (dxf, dyf) = (dx as f64, dy as f64);
+                offsets.push((dxf, dyf));
+                values.push(f[y * cols + x]);
+                jacobian.push([gx, gx * dxf, gx * dyf, gy, gy * dxf, gy * dyf]);
+            }
+        }
+        let Some(f_norm) = zero_mean(&mut values) else {
+            return Ok(point);
+        };
+
+        // Integer search on whole-pixel shifts that keep the subset in the image
+        let radius = self.search_radius as i64;
+        let mut best = (f64::NEG_INFINITY, 0i64, 0i64);
+        let mut shifted = vec![0.0; values.len()];
+        for sy in -radius..=radius {
+            for sx in -radius..=radius {
+                let (cx, cy) = (x0 as i64 + sx, y0 as i64 + sy);
+                if cx < half || cy < half || cx + half >= cols as i64 || cy + half >= rows as i64 {
+                    continue;
+                }
+                for (v, &(dx, dy)) in shifted.iter_mut().zip(&offsets) {
+                    *v = g[(cy + dy as i64) as usize * cols + (cx + dx as i64) as usize];
+                }
+                if let Some(zncc) = correlation(&values, f_norm, &mut shifted) {
+                    if zncc > best.0 {
+                        best = (zncc, sx, sy);
+                    }
+                }
+            }
+        }
+        if best.0 == f64::NEG_INFINITY {
+            return Ok(point);
+        }
+
+        // Inverse-compositional Gauss-Newton on the affine subset shape
+        let mut hessian = [[0.0; 6]; 6];
+        for j in &jacobian {
+            for a in 0..6 {
+                for b in 0..6 {
+                    hessian[a][b] += j[a] * j[b];
+                }
+            }
+        }
+        let mut warp = [[1.0, 0.0, best.1 as f64], [0.0, 1.0, best.2 as f64]];
+        let mut zncc = best.0;
+        let mut converged = false;
+        let mut sample = [0.0f64];
+        for _ in 0..=self.max_iterations {
+            for (v, &(dx, dy)) in shifted.iter_mut().zip(&offsets) {
+                let x = x0 as f64 + warp[0][0] * dx + warp[0][1] * dy + warp[0][2];
+                let y = y0 as f64 + warp[1][0] * dx + warp[1][1] * dy + warp[1][2];
+                self.sampler.sample(deformed, x, y, &mut sample)?;
+                *v = sample[0];
+            }
+            let Some(current) = correlation(&values, f_norm, &mut shifted) else {
+                return Ok(point);
+            };
+            zncc = current;
+            if converged {
+                break;
+            }
+
+            // shifted now holds g - g_mean scaled to the norm of f
+            let mut gradient = [0.0; 6];
+            for ((j, &fv), &gv) in jacobian.iter().zip(&values).zip(&shifted) {
+                let residual = fv - gv;
+                for a in 0..6 {
+                    gradient[a] += j[a] * residual;
+                }
+            }
+            let Some(dp) = solve6(hessian, gradient.map(|v| -v)) else {
+                return Ok(point);
+            };
+            let h = half as f64;
+            let size = (dp[0] * dp[0] + dp[3] * dp[3] + h * h * (dp[1] * dp[1] + dp[2] * dp[2] + dp[4] * dp[4] + dp[5] * dp[5])).sqrt();
+            warp = compose_inverse(warp, dp);
+            converged = size < CONVERGENCE;
+        }
+
+        point.displacement = Point2f::new(warp[0][2] as f32, warp[1][2] as f32);
+        point.zncc = zncc as f32;
+        point.valid = converged && zncc >= self.min_zncc;
+        Ok(point)
+    }
+}
+
+/// Subset centres along one axis of a region
+fn grid_range(start: i32, len: i32, half: usize, size: usize) -> std::ops::Range<usize> {
+    let lo = (start.max(0) as usize).max(half);
+    let hi = ((start + len).max(0) as usize).min(size.saturating_sub(half));
+    lo..hi.max(lo)
+}
+
+/// Image values in their own units
+fn plane(mat: &Mat) -> Result<Vec<f64>> {
+    match mat.depth() {
+        MatDepth::U8 => Ok(mat.data().iter().map(|&v| f64::from(v)).collect()),
+        MatDepth::F32 => (0..mat.rows() * mat.cols())
+            .map(|i| mat.at_f32(i / mat.cols(), i % mat.cols(), 0).map(f64::from))
+            .collect(),
+        _ => Err(Error::UnsupportedOperation("DIC supports U8 and F32 images".to_string())),
+    }
+}
+
+fn central_gradient(f: &[f64], rows: usize, cols: usize, x: usize, y: usize) -> (f64, f64) {
+    let (xl, xr) = (x.saturating_sub(1), (x + 1).min(cols - 1));
+    let (yu, yd) = (y.saturating_sub(1), (y + 1).min(rows - 1));
+    let gx = (f[y * cols + xr] - f[y * cols + xl]) / (xr - xl).max(1) as f64;
+    let gy = (f[yd * cols + x] - f[yu * cols + x]) / (yd - yu).max(1) as f64;
+    (gx, gy)
+}
+
+/// Subtract the mean in place and return the resulting norm, `None` if flat
+fn zero_mean(values: &mut [f64]) -> Option<f64> {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter_mut().for_each(|v| *v -= mean);
+    let norm = values.iter().map(|v| v * v).sum::<f64>().sqrt();
+    (norm > 1e-9 * values.len() as f64).then_some(norm)
+}
+
+/// ZNCC of the zero-mean reference subset `f` (norm `f_norm`) with `g`
+///
+/// `g` is left zero-mean and scaled to the norm of `f`, ready for the
+/// Gauss-Newton residual. `None` if `g` is flat.
+fn correlation(f: &[f64], f_norm: f64, g: &mut [f64]) -> Option<f64> {
+    let g_norm = zero_mean(g)?;
+    let scale = f_norm / g_norm;
+    g.iter_mut().for_each(|v| *v *= scale);
+    Some(f.iter().zip(g.iter()).map(|(a, b)| a * b).sum::<f64>() / (f_norm * f_norm))
+}
+
+/// `warp ∘ W(dp)⁻¹` for affine warps with parameters `(u, ux, uy, v, vx, vy)`
+fn compose_inverse(warp: [[f64; 3]; 2], dp: [f64; 6]) -> [[f64; 3]; 2] {
+    let (a, b, c) = (1.0 + dp[1], dp[2], dp[0]);
+    let (d, e, f) = (dp[4], 1.0 + dp[5], dp[3]);
+    let det = a * e - b * d;
+    // Inverse of [[a, b, c], [d, e, f], [0, 0, 1]]
+    let inv = [
+        [e / det, -b / det, (b * f - c * e) / det],
+        [-d / det, a / det, (c * d - a * f) / det],
+    ];
+    let mut out = [[0.0; 3]; 2];
+    for r in 0..2 {
+        for k in 0..3 {
+            out[r][k] = warp[r][0] * inv[0][k] + warp[r][1] * inv[1][k];
+        }
+        out[r][2] += warp[r][2];
+    }
+    out
+}
+
+/// Solve the 6x6 system `m x = b` by Gaussian elimination with pivoting
+fn solve6(mut m: [[f64; 6]; 6], mut b: [f64; 6]) -> Option<[f64; 6]> {
+    for col in 0..6 {
+        let pivot = (col..6).max_by(|&i, &j| m[i][col].abs().total_cmp(&m[j][col].abs()))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..6 {
+            let factor = m[row][col] / m[col][col];
+            let pivot_row = m[col];
+            for (v, p) in m[row].iter_mut().zip(pivot_row).skip(col) {
+                *v -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; 6];
+    for row in (0..6).rev() {
+        let tail: f64 = (row + 1..6).map(|k| m[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / m[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speckle(x: f64, y: f64) -> f64 {
+        100.0 + 40.0 * (0.16 * x + 0.09 * y).sin() + 35.0 * (0.12 * y - 0.2 * x + 1.0).sin() + 25.0 * (0.26 * x + 0.23 * y).cos()
+    }
+
+    fn render(size: usize, map: impl Fn(f64, f64) -> (f64, f64)) -> Mat {
+        let mut mat = Mat::new(size, size, 1, MatDepth::F32).unwrap();
+        for r in 0..size {
+            for c in 0..size {
+                let (x, y) = map(c as f64, r as f64);
+                mat.set_f32(r, c, 0, speckle(x, y) as f32).unwrap();
+            }
+        }
+        mat
+    }
+
+    #[test]
+    fn test_dic_measures_displacement_and_strain() {
+        // u = 1.3 + 0.01 X, v = -0.7: reference point X lands at X + u(X)
+        let reference = render(96, |x, y| (x, y));
+        let deformed = render(96, |x, y| ((x - 1.3) / 1.01, y + 0.7));
+
+        let dic = DigitalImageCorrelation::new(21, 8).unwrap().with_search_radius(4).with_roi(Rect::new(16, 16, 64, 64));
+        let field = dic.correlate(&reference, &deformed).unwrap();
+        assert_eq!((field.grid_rows(), field.grid_cols()), (8, 8));
+        assert_eq!(field.valid_count(), 64);
+        for p in field.points() {
+            let u = 1.3 + 0.01 * f64::from(p.position.x);
+            assert!((f64::from(p.displacement.x) - u).abs() < 0.02, "{p:?}");
+            assert!((f64::from(p.displacement.y) + 0.7).abs() < 0.02, "{p:?}");
+            assert!(p.zncc > 0.99);
+        }
+
+        let strain = field.strain().unwrap();
+        for r in 0..8 {
+            for c in 0..8 {
+                assert!((strain.at_f32(r, c, 0).unwrap() - 0.01).abs() < 1e-3);
+                assert!(strain.at_f32(r, c, 1).unwrap().abs() < 1e-3);
+                assert!(strain.at_f32(r, c, 2).unwrap().abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dic_rejects_flat_and_bad_input() {
+        let flat = Mat::new(40, 40, 1, MatDepth::U8).unwrap();
+        let dic = DigitalImageCorrelation::new(11, 10).unwrap();
+        let field = dic.correlate(&flat, &flat).unwrap();
+        assert_eq!(field.valid_count(), 0);
+        assert!(field.to_mat().unwrap().at_f32(0, 0, 0).unwrap().is_nan());
+
+        assert!(DigitalImageCorrelation::new(10, 4).is_err());
+        assert!(dic.correlate(&flat, &Mat::new(40, 41, 1, MatDepth::U8).unwrap()).is_err());
+        assert!(dic.clone().with_roi(Rect::new(0, 0, 4, 4)).correlate(&flat, &flat).is_err());
+    }
+}
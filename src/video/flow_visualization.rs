@@ -0,0 +1,196 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+//! Visualization of dense optical flow fields
+//!
+//! Both helpers take a 2-channel F32 flow Mat holding `(dx, dy)` per pixel, as
+//! returned by [`calc_optical_flow_farneback`](super::calc_optical_flow_farneback)
+//! and [`FarnebackOpticalFlow`](super::FarnebackOpticalFlow).
+
+use crate::core::types::{Point, Scalar};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::line;
+use std::f32::consts::PI;
+
+/// Color-code a flow field with the Middlebury color wheel
+///
+/// Hue encodes direction and saturation encodes magnitude, normalized by the
+/// largest flow vector in the field, so a zero field is white. Returns a
+/// 3-channel U8 image in RGB order.
+pub fn draw_flow_hsv(flow: &Mat) -> Result<Mat> {
+    check_flow(flow)?;
+
+    let (rows, cols) = (flow.rows(), flow.cols());
+    let mut vectors = Vec::with_capacity(rows * cols);
+    let mut max_radius = 0.0f32;
+    for row in 0..rows {
+        for col in 0..cols {
+            let (dx, dy) = (flow.at_f32(row, col, 0)?, flow.at_f32(row, col, 1)?);
+            if dx.is_finite() && dy.is_finite() {
+                max_radius = max_radius.max(dx.hypot(dy));
+            }
+            vectors.push((dx, dy));
+        }
+    }
+
+    let wheel = color_wheel();
+    let mut out = Mat::new(rows, cols, 3, MatDepth::U8)?;
+    for (i, &(dx, dy)) in vectors.iter().enumerate() {
+        let rgb = if max_radius > 0.0 && dx.is_finite() && dy.is_finite() {
+            wheel_color(&wheel, dx / max_radius, dy / max_radius)
+        } else {
+            [255, 255, 255]
+        };
+        out.at_mut(i / cols, i % cols)?.copy_from_slice(&rgb);
+    }
+
+    Ok(out)
+}
+
+/// Draw the flow as arrows sampled every `step` pixels onto `img`
+///
+/// Each arrow starts at the sample position and ends at the position
+/// displaced by the flow. `img` must be U8 and the same size as `flow`.
+pub fn draw_flow_arrows(img: &mut Mat, flow: &Mat, step: usize, color: Scalar) -> Result<()> {
+    check_flow(flow)?;
+    if step == 0 {
+        return Err(Error::InvalidParameter("step must be positive".to_string()));
+    }
+    if img.rows() != flow.rows() || img.cols() != flow.cols() {
+        return Err(Error::InvalidDimensions(
+            "Image and flow must have the same size".to_string(),
+        ));
+    }
+
+    for row in (step / 2..flow.rows()).step_by(step) {
+        for col in (step / 2..flow.cols()).step_by(step) {
+            let (dx, dy) = (flow.at_f32(row, col, 0)?, flow.at_f32(row, col, 1)?);
+            if !(dx.is_finite() && dy.is_finite()) {
+                continue;
+            }
+
+            let start = Point::new(col as i32, row as i32);
+            let end = Point::new((col as f32 + dx).round() as i32, (row as f32 + dy).round() as i32);
+            line(img, start, end, color, 1)?;
+
+            // Arrow head: two short strokes at +-30 degrees from the shaft
+            let length = dx.hypot(dy);
+            if length >= 2.0 {
+                let head = (length * 0.3).clamp(2.0, 6.0);
+                let angle = dy.atan2(dx);
+                for side in [-1.0f32, 1.0] {
+                    let a = angle + PI + side * PI / 6.0;
+                    let tip = Point::new(
+                        (end.x as f32 + head * a.cos()).round() as i32,
+                        (end.y as f32 + head * a.sin()).round() as i32,
+                    );
+                    line(img, end, tip, color, 1)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_flow(flow: &Mat) -> Result<()> {
+    if flow.channels() != 2 || flow.depth() != MatDepth::F32 {
+        return Err(Error::InvalidParameter(
+            "Flow must be a 2-channel F32 Mat".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The 55-entry Middlebury color wheel (Baker et al., 2011)
+fn color_wheel() -> Vec<[f32; 3]> {
+    // Transition lengths: red-yellow, yellow-green, green-cyan, cyan-blue,
+    // blue-magenta, magenta-red
+    const SEGMENTS: [usize; 6] = [15, 6, 4, 11, 13, 6];
+    let mut wheel = Vec::with_capacity(SEGMENTS.iter().sum());
+
+    for (segment, &len) in SEGMENTS.iter().enumerate() {
+        for i in 0..len {
+            let t = 255.0 * i as f32 / len as f32;
+            wheel.push(match segment {
+                0 => [255.0, t, 0.0],
+                1 => [255.0 - t, 255.0, 0.0],
+                2 => [0.0, 255.0, t],
+                3 => [0.0, 255.0 - t, 255.0],
+                4 => [t, 0.0, 255.0],
+                _ => [255.0, 0.0, 255.0 - t],
+            });
+        }
+    }
+    wheel
+}
+
+/// Color of a normalized flow vector (`|(u, v)| <= 1` is saturated at 1)
+fn wheel_color(wheel: &[[f32; 3]], u: f32, v: f32) -> [u8; 3] {
+    let n = wheel.len();
+    let radius = u.hypot(v);
+    let angle = (-v).atan2(-u) / PI;
+    let fk = (angle + 1.0) / 2.0 * (n - 1) as f32;
+    let k0 = (fk.floor() as usize).min(n - 1);
+    let k1 = (k0 + 1) % n;
+    let f = fk - k0 as f32;
+
+    let mut rgb = [0u8; 3];
+    for (ch, out) in rgb.iter_mut().enumerate() {
+        let c = ((1.0 - f) * wheel[k0][ch] + f * wheel[k1][ch]) / 255.0;
+        let c = if radius <= 1.0 {
+            1.0 - radius * (1.0 - c)
+        } else {
+            c * 0.75
+        };
+        *out = (255.0 * c).round().clamp(0.0, 255.0) as u8;
+    }
+    rgb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform_flow(dx: f32, dy: f32) -> Mat {
+        let mut flow = Mat::new(8, 8, 2, MatDepth::F32).unwrap();
+        for row in 0..8 {
+            for col in 0..8 {
+                flow.set_f32(row, col, 0, dx).unwrap();
+                flow.set_f32(row, col, 1, dy).unwrap();
+            }
+        }
+        flow
+    }
+
+    #[test]
+    fn test_draw_flow_hsv() {
+        let zero = draw_flow_hsv(&uniform_flow(0.0, 0.0)).unwrap();
+        assert_eq!(zero.channels(), 3);
+        assert_eq!(zero.at(3, 3).unwrap(), &[255, 255, 255]);
+
+        // Rightward motion is red on the Middlebury wheel, leftward cyan-blue
+        let mut flow = uniform_flow(0.0, 0.0);
+        flow.set_f32(1, 1, 0, 4.0).unwrap();
+        flow.set_f32(2, 2, 0, -4.0).unwrap();
+        let color = draw_flow_hsv(&flow).unwrap();
+        assert_eq!(color.at(1, 1).unwrap(), &[255, 0, 0]);
+        let left = color.at(2, 2).unwrap();
+        assert!(left[0] < 50 && left[1] > 150 && left[2] > 150);
+        assert_eq!(color.at(5, 5).unwrap(), &[255, 255, 255]);
+
+        assert!(draw_flow_hsv(&Mat::new(8, 8, 2, MatDepth::U8).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_draw_flow_arrows() {
+        let flow = uniform_flow(3.0, 0.0);
+        let mut img = Mat::new(8, 8, 3, MatDepth::U8).unwrap();
+        draw_flow_arrows(&mut img, &flow, 4, Scalar::new(0.0, 255.0, 0.0, 0.0)).unwrap();
+
+        assert_eq!(img.at(2, 2).unwrap(), &[0, 255, 0]);
+        assert_eq!(img.at(2, 5).unwrap(), &[0, 255, 0]);
+        assert_eq!(img.at(0, 0).unwrap(), &[0, 0, 0]);
+
+        assert!(draw_flow_arrows(&mut img, &flow, 0, Scalar::all(255.0)).is_err());
+    }
+}
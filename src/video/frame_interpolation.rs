@@ -0,0 +1,274 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+use crate::core::types::{BorderType, InterpolationFlag};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::{to_gray, PixelSampler};
+use super::optical_flow::calc_optical_flow_farneback;
+
+/// Block size passed to the dense flow backend
+const FLOW_WINDOW: i32 = 7;
+/// Largest forward-backward flow disagreement, in pixels, for a pixel to be
+/// considered visible in both frames
+const CONSISTENCY_THRESHOLD: f32 = 1.5;
+
+/// Synthesize the frame at time `t` between `prev` (`t = 0`) and `next`
+/// (`t = 1`) by motion-compensated interpolation
+///
+/// Dense flow is computed in both directions with
+/// [`calc_optical_flow_farneback`] and splatted to time `t`; where several
+/// pixels land on the same spot, the best matched one wins, so foreground
+/// objects keep their shape. Each output pixel then samples both frames along
+/// its flow. Pixels that fail the forward-backward consistency check are
+/// treated as occluded: content that is covered up by `next` is taken from
+/// `prev` only, and content uncovered in `next` from `next` only, which
+/// avoids the ghosting of a plain cross-fade. Motion is limited to the search
+/// range of the flow backend (5 pixels).
+///
+/// Frames are U8 with 1 or 3 (RGB) channels and the same size.
+pub fn interpolate_frames(prev: &Mat, next: &Mat, t: f64) -> Result<Mat> {
+    if prev.depth() != MatDepth::U8 || !matches!(prev.channels(), 1 | 3) {
+        return Err(Error::UnsupportedOperation(
+            "Frame interpolation requires 1- or 3-channel U8 frames".to_string(),
+        ));
+    }
+    if prev.rows() != next.rows() || prev.cols() != next.cols()
+        || prev.channels() != next.channels() || prev.depth() != next.depth()
+    {
+        return Err(Error::InvalidDimensions(
+            "Frames must have the same size and type".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&t) {
+        return Err(Error::InvalidParameter(
+            "Interpolation time must be in [0, 1]".to_string(),
+        ));
+    }
+    if t == 0.0 {
+        return Ok(prev.clone());
+    }
+    if t == 1.0 {
+        return Ok(next.clone());
+    }
+
+    let (rows, cols) = (prev.rows(), prev.cols());
+    let mut gray0 = Mat::new(1, 1, 1, MatDepth::U8)?;
+    to_gray(prev, &mut gray0)?;
+    let mut gray1 = Mat::new(1, 1, 1, MatDepth::U8)?;
+    to_gray(next, &mut gray1)?;
+    let forward = FlowField::from_mat(&calc_optical_flow_farneback(&gray0, &gray1, 0.5, 1, FLOW_WINDOW, 1)?)?;
+    let backward = FlowField::from_mat(&calc_optical_flow_farneback(&gray1, &gray0, 0.5, 1, FLOW_WINDOW, 1)?)?;
+
+    let visible0 = forward.consistency(&backward);
+    let visible1 = backward.consistency(&forward);
+
+    // Splat both flows to time t; on collisions keep the best matched source
+    let t = t as f32;
+    let mut flow_t: Vec<Option<(f32, f32)>> = vec![None; rows * cols];
+    let mut score = vec![f32::INFINITY; rows * cols];
+    let (g0, g1) = (gray0.data(), gray1.data());
+    for (field, visible, src, dst, time, sign) in [
+        (&forward, &visible0, g0, g1, t, 1.0f32),
+        (&backward, &visible1, g1, g0, 1.0 - t, -1.0f32),
+    ] {
+        for row in 0..rows {
+            for col in 0..cols {
+                let i = row * cols + col;
+                let (dx, dy) = field.at(i);
+                let Some(target) = field.offset(row, col, dx * time, dy * time) else {
+                    continue;
+                };
+                let matched = field.offset(row, col, dx, dy).map_or(255.0, |j| {
+                    (f32::from(src[i]) - f32::from(dst[j])).abs()
+                });
+                // Occluded sources only fill what nothing visible reaches
+                let s = matched + if visible[i] { 0.0 } else { 1000.0 };
+                if s < score[target] {
+                    score[target] = s;
+                    flow_t[target] = Some((sign * dx, sign * dy));
+                }
+            }
+        }
+    }
+    let flow_t = fill_holes(flow_t, rows, cols);
+
+    let channels = prev.channels();
+    let mut out = Mat::new(rows, cols, channels, MatDepth::U8)?;
+    let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate);
+    let mut c0 = vec![0.0f64; channels];
+    let mut c1 = vec![0.0f64; channels];
+    for row in 0..rows {
+        for col in 0..cols {
+            let (dx, dy) = flow_t[row * cols + col];
+            let (x0, y0) = (col as f32 - t * dx, row as f32 - t * dy);
+            let (x1, y1) = (col as f32 + (1.0 - t) * dx, row as f32 + (1.0 - t) * dy);
+            sampler.sample(prev, f64::from(x0), f64::from(y0), &mut c0)?;
+            sampler.sample(next, f64::from(x1), f64::from(y1), &mut c1)?;
+
+            let seen0 = visible0[nearest(x0, y0, rows, cols)];
+            let seen1 = visible1[nearest(x1, y1, rows, cols)];
+            let w1: f64 = match (seen0, seen1) {
+                // Covered by the time of `next`
+                (false, true) => 0.0,
+                // Uncovered since `prev`
+                (true, false) => 1.0,
+                _ => f64::from(t),
+            };
+
+            let px = out.at_mut(row, col)?;
+            for ch in 0..channels {
+                px[ch] = ((1.0 - w1) * c0[ch] + w1 * c1[ch]).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Row-major copy of a 2-channel F32 flow Mat
+struct FlowField {
+    rows: usize,
+    cols: usize,
+    data: Vec<(f32, f32)>,
+}
+
+impl FlowField {
+    fn from_mat(flow: &Mat) -> Result<Self> {
+        let (rows, cols) = (flow.rows(), flow.cols());
+        let mut data = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                data.push((flow.at_f32(row, col, 0)?, flow.at_f32(row, col, 1)?));
+            }
+        }
+        Ok(Self { rows, cols, data })
+    }
+
+    fn at(&self, index: usize) -> (f32, f32) {
+        self.data[index]
+    }
+
+    /// Index of the pixel nearest to `(row, col) + (dx, dy)`, if inside
+    fn offset(&self, row: usize, col: usize, dx: f32, dy: f32) -> Option<usize> {
+        let x = (col as f32 + dx).round();
+        let y = (row as f32 + dy).round();
+        if x < 0.0 || y < 0.0 || x >= self.cols as f32 || y >= self.rows as f32 {
+            return None;
+        }
+        Some(y as usize * self.cols + x as usize)
+    }
+
+    /// Whether each pixel's flow is undone by `reverse` at its destination
+    fn consistency(&self, reverse: &FlowField) -> Vec<bool> {
+        let mut visible = vec![false; self.data.len()];
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let i = row * self.cols + col;
+                let (dx, dy) = self.data[i];
+                if let Some(j) = self.offset(row, col, dx, dy) {
+                    let (rx, ry) = reverse.data[j];
+                    visible[i] = (dx + rx).hypot(dy + ry) <= CONSISTENCY_THRESHOLD;
+                }
+            }
+        }
+        visible
+    }
+}
+
+/// Fill pixels no flow was splatted to with the mean of their filled
+/// 4-neighbours, growing inwards from the hole borders
+fn fill_holes(mut flow: Vec<Option<(f32, f32)>>, rows: usize, cols: usize) -> Vec<(f32, f32)> {
+    loop {
+        let holes: Vec<usize> = (0..flow.len()).filter(|&i| flow[i].is_none()).collect();
+        if holes.is_empty() || holes.len() == flow.len() {
+            break;
+        }
+        let mut updates = Vec::new();
+        for &i in &holes {
+            let (row, col) = (i / cols, i % cols);
+            let mut neighbours = Vec::with_capacity(4);
+            if row > 0 {
+                neighbours.push(i - cols);
+            }
+            if row + 1 < rows {
+                neighbours.push(i + cols);
+            }
+            if col > 0 {
+                neighbours.push(i - 1);
+            }
+            if col + 1 < cols {
+                neighbours.push(i + 1);
+            }
+            let filled: Vec<(f32, f32)> = neighbours.into_iter().filter_map(|j| flow[j]).collect();
+            if !filled.is_empty() {
+                let n = filled.len() as f32;
+                let sum = filled.iter().fold((0.0, 0.0), |acc, f| (acc.0 + f.0, acc.1 + f.1));
+                updates.push((i, (sum.0 / n, sum.1 / n)));
+            }
+        }
+        for (i, f) in updates {
+            flow[i] = Some(f);
+        }
+    }
+    flow.into_iter().map(|f| f.unwrap_or((0.0, 0.0))).collect()
+}
+
+fn nearest(x: f32, y: f32, rows: usize, cols: usize) -> usize {
+    let col = x.round().clamp(0.0, (cols - 1) as f32) as usize;
+    let row = y.round().clamp(0.0, (rows - 1) as f32) as usize;
+    row * cols + col
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imgproc::noise::SplitMix64;
+
+    /// Static textured background with a textured square whose left edge is
+    /// at `square_x`
+    fn frame(square_x: usize) -> Mat {
+        let mut background = SplitMix64::new(7);
+        let mut foreground = SplitMix64::new(11);
+        let square: Vec<u8> = (0..144).map(|_| (foreground.next_u64() % 256) as u8).collect();
+        let mut img = Mat::new(48, 48, 1, MatDepth::U8).unwrap();
+        for row in 0..48 {
+            for col in 0..48 {
+                let bg = (background.next_u64() % 256) as u8;
+                let in_square = (18..30).contains(&row) && (square_x..square_x + 12).contains(&col);
+                img.at_mut(row, col).unwrap()[0] = if in_square {
+                    square[(row - 18) * 12 + col - square_x]
+                } else {
+                    bg
+                };
+            }
+        }
+        img
+    }
+
+    fn interior_error(a: &Mat, b: &Mat) -> f64 {
+        let mut sum = 0.0;
+        for row in 6..42 {
+            for col in 6..42 {
+                sum += f64::from(a.at(row, col).unwrap()[0].abs_diff(b.at(row, col).unwrap()[0]));
+            }
+        }
+        sum / (36.0 * 36.0)
+    }
+
+    #[test]
+    fn test_interpolate_frames() {
+        let (prev, next, truth) = (frame(16), frame(20), frame(18));
+        let mid = interpolate_frames(&prev, &next, 0.5).unwrap();
+        assert_eq!((mid.rows(), mid.cols(), mid.channels()), (48, 48, 1));
+
+        let mut blend = Mat::new(48, 48, 1, MatDepth::U8).unwrap();
+        for ((b, &p), &n) in blend.data_mut().iter_mut().zip(prev.data()).zip(next.data()) {
+            *b = ((u16::from(p) + u16::from(n)) / 2) as u8;
+        }
+        let error = interior_error(&mid, &truth);
+        let blend_error = interior_error(&blend, &truth);
+        assert!(error < 0.25 * blend_error, "error {error} vs cross-fade {blend_error}");
+
+        assert_eq!(interpolate_frames(&prev, &next, 0.0).unwrap().data(), prev.data());
+        assert!(interpolate_frames(&prev, &next, 1.5).is_err());
+        assert!(interpolate_frames(&prev, &Mat::new(10, 10, 1, MatDepth::U8).unwrap(), 0.5).is_err());
+    }
+}
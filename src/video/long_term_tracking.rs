@@ -0,0 +1,327 @@
+//! Long-term tracking: loss detection and re-detection around any tracker
+//!
+//! Short-term trackers such as [`TrackerNCC`] or [`MOSSETracker`] keep
+//! reporting a box after the target has left the frame. [`LongTermTracker`]
+//! wraps any [`Tracker`], declares the target lost when the tracker fails or
+//! its confidence drops below a threshold, fires the `on_lost` hook, and then
+//! asks a pluggable [`Redetector`] to find the target again.
+
+use crate::core::types::Rect;
+use crate::core::Mat;
+use crate::error::{Error, Result};
+use crate::video::{CSRTTracker, MOSSETracker, TrackerNCC};
+
+/// Common interface of single-target trackers
+pub trait Tracker {
+    /// Start tracking the object inside `bbox`
+    fn init(&mut self, frame: &Mat, bbox: Rect) -> Result<()>;
+
+    /// Locate the object in the next frame
+    fn update(&mut self, frame: &Mat) -> Result<Rect>;
+
+    /// Confidence of the last update, if the tracker can estimate one
+    ///
+    /// Higher is better; trackers returning `None` are only considered lost
+    /// when [`update`](Self::update) fails.
+    fn confidence(&self) -> Option<f32> {
+        None
+    }
+}
+
+impl Tracker for MOSSETracker {
+    fn init(&mut self, frame: &Mat, bbox: Rect) -> Result<()> {
+        MOSSETracker::init(self, frame, bbox)
+    }
+
+    fn update(&mut self, frame: &Mat) -> Result<Rect> {
+        MOSSETracker::update(self, frame)
+    }
+}
+
+impl Tracker for CSRTTracker {
+    fn init(&mut self, frame: &Mat, bbox: Rect) -> Result<()> {
+        CSRTTracker::init(self, frame, bbox)
+    }
+
+    fn update(&mut self, frame: &Mat) -> Result<Rect> {
+        CSRTTracker::update(self, frame)
+    }
+}
+
+impl Tracker for TrackerNCC {
+    fn init(&mut self, frame: &Mat, bbox: Rect) -> Result<()> {
+        TrackerNCC::init(self, frame, bbox)
+    }
+
+    fn update(&mut self, frame: &Mat) -> Result<Rect> {
+        TrackerNCC::update(self, frame)
+    }
+
+    fn confidence(&self) -> Option<f32> {
+        Some(self.score())
+    }
+}
+
+/// Finds the target again after it was lost
+///
+/// Implemented for closures `FnMut(&Mat) -> Result<Option<Rect>>`, so any
+/// detector (cascade, template match, DNN) can be plugged in.
+pub trait Redetector {
+    /// Search `frame` for the target, returning its box if found
+    fn redetect(&mut self, frame: &Mat) -> Result<Option<Rect>>;
+}
+
+impl<F> Redetector for F
+where
+    F: FnMut(&Mat) -> Result<Option<Rect>>,
+{
+    fn redetect(&mut self, frame: &Mat) -> Result<Option<Rect>> {
+        self(frame)
+    }
+}
+
+/// Outcome of one [`LongTermTracker::update`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackState {
+    /// The tracker followed the target; confidence is `None` when the
+    /// tracker does not estimate one
+    Tracking { bbox: Rect, confidence: Option<f32> },
+    /// The target was found again by the re-detector and the tracker was
+    /// re-initialized on it
+    Redetected { bbox: Rect },
+    /// The target is not visible
+    Lost,
+}
+
+type LostHook = Box<dyn FnMut(usize, Rect)>;
+type RedetectedHook = Box<dyn FnMut(usize, Rect)>;
+
+/// Wraps a [`Tracker`] with loss detection, hooks and re-detection
+pub struct LongTermTracker<T: Tracker> {
+    tracker: T,
+    confidence_threshold: f32,
+    redetector: Option<Box<dyn Redetector>>,
+    redetect_interval: usize,
+    on_lost: Option<LostHook>,
+    on_redetected: Option<RedetectedHook>,
+    frame_index: usize,
+    lost_since: Option<usize>,
+    last_bbox: Option<Rect>,
+}
+
+impl<T: Tracker> LongTermTracker<T> {
+    /// Wrap `tracker` with a confidence threshold of 0.5 and no re-detector
+    pub fn new(tracker: T) -> Self {
+        Self {
+            tracker,
+            confidence_threshold: 0.5,
+            redetector: None,
+            redetect_interval: 1,
+            on_lost: None,
+            on_redetected: None,
+            frame_index: 0,
+            lost_since: None,
+            last_bbox: None,
+        }
+    }
+
+    /// Confidence below which the target is considered lost
+    #[must_use]
+    pub fn with_confidence_threshold(mut self, threshold: f32) -> Self {
+        self.confidence_threshold = threshold;
+        self
+    }
+
+    /// Strategy used to find the target after it was lost
+    #[must_use]
+    pub fn with_redetector(mut self, redetector: impl Redetector + 'static) -> Self {
+        self.redetector = Some(Box::new(redetector));
+        self
+    }
+
+    /// While lost, run the re-detector only every `frames` frames (at least 1)
+    ///
+    /// Detectors are usually much slower than trackers; a larger interval
+    /// trades recovery latency for throughput.
+    #[must_use]
+    pub fn with_redetect_interval(mut self, frames: usize) -> Self {
+        self.redetect_interval = frames.max(1);
+        self
+    }
+
+    /// Called with the frame index and last known box when the target is lost
+    #[must_use]
+    pub fn on_lost(mut self, hook: impl FnMut(usize, Rect) + 'static) -> Self {
+        self.on_lost = Some(Box::new(hook));
+        self
+    }
+
+    /// Called with the frame index and new box when the target is re-detected
+    #[must_use]
+    pub fn on_redetected(mut self, hook: impl FnMut(usize, Rect) + 'static) -> Self {
+        self.on_redetected = Some(Box::new(hook));
+        self
+    }
+
+    /// Start tracking the object inside `bbox`
+    pub fn init(&mut self, frame: &Mat, bbox: Rect) -> Result<()> {
+        self.tracker.init(frame, bbox)?;
+        self.frame_index = 0;
+        self.lost_since = None;
+        self.last_bbox = Some(bbox);
+        Ok(())
+    }
+
+    /// Process the next frame
+    ///
+    /// Tracker failures are treated as loss rather than returned; errors from
+    /// the re-detector or from re-initializing the tracker are propagated.
+    pub fn update(&mut self, frame: &Mat) -> Result<TrackState> {
+        let Some(last_bbox) = self.last_bbox else {
+            return Err(Error::InvalidParameter("Tracker not initialized".to_string()));
+        };
+        self.frame_index += 1;
+
+        if let Some(since) = self.lost_since {
+            return self.try_redetect(frame, since);
+        }
+
+        let tracked = self.tracker.update(frame).ok().and_then(|bbox| {
+            let confidence = self.tracker.confidence();
+            let confident = confidence.is_none_or(|c| c >= self.confidence_threshold);
+            confident.then_some((bbox, confidence))
+        });
+
+        if let Some((bbox, confidence)) = tracked {
+            self.last_bbox = Some(bbox);
+            return Ok(TrackState::Tracking { bbox, confidence });
+        }
+
+        self.lost_since = Some(self.frame_index);
+        if let Some(hook) = &mut self.on_lost {
+            hook(self.frame_index, last_bbox);
+        }
+        // Losing the target and finding it again in the same frame is the
+        // common case for fast motion
+        self.try_redetect(frame, self.frame_index)
+    }
+
+    /// Whether the target is currently lost
+    #[must_use]
+    pub fn is_lost(&self) -> bool {
+        self.lost_since.is_some()
+    }
+
+    /// Last box reported while tracking (or re-detected)
+    #[must_use]
+    pub fn last_bbox(&self) -> Option<Rect> {
+        self.last_bbox
+    }
+
+    /// The wrapped tracker
+    #[must_use]
+    pub fn tracker(&self) -> &T {
+        &self.tracker
+    }
+
+    fn try_redetect(&mut self, frame: &Mat, lost_since: usize) -> Result<TrackState> {
+        let Some(redetector) = &mut self.redetector else {
+            return Ok(TrackState::Lost);
+        };
+        if !(self.frame_index - lost_since).is_multiple_of(self.redetect_interval) {
+            return Ok(TrackState::Lost);
+        }
+
+        let Some(bbox) = redetector.redetect(frame)? else {
+            return Ok(TrackState::Lost);
+        };
+
+        self.tracker.init(frame, bbox)?;
+        self.lost_since = None;
+        self.last_bbox = Some(bbox);
+        if let Some(hook) = &mut self.on_redetected {
+            hook(self.frame_index, bbox);
+        }
+        Ok(TrackState::Redetected { bbox })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MatDepth;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    /// Blank frame with a textured 12x12 target at (x, y), or none
+    fn frame_with_target(target: Option<(usize, usize)>) -> Mat {
+        let mut frame = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        if let Some((x, y)) = target {
+            for row in 0..12 {
+                for col in 0..12 {
+                    frame.at_mut(y + row, x + col).unwrap()[0] = ((row * 37 + col * 91) % 200 + 40) as u8;
+                }
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn test_long_term_tracker_loss_and_redetection() {
+        let lost_at = Rc::new(Cell::new(None));
+        let recovered = Rc::new(RefCell::new(Vec::new()));
+        let (lost_hook, recovered_hook) = (Rc::clone(&lost_at), Rc::clone(&recovered));
+
+        let mut tracker = LongTermTracker::new(TrackerNCC::new().with_search_radius(8))
+            .with_confidence_threshold(0.8)
+            .with_redetect_interval(2)
+            .with_redetector(|frame: &Mat| -> Result<Option<Rect>> {
+                // Stand-in detector: the target is wherever the frame is bright
+                for row in 0..frame.rows() {
+                    for col in 0..frame.cols() {
+                        if frame.at(row, col)?[0] > 0 {
+                            return Ok(Some(Rect::new(col as i32, row as i32, 12, 12)));
+                        }
+                    }
+                }
+                Ok(None)
+            })
+            .on_lost(move |frame, bbox| lost_hook.set(Some((frame, bbox))))
+            .on_redetected(move |frame, bbox| recovered_hook.borrow_mut().push((frame, bbox)));
+
+        tracker.init(&frame_with_target(Some((10, 10))), Rect::new(10, 10, 12, 12)).unwrap();
+
+        let state = tracker.update(&frame_with_target(Some((12, 11)))).unwrap();
+        assert!(matches!(state, TrackState::Tracking { bbox, confidence: Some(c) }
+            if bbox == Rect::new(12, 11, 12, 12) && c > 0.99));
+
+        // Target leaves: lost on frame 2, detector finds nothing
+        assert_eq!(tracker.update(&frame_with_target(None)).unwrap(), TrackState::Lost);
+        assert!(tracker.is_lost());
+        assert_eq!(lost_at.get(), Some((2, Rect::new(12, 11, 12, 12))));
+
+        // Target reappears far away; frame 3 is skipped by the interval,
+        // frame 4 runs the detector
+        let far = frame_with_target(Some((45, 40)));
+        assert_eq!(tracker.update(&far).unwrap(), TrackState::Lost);
+        assert_eq!(
+            tracker.update(&far).unwrap(),
+            TrackState::Redetected { bbox: Rect::new(45, 40, 12, 12) }
+        );
+        assert_eq!(*recovered.borrow(), [(4, Rect::new(45, 40, 12, 12))]);
+
+        let state = tracker.update(&frame_with_target(Some((46, 41)))).unwrap();
+        assert!(matches!(state, TrackState::Tracking { bbox, .. } if bbox == Rect::new(46, 41, 12, 12)));
+    }
+
+    #[test]
+    fn test_long_term_tracker_without_redetector() {
+        let mut tracker = LongTermTracker::new(TrackerNCC::new());
+        assert!(tracker.update(&frame_with_target(None)).is_err());
+
+        tracker.init(&frame_with_target(Some((10, 10))), Rect::new(10, 10, 12, 12)).unwrap();
+        assert_eq!(tracker.update(&frame_with_target(None)).unwrap(), TrackState::Lost);
+        assert_eq!(tracker.update(&frame_with_target(Some((10, 10)))).unwrap(), TrackState::Lost);
+        assert_eq!(tracker.last_bbox(), Some(Rect::new(10, 10, 12, 12)));
+    }
+}
@@ -3,6 +3,15 @@ pub mod tracking;
 pub mod camshift;
 pub mod background_subtraction;
 pub mod advanced_tracking;
+pub mod flow_visualization;
+pub mod long_term_tracking;
+pub mod frame_interpolation;
+pub mod scene_detection;
+pub mod motion_compensation;
+pub mod temporal_filter;
+pub mod change_detection;
+pub mod annotator;
+pub mod dic;
 
 pub use optical_flow::*;
 pub use tracking::*;
@@ -10,3 +19,12 @@ pub use camshift::*;
 // Export BackgroundSubtractorKNN from background_subtraction, MOG2 from tracking
 pub use background_subtraction::BackgroundSubtractorKNN;
 pub use advanced_tracking::*;
+pub use flow_visualization::*;
+pub use long_term_tracking::*;
+pub use frame_interpolation::*;
+pub use scene_detection::*;
+pub use motion_compensation::MotionCompensator;
+pub use temporal_filter::{RunningAverage, TemporalMedian};
+pub use change_detection::{AlarmZone, ChangeDetector, ChangeEvent, ChangeEventKind};
+pub use annotator::{Annotator, OsdCorner};
+pub use dic::{DicPoint, DigitalImageCorrelation, DisplacementField};
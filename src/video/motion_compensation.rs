@@ -0,0 +1,238 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss)]
+use crate::calib3d::homography::estimate_affine_2d;
+use crate::calib3d::robust::{Estimator, RobustMethod};
+use crate::core::types::Point2f;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::features2d::good_features_to_track;
+use crate::imgproc::{build_gaussian_pyramid, to_gray};
+use super::optical_flow::track_points_lk;
+
+/// Tracks needed before a global motion estimate is trusted
+const MIN_TRACKS: usize = 8;
+
+/// Global camera motion between consecutive frames
+///
+/// Corners of the previous frame are tracked into the current one with
+/// pyramidal Lucas-Kanade, and a RANSAC affine fit over the tracks gives the
+/// camera motion while ignoring independently moving objects. Background
+/// subtractors use it, via their `with_motion_compensation` option, to
+/// re-align their model before each update so that handheld or drone footage
+/// doesn't flood the foreground mask.
+#[derive(Debug, Clone)]
+pub struct MotionCompensator {
+    pub max_corners: usize,
+    /// Minimum spacing between tracked corners, in pixels
+    pub min_distance: f64,
+    /// Pyramid levels; each doubles the largest trackable motion
+    pub levels: usize,
+    /// Lucas-Kanade window half-size
+    pub half_window: usize,
+    /// RANSAC inlier threshold, in pixels
+    pub ransac_threshold: f64,
+    previous: Option<(Mat, Vec<Mat>)>,
+}
+
+impl Default for MotionCompensator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MotionCompensator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_corners: 200,
+            min_distance: 8.0,
+            levels: 3,
+            half_window: 7,
+            ransac_threshold: 1.0,
+            previous: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_max_corners(mut self, max_corners: usize) -> Self {
+        self.max_corners = max_corners;
+        self
+    }
+
+    #[must_use]
+    pub fn with_levels(mut self, levels: usize) -> Self {
+        self.levels = levels;
+        self
+    }
+
+    #[must_use]
+    pub fn with_ransac_threshold(mut self, threshold: f64) -> Self {
+        self.ransac_threshold = threshold;
+        self
+    }
+
+    /// Forget the previous frame, e.g. after a scene cut
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Feed the next frame and estimate its motion relative to the previous
+    /// one
+    ///
+    /// `frame` is 1- or 3-channel U8; colour frames are converted by their
+    /// [`ColorOrder`](crate::core::types::ColorOrder) tag. The returned affine transform maps
+    /// pixel coordinates of `frame` to where the same scene point was in the
+    /// previous frame, which is the destination-to-source convention of
+    /// [`warp_affine`](crate::imgproc::warp_affine). Returns `None` for the
+    /// first frame, a frame of a different size, or when too few corners
+    /// could be tracked to estimate the motion.
+    pub fn estimate(&mut self, frame: &Mat) -> Result<Option<[[f64; 3]; 2]>> {
+        if frame.depth() != MatDepth::U8 || !matches!(frame.channels(), 1 | 3) {
+            return Err(Error::UnsupportedOperation(
+                "Motion compensation requires a 1- or 3-channel U8 frame".to_string(),
+            ));
+        }
+        let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+        to_gray(frame, &mut gray)?;
+        let pyramid = build_gaussian_pyramid(&gray, self.levels.max(1))?;
+
+        let transform = match self.previous.take() {
+            Some((prev_gray, prev_pyramid))
+                if prev_gray.rows() == gray.rows() && prev_gray.cols() == gray.cols() && prev_pyramid.len() == pyramid.len() =>
+            {
+                self.track(&prev_gray, &prev_pyramid, &pyramid)?
+            }
+            _ => None,
+        };
+        self.previous = Some((gray, pyramid));
+        Ok(transform)
+    }
+
+    fn track(&self, prev_gray: &Mat, prev_pyramid: &[Mat], pyramid: &[Mat]) -> Result<Option<[[f64; 3]; 2]>> {
+        let corners: Vec<Point2f> = good_features_to_track(prev_gray, self.max_corners, 1e3, self.min_distance, 3)?
+            .iter()
+            .map(|kp| Point2f::new(kp.pt.x as f32, kp.pt.y as f32))
+            .collect();
+        let tracked = track_points_lk(prev_pyramid, pyramid, &corners, self.half_window)?;
+
+        let (current, previous): (Vec<Point2f>, Vec<Point2f>) = tracked
+            .iter()
+            .zip(&corners)
+            .filter_map(|(t, &c)| t.map(|t| (t, c)))
+            .unzip();
+        if current.len() < MIN_TRACKS {
+            return Ok(None);
+        }
+        let estimator = Estimator::new(RobustMethod::Ransac).with_threshold(self.ransac_threshold);
+        Ok(estimate_affine_2d(&current, &previous, &estimator)
+            .ok()
+            .filter(|estimate| estimate.inlier_count() >= MIN_TRACKS)
+            .map(|estimate| estimate.model))
+    }
+}
+
+/// For each pixel of a `rows` x `cols` frame, the nearest pixel of the
+/// previous frame under `transform` (as returned by
+/// [`MotionCompensator::estimate`]), or `None` where it falls outside
+pub(crate) fn source_pixels(rows: usize, cols: usize, transform: &[[f64; 3]; 2]) -> Vec<Option<(usize, usize)>> {
+    let mut sources = Vec::with_capacity(rows * cols);
+    for row in 0..rows {
+        for col in 0..cols {
+            let (x, y) = (col as f64, row as f64);
+            let sx = (transform[0][0] * x + transform[0][1] * y + transform[0][2]).round();
+            let sy = (transform[1][0] * x + transform[1][1] * y + transform[1][2]).round();
+            let inside = sx >= 0.0 && sy >= 0.0 && sx < cols as f64 && sy < rows as f64;
+            sources.push(inside.then_some((sy as usize, sx as usize)));
+        }
+    }
+    sources
+}
+
+/// Re-align a per-pixel `[row][col]` background model with the current
+/// frame, seeding pixels that just came into view with `seed(row, col)`
+pub(crate) fn remap_model<T: Clone>(
+    model: &mut Vec<Vec<T>>,
+    sources: &[Option<(usize, usize)>],
+    mut seed: impl FnMut(usize, usize) -> T,
+) {
+    let cols = model.first().map_or(0, Vec::len);
+    let remapped = (0..model.len())
+        .map(|row| {
+            (0..cols)
+                .map(|col| match sources[row * cols + col] {
+                    Some((sr, sc)) => model[sr][sc].clone(),
+                    None => seed(row, col),
+                })
+                .collect()
+        })
+        .collect();
+    *model = remapped;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imgproc::noise::SplitMix64;
+
+    /// Blocky random texture, sampled with a sub-pixel offset
+    fn textured_frame(rows: usize, cols: usize, dx: f64, dy: f64) -> Mat {
+        let mut rng = SplitMix64::new(3);
+        let blocks: Vec<f64> = (0..64 * 64).map(|_| rng.next_f64() * 200.0 + 20.0).collect();
+        let texture = |x: f64, y: f64| {
+            let (bx, by) = ((x / 8.0).floor() as usize % 64, (y / 8.0).floor() as usize % 64);
+            blocks[by * 64 + bx]
+        };
+        let mut frame = Mat::new(rows, cols, 1, MatDepth::U8).unwrap();
+        for row in 0..rows {
+            for col in 0..cols {
+                // 4x4 supersampling gives anti-aliased block edges
+                let mut sum = 0.0;
+                for s in 0..16 {
+                    let (ox, oy) = (f64::from(s % 4) * 0.25 - 0.375, f64::from(s / 4) * 0.25 - 0.375);
+                    sum += texture(col as f64 + ox + dx + 100.0, row as f64 + oy + dy + 100.0);
+                }
+                frame.at_mut(row, col).unwrap()[0] = (sum / 16.0).round() as u8;
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn test_motion_compensator_translation() {
+        let mut compensator = MotionCompensator::new();
+        assert!(compensator.estimate(&textured_frame(96, 128, 0.0, 0.0)).unwrap().is_none());
+
+        // The camera pans, so the scene moves by (-3.4, 1.7) in the image
+        let transform = compensator.estimate(&textured_frame(96, 128, 3.4, -1.7)).unwrap().unwrap();
+        assert!((transform[0][2] - 3.4).abs() < 0.15, "{transform:?}");
+        assert!((transform[1][2] + 1.7).abs() < 0.15, "{transform:?}");
+        assert!((transform[0][0] - 1.0).abs() < 0.01 && transform[0][1].abs() < 0.01);
+
+        // A frame of a different size starts over
+        assert!(compensator.estimate(&textured_frame(64, 64, 0.0, 0.0)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_motion_compensator_bgr_tagged() {
+        use crate::core::types::ColorOrder;
+
+        let bgr = |dx, dy| {
+            let gray = textured_frame(96, 128, dx, dy);
+            let data = gray.data().iter().flat_map(|&v| [v / 2, v / 2, v]).collect();
+            Mat::from_raw(data, 96, 128, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr)
+        };
+        let mut compensator = MotionCompensator::new();
+        assert!(compensator.estimate(&bgr(0.0, 0.0)).unwrap().is_none());
+        let transform = compensator.estimate(&bgr(2.0, 1.0)).unwrap().unwrap();
+        assert!((transform[0][2] - 2.0).abs() < 0.15, "{transform:?}");
+        assert!((transform[1][2] - 1.0).abs() < 0.15, "{transform:?}");
+    }
+
+    #[test]
+    fn test_remap_model() {
+        let mut model = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        // Current pixel (row, col) shows previous pixel (row, col + 1)
+        let sources = source_pixels(2, 3, &[[1.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+        remap_model(&mut model, &sources, |_, _| 0);
+        assert_eq!(model, vec![vec![2, 3, 0], vec![5, 6, 0]]);
+    }
+}
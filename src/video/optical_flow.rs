@@ -1,5 +1,5 @@
 use crate::core::{Mat, MatDepth};
-use crate::core::types::{Point, Size};
+use crate::core::types::{Point, Point2f, Size};
 use crate::error::{Error, Result};
 
 /// Calculate optical flow using Lucas-Kanade method
@@ -118,6 +118,9 @@ fn window_ssd(
 }
 
 /// Calculate dense optical flow using Farneback method (simplified)
+///
+/// Returns a 2-channel F32 Mat holding the `(dx, dy)` displacement of each
+/// pixel; see [`draw_flow_hsv`](super::draw_flow_hsv) to visualize it.
 pub fn calc_optical_flow_farneback(
     prev: &Mat,
     next: &Mat,
@@ -139,7 +142,7 @@ pub fn calc_optical_flow_farneback(
     }
 
     // Create flow matrix (2 channels for x and y flow)
-    let mut flow = Mat::new(prev.rows(), prev.cols(), 2, MatDepth::U8)?;
+    let mut flow = Mat::new(prev.rows(), prev.cols(), 2, MatDepth::F32)?;
 
     // Simplified Farneback - calculate gradients
     use crate::imgproc::sobel;
@@ -199,19 +202,134 @@ pub fn calc_optical_flow_farneback(
                 }
             }
 
-            let flow_pixel = flow.at_mut(row, col)?;
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let dx_u8 = (best_dx + 128).clamp(0, 255) as u8;
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let dy_u8 = (best_dy + 128).clamp(0, 255) as u8;
-            flow_pixel[0] = dx_u8;
-            flow_pixel[1] = dy_u8;
+            #[allow(clippy::cast_precision_loss)]
+            {
+                flow.set_f32(row, col, 0, best_dx as f32)?;
+                flow.set_f32(row, col, 1, best_dy as f32)?;
+            }
         }
     }
 
     Ok(flow)
 }
 
+/// Track `points` from `prev` to `next` with iterative pyramidal
+/// Lucas-Kanade at sub-pixel precision
+///
+/// Both pyramids come from
+/// [`build_gaussian_pyramid`](crate::imgproc::build_gaussian_pyramid) with the same number
+/// of levels. Each point is refined coarse to fine over a
+/// `(2 * half_win + 1)²` window, replicating the image border where the
+/// window sticks out; `None` marks points that left the image or sit on
+/// texture too flat to track.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_precision_loss, clippy::cast_sign_loss)]
+pub(crate) fn track_points_lk(prev: &[Mat], next: &[Mat], points: &[Point2f], half_win: usize) -> Result<Vec<Option<Point2f>>> {
+    if prev.len() != next.len() || prev.is_empty() {
+        return Err(Error::InvalidParameter(
+            "Pyramids must be non-empty and have the same number of levels".to_string(),
+        ));
+    }
+    let prev: Vec<Plane> = prev.iter().map(Plane::new).collect::<Result<_>>()?;
+    let next: Vec<Plane> = next.iter().map(Plane::new).collect::<Result<_>>()?;
+    let half = half_win as isize;
+    let window = (2 * half_win + 1).pow(2);
+
+    Ok(points
+        .iter()
+        .map(|pt| {
+            let mut flow = (0.0f32, 0.0f32);
+            for level in (0..prev.len()).rev() {
+                let scale = (1u32 << level) as f32;
+                let (px, py) = (pt.x / scale, pt.y / scale);
+                let (a, b) = (&prev[level], &next[level]);
+
+                // Template and its gradient, fixed over the iterations
+                let mut template = Vec::with_capacity(window);
+                let (mut gxx, mut gxy, mut gyy) = (0.0f32, 0.0f32, 0.0f32);
+                for dy in -half..=half {
+                    for dx in -half..=half {
+                        let (x, y) = (px + dx as f32, py + dy as f32);
+                        let v = a.sample(x, y);
+                        let (l, r) = (a.sample(x - 1.0, y), a.sample(x + 1.0, y));
+                        let (u, d) = (a.sample(x, y - 1.0), a.sample(x, y + 1.0));
+                        let (ix, iy) = (0.5 * (r - l), 0.5 * (d - u));
+                        gxx += ix * ix;
+                        gxy += ix * iy;
+                        gyy += iy * iy;
+                        template.push((v, ix, iy));
+                    }
+                }
+                let det = gxx * gyy - gxy * gxy;
+                let min_eigen = 0.5 * (gxx + gyy - ((gxx - gyy).powi(2) + 4.0 * gxy * gxy).sqrt());
+                if det.abs() < f32::EPSILON || min_eigen / (window as f32) < 1e-3 {
+                    return None;
+                }
+
+                for _ in 0..20 {
+                    let (mut bx, mut by) = (0.0f32, 0.0f32);
+                    let mut samples = template.iter();
+                    for dy in -half..=half {
+                        for dx in -half..=half {
+                            let &(v, ix, iy) = samples.next()?;
+                            let w = b.sample(px + flow.0 + dx as f32, py + flow.1 + dy as f32);
+                            bx += (v - w) * ix;
+                            by += (v - w) * iy;
+                        }
+                    }
+                    let step = ((gyy * bx - gxy * by) / det, (gxx * by - gxy * bx) / det);
+                    flow = (flow.0 + step.0, flow.1 + step.1);
+                    if step.0.hypot(step.1) < 0.01 {
+                        break;
+                    }
+                }
+                if level > 0 {
+                    flow = (flow.0 * 2.0, flow.1 * 2.0);
+                }
+            }
+            let tracked = Point2f::new(pt.x + flow.0, pt.y + flow.1);
+            next[0].contains(tracked.x, tracked.y).then_some(tracked)
+        })
+        .collect())
+}
+
+/// 1-channel F32 image unpacked for bilinear sampling
+struct Plane {
+    data: Vec<f32>,
+    rows: usize,
+    cols: usize,
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss)]
+impl Plane {
+    fn new(mat: &Mat) -> Result<Self> {
+        if mat.channels() != 1 || mat.depth() != MatDepth::F32 {
+            return Err(Error::UnsupportedOperation(
+                "Pyramid levels must be 1-channel F32".to_string(),
+            ));
+        }
+        let data = (0..mat.rows() * mat.cols())
+            .map(|i| mat.at_f32(i / mat.cols(), i % mat.cols(), 0))
+            .collect::<Result<_>>()?;
+        Ok(Self { data, rows: mat.rows(), cols: mat.cols() })
+    }
+
+    fn contains(&self, x: f32, y: f32) -> bool {
+        x >= 0.0 && y >= 0.0 && x <= (self.cols - 1) as f32 && y <= (self.rows - 1) as f32
+    }
+
+    /// Bilinear sample, replicating the border
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        let (x, y) = (x.clamp(0.0, (self.cols - 1) as f32), y.clamp(0.0, (self.rows - 1) as f32));
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.cols - 1), (y0 + 1).min(self.rows - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+        let at = |r: usize, c: usize| self.data[r * self.cols + c];
+        let top = at(y0, x0) + fx * (at(y0, x1) - at(y0, x0));
+        let bottom = at(y1, x0) + fx * (at(y1, x1) - at(y1, x0));
+        top + fy * (bottom - top)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
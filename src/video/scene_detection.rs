@@ -0,0 +1,348 @@
+#![allow(clippy::cast_precision_loss)]
+//! Shot boundary detection over a frame stream
+//!
+//! [`SceneDetector`] compares each frame with the previous one. Hard cuts are
+//! flagged when the intensity histograms differ and the edge change ratio
+//! (Zabih, Miller & Mai, 1995) confirms that the structure of the image
+//! changed too. Fades are recognised as runs of frames that are scaled
+//! copies of each other and reported when the stream reaches or leaves black.
+
+use crate::core::types::Size;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+use crate::imgproc::{calc_hist, canny, compare_hist, dilate, get_structuring_element, HistCompMethod, MorphShape, to_gray};
+
+/// Kind of a [`SceneChange`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneChangeKind {
+    /// Abrupt transition between two shots
+    Cut,
+    /// The picture has faded to black
+    FadeOut,
+    /// The picture is fading in from black
+    FadeIn,
+}
+
+/// A shot boundary found by [`SceneDetector`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneChange {
+    /// Index of the first frame after the boundary, counting from 0
+    pub frame: usize,
+    pub kind: SceneChangeKind,
+    /// Bhattacharyya distance between the gray histograms of the two frames
+    pub histogram_distance: f64,
+    /// Fraction of edges that appeared or disappeared, in `[0, 1]`
+    pub edge_change_ratio: f64,
+}
+
+type ChangeCallback = Box<dyn FnMut(&SceneChange)>;
+
+/// Per-frame features kept from the previous frame
+struct FrameFeatures {
+    gray: Mat,
+    histogram: Vec<f32>,
+    edges: Mat,
+    dilated_edges: Mat,
+    mean: f64,
+}
+
+/// Streaming cut and fade detector
+///
+/// Feed frames in order with [`process`](Self::process), or wrap a frame
+/// iterator with [`detect`](Self::detect) to iterate over the boundaries
+/// only. Frames are U8 with 1 or 3 channels; colour frames are converted by their
+/// [`ColorOrder`](crate::core::types::ColorOrder) tag.
+pub struct SceneDetector {
+    histogram_threshold: f64,
+    edge_threshold: f64,
+    black_threshold: f64,
+    min_scene_length: usize,
+    callbacks: Vec<ChangeCallback>,
+    previous: Option<FrameFeatures>,
+    frame: usize,
+    last_cut: Option<usize>,
+}
+
+impl Default for SceneDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneDetector {
+    /// Detector with a histogram threshold of 0.5, an edge change threshold
+    /// of 0.5, black below mean intensity 20 and scenes of at least 5 frames
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            histogram_threshold: 0.5,
+            edge_threshold: 0.5,
+            black_threshold: 20.0,
+            min_scene_length: 5,
+            callbacks: Vec::new(),
+            previous: None,
+            frame: 0,
+            last_cut: None,
+        }
+    }
+
+    /// Bhattacharyya histogram distance (as returned by [`compare_hist`])
+    /// above which a frame pair is a cut candidate
+    #[must_use]
+    pub fn with_histogram_threshold(mut self, threshold: f64) -> Self {
+        self.histogram_threshold = threshold;
+        self
+    }
+
+    /// Edge change ratio a cut candidate must reach; 0 accepts every
+    /// histogram-based candidate
+    #[must_use]
+    pub fn with_edge_threshold(mut self, threshold: f64) -> Self {
+        self.edge_threshold = threshold;
+        self
+    }
+
+    /// Mean gray level below which a frame counts as black for fades
+    #[must_use]
+    pub fn with_black_threshold(mut self, level: f64) -> Self {
+        self.black_threshold = level;
+        self
+    }
+
+    /// Minimum number of frames between two cuts; closer candidates, such
+    /// as camera flashes, are ignored
+    #[must_use]
+    pub fn with_min_scene_length(mut self, frames: usize) -> Self {
+        self.min_scene_length = frames;
+        self
+    }
+
+    /// Call `callback` for every detected change
+    #[must_use]
+    pub fn on_change(mut self, callback: impl FnMut(&SceneChange) + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Process the next frame and return the boundary it starts, if any
+    pub fn process(&mut self, frame: &Mat) -> Result<Option<SceneChange>> {
+        let features = Self::features(frame)?;
+        let index = self.frame;
+        self.frame += 1;
+
+        let change = match self.previous.as_ref() {
+            Some(previous) => self.compare(previous, &features, index)?,
+            None => None,
+        };
+        self.previous = Some(features);
+
+        if let Some(change) = &change {
+            if change.kind == SceneChangeKind::Cut {
+                self.last_cut = Some(index);
+            }
+            for callback in &mut self.callbacks {
+                callback(change);
+            }
+        }
+        Ok(change)
+    }
+
+    /// Iterate over the boundaries in `frames`
+    pub fn detect<I>(&mut self, frames: I) -> SceneChanges<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = Mat>,
+    {
+        SceneChanges { detector: self, frames: frames.into_iter() }
+    }
+
+    /// Forget the previous frame and restart frame numbering at 0
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.frame = 0;
+        self.last_cut = None;
+    }
+
+    fn compare(&self, previous: &FrameFeatures, current: &FrameFeatures, index: usize) -> Result<Option<SceneChange>> {
+        if previous.gray.rows() != current.gray.rows() || previous.gray.cols() != current.gray.cols() {
+            return Err(Error::InvalidDimensions(
+                "All frames must have the same size".to_string(),
+            ));
+        }
+
+        let histogram_distance = compare_hist(&previous.histogram, &current.histogram, HistCompMethod::Bhattacharyya)?;
+        let edge_change_ratio = edge_change_ratio(previous, current);
+        let change = |kind| Some(SceneChange { frame: index, kind, histogram_distance, edge_change_ratio });
+
+        let was_black = previous.mean < self.black_threshold;
+        let is_black = current.mean < self.black_threshold;
+        match (was_black, is_black) {
+            (false, true) => return Ok(change(SceneChangeKind::FadeOut)),
+            (true, false) => return Ok(change(SceneChangeKind::FadeIn)),
+            (true, true) => return Ok(None),
+            (false, false) => {}
+        }
+
+        let is_cut = histogram_distance > self.histogram_threshold
+            && edge_change_ratio >= self.edge_threshold
+            && !is_fade_step(previous, current)
+            && self.last_cut.is_none_or(|last| index - last >= self.min_scene_length);
+        Ok(if is_cut { change(SceneChangeKind::Cut) } else { None })
+    }
+
+    fn features(frame: &Mat) -> Result<FrameFeatures> {
+        if frame.depth() != MatDepth::U8 || !matches!(frame.channels(), 1 | 3) {
+            return Err(Error::UnsupportedOperation(
+                "Scene detection requires 1- or 3-channel U8 frames".to_string(),
+            ));
+        }
+        let mut gray = Mat::new(1, 1, 1, MatDepth::U8)?;
+        to_gray(frame, &mut gray)?;
+
+        let histogram = calc_hist(&gray, 64, (0.0, 256.0))?;
+        let mut edges = Mat::new(1, 1, 1, MatDepth::U8)?;
+        canny(&gray, &mut edges, 50.0, 150.0)?;
+        let mut dilated_edges = Mat::new(1, 1, 1, MatDepth::U8)?;
+        dilate(&edges, &mut dilated_edges, &get_structuring_element(MorphShape::Rect, Size::new(5, 5)))?;
+        let pixels = gray.data().len().max(1) as f64;
+        let mean = gray.data().iter().map(|&v| f64::from(v)).sum::<f64>() / pixels;
+
+        Ok(FrameFeatures { gray, histogram, edges, dilated_edges, mean })
+    }
+}
+
+/// Larger of the fractions of entering and exiting edge pixels, where an
+/// edge pixel counts as unchanged if the other frame has an edge within two
+/// pixels
+fn edge_change_ratio(previous: &FrameFeatures, current: &FrameFeatures) -> f64 {
+    let ratio = |edges: &Mat, other_dilated: &Mat| {
+        let (mut total, mut changed) = (0usize, 0usize);
+        for (&e, &d) in edges.data().iter().zip(other_dilated.data()) {
+            if e != 0 {
+                total += 1;
+                if d == 0 {
+                    changed += 1;
+                }
+            }
+        }
+        if total == 0 { 0.0 } else { changed as f64 / total as f64 }
+    };
+    let entering = ratio(&current.edges, &previous.dilated_edges);
+    let exiting = ratio(&previous.edges, &current.dilated_edges);
+    entering.max(exiting)
+}
+
+/// Whether `current` is `previous` with its brightness scaled, as in a fade
+fn is_fade_step(previous: &FrameFeatures, current: &FrameFeatures) -> bool {
+    if previous.mean <= 0.0 {
+        return false;
+    }
+    let gain = current.mean / previous.mean;
+    if (gain - 1.0).abs() < 0.05 {
+        return false;
+    }
+    let residual: f64 = previous
+        .gray
+        .data()
+        .iter()
+        .zip(current.gray.data())
+        .map(|(&p, &c)| (f64::from(c) - gain * f64::from(p)).abs())
+        .sum::<f64>()
+        / previous.gray.data().len() as f64;
+    residual < 0.1 * current.mean
+}
+
+/// Iterator over the boundaries of a frame stream, created by
+/// [`SceneDetector::detect`]
+pub struct SceneChanges<'a, I> {
+    detector: &'a mut SceneDetector,
+    frames: I,
+}
+
+impl<I: Iterator<Item = Mat>> Iterator for SceneChanges<'_, I> {
+    type Item = Result<SceneChange>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for frame in self.frames.by_ref() {
+            match self.detector.process(&frame) {
+                Ok(Some(change)) => return Some(Ok(change)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Vertical stripes drifting right by `shift` pixels, scaled by `gain`
+    fn stripes(shift: usize, gain: f64) -> Mat {
+        let mut img = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 0..64 {
+                let v = if ((col + 64 - shift) / 8).is_multiple_of(2) { 60.0 } else { 200.0 };
+                img.at_mut(row, col).unwrap()[0] = (v * gain) as u8;
+            }
+        }
+        img
+    }
+
+    /// Checkerboard of three gray levels, scaled by `gain`
+    fn blocks(gain: f64) -> Mat {
+        let mut img = Mat::new(64, 64, 1, MatDepth::U8).unwrap();
+        for row in 0..64 {
+            for col in 0..64 {
+                let v = [30.0, 120.0, 230.0][(row / 16 + col / 16) % 3];
+                img.at_mut(row, col).unwrap()[0] = (v * gain) as u8;
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_scene_detector() {
+        let mut frames: Vec<Mat> = (0..5).map(|i| stripes(i, 1.0)).collect();
+        // Hard cut at frame 5, a single-frame flash at 7, fade out to black
+        // (frames 10-13) and fade in from black (15-17)
+        frames.extend([blocks(1.0), blocks(1.0), stripes(0, 1.0), blocks(1.0), blocks(1.0)]);
+        frames.extend([0.75, 0.5, 0.25, 0.05, 0.0].map(blocks));
+        frames.extend([0.3, 0.6, 1.0].map(|g| stripes(0, g)));
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&log);
+        let mut detector = SceneDetector::new().on_change(move |c| sink.borrow_mut().push(c.frame));
+        let changes: Vec<SceneChange> = detector.detect(frames).collect::<Result<_>>().unwrap();
+
+        let found: Vec<(usize, SceneChangeKind)> = changes.iter().map(|c| (c.frame, c.kind)).collect();
+        assert_eq!(
+            found,
+            [(5, SceneChangeKind::Cut), (13, SceneChangeKind::FadeOut), (15, SceneChangeKind::FadeIn)]
+        );
+        assert!(changes[0].edge_change_ratio > 0.5);
+        assert_eq!(*log.borrow(), [5, 13, 15]);
+
+        detector.reset();
+        assert!(detector.process(&stripes(0, 1.0)).unwrap().is_none());
+        assert!(detector.process(&Mat::new(32, 32, 1, MatDepth::U8).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_scene_detector_bgr_tagged() {
+        use crate::core::types::ColorOrder;
+
+        let gray = stripes(0, 1.0);
+        let rgb: Vec<u8> = gray.data().iter().flat_map(|&v| [v, v / 2, 0]).collect();
+        let bgr: Vec<u8> = gray.data().iter().flat_map(|&v| [0, v / 2, v]).collect();
+        let rgb = Mat::from_raw(rgb, 64, 64, 3, MatDepth::U8).unwrap();
+        let bgr = Mat::from_raw(bgr, 64, 64, 3, MatDepth::U8).unwrap().with_color_order(ColorOrder::Bgr);
+
+        let expected = SceneDetector::features(&rgb).unwrap();
+        let features = SceneDetector::features(&bgr).unwrap();
+        assert_eq!(features.gray.data(), expected.gray.data());
+        assert!(SceneDetector::new().process(&bgr).unwrap().is_none());
+    }
+}
@@ -0,0 +1,282 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+//! Temporal denoising filters for video streams
+//!
+//! Static parts of a scene carry the same signal in every frame while sensor
+//! noise changes, so combining each pixel over time removes noise without
+//! the blur of single-frame denoisers. Both filters keep their state between
+//! calls and take an optional motion mask: pixels flagged as moving pass
+//! through unfiltered and restart their history, so moving objects neither
+//! smear nor leave ghost trails.
+
+use std::collections::VecDeque;
+
+use crate::core::parallel::parallel_for_rows;
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Per-pixel median over the last `window` frames
+///
+/// Removes impulsive noise and briefly passing objects better than an
+/// average; the output lags scene changes by about half the window.
+#[derive(Debug, Clone)]
+pub struct TemporalMedian {
+    window: usize,
+    history: VecDeque<Vec<u8>>,
+    layout: Option<(usize, usize, usize)>,
+}
+
+impl TemporalMedian {
+    /// Filter over the last `window` frames (at least 1)
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        Self { window: window.max(1), history: VecDeque::new(), layout: None }
+    }
+
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Number of frames currently held, up to `window`
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Forget all frames, e.g. after a scene cut
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.layout = None;
+    }
+
+    /// Add `frame` and write the median of the held frames to `dst`
+    ///
+    /// `frame` is U8 with any number of channels; a frame of a different
+    /// layout than the previous ones restarts the history. `motion_mask`, if
+    /// given, is a 1-channel U8 Mat of the frame's size whose non-zero
+    /// pixels are treated as moving.
+    pub fn apply(&mut self, frame: &Mat, motion_mask: Option<&Mat>, dst: &mut Mat) -> Result<()> {
+        let layout = check_frame(frame, motion_mask)?;
+        if self.layout != Some(layout) {
+            self.reset();
+            self.layout = Some(layout);
+        }
+
+        let current = frame.data().to_vec();
+        if let Some(mask) = motion_mask {
+            let channels = frame.channels();
+            for past in &mut self.history {
+                restart_moving(past, &current, mask.data(), channels);
+            }
+        }
+        if self.history.len() == self.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(current);
+
+        let (rows, cols, channels) = layout;
+        dst.create(rows, cols, channels, MatDepth::U8)?;
+        dst.set_color_order(frame.color_order());
+        let history = &self.history;
+        parallel_for_rows(dst.data_mut(), cols * channels, |row, out| {
+            let start = row * out.len();
+            let mut values = Vec::with_capacity(history.len());
+            for (i, o) in out.iter_mut().enumerate() {
+                values.clear();
+                values.extend(history.iter().map(|past| past[start + i]));
+                let mid = values.len() / 2;
+                *o = *values.select_nth_unstable(mid).1;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Exponential running average `acc = (1 - alpha) * acc + alpha * frame`
+///
+/// Cheap and memory-light: noise variance drops by `alpha / (2 - alpha)` on
+/// static pixels, at the cost of trailing behind changes.
+#[derive(Debug, Clone)]
+pub struct RunningAverage {
+    alpha: f32,
+    accumulator: Vec<f32>,
+    layout: Option<(usize, usize, usize)>,
+}
+
+impl RunningAverage {
+    /// Average with weight `alpha` in (0, 1] for the newest frame
+    pub fn new(alpha: f64) -> Result<Self> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(Error::InvalidParameter(
+                "Running average alpha must be in (0, 1]".to_string(),
+            ));
+        }
+        Ok(Self { alpha: alpha as f32, accumulator: Vec::new(), layout: None })
+    }
+
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        f64::from(self.alpha)
+    }
+
+    /// Forget the accumulated frames
+    pub fn reset(&mut self) {
+        self.accumulator.clear();
+        self.layout = None;
+    }
+
+    /// Blend `frame` into the average and write the result to `dst`
+    ///
+    /// Accepts the same frames and masks as [`TemporalMedian::apply`]. The
+    /// first frame (and the first after a layout change) initializes the
+    /// average.
+    pub fn apply(&mut self, frame: &Mat, motion_mask: Option<&Mat>, dst: &mut Mat) -> Result<()> {
+        let layout = check_frame(frame, motion_mask)?;
+        let (rows, cols, channels) = layout;
+        let data = frame.data();
+
+        if self.layout == Some(layout) {
+            let alpha = self.alpha;
+            let mask = motion_mask.map(Mat::data);
+            parallel_for_rows(&mut self.accumulator, cols * channels, |row, acc| {
+                let start = row * acc.len();
+                for (i, a) in acc.iter_mut().enumerate() {
+                    let value = f32::from(data[start + i]);
+                    let moving = mask.is_some_and(|m| m[(start + i) / channels] != 0);
+                    *a = if moving { value } else { *a + alpha * (value - *a) };
+                }
+                Ok(())
+            })?;
+        } else {
+            self.accumulator = data.iter().map(|&v| f32::from(v)).collect();
+            self.layout = Some(layout);
+        }
+
+        dst.create(rows, cols, channels, MatDepth::U8)?;
+        dst.set_color_order(frame.color_order());
+        for (o, a) in dst.data_mut().iter_mut().zip(&self.accumulator) {
+            *o = a.round().clamp(0.0, 255.0) as u8;
+        }
+        Ok(())
+    }
+}
+
+/// Validate a frame and its optional motion mask, returning its layout
+fn check_frame(frame: &Mat, motion_mask: Option<&Mat>) -> Result<(usize, usize, usize)> {
+    if frame.depth() != MatDepth::U8 || frame.is_empty() {
+        return Err(Error::UnsupportedOperation(
+            "Temporal filters require a non-empty U8 frame".to_string(),
+        ));
+    }
+    if let Some(mask) = motion_mask {
+        if mask.depth() != MatDepth::U8 || mask.channels() != 1 {
+            return Err(Error::InvalidParameter(
+                "Motion mask must be a 1-channel U8 Mat".to_string(),
+            ));
+        }
+        if mask.rows() != frame.rows() || mask.cols() != frame.cols() {
+            return Err(Error::InvalidDimensions(
+                "Motion mask must match the frame size".to_string(),
+            ));
+        }
+    }
+    Ok((frame.rows(), frame.cols(), frame.channels()))
+}
+
+/// Overwrite the moving pixels of a past frame with the current values
+fn restart_moving(past: &mut [u8], current: &[u8], mask: &[u8], channels: usize) {
+    for ((p, c), &m) in past.chunks_exact_mut(channels).zip(current.chunks_exact(channels)).zip(mask) {
+        if m != 0 {
+            p.copy_from_slice(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A flat 100-gray 3-channel frame with deterministic noise
+    fn noisy_frame(seed: usize) -> Mat {
+        let mut frame = Mat::new(16, 16, 3, MatDepth::U8).unwrap();
+        for (i, v) in frame.data_mut().iter_mut().enumerate() {
+            let noise = ((i * 31 + seed * 17) % 21) as i32 - 10;
+            *v = (100 + noise) as u8;
+        }
+        frame
+    }
+
+    fn mean_abs_error(mat: &Mat, value: u8) -> f64 {
+        mat.data().iter().map(|&v| f64::from(v.abs_diff(value))).sum::<f64>() / mat.data().len() as f64
+    }
+
+    #[test]
+    fn test_temporal_median() {
+        let mut filter = TemporalMedian::new(5);
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        for seed in 0..8 {
+            filter.apply(&noisy_frame(seed), None, &mut dst).unwrap();
+        }
+        assert_eq!(filter.frame_count(), 5);
+        assert_eq!((dst.rows(), dst.cols(), dst.channels()), (16, 16, 3));
+        assert!(mean_abs_error(&dst, 100) < mean_abs_error(&noisy_frame(7), 100) / 2.0);
+
+        // A single bright frame is rejected entirely
+        let mut flash = Mat::new(16, 16, 3, MatDepth::U8).unwrap();
+        flash.data_mut().fill(255);
+        filter.apply(&flash, None, &mut dst).unwrap();
+        assert!(dst.data().iter().all(|&v| v < 120));
+
+        // A new frame size restarts the history
+        filter.apply(&Mat::new(4, 4, 1, MatDepth::U8).unwrap(), None, &mut dst).unwrap();
+        assert_eq!((filter.frame_count(), dst.rows()), (1, 4));
+    }
+
+    #[test]
+    fn test_running_average() {
+        assert!(RunningAverage::new(0.0).is_err());
+        let mut filter = RunningAverage::new(0.2).unwrap();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        for seed in 0..30 {
+            filter.apply(&noisy_frame(seed), None, &mut dst).unwrap();
+        }
+        assert!(mean_abs_error(&dst, 100) < mean_abs_error(&noisy_frame(29), 100) / 2.0);
+    }
+
+    #[test]
+    fn test_motion_mask_passes_moving_pixels() {
+        let mut mask = Mat::new(16, 16, 1, MatDepth::U8).unwrap();
+        for col in 0..8 {
+            mask.at_mut(3, col).unwrap()[0] = 255;
+        }
+        let mut moved = noisy_frame(1);
+        for col in 0..8 {
+            moved.at_mut(3, col).unwrap().copy_from_slice(&[250, 10, 30]);
+        }
+
+        let mut median = TemporalMedian::new(5);
+        let mut average = RunningAverage::new(0.1).unwrap();
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        for seed in 2..6 {
+            median.apply(&noisy_frame(seed), None, &mut dst).unwrap();
+            average.apply(&noisy_frame(seed), None, &mut dst).unwrap();
+        }
+
+        median.apply(&moved, Some(&mask), &mut dst).unwrap();
+        assert_eq!(dst.at(3, 4).unwrap(), &[250, 10, 30]);
+        assert!(dst.at(4, 4).unwrap()[0] < 120);
+        average.apply(&moved, Some(&mask), &mut dst).unwrap();
+        assert_eq!(dst.at(3, 4).unwrap(), &[250, 10, 30]);
+
+        // Without a mask the moving object is suppressed
+        let mut plain = TemporalMedian::new(5);
+        for seed in 2..6 {
+            plain.apply(&noisy_frame(seed), None, &mut dst).unwrap();
+        }
+        plain.apply(&moved, None, &mut dst).unwrap();
+        assert!(dst.at(3, 4).unwrap()[0] < 120);
+
+        let wrong = Mat::new(8, 8, 1, MatDepth::U8).unwrap();
+        assert!(median.apply(&moved, Some(&wrong), &mut dst).is_err());
+    }
+}
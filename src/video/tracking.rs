@@ -1,12 +1,17 @@
-use crate::core::{Mat, MatDepth};
-use crate::core::types::Rect;
+use crate::core::{in_range, Mat, MatDepth};
+use crate::core::types::{BorderType, ColorConversionCode, InterpolationFlag, Point, Point2f, Rect, RotatedRect, Scalar};
 use crate::error::{Error, Result};
+use crate::imgproc::color::cvt_color;
+use crate::imgproc::histogram::{calc_back_project_nd, calc_hist_nd, HistogramNd};
+use crate::imgproc::{warp_affine_with_sampler, PixelSampler};
+use super::motion_compensation::MotionCompensator;
 
 /// Background subtractor using MOG2 algorithm (simplified)
 pub struct BackgroundSubtractorMOG2 {
     history: Vec<Mat>,
     max_history: usize,
     var_threshold: f64,
+    motion: Option<MotionCompensator>,
 }
 
 impl BackgroundSubtractorMOG2 {
@@ -18,9 +23,21 @@ impl BackgroundSubtractorMOG2 {
             history: Vec::new(),
             max_history,
             var_threshold,
+            motion: None,
         }
     }
 
+    /// Compensate global camera motion before each model update
+    ///
+    /// The stored history is warped into alignment with every new frame using
+    /// the motion estimated by `compensator`, replicating edge pixels where
+    /// the view has moved past the older frames.
+    #[must_use]
+    pub fn with_motion_compensation(mut self, compensator: MotionCompensator) -> Self {
+        self.motion = Some(compensator);
+        self
+    }
+
     pub fn apply(&mut self, image: &Mat, learning_rate: f64) -> Result<Mat> {
         if image.depth() != MatDepth::U8 {
             return Err(Error::UnsupportedOperation(
@@ -28,6 +45,17 @@ impl BackgroundSubtractorMOG2 {
             ));
         }
 
+        if let Some(transform) = self.motion.as_mut().map(|m| m.estimate(image)).transpose()?.flatten() {
+            let sampler = PixelSampler::new(InterpolationFlag::Linear, BorderType::Replicate);
+            for frame in &mut self.history {
+                if frame.rows() == image.rows() && frame.cols() == image.cols() {
+                    let mut aligned = Mat::new(1, 1, 1, MatDepth::U8)?;
+                    warp_affine_with_sampler(frame, &mut aligned, &transform, image.size(), &sampler)?;
+                    *frame = aligned;
+                }
+            }
+        }
+
         // Add current frame to history
         self.history.push(image.clone_mat());
 
@@ -89,11 +117,146 @@ impl BackgroundSubtractorMOG2 {
     }
 }
 
+/// Hue/saturation histogram layout used by the colour trackers
+const HUE_BINS: usize = 30;
+const SATURATION_BINS: usize = 32;
+/// Pixels darker or greyer than this carry no reliable hue and are left out
+/// of the colour model
+const MIN_SATURATION: f64 = 60.0;
+const MIN_VALUE: f64 = 32.0;
+/// Margin around the converged window searched for `CamShift`'s size and
+/// orientation estimate
+const CAMSHIFT_MARGIN: i32 = 10;
+
+/// Move `window` to the centroid of the probability mass it covers until the
+/// shift drops below `epsilon` pixels or `max_iterations` is reached
+///
+/// The window keeps its size and is clamped to the image. Returns the final
+/// window and the number of iterations run.
+pub fn mean_shift(prob_image: &Mat, window: Rect, max_iterations: usize, epsilon: f64) -> Result<(Rect, usize)> {
+    if prob_image.channels() != 1 || prob_image.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "MeanShift requires single-channel U8 probability image".to_string(),
+        ));
+    }
+
+    let mut current = clamp_window(window, prob_image);
+    let mut iterations = 0;
+
+    for _ in 0..max_iterations {
+        iterations += 1;
+
+        let moments = WindowMoments::compute(prob_image, current)?;
+        if moments.m00 <= 0.0 {
+            break;
+        }
+
+        let (cx, cy) = moments.centroid();
+        #[allow(clippy::cast_possible_truncation)]
+        let moved = clamp_window(
+            Rect::new(
+                (cx - f64::from(current.width) / 2.0).round() as i32,
+                (cy - f64::from(current.height) / 2.0).round() as i32,
+                current.width,
+                current.height,
+            ),
+            prob_image,
+        );
+
+        let shift = f64::from((moved.x - current.x).pow(2) + (moved.y - current.y).pow(2)).sqrt();
+        current = moved;
+
+        if shift < epsilon {
+            break;
+        }
+    }
+
+    Ok((current, iterations))
+}
+
+/// Continuously adaptive mean shift
+///
+/// Runs [`mean_shift`], then fits the size and orientation of the tracked
+/// blob from the second-order moments around the converged window. Returns
+/// the rotated object box (width along its major axis) and the search window
+/// for the next frame, which is the box's bounding rectangle clipped to the
+/// image.
+pub fn cam_shift(prob_image: &Mat, window: Rect, max_iterations: usize, epsilon: f64) -> Result<(RotatedRect, Rect)> {
+    let (window, _) = mean_shift(prob_image, window, max_iterations, epsilon)?;
+
+    let search = clamp_window(
+        Rect::new(
+            window.x - CAMSHIFT_MARGIN,
+            window.y - CAMSHIFT_MARGIN,
+            window.width + 2 * CAMSHIFT_MARGIN,
+            window.height + 2 * CAMSHIFT_MARGIN,
+        ),
+        prob_image,
+    );
+    let moments = WindowMoments::compute(prob_image, search)?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    if moments.m00 <= 0.0 {
+        let center = Point2f::new(
+            (f64::from(window.x) + f64::from(window.width) / 2.0) as f32,
+            (f64::from(window.y) + f64::from(window.height) / 2.0) as f32,
+        );
+        let rect = RotatedRect::new(center, window.width as f32, window.height as f32, 0.0);
+        return Ok((rect, window));
+    }
+
+    let (cx, cy) = moments.centroid();
+    let a = moments.m20 / moments.m00 - cx * cx;
+    let b = moments.m11 / moments.m00 - cx * cy;
+    let c = moments.m02 / moments.m00 - cy * cy;
+
+    // Eigen-decomposition of the covariance: theta is the major axis angle
+    let theta = (2.0 * b).atan2(a - c + (4.0 * b * b + (a - c).powi(2)).sqrt());
+    let (sin, cos) = theta.sin_cos();
+    let major = cos * cos * a + 2.0 * cos * sin * b + sin * sin * c;
+    let minor = sin * sin * a - 2.0 * cos * sin * b + cos * cos * c;
+    let length = 4.0 * major.max(0.0).sqrt();
+    let width = 4.0 * minor.max(0.0).sqrt();
+
+    #[allow(clippy::cast_possible_truncation)]
+    let rect = RotatedRect::new(
+        Point2f::new(cx as f32, cy as f32),
+        length.max(1.0) as f32,
+        width.max(1.0) as f32,
+        theta.to_degrees() as f32,
+    );
+
+    // Bounding box of the rotated rect becomes the next search window
+    let corners = rect.points();
+    let min_x = corners.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+    let max_x = corners.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+    let min_y = corners.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+    let max_y = corners.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+    #[allow(clippy::cast_possible_truncation)]
+    let next = clamp_window(
+        Rect::new(
+            min_x.floor() as i32,
+            min_y.floor() as i32,
+            (max_x - min_x).ceil().max(1.0) as i32,
+            (max_y - min_y).ceil().max(1.0) as i32,
+        ),
+        prob_image,
+    );
+
+    Ok((rect, next))
+}
+
 /// `MeanShift` tracker
+///
+/// Either feed it probability images with [`track`](Self::track), or call
+/// [`init`](Self::init) with the first RGB frame and the object's ROI to build
+/// a hue/saturation colour model, then [`update`](Self::update) with each new
+/// frame: the model is back-projected and the window shifted onto the mode.
 pub struct MeanShiftTracker {
     window: Rect,
-    max_iterations: i32,
+    max_iterations: usize,
     epsilon: f64,
+    hist: Option<HistogramNd>,
 }
 
 impl MeanShiftTracker {
@@ -103,75 +266,75 @@ impl MeanShiftTracker {
             window,
             max_iterations: 100,
             epsilon: 1.0,
+            hist: None,
         }
     }
 
-    pub fn track(&mut self, prob_image: &Mat) -> Result<Rect> {
-        if prob_image.channels() != 1 {
-            return Err(Error::InvalidParameter(
-                "MeanShift requires single-channel probability image".to_string(),
-            ));
-        }
-
-        let mut current_window = self.window;
-
-        for _ in 0..self.max_iterations {
-            // Calculate centroid of probability distribution in window
-            let mut sum_x = 0.0;
-            let mut sum_y = 0.0;
-            let mut sum_weight = 0.0;
-
-            for y in current_window.y..(current_window.y + current_window.height) {
-                for x in current_window.x..(current_window.x + current_window.width) {
-                    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
-                    let rows_i32 = prob_image.rows() as i32;
-                    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
-                    let cols_i32 = prob_image.cols() as i32;
-
-                    if y >= 0 && y < rows_i32 && x >= 0 && x < cols_i32 {
-                        #[allow(clippy::cast_sign_loss)]
-                        let y_usize = y as usize;
-                        #[allow(clippy::cast_sign_loss)]
-                        let x_usize = x as usize;
-                        let pixel = prob_image.at(y_usize, x_usize)?;
-                        let weight = f64::from(pixel[0]);
-
-                        sum_x += f64::from(x) * weight;
-                        sum_y += f64::from(y) * weight;
-                        sum_weight += weight;
-                    }
-                }
-            }
-
-            if sum_weight == 0.0 {
-                break;
-            }
+    /// Stop after `max_iterations` or once the window moves less than
+    /// `epsilon` pixels
+    #[must_use]
+    pub fn with_criteria(mut self, max_iterations: usize, epsilon: f64) -> Self {
+        self.max_iterations = max_iterations;
+        self.epsilon = epsilon;
+        self
+    }
 
-            #[allow(clippy::cast_possible_truncation)]
-            let centroid_x = (sum_x / sum_weight) as i32;
-            #[allow(clippy::cast_possible_truncation)]
-            let centroid_y = (sum_y / sum_weight) as i32;
+    /// Current search window
+    #[must_use]
+    pub fn window(&self) -> Rect {
+        self.window
+    }
 
-            // Move window to centroid
-            let new_x = centroid_x - current_window.width / 2;
-            let new_y = centroid_y - current_window.height / 2;
+    /// Hue/saturation model built by [`init`](Self::init)
+    #[must_use]
+    pub fn histogram(&self) -> Option<&HistogramNd> {
+        self.hist.as_ref()
+    }
 
-            let shift = f64::from((new_x - current_window.x).pow(2) + (new_y - current_window.y).pow(2));
+    /// Build the colour model from `roi` of a 3-channel U8 RGB frame
+    pub fn init(&mut self, frame: &Mat, roi: Rect) -> Result<()> {
+        self.hist = Some(hue_saturation_model(frame, roi)?);
+        self.window = roi;
+        Ok(())
+    }
 
-            current_window.x = new_x;
-            current_window.y = new_y;
+    /// Back-projection of the colour model onto an RGB frame
+    pub fn back_projection(&self, frame: &Mat) -> Result<Mat> {
+        let hist = self.hist.as_ref().ok_or_else(|| {
+            Error::InvalidParameter("Tracker must be initialised with init() first".to_string())
+        })?;
+        hue_saturation_back_project(frame, hist)
+    }
 
-            if shift.sqrt() < self.epsilon {
-                break;
-            }
-        }
+    /// Locate the object in a new RGB frame
+    ///
+    /// `MeanShift` does not adapt size or orientation, so the returned box is
+    /// the search window with zero angle.
+    pub fn update(&mut self, frame: &Mat) -> Result<RotatedRect> {
+        let prob = self.back_projection(frame)?;
+        let window = self.track(&prob)?;
+
+        #[allow(clippy::cast_precision_loss)]
+        let center = Point2f::new(
+            window.x as f32 + window.width as f32 / 2.0,
+            window.y as f32 + window.height as f32 / 2.0,
+        );
+        #[allow(clippy::cast_precision_loss)]
+        Ok(RotatedRect::new(center, window.width as f32, window.height as f32, 0.0))
+    }
 
-        self.window = current_window;
-        Ok(current_window)
+    /// Shift the window on a precomputed single-channel probability image
+    pub fn track(&mut self, prob_image: &Mat) -> Result<Rect> {
+        let (window, _) = mean_shift(prob_image, self.window, self.max_iterations, self.epsilon)?;
+        self.window = window;
+        Ok(window)
     }
 }
 
 /// `CamShift` tracker (continuously adaptive mean shift)
+///
+/// Same workflow as [`MeanShiftTracker`], but the window adapts to the size
+/// of the object and the result carries its orientation.
 pub struct CamShiftTracker {
     mean_shift: MeanShiftTracker,
 }
@@ -184,15 +347,155 @@ impl CamShiftTracker {
         }
     }
 
-    pub fn track(&mut self, prob_image: &Mat) -> Result<Rect> {
-        // First apply mean shift
-        let new_window = self.mean_shift.track(prob_image)?;
+    /// Stop after `max_iterations` or once the window moves less than
+    /// `epsilon` pixels
+    #[must_use]
+    pub fn with_criteria(mut self, max_iterations: usize, epsilon: f64) -> Self {
+        self.mean_shift = self.mean_shift.with_criteria(max_iterations, epsilon);
+        self
+    }
 
-        // Calculate moments to adjust window size (simplified)
-        // In full CamShift, we would calculate orientation and size from moments
+    /// Current search window
+    #[must_use]
+    pub fn window(&self) -> Rect {
+        self.mean_shift.window
+    }
+
+    /// Hue/saturation model built by [`init`](Self::init)
+    #[must_use]
+    pub fn histogram(&self) -> Option<&HistogramNd> {
+        self.mean_shift.histogram()
+    }
 
-        Ok(new_window)
+    /// Build the colour model from `roi` of a 3-channel U8 RGB frame
+    pub fn init(&mut self, frame: &Mat, roi: Rect) -> Result<()> {
+        self.mean_shift.init(frame, roi)
     }
+
+    /// Back-projection of the colour model onto an RGB frame
+    pub fn back_projection(&self, frame: &Mat) -> Result<Mat> {
+        self.mean_shift.back_projection(frame)
+    }
+
+    /// Locate the object in a new RGB frame
+    pub fn update(&mut self, frame: &Mat) -> Result<RotatedRect> {
+        let prob = self.back_projection(frame)?;
+        self.track(&prob)
+    }
+
+    /// Track on a precomputed single-channel probability image
+    pub fn track(&mut self, prob_image: &Mat) -> Result<RotatedRect> {
+        let ms = &mut self.mean_shift;
+        let (rect, window) = cam_shift(prob_image, ms.window, ms.max_iterations, ms.epsilon)?;
+        ms.window = window;
+        Ok(rect)
+    }
+}
+
+/// Raw moments of a U8 image inside a window
+struct WindowMoments {
+    m00: f64,
+    m10: f64,
+    m01: f64,
+    m20: f64,
+    m11: f64,
+    m02: f64,
+}
+
+impl WindowMoments {
+    /// `window` must already lie inside the image
+    #[allow(clippy::cast_sign_loss)]
+    fn compute(image: &Mat, window: Rect) -> Result<Self> {
+        let mut m = Self { m00: 0.0, m10: 0.0, m01: 0.0, m20: 0.0, m11: 0.0, m02: 0.0 };
+
+        for y in window.y..window.y + window.height {
+            for x in window.x..window.x + window.width {
+                let w = f64::from(image.at(y as usize, x as usize)?[0]);
+                if w == 0.0 {
+                    continue;
+                }
+                let (xf, yf) = (f64::from(x), f64::from(y));
+                m.m00 += w;
+                m.m10 += xf * w;
+                m.m01 += yf * w;
+                m.m20 += xf * xf * w;
+                m.m11 += xf * yf * w;
+                m.m02 += yf * yf * w;
+            }
+        }
+
+        Ok(m)
+    }
+
+    fn centroid(&self) -> (f64, f64) {
+        (self.m10 / self.m00, self.m01 / self.m00)
+    }
+}
+
+/// Clip a window to the image, shifting it back inside where possible
+#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+fn clamp_window(window: Rect, image: &Mat) -> Rect {
+    let cols = image.cols() as i32;
+    let rows = image.rows() as i32;
+
+    let width = window.width.clamp(1, cols.max(1));
+    let height = window.height.clamp(1, rows.max(1));
+    let x = window.x.clamp(0, (cols - width).max(0));
+    let y = window.y.clamp(0, (rows - height).max(0));
+
+    Rect::new(x, y, width, height)
+}
+
+fn to_hsv(frame: &Mat) -> Result<Mat> {
+    if frame.channels() != 3 || frame.depth() != MatDepth::U8 {
+        return Err(Error::InvalidParameter(
+            "Colour tracking requires a 3-channel U8 RGB frame".to_string(),
+        ));
+    }
+
+    let mut hsv = Mat::new(1, 1, 3, MatDepth::U8)?;
+    cvt_color(frame, &mut hsv, ColorConversionCode::RgbToHsv)?;
+    Ok(hsv)
+}
+
+/// H-S histogram of the saturated, reasonably bright pixels inside `roi`
+fn hue_saturation_model(frame: &Mat, roi: Rect) -> Result<HistogramNd> {
+    let hsv = to_hsv(frame)?;
+    let roi = clamp_window(roi, &hsv);
+
+    let mut mask = Mat::new(1, 1, 1, MatDepth::U8)?;
+    in_range(
+        &hsv,
+        &mut mask,
+        Scalar::new(0.0, MIN_SATURATION, MIN_VALUE, 0.0),
+        Scalar::new(180.0, 255.0, 255.0, 0.0),
+    )?;
+
+    #[allow(clippy::cast_sign_loss)]
+    for row in 0..mask.rows() {
+        for col in 0..mask.cols() {
+            if !roi.contains(Point::new(col as i32, row as i32)) {
+                mask.at_mut(row, col)?[0] = 0;
+            }
+        }
+    }
+
+    let mut hist = calc_hist_nd(
+        &hsv,
+        &[0, 1],
+        &[HUE_BINS, SATURATION_BINS],
+        &[(0.0, 180.0), (0.0, 256.0)],
+        Some(&mask),
+    )?;
+    hist.normalize(255.0);
+    Ok(hist)
+}
+
+fn hue_saturation_back_project(frame: &Mat, hist: &HistogramNd) -> Result<Mat> {
+    let hsv = to_hsv(frame)?;
+    let mut prob = Mat::new(1, 1, 1, MatDepth::U8)?;
+    calc_back_project_nd(&hsv, hist, &mut prob)?;
+    Ok(prob)
 }
 
 #[cfg(test)]
@@ -213,6 +516,36 @@ mod tests {
         assert_eq!(fg_mask.rows(), frame1.rows());
     }
 
+    #[test]
+    fn test_background_subtractor_motion_compensation() {
+        use crate::imgproc::noise::SplitMix64;
+
+        // Blocky texture panning 3 pixels left per frame
+        let mut rng = SplitMix64::new(2);
+        let blocks: Vec<u8> = (0..32 * 32).map(|_| (rng.next_u64() % 200 + 30) as u8).collect();
+        let frame = |i: usize| {
+            let mut frame = Mat::new(72, 96, 1, MatDepth::U8).unwrap();
+            for row in 0..72 {
+                for col in 0..96 {
+                    frame.at_mut(row, col).unwrap()[0] = blocks[(row / 6) * 32 + (col + 3 * i) / 6];
+                }
+            }
+            frame
+        };
+        let foreground = |mask: &Mat| mask.data().iter().filter(|&&v| v != 0).count();
+
+        let mut fixed = BackgroundSubtractorMOG2::new(5, 20.0);
+        let mut compensated = BackgroundSubtractorMOG2::new(5, 20.0).with_motion_compensation(MotionCompensator::new());
+        let (mut fixed_fg, mut compensated_fg) = (0, 0);
+        for i in 0..6 {
+            fixed_fg = foreground(&fixed.apply(&frame(i), -1.0).unwrap());
+            compensated_fg = foreground(&compensated.apply(&frame(i), -1.0).unwrap());
+        }
+        // Only the strip that just came into view may differ
+        assert!(compensated_fg <= 72 * 3, "{compensated_fg}");
+        assert!(fixed_fg > 72 * 96 / 5, "{fixed_fg}");
+    }
+
     #[test]
     fn test_meanshift_tracker() {
         let prob_image = Mat::new_with_default(100, 100, 1, MatDepth::U8, Scalar::all(128.0)).unwrap();
@@ -221,4 +554,58 @@ mod tests {
         let result = tracker.track(&prob_image).unwrap();
         assert!(result.width > 0 && result.height > 0);
     }
+
+    /// Grey RGB frame with a red ellipse (semi-axes 24×10) rotated by `angle`
+    /// degrees and a green distractor in the corner
+    fn ellipse_frame(cx: f64, cy: f64, angle: f64) -> Mat {
+        let mut frame = Mat::new_with_default(120, 160, 3, MatDepth::U8, Scalar::all(128.0)).unwrap();
+        let (sin, cos) = angle.to_radians().sin_cos();
+        for row in 0..120 {
+            for col in 0..160 {
+                let dx = col as f64 - cx;
+                let dy = row as f64 - cy;
+                let u = dx * cos + dy * sin;
+                let v = -dx * sin + dy * cos;
+                let px = frame.at_mut(row, col).unwrap();
+                if (u / 24.0).powi(2) + (v / 10.0).powi(2) <= 1.0 {
+                    px.copy_from_slice(&[220, 20, 30]);
+                } else if row > 100 && col > 140 {
+                    px.copy_from_slice(&[20, 220, 30]);
+                }
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn test_meanshift_follows_colour_model() {
+        let mut tracker = MeanShiftTracker::new(Rect::new(0, 0, 1, 1)).with_criteria(50, 0.5);
+        assert!(tracker.update(&ellipse_frame(60.0, 60.0, 0.0)).is_err());
+
+        tracker.init(&ellipse_frame(60.0, 60.0, 0.0), Rect::new(34, 48, 52, 24)).unwrap();
+        assert!(tracker.histogram().unwrap().bins.iter().any(|&b| b > 0.0));
+
+        let found = tracker.update(&ellipse_frame(70.0, 66.0, 0.0)).unwrap();
+        assert!((found.center.x - 70.0).abs() <= 1.5, "{found:?}");
+        assert!((found.center.y - 66.0).abs() <= 1.5, "{found:?}");
+        assert_eq!(tracker.window().width, 52);
+    }
+
+    #[test]
+    fn test_camshift_recovers_size_and_orientation() {
+        let mut tracker = CamShiftTracker::new(Rect::new(0, 0, 1, 1)).with_criteria(20, 0.5);
+        tracker.init(&ellipse_frame(60.0, 60.0, 30.0), Rect::new(45, 50, 30, 20)).unwrap();
+
+        let found = tracker.update(&ellipse_frame(72.0, 64.0, 30.0)).unwrap();
+        assert!((found.center.x - 72.0).abs() <= 1.0, "{found:?}");
+        assert!((found.center.y - 64.0).abs() <= 1.0, "{found:?}");
+        assert!((found.angle - 30.0).abs() <= 3.0, "{found:?}");
+        // 4σ of a filled ellipse is twice its semi-axis
+        assert!((found.width - 48.0).abs() <= 3.0, "{found:?}");
+        assert!((found.height - 20.0).abs() <= 3.0, "{found:?}");
+
+        // The search window grew to cover the whole ellipse
+        let window = tracker.window();
+        assert!(window.contains(Point::new(72 + 18, 64 + 10)), "{window:?}");
+    }
 }
@@ -0,0 +1,195 @@
+//! Async adapters over [`VideoCapture`] for Tokio applications
+//!
+//! Capturing and per-frame processing are blocking, CPU-bound work, so both
+//! run on Tokio's blocking thread pool and are handed to async code through
+//! [`Stream`]s. Requires the `tokio` feature.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::video_capture::{FrameInfo, VideoCapture};
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Frames read from a [`VideoCapture`], as returned by
+/// [`VideoCapture::into_stream`]
+///
+/// The capture is read on a blocking thread into a bounded buffer. When the
+/// consumer falls behind the buffer fills up and reading pauses, so a
+/// camera drops frames (reported in [`FrameInfo::dropped`]) instead of
+/// memory growing. Dropping the stream stops the reader.
+pub struct CaptureStream {
+    frames: mpsc::Receiver<Result<(Mat, FrameInfo)>>,
+}
+
+impl VideoCapture {
+    /// Read this capture in the background and yield its frames as a
+    /// [`Stream`], holding at most `buffer` unconsumed frames
+    ///
+    /// Must be called from within a Tokio runtime. The stream ends at the
+    /// end of a file or after the first read error, which it yields.
+    #[must_use]
+    pub fn into_stream(mut self, buffer: usize) -> CaptureStream {
+        let (sender, frames) = mpsc::channel(buffer.max(1));
+        tokio::task::spawn_blocking(move || loop {
+            let item = Mat::new(1, 1, 1, MatDepth::U8).and_then(|mut frame| {
+                Ok(self.read_with_info(&mut frame)?.map(|info| (frame, info)))
+            });
+            let item = match item {
+                Ok(Some(frame)) => Ok(frame),
+                Ok(None) => break,
+                Err(err) => Err(err),
+            };
+            let failed = item.is_err();
+            if sender.blocking_send(item).is_err() || failed {
+                break;
+            }
+        });
+        CaptureStream { frames }
+    }
+}
+
+impl CaptureStream {
+    /// Run `process` on every frame on the blocking thread pool, with up to
+    /// `concurrency` frames in flight, yielding the results in frame order
+    ///
+    /// ```no_run
+    /// # async fn run() -> opencv_rust::error::Result<()> {
+    /// use opencv_rust::videoio::VideoCapture;
+    ///
+    /// let capture = VideoCapture::from_camera(0)?;
+    /// let mut brightness = capture.into_stream(4).map_blocking(2, |frame, info| {
+    ///     let sum: u64 = frame.data().iter().map(|&v| u64::from(v)).sum();
+    ///     Ok((info.timestamp, sum / frame.data().len() as u64))
+    /// });
+    /// // Poll `brightness` with any Stream combinator library
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_blocking<T, F>(self, concurrency: usize, process: F) -> ProcessedStream<T>
+    where
+        T: Send + 'static,
+        F: Fn(Mat, FrameInfo) -> Result<T> + Send + Sync + 'static,
+    {
+        ProcessedStream {
+            frames: self,
+            process: Arc::new(process),
+            concurrency: concurrency.max(1),
+            in_flight: VecDeque::new(),
+            frames_done: false,
+        }
+    }
+}
+
+impl Stream for CaptureStream {
+    type Item = Result<(Mat, FrameInfo)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.frames.poll_recv(cx)
+    }
+}
+
+type Processor<T> = Arc<dyn Fn(Mat, FrameInfo) -> Result<T> + Send + Sync>;
+
+/// Per-frame results of [`CaptureStream::map_blocking`]
+pub struct ProcessedStream<T> {
+    frames: CaptureStream,
+    process: Processor<T>,
+    concurrency: usize,
+    in_flight: VecDeque<JoinHandle<Result<T>>>,
+    frames_done: bool,
+}
+
+impl<T: Send + 'static> Stream for ProcessedStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.frames_done && this.in_flight.len() < this.concurrency {
+            match Pin::new(&mut this.frames).poll_next(cx) {
+                Poll::Ready(Some(Ok((frame, info)))) => {
+                    let process = Arc::clone(&this.process);
+                    this.in_flight.push_back(tokio::task::spawn_blocking(move || process(frame, info)));
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    // Queue the capture error behind the frames already in flight
+                    this.in_flight.push_back(tokio::task::spawn_blocking(move || Err(err)));
+                }
+                Poll::Ready(None) => this.frames_done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        let Some(front) = this.in_flight.front_mut() else {
+            return if this.frames_done { Poll::Ready(None) } else { Poll::Pending };
+        };
+        match Pin::new(front).poll(cx) {
+            Poll::Ready(result) => {
+                this.in_flight.pop_front();
+                Poll::Ready(Some(result.unwrap_or_else(|err| {
+                    Err(Error::UnsupportedOperation(format!("Frame processing task failed: {err}")))
+                })))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::videoio::VideoCaptureProperty;
+    use std::future::poll_fn;
+
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().build().unwrap()
+    }
+
+    #[test]
+    fn test_capture_stream() {
+        runtime().block_on(async {
+            let mut capture = VideoCapture::from_camera(0).unwrap();
+            capture.set(VideoCaptureProperty::Fps, 200.0).unwrap();
+            let mut frames = capture.into_stream(2);
+
+            let (frame, first) = next(&mut frames).await.unwrap().unwrap();
+            assert_eq!((frame.rows(), frame.cols()), (480, 640));
+            let (_, second) = next(&mut frames).await.unwrap().unwrap();
+            assert!(second.index > first.index && second.timestamp > first.timestamp);
+        });
+    }
+
+    #[test]
+    fn test_processed_stream_keeps_frame_order() {
+        runtime().block_on(async {
+            let mut capture = VideoCapture::from_camera(0).unwrap();
+            capture.set(VideoCaptureProperty::Fps, 200.0).unwrap();
+            capture.set(VideoCaptureProperty::FrameWidth, 8.0).unwrap();
+            capture.set(VideoCaptureProperty::FrameHeight, 4.0).unwrap();
+
+            let mut processed = capture.into_stream(4).map_blocking(3, |frame, info| {
+                // Later frames finish first
+                std::thread::sleep(std::time::Duration::from_millis(10u64.saturating_sub(info.index % 10)));
+                Ok((info.index, frame.cols()))
+            });
+            let mut previous = None;
+            for _ in 0..6 {
+                let (index, cols) = next(&mut processed).await.unwrap().unwrap();
+                assert_eq!(cols, 8);
+                assert!(previous.is_none_or(|p| index > p));
+                previous = Some(index);
+            }
+        });
+    }
+}
@@ -1,5 +1,11 @@
 pub mod video_capture;
 pub mod video_writer;
+pub mod pixel_format;
+#[cfg(feature = "tokio")]
+pub mod async_capture;
 
 pub use video_capture::*;
 pub use video_writer::*;
+pub use pixel_format::*;
+#[cfg(feature = "tokio")]
+pub use async_capture::{CaptureStream, ProcessedStream};
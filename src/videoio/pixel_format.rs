@@ -0,0 +1,257 @@
+use crate::core::{Mat, MatDepth};
+use crate::error::{Error, Result};
+
+/// Raw pixel layouts delivered by camera and capture backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Packed 4:2:2, `Y0 U Y1 V` per pixel pair (V4L2 `YUYV`, a.k.a. `YUY2`)
+    Yuyv,
+    /// Packed 4:2:2, `U Y0 V Y1` per pixel pair (macOS `2vuy`)
+    Uyvy,
+    /// 4:2:0, Y plane followed by an interleaved UV plane
+    Nv12,
+    /// 4:2:0, Y plane followed by an interleaved VU plane (Android camera)
+    Nv21,
+    /// 16-bit little-endian `RRRRRGGG GGGBBBBB`
+    Rgb565,
+    /// 32-bit `B G R A` (Windows and most GPU surfaces); alpha is dropped
+    Bgra,
+}
+
+impl PixelFormat {
+    /// Bytes per pixel in the first (or only) plane
+    #[must_use]
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Nv12 | Self::Nv21 => 1,
+            Self::Yuyv | Self::Uyvy | Self::Rgb565 => 2,
+            Self::Bgra => 4,
+        }
+    }
+}
+
+/// Geometry of a raw frame buffer passed to [`convert_pixel_format`]
+///
+/// `stride` is the distance in bytes between the starts of consecutive
+/// rows, which backends often pad for alignment; for NV12/NV21 it applies
+/// to both planes. The chroma plane of NV12/NV21 starts at
+/// `chroma_offset`, right after the Y plane unless the backend aligns it
+/// further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameFormat {
+    pub pixel_format: PixelFormat,
+    pub width: usize,
+    pub height: usize,
+    pub stride: usize,
+    pub chroma_offset: usize,
+}
+
+impl FrameFormat {
+    /// A tightly packed frame
+    #[must_use]
+    pub fn new(pixel_format: PixelFormat, width: usize, height: usize) -> Self {
+        let stride = width * pixel_format.bytes_per_pixel();
+        Self {
+            pixel_format,
+            width,
+            height,
+            stride,
+            chroma_offset: stride * height,
+        }
+    }
+
+    /// Set the row stride; the chroma plane moves to follow the Y plane
+    #[must_use]
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self.chroma_offset = stride * self.height;
+        self
+    }
+
+    #[must_use]
+    pub fn with_chroma_offset(mut self, chroma_offset: usize) -> Self {
+        self.chroma_offset = chroma_offset;
+        self
+    }
+
+    /// Smallest buffer holding the frame
+    #[must_use]
+    pub fn min_buffer_len(&self) -> usize {
+        let last_row = |rows: usize, row_bytes: usize| if rows == 0 { 0 } else { (rows - 1) * self.stride + row_bytes };
+        match self.pixel_format {
+            PixelFormat::Nv12 | PixelFormat::Nv21 => self.chroma_offset + last_row(self.height / 2, self.width),
+            format => last_row(self.height, self.width * format.bytes_per_pixel()),
+        }
+    }
+
+    fn validate(&self, len: usize) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::InvalidDimensions(
+                "Frame size must be non-zero".to_string(),
+            ));
+        }
+        let subsampled_rows = matches!(self.pixel_format, PixelFormat::Nv12 | PixelFormat::Nv21);
+        let subsampled_cols = subsampled_rows || matches!(self.pixel_format, PixelFormat::Yuyv | PixelFormat::Uyvy);
+        if (subsampled_cols && !self.width.is_multiple_of(2)) || (subsampled_rows && !self.height.is_multiple_of(2)) {
+            return Err(Error::InvalidDimensions(format!(
+                "{:?} frames need an even width{}",
+                self.pixel_format,
+                if subsampled_rows { " and height" } else { "" }
+            )));
+        }
+        if self.stride < self.width * self.pixel_format.bytes_per_pixel() {
+            return Err(Error::InvalidParameter(format!(
+                "Stride of {} bytes is shorter than a {}-pixel row",
+                self.stride, self.width
+            )));
+        }
+        if subsampled_rows && self.chroma_offset < self.stride * (self.height - 1) + self.width {
+            return Err(Error::InvalidParameter(
+                "Chroma plane overlaps the Y plane".to_string(),
+            ));
+        }
+        if len < self.min_buffer_len() {
+            return Err(Error::InvalidParameter(format!(
+                "Frame buffer holds {len} bytes, {:?} {}x{} needs {}",
+                self.pixel_format,
+                self.width,
+                self.height,
+                self.min_buffer_len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Convert a raw camera buffer to an RGB U8 Mat
+///
+/// YUV formats use the BT.601 limited-range matrix, as OpenCV's
+/// `COLOR_YUV2RGB_*` conversions do; 4:2:2 and 4:2:0 chroma is shared by
+/// each pair or 2x2 block of pixels. `dst` gets `height` rows and `width`
+/// columns.
+pub fn convert_pixel_format(src: &[u8], format: &FrameFormat, dst: &mut Mat) -> Result<()> {
+    format.validate(src.len())?;
+    let (width, height, stride) = (format.width, format.height, format.stride);
+    *dst = Mat::new(height, width, 3, MatDepth::U8)?;
+    let out = dst.data_mut();
+
+    for (row, out_row) in out.chunks_exact_mut(width * 3).enumerate() {
+        let line = &src[row * stride..];
+        for (col, px) in out_row.chunks_exact_mut(3).enumerate() {
+            let rgb = match format.pixel_format {
+                PixelFormat::Yuyv | PixelFormat::Uyvy => {
+                    let pair = &line[(col / 2) * 4..(col / 2) * 4 + 4];
+                    let (y, u, v) = if format.pixel_format == PixelFormat::Yuyv {
+                        (pair[(col % 2) * 2], pair[1], pair[3])
+                    } else {
+                        (pair[(col % 2) * 2 + 1], pair[0], pair[2])
+                    };
+                    yuv_to_rgb(y, u, v)
+                }
+                PixelFormat::Nv12 | PixelFormat::Nv21 => {
+                    let chroma = format.chroma_offset + (row / 2) * stride + (col / 2) * 2;
+                    let (first, second) = (src[chroma], src[chroma + 1]);
+                    let (u, v) = if format.pixel_format == PixelFormat::Nv12 { (first, second) } else { (second, first) };
+                    yuv_to_rgb(line[col], u, v)
+                }
+                PixelFormat::Rgb565 => {
+                    let value = u16::from_le_bytes([line[col * 2], line[col * 2 + 1]]);
+                    let expand = |bits: u16, width: u32| {
+                        let v = u32::from(bits);
+                        let max = (1u32 << width) - 1;
+                        // Exact rounding of v * 255 / max
+                        u8::try_from((v * 255 + max / 2) / max).unwrap_or(u8::MAX)
+                    };
+                    [expand(value >> 11, 5), expand((value >> 5) & 0x3F, 6), expand(value & 0x1F, 5)]
+                }
+                PixelFormat::Bgra => [line[col * 4 + 2], line[col * 4 + 1], line[col * 4]],
+            };
+            px.copy_from_slice(&rgb);
+        }
+    }
+    Ok(())
+}
+
+/// BT.601 limited-range YUV to RGB
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let y = 1.164 * (f32::from(y) - 16.0);
+    let u = f32::from(u) - 128.0;
+    let v = f32::from(v) - 128.0;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let to_byte = |x: f32| x.round().clamp(0.0, 255.0) as u8;
+    [
+        to_byte(y + 1.596 * v),
+        to_byte(y - 0.813 * v - 0.391 * u),
+        to_byte(y + 2.018 * u),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_yuv() {
+        // Two pixel pairs: gray pair, then a pair with distinct lumas
+        let yuyv = [128, 128, 128, 128, 16, 90, 235, 240];
+        let uyvy = [128, 128, 128, 128, 90, 16, 240, 235];
+        let mut a = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        let mut b = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        convert_pixel_format(&yuyv, &FrameFormat::new(PixelFormat::Yuyv, 4, 1), &mut a).unwrap();
+        convert_pixel_format(&uyvy, &FrameFormat::new(PixelFormat::Uyvy, 4, 1), &mut b).unwrap();
+
+        assert_eq!((a.rows(), a.cols(), a.channels()), (1, 4, 3));
+        assert_eq!(a.data(), b.data());
+        assert_eq!(a.at(0, 0).unwrap(), &[130, 130, 130]);
+        assert_eq!(a.at(0, 2).unwrap(), &yuv_to_rgb(16, 90, 240));
+        assert_eq!(a.at(0, 3).unwrap(), &yuv_to_rgb(235, 90, 240));
+        assert!(convert_pixel_format(&yuyv, &FrameFormat::new(PixelFormat::Yuyv, 3, 1), &mut a).is_err());
+    }
+
+    #[test]
+    fn test_nv12_with_stride() {
+        // 4x2 frame, rows padded to 6 bytes; UV plane after the Y plane
+        let (u, v) = (60, 200);
+        let mut buffer = vec![0u8; 6 * 2 + 6];
+        for row in 0..2 {
+            for col in 0..4 {
+                buffer[row * 6 + col] = 100 + (row * 4 + col) as u8;
+            }
+        }
+        buffer[12..16].copy_from_slice(&[u, v, v, u]);
+
+        let format = FrameFormat::new(PixelFormat::Nv12, 4, 2).with_stride(6);
+        assert_eq!(format.min_buffer_len(), 16);
+        let mut rgb = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        convert_pixel_format(&buffer, &format, &mut rgb).unwrap();
+        assert_eq!(rgb.at(1, 1).unwrap(), &yuv_to_rgb(105, u, v));
+        assert_eq!(rgb.at(0, 3).unwrap(), &yuv_to_rgb(103, v, u));
+
+        // NV21 swaps the chroma bytes
+        let nv21 = FrameFormat { pixel_format: PixelFormat::Nv21, ..format };
+        convert_pixel_format(&buffer, &nv21, &mut rgb).unwrap();
+        assert_eq!(rgb.at(1, 1).unwrap(), &yuv_to_rgb(105, v, u));
+
+        assert!(convert_pixel_format(&buffer[..15], &format, &mut rgb).is_err());
+        assert!(convert_pixel_format(&buffer, &format.with_chroma_offset(8), &mut rgb).is_err());
+    }
+
+    #[test]
+    fn test_rgb565_and_bgra() {
+        let pixels: [u16; 2] = [0xF800, 0x07E0 | 0x0010];
+        let bytes: Vec<u8> = pixels.iter().flat_map(|p| p.to_le_bytes()).collect();
+        let mut rgb = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        convert_pixel_format(&bytes, &FrameFormat::new(PixelFormat::Rgb565, 2, 1), &mut rgb).unwrap();
+        assert_eq!(rgb.data(), &[255, 0, 0, 0, 255, 132]);
+
+        // 1x2 BGRA frame with 12-byte rows
+        let mut bgra = vec![0u8; 12 + 4];
+        bgra[..4].copy_from_slice(&[10, 20, 30, 255]);
+        bgra[12..16].copy_from_slice(&[1, 2, 3, 0]);
+        let format = FrameFormat::new(PixelFormat::Bgra, 1, 2).with_stride(12);
+        convert_pixel_format(&bgra, &format, &mut rgb).unwrap();
+        assert_eq!(rgb.data(), &[30, 20, 10, 3, 2, 1]);
+        assert!(convert_pixel_format(&bgra, &format.with_stride(2), &mut rgb).is_err());
+    }
+}
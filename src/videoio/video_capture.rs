@@ -2,6 +2,21 @@
 use crate::core::Mat;
 use crate::error::{Error, Result};
 use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Timing metadata of a frame returned by [`VideoCapture::read_with_info`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Frame number in the stream; for a camera it counts every frame the
+    /// sensor produced, so it skips past dropped frames
+    pub index: u64,
+    /// Presentation time since the start of the stream, on a monotonic
+    /// clock, for ordering frames and matching them across streams
+    pub timestamp: Duration,
+    /// Frames the camera produced since the previous read but that were
+    /// never returned because the caller fell behind
+    pub dropped: u64,
+}
 
 /// Video capture from file or camera
 pub struct VideoCapture {
@@ -12,6 +27,10 @@ pub struct VideoCapture {
     frame_width: usize,
     frame_height: usize,
     is_opened: bool,
+    /// Start of camera frame pacing and the frame index produced at it
+    epoch: Option<(Instant, u64)>,
+    last_info: Option<FrameInfo>,
+    dropped_total: u64,
 }
 
 enum VideoSource {
@@ -42,6 +61,9 @@ impl VideoCapture {
             frame_width: 640,
             frame_height: 480,
             is_opened: true,
+            epoch: None,
+            last_info: None,
+            dropped_total: 0,
         })
     }
 
@@ -55,6 +77,9 @@ impl VideoCapture {
             frame_width: 640,
             frame_height: 480,
             is_opened: true,
+            epoch: monotonic_now().map(|now| (now, 0)),
+            last_info: None,
+            dropped_total: 0,
         })
     }
 
@@ -66,21 +91,34 @@ impl VideoCapture {
 
     /// Read next frame
     pub fn read(&mut self, frame: &mut Mat) -> Result<bool> {
+        Ok(self.read_with_info(frame)?.is_some())
+    }
+
+    /// Read the next frame along with its index, timestamp and the number
+    /// of frames dropped since the previous read
+    ///
+    /// Returns `None` at the end of a file. File timestamps follow the
+    /// frame rate. A camera produces frames at its frame rate from the
+    /// moment it is opened: reading waits for the next frame, and a caller
+    /// that falls behind gets the latest one, with the frames in between
+    /// counted as dropped.
+    pub fn read_with_info(&mut self, frame: &mut Mat) -> Result<Option<FrameInfo>> {
         if !self.is_opened {
             return Err(Error::InvalidParameter("Video capture not opened".to_string()));
         }
 
-        match &self.source {
+        let info = match &self.source {
             VideoSource::File { frames, .. } => {
                 if self.current_frame >= frames.len() {
-                    return Ok(false);
+                    return Ok(None);
                 }
 
                 *frame = frames[self.current_frame].clone_mat();
+                let index = self.current_frame as u64;
                 self.current_frame += 1;
-                Ok(true)
+                FrameInfo { index, timestamp: self.frame_time(index), dropped: 0 }
             }
-            VideoSource::Camera { device_id } => {
+            VideoSource::Camera { .. } => {
                 // In real implementation, would capture from camera
                 // For now, return a placeholder frame
                 use crate::core::{MatDepth, types::Scalar};
@@ -91,9 +129,49 @@ impl VideoCapture {
                     MatDepth::U8,
                     Scalar::all(128.0),
                 )?;
-                Ok(true)
+                let expected = self.last_info.map_or(0, |info| info.index + 1);
+                let index = self.wait_for_camera_frame(expected);
+                FrameInfo { index, timestamp: self.frame_time(index), dropped: index - expected }
             }
+        };
+        self.dropped_total += info.dropped;
+        self.last_info = Some(info);
+        Ok(Some(info))
+    }
+
+    /// Metadata of the most recently read frame
+    #[must_use]
+    pub fn last_frame_info(&self) -> Option<FrameInfo> {
+        self.last_info
+    }
+
+    /// Frames dropped since the capture was opened
+    #[must_use]
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_total
+    }
+
+    fn frame_time(&self, index: u64) -> Duration {
+        if self.fps > 0.0 {
+            Duration::from_secs_f64(index as f64 / self.fps)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Index of the camera frame to return: the latest one produced, but
+    /// no earlier than `expected`, waiting for it if necessary
+    fn wait_for_camera_frame(&self, expected: u64) -> u64 {
+        let Some((start, base)) = self.epoch.filter(|_| self.fps > 0.0) else {
+            return expected;
+        };
+        let produced = base + (start.elapsed().as_secs_f64() * self.fps).floor() as u64;
+        if produced >= expected {
+            return produced;
         }
+        let due = start + Duration::from_secs_f64((expected - base) as f64 / self.fps);
+        std::thread::sleep(due.saturating_duration_since(Instant::now()));
+        expected
     }
 
     /// Get video property
@@ -130,6 +208,11 @@ impl VideoCapture {
             }
             VideoCaptureProperty::Fps => {
                 self.fps = value;
+                // Pace the frames that follow at the new rate
+                if self.epoch.is_some() {
+                    let next = self.last_info.map_or(0, |info| info.index + 1);
+                    self.epoch = monotonic_now().map(|now| (now, next));
+                }
                 Ok(())
             }
             _ => Err(Error::InvalidParameter(format!("Property {prop:?} cannot be set"))),
@@ -187,6 +270,19 @@ pub enum VideoCaptureProperty {
     Exposure,
 }
 
+/// The monotonic clock, unavailable on `wasm32-unknown-unknown`, where
+/// camera frames are then numbered consecutively
+fn monotonic_now() -> Option<Instant> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Some(Instant::now())
+    }
+}
+
 impl Drop for VideoCapture {
     fn drop(&mut self) {
         self.release();
@@ -224,4 +320,25 @@ mod tests {
         assert_eq!(frame.rows(), 480);
         assert_eq!(frame.cols(), 640);
     }
+
+    #[test]
+    fn test_frame_info() {
+        let mut cap = VideoCapture::from_camera(0).unwrap();
+        cap.set(VideoCaptureProperty::Fps, 100.0).unwrap();
+        let mut frame = Mat::new(1, 1, 1, crate::core::MatDepth::U8).unwrap();
+
+        let first = cap.read_with_info(&mut frame).unwrap().unwrap();
+        let second = cap.read_with_info(&mut frame).unwrap().unwrap();
+        assert!(second.index > first.index);
+        assert!(second.timestamp > first.timestamp);
+
+        // Falling behind by five frame intervals drops at least four frames
+        std::thread::sleep(Duration::from_millis(50));
+        let late = cap.read_with_info(&mut frame).unwrap().unwrap();
+        assert!(late.dropped >= 4, "{late:?}");
+        assert_eq!(late.index, second.index + late.dropped + 1);
+        assert_eq!(late.timestamp, Duration::from_secs_f64(late.index as f64 / 100.0));
+        assert_eq!(cap.last_frame_info(), Some(late));
+        assert_eq!(cap.dropped_frames(), first.dropped + second.dropped + late.dropped);
+    }
 }
@@ -6,8 +6,6 @@ use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use crate::core::{Mat, MatDepth};
 #[cfg(target_arch = "wasm32")]
-use crate::core::types::ColorConversionCode;
-#[cfg(target_arch = "wasm32")]
 use crate::wasm::WasmMat;
 #[cfg(target_arch = "wasm32")]
 use crate::wasm::backend;
@@ -24,7 +22,7 @@ pub async fn canny_wasm(
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        crate::imgproc::cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -63,7 +61,7 @@ pub async fn sobel_wasm(
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        crate::imgproc::cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -101,7 +99,7 @@ pub async fn scharr_wasm(
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        crate::imgproc::cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -138,7 +136,7 @@ pub async fn laplacian_wasm(
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        crate::imgproc::cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
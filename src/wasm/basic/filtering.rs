@@ -180,14 +180,12 @@ pub async fn guided_filter_wasm(
     eps: f64,
 ) -> Result<WasmMat, JsValue> {
     use crate::imgproc::advanced_filter::guided_filter;
-    use crate::core::types::ColorConversionCode;
-    use crate::imgproc::color::cvt_color;
 
     // Convert to grayscale for guide
     let guide = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -231,14 +229,12 @@ pub async fn gabor_filter_wasm(
     psi: f64,
 ) -> Result<WasmMat, JsValue> {
     use crate::imgproc::advanced_filter::gabor_filter;
-    use crate::core::types::ColorConversionCode;
-    use crate::imgproc::color::cvt_color;
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -336,6 +332,7 @@ pub async fn anisotropic_diffusion_wasm(
 
 
 // ===== fastNlMeans =====
+#[cfg(feature = "photo")]
 #[wasm_bindgen(js_name = fastNlMeans)]
 pub async fn fast_nl_means_wasm(src: &WasmMat, h: f32, template_window_size: i32, search_window_size: i32) -> Result<WasmMat, JsValue> {
     use crate::photo::fast_nl_means_denoising;
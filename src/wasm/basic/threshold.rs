@@ -6,7 +6,7 @@ use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use crate::core::{Mat, MatDepth};
 #[cfg(target_arch = "wasm32")]
-use crate::core::types::{ColorConversionCode, ThresholdType};
+use crate::core::types::ThresholdType;
 #[cfg(target_arch = "wasm32")]
 use crate::wasm::WasmMat;
 #[cfg(target_arch = "wasm32")]
@@ -44,12 +44,8 @@ pub async fn threshold_wasm(
             let gray = if src.inner.channels() > 1 {
                 let mut gray = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
                     .map_err(|e| JsValue::from_str(&e.to_string()))?;
-                crate::imgproc::cvt_color(
-                    &src.inner,
-                    &mut gray,
-                    ColorConversionCode::BgrToGray,
-                )
-                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+                crate::imgproc::to_gray(&src.inner, &mut gray)
+                    .map_err(|e| JsValue::from_str(&e.to_string()))?;
                 gray
             } else {
                 src.inner.clone()
@@ -88,7 +84,7 @@ pub async fn adaptive_threshold_wasm(
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        crate::imgproc::cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -8,15 +8,14 @@ use crate::wasm::WasmMat;
 #[wasm_bindgen(js_name = findHomography)]
 pub async fn find_homography_wasm(src: &WasmMat, n_features: usize) -> Result<WasmMat, JsValue> {
     use crate::features2d::SIFTF32;
-    use crate::imgproc::color::cvt_color;
     use crate::imgproc::drawing::circle;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -41,17 +40,60 @@ pub async fn find_homography_wasm(src: &WasmMat, n_features: usize) -> Result<Wa
 
 
 // ===== calibrateCamera =====
-#[wasm_bindgen(js_name = calibrateCamera)]
-pub async fn calibrate_camera_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::imgproc::drawing::put_text;
-    use crate::core::types::{Point, Scalar};
+/// Intrinsics estimated by `calibrateCamera`
+#[wasm_bindgen]
+pub struct CameraCalibration {
+    camera_matrix: Vec<f64>,
+    dist_coeffs: Vec<f64>,
+    rms: f64,
+}
 
-    // Simplified demo - would need checkerboard pattern in production
-    let mut result = src.inner.clone();
-    let text = "Camera calibration demo".to_string();
-    let _ = put_text(&mut result, &text, Point::new(10, 30), 0.7, Scalar::new(255.0, 255.0, 255.0, 255.0));
+#[wasm_bindgen]
+impl CameraCalibration {
+    /// 3x3 camera matrix, row-major
+    #[wasm_bindgen(getter, js_name = cameraMatrix)]
+    pub fn camera_matrix(&self) -> Vec<f64> {
+        self.camera_matrix.clone()
+    }
 
-    Ok(WasmMat { inner: result })
+    /// Distortion coefficients in OpenCV order: k1, k2, p1, p2, k3
+    #[wasm_bindgen(getter, js_name = distCoeffs)]
+    pub fn dist_coeffs(&self) -> Vec<f64> {
+        self.dist_coeffs.clone()
+    }
+
+    /// RMS reprojection error in pixels
+    #[wasm_bindgen(getter)]
+    pub fn rms(&self) -> f64 {
+        self.rms
+    }
+}
+
+/// Calibrate a camera from views of a known target
+///
+/// `object_points` holds x, y, z triples and `image_points` u, v pairs for
+/// every view back to back; `point_counts` gives the number of points in
+/// each view.
+#[wasm_bindgen(js_name = calibrateCamera)]
+pub async fn calibrate_camera_wasm(
+    object_points: &[f64],
+    image_points: &[f64],
+    point_counts: &[u32],
+    width: usize,
+    height: usize,
+) -> Result<CameraCalibration, JsValue> {
+    use crate::calib3d::calibrate_camera;
+
+    let objects = split_views(&points_3d(object_points)?, point_counts)?;
+    let images = split_views(&pixel_points(image_points)?, point_counts)?;
+    let (camera, dist, rms) = calibrate_camera(&objects, &images, (width, height))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok(CameraCalibration {
+        camera_matrix: camera_to_vec(&camera),
+        dist_coeffs: dist_to_vec(&dist),
+        rms,
+    })
 }
 
 
@@ -71,74 +113,222 @@ pub async fn fisheye_calibration_wasm(src: &WasmMat) -> Result<WasmMat, JsValue>
 
 
 // ===== solvePnp =====
-#[wasm_bindgen(js_name = solvePnp)]
-pub async fn solve_pnp_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::imgproc::drawing::{put_text, circle};
-    use crate::core::types::{Point, Scalar};
+/// Object pose estimated by `solvePnp`
+#[wasm_bindgen]
+pub struct PoseEstimate {
+    rvec: Vec<f64>,
+    tvec: Vec<f64>,
+}
 
-    // Simplified demo - show reference points
-    let mut result = src.inner.clone();
-    let text = "PnP pose estimation".to_string();
-    let _ = put_text(&mut result, &text, Point::new(10, 30), 0.7, Scalar::new(255.0, 0.0, 255.0, 255.0));
+#[wasm_bindgen]
+impl PoseEstimate {
+    /// Rotation vector (axis times angle in radians)
+    #[wasm_bindgen(getter)]
+    pub fn rvec(&self) -> Vec<f64> {
+        self.rvec.clone()
+    }
 
-    // Draw some reference points
-    let points = vec![
-        Point::new(result.cols() as i32 / 4, result.rows() as i32 / 4),
-        Point::new(3 * result.cols() as i32 / 4, result.rows() as i32 / 4),
-        Point::new(result.cols() as i32 / 2, result.rows() as i32 / 2),
-    ];
-    for pt in points {
-        let _ = circle(&mut result, pt, 5, Scalar::new(255.0, 0.0, 0.0, 255.0));
+    /// Translation in object point units
+    #[wasm_bindgen(getter)]
+    pub fn tvec(&self) -> Vec<f64> {
+        self.tvec.clone()
     }
 
-    Ok(WasmMat { inner: result })
+    /// 3x3 rotation matrix, row-major
+    #[wasm_bindgen(getter, js_name = rotationMatrix)]
+    pub fn rotation_matrix(&self) -> Vec<f64> {
+        let r = crate::calib3d::rodrigues(&[self.rvec[0], self.rvec[1], self.rvec[2]]);
+        r.iter().flatten().copied().collect()
+    }
+}
+
+/// Pose of an object from 3-D to 2-D point correspondences
+///
+/// `object_points` holds x, y, z triples and `image_points` u, v pairs.
+/// `camera_matrix` is 3x3 row-major and `dist_coeffs` is empty or
+/// k1, k2, p1, p2[, k3]. Planar targets (all z = 0, at least 4 points)
+/// are solved with sub-pixel accuracy.
+#[wasm_bindgen(js_name = solvePnp)]
+pub async fn solve_pnp_wasm(
+    object_points: &[f64],
+    image_points: &[f64],
+    camera_matrix: &[f64],
+    dist_coeffs: &[f64],
+) -> Result<PoseEstimate, JsValue> {
+    use crate::calib3d::{solve_pnp, solve_pnp_planar, PnPMethod};
+    use crate::core::types::{Point, Point2f};
+
+    let objects = points_3d(object_points)?;
+    let images: Vec<Point2f> = image_points
+        .chunks_exact(2)
+        .map(|p| Point2f::new(p[0] as f32, p[1] as f32))
+        .collect();
+    if image_points.len() % 2 != 0 || images.len() != objects.len() {
+        return Err(JsValue::from_str("Need one u, v pair per object point"));
+    }
+    let camera = camera_from_slice(camera_matrix)?;
+    let dist = dist_from_slice(dist_coeffs)?;
+
+    let (rvec, tvec) = if objects.len() >= 4 && objects.iter().all(|p| p.z == 0.0) {
+        solve_pnp_planar(&objects, &images, &camera, &dist)
+    } else {
+        // The general solver works on whole, undistorted pixels
+        let undistorted: Vec<Point> = images
+            .iter()
+            .map(|p| {
+                let (x, y) = dist.undistort(
+                    (f64::from(p.x) - camera.cx) / camera.fx,
+                    (f64::from(p.y) - camera.cy) / camera.fy,
+                );
+                Point::new(
+                    (camera.fx * x + camera.cx).round() as i32,
+                    (camera.fy * y + camera.cy).round() as i32,
+                )
+            })
+            .collect();
+        solve_pnp(&objects, &undistorted, &camera, PnPMethod::ITERATIVE)
+    }
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok(PoseEstimate { rvec: rvec.to_vec(), tvec: tvec.to_vec() })
 }
 
 
 // ===== stereoCalibration =====
+/// Stereo rig parameters estimated by `stereoCalibration`
+#[wasm_bindgen]
+pub struct StereoCalibration {
+    params: crate::calib3d::StereoParameters,
+}
+
+#[wasm_bindgen]
+impl StereoCalibration {
+    /// Left camera matrix, 3x3 row-major
+    #[wasm_bindgen(getter, js_name = cameraMatrixLeft)]
+    pub fn camera_matrix_left(&self) -> Vec<f64> {
+        camera_to_vec(&self.params.camera_matrix_left)
+    }
+
+    /// Right camera matrix, 3x3 row-major
+    #[wasm_bindgen(getter, js_name = cameraMatrixRight)]
+    pub fn camera_matrix_right(&self) -> Vec<f64> {
+        camera_to_vec(&self.params.camera_matrix_right)
+    }
+
+    /// Left distortion coefficients: k1, k2, p1, p2, k3
+    #[wasm_bindgen(getter, js_name = distCoeffsLeft)]
+    pub fn dist_coeffs_left(&self) -> Vec<f64> {
+        dist_to_vec(&self.params.dist_coeffs_left)
+    }
+
+    /// Right distortion coefficients: k1, k2, p1, p2, k3
+    #[wasm_bindgen(getter, js_name = distCoeffsRight)]
+    pub fn dist_coeffs_right(&self) -> Vec<f64> {
+        dist_to_vec(&self.params.dist_coeffs_right)
+    }
+
+    /// Rotation from the left to the right camera, 3x3 row-major
+    #[wasm_bindgen(getter)]
+    pub fn rotation(&self) -> Vec<f64> {
+        self.params.rotation.iter().flatten().copied().collect()
+    }
+
+    /// Translation from the left to the right camera
+    #[wasm_bindgen(getter)]
+    pub fn translation(&self) -> Vec<f64> {
+        self.params.translation.to_vec()
+    }
+
+    /// Essential matrix, 3x3 row-major
+    #[wasm_bindgen(getter, js_name = essentialMatrix)]
+    pub fn essential_matrix(&self) -> Vec<f64> {
+        self.params.essential_matrix.iter().flatten().copied().collect()
+    }
+
+    /// Fundamental matrix, 3x3 row-major
+    #[wasm_bindgen(getter, js_name = fundamentalMatrix)]
+    pub fn fundamental_matrix(&self) -> Vec<f64> {
+        self.params.fundamental_matrix.iter().flatten().copied().collect()
+    }
+}
+
+/// Calibrate a stereo pair from simultaneous views of a known target
+///
+/// Point layout as for `calibrateCamera`, with one u, v pair per object
+/// point in each of `left_points` and `right_points`.
 #[wasm_bindgen(js_name = stereoCalibration)]
-pub async fn stereo_calibration_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::imgproc::drawing::put_text;
-    use crate::core::types::{Point, Scalar};
+pub async fn stereo_calibration_wasm(
+    object_points: &[f64],
+    left_points: &[f64],
+    right_points: &[f64],
+    point_counts: &[u32],
+    width: usize,
+    height: usize,
+) -> Result<StereoCalibration, JsValue> {
+    use crate::calib3d::stereo_calibrate;
+
+    let objects = split_views(&points_3d(object_points)?, point_counts)?;
+    let left = split_views(&pixel_points(left_points)?, point_counts)?;
+    let right = split_views(&pixel_points(right_points)?, point_counts)?;
+    let params = stereo_calibrate(&objects, &left, &right, (width, height))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    // Simplified demo
-    let mut result = src.inner.clone();
-    let text = "Stereo calibration demo".to_string();
-    let _ = put_text(&mut result, &text, Point::new(10, 30), 0.7, Scalar::new(128.0, 255.0, 128.0, 255.0));
+    Ok(StereoCalibration { params })
+}
 
-    Ok(WasmMat { inner: result })
+
+// ===== undistort =====
+/// Remove lens distortion from an image
+///
+/// `camera_matrix` is 3x3 row-major and `dist_coeffs` k1, k2, p1, p2[, k3].
+#[wasm_bindgen(js_name = undistort)]
+pub async fn undistort_wasm(src: &WasmMat, camera_matrix: &[f64], dist_coeffs: &[f64]) -> Result<WasmMat, JsValue> {
+    let camera = camera_from_slice(camera_matrix)?;
+    let dist = dist_from_slice(dist_coeffs)?;
+    let mut dst = Mat::new(1, 1, 1, MatDepth::U8)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    crate::calib3d::undistort(&src.inner, &mut dst, &camera, &dist)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok(WasmMat { inner: dst })
 }
 
 
 // ===== computeDisparity =====
+/// Block-matching disparity of a rectified pair
+///
+/// Searches disparities `min_disparity..min_disparity + num_disparities`
+/// with `block_size` SAD windows. The result is an 8-bit image scaled so
+/// the largest searched disparity is 255.
 #[wasm_bindgen(js_name = computeDisparity)]
-pub async fn compute_disparity_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
-
-    // Simplified: Use shifted image as "right" view for demo
-    let gray = if src.inner.channels() > 1 {
-        let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        g
-    } else {
-        src.inner.clone()
-    };
-
-    // Create shifted version as disparity map demo
-    let mut disparity = Mat::new(gray.rows(), gray.cols(), 1, gray.depth())
+pub async fn compute_disparity_wasm(
+    left: &WasmMat,
+    right: &WasmMat,
+    min_disparity: i32,
+    num_disparities: i32,
+    block_size: usize,
+) -> Result<WasmMat, JsValue> {
+    use crate::calib3d::compute_stereo_disparity;
+    use crate::wasm::flat::to_gray;
+
+    if num_disparities <= 0 {
+        return Err(JsValue::from_str("numDisparities must be positive"));
+    }
+    let max_disparity = min_disparity + num_disparities;
+    let disparity = compute_stereo_disparity(&to_gray(&left.inner)?, &to_gray(&right.inner)?, min_disparity, max_disparity, block_size)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    for row in 0..gray.rows() {
-        for col in 0..gray.cols() {
-            let shift = (col % 20) as u8;
-            disparity.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0] = shift * 12;
+    let scale = 255.0 / max_disparity.abs().max(1) as f32;
+    let mut result = Mat::new(disparity.rows(), disparity.cols(), 1, MatDepth::U8)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    for row in 0..disparity.rows() {
+        for col in 0..disparity.cols() {
+            let d = disparity.at_f32(row, col, 0).map_err(|e| JsValue::from_str(&e.to_string()))?;
+            result.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0] = (d * scale).round().clamp(0.0, 255.0) as u8;
         }
     }
 
-    Ok(WasmMat { inner: disparity })
+    Ok(WasmMat { inner: result })
 }
 
 
@@ -214,4 +404,71 @@ pub async fn stereo_rectification_wasm(src: &WasmMat) -> Result<WasmMat, JsValue
     Ok(WasmMat { inner: result })
 }
 
+/// x, y, z triples
+fn points_3d(flat: &[f64]) -> Result<Vec<crate::core::types::Point3f>, JsValue> {
+    if flat.len() % 3 != 0 {
+        return Err(JsValue::from_str("Object points must be x, y, z triples"));
+    }
+    Ok(flat
+        .chunks_exact(3)
+        .map(|p| crate::core::types::Point3f::new(p[0] as f32, p[1] as f32, p[2] as f32))
+        .collect())
+}
+
+/// u, v pairs rounded to whole pixels
+fn pixel_points(flat: &[f64]) -> Result<Vec<crate::core::types::Point>, JsValue> {
+    if flat.len() % 2 != 0 {
+        return Err(JsValue::from_str("Image points must be u, v pairs"));
+    }
+    Ok(flat
+        .chunks_exact(2)
+        .map(|p| crate::core::types::Point::new(p[0].round() as i32, p[1].round() as i32))
+        .collect())
+}
 
+/// Split points listed view after view into one `Vec` per view
+fn split_views<T: Clone>(points: &[T], counts: &[u32]) -> Result<Vec<Vec<T>>, JsValue> {
+    let total: usize = counts.iter().map(|&n| n as usize).sum();
+    if total != points.len() {
+        return Err(JsValue::from_str(&format!(
+            "Point counts add up to {total} but {} points were given",
+            points.len()
+        )));
+    }
+    let mut start = 0;
+    Ok(counts
+        .iter()
+        .map(|&n| {
+            let view = points[start..start + n as usize].to_vec();
+            start += n as usize;
+            view
+        })
+        .collect())
+}
+
+fn camera_from_slice(m: &[f64]) -> Result<crate::calib3d::CameraMatrix, JsValue> {
+    if m.len() != 9 {
+        return Err(JsValue::from_str("Camera matrix must have 9 elements"));
+    }
+    Ok(crate::calib3d::CameraMatrix::new(m[0], m[4], m[2], m[5]))
+}
+
+fn camera_to_vec(camera: &crate::calib3d::CameraMatrix) -> Vec<f64> {
+    vec![camera.fx, 0.0, camera.cx, 0.0, camera.fy, camera.cy, 0.0, 0.0, 1.0]
+}
+
+/// OpenCV order k1, k2, p1, p2[, k3]; empty means no distortion
+fn dist_from_slice(d: &[f64]) -> Result<crate::calib3d::DistortionCoefficients, JsValue> {
+    use crate::calib3d::DistortionCoefficients;
+
+    match d {
+        [] => Ok(DistortionCoefficients::zero()),
+        [k1, k2, p1, p2] => Ok(DistortionCoefficients::new(*k1, *k2, 0.0, *p1, *p2)),
+        [k1, k2, p1, p2, k3] => Ok(DistortionCoefficients::new(*k1, *k2, *k3, *p1, *p2)),
+        _ => Err(JsValue::from_str("Distortion coefficients must have 0, 4 or 5 elements")),
+    }
+}
+
+fn dist_to_vec(dist: &crate::calib3d::DistortionCoefficients) -> Vec<f64> {
+    vec![dist.k[0], dist.k[1], dist.p[0], dist.p[1], dist.k[2]]
+}
@@ -15,14 +15,13 @@ pub async fn harris_corners_wasm(
 ) -> Result<WasmMat, JsValue> {
     use crate::features2d::harris_corners;
     use crate::imgproc::drawing::circle;
-    use crate::core::types::{ColorConversionCode, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::Scalar;
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -33,9 +32,9 @@ pub async fn harris_corners_wasm(
 
     crate::backend_dispatch! {
         gpu => {
-            // Complex feature detection uses CPU implementation
             // Detect corners
-            let keypoints = harris_corners(&gray, block_size, ksize, k, threshold)
+            let keypoints = crate::gpu::ops::harris_corners_gpu_async(&gray, block_size, k, threshold, 0)
+                .await
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
             // Draw keypoints on original image
@@ -44,7 +43,7 @@ pub async fn harris_corners_wasm(
             for kp in keypoints {
                 circle(&mut result, kp.pt, 3, color)
                     .map_err(|e| JsValue::from_str(&e.to_string()))?;
-                    }
+            }
         }
 cpu => {
             // Detect corners
@@ -75,14 +74,13 @@ pub async fn good_features_to_track_wasm(
 ) -> Result<WasmMat, JsValue> {
     use crate::features2d::good_features_to_track;
     use crate::imgproc::drawing::circle;
-    use crate::core::types::{ColorConversionCode, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::Scalar;
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -133,14 +131,13 @@ pub async fn fast_wasm(
 ) -> Result<WasmMat, JsValue> {
     use crate::features2d::fast;
     use crate::imgproc::drawing::circle;
-    use crate::core::types::{ColorConversionCode, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::Scalar;
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -151,9 +148,9 @@ pub async fn fast_wasm(
 
     crate::backend_dispatch! {
         gpu => {
-            // Complex feature detection uses CPU implementation
             // Detect keypoints
-            let keypoints = fast(&gray, threshold, nonmax_suppression)
+            let keypoints = crate::gpu::ops::fast_gpu_async(&gray, threshold, nonmax_suppression)
+                .await
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
             // Draw keypoints on original image
@@ -162,7 +159,7 @@ pub async fn fast_wasm(
             for kp in keypoints {
                 circle(&mut result, kp.pt, 2, color)
                     .map_err(|e| JsValue::from_str(&e.to_string()))?;
-                    }
+            }
         }
 cpu => {
             // Detect keypoints
@@ -186,8 +183,6 @@ cpu => {
 #[wasm_bindgen(js_name = sift)]
 pub async fn sift_wasm(src: &WasmMat, n_features: usize) -> Result<WasmMat, JsValue> {
     use crate::features2d::SIFTF32;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
     use crate::imgproc::drawing::circle;
     use crate::core::types::{Point, Scalar};
 
@@ -195,7 +190,7 @@ pub async fn sift_wasm(src: &WasmMat, n_features: usize) -> Result<WasmMat, JsVa
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -243,8 +238,6 @@ cpu => {
 #[wasm_bindgen(js_name = orb)]
 pub async fn orb_wasm(src: &WasmMat, n_features: usize) -> Result<WasmMat, JsValue> {
     use crate::features2d::ORB;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
     use crate::imgproc::drawing::circle;
     use crate::core::types::{Point, Scalar};
 
@@ -252,7 +245,7 @@ pub async fn orb_wasm(src: &WasmMat, n_features: usize) -> Result<WasmMat, JsVal
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -300,8 +293,6 @@ cpu => {
 #[wasm_bindgen(js_name = brisk)]
 pub async fn brisk_wasm(src: &WasmMat, threshold: i32) -> Result<WasmMat, JsValue> {
     use crate::features2d::BRISK;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
     use crate::imgproc::drawing::circle;
     use crate::core::types::{Point, Scalar};
 
@@ -309,7 +300,7 @@ pub async fn brisk_wasm(src: &WasmMat, threshold: i32) -> Result<WasmMat, JsValu
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -357,8 +348,6 @@ cpu => {
 #[wasm_bindgen(js_name = akaze)]
 pub async fn akaze_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::features2d::AKAZE;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
     use crate::imgproc::drawing::circle;
     use crate::core::types::{Point, Scalar};
 
@@ -366,7 +355,7 @@ pub async fn akaze_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -414,8 +403,6 @@ cpu => {
 #[wasm_bindgen(js_name = kaze)]
 pub async fn kaze_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::features2d::KAZE;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
     use crate::imgproc::drawing::circle;
     use crate::core::types::{Point, Scalar};
 
@@ -423,7 +410,7 @@ pub async fn kaze_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -466,3 +453,43 @@ cpu => {
 
     Ok(WasmMat { inner: result })
 }
+
+/// FAST keypoints as a flat `Float32Array` (see [`crate::wasm::flat`])
+#[wasm_bindgen(js_name = fastFlat)]
+pub fn fast_flat_wasm(src: &WasmMat, threshold: i32, nonmax_suppression: bool) -> Result<Vec<f32>, JsValue> {
+    use crate::wasm::flat::{pack_keypoints, to_gray};
+
+    let gray = to_gray(&src.inner)?;
+    let keypoints = crate::features2d::fast(&gray, threshold, nonmax_suppression)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(pack_keypoints(&keypoints))
+}
+
+/// Shi-Tomasi corners as a flat `Float32Array` (see [`crate::wasm::flat`])
+#[wasm_bindgen(js_name = goodFeaturesToTrackFlat)]
+pub fn good_features_to_track_flat_wasm(
+    src: &WasmMat,
+    max_corners: usize,
+    quality_level: f64,
+    min_distance: f64,
+    block_size: i32,
+) -> Result<Vec<f32>, JsValue> {
+    use crate::wasm::flat::{pack_keypoints, to_gray};
+
+    let gray = to_gray(&src.inner)?;
+    let keypoints = crate::features2d::good_features_to_track(&gray, max_corners, quality_level, min_distance, block_size)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(pack_keypoints(&keypoints))
+}
+
+/// ORB keypoints as a flat `Float32Array` (see [`crate::wasm::flat`])
+#[wasm_bindgen(js_name = orbFlat)]
+pub fn orb_flat_wasm(src: &WasmMat, n_features: usize) -> Result<Vec<f32>, JsValue> {
+    use crate::features2d::ORB;
+    use crate::wasm::flat::{pack_keypoints, to_gray};
+
+    let gray = to_gray(&src.inner)?;
+    let (keypoints, _) = ORB::new(n_features).detect_and_compute(&gray)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(pack_keypoints(&keypoints))
+}
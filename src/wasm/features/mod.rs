@@ -1,11 +1,14 @@
 //! Feature detection and matching operations
 
+#[cfg(feature = "features2d")]
 pub mod detection;
+#[cfg(feature = "objdetect")]
 pub mod object;
 
 // Re-export all public functions for WASM bindings
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "features2d"))]
 pub use detection::{
     harris_corners_wasm, good_features_to_track_wasm, fast_wasm,
-    sift_wasm, orb_wasm, brisk_wasm, akaze_wasm, kaze_wasm
+    sift_wasm, orb_wasm, brisk_wasm, akaze_wasm, kaze_wasm,
+    fast_flat_wasm, good_features_to_track_flat_wasm, orb_flat_wasm
 };
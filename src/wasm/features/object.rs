@@ -9,14 +9,13 @@ use crate::wasm::WasmMat;
 pub async fn detect_aruco_wasm(src: &WasmMat, dict_id: i32) -> Result<WasmMat, JsValue> {
     use crate::objdetect::aruco::{ArucoDetector, ArucoDictionary};
     use crate::imgproc::drawing::{line, circle};
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -67,14 +66,13 @@ pub async fn detect_aruco_wasm(src: &WasmMat, dict_id: i32) -> Result<WasmMat, J
 pub async fn detect_qr_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::objdetect::qr_detector::QRCodeDetector;
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -117,15 +115,13 @@ pub async fn detect_qr_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 #[wasm_bindgen(js_name = hogDescriptor)]
 pub async fn hog_descriptor_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::objdetect::hog::HOGDescriptor;
-    use crate::imgproc::color::cvt_color;
-    use crate::imgproc::drawing::rectangle;
-    use crate::core::types::{ColorConversionCode, Rect, Scalar};
+    use crate::core::types::Size;
 
     // Convert to grayscale
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -139,18 +135,26 @@ pub async fn hog_descriptor_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
             // HOG descriptor uses CPU implementation
         }
         cpu => {
-            let hog = HOGDescriptor::new();
-            let _descriptors = hog.compute(&gray)
+            let hog = HOGDescriptor::new().with_cell_size(Size::new(16, 16)).with_block_size(Size::new(32, 32));
+            let glyphs = hog.visualize(&gray)
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-            // Draw grid to show HOG cells
-            let cell_size = 16;
-            let color = Scalar::new(0.0, 255.0, 0.0, 255.0);
-
-            for y in (0..result.rows()).step_by(cell_size) {
-                for x in (0..result.cols()).step_by(cell_size) {
-                    let rect = Rect::new(x as i32, y as i32, cell_size as i32, cell_size as i32);
-                    let _ = rectangle(&mut result, rect, color, 1);
+            // Overlay the orientation glyphs in green
+            let channels = result.channels();
+            for row in 0..result.rows() {
+                for col in 0..result.cols() {
+                    let g = glyphs.at(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0];
+                    if g == 0 {
+                        continue;
+                    }
+                    let px = result.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?;
+                    if channels >= 3 {
+                        px[0] = 0;
+                        px[1] = g;
+                        px[2] = 0;
+                    } else {
+                        px[0] = g;
+                    }
                 }
             }
         }
@@ -165,14 +169,13 @@ pub async fn hog_descriptor_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 pub async fn cascade_classifier_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::objdetect::cascade::CascadeClassifier;
     use crate::imgproc::drawing::rectangle;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::{ColorConversionCode, Rect, Scalar};
+    use crate::core::types::{Rect, Scalar};
 
     // Convert to grayscale
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
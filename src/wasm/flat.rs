@@ -0,0 +1,79 @@
+//! Flat typed-array result layouts
+//!
+//! The `*Flat` bindings return detection results as one `Float32Array` per
+//! call instead of drawing them or building nested JS objects, so real-time
+//! loops pay a single copy out of WASM memory and no per-element allocation.
+//! They are synchronous and always run on the CPU.
+//!
+//! Layouts (all values `f32`, integers stored exactly):
+//!
+//! - **Keypoints** (`fastFlat`, `goodFeaturesToTrackFlat`, `orbFlat`):
+//!   [`KEYPOINT_STRIDE`] values per keypoint,
+//!   `[x, y, size, angle, response, octave]`
+//! - **Contours** (`findContoursFlat`): `[count, n0, x, y, ..., n1, x, y, ...]`,
+//!   the number of contours followed by each contour as its point count and
+//!   that many `x, y` pairs
+//! - **Dense flow** (`farnebackFlowFlat`): `[dx, dy]` per pixel in row-major
+//!   order, `width * height * 2` values
+//! - **Sparse flow** (`lucasKanadeFlat`): [`TRACKED_POINT_STRIDE`] values per
+//!   input point, `[x, y, status]` with status 1 if the point was tracked
+//!
+//! ```javascript
+//! const kp = fastFlat(frame, 20, true);
+//! for (let i = 0; i < kp.length; i += 6) {
+//!   ctx.fillRect(kp[i] - 1, kp[i + 1] - 1, 3, 3);
+//! }
+//! ```
+
+#![allow(clippy::cast_precision_loss)]
+
+use wasm_bindgen::prelude::*;
+use crate::core::Mat;
+use crate::core::types::Point;
+
+/// Values per keypoint in a flat keypoint array
+pub const KEYPOINT_STRIDE: usize = 6;
+
+/// Values per point in a flat sparse flow array
+pub const TRACKED_POINT_STRIDE: usize = 3;
+
+#[cfg(feature = "features2d")]
+pub(crate) fn pack_keypoints(keypoints: &[crate::features2d::KeyPoint]) -> Vec<f32> {
+    let _scope = crate::core::AllocScope::Wasm.enter();
+    let mut out = Vec::with_capacity(keypoints.len() * KEYPOINT_STRIDE);
+    for kp in keypoints {
+        out.extend_from_slice(&[
+            kp.pt.x as f32,
+            kp.pt.y as f32,
+            kp.size,
+            kp.angle,
+            kp.response,
+            kp.octave as f32,
+        ]);
+    }
+    out
+}
+
+pub(crate) fn pack_contours(contours: &[Vec<Point>]) -> Vec<f32> {
+    let _scope = crate::core::AllocScope::Wasm.enter();
+    let len = 1 + contours.iter().map(|c| 1 + 2 * c.len()).sum::<usize>();
+    let mut out = Vec::with_capacity(len);
+    out.push(contours.len() as f32);
+    for contour in contours {
+        out.push(contour.len() as f32);
+        for p in contour {
+            out.extend_from_slice(&[p.x as f32, p.y as f32]);
+        }
+    }
+    out
+}
+
+/// Single-channel copy of `src`, converted by its
+/// [`ColorOrder`](crate::core::types::ColorOrder) tag (see [`crate::imgproc::to_gray`])
+pub(crate) fn to_gray(src: &Mat) -> Result<Mat, JsValue> {
+    let mut gray = Mat::new(src.rows(), src.cols(), 1, src.depth())
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    crate::imgproc::to_gray(src, &mut gray)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(gray)
+}
@@ -14,12 +14,6 @@ pub async fn cvt_color_gray_wasm(
     let mut dst = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    let code = if src.inner.channels() == 4 {
-        ColorConversionCode::RgbaToGray
-    } else {
-        ColorConversionCode::RgbToGray
-    };
-
     // Backend dispatch
     crate::backend_dispatch! {
         gpu => {
@@ -28,7 +22,7 @@ pub async fn cvt_color_gray_wasm(
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
         }
         cpu => {
-            crate::imgproc::cvt_color(&src.inner, &mut dst, code)
+            crate::imgproc::to_gray(&src.inner, &mut dst)
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
         }
     }
@@ -114,6 +108,8 @@ pub async fn cvt_color_ycrcb_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 }
 
 /// Convert RGB to grayscale (GPU-accelerated)
+///
+/// BGR-tagged input is converted as BGR on both backends, like `cvtColorGray`
 #[wasm_bindgen(js_name = rgbToGray)]
 pub async fn rgb_to_gray_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     let mut dst = Mat::new(src.inner.rows(), src.inner.cols(), 1, MatDepth::U8)
@@ -127,7 +123,7 @@ pub async fn rgb_to_gray_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
         }
         cpu => {
-            crate::imgproc::cvt_color(&src.inner, &mut dst, ColorConversionCode::RgbToGray)
+            crate::imgproc::to_gray(&src.inner, &mut dst)
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
         }
     }
@@ -11,14 +11,13 @@ pub async fn find_contours_wasm(src: &WasmMat, threshold_value: f64) -> Result<W
     use crate::imgproc::threshold::threshold;
     use crate::core::types::ThresholdType;
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -64,14 +63,13 @@ pub async fn bounding_rect_wasm(src: &WasmMat, threshold_value: f64) -> Result<W
     use crate::imgproc::threshold::threshold;
     use crate::core::types::ThresholdType;
     use crate::imgproc::drawing::rectangle;
-    use crate::core::types::{ColorConversionCode, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::Scalar;
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -114,14 +112,13 @@ pub async fn contour_area_wasm(src: &WasmMat, threshold_value: f64) -> Result<Wa
     use crate::imgproc::threshold::threshold;
     use crate::core::types::ThresholdType;
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -176,14 +173,13 @@ pub async fn arc_length_wasm(src: &WasmMat, threshold_value: f64) -> Result<Wasm
     use crate::imgproc::threshold::threshold;
     use crate::core::types::ThresholdType;
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -238,14 +234,13 @@ pub async fn approx_poly_dp_wasm(src: &WasmMat, threshold_value: f64, epsilon: f
     use crate::imgproc::threshold::threshold;
     use crate::core::types::ThresholdType;
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -291,8 +286,7 @@ pub async fn approx_poly_dp_wasm(src: &WasmMat, threshold_value: f64, epsilon: f
 pub async fn moments_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat, JsValue> {
     use crate::imgproc::contours::{find_contours, moments};
     use crate::imgproc::threshold::threshold;
-    use crate::core::types::{ColorConversionCode, ThresholdType};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::ThresholdType;
     use crate::imgproc::drawing::circle;
     use crate::core::types::{Point, Scalar};
 
@@ -300,7 +294,7 @@ pub async fn moments_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -341,20 +335,20 @@ pub async fn moments_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat
 
 
 // ===== minEnclosingCircle =====
+#[cfg(feature = "shape")]
 #[wasm_bindgen(js_name = minEnclosingCircle)]
 pub async fn min_enclosing_circle_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat, JsValue> {
     use crate::imgproc::contours::find_contours;
     use crate::imgproc::threshold::threshold;
-    use crate::imgproc::color::cvt_color;
     use crate::imgproc::drawing::circle;
     use crate::shape::descriptors::min_enclosing_circle;
-    use crate::core::types::{ColorConversionCode, ThresholdType, Point, Scalar};
+    use crate::core::types::{ThresholdType, Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -391,20 +385,20 @@ pub async fn min_enclosing_circle_wasm(src: &WasmMat, threshold_value: f64) -> R
 
 
 // ===== convexHull =====
+#[cfg(feature = "shape")]
 #[wasm_bindgen(js_name = convexHull)]
 pub async fn convex_hull_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat, JsValue> {
     use crate::imgproc::contours::find_contours;
     use crate::imgproc::threshold::threshold;
-    use crate::imgproc::color::cvt_color;
     use crate::imgproc::drawing::polylines;
     use crate::shape::descriptors::convex_hull;
-    use crate::core::types::{ColorConversionCode, ThresholdType, Scalar};
+    use crate::core::types::{ThresholdType, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -441,19 +435,19 @@ pub async fn convex_hull_wasm(src: &WasmMat, threshold_value: f64) -> Result<Was
 
 
 // ===== huMoments =====
+#[cfg(feature = "shape")]
 #[wasm_bindgen(js_name = huMoments)]
 pub async fn hu_moments_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat, JsValue> {
     use crate::imgproc::threshold::threshold;
-    use crate::imgproc::color::cvt_color;
     use crate::imgproc::drawing::put_text;
     use crate::shape::moments::{compute_moments, hu_moments};
-    use crate::core::types::{ColorConversionCode, ThresholdType, Point, Scalar};
+    use crate::core::types::{ThresholdType, Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -492,21 +486,21 @@ pub async fn hu_moments_wasm(src: &WasmMat, threshold_value: f64) -> Result<Wasm
 
 
 // ===== matchShapes =====
+#[cfg(feature = "shape")]
 #[wasm_bindgen(js_name = matchShapes)]
 pub async fn match_shapes_wasm(src: &WasmMat, threshold_value: f64) -> Result<WasmMat, JsValue> {
     use crate::imgproc::contours::find_contours;
     use crate::imgproc::threshold::threshold;
-    use crate::imgproc::color::cvt_color;
     use crate::imgproc::drawing::{polylines, put_text};
     use crate::shape::matching::{match_shapes, ShapeMatchMethod};
     use crate::shape::moments::compute_moments;
-    use crate::core::types::{ColorConversionCode, ThresholdType, Point, Scalar};
+    use crate::core::types::{ThresholdType, Point, Scalar};
 
     // Convert to grayscale and threshold
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -556,3 +550,24 @@ pub async fn match_shapes_wasm(src: &WasmMat, threshold_value: f64) -> Result<Wa
 }
 
 
+
+// ===== findContoursFlat =====
+/// External contours of the thresholded image as a flat `Float32Array`
+/// (see [`crate::wasm::flat`])
+#[wasm_bindgen(js_name = findContoursFlat)]
+pub fn find_contours_flat_wasm(src: &WasmMat, threshold_value: f64) -> Result<Vec<f32>, JsValue> {
+    use crate::imgproc::contours::{find_contours, ChainApproxMode, RetrievalMode};
+    use crate::imgproc::threshold::threshold;
+    use crate::core::types::ThresholdType;
+    use crate::wasm::flat::{pack_contours, to_gray};
+
+    let gray = to_gray(&src.inner)?;
+    let mut binary = Mat::new(gray.rows(), gray.cols(), 1, gray.depth())
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    threshold(&gray, &mut binary, threshold_value, 255.0, ThresholdType::Binary)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let contours = find_contours(&binary, RetrievalMode::External, ChainApproxMode::Simple)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(pack_contours(&contours))
+}
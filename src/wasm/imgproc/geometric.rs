@@ -44,6 +44,49 @@ pub async fn resize_wasm(
     Ok(WasmMat { inner: dst })
 }
 
+/// Resize with an explicit interpolation mode (WASM-compatible, GPU-accelerated, ASYNC)
+///
+/// `interpolation` uses `OpenCV` codes: 0=nearest, 1=linear, 2=cubic, 3=area, 4=lanczos4
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = resizeWithInterpolation)]
+pub async fn resize_with_interpolation_wasm(
+    src: &WasmMat,
+    dst_width: usize,
+    dst_height: usize,
+    interpolation: i32,
+) -> Result<WasmMat, JsValue> {
+    let interp = match interpolation {
+        0 => InterpolationFlag::Nearest,
+        1 => InterpolationFlag::Linear,
+        2 => InterpolationFlag::Cubic,
+        3 => InterpolationFlag::Area,
+        4 => InterpolationFlag::Lanczos4,
+        _ => return Err(JsValue::from_str("Invalid interpolation, use 0-4")),
+    };
+
+    let mut dst = Mat::new(dst_height, dst_width, src.inner.channels(), MatDepth::U8)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    crate::backend_dispatch! {
+        gpu => {
+            crate::gpu::ops::resize_gpu_with_interpolation_async(&src.inner, &mut dst, dst_width, dst_height, interp)
+                .await
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        }
+        cpu => {
+            crate::imgproc::resize(
+                &src.inner,
+                &mut dst,
+                Size::new(dst_width as i32, dst_height as i32),
+                interp,
+            )
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        }
+    }
+
+    Ok(WasmMat { inner: dst })
+}
+
 /// Flip image (WASM-compatible)
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(js_name = flip)]
@@ -243,6 +286,71 @@ pub async fn get_rotation_matrix_2d_wasm(
     Ok(WasmMat { inner: dst })
 }
 
+/// Rotate by an arbitrary angle, enlarging the canvas so no corner is cropped
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = rotateBound)]
+pub async fn rotate_bound_wasm(src: &WasmMat, angle: f64, scale: f64) -> Result<WasmMat, JsValue> {
+    use crate::imgproc::geometric::{get_rotation_matrix_2d_bounded, invert_affine_transform, rotate_bound};
+
+    let (m, bound) = get_rotation_matrix_2d_bounded(src.inner.size(), angle, scale);
+    let mut dst = Mat::new(bound.height as usize, bound.width as usize, src.inner.channels(), src.inner.depth())
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    // Backend dispatch
+    crate::backend_dispatch! {
+        gpu => {
+            let inv = invert_affine_transform(&m);
+            let m_gpu: [f32; 6] = [
+                inv[0][0] as f32, inv[0][1] as f32, inv[0][2] as f32,
+                inv[1][0] as f32, inv[1][1] as f32, inv[1][2] as f32,
+            ];
+            crate::gpu::ops::warp_affine_gpu_async(&src.inner, &mut dst, &m_gpu, (bound.width as usize, bound.height as usize))
+                .await
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        }
+        cpu => {
+            rotate_bound(&src.inner, &mut dst, angle, scale)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        }
+    }
+
+    Ok(WasmMat { inner: dst })
+}
+
+/// Polar / log-polar unwrap around (center_x, center_y)
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = warpPolar)]
+pub async fn warp_polar_wasm(
+    src: &WasmMat,
+    width: i32,
+    height: i32,
+    center_x: f32,
+    center_y: f32,
+    max_radius: f64,
+    log: bool,
+    inverse: bool,
+) -> Result<WasmMat, JsValue> {
+    use crate::imgproc::geometric::{warp_polar, WarpPolarMode};
+    use crate::core::types::Point2f;
+
+    let mode = if log { WarpPolarMode::Log } else { WarpPolarMode::Linear };
+    let mut dst = Mat::new(1, 1, src.inner.channels(), src.inner.depth())
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    warp_polar(
+        &src.inner,
+        &mut dst,
+        Size::new(width, height),
+        Point2f::new(center_x, center_y),
+        max_radius,
+        mode,
+        inverse,
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok(WasmMat { inner: dst })
+}
+
 /// Remap (generic pixel remapping) - GPU-accelerated
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(js_name = remap)]
@@ -7,14 +7,11 @@ use crate::wasm::WasmMat;
 // ===== equalizeHistogram =====
 #[wasm_bindgen(js_name = equalizeHistogram)]
 pub async fn equalize_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::core::types::ColorConversionCode;
-    use crate::imgproc::color::cvt_color;
-
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -44,14 +41,13 @@ pub async fn equalize_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue>
 #[wasm_bindgen(js_name = calcHistogram)]
 pub async fn calc_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::imgproc::drawing::rectangle;
-    use crate::core::types::{ColorConversionCode, Rect, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Rect, Scalar};
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -117,8 +113,6 @@ pub async fn calc_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 // ===== normalizeHistogram =====
 #[wasm_bindgen(js_name = normalizeHistogram)]
 pub async fn normalize_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
     use crate::imgproc::drawing::rectangle;
     use crate::core::types::{Rect, Scalar};
 
@@ -126,7 +120,7 @@ pub async fn normalize_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue>
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -185,14 +179,11 @@ pub async fn normalize_histogram_wasm(src: &WasmMat) -> Result<WasmMat, JsValue>
 // ===== compareHistograms =====
 #[wasm_bindgen(js_name = compareHistograms)]
 pub async fn compare_histograms_wasm(src1: &WasmMat, src2: &WasmMat) -> Result<f64, JsValue> {
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
-
     // Convert both to grayscale
     let gray1 = if src1.inner.channels() > 1 {
         let mut g = Mat::new(src1.inner.rows(), src1.inner.cols(), 1, src1.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src1.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src1.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -202,7 +193,7 @@ pub async fn compare_histograms_wasm(src1: &WasmMat, src2: &WasmMat) -> Result<f
     let gray2 = if src2.inner.channels() > 1 {
         let mut g = Mat::new(src2.inner.rows(), src2.inner.cols(), 1, src2.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src2.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src2.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -239,14 +230,11 @@ pub async fn compare_histograms_wasm(src1: &WasmMat, src2: &WasmMat) -> Result<f
 // ===== backProjection =====
 #[wasm_bindgen(js_name = backProjection)]
 pub async fn back_projection_wasm(src: &WasmMat, model: &WasmMat) -> Result<WasmMat, JsValue> {
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
-
     // Convert both to grayscale
     let gray_src = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -256,7 +244,7 @@ pub async fn back_projection_wasm(src: &WasmMat, model: &WasmMat) -> Result<Wasm
     let gray_model = if model.inner.channels() > 1 {
         let mut g = Mat::new(model.inner.rows(), model.inner.cols(), 1, model.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&model.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&model.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -21,8 +21,9 @@ pub use drawing::{
 };
 #[cfg(target_arch = "wasm32")]
 pub use geometric::{
-    resize_wasm, flip_wasm, rotate_wasm, warp_affine_wasm,
-    warp_perspective_wasm, get_rotation_matrix_2d_wasm, remap_wasm
+    resize_wasm, resize_with_interpolation_wasm, flip_wasm, rotate_wasm, warp_affine_wasm,
+    warp_perspective_wasm, get_rotation_matrix_2d_wasm, remap_wasm,
+    rotate_bound_wasm, warp_polar_wasm
 };
 #[cfg(target_arch = "wasm32")]
 pub use color::{
@@ -26,7 +26,10 @@ macro_rules! backend_dispatch {
             1 => {
                 // GPU backend selected
                 #[cfg(feature = "gpu")]
-                $gpu_block
+                {
+                    let _scope = $crate::core::AllocScope::Gpu.enter();
+                    $gpu_block
+                }
 
                 #[cfg(not(feature = "gpu"))]
                 {
@@ -79,6 +82,7 @@ macro_rules! backend_dispatch_gpu {
                 // GPU backend selected
                 #[cfg(feature = "gpu")]
                 {
+                    let _scope = $crate::core::AllocScope::Gpu.enter();
                     $gpu_fn($src, $dst, $($param),*)
                         .await
                         .map_err(|e| {
@@ -0,0 +1,37 @@
+//! Heap usage per module, for attributing WASM memory growth
+//!
+//! Counters are only populated in builds with the `alloc-tracking` feature.
+
+use wasm_bindgen::prelude::*;
+use crate::core::{allocation_stats, reset_peaks, AllocScope};
+
+/// Heap counters as `{ tracking, heapBytes, core: {...}, gpu: {...}, wasm: {...}, other: {...} }`
+///
+/// Each scope has `liveBytes`, `peakBytes`, `allocations` and
+/// `deallocations`. `heapBytes` is the size of the linear memory, which
+/// never shrinks; `tracking` is false when the build has no tracking
+/// allocator, and the scope counters are all zero then.
+#[wasm_bindgen(js_name = allocationStats)]
+pub fn allocation_stats_js() -> Result<js_sys::Object, JsValue> {
+    let stats = allocation_stats();
+    let out = js_sys::Object::new();
+    js_sys::Reflect::set(&out, &"tracking".into(), &stats.tracking.into())?;
+    let heap_bytes = core::arch::wasm32::memory_size(0) * 65536;
+    js_sys::Reflect::set(&out, &"heapBytes".into(), &(heap_bytes as f64).into())?;
+    for scope in AllocScope::ALL {
+        let s = stats.scope(scope);
+        let entry = js_sys::Object::new();
+        js_sys::Reflect::set(&entry, &"liveBytes".into(), &(s.live_bytes as f64).into())?;
+        js_sys::Reflect::set(&entry, &"peakBytes".into(), &(s.peak_bytes as f64).into())?;
+        js_sys::Reflect::set(&entry, &"allocations".into(), &(s.allocations as f64).into())?;
+        js_sys::Reflect::set(&entry, &"deallocations".into(), &(s.deallocations as f64).into())?;
+        js_sys::Reflect::set(&out, &scope.name().into(), &entry)?;
+    }
+    Ok(out)
+}
+
+/// Restart peak tracking from the current live bytes
+#[wasm_bindgen(js_name = resetAllocationPeaks)]
+pub fn reset_allocation_peaks() {
+    reset_peaks();
+}
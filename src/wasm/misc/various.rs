@@ -8,21 +8,19 @@ use crate::wasm::WasmMat;
 #[wasm_bindgen(js_name = distanceTransform)]
 pub async fn distance_transform_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::imgproc::advanced_filter::distance_transform;
-    use crate::core::types::ColorConversionCode;
-    use crate::imgproc::color::cvt_color;
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
         src.inner.clone()
     };
 
-    let mut dst = Mat::new(gray.rows(), gray.cols(), 1, MatDepth::U8)
+    let mut dst = Mat::new(gray.rows(), gray.cols(), 1, MatDepth::F32)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
     crate::backend_dispatch! {
@@ -37,7 +35,28 @@ pub async fn distance_transform_wasm(src: &WasmMat) -> Result<WasmMat, JsValue>
         }
     }
 
-    Ok(WasmMat { inner: dst })
+    Ok(WasmMat { inner: distances_to_u8(&dst)? })
+}
+
+/// Scale the F32 distances of either backend to U8 for display, the
+/// largest finite distance mapping to 255
+fn distances_to_u8(dist: &Mat) -> Result<Mat, JsValue> {
+    if dist.depth() != MatDepth::F32 {
+        return Err(JsValue::from_str("distance transform backends must return F32 distances"));
+    }
+    let values: Vec<f32> = dist.data()
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    let max = values.iter().copied().filter(|d| *d < f32::MAX).fold(0.0f32, f32::max);
+    let scale = if max > 0.0 { 255.0 / max } else { 0.0 };
+
+    let mut out = Mat::new(dist.rows(), dist.cols(), 1, MatDepth::U8)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    for (o, d) in out.data_mut().iter_mut().zip(values) {
+        *o = if d < f32::MAX { (d * scale).round().clamp(0.0, 255.0) as u8 } else { 255 };
+    }
+    Ok(out)
 }
 
 
@@ -48,14 +67,13 @@ pub async fn hough_lines_wasm(
     threshold: i32,
 ) -> Result<WasmMat, JsValue> {
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -105,14 +123,13 @@ pub async fn hough_lines_p_wasm(
 ) -> Result<WasmMat, JsValue> {
     use crate::imgproc::hough::hough_lines_p;
     use crate::imgproc::drawing::line;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -146,14 +163,13 @@ pub async fn hough_circles_wasm(
 ) -> Result<WasmMat, JsValue> {
     use crate::imgproc::hough::{hough_circles, HoughCirclesMethod};
     use crate::imgproc::drawing::circle;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
-    use crate::imgproc::color::cvt_color;
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -179,14 +195,12 @@ pub async fn hough_circles_wasm(
 #[wasm_bindgen(js_name = logFilter)]
 pub async fn log_filter_wasm(src: &WasmMat, ksize: i32, sigma: f64) -> Result<WasmMat, JsValue> {
     use crate::imgproc::advanced_filter::laplacian_of_gaussian;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
 
     // Convert to grayscale if needed
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -204,6 +218,7 @@ pub async fn log_filter_wasm(src: &WasmMat, ksize: i32, sigma: f64) -> Result<Wa
 
 
 // ===== inpaint =====
+#[cfg(feature = "photo")]
 #[wasm_bindgen(js_name = inpaint)]
 pub async fn inpaint_wasm(src: &WasmMat, radius: i32) -> Result<WasmMat, JsValue> {
     use crate::photo::inpaint;
@@ -238,6 +253,7 @@ pub async fn inpaint_wasm(src: &WasmMat, radius: i32) -> Result<WasmMat, JsValue
 
 
 // ===== tonemapDrago =====
+#[cfg(feature = "photo")]
 #[wasm_bindgen(js_name = tonemapDrago)]
 pub async fn tonemap_drago_wasm(src: &WasmMat, bias: f64) -> Result<WasmMat, JsValue> {
     use crate::photo::hdr::TonemapDrago;
@@ -251,6 +267,7 @@ pub async fn tonemap_drago_wasm(src: &WasmMat, bias: f64) -> Result<WasmMat, JsV
 
 
 // ===== tonemapReinhard =====
+#[cfg(feature = "photo")]
 #[wasm_bindgen(js_name = tonemapReinhard)]
 pub async fn tonemap_reinhard_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::photo::hdr::TonemapReinhard;
@@ -264,18 +281,18 @@ pub async fn tonemap_reinhard_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 
 
 // ===== bruteForceMatcher =====
+#[cfg(feature = "features2d")]
 #[wasm_bindgen(js_name = bruteForceMatcher)]
 pub async fn brute_force_matcher_wasm(src: &WasmMat, n_features: usize) -> Result<WasmMat, JsValue> {
     use crate::features2d::SIFTF32;
-    use crate::imgproc::color::cvt_color;
     use crate::imgproc::drawing::circle;
-    use crate::core::types::{ColorConversionCode, Point, Scalar};
+    use crate::core::types::{Point, Scalar};
 
     // Convert to grayscale
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -322,6 +339,7 @@ pub async fn brute_force_matcher_wasm(src: &WasmMat, n_features: usize) -> Resul
 
 
 // ===== superResolution =====
+#[cfg(feature = "photo")]
 #[wasm_bindgen(js_name = superResolution)]
 pub async fn super_resolution_wasm(src: &WasmMat, scale: f32) -> Result<WasmMat, JsValue> {
     use crate::photo::super_resolution::SuperResolutionBicubic;
@@ -335,6 +353,7 @@ pub async fn super_resolution_wasm(src: &WasmMat, scale: f32) -> Result<WasmMat,
 
 
 // ===== mergeDebevec =====
+#[cfg(feature = "photo")]
 #[wasm_bindgen(js_name = mergeDebevec)]
 pub async fn merge_debevec_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::photo::hdr::MergeDebevec;
@@ -391,6 +410,7 @@ pub async fn feather_blender_wasm(src: &WasmMat, blend_strength: f32) -> Result<
 
 
 // ===== multibandBlender =====
+#[cfg(feature = "stitching")]
 #[wasm_bindgen(js_name = multibandBlender)]
 pub async fn multiband_blender_wasm(src: &WasmMat, num_bands: usize) -> Result<WasmMat, JsValue> {
     use crate::stitching::blending::MultiBandBlender;
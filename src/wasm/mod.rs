@@ -5,17 +5,25 @@
 
 pub mod backend;
 pub mod macros;
+pub mod flat;
 pub mod basic;
 pub mod imgproc;
+#[cfg(any(feature = "features2d", feature = "objdetect"))]
 pub mod features;
 pub mod arithmetic;
 pub mod comparison;
+#[cfg(feature = "video")]
 pub mod video;
+#[cfg(all(feature = "calib3d", feature = "features2d"))]
 pub mod calib3d;
+#[cfg(feature = "dnn")]
 pub mod dnn;
+#[cfg(feature = "ml")]
 pub mod ml;
+#[cfg(feature = "ml")]
 pub mod segmentation;
 pub mod misc;
+pub mod memory;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -23,7 +31,7 @@ use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use crate::core::{Mat, MatDepth};
 #[cfg(target_arch = "wasm32")]
-use crate::core::types::{Size, InterpolationFlag, ColorConversionCode, ThresholdType};
+use crate::core::types::{Size, InterpolationFlag, ThresholdType};
 
 /// Initialize the WASM module with panic hooks for better error messages
 #[cfg(target_arch = "wasm32")]
@@ -39,6 +47,7 @@ pub fn wasm_init() {
 #[wasm_bindgen]
 pub fn init_thread_pool(num_threads: usize) -> Result<(), JsValue> {
     wasm_bindgen_rayon::init_thread_pool(num_threads);
+    crate::core::parallel::set_thread_pool_ready();
     web_sys::console::log_1(&format!("✓ Rayon thread pool initialized with {} threads", num_threads).into());
     Ok(())
 }
@@ -150,12 +159,16 @@ impl WasmMat {
         }
 
         mat_data.copy_from_slice(data);
+        if channels >= 3 {
+            mat.set_color_order(crate::core::types::ColorOrder::Rgb);
+        }
         Ok(WasmMat { inner: mat })
     }
 
     /// Get raw data as bytes (for creating ImageData in JS)
     #[wasm_bindgen(js_name = getData)]
     pub fn get_data(&self) -> Vec<u8> {
+        let _scope = crate::core::AllocScope::Wasm.enter();
         self.inner.data().to_vec()
     }
 
@@ -206,8 +219,9 @@ pub use imgproc::drawing::{
 };
 #[cfg(target_arch = "wasm32")]
 pub use imgproc::geometric::{
-    resize_wasm, flip_wasm, rotate_wasm, warp_affine_wasm,
-    warp_perspective_wasm, get_rotation_matrix_2d_wasm, remap_wasm
+    resize_wasm, resize_with_interpolation_wasm, flip_wasm, rotate_wasm, warp_affine_wasm,
+    warp_perspective_wasm, get_rotation_matrix_2d_wasm, remap_wasm,
+    rotate_bound_wasm, warp_polar_wasm
 };
 
 
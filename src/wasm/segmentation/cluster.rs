@@ -27,40 +27,45 @@ pub async fn watershed_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     let gray = if bgr.channels() > 1 {
         let mut g = Mat::new(bgr.rows(), bgr.cols(), 1, bgr.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&bgr, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&bgr, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
         bgr.clone()
     };
 
-    let mut markers = Mat::new(gray.rows(), gray.cols(), 1, MatDepth::U8)
+    let mut markers = Mat::new(gray.rows(), gray.cols(), 1, MatDepth::S32)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
     // Initialize markers: foreground (label 1), background (label 2), unknown (0)
     for row in 0..markers.rows() {
         for col in 0..markers.cols() {
             let val = gray.at(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0];
-            markers.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0] = if val < 50 {
+            let label = if val < 50 {
                 1 // Foreground
             } else if val > 200 {
                 2 // Background
             } else {
                 0 // Unknown
             };
+            markers.set_i32(row, col, 0, label).map_err(|e| JsValue::from_str(&e.to_string()))?;
         }
     }
 
     watershed(&bgr, &mut markers)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    // Visualize markers - multiply by 50 to make labels visible
+    // Visualize markers - multiply by 50 to make labels visible, boundaries white
     let mut result = Mat::new(markers.rows(), markers.cols(), 1, MatDepth::U8)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
     for row in 0..markers.rows() {
         for col in 0..markers.cols() {
-            let marker = markers.at(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0];
-            result.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0] = (marker.saturating_mul(50)).min(255);
+            let marker = markers.at_i32(row, col, 0).map_err(|e| JsValue::from_str(&e.to_string()))?;
+            result.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0] = if marker < 0 {
+                255
+            } else {
+                u8::try_from(marker.saturating_mul(50).min(255)).unwrap_or(255)
+            };
         }
     }
 
@@ -1,6 +1,9 @@
 //! Video analysis operations
 
 pub mod tracking;
+pub mod processor;
 
 #[cfg(target_arch = "wasm32")]
 pub use tracking::*;
+#[cfg(target_arch = "wasm32")]
+pub use processor::VideoProcessor;
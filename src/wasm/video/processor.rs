@@ -0,0 +1,507 @@
+//! Streaming video processing
+//!
+//! A [`VideoProcessor`] holds a pipeline of steps and runs it on every
+//! frame of a `<video>` element (via `requestVideoFrameCallback`, drawing
+//! to a canvas) or of a camera `MediaStreamTrack` (via
+//! `MediaStreamTrackProcessor`, producing a new track through a
+//! `MediaStreamTrackGenerator`). At most one frame is in flight: frames
+//! that arrive while the pipeline is busy are dropped and counted, so a
+//! slow pipeline lowers the frame rate instead of building up latency.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::convert::TryFromJsValue;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+use crate::core::{Mat, MatDepth};
+use crate::video::background_subtraction::BackgroundSubtractorMOG2;
+use crate::wasm::WasmMat;
+
+// Browser APIs web-sys lacks (requestVideoFrameCallback) or only exposes
+// behind `web_sys_unstable_apis` (WebCodecs and insertable streams)
+#[wasm_bindgen]
+extern "C" {
+    #[derive(Clone)]
+    type FrameCallbackVideo;
+
+    #[wasm_bindgen(method, js_name = requestVideoFrameCallback)]
+    fn request_video_frame_callback(this: &FrameCallbackVideo, callback: &js_sys::Function) -> u32;
+
+    #[wasm_bindgen(method, js_name = cancelVideoFrameCallback)]
+    fn cancel_video_frame_callback(this: &FrameCallbackVideo, handle: u32);
+
+    type VideoFrame;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(source: &web_sys::OffscreenCanvas, init: &js_sys::Object) -> Result<VideoFrame, JsValue>;
+
+    #[wasm_bindgen(method, getter, js_name = displayWidth)]
+    fn display_width(this: &VideoFrame) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = displayHeight)]
+    fn display_height(this: &VideoFrame) -> u32;
+
+    #[wasm_bindgen(method, getter)]
+    fn timestamp(this: &VideoFrame) -> f64;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &VideoFrame);
+
+    type MediaStreamTrackProcessor;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(init: &js_sys::Object) -> Result<MediaStreamTrackProcessor, JsValue>;
+
+    #[wasm_bindgen(method, getter)]
+    fn readable(this: &MediaStreamTrackProcessor) -> web_sys::ReadableStream;
+
+    type MediaStreamTrackGenerator;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(init: &js_sys::Object) -> Result<MediaStreamTrackGenerator, JsValue>;
+
+    #[wasm_bindgen(method, getter)]
+    fn writable(this: &MediaStreamTrackGenerator) -> web_sys::WritableStream;
+
+    /// An `OffscreenCanvasRenderingContext2d` viewed through the
+    /// `drawImage(VideoFrame)` overload
+    type FrameContext;
+
+    #[wasm_bindgen(method, js_name = drawImage)]
+    fn draw_video_frame(this: &FrameContext, frame: &VideoFrame, dx: f64, dy: f64);
+}
+
+#[derive(Clone)]
+enum Step {
+    Grayscale,
+    GaussianBlur { ksize: usize, sigma: f64 },
+    Canny { threshold1: f64, threshold2: f64 },
+    Threshold { thresh: f64, max_val: f64 },
+    BackgroundSubtraction { model: Rc<RefCell<BackgroundSubtractorMOG2>>, learning_rate: f64 },
+    Custom(js_sys::Function),
+}
+
+/// 2-D canvas sized on demand, reused across frames
+struct Surface {
+    canvas: web_sys::OffscreenCanvas,
+    context: web_sys::OffscreenCanvasRenderingContext2d,
+}
+
+impl Surface {
+    fn new() -> Result<Self, JsValue> {
+        let canvas = web_sys::OffscreenCanvas::new(1, 1)?;
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("2D canvas context not available"))?
+            .dyn_into::<web_sys::OffscreenCanvasRenderingContext2d>()?;
+        Ok(Self { canvas, context })
+    }
+
+    fn resize(&self, width: u32, height: u32) {
+        if self.canvas.width() != width || self.canvas.height() != height {
+            self.canvas.set_width(width);
+            self.canvas.set_height(height);
+        }
+    }
+
+    /// Current contents as a 4-channel RGBA Mat
+    fn read(&self) -> Result<Mat, JsValue> {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let pixels = self.context.get_image_data(0.0, 0.0, f64::from(width), f64::from(height))?;
+        let mut mat = Mat::new(height as usize, width as usize, 4, MatDepth::U8)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        mat.data_mut().copy_from_slice(&pixels.data());
+        Ok(mat)
+    }
+}
+
+#[derive(Default)]
+struct ProcessorState {
+    steps: Vec<Step>,
+    /// Bumped by `stop`, ending the frame loops of earlier starts
+    generation: u32,
+    busy: bool,
+    frames_processed: u32,
+    frames_dropped: u32,
+    last_frame_ms: f64,
+    /// RGBA staging buffers for output, reallocated only when the size changes
+    rgba: Vec<u8>,
+    pixels: Option<js_sys::Uint8ClampedArray>,
+    video_callback: Option<VideoCallback>,
+}
+
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64, JsValue)>>>>;
+
+/// The pending `requestVideoFrameCallback` registration of `attachVideo`
+struct VideoCallback {
+    video: FrameCallbackVideo,
+    handle: u32,
+    closure: FrameCallback,
+}
+
+/// Runs a pipeline of operations on every frame of a video or camera track
+///
+/// ```javascript
+/// const processor = new VideoProcessor();
+/// processor.addGrayscale();
+/// processor.addCanny(50, 150);
+/// processor.attachVideo(videoElement, canvas);
+/// // or: const processed = processor.attachTrack(cameraTrack);
+/// ```
+#[wasm_bindgen]
+pub struct VideoProcessor {
+    state: Rc<RefCell<ProcessorState>>,
+}
+
+impl Default for VideoProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl VideoProcessor {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> VideoProcessor {
+        VideoProcessor { state: Rc::new(RefCell::new(ProcessorState::default())) }
+    }
+
+    /// Append a grayscale conversion
+    #[wasm_bindgen(js_name = addGrayscale)]
+    pub fn add_grayscale(&self) {
+        self.push(Step::Grayscale);
+    }
+
+    /// Append a Gaussian blur
+    #[wasm_bindgen(js_name = addGaussianBlur)]
+    pub fn add_gaussian_blur(&self, ksize: usize, sigma: f64) {
+        self.push(Step::GaussianBlur { ksize, sigma });
+    }
+
+    /// Append Canny edge detection (converts to grayscale first)
+    #[wasm_bindgen(js_name = addCanny)]
+    pub fn add_canny(&self, threshold1: f64, threshold2: f64) {
+        self.push(Step::Canny { threshold1, threshold2 });
+    }
+
+    /// Append a binary threshold
+    #[wasm_bindgen(js_name = addThreshold)]
+    pub fn add_threshold(&self, thresh: f64, max_val: f64) {
+        self.push(Step::Threshold { thresh, max_val });
+    }
+
+    /// Append MOG2 background subtraction; the model persists across frames
+    /// and outputs the foreground mask
+    #[wasm_bindgen(js_name = addBackgroundSubtraction)]
+    pub fn add_background_subtraction(&self, learning_rate: f64) {
+        self.push(Step::BackgroundSubtraction {
+            model: Rc::new(RefCell::new(BackgroundSubtractorMOG2::new())),
+            learning_rate,
+        });
+    }
+
+    /// Append a JavaScript step: `(WasmMat) => WasmMat | Promise<WasmMat>`
+    #[wasm_bindgen(js_name = addStep)]
+    pub fn add_step(&self, step: js_sys::Function) {
+        self.push(Step::Custom(step));
+    }
+
+    /// Remove every step; frames then pass through unchanged
+    #[wasm_bindgen(js_name = clearPipeline)]
+    pub fn clear_pipeline(&self) {
+        self.state.borrow_mut().steps.clear();
+    }
+
+    /// Run the pipeline once on a single frame
+    #[wasm_bindgen(js_name = processFrame)]
+    pub async fn process_frame(&self, frame: &WasmMat) -> Result<WasmMat, JsValue> {
+        let steps = self.state.borrow().steps.clone();
+        let inner = run_pipeline(&steps, frame.inner.clone()).await?;
+        Ok(WasmMat { inner })
+    }
+
+    /// Process every frame `video` presents and draw the results to `canvas`
+    ///
+    /// Uses `requestVideoFrameCallback`, so frames are picked up as they are
+    /// composited rather than polled. Replaces any earlier attachment.
+    #[wasm_bindgen(js_name = attachVideo)]
+    pub fn attach_video(&self, video: web_sys::HtmlVideoElement, canvas: web_sys::HtmlCanvasElement) -> Result<(), JsValue> {
+        self.stop();
+        let output = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("2D canvas context not available"))?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+        let input = Surface::new()?;
+        let generation = self.state.borrow().generation;
+        let video: FrameCallbackVideo = video.unchecked_into();
+
+        // The callback re-registers itself for the next frame; `stop`
+        // cancels it and releases the closure
+        let callback: FrameCallback = Rc::new(RefCell::new(None));
+        let next = Rc::clone(&callback);
+        let state = Rc::clone(&self.state);
+        let element = video.clone();
+        let closure = Closure::new(move |_now: f64, _metadata: JsValue| {
+            if state.borrow().generation != generation {
+                return;
+            }
+            if let Some(cb) = next.borrow().as_ref() {
+                let handle = element.request_video_frame_callback(cb.as_ref().unchecked_ref());
+                if let Some(registered) = state.borrow_mut().video_callback.as_mut() {
+                    registered.handle = handle;
+                }
+            }
+
+            let html_video: &web_sys::HtmlVideoElement = element.unchecked_ref();
+            if html_video.video_width() == 0 || !begin_frame(&state) {
+                return;
+            }
+            input.resize(html_video.video_width(), html_video.video_height());
+            let frame = input
+                .context
+                .draw_image_with_html_video_element(html_video, 0.0, 0.0)
+                .and_then(|()| input.read());
+            let (state, output, canvas) = (Rc::clone(&state), output.clone(), canvas.clone());
+            spawn_local(async move {
+                let started = js_sys::Date::now();
+                let result = match frame {
+                    Ok(frame) => process(&state, frame).await,
+                    Err(e) => Err(e),
+                };
+                let drawn = result.and_then(|(pixels, width, height)| {
+                    if canvas.width() != width || canvas.height() != height {
+                        canvas.set_width(width);
+                        canvas.set_height(height);
+                    }
+                    let image = web_sys::ImageData::new_with_js_u8_clamped_array_and_sh(&pixels, width, height)?;
+                    output.put_image_data(&image, 0.0, 0.0)
+                });
+                end_frame(&state, started, drawn);
+            });
+        });
+
+        let handle = video.request_video_frame_callback(closure.as_ref().unchecked_ref());
+        *callback.borrow_mut() = Some(closure);
+        self.state.borrow_mut().video_callback = Some(VideoCallback { video, handle, closure: callback });
+        Ok(())
+    }
+
+    /// Process every frame of a video `track` and return the processed track
+    ///
+    /// Frames are pulled one at a time and the source keeps only the newest
+    /// unread frame, so a slow pipeline skips frames instead of queueing
+    /// them. Output frames keep the input timestamps. Replaces any earlier
+    /// attachment.
+    #[wasm_bindgen(js_name = attachTrack)]
+    pub fn attach_track(&self, track: web_sys::MediaStreamTrack) -> Result<web_sys::MediaStreamTrack, JsValue> {
+        self.stop();
+        let init = js_sys::Object::new();
+        js_sys::Reflect::set(&init, &"track".into(), &track)?;
+        js_sys::Reflect::set(&init, &"maxBufferSize".into(), &1.into())?;
+        let source = MediaStreamTrackProcessor::new(&init)?;
+
+        let init = js_sys::Object::new();
+        js_sys::Reflect::set(&init, &"kind".into(), &"video".into())?;
+        let generator = MediaStreamTrackGenerator::new(&init)?;
+
+        let reader: web_sys::ReadableStreamDefaultReader = source.readable().get_reader().unchecked_into();
+        let writer = generator.writable().get_writer()?;
+        let (input, output) = (Surface::new()?, Surface::new()?);
+        let state = Rc::clone(&self.state);
+        let generation = state.borrow().generation;
+
+        spawn_local(async move {
+            let result = async {
+                while state.borrow().generation == generation {
+                    let chunk = JsFuture::from(reader.read()).await?;
+                    if js_sys::Reflect::get(&chunk, &"done".into())?.is_truthy() {
+                        break;
+                    }
+                    let frame: VideoFrame = js_sys::Reflect::get(&chunk, &"value".into())?.unchecked_into();
+                    let timestamp = frame.timestamp();
+                    input.resize(frame.display_width(), frame.display_height());
+                    input.context.unchecked_ref::<FrameContext>().draw_video_frame(&frame, 0.0, 0.0);
+                    frame.close();
+
+                    if !begin_frame(&state) {
+                        continue;
+                    }
+                    let started = js_sys::Date::now();
+                    let drawn = async {
+                        let (pixels, width, height) = process(&state, input.read()?).await?;
+                        output.resize(width, height);
+                        let image = web_sys::ImageData::new_with_js_u8_clamped_array_and_sh(&pixels, width, height)?;
+                        output.context.put_image_data(&image, 0.0, 0.0)?;
+
+                        let init = js_sys::Object::new();
+                        js_sys::Reflect::set(&init, &"timestamp".into(), &timestamp.into())?;
+                        let processed = VideoFrame::new(&output.canvas, &init)?;
+                        // The generator takes ownership of the frame and closes it
+                        JsFuture::from(writer.write_with_chunk(&processed)).await.map(|_| ())
+                    }
+                    .await;
+                    end_frame(&state, started, drawn);
+                }
+                Ok::<(), JsValue>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                web_sys::console::error_2(&"VideoProcessor track loop stopped:".into(), &e);
+            }
+            let _ = reader.cancel();
+            let _ = writer.close();
+        });
+
+        Ok(generator.unchecked_into())
+    }
+
+    /// Stop processing; the current frame, if any, still completes
+    pub fn stop(&self) {
+        let mut state = self.state.borrow_mut();
+        state.generation = state.generation.wrapping_add(1);
+        if let Some(registered) = state.video_callback.take() {
+            registered.video.cancel_video_frame_callback(registered.handle);
+            registered.closure.borrow_mut().take();
+        }
+    }
+
+    /// Frames that went through the pipeline
+    #[wasm_bindgen(getter, js_name = framesProcessed)]
+    pub fn frames_processed(&self) -> u32 {
+        self.state.borrow().frames_processed
+    }
+
+    /// Frames skipped because the previous frame was still being processed
+    #[wasm_bindgen(getter, js_name = framesDropped)]
+    pub fn frames_dropped(&self) -> u32 {
+        self.state.borrow().frames_dropped
+    }
+
+    /// Milliseconds the last frame took, from capture to output
+    #[wasm_bindgen(getter, js_name = lastFrameMs)]
+    pub fn last_frame_ms(&self) -> f64 {
+        self.state.borrow().last_frame_ms
+    }
+}
+
+impl VideoProcessor {
+    fn push(&self, step: Step) {
+        self.state.borrow_mut().steps.push(step);
+    }
+}
+
+/// Claim the pipeline for a new frame, or count the frame as dropped
+fn begin_frame(state: &Rc<RefCell<ProcessorState>>) -> bool {
+    let mut state = state.borrow_mut();
+    if state.busy {
+        state.frames_dropped += 1;
+        return false;
+    }
+    state.busy = true;
+    true
+}
+
+fn end_frame(state: &Rc<RefCell<ProcessorState>>, started: f64, result: Result<(), JsValue>) {
+    let mut state = state.borrow_mut();
+    state.busy = false;
+    match result {
+        Ok(()) => {
+            state.frames_processed += 1;
+            state.last_frame_ms = js_sys::Date::now() - started;
+        }
+        Err(e) => web_sys::console::error_2(&"VideoProcessor frame failed:".into(), &e),
+    }
+}
+
+/// Run the current pipeline and stage the result as RGBA pixels
+async fn process(state: &Rc<RefCell<ProcessorState>>, frame: Mat) -> Result<(js_sys::Uint8ClampedArray, u32, u32), JsValue> {
+    let steps = state.borrow().steps.clone();
+    let result = run_pipeline(&steps, frame).await?;
+
+    let mut state = state.borrow_mut();
+    let mut rgba = std::mem::take(&mut state.rgba);
+    to_rgba(&result, &mut rgba)?;
+    let pixels = match state.pixels.take() {
+        Some(pixels) if pixels.length() as usize == rgba.len() => pixels,
+        _ => js_sys::Uint8ClampedArray::new_with_length(rgba.len() as u32),
+    };
+    // Copy out of wasm memory: ImageData rejects views of shared memory
+    pixels.copy_from(&rgba);
+    state.rgba = rgba;
+    state.pixels = Some(pixels.clone());
+    Ok((pixels, result.cols() as u32, result.rows() as u32))
+}
+
+async fn run_pipeline(steps: &[Step], frame: Mat) -> Result<Mat, JsValue> {
+    use crate::wasm::basic::edge::canny_wasm;
+    use crate::wasm::basic::filtering::gaussian_blur_wasm;
+    use crate::wasm::basic::threshold::threshold_wasm;
+    use crate::wasm::imgproc::color::cvt_color_gray_wasm;
+
+    let mut frame = WasmMat { inner: frame };
+    for step in steps {
+        frame = match step {
+            Step::Grayscale if frame.inner.channels() == 1 => frame,
+            Step::Grayscale => cvt_color_gray_wasm(&frame).await?,
+            Step::GaussianBlur { ksize, sigma } => gaussian_blur_wasm(&frame, *ksize, *sigma).await?,
+            Step::Canny { threshold1, threshold2 } => {
+                if frame.inner.channels() > 1 {
+                    frame = cvt_color_gray_wasm(&frame).await?;
+                }
+                canny_wasm(&frame, *threshold1, *threshold2).await?
+            }
+            Step::Threshold { thresh, max_val } => threshold_wasm(&frame, *thresh, *max_val).await?,
+            Step::BackgroundSubtraction { model, learning_rate } => {
+                let mut mask = Mat::new(1, 1, 1, MatDepth::U8)
+                    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+                model
+                    .borrow_mut()
+                    .apply(&to_rgb(&frame.inner)?, &mut mask, *learning_rate)
+                    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+                WasmMat { inner: mask }
+            }
+            Step::Custom(step) => {
+                let mut result = step.call1(&JsValue::NULL, &JsValue::from(frame))?;
+                if let Some(promise) = result.dyn_ref::<js_sys::Promise>() {
+                    result = JsFuture::from(promise.clone()).await?;
+                }
+                WasmMat::try_from_js_value(result)
+                    .map_err(|_| JsValue::from_str("Pipeline steps must return a WasmMat"))?
+            }
+        };
+    }
+    Ok(frame.inner)
+}
+
+/// Drop the alpha channel of an RGBA frame
+fn to_rgb(src: &Mat) -> Result<Mat, JsValue> {
+    if src.channels() != 4 {
+        return Ok(src.clone());
+    }
+    let mut rgb = Mat::new(src.rows(), src.cols(), 3, MatDepth::U8)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    for (dst, px) in rgb.data_mut().chunks_exact_mut(3).zip(src.data().chunks_exact(4)) {
+        dst.copy_from_slice(&px[..3]);
+    }
+    Ok(rgb)
+}
+
+/// Expand a 1, 3 or 4-channel U8 frame to opaque RGBA
+fn to_rgba(src: &Mat, rgba: &mut Vec<u8>) -> Result<(), JsValue> {
+    if src.depth() != MatDepth::U8 {
+        return Err(JsValue::from_str("Pipeline output must be U8"));
+    }
+    rgba.clear();
+    rgba.reserve(src.rows() * src.cols() * 4);
+    match src.channels() {
+        1 => rgba.extend(src.data().iter().flat_map(|&v| [v, v, v, 255])),
+        3 => rgba.extend(src.data().chunks_exact(3).flat_map(|px| [px[0], px[1], px[2], 255])),
+        4 => rgba.extend_from_slice(src.data()),
+        n => return Err(JsValue::from_str(&format!("Cannot display a {n}-channel frame"))),
+    }
+    Ok(())
+}
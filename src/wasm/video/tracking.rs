@@ -40,14 +40,12 @@ pub async fn bg_subtractor_knn_wasm(src: &WasmMat, learning_rate: f64) -> Result
 #[wasm_bindgen(js_name = farnebackOpticalFlow)]
 pub async fn farneback_optical_flow_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::video::optical_flow::calc_optical_flow_farneback;
-    use crate::imgproc::color::cvt_color;
-    use crate::core::types::ColorConversionCode;
 
     // Convert to grayscale
     let gray = if src.inner.channels() > 1 {
         let mut g = Mat::new(src.inner.rows(), src.inner.cols(), 1, src.inner.depth())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        cvt_color(&src.inner, &mut g, ColorConversionCode::BgrToGray)
+        crate::imgproc::to_gray(&src.inner, &mut g)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         g
     } else {
@@ -67,20 +65,10 @@ pub async fn farneback_optical_flow_wasm(src: &WasmMat) -> Result<WasmMat, JsVal
     let flow = calc_optical_flow_farneback(&gray, &next_frame, 0.5, 3, 15, 3)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    // Visualize flow as grayscale magnitude
-    let mut result = Mat::new(flow.rows(), flow.cols(), 1, src.inner.depth())
+    // Visualize flow with the Middlebury color wheel (direction + magnitude)
+    let result = crate::video::draw_flow_hsv(&flow)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    for row in 0..flow.rows() {
-        for col in 0..flow.cols() {
-            let fx = flow.at_f32(row, col, 0).unwrap_or(0.0);
-            let fy = flow.at_f32(row, col, 1).unwrap_or(0.0);
-            let mag = (fx * fx + fy * fy).sqrt();
-            result.at_mut(row, col).map_err(|e| JsValue::from_str(&e.to_string()))?[0] = 
-                (mag * 10.0).min(255.0) as u8;
-        }
-    }
-
     Ok(WasmMat { inner: result })
 }
 
@@ -98,8 +86,11 @@ pub async fn meanshift_tracker_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     let initial_window = Rect::new(w / 4, h / 4, w / 2, h / 2);
 
     let mut tracker = MeanShiftTracker::new(initial_window);
-    let result_window = tracker.track(&src.inner)
+    tracker.init(&src.inner, initial_window)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    tracker.update(&src.inner)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let result_window = tracker.window();
 
     // Draw tracked region
     let mut result = src.inner.clone();
@@ -114,8 +105,8 @@ pub async fn meanshift_tracker_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 #[wasm_bindgen(js_name = camshiftTracker)]
 pub async fn camshift_tracker_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     use crate::video::tracking::CamShiftTracker;
-    use crate::imgproc::drawing::rectangle;
-    use crate::core::types::{Rect, Scalar};
+    use crate::imgproc::drawing::line;
+    use crate::core::types::{Point, Rect, Scalar};
 
     // Initialize tracker with center region
     let w = src.inner.cols() as i32;
@@ -123,13 +114,18 @@ pub async fn camshift_tracker_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
     let initial_window = Rect::new(w / 4, h / 4, w / 2, h / 2);
 
     let mut tracker = CamShiftTracker::new(initial_window);
-    let result_window = tracker.track(&src.inner)
+    tracker.init(&src.inner, initial_window)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let found = tracker.update(&src.inner)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    // Draw tracked region
+    // Draw the rotated object box
     let mut result = src.inner.clone();
     let color = Scalar::new(255.0, 0.0, 0.0, 255.0);
-    let _ = rectangle(&mut result, result_window, color, 2);
+    let corners = found.points().map(|p| Point::new(p.x.round() as i32, p.y.round() as i32));
+    for i in 0..4 {
+        let _ = line(&mut result, corners[i], corners[(i + 1) % 4], color, 2);
+    }
 
     Ok(WasmMat { inner: result })
 }
@@ -191,3 +187,65 @@ pub async fn csrt_tracker_wasm(src: &WasmMat) -> Result<WasmMat, JsValue> {
 }
 
 
+
+// ===== farnebackFlowFlat =====
+/// Dense Farneback flow between two frames as a flat `Float32Array`
+/// (see [`crate::wasm::flat`])
+#[wasm_bindgen(js_name = farnebackFlowFlat)]
+pub fn farneback_flow_flat_wasm(
+    prev: &WasmMat,
+    next: &WasmMat,
+    pyr_scale: f64,
+    levels: i32,
+    winsize: i32,
+    iterations: i32,
+) -> Result<Vec<f32>, JsValue> {
+    use crate::video::optical_flow::calc_optical_flow_farneback;
+    use crate::wasm::flat::to_gray;
+
+    let flow = calc_optical_flow_farneback(&to_gray(&prev.inner)?, &to_gray(&next.inner)?, pyr_scale, levels, winsize, iterations)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let (cols, channels) = (flow.cols(), flow.channels());
+    (0..flow.rows() * cols * channels)
+        .map(|i| flow.at_f32(i / (cols * channels), i / channels % cols, i % channels))
+        .collect::<crate::error::Result<_>>()
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// ===== lucasKanadeFlat =====
+/// Track `points` (flat `[x, y, ...]`) from `prev` to `next` with pyramidal
+/// Lucas-Kanade, returning a flat `Float32Array` (see [`crate::wasm::flat`])
+#[wasm_bindgen(js_name = lucasKanadeFlat)]
+pub fn lucas_kanade_flat_wasm(
+    prev: &WasmMat,
+    next: &WasmMat,
+    points: &[f32],
+    win_size: i32,
+    max_level: i32,
+) -> Result<Vec<f32>, JsValue> {
+    use crate::core::types::{Point, Size};
+    use crate::video::optical_flow::calc_optical_flow_pyr_lk;
+    use crate::wasm::flat::{to_gray, TRACKED_POINT_STRIDE};
+
+    if !points.len().is_multiple_of(2) {
+        return Err(JsValue::from_str("points must hold x, y pairs"));
+    }
+    let prev_pts: Vec<Point> = points
+        .chunks_exact(2)
+        .map(|p| Point::new(p[0].round() as i32, p[1].round() as i32))
+        .collect();
+    let (next_pts, status) = calc_optical_flow_pyr_lk(
+        &to_gray(&prev.inner)?,
+        &to_gray(&next.inner)?,
+        &prev_pts,
+        Size::new(win_size, win_size),
+        max_level,
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let mut out = Vec::with_capacity(next_pts.len() * TRACKED_POINT_STRIDE);
+    for (p, s) in next_pts.iter().zip(&status) {
+        out.extend_from_slice(&[p.x as f32, p.y as f32, f32::from(*s)]);
+    }
+    Ok(out)
+}
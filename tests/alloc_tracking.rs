@@ -0,0 +1,44 @@
+//! Per-scope counters with the tracking allocator installed
+
+#![cfg(not(feature = "alloc-tracking"))]
+
+use opencv_rust::core::{allocation_stats, AllocScope, Mat, MatDepth, TrackingAllocator};
+
+#[global_allocator]
+static ALLOC: TrackingAllocator = TrackingAllocator::system();
+
+#[test]
+fn test_allocations_attributed_to_scopes() {
+    assert!(allocation_stats().tracking);
+
+    let before = allocation_stats().scope(AllocScope::Core);
+    let mat = Mat::new(100, 100, 3, MatDepth::U8).unwrap();
+    let copy = mat.clone_mat();
+    let during = allocation_stats().scope(AllocScope::Core);
+    assert!(during.live_bytes >= before.live_bytes + 2 * 30_000);
+    assert!(during.peak_bytes >= during.live_bytes);
+    drop((mat, copy));
+    let after = allocation_stats().scope(AllocScope::Core);
+    assert!(after.deallocations >= before.deallocations + 2);
+
+    // `Clone` goes through the same scope as `clone_mat`
+    let mat = Mat::new(100, 100, 3, MatDepth::U8).unwrap();
+    let before = allocation_stats().scope(AllocScope::Core);
+    let copy = mat.clone();
+    let during = allocation_stats().scope(AllocScope::Core);
+    assert!(during.allocations > before.allocations);
+    assert!(during.live_bytes >= before.live_bytes + 30_000);
+    drop((mat, copy));
+
+    let gpu_before = allocation_stats().scope(AllocScope::Gpu);
+    let buffer = {
+        let _gpu = AllocScope::Gpu.enter();
+        vec![0u8; 4096]
+    };
+    let gpu_after = allocation_stats().scope(AllocScope::Gpu);
+    assert_eq!(gpu_after.allocations, gpu_before.allocations + 1);
+    assert_eq!(gpu_after.live_bytes, gpu_before.live_bytes + 4096);
+    // Freed outside the scope, still credited back to it
+    drop(buffer);
+    assert_eq!(allocation_stats().scope(AllocScope::Gpu).live_bytes, gpu_before.live_bytes);
+}
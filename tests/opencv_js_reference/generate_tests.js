@@ -25,6 +25,43 @@ import { fileURLToPath } from 'url';
 const __filename = fileURLToPath(import.meta.url);
 const __dirname = path.dirname(__filename);
 
+/**
+ * Load the parity manifest shared with the Rust crate
+ *
+ * Tolerances live in src/parity/manifest.tsv so that this harness, the
+ * native conformance tests and opencv_rust::parity::report() agree.
+ */
+export function loadParityManifest() {
+  const manifestPath = path.join(__dirname, '..', '..', 'src', 'parity', 'manifest.tsv');
+  const entries = {};
+  for (const line of fs.readFileSync(manifestPath, 'utf8').split('\n')) {
+    if (!line.trim() || line.startsWith('#')) continue;
+    const [op, opencv, checkedBy, maxAbs, maxMean, outlier, outlierPercent, notes] = line.split('\t');
+    entries[op] = {
+      opencv,
+      checked_by: checkedBy,
+      tolerance: {
+        max_abs_diff: Number(maxAbs),
+        max_mean_diff: Number(maxMean),
+        outlier_diff: Number(outlier),
+        max_outlier_percent: Number(outlierPercent),
+      },
+      notes,
+    };
+  }
+  return entries;
+}
+
+const PARITY_MANIFEST = loadParityManifest();
+
+function toleranceFor(op) {
+  const entry = PARITY_MANIFEST[op];
+  if (!entry) {
+    throw new Error(`${op} has no entry in src/parity/manifest.tsv`);
+  }
+  return entry.tolerance;
+}
+
 // Test configurations for each operation
 const TEST_CONFIGS = {
   gaussian_blur: {
@@ -34,11 +71,7 @@ const TEST_CONFIGS = {
       { ksize: 9, sigma: 2.0 },
       { ksize: 15, sigma: 3.0 },
     ],
-    tolerance: {
-      max_pixel_diff: 1, // ±1 due to rounding
-      max_mean_diff: 0.1,
-      max_outliers_percent: 1.0, // 1% of pixels can exceed tolerance
-    },
+    tolerance: toleranceFor('gaussian_blur'),
   },
 
   resize: {
@@ -48,11 +81,7 @@ const TEST_CONFIGS = {
       { width: 640, height: 480, interpolation: 'INTER_LINEAR' },
       { width: 160, height: 120, interpolation: 'INTER_NEAREST' },
     ],
-    tolerance: {
-      max_pixel_diff: 2,
-      max_mean_diff: 0.5,
-      max_outliers_percent: 2.0,
-    },
+    tolerance: toleranceFor('resize'),
   },
 
   threshold: {
@@ -62,11 +91,7 @@ const TEST_CONFIGS = {
       { thresh: 100, maxval: 255, type: 'THRESH_BINARY_INV' },
       { thresh: 127, maxval: 255, type: 'THRESH_TRUNC' },
     ],
-    tolerance: {
-      max_pixel_diff: 0, // Exact match expected
-      max_mean_diff: 0.0,
-      max_outliers_percent: 0.0,
-    },
+    tolerance: toleranceFor('threshold'),
   },
 
   canny: {
@@ -76,11 +101,7 @@ const TEST_CONFIGS = {
       { threshold1: 100, threshold2: 200 },
       { threshold1: 30, threshold2: 90 },
     ],
-    tolerance: {
-      max_pixel_diff: 1,
-      max_mean_diff: 0.2,
-      max_outliers_percent: 5.0, // Edge detection has more variation
-    },
+    tolerance: toleranceFor('canny'),
   },
 
   sobel: {
@@ -90,11 +111,7 @@ const TEST_CONFIGS = {
       { ddepth: -1, dx: 0, dy: 1, ksize: 3 },
       { ddepth: -1, dx: 1, dy: 1, ksize: 5 },
     ],
-    tolerance: {
-      max_pixel_diff: 2,
-      max_mean_diff: 0.5,
-      max_outliers_percent: 3.0,
-    },
+    tolerance: toleranceFor('sobel'),
   },
 
   erode: {
@@ -104,11 +121,7 @@ const TEST_CONFIGS = {
       { ksize: 5, iterations: 2 },
       { ksize: 7, iterations: 1 },
     ],
-    tolerance: {
-      max_pixel_diff: 0,
-      max_mean_diff: 0.0,
-      max_outliers_percent: 0.5,
-    },
+    tolerance: toleranceFor('erode'),
   },
 
   dilate: {
@@ -118,11 +131,7 @@ const TEST_CONFIGS = {
       { ksize: 5, iterations: 2 },
       { ksize: 7, iterations: 1 },
     ],
-    tolerance: {
-      max_pixel_diff: 0,
-      max_mean_diff: 0.0,
-      max_outliers_percent: 0.5,
-    },
+    tolerance: toleranceFor('dilate'),
   },
 
   bilateral_filter: {
@@ -131,11 +140,7 @@ const TEST_CONFIGS = {
       { d: 5, sigmaColor: 75, sigmaSpace: 75 },
       { d: 9, sigmaColor: 150, sigmaSpace: 150 },
     ],
-    tolerance: {
-      max_pixel_diff: 3, // More tolerance for edge-preserving filters
-      max_mean_diff: 1.0,
-      max_outliers_percent: 5.0,
-    },
+    tolerance: toleranceFor('bilateral_filter'),
   },
 
   median_blur: {
@@ -145,11 +150,7 @@ const TEST_CONFIGS = {
       { ksize: 5 },
       { ksize: 9 },
     ],
-    tolerance: {
-      max_pixel_diff: 0,
-      max_mean_diff: 0.0,
-      max_outliers_percent: 0.5,
-    },
+    tolerance: toleranceFor('median_blur'),
   },
 
   laplacian: {
@@ -159,11 +160,7 @@ const TEST_CONFIGS = {
       { ddepth: -1, ksize: 3 },
       { ddepth: -1, ksize: 5 },
     ],
-    tolerance: {
-      max_pixel_diff: 2,
-      max_mean_diff: 0.5,
-      max_outliers_percent: 3.0,
-    },
+    tolerance: toleranceFor('laplacian'),
   },
 
   flip: {
@@ -173,11 +170,7 @@ const TEST_CONFIGS = {
       { flipCode: 1 }, // Horizontal
       { flipCode: -1 }, // Both
     ],
-    tolerance: {
-      max_pixel_diff: 0, // Exact match expected
-      max_mean_diff: 0.0,
-      max_outliers_percent: 0.0,
-    },
+    tolerance: toleranceFor('flip'),
   },
 
   adaptive_threshold: {
@@ -186,11 +179,7 @@ const TEST_CONFIGS = {
       { maxValue: 255, adaptiveMethod: 'ADAPTIVE_THRESH_MEAN_C', thresholdType: 'THRESH_BINARY', blockSize: 11, C: 2 },
       { maxValue: 255, adaptiveMethod: 'ADAPTIVE_THRESH_GAUSSIAN_C', thresholdType: 'THRESH_BINARY', blockSize: 11, C: 2 },
     ],
-    tolerance: {
-      max_pixel_diff: 1,
-      max_mean_diff: 0.2,
-      max_outliers_percent: 2.0,
-    },
+    tolerance: toleranceFor('adaptive_threshold'),
   },
 };
 
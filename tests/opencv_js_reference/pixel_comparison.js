@@ -15,10 +15,11 @@
  *
  * @param {Object} imageA - First image { data: Uint8Array/Array, width, height }
  * @param {Object} imageB - Second image { data: Uint8Array/Array, width, height }
- * @param {Object} tolerance - Tolerance thresholds
- * @param {number} tolerance.max_pixel_diff - Maximum allowed pixel difference
- * @param {number} tolerance.max_mean_diff - Maximum allowed mean difference
- * @param {number} tolerance.max_outliers_percent - Maximum percentage of outliers
+ * @param {Object} tolerance - Tolerance thresholds from the parity manifest
+ * @param {number} tolerance.max_abs_diff - Maximum allowed difference of any value
+ * @param {number} tolerance.max_mean_diff - Maximum mean difference over all values
+ * @param {number} tolerance.outlier_diff - Differences above this count as outliers
+ * @param {number} tolerance.max_outlier_percent - Maximum percentage of outliers
  * @returns {Object} Comparison results with metrics and pass/fail status
  */
 export function compareImages(imageA, imageB, tolerance) {
@@ -50,22 +51,21 @@ export function compareImages(imageA, imageB, tolerance) {
       totalDiff += diff;
       maxDiff = Math.max(maxDiff, diff);
 
-      // Count outliers (pixels exceeding base tolerance)
-      if (diff > tolerance.max_pixel_diff) {
+      // Count outliers (pixels exceeding the outlier threshold)
+      if (diff > tolerance.outlier_diff) {
         outlierCount++;
       }
     }
   }
 
-  const meanDiff = diffCount > 0 ? totalDiff / diffCount : 0;
+  const meanDiff = totalDiff / dataA.length;
   const outlierPercent = (outlierCount / dataA.length) * 100;
 
-  // Determine pass/fail
-  // Allow 2x max_pixel_diff for occasional outliers, but enforce mean and outlier %
+  // Determine pass/fail, with the same rule as opencv_rust::parity::compare
   const passed =
-    maxDiff <= tolerance.max_pixel_diff * 2 &&
+    maxDiff <= tolerance.max_abs_diff &&
     meanDiff <= tolerance.max_mean_diff &&
-    outlierPercent <= tolerance.max_outliers_percent;
+    outlierPercent <= tolerance.max_outlier_percent;
 
   return {
     passed,
@@ -98,17 +98,17 @@ Different Pixels: ${comparison.differentPixels} (${(
   ).toFixed(2)}%)
 
 Max Difference: ${comparison.maxDiff.toFixed(2)} (threshold: ${
-    comparison.tolerance.max_pixel_diff * 2
+    comparison.tolerance.max_abs_diff
   })
 Mean Difference: ${comparison.meanDiff.toFixed(4)} (threshold: ${
     comparison.tolerance.max_mean_diff
   })
 Outliers: ${comparison.outlierPercent.toFixed(2)}% (threshold: ${
-    comparison.tolerance.max_outliers_percent
+    comparison.tolerance.max_outlier_percent
   }%)
 
 Outlier Pixels: ${comparison.outlierPixels} (exceeding ${
-    comparison.tolerance.max_pixel_diff
+    comparison.tolerance.outlier_diff
   }px diff)
 `.trim();
 }
@@ -146,7 +146,7 @@ export function compareImagesDetailed(imageA, imageB, tolerance, channels = 4) {
       }
     }
 
-    const meanDiff = diffCount > 0 ? totalDiff / diffCount : 0;
+    const meanDiff = totalDiff / dataA.length;
 
     channelStats.push({
       channel: channelNames[c],
@@ -133,13 +133,13 @@ test.describe('Gaussian Blur - OpenCV.js Parity', () => {
 
         // Additional sanity checks
         expect(comparison.maxDiff).toBeLessThanOrEqual(
-          config.tolerance.max_pixel_diff * 2
+          config.tolerance.max_abs_diff
         );
         expect(comparison.meanDiff).toBeLessThanOrEqual(
           config.tolerance.max_mean_diff
         );
         expect(comparison.outlierPercent).toBeLessThanOrEqual(
-          config.tolerance.max_outliers_percent
+          config.tolerance.max_outlier_percent
         );
       });
     }
@@ -1,3 +1,4 @@
+#![cfg(feature = "imgproc-core")]
 #![allow(unused_comparisons)]
 /// Bit-level accuracy tests for Adaptive Threshold
 mod test_utils;
@@ -1,3 +1,4 @@
+#![cfg(feature = "imgproc-core")]
 #![allow(unused_comparisons)]
 /// Bit-level accuracy tests for Blur (box filter)
 mod test_utils;
@@ -1,3 +1,4 @@
+#![cfg(feature = "imgproc-core")]
 #![allow(unused_comparisons)]
 /// Bit-level accuracy tests for Canny edge detection
 /// These tests verify that optimizations don't change results
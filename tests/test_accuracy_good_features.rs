@@ -1,3 +1,4 @@
+#![cfg(feature = "features2d")]
 #![allow(unused_comparisons)]
 /// Bit-level accuracy tests for Good Features to Track
 /// These tests verify that optimizations don't change results
@@ -1,3 +1,4 @@
+#![cfg(feature = "calib3d")]
 // Camera calibration tests ported from OpenCV test suite
 // opencv/modules/calib3d/test/test_camera_calibration.cpp
 // opencv/modules/calib3d/test/test_fisheye.cpp
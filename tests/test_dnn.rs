@@ -1,3 +1,4 @@
+#![cfg(feature = "dnn")]
 // DNN tests ported from OpenCV test suite
 // opencv/modules/dnn/test/test_layers.cpp
 // opencv/modules/dnn/test/test_caffe_importer.cpp
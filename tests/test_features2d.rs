@@ -1,3 +1,4 @@
+#![cfg(feature = "features2d")]
 // Features2D tests ported from OpenCV test suite
 // opencv/modules/features2d/test/test_descriptors.cpp
 // opencv/modules/features2d/test/test_keypoints.cpp
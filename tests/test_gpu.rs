@@ -157,3 +157,313 @@ fn test_gpu_blur_edge_cases() {
 
     println!("Edge case tests completed successfully");
 }
+
+#[test]
+fn test_gpu_yuv420_to_rgb_matches_cpu() {
+    use opencv_rust::gpu::ops::yuv420_to_rgb_gpu;
+    use opencv_rust::imgproc::{yuv420_to_rgb, Yuv420Layout};
+
+    if !init_gpu() {
+        println!("Skipping GPU YUV test - GPU not available");
+        return;
+    }
+
+    // 30x18 image: the pixel count is not a multiple of 4 words
+    let mut frame = Mat::new(27, 30, 1, MatDepth::U8).unwrap();
+    for (i, v) in frame.data_mut().iter_mut().enumerate() {
+        *v = ((i * 37 + i / 7) % 256) as u8;
+    }
+
+    for layout in [Yuv420Layout::Nv12, Yuv420Layout::Nv21, Yuv420Layout::I420] {
+        for channels in [3, 4] {
+            let mut cpu = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            let mut gpu = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            yuv420_to_rgb(&frame, &mut cpu, layout, channels).unwrap();
+            yuv420_to_rgb_gpu(&frame, &mut gpu, layout, channels).unwrap();
+
+            assert_eq!((gpu.rows(), gpu.cols(), gpu.channels()), (18, 30, channels));
+            for (&a, &b) in cpu.data().iter().zip(gpu.data()) {
+                assert!((i32::from(a) - i32::from(b)).abs() <= 1, "{layout:?} x{channels}: {a} vs {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_gpu_mat_texture_round_trip() {
+    use opencv_rust::gpu::{GpuContext, GpuMat};
+
+    // An application-owned device, as a wgpu renderer would have
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let Ok(adapter) = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())) else {
+        println!("Skipping GpuMat test - GPU not available");
+        return;
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).unwrap();
+    let ctx = GpuContext { device, queue, adapter };
+
+    // 13 RGBA pixels per row is not a 256-byte pitch, 64 is
+    for cols in [13, 64] {
+        let mut src = Mat::new(7, cols, 4, MatDepth::U8).unwrap();
+        for (i, v) in src.data_mut().iter_mut().enumerate() {
+            *v = (i % 251) as u8;
+        }
+
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d { width: cols as u32, height: 7, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        GpuMat::upload(&ctx, &src).unwrap().copy_to_texture(&ctx, &texture).unwrap();
+        let back = GpuMat::from_texture(&ctx, &texture).unwrap();
+        assert_eq!((back.rows(), back.cols(), back.channels()), (7, cols, 4));
+        assert_eq!(back.download(&ctx).unwrap().data(), src.data());
+
+        let gray = GpuMat::upload(&ctx, &Mat::new(7, cols, 1, MatDepth::U8).unwrap()).unwrap();
+        assert!(gray.copy_to_texture(&ctx, &texture).is_err());
+    }
+}
+
+#[test]
+fn test_gpu_mog2_matches_cpu() {
+    use opencv_rust::gpu::ops::BackgroundSubtractorMOG2Gpu;
+    use opencv_rust::video::background_subtraction::BackgroundSubtractorMOG2;
+
+    if !init_gpu() {
+        println!("Skipping GPU MOG2 test - GPU not available");
+        return;
+    }
+
+    let frame = |t: usize, square: bool| {
+        let mut img = Mat::new(18, 23, 3, MatDepth::U8).unwrap();
+        for row in 0..18 {
+            for col in 0..23 {
+                let inside = square && (5..12).contains(&row) && (8..16).contains(&col);
+                let v = if inside { 230 } else { (90 + (row * 7 + col * 3 + t) % 9) as u8 };
+                img.at_mut(row, col).unwrap().fill(v);
+            }
+        }
+        img
+    };
+
+    let mut cpu = BackgroundSubtractorMOG2::new();
+    let mut gpu = BackgroundSubtractorMOG2Gpu::new();
+    let mut cpu_mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    let mut gpu_mask = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+
+    for t in 0..40 {
+        let img = frame(t, t == 39);
+        cpu.apply(&img, &mut cpu_mask, 0.05).unwrap();
+        gpu.apply(&img, &mut gpu_mask, 0.05).unwrap();
+        assert_eq!((gpu_mask.rows(), gpu_mask.cols()), (18, 23));
+        assert_eq!(gpu_mask.data(), cpu_mask.data(), "frame {t}");
+    }
+    // The square is foreground, the learned background is not
+    assert_eq!(gpu_mask.at(8, 10).unwrap()[0], 255);
+    assert_eq!(gpu_mask.at(1, 1).unwrap()[0], 0);
+
+    let mut cpu_bg = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    let mut gpu_bg = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    cpu.get_background_image(&mut cpu_bg).unwrap();
+    gpu.get_background_image(&mut gpu_bg).unwrap();
+    for (&a, &b) in cpu_bg.data().iter().zip(gpu_bg.data()) {
+        assert!((i32::from(a) - i32::from(b)).abs() <= 1);
+    }
+}
+
+#[test]
+fn test_gpu_corners_match_cpu() {
+    use opencv_rust::features2d::{fast, harris_corners};
+    use opencv_rust::gpu::ops::{fast_gpu, harris_corners_gpu};
+
+    if !init_gpu() {
+        println!("Skipping GPU corner test - GPU not available");
+        return;
+    }
+
+    // Bright squares and dots on a gradient: corners for Harris, blobs for FAST
+    let mut img = Mat::new(61, 75, 1, MatDepth::U8).unwrap();
+    for row in 0..61 {
+        for col in 0..75 {
+            let square = (10..25).contains(&row) && (12..30).contains(&col);
+            let dot = row % 16 == 8 && col % 16 == 8 && col > 35;
+            let v = if square || dot { 230 } else { 40 + (row + col) / 4 };
+            img.at_mut(row, col).unwrap()[0] = v as u8;
+        }
+    }
+    let sorted = |mut kps: Vec<opencv_rust::features2d::KeyPoint>| {
+        kps.sort_by(|a, b| (a.pt.y, a.pt.x).partial_cmp(&(b.pt.y, b.pt.x)).unwrap());
+        kps
+    };
+
+    for nms in [false, true] {
+        let cpu = sorted(fast(&img, 20, nms).unwrap());
+        let gpu = sorted(fast_gpu(&img, 20, nms).unwrap());
+        assert!(!cpu.is_empty());
+        assert_eq!(gpu.len(), cpu.len(), "nms {nms}");
+        for (a, b) in cpu.iter().zip(&gpu) {
+            assert_eq!(a.pt, b.pt);
+        }
+    }
+
+    let cpu = sorted(harris_corners(&img, 3, 3, 0.04, 1e6).unwrap());
+    let gpu = sorted(harris_corners_gpu(&img, 3, 0.04, 1e6, 0).unwrap());
+    assert!(!cpu.is_empty());
+    assert_eq!(gpu.len(), cpu.len());
+    for (a, b) in cpu.iter().zip(&gpu) {
+        assert_eq!(a.pt, b.pt);
+        assert!((a.response - b.response).abs() <= 1e-5 * a.response.abs().max(1.0));
+    }
+}
+
+#[test]
+fn test_gpu_knn2_match_matches_cpu() {
+    use opencv_rust::features2d::{ratio_test_filter, BFMatcher, DescriptorDistance, DistanceType};
+    use opencv_rust::gpu::ops::{knn2_match_f32_gpu, knn2_match_gpu};
+
+    if !init_gpu() {
+        println!("Skipping GPU matcher test - GPU not available");
+        return;
+    }
+
+    // 31-byte descriptors do not fill whole words
+    let byte = |i: usize, j: usize| ((i * 131 + j * 71 + i * j * 7) % 256) as u8;
+    let query: Vec<Vec<u8>> = (0..70).map(|i| (0..31).map(|j| byte(i, j)).collect()).collect();
+    let train: Vec<Vec<u8>> = (0..90).map(|i| (0..31).map(|j| byte(i + 200, j)).collect()).collect();
+
+    for distance_type in [DistanceType::Hamming, DistanceType::L2] {
+        let cpu = BFMatcher::new(distance_type, false).knn_match(&query, &train, 2).unwrap();
+        let gpu = knn2_match_gpu(&query, &train, distance_type).unwrap();
+        assert_eq!(gpu.len(), cpu.len());
+        for (a, b) in cpu.iter().zip(&gpu) {
+            assert_eq!(b.len(), 2);
+            for (x, y) in a.iter().zip(b) {
+                assert_eq!((x.query_idx, x.train_idx), (y.query_idx, y.train_idx), "{distance_type:?}");
+                assert!((x.distance - y.distance).abs() < 1e-3);
+            }
+        }
+        assert_eq!(ratio_test_filter(&gpu, 0.8).len(), ratio_test_filter(&cpu, 0.8).len());
+    }
+
+    // SIFT-sized float descriptors against a single train descriptor
+    let query: Vec<Vec<f32>> = (0..20).map(|i| (0..128).map(|j| f32::from(byte(i, j)) / 255.0).collect()).collect();
+    let train = vec![(0..128).map(|j| f32::from(byte(7, j + 1)) / 255.0).collect::<Vec<f32>>()];
+    let gpu = knn2_match_f32_gpu(&query, &train).unwrap();
+    for (q, m) in query.iter().zip(&gpu) {
+        assert_eq!(m.len(), 1);
+        assert!((m[0].distance - q.distance(&train[0])).abs() < 1e-4);
+    }
+
+    let mismatched = vec![vec![0u8; 32], vec![0u8; 16]];
+    assert!(knn2_match_gpu(&mismatched, &[vec![0u8; 32]], DistanceType::Hamming).is_err());
+}
+
+#[test]
+fn test_gpu_component_stats_matches_cpu() {
+    use opencv_rust::gpu::ops::component_stats_gpu;
+    use opencv_rust::imgproc::{component_stats, connected_components};
+
+    if !init_gpu() {
+        println!("Skipping GPU component stats test - GPU not available");
+        return;
+    }
+
+    // Scattered blobs plus one large region spanning many 16x16 tiles
+    let mut mask = Mat::new(203, 317, 1, MatDepth::U8).unwrap();
+    for row in 0..203 {
+        for col in 0..317 {
+            let big = (40..150).contains(&row) && (100..260).contains(&col);
+            let dot = (row * 7 + col * 13) % 11 == 0;
+            mask.at_mut(row, col).unwrap()[0] = if big || dot { 255 } else { 0 };
+        }
+    }
+    let mut labels = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    let num_labels = connected_components(&mask, &mut labels, 8).unwrap();
+    // Out-of-range labels are skipped by both
+    labels.set_i32(0, 0, 0, -1).unwrap();
+
+    let cpu = component_stats(&labels, num_labels + 2).unwrap();
+    let gpu = component_stats_gpu(&labels, num_labels + 2).unwrap();
+    assert_eq!(gpu, cpu);
+    assert!(component_stats_gpu(&mask, 2).is_err());
+}
+
+#[test]
+fn test_gpu_resize_matches_cpu() {
+    use opencv_rust::core::types::InterpolationFlag;
+    use opencv_rust::gpu::ops::resize_gpu_with_interpolation;
+    use opencv_rust::imgproc::resize;
+
+    if !init_gpu() {
+        println!("Skipping GPU resize test - GPU not available");
+        return;
+    }
+
+    // Hard edges make any difference in the coordinate mapping show up as
+    // a full-scale error rather than a rounding step. RGBA keeps each output
+    // pixel in its own word: the shader's byte writes are not atomic.
+    let mut src = Mat::new(23, 31, 4, MatDepth::U8).unwrap();
+    for row in 0..23 {
+        for col in 0..31 {
+            let pixel = src.at_mut(row, col).unwrap();
+            pixel[0] = if (row / 3 + col / 4) % 2 == 0 { 255 } else { 0 };
+            pixel[1] = ((row * 11 + col * 7) % 256) as u8;
+            pixel[2] = (col * 8) as u8;
+        }
+    }
+
+    for interpolation in [InterpolationFlag::Nearest, InterpolationFlag::Linear] {
+        for (width, height) in [(64, 47), (12, 9), (31, 1)] {
+            let mut cpu = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            let mut gpu = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            resize(&src, &mut cpu, Size::new(width, height), interpolation).unwrap();
+            resize_gpu_with_interpolation(&src, &mut gpu, width as usize, height as usize, interpolation).unwrap();
+
+            assert_eq!((gpu.rows(), gpu.cols()), (cpu.rows(), cpu.cols()));
+            // One level of slack for fused multiply-adds on the device
+            for (&a, &b) in cpu.data().iter().zip(gpu.data()) {
+                assert!((i32::from(a) - i32::from(b)).abs() <= 1, "{interpolation:?} {width}x{height}: {a} vs {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_gpu_distance_transform_matches_cpu() {
+    use opencv_rust::gpu::ops::distance_transform_gpu;
+    use opencv_rust::imgproc::advanced_filter::{distance_transform, DistanceType};
+
+    if !init_gpu() {
+        println!("Skipping GPU distance transform test - GPU not available");
+        return;
+    }
+
+    // Zero every eighth row and column so all pixels lie inside the
+    // shader's search radius, where it is exact
+    let mut src = Mat::new(37, 45, 1, MatDepth::U8).unwrap();
+    for row in 0..37 {
+        for col in 0..45 {
+            src.at_mut(row, col).unwrap()[0] = if row % 8 == 0 || col % 8 == 0 { 0 } else { 255 };
+        }
+    }
+
+    let mut cpu = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    let mut gpu = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    distance_transform(&src, &mut cpu, DistanceType::L2, 3).unwrap();
+    distance_transform_gpu(&src, &mut gpu).unwrap();
+
+    assert_eq!(gpu.depth(), cpu.depth());
+    assert_eq!((gpu.rows(), gpu.cols()), (cpu.rows(), cpu.cols()));
+    for row in 0..37 {
+        for col in 0..45 {
+            let (a, b) = (cpu.at_f32(row, col, 0).unwrap(), gpu.at_f32(row, col, 0).unwrap());
+            assert!((a - b).abs() < 1e-4, "({row}, {col}): {a} vs {b}");
+        }
+    }
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "imgproc-core")]
 // Integration tests for imgproc module ported from OpenCV test suite
 // These tests validate correctness against known-good outputs
 
@@ -233,7 +234,7 @@ fn test_dilate_grows_white_region() {
 }
 
 #[test]
-fn test_resize_downscale_preserves_corners() {
+fn test_resize_downscale_aligns_pixel_centres() {
     let mut src = Mat::new(100, 100, 1, MatDepth::U8).unwrap();
 
     // Set corners to specific values
@@ -248,10 +249,12 @@ fn test_resize_downscale_preserves_corners() {
     assert_eq!(dst.rows(), 50);
     assert_eq!(dst.cols(), 50);
 
-    // Corner values should be approximately preserved
-    let tol = 15.0;
-    assert!((dst.at(0, 0).unwrap()[0] as f64 - 10.0).abs() < tol);
-    assert!((dst.at(0, 49).unwrap()[0] as f64 - 20.0).abs() < tol);
+    // Pixel centres are aligned like OpenCV, so a 2x reduction averages each
+    // 2x2 block and the corner values are shared with their zero neighbours
+    assert_eq!(dst.at(0, 0).unwrap()[0], 3);
+    assert_eq!(dst.at(0, 49).unwrap()[0], 5);
+    assert_eq!(dst.at(49, 0).unwrap()[0], 8);
+    assert_eq!(dst.at(49, 49).unwrap()[0], 10);
 }
 
 #[test]
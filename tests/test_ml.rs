@@ -1,3 +1,4 @@
+#![cfg(feature = "ml")]
 // ML tests ported from OpenCV test suite
 // opencv/modules/ml/test/test_mltests.cpp
 // opencv/modules/ml/test/test_mltests2.cpp
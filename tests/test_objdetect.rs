@@ -1,3 +1,4 @@
+#![cfg(feature = "objdetect")]
 #![allow(unused_comparisons)]
 // Object detection tests ported from OpenCV test suite
 // opencv/modules/objdetect/test/test_qrcode.cpp
@@ -0,0 +1,189 @@
+#![cfg(feature = "imgproc-core")]
+//! Native conformance checks for the parity manifest
+//!
+//! Every `native` entry of `src/parity/manifest.tsv` is checked here against
+//! a reference computed with `OpenCV`'s own arithmetic (or, for Lab, values
+//! produced by `OpenCV`), and every operation of the opencv.js harness must
+//! have a manifest entry.
+
+use opencv_rust::core::{Mat, MatDepth};
+use opencv_rust::core::types::{ColorConversionCode, InterpolationFlag, Size, ThresholdType};
+use opencv_rust::imgcodecs::imread;
+use opencv_rust::imgproc::{cvt_color, flip, resize, threshold};
+use opencv_rust::parity::{self, compare};
+
+const FIXTURES: [&str; 3] = ["lenna.png", "gradient.png", "shapes.png"];
+
+/// Threshold, type and the per-pixel `OpenCV` rule `(value, thresh) -> output`
+type ThresholdCase = (f64, ThresholdType, fn(u8, u8) -> u8);
+
+fn fixture(name: &str) -> Mat {
+    imread(format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))).unwrap()
+}
+
+fn mat_like(src: &Mat, rows: usize, cols: usize, data: Vec<u8>) -> Mat {
+    let mut mat = Mat::new(rows, cols, src.channels(), MatDepth::U8).unwrap();
+    mat.data_mut().copy_from_slice(&data);
+    mat
+}
+
+fn assert_within(op: &str, actual: &Mat, expected: &Mat, what: &str) {
+    let entry = parity::entry(op).unwrap_or_else(|| panic!("{op} missing from the parity manifest"));
+    let check = compare(actual, expected, &entry.tolerance).unwrap();
+    assert!(check.passed, "{op} ({what}) exceeds its manifest tolerance: {check:?} vs {:?}", entry.tolerance);
+}
+
+/// `OpenCV`'s `INTER_LINEAR` on pixel centres, in f64
+fn reference_resize_linear(src: &Mat, cols: usize, rows: usize) -> Mat {
+    let channels = src.channels();
+    let sx = src.cols() as f64 / cols as f64;
+    let sy = src.rows() as f64 / rows as f64;
+    let mut out = Vec::with_capacity(rows * cols * channels);
+    for y in 0..rows {
+        let fy = ((y as f64 + 0.5) * sy - 0.5).max(0.0);
+        let y0 = (fy as usize).min(src.rows() - 1);
+        let y1 = (y0 + 1).min(src.rows() - 1);
+        let ay = fy - y0 as f64;
+        for x in 0..cols {
+            let fx = ((x as f64 + 0.5) * sx - 0.5).max(0.0);
+            let x0 = (fx as usize).min(src.cols() - 1);
+            let x1 = (x0 + 1).min(src.cols() - 1);
+            let ax = fx - x0 as f64;
+            for ch in 0..channels {
+                let p = |r: usize, c: usize| f64::from(src.at(r, c).unwrap()[ch]);
+                let v = (p(y0, x0) * (1.0 - ax) + p(y0, x1) * ax) * (1.0 - ay) + (p(y1, x0) * (1.0 - ax) + p(y1, x1) * ax) * ay;
+                out.push(v.round() as u8);
+            }
+        }
+    }
+    mat_like(src, rows, cols, out)
+}
+
+/// `OpenCV`'s `INTER_NEAREST`: `src = floor(dst * scale)`
+fn reference_resize_nearest(src: &Mat, cols: usize, rows: usize) -> Mat {
+    let mut out = Vec::new();
+    for y in 0..rows {
+        let sy = ((y * src.rows()) / rows).min(src.rows() - 1);
+        for x in 0..cols {
+            let sx = ((x * src.cols()) / cols).min(src.cols() - 1);
+            out.extend_from_slice(src.at(sy, sx).unwrap());
+        }
+    }
+    mat_like(src, rows, cols, out)
+}
+
+fn check_resize() {
+    for name in FIXTURES {
+        let src = fixture(name);
+        for (cols, rows) in [(384, 384), (256, 200), (700, 650)] {
+            let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            resize(&src, &mut dst, Size::new(cols as i32, rows as i32), InterpolationFlag::Linear).unwrap();
+            assert_within("resize", &dst, &reference_resize_linear(&src, cols, rows), name);
+
+            resize(&src, &mut dst, Size::new(cols as i32, rows as i32), InterpolationFlag::Nearest).unwrap();
+            assert_eq!(dst.data(), reference_resize_nearest(&src, cols, rows).data(), "nearest resize of {name}");
+        }
+    }
+}
+
+fn check_threshold() {
+    for name in FIXTURES {
+        let mut gray = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        cvt_color(&fixture(name), &mut gray, ColorConversionCode::RgbToGray).unwrap();
+        let cases: [ThresholdCase; 3] = [
+            (128.0, ThresholdType::Binary, |v, t| if v > t { 255 } else { 0 }),
+            (100.0, ThresholdType::BinaryInv, |v, t| if v > t { 0 } else { 255 }),
+            (127.0, ThresholdType::Trunc, |v, t| v.min(t)),
+        ];
+        for (thresh, kind, reference) in cases {
+            let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+            threshold(&gray, &mut dst, thresh, 255.0, kind).unwrap();
+            let expected = gray.data().iter().map(|&v| reference(v, thresh as u8)).collect();
+            assert_within("threshold", &dst, &mat_like(&gray, gray.rows(), gray.cols(), expected), name);
+        }
+    }
+}
+
+fn check_flip() {
+    let src = fixture("shapes.png");
+    let (rows, cols) = (src.rows(), src.cols());
+    for code in [0, 1, -1] {
+        let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        flip(&src, &mut dst, code).unwrap();
+        let mut expected = Vec::new();
+        for r in 0..rows {
+            for c in 0..cols {
+                let sr = if code <= 0 { rows - 1 - r } else { r };
+                let sc = if code != 0 { cols - 1 - c } else { c };
+                expected.extend_from_slice(src.at(sr, sc).unwrap());
+            }
+        }
+        assert_within("flip", &dst, &mat_like(&src, rows, cols, expected), "shapes.png");
+    }
+}
+
+fn check_cvt_color_gray() {
+    for name in FIXTURES {
+        let src = fixture(name);
+        let mut gray = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+        cvt_color(&src, &mut gray, ColorConversionCode::RgbToGray).unwrap();
+
+        // OpenCV's 14-bit fixed-point weights with rounding
+        let expected: Vec<u8> = src
+            .data()
+            .chunks_exact(src.channels())
+            .map(|p| ((u32::from(p[0]) * 4899 + u32::from(p[1]) * 9617 + u32::from(p[2]) * 1868 + 8192) >> 14) as u8)
+            .collect();
+        let mut reference = Mat::new(src.rows(), src.cols(), 1, MatDepth::U8).unwrap();
+        reference.data_mut().copy_from_slice(&expected);
+        assert_within("cvt_color_gray", &gray, &reference, name);
+    }
+}
+
+fn check_cvt_color_lab() {
+    // cv2.cvtColor(..., COLOR_RGB2Lab) on 8-bit primaries, white and black
+    let rgb = [[255u8, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255], [0, 0, 0]];
+    let lab = [[136u8, 208, 195], [224, 42, 211], [82, 207, 20], [255, 128, 128], [0, 128, 128]];
+
+    let mut src = Mat::new(1, 5, 3, MatDepth::U8).unwrap();
+    src.data_mut().copy_from_slice(rgb.as_flattened());
+    let mut expected = Mat::new(1, 5, 3, MatDepth::U8).unwrap();
+    expected.data_mut().copy_from_slice(lab.as_flattened());
+
+    let mut dst = Mat::new(1, 1, 1, MatDepth::U8).unwrap();
+    cvt_color(&src, &mut dst, ColorConversionCode::RgbToLab).unwrap();
+    assert_within("cvt_color_lab", &dst, &expected, "primaries");
+}
+
+#[test]
+fn test_native_manifest_entries() {
+    for entry in parity::manifest().iter().filter(|e| e.checked_by == "native") {
+        match entry.op {
+            "resize" => check_resize(),
+            "threshold" => check_threshold(),
+            "flip" => check_flip(),
+            "cvt_color_gray" => check_cvt_color_gray(),
+            "cvt_color_lab" => check_cvt_color_lab(),
+            op => panic!("{op} is marked native but has no check in tests/test_parity.rs"),
+        }
+    }
+}
+
+#[test]
+fn test_opencv_js_harness_ops_in_manifest() {
+    let configs = std::fs::read_to_string(format!(
+        "{}/tests/opencv_js_reference/generate_tests.js",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+    .unwrap();
+    let ops: Vec<&str> = configs
+        .lines()
+        .filter_map(|line| line.strip_prefix("  ")?.strip_suffix(": {"))
+        .filter(|op| op.chars().all(|c| c.is_ascii_lowercase() || c == '_'))
+        .collect();
+
+    assert!(ops.len() >= 10, "could not find the TEST_CONFIGS of generate_tests.js");
+    for op in ops {
+        assert!(parity::entry(op).is_some(), "{op} is tested by the opencv.js harness but missing from the manifest");
+    }
+}
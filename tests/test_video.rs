@@ -1,3 +1,4 @@
+#![cfg(feature = "video")]
 // Video analysis tests ported from OpenCV test suite
 // opencv/modules/video/test/test_optflowpyrlk.cpp
 // opencv/modules/video/test/test_OF_accuracy.cpp
@@ -0,0 +1,70 @@
+//! WASM tests for the flat typed-array result bindings
+//!
+//! Checks the documented layouts of the `*Flat` variants
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use wasm_bindgen_test::*;
+use opencv_rust::wasm::WasmMat;
+use opencv_rust::wasm::flat::{KEYPOINT_STRIDE, TRACKED_POINT_STRIDE};
+use opencv_rust::wasm::features::fast_flat_wasm;
+use opencv_rust::wasm::imgproc::contour::find_contours_flat_wasm;
+use opencv_rust::wasm::video::{farneback_flow_flat_wasm, lucas_kanade_flat_wasm};
+
+mod wasm_test_utils;
+use wasm_test_utils::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// 40x40 gray image with white squares at the given top-left corners
+fn squares(corners: &[(usize, usize)]) -> WasmMat {
+    let mut data = vec![0u8; 40 * 40];
+    for &(x0, y0) in corners {
+        for y in y0..y0 + 10 {
+            for x in x0..x0 + 10 {
+                data[y * 40 + x] = 255;
+            }
+        }
+    }
+    WasmMat::from_image_data(&data, 40, 40, 1).expect("Failed to create test image")
+}
+
+#[wasm_bindgen_test]
+fn test_fast_flat_layout() {
+    let kp = fast_flat_wasm(&squares(&[(10, 10)]), 20, true).unwrap();
+    assert!(!kp.is_empty(), "Square corners should be detected");
+    assert_eq!(kp.len() % KEYPOINT_STRIDE, 0);
+    for k in kp.chunks_exact(KEYPOINT_STRIDE) {
+        assert!((5.0..=25.0).contains(&k[0]) && (5.0..=25.0).contains(&k[1]));
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_find_contours_flat_layout() {
+    let flat = find_contours_flat_wasm(&squares(&[(2, 2), (25, 25)]), 127.0).unwrap();
+    assert_eq!(flat[0], 2.0);
+
+    let mut i = 1;
+    for _ in 0..2 {
+        let n = flat[i] as usize;
+        assert!(n >= 4, "A square has at least 4 vertices");
+        i += 1 + 2 * n;
+    }
+    assert_eq!(i, flat.len());
+}
+
+#[wasm_bindgen_test]
+fn test_flow_flat_layout() {
+    let prev = squares(&[(10, 10)]);
+    let next = squares(&[(12, 10)]);
+
+    let dense = farneback_flow_flat_wasm(&prev, &next, 0.5, 2, 9, 3).unwrap();
+    assert_eq!(dense.len(), 40 * 40 * 2);
+
+    let sparse = lucas_kanade_flat_wasm(&prev, &next, &[10.0, 10.0, 19.0, 19.0], 9, 2).unwrap();
+    assert_eq!(sparse.len(), 2 * TRACKED_POINT_STRIDE);
+    assert!(lucas_kanade_flat_wasm(&prev, &next, &[1.0], 9, 2).is_err());
+
+    let rgb = create_test_image_rgb();
+    assert!(farneback_flow_flat_wasm(&rgb, &rgb, 0.5, 1, 5, 1).is_ok());
+}